@@ -0,0 +1,16 @@
+//! Экспорт JSON Schema событий и ответов команд (тот же контракт, что отдаёт команда get_api_schema).
+//!
+//! Запуск (из src-tauri):
+//!   cargo run --example export_api_schema -- ../src/types/api-schema.json
+//! Без аргумента схема печатается в stdout.
+
+fn main() {
+    let schema = app_lib::api_schema_json();
+    match std::env::args().nth(1) {
+        Some(path) => {
+            std::fs::write(&path, schema + "\n").unwrap_or_else(|e| panic!("Failed to write {}: {}", path, e));
+            println!("API schema written to {}", path);
+        }
+        None => println!("{}", schema),
+    }
+}
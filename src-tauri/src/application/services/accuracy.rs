@@ -0,0 +1,277 @@
+use crate::domain::{
+    AccuracyScript, AccuracySuggestion, AccuracyTestResult, ErrorCounts, SttConfig, SttProviderType,
+};
+
+/// WER, начиная с которого есть смысл что-то менять
+const ACCEPTABLE_WER: f64 = 0.05;
+
+/// WER, при котором стоит сменить модель или провайдера
+const POOR_WER: f64 = 0.15;
+
+/// Модели Whisper от маленькой к большой
+const WHISPER_MODELS: [&str; 5] = ["tiny", "base", "small", "medium", "large"];
+
+/// Встроенные тексты для самопроверки: без цифр и сокращений, чтобы оценка не зависела
+/// от того, как провайдер записывает числа
+pub fn builtin_accuracy_scripts() -> Vec<AccuracyScript> {
+    [
+        (
+            "en-weather",
+            "en",
+            "Weather report",
+            "The weather this morning is cool and cloudy, with a light breeze coming from the north. \
+             By the afternoon the clouds should clear, and the evening will be calm and pleasant. \
+             If you plan to walk in the park, take a warm jacket just in case.",
+        ),
+        (
+            "en-meeting",
+            "en",
+            "Project update",
+            "Our team finished the first version of the new design last week. \
+             We still need to review the feedback from customers and fix a few small problems. \
+             The next meeting will focus on the release plan and the budget for the spring.",
+        ),
+        (
+            "ru-weather",
+            "ru",
+            "Прогноз погоды",
+            "Сегодня утром прохладно и облачно, с севера дует лёгкий ветер. \
+             После обеда облака рассеются, а вечер будет тихим и тёплым. \
+             Если собираетесь гулять в парке, на всякий случай возьмите куртку.",
+        ),
+        (
+            "ru-meeting",
+            "ru",
+            "Рабочая встреча",
+            "На прошлой неделе команда закончила первую версию нового дизайна. \
+             Осталось разобрать отзывы клиентов и исправить несколько небольших ошибок. \
+             На следующей встрече обсудим план выпуска и бюджет на весну.",
+        ),
+    ]
+    .into_iter()
+    .map(|(id, language, title, text)| AccuracyScript {
+        id: id.to_string(),
+        language: language.to_string(),
+        title: title.to_string(),
+        text: text.to_string(),
+    })
+    .collect()
+}
+
+/// Слова для оценки: нижний регистр, без пунктуации, "ё" = "е"
+pub fn scoring_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .replace('ё', "е")
+        .replace('’', "'")
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Замены/пропуски/вставки по выравниванию Левенштейна
+pub fn error_counts<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> ErrorCounts {
+    // Одна строка DP: в каждой клетке — лучшая разбивка ошибок для префиксов
+    let mut prev: Vec<ErrorCounts> = (0..=hypothesis.len())
+        .map(|j| ErrorCounts {
+            insertions: j,
+            ..Default::default()
+        })
+        .collect();
+
+    for (i, r) in reference.iter().enumerate() {
+        let mut row = Vec::with_capacity(hypothesis.len() + 1);
+        row.push(ErrorCounts {
+            deletions: i + 1,
+            ..Default::default()
+        });
+        for (j, h) in hypothesis.iter().enumerate() {
+            let diagonal = if r == h {
+                prev[j]
+            } else {
+                ErrorCounts {
+                    substitutions: prev[j].substitutions + 1,
+                    ..prev[j]
+                }
+            };
+            let deletion = ErrorCounts {
+                deletions: prev[j + 1].deletions + 1,
+                ..prev[j + 1]
+            };
+            let insertion = ErrorCounts {
+                insertions: row[j].insertions + 1,
+                ..row[j]
+            };
+            let mut best = diagonal;
+            for candidate in [deletion, insertion] {
+                if candidate.errors() < best.errors() {
+                    best = candidate;
+                }
+            }
+            row.push(best);
+        }
+        prev = row;
+    }
+
+    ErrorCounts {
+        reference_len: reference.len(),
+        ..prev[hypothesis.len()]
+    }
+}
+
+/// Что посоветовать по результату теста с текущими настройками
+pub fn accuracy_suggestions(config: &SttConfig, words: &ErrorCounts, hypothesis_words: usize) -> Vec<AccuracySuggestion> {
+    let mut suggestions = Vec::new();
+    if words.reference_len > 0 && hypothesis_words * 2 < words.reference_len {
+        suggestions.push(AccuracySuggestion::CheckMicrophone);
+    }
+
+    let wer = words.rate();
+    if wer <= ACCEPTABLE_WER {
+        return suggestions;
+    }
+
+    if wer > POOR_WER {
+        let larger_model = if config.provider == SttProviderType::WhisperLocal {
+            let current = config.effective_model().unwrap_or("base");
+            WHISPER_MODELS
+                .iter()
+                .position(|m| *m == current)
+                .and_then(|i| WHISPER_MODELS.get(i + 1))
+        } else {
+            None
+        };
+        suggestions.push(match larger_model {
+            Some(model) => AccuracySuggestion::LargerModel {
+                model: model.to_string(),
+            },
+            None => AccuracySuggestion::SwitchProvider {
+                provider: if config.provider == SttProviderType::Deepgram {
+                    SttProviderType::AssemblyAI
+                } else {
+                    SttProviderType::Deepgram
+                },
+            },
+        });
+    }
+
+    // Слова распознаны, но не те — это лечится словарём исправлений
+    if words.substitutions > words.deletions + words.insertions {
+        suggestions.push(AccuracySuggestion::AddCorrections);
+    }
+    suggestions
+}
+
+/// Оценивает распознанный текст против эталона и собирает результат теста
+pub fn score_accuracy_test(
+    script: &AccuracyScript,
+    transcript: &str,
+    config: &SttConfig,
+    audio_ms: u64,
+    timestamp: i64,
+) -> AccuracyTestResult {
+    let reference = scoring_words(&script.text);
+    let hypothesis = scoring_words(transcript);
+    let word_errors = error_counts(&reference, &hypothesis);
+
+    let reference_chars: Vec<char> = reference.join(" ").chars().collect();
+    let hypothesis_chars: Vec<char> = hypothesis.join(" ").chars().collect();
+    let char_errors = error_counts(&reference_chars, &hypothesis_chars);
+
+    AccuracyTestResult {
+        timestamp,
+        script_id: script.id.clone(),
+        language: script.language.clone(),
+        provider: config.provider,
+        model: config.model.clone(),
+        transcript: transcript.trim().to_string(),
+        word_errors,
+        char_errors,
+        word_error_rate: word_errors.rate(),
+        char_error_rate: char_errors.rate(),
+        audio_ms,
+        suggestions: accuracy_suggestions(config, &word_errors, hypothesis.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        scoring_words(text)
+    }
+
+    #[test]
+    fn scoring_words_ignore_case_and_punctuation() {
+        assert_eq!(words("Ёлка, ЁЖИК — it’s fine!"), vec!["елка", "ежик", "it's", "fine"]);
+    }
+
+    #[test]
+    fn counts_substitutions_deletions_and_insertions() {
+        let reference = words("the cat sat on the mat");
+        assert_eq!(error_counts(&reference, &reference).errors(), 0);
+
+        let counts = error_counts(&reference, &words("the bat sat on mat"));
+        assert_eq!((counts.substitutions, counts.deletions, counts.insertions), (1, 1, 0));
+        assert_eq!(counts.reference_len, 6);
+
+        let counts = error_counts(&reference, &words("the cat sat on the mat today please"));
+        assert_eq!((counts.substitutions, counts.deletions, counts.insertions), (0, 0, 2));
+        assert_eq!(counts.rate(), 2.0 / 6.0);
+
+        let chars: Vec<char> = "kitten".chars().collect();
+        let other: Vec<char> = "sitting".chars().collect();
+        assert_eq!(error_counts(&chars, &other).errors(), 3);
+    }
+
+    #[test]
+    fn suggests_larger_whisper_model_or_other_provider() {
+        let poor = ErrorCounts {
+            substitutions: 4,
+            deletions: 0,
+            insertions: 0,
+            reference_len: 10,
+        };
+        let whisper = SttConfig::new(SttProviderType::WhisperLocal).with_model("small");
+        assert_eq!(
+            accuracy_suggestions(&whisper, &poor, 10),
+            vec![
+                AccuracySuggestion::LargerModel {
+                    model: "medium".to_string()
+                },
+                AccuracySuggestion::AddCorrections,
+            ]
+        );
+
+        let deepgram = SttConfig::new(SttProviderType::Deepgram);
+        let cut_off = ErrorCounts {
+            deletions: 8,
+            reference_len: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            accuracy_suggestions(&deepgram, &cut_off, 2),
+            vec![
+                AccuracySuggestion::CheckMicrophone,
+                AccuracySuggestion::SwitchProvider {
+                    provider: SttProviderType::AssemblyAI
+                },
+            ]
+        );
+
+        assert!(accuracy_suggestions(&deepgram, &ErrorCounts::default(), 0).is_empty());
+    }
+
+    #[test]
+    fn scores_script_reading() {
+        let script = builtin_accuracy_scripts().into_iter().find(|s| s.id == "ru-weather").unwrap();
+        let config = SttConfig::new(SttProviderType::Deepgram).with_language("ru");
+        let result = score_accuracy_test(&script, &script.text.replace('ё', "е"), &config, 12_000, 1_700_000_000);
+        assert_eq!(result.word_error_rate, 0.0);
+        assert_eq!(result.char_error_rate, 0.0);
+        assert!(result.suggestions.is_empty());
+        assert_eq!(result.language, "ru");
+    }
+}
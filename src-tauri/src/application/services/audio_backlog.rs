@@ -0,0 +1,181 @@
+use crate::domain::ConnectionQualityReason;
+
+/// Политика backpressure для отправки аудио провайдеру.
+///
+/// Если отправка в WS не успевает за захватом, очередь неотправленного аудио растёт.
+/// Держать его бесконечно бессмысленно: распознавание всё равно будет отставать от речи,
+/// а память — расти. Поэтому ограничиваем отставание и выбрасываем самое старое аудио.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackpressurePolicy {
+    /// Максимальное отставание отправки; при превышении самые старые чанки выбрасываются
+    pub max_backlog_ms: u64,
+    /// Отставание, начиная с которого считаем связь плохой (и до которого сбрасываем очередь)
+    pub warn_backlog_ms: u64,
+}
+
+impl Default for BackpressurePolicy {
+    fn default() -> Self {
+        Self {
+            max_backlog_ms: 3000,
+            warn_backlog_ms: 1000,
+        }
+    }
+}
+
+/// Сообщение для connection:quality
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacklogReport {
+    pub quality: &'static str,
+    pub reason: Option<ConnectionQualityReason>,
+}
+
+/// Отслеживает отставание отправки аудио и решает, сколько чанков выбросить.
+///
+/// Живёт внутри audio processor одной сессии записи, поэтому без блокировок.
+#[derive(Debug)]
+pub struct AudioBacklogMonitor {
+    policy: BackpressurePolicy,
+    backlog_ms: f64,
+    congested: bool,
+    dropped_ms_since_report: f64,
+    dropped_ms_total: f64,
+}
+
+impl AudioBacklogMonitor {
+    pub fn new(policy: BackpressurePolicy) -> Self {
+        Self {
+            policy,
+            backlog_ms: 0.0,
+            congested: false,
+            dropped_ms_since_report: 0.0,
+            dropped_ms_total: 0.0,
+        }
+    }
+
+    /// Учитывает текущую очередь и возвращает, сколько самых старых чанков выбросить
+    /// (считая текущий, который уже вынут из очереди).
+    ///
+    /// `queued_chunks` — сколько чанков ещё ждёт в очереди после текущего.
+    pub fn chunks_to_drop(&mut self, queued_chunks: usize, chunk_ms: f64) -> usize {
+        if chunk_ms <= 0.0 {
+            return 0;
+        }
+
+        let total_chunks = queued_chunks + 1;
+        self.backlog_ms = total_chunks as f64 * chunk_ms;
+        if self.backlog_ms <= self.policy.max_backlog_ms as f64 {
+            return 0;
+        }
+
+        // Сбрасываем очередь до порога предупреждения, а не до нуля:
+        // свежие ~warn_backlog_ms аудио ещё имеет смысл распознать.
+        let keep = ((self.policy.warn_backlog_ms as f64 / chunk_ms).floor() as usize).max(1);
+        let drop = total_chunks.saturating_sub(keep);
+        let dropped_ms = drop as f64 * chunk_ms;
+
+        self.backlog_ms -= dropped_ms;
+        self.dropped_ms_since_report += dropped_ms;
+        self.dropped_ms_total += dropped_ms;
+        drop
+    }
+
+    /// Текущее отставание отправки в секундах
+    pub fn backlog_secs(&self) -> f64 {
+        self.backlog_ms / 1000.0
+    }
+
+    /// Всего выброшено аудио за сессию, в секундах
+    pub fn dropped_secs(&self) -> f64 {
+        self.dropped_ms_total / 1000.0
+    }
+
+    /// Изменение качества связи для UI. Возвращает Some только на переходах, чтобы не спамить событиями.
+    pub fn poll_report(&mut self) -> Option<BacklogReport> {
+        if self.dropped_ms_since_report > 0.0 {
+            let dropped_secs = self.dropped_ms_since_report / 1000.0;
+            self.dropped_ms_since_report = 0.0;
+            self.congested = true;
+            return Some(BacklogReport {
+                quality: "Poor",
+                reason: Some(ConnectionQualityReason::AudioDropped {
+                    backlog_secs: self.backlog_secs(),
+                    dropped_secs,
+                }),
+            });
+        }
+
+        let warn_ms = self.policy.warn_backlog_ms as f64;
+        if !self.congested && self.backlog_ms >= warn_ms {
+            self.congested = true;
+            return Some(BacklogReport {
+                quality: "Poor",
+                reason: Some(ConnectionQualityReason::AudioSendBacklog {
+                    backlog_secs: self.backlog_secs(),
+                }),
+            });
+        }
+
+        // Гистерезис: "отпускаем" только когда очередь заметно разгрузилась
+        if self.congested && self.backlog_ms < warn_ms / 2.0 {
+            self.congested = false;
+            return Some(BacklogReport {
+                quality: "Recovering",
+                reason: Some(ConnectionQualityReason::AudioBacklogCleared),
+            });
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_backlog_is_not_dropped() {
+        let mut monitor = AudioBacklogMonitor::new(BackpressurePolicy::default());
+        assert_eq!(monitor.chunks_to_drop(10, 64.0), 0);
+        assert!((monitor.backlog_secs() - 0.704).abs() < 1e-9);
+        assert_eq!(monitor.poll_report(), None);
+    }
+
+    #[test]
+    fn drops_oldest_down_to_warn_threshold() {
+        let mut monitor = AudioBacklogMonitor::new(BackpressurePolicy::default());
+
+        // 60 чанков по 64мс ≈ 3.84с > 3с
+        let drop = monitor.chunks_to_drop(59, 64.0);
+        // Оставляем floor(1000 / 64) = 15 чанков
+        assert_eq!(drop, 45);
+        assert!((monitor.backlog_secs() - 0.96).abs() < 1e-9);
+        assert!((monitor.dropped_secs() - 2.88).abs() < 1e-9);
+
+        let report = monitor.poll_report().unwrap();
+        assert_eq!(report.quality, "Poor");
+        match report.reason {
+            Some(ConnectionQualityReason::AudioDropped { dropped_secs, .. }) => {
+                assert!((dropped_secs - 2.88).abs() < 1e-9)
+            }
+            other => panic!("Expected AudioDropped reason, got {:?}", other),
+        }
+
+        // Повторный опрос без новых событий — тишина
+        assert_eq!(monitor.poll_report(), None);
+    }
+
+    #[test]
+    fn reports_congestion_and_recovery_with_hysteresis() {
+        let mut monitor = AudioBacklogMonitor::new(BackpressurePolicy::default());
+
+        monitor.chunks_to_drop(19, 64.0); // 1.28с
+        assert_eq!(monitor.poll_report().unwrap().quality, "Poor");
+
+        monitor.chunks_to_drop(11, 64.0); // 0.768с — ещё не отпускаем
+        assert_eq!(monitor.poll_report(), None);
+
+        monitor.chunks_to_drop(2, 64.0); // 0.192с
+        assert_eq!(monitor.poll_report().unwrap().quality, "Recovering");
+        assert_eq!(monitor.poll_report(), None);
+    }
+}
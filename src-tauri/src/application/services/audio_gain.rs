@@ -0,0 +1,167 @@
+use serde::Serialize;
+
+use super::chunk_rms;
+
+/// Запас до полной шкалы, который оставляет лимитер
+const LIMITER_HEADROOM: f32 = 0.98;
+
+/// Длина чанка для офлайн-прогона цепочки (30 мс @ 16kHz).
+/// В живой записи лимитер работает по чанкам захвата — их длина зависит от частоты устройства.
+pub const PREVIEW_CHUNK_SAMPLES: usize = 480;
+
+/// Linear gain for a microphone sensitivity (0-200%)
+///
+///   0%   = gain 0.0x (полная тишина)
+///   100% = gain 1.0x (без изменений, как записывает микрофон)
+///   200% = gain 5.0x (максимальное усиление для тихих микрофонов)
+pub fn sensitivity_gain(sensitivity: u8) -> f32 {
+    let sensitivity = sensitivity.min(200);
+    if sensitivity <= 100 {
+        // 0-100% → 0.0x-1.0x (приглушение/нормальный уровень)
+        sensitivity as f32 / 100.0
+    } else {
+        // 100-200% → 1.0x-5.0x (усиление для тихих микрофонов)
+        1.0 + (sensitivity - 100) as f32 / 100.0 * 4.0
+    }
+}
+
+/// Простой limiter: если requested_gain приводит к клиппингу — уменьшаем gain для этого чанка.
+/// Это сохраняет "помощь" тихим микрофонам и не ухудшает распознавание на нормальных уровнях.
+pub fn limited_gain(requested_gain: f32, max_amplitude: i32) -> f32 {
+    if max_amplitude <= 0 {
+        requested_gain
+    } else {
+        let limiter_gain = (32767.0 * LIMITER_HEADROOM) / (max_amplitude as f32);
+        requested_gain.min(limiter_gain)
+    }
+}
+
+pub fn max_amplitude(samples: &[i16]) -> i32 {
+    samples.iter().map(|&s| (s as i32).abs()).max().unwrap_or(0)
+}
+
+/// Применяем gain к каждому сэмплу с защитой от clipping
+pub fn apply_gain(samples: &[i16], gain: f32) -> Vec<i16> {
+    samples
+        .iter()
+        .map(|&sample| (sample as f32 * gain).clamp(-32767.0, 32767.0) as i16)
+        .collect()
+}
+
+/// Signal level of a buffer (values relative to full scale)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AudioLevelMetrics {
+    /// Пик 0..1
+    pub peak: f32,
+    /// RMS 0..1
+    pub rms: f32,
+    /// RMS в dBFS (тишина — -120)
+    pub rms_dbfs: f32,
+    /// Сэмплы на полной шкале (клиппинг)
+    pub clipped_samples: usize,
+}
+
+impl AudioLevelMetrics {
+    pub fn measure(samples: &[i16]) -> Self {
+        let peak = max_amplitude(samples) as f32 / 32767.0;
+        let rms = (chunk_rms(samples) / 32767.0) as f32;
+        let rms_dbfs = if rms > 0.0 { (20.0 * rms.log10()).max(-120.0) } else { -120.0 };
+        let clipped_samples = samples.iter().filter(|&&s| s >= 32767 || s <= -32767).count();
+
+        Self {
+            peak: peak.min(1.0),
+            rms,
+            rms_dbfs,
+            clipped_samples,
+        }
+    }
+}
+
+/// What the sensitivity gain and the limiter did to a buffer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GainChainMetrics {
+    pub sensitivity: u8,
+    pub requested_gain: f32,
+    /// Уровень после усиления без лимитера (клиппинг, который лимитер предотвратил)
+    pub after_gain: AudioLevelMetrics,
+    /// Уровень после лимитера — то, что уходит в STT
+    pub after_limiter: AudioLevelMetrics,
+    pub min_effective_gain: f32,
+    /// Сколько чанков лимитер приглушил
+    pub limited_chunks: usize,
+    pub total_chunks: usize,
+}
+
+/// Прогоняет буфер через усиление и лимитер так же, как запись (по чанкам)
+pub fn run_gain_chain(samples: &[i16], sensitivity: u8, chunk_samples: usize) -> (Vec<i16>, GainChainMetrics) {
+    let requested_gain = sensitivity_gain(sensitivity);
+    let mut output = Vec::with_capacity(samples.len());
+    let mut min_effective_gain = requested_gain;
+    let mut limited_chunks = 0;
+    let mut total_chunks = 0;
+
+    for chunk in samples.chunks(chunk_samples.max(1)) {
+        let effective_gain = limited_gain(requested_gain, max_amplitude(chunk));
+        if effective_gain < requested_gain {
+            limited_chunks += 1;
+        }
+        min_effective_gain = min_effective_gain.min(effective_gain);
+        total_chunks += 1;
+        output.extend(apply_gain(chunk, effective_gain));
+    }
+
+    let metrics = GainChainMetrics {
+        sensitivity: sensitivity.min(200),
+        requested_gain,
+        after_gain: AudioLevelMetrics::measure(&apply_gain(samples, requested_gain)),
+        after_limiter: AudioLevelMetrics::measure(&output),
+        min_effective_gain,
+        limited_chunks,
+        total_chunks,
+    };
+    (output, metrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensitivity_gain_is_piecewise_linear() {
+        assert_eq!(sensitivity_gain(0), 0.0);
+        assert_eq!(sensitivity_gain(50), 0.5);
+        assert_eq!(sensitivity_gain(100), 1.0);
+        assert_eq!(sensitivity_gain(150), 3.0);
+        assert_eq!(sensitivity_gain(200), 5.0);
+        assert_eq!(sensitivity_gain(255), 5.0);
+    }
+
+    #[test]
+    fn limiter_prevents_clipping_only_on_loud_chunks() {
+        let mut samples = vec![1000i16; 480];
+        samples.extend(vec![20000i16; 480]);
+
+        let (output, metrics) = run_gain_chain(&samples, 200, 480);
+
+        assert_eq!(output.len(), samples.len());
+        assert_eq!(output[0], 5000); // тихий чанк усилен полностью
+        assert!(output[480] < 32767 && output[480] > 30000); // громкий — приглушён лимитером
+        assert_eq!(metrics.limited_chunks, 1);
+        assert_eq!(metrics.total_chunks, 2);
+        assert!(metrics.after_gain.clipped_samples >= 480);
+        assert_eq!(metrics.after_limiter.clipped_samples, 0);
+        assert!(metrics.min_effective_gain < 5.0);
+    }
+
+    #[test]
+    fn level_metrics_of_silence_and_full_scale() {
+        let silence = AudioLevelMetrics::measure(&[0; 100]);
+        assert_eq!(silence.peak, 0.0);
+        assert_eq!(silence.rms_dbfs, -120.0);
+
+        let full = AudioLevelMetrics::measure(&[32767, -32767]);
+        assert_eq!(full.peak, 1.0);
+        assert!(full.rms_dbfs.abs() < 0.01);
+        assert_eq!(full.clipped_samples, 2);
+    }
+}
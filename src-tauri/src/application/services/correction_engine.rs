@@ -0,0 +1,369 @@
+use std::sync::{Arc, RwLock};
+
+use crate::domain::{CorrectionEntry, CorrectionStore};
+
+type Result<T> = anyhow::Result<T>;
+
+/// Сколько исправленных терминов отдаём в keyword boosting (у провайдеров есть лимит на keyterms)
+const MAX_CORRECTION_KEYTERMS: usize = 50;
+
+/// Максимальная длина фразы (в словах), которую выучиваем из правки текста.
+/// Если пользователь переписал полпредложения — это не ошибка распознавания слова.
+const MAX_LEARNED_PHRASE_WORDS: usize = 3;
+
+/// Словарь исправлений, который учится на правках пользователя.
+///
+/// Пользователь исправляет слово в UI → сохраняем пару (wrong → right) и дальше
+/// автоматически применяем её к финальным фразам. Исправленные варианты также
+/// уходят в keyword boosting провайдера, чтобы ошибка не повторялась в принципе.
+///
+/// Замена идёт по целым словам без учёта регистра; регистр первой буквы
+/// (начало предложения) сохраняется.
+pub struct CorrectionEngine {
+    store: Arc<dyn CorrectionStore>,
+    // Синхронный lock: apply вызывается из callback провайдера
+    entries: RwLock<Vec<CorrectionEntry>>,
+}
+
+impl CorrectionEngine {
+    /// Создаёт движок и загружает сохранённый словарь.
+    /// Ошибка загрузки не фатальна: начинаем с пустого словаря.
+    pub fn new(store: Arc<dyn CorrectionStore>) -> Self {
+        let entries = match store.load() {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Failed to load corrections dictionary: {}. Starting with empty one.", e);
+                Vec::new()
+            }
+        };
+        log::info!("Corrections dictionary loaded: {} entries", entries.len());
+
+        Self {
+            store,
+            entries: RwLock::new(entries),
+        }
+    }
+
+    /// Все выученные пары (от старых к новым)
+    pub fn entries(&self) -> Vec<CorrectionEntry> {
+        self.entries.read().map(|e| e.clone()).unwrap_or_default()
+    }
+
+    /// Запоминает пару wrong → right (повторная правка того же слова перезаписывает пару)
+    pub fn learn(&self, wrong: &str, right: &str) -> Result<CorrectionEntry> {
+        let wrong = normalize_phrase(wrong);
+        let right = normalize_phrase(right);
+        if wrong.is_empty() || right.is_empty() {
+            anyhow::bail!("Исправление не может быть пустым");
+        }
+        if wrong == right {
+            anyhow::bail!("Исправление совпадает с исходным текстом");
+        }
+
+        let entry = CorrectionEntry::new(wrong, right);
+        self.update(|entries| {
+            entries.retain(|e| !phrase_eq_ci(&e.wrong, &entry.wrong));
+            entries.push(entry.clone());
+        })?;
+
+        log::info!("Learned correction: '{}' -> '{}'", entry.wrong, entry.right);
+        Ok(entry)
+    }
+
+    /// Выучивает пары из правки финального текста: сравнивает исходную и исправленную фразу
+    /// и запоминает изменённый фрагмент, если он короткий.
+    pub fn learn_from_edit(&self, original: &str, edited: &str) -> Result<Vec<CorrectionEntry>> {
+        let Some((wrong, right)) = diff_edited_phrase(original, edited) else {
+            return Ok(Vec::new());
+        };
+        Ok(vec![self.learn(&wrong, &right)?])
+    }
+
+    /// Удаляет пару по исходному слову. Возвращает false, если такой пары не было.
+    pub fn remove(&self, wrong: &str) -> Result<bool> {
+        let wrong = normalize_phrase(wrong);
+        let mut removed = false;
+        self.update(|entries| {
+            let len = entries.len();
+            entries.retain(|e| !phrase_eq_ci(&e.wrong, &wrong));
+            removed = entries.len() != len;
+        })?;
+        Ok(removed)
+    }
+
+    /// Применяет словарь к тексту
+    pub fn apply(&self, text: &str) -> String {
+        let Ok(entries) = self.entries.read() else {
+            return text.to_string();
+        };
+        if entries.is_empty() {
+            return text.to_string();
+        }
+
+        // Сначала длинные фразы, чтобы "кубер нетис" не разбилось правилом для "кубер"
+        let mut ordered: Vec<&CorrectionEntry> = entries.iter().collect();
+        ordered.sort_by_key(|e| std::cmp::Reverse(e.wrong.chars().count()));
+
+        let mut result = text.to_string();
+        for entry in ordered {
+            result = replace_whole_words(&result, &entry.wrong, &entry.right);
+        }
+        result
+    }
+
+    /// Исправленные варианты для keyword boosting (самые свежие первыми)
+    pub fn keyterms(&self) -> Vec<String> {
+        let Ok(entries) = self.entries.read() else {
+            return Vec::new();
+        };
+        let mut terms: Vec<String> = Vec::new();
+        for entry in entries.iter().rev() {
+            if terms.len() >= MAX_CORRECTION_KEYTERMS {
+                break;
+            }
+            if !terms.iter().any(|t| phrase_eq_ci(t, &entry.right)) {
+                terms.push(entry.right.clone());
+            }
+        }
+        terms
+    }
+
+    /// Меняет словарь и сохраняет его. При ошибке сохранения изменения откатываются,
+    /// чтобы состояние в памяти не расходилось с диском.
+    fn update<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Vec<CorrectionEntry>),
+    {
+        let mut entries = self
+            .entries
+            .write()
+            .map_err(|_| anyhow::anyhow!("Corrections dictionary lock is poisoned"))?;
+        let mut next = entries.clone();
+        f(&mut next);
+        if next == *entries {
+            return Ok(());
+        }
+        self.store.save(&next)?;
+        *entries = next;
+        Ok(())
+    }
+}
+
+fn normalize_phrase(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn chars_eq_ci(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+fn phrase_eq_ci(a: &str, b: &str) -> bool {
+    a.chars().count() == b.chars().count() && a.chars().zip(b.chars()).all(|(x, y)| chars_eq_ci(x, y))
+}
+
+/// Подгоняет регистр замены под найденный фрагмент: "ПРИВЕТ" → "МИР", "Привет" → "Мир".
+/// В остальных случаях оставляем вариант пользователя как есть (например, "Kubernetes").
+fn match_case(found: &[char], replacement: &str) -> String {
+    let letters: Vec<&char> = found.iter().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        return replacement.to_uppercase();
+    }
+
+    let found_capitalized = found.first().map(|c| c.is_uppercase()).unwrap_or(false);
+    let mut chars = replacement.chars();
+    match chars.next() {
+        Some(first) if found_capitalized && first.is_lowercase() => {
+            first.to_uppercase().chain(chars).collect()
+        }
+        _ => replacement.to_string(),
+    }
+}
+
+/// Заменяет `wrong` на `right` по границам слов без учёта регистра
+fn replace_whole_words(text: &str, wrong: &str, right: &str) -> String {
+    let hay: Vec<char> = text.chars().collect();
+    let needle: Vec<char> = wrong.chars().collect();
+    let n = needle.len();
+    if n == 0 || hay.len() < n {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < hay.len() {
+        let matches = i + n <= hay.len()
+            && (i == 0 || !is_word_char(hay[i - 1]))
+            && (i + n == hay.len() || !is_word_char(hay[i + n]))
+            && hay[i..i + n].iter().zip(&needle).all(|(a, b)| chars_eq_ci(*a, *b));
+
+        if matches {
+            out.push_str(&match_case(&hay[i..i + n], right));
+            i += n;
+        } else {
+            out.push(hay[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+fn strip_punctuation(word: &str) -> &str {
+    word.trim_matches(|c: char| !is_word_char(c))
+}
+
+/// Находит изменённый фрагмент между исходной и исправленной фразой (по словам).
+///
+/// Общие слова в начале и в конце отбрасываются; оставшаяся разница — кандидат в пару.
+/// Возвращает None, если разница пустая, затрагивает слишком много слов или это
+/// чистое удаление/вставка (нечего заменять).
+fn diff_edited_phrase(original: &str, edited: &str) -> Option<(String, String)> {
+    let a: Vec<&str> = original.split_whitespace().collect();
+    let b: Vec<&str> = edited.split_whitespace().collect();
+
+    let same = |x: &str, y: &str| phrase_eq_ci(strip_punctuation(x), strip_punctuation(y));
+
+    let mut prefix = 0;
+    while prefix < a.len() && prefix < b.len() && same(a[prefix], b[prefix]) {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < a.len() - prefix
+        && suffix < b.len() - prefix
+        && same(a[a.len() - 1 - suffix], b[b.len() - 1 - suffix])
+    {
+        suffix += 1;
+    }
+
+    let wrong_words = &a[prefix..a.len() - suffix];
+    let right_words = &b[prefix..b.len() - suffix];
+    if wrong_words.is_empty()
+        || right_words.is_empty()
+        || wrong_words.len() > MAX_LEARNED_PHRASE_WORDS
+        || right_words.len() > MAX_LEARNED_PHRASE_WORDS
+    {
+        return None;
+    }
+
+    // Пунктуацию по краям фрагмента не учим: "кубер," → "Kubernetes," даёт пару без запятых
+    let wrong = strip_punctuation(&wrong_words.join(" ")).to_string();
+    let right = strip_punctuation(&right_words.join(" ")).to_string();
+    if wrong.is_empty() || right.is_empty() || wrong == right {
+        return None;
+    }
+    Some((wrong, right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CorrectionStoreError, CorrectionStoreResult};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        saved: Mutex<Vec<CorrectionEntry>>,
+        fail_save: bool,
+    }
+
+    impl CorrectionStore for MemoryStore {
+        fn load(&self) -> CorrectionStoreResult<Vec<CorrectionEntry>> {
+            Ok(self.saved.lock().unwrap().clone())
+        }
+
+        fn save(&self, entries: &[CorrectionEntry]) -> CorrectionStoreResult<()> {
+            if self.fail_save {
+                return Err(CorrectionStoreError::Io("disk full".to_string()));
+            }
+            *self.saved.lock().unwrap() = entries.to_vec();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn applies_learned_pairs_to_whole_words_preserving_case() {
+        let store = Arc::new(MemoryStore::default());
+        let engine = CorrectionEngine::new(store.clone());
+
+        engine.learn("кубер", "Kubernetes").unwrap();
+        engine.learn("дип грам", "Deepgram").unwrap();
+        engine.learn("превед", "привет").unwrap();
+
+        assert_eq!(
+            engine.apply("Деплой в кубер через дип грам, кубернетес не трогаем"),
+            "Деплой в Kubernetes через Deepgram, кубернетес не трогаем"
+        );
+        assert_eq!(engine.apply("Превед! ПРЕВЕД"), "Привет! ПРИВЕТ");
+
+        // Пары сохранены в store
+        assert_eq!(store.saved.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn relearning_overrides_pair_and_survives_restart() {
+        let store = Arc::new(MemoryStore::default());
+        let engine = CorrectionEngine::new(store.clone());
+        engine.learn("кубер", "Kubernetes").unwrap();
+        engine.learn("Кубер", "K8s").unwrap();
+
+        let reloaded = CorrectionEngine::new(store);
+        assert_eq!(reloaded.entries().len(), 1);
+        assert_eq!(reloaded.apply("кубер"), "K8s");
+
+        assert!(reloaded.remove("КУБЕР").unwrap());
+        assert!(!reloaded.remove("кубер").unwrap());
+        assert_eq!(reloaded.apply("кубер"), "кубер");
+    }
+
+    #[test]
+    fn rejects_empty_or_identity_corrections() {
+        let engine = CorrectionEngine::new(Arc::new(MemoryStore::default()));
+        assert!(engine.learn("  ", "x").is_err());
+        assert!(engine.learn("слово", "слово").is_err());
+        assert!(engine.entries().is_empty());
+    }
+
+    #[test]
+    fn failed_save_does_not_change_dictionary() {
+        let store = Arc::new(MemoryStore {
+            fail_save: true,
+            ..Default::default()
+        });
+        let engine = CorrectionEngine::new(store);
+        assert!(engine.learn("кубер", "Kubernetes").is_err());
+        assert_eq!(engine.apply("кубер"), "кубер");
+    }
+
+    #[test]
+    fn learns_changed_fragment_from_edit() {
+        let engine = CorrectionEngine::new(Arc::new(MemoryStore::default()));
+
+        let learned = engine
+            .learn_from_edit("Задеплой это в кубер, пожалуйста", "Задеплой это в Kubernetes, пожалуйста")
+            .unwrap();
+        assert_eq!(learned.len(), 1);
+        assert_eq!(learned[0].wrong, "кубер");
+        assert_eq!(learned[0].right, "Kubernetes");
+
+        // Переписанная фраза целиком — не ошибка распознавания
+        assert!(engine
+            .learn_from_edit("один два три четыре пять", "совсем другой текст без общих слов")
+            .unwrap()
+            .is_empty());
+        // Только удаление слова — заменять нечего
+        assert!(engine.learn_from_edit("ну вот так", "вот так").unwrap().is_empty());
+    }
+
+    #[test]
+    fn keyterms_are_unique_and_most_recent_first() {
+        let engine = CorrectionEngine::new(Arc::new(MemoryStore::default()));
+        engine.learn("кубер", "Kubernetes").unwrap();
+        engine.learn("кубик", "kubernetes").unwrap();
+        engine.learn("дип грам", "Deepgram").unwrap();
+
+        assert_eq!(engine.keyterms(), vec!["Deepgram".to_string(), "kubernetes".to_string()]);
+    }
+}
@@ -0,0 +1,226 @@
+use std::sync::Mutex;
+
+use crate::application::{EnsembleListener, EnsembleTranscript};
+
+/// Уверенность слова, если провайдер её не прислал (локальный Whisper)
+const DEFAULT_CONFIDENCE: f32 = 0.5;
+
+/// Дальше выравнивание (квадратичное по словам) на каждый финал слишком дорогое — сессия идёт без ансамбля
+const MAX_ENSEMBLE_WORDS: usize = 3000;
+
+/// Слово гипотезы с уверенностью его сегмента (пословной уверенности провайдеры не отдают)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredWord {
+    pub text: String,
+    pub confidence: f32,
+}
+
+impl ScoredWord {
+    pub fn new(text: impl Into<String>, confidence: f32) -> Self {
+        Self {
+            text: text.into(),
+            confidence,
+        }
+    }
+}
+
+/// Шаг выравнивания двух гипотез
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// Слова на одном месте (одинаковые или замена)
+    Pair(usize, usize),
+    /// Слово есть только в основной гипотезе
+    Primary(usize),
+    /// Слово есть только во второй гипотезе
+    Secondary(usize),
+}
+
+/// Сливает две гипотезы одного аудио: выравнивание Левенштейна по словам и победитель по уверенности.
+///
+/// Совпавшие слова берутся из основной гипотезы (её форматирование), при замене — слово с большей
+/// уверенностью; слово, которое есть только у одного провайдера, остаётся, если оно увереннее следующего
+/// слова другого (или другой провайдер до этого места ещё не дошёл). При равной уверенности выигрывает
+/// основной провайдер. Возвращает текст и сколько слов взято из второй гипотезы.
+pub fn merge_hypotheses(primary: &[ScoredWord], secondary: &[ScoredWord]) -> (String, usize) {
+    let mut words: Vec<&str> = Vec::with_capacity(primary.len().max(secondary.len()));
+    let mut from_secondary = 0;
+    // Следующие ещё не выровненные слова гипотез: [основной, второй]
+    let mut next = [0usize; 2];
+    for step in align(primary, secondary) {
+        match step {
+            Step::Pair(i, j) => {
+                let (a, b) = (&primary[i], &secondary[j]);
+                if normalized(&a.text) != normalized(&b.text) && b.confidence > a.confidence {
+                    words.push(&b.text);
+                    from_secondary += 1;
+                } else {
+                    words.push(&a.text);
+                }
+                next = [i + 1, j + 1];
+            }
+            Step::Primary(i) => {
+                let other = secondary.get(next[1]).map(|w| w.confidence);
+                if other.map_or(true, |other| primary[i].confidence >= other) {
+                    words.push(&primary[i].text);
+                }
+                next[0] = i + 1;
+            }
+            Step::Secondary(j) => {
+                let other = primary.get(next[0]).map(|w| w.confidence);
+                if other.map_or(true, |other| secondary[j].confidence > other) {
+                    words.push(&secondary[j].text);
+                    from_secondary += 1;
+                }
+                next[1] = j + 1;
+            }
+        }
+    }
+    (words.join(" "), from_secondary)
+}
+
+fn align(primary: &[ScoredWord], secondary: &[ScoredWord]) -> Vec<Step> {
+    let a: Vec<String> = primary.iter().map(|w| normalized(&w.text)).collect();
+    let b: Vec<String> = secondary.iter().map(|w| normalized(&w.text)).collect();
+    let (n, m) = (a.len(), b.len());
+
+    // cost[i][j] — расстояние между префиксами a[..i] и b[..j]
+    let mut cost = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in cost.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in cost[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = cost[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            cost[i][j] = substitution.min(cost[i - 1][j] + 1).min(cost[i][j - 1] + 1);
+        }
+    }
+
+    let mut steps = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && cost[i][j] == cost[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]) {
+            steps.push(Step::Pair(i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && cost[i][j] == cost[i - 1][j] + 1 {
+            steps.push(Step::Primary(i - 1));
+            i -= 1;
+        } else {
+            steps.push(Step::Secondary(j - 1));
+            j -= 1;
+        }
+    }
+    steps.reverse();
+    steps
+}
+
+/// Слово для сравнения: нижний регистр, без знаков по краям, "ё" = "е"
+fn normalized(token: &str) -> String {
+    token
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+        .replace('ё', "е")
+}
+
+/// Ансамбль A/B сессии: копит финальные слова обоих провайдеров и после каждого финала
+/// отдаёт listener'у слитый текст всей сессии
+pub struct EnsembleMerger {
+    listener: EnsembleListener,
+    /// Финальные слова сессии: [основной, сравниваемый]
+    words: Mutex<[Vec<ScoredWord>; 2]>,
+    last: Mutex<String>,
+}
+
+impl EnsembleMerger {
+    pub fn new(listener: EnsembleListener) -> Self {
+        Self {
+            listener,
+            words: Mutex::new([Vec::new(), Vec::new()]),
+            last: Mutex::new(String::new()),
+        }
+    }
+
+    /// Финализированный сегмент провайдера
+    pub fn push_final(&self, primary: bool, text: &str, confidence: Option<f32>) {
+        let confidence = confidence.unwrap_or(DEFAULT_CONFIDENCE);
+        let merged = {
+            let Ok(mut words) = self.words.lock() else {
+                return;
+            };
+            let was_within_limit = words.iter().all(|side| side.len() <= MAX_ENSEMBLE_WORDS);
+            words[usize::from(!primary)].extend(text.split_whitespace().map(|word| ScoredWord::new(word, confidence)));
+            if words.iter().any(|side| side.len() > MAX_ENSEMBLE_WORDS) {
+                if was_within_limit {
+                    log::info!("Ensemble merge stopped: session is longer than {} words", MAX_ENSEMBLE_WORDS);
+                }
+                return;
+            }
+            merge_hypotheses(&words[0], &words[1])
+        };
+
+        let (transcript, secondary_words) = merged;
+        if let Ok(mut last) = self.last.lock() {
+            if *last == transcript {
+                return;
+            }
+            last.clone_from(&transcript);
+        }
+        (self.listener)(EnsembleTranscript {
+            transcript,
+            secondary_words,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn words(text: &str, confidence: f32) -> Vec<ScoredWord> {
+        text.split_whitespace().map(|w| ScoredWord::new(w, confidence)).collect()
+    }
+
+    #[test]
+    fn picks_per_word_winners_by_confidence() {
+        // Замена: увереннее вторая гипотеза
+        let (text, from_secondary) = merge_hypotheses(&words("I red the book.", 0.6), &words("i read the book", 0.9));
+        assert_eq!(text, "I read the book.");
+        assert_eq!(from_secondary, 1);
+
+        // Совпадения — в форматировании основной гипотезы, равная уверенность — основная
+        assert_eq!(merge_hypotheses(&words("Hello, world", 0.7), &words("hello word", 0.7)).0, "Hello, world");
+
+        // Лишнее слово неуверенной гипотезы выпадает, пропущенное уверенной — добавляется
+        assert_eq!(merge_hypotheses(&words("send the the report", 0.4), &words("send the report", 0.8)).0, "send the report");
+        assert_eq!(merge_hypotheses(&words("send report", 0.4), &words("send the report", 0.8)).0, "send the report");
+
+        assert_eq!(merge_hypotheses(&words("только основной", 0.5), &[]).0, "только основной");
+        assert_eq!(merge_hypotheses(&[], &words("только второй", 0.5)).0, "только второй");
+    }
+
+    #[test]
+    fn merger_emits_session_transcript_on_change() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_cb = events.clone();
+        let merger = EnsembleMerger::new(Arc::new(move |t: EnsembleTranscript| events_cb.lock().unwrap().push(t.transcript)));
+
+        merger.push_final(true, "Встреча в среду.", Some(0.9));
+        merger.push_final(false, "Встреча в среду.", Some(0.8));
+        // Основной отстаёт — слова второго провайдера уже в ансамбле
+        merger.push_final(false, "Бюджет утверждён.", Some(0.9));
+        // Основной ошибся менее уверенно — ансамбль не меняется
+        merger.push_final(true, "Бюджет отвергнут.", Some(0.6));
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "Встреча в среду.".to_string(),
+                "Встреча в среду. Бюджет утверждён.".to_string(),
+            ]
+        );
+    }
+}
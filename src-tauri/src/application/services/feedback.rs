@@ -0,0 +1,97 @@
+use std::sync::{Arc, Mutex};
+
+use crate::domain::{FeedbackConfig, FeedbackEvent, FeedbackSink};
+
+/// Dispatches recording events to feedback devices (sound now, haptics later).
+///
+/// Конфиг фиксируется на старте записи: профиль записи может задать свои звуки,
+/// а в режиме фокусировки ОС сессия идёт без обратной связи. Звуки играются на устройстве
+/// вывода и не проходят через захват звука — в запись и в STT они не попадают.
+pub struct FeedbackService {
+    sinks: Vec<Arc<dyn FeedbackSink>>,
+    /// Конфиг профиля записи для следующей сессии (как override STT у TranscriptionService)
+    next_session_override: Mutex<Option<FeedbackConfig>>,
+    /// Конфиг текущей сессии; None — без обратной связи
+    session: Mutex<Option<FeedbackConfig>>,
+}
+
+impl FeedbackService {
+    pub fn new(sinks: Vec<Arc<dyn FeedbackSink>>) -> Self {
+        Self {
+            sinks,
+            next_session_override: Mutex::new(None),
+            session: Mutex::new(None),
+        }
+    }
+
+    pub fn set_next_session_override(&self, config: Option<FeedbackConfig>) {
+        if let Ok(mut next) = self.next_session_override.lock() {
+            *next = config;
+        }
+    }
+
+    /// Начало записи: конфиг профиля (если был задан) или общий; `quiet` — без обратной связи
+    pub fn begin_session(&self, default: FeedbackConfig, quiet: bool) {
+        let profile = self.next_session_override.lock().ok().and_then(|mut next| next.take());
+        let config = (!quiet).then(|| profile.unwrap_or(default));
+        if let Ok(mut session) = self.session.lock() {
+            *session = config;
+        }
+    }
+
+    pub fn notify(&self, event: FeedbackEvent) {
+        let Some(config) = self.session.lock().ok().and_then(|session| *session) else {
+            return;
+        };
+        for sink in &self.sinks {
+            log::debug!("Feedback {:?} -> {}", event, sink.name());
+            sink.notify(event, &config);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<(FeedbackEvent, u8)>>,
+    }
+
+    impl FeedbackSink for RecordingSink {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn notify(&self, event: FeedbackEvent, config: &FeedbackConfig) {
+            self.events.lock().unwrap().push((event, config.volume));
+        }
+    }
+
+    #[test]
+    fn profile_override_applies_to_one_session() {
+        let sink = Arc::new(RecordingSink::default());
+        let service = FeedbackService::new(vec![sink.clone()]);
+        let default = FeedbackConfig::default();
+
+        // До первой записи сессии нет — молчим
+        service.notify(FeedbackEvent::Error);
+
+        service.set_next_session_override(Some(FeedbackConfig { volume: 20, ..default }));
+        service.begin_session(default, false);
+        service.notify(FeedbackEvent::RecordingStarted);
+
+        service.begin_session(default, false);
+        service.notify(FeedbackEvent::RecordingStopped);
+
+        service.set_next_session_override(Some(FeedbackConfig { volume: 20, ..default }));
+        service.begin_session(default, true);
+        service.notify(FeedbackEvent::RecordingStarted);
+
+        assert_eq!(
+            *sink.events.lock().unwrap(),
+            vec![(FeedbackEvent::RecordingStarted, 20), (FeedbackEvent::RecordingStopped, 60)]
+        );
+    }
+}
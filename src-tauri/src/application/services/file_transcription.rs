@@ -0,0 +1,154 @@
+//! Пакетная расшифровка аудиофайлов.
+//!
+//! Стриминговые провайдеры рассчитаны на реплики, а не на час аудио в одном соединении,
+//! поэтому файл режется на куски. Граница куска сдвигается на самое тихое место рядом с ней,
+//! чтобы не резать слово пополам. Через REST API провайдера куски отправляются параллельно,
+//! через стриминг — по очереди; результаты в любом случае приходят в порядке кусков.
+
+use std::future::Future;
+use std::ops::Range;
+
+use futures_util::{Stream, StreamExt};
+
+/// Длина куска файла, секунды
+pub const FILE_CHUNK_SECS: usize = 30;
+
+/// В каком хвосте куска искать тишину для границы, секунды
+const CUT_SEARCH_SECS: usize = 2;
+
+/// Окно оценки громкости при поиске тишины, миллисекунды
+const CUT_FRAME_MS: usize = 20;
+
+/// Делит `samples` на куски примерно по `chunk_len` сэмплов, сдвигая каждую границу
+/// назад (не дальше `search_len`) на самое тихое окно в `frame_len` сэмплов.
+pub fn split_at_quiet_points(samples: &[i16], chunk_len: usize, search_len: usize, frame_len: usize) -> Vec<Range<usize>> {
+    let chunk_len = chunk_len.max(1);
+    let frame_len = frame_len.clamp(1, chunk_len);
+    let search_len = search_len.min(chunk_len / 2);
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while samples.len() - start > chunk_len {
+        let nominal_end = start + chunk_len;
+        let mut best = (u64::MAX, nominal_end);
+        let mut frame_start = nominal_end - search_len;
+        while frame_start + frame_len <= nominal_end {
+            let energy: u64 = samples[frame_start..frame_start + frame_len]
+                .iter()
+                .map(|&s| (s as i32).unsigned_abs() as u64)
+                .sum();
+            // При равенстве берём более позднее окно — кусок ближе к номинальной длине
+            if energy <= best.0 {
+                best = (energy, frame_start + frame_len / 2);
+            }
+            frame_start += frame_len;
+        }
+        ranges.push(start..best.1);
+        start = best.1;
+    }
+    if start < samples.len() {
+        ranges.push(start..samples.len());
+    }
+    ranges
+}
+
+/// Куски файла (16kHz mono PCM) начиная с `offset` — с этого места продолжается прерванная задача
+pub fn file_chunk_ranges(samples: &[i16], sample_rate: u32, offset: usize) -> Vec<Range<usize>> {
+    let rate = sample_rate.max(1) as usize;
+    let offset = offset.min(samples.len());
+    split_at_quiet_points(
+        &samples[offset..],
+        FILE_CHUNK_SECS * rate,
+        CUT_SEARCH_SECS * rate,
+        CUT_FRAME_MS * rate / 1000,
+    )
+    .into_iter()
+    .map(|range| range.start + offset..range.end + offset)
+    .collect()
+}
+
+/// Запускает `transcribe` для кусков, держа до `concurrency` в полёте; результаты — строго по порядку кусков,
+/// чтобы готовые главы можно было сохранять подряд (и продолжать прерванную задачу с последней)
+pub fn transcribe_chunks<'a, F, Fut>(
+    ranges: Vec<Range<usize>>,
+    concurrency: usize,
+    transcribe: F,
+) -> impl Stream<Item = (Range<usize>, anyhow::Result<String>)> + 'a
+where
+    F: Fn(Range<usize>) -> Fut + 'a,
+    Fut: Future<Output = anyhow::Result<String>> + 'a,
+{
+    futures_util::stream::iter(ranges)
+        .map(move |range| {
+            let result = transcribe(range.clone());
+            async move { (range, result.await) }
+        })
+        .buffered(concurrency.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_move_to_the_quietest_frame() {
+        // Громко везде, кроме окна 80..90 перед номинальной границей 100
+        let mut samples = vec![1000i16; 250];
+        for s in &mut samples[80..90] {
+            *s = 0;
+        }
+        let ranges = split_at_quiet_points(&samples, 100, 30, 10);
+        assert_eq!(ranges[0], 0..85);
+        assert_eq!(ranges[1].start, 85);
+        assert_eq!(ranges.last().unwrap().end, 250);
+        // Куски идут встык и покрывают весь файл
+        assert!(ranges.windows(2).all(|w| w[0].end == w[1].start));
+    }
+
+    #[test]
+    fn short_and_empty_input() {
+        assert_eq!(split_at_quiet_points(&[1i16; 50], 100, 30, 10), vec![0..50]);
+        assert!(split_at_quiet_points(&[], 100, 30, 10).is_empty());
+        // Ровная тишина: граница остаётся у номинальной длины
+        assert_eq!(split_at_quiet_points(&[0i16; 200], 100, 30, 10), vec![0..95, 95..190, 190..200]);
+    }
+
+    #[test]
+    fn chunk_ranges_continue_from_offset() {
+        let samples = vec![0i16; 16_000 * 65];
+        let ranges = file_chunk_ranges(&samples, 16_000, 16_000 * 10);
+        assert_eq!(ranges.first().unwrap().start, 16_000 * 10);
+        assert_eq!(ranges.last().unwrap().end, samples.len());
+        assert_eq!(ranges.len(), 2);
+        assert!(file_chunk_ranges(&samples, 16_000, samples.len() + 1).is_empty());
+    }
+
+    #[tokio::test]
+    async fn chunks_run_concurrently_but_arrive_in_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let ranges: Vec<Range<usize>> = (0..6).map(|i| i * 10..(i + 1) * 10).collect();
+
+        let results: Vec<_> = transcribe_chunks(ranges, 3, |range| {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                // Первые куски отвечают дольше последних
+                tokio::time::sleep(std::time::Duration::from_millis(30 - range.start as u64 / 2)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(format!("chunk {}", range.start / 10))
+            }
+        })
+        .collect()
+        .await;
+
+        let texts: Vec<String> = results.into_iter().map(|(_, r)| r.unwrap()).collect();
+        assert_eq!(texts, (0..6).map(|i| format!("chunk {}", i)).collect::<Vec<_>>());
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+    }
+}
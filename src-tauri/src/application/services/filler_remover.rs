@@ -0,0 +1,209 @@
+use super::bare_word;
+use crate::domain::{capitalize_first, FillerAggressiveness, FillerRemovalConfig};
+
+/// Removes filler words ("um", "uh", "ну", "эээ") from final text.
+///
+/// Списки слов — по языку фразы и уровню агрессивности, плюс свои слова пользователя.
+/// Слово удаляется только целиком вместе с обрамляющими запятыми, а если оно начинало
+/// предложение — следующее слово получает заглавную букву.
+#[derive(Debug, Clone)]
+pub struct FillerRemover {
+    /// Звуки-заминки: совпадают и растянутые варианты ("ummm", "эээээ")
+    sounds: Vec<&'static str>,
+    /// Слова и фразы, совпадающие только точно (без учёта регистра), по словам
+    phrases: Vec<Vec<String>>,
+}
+
+const EN_SOUNDS: &[&str] = &["um", "umm", "uh", "uhm", "er", "erm", "hm", "hmm", "mm", "ah"];
+const RU_SOUNDS: &[&str] = &["э", "эм", "эмм", "ээ", "мм", "хм", "ммм", "аа", "ам"];
+
+const EN_MODERATE: &[&str] = &["you know", "i mean"];
+const RU_MODERATE: &[&str] = &["ну", "как бы", "типа", "короче", "это самое"];
+
+const EN_AGGRESSIVE: &[&str] = &["like", "basically", "actually", "literally", "kind of", "sort of"];
+const RU_AGGRESSIVE: &[&str] = &["вот", "значит", "в общем", "так сказать", "собственно", "в принципе"];
+
+/// Знаки, которые остаются на месте удалённого слова (конец предложения)
+const SENTENCE_END: &[char] = &['.', '!', '?', '…'];
+
+impl FillerRemover {
+    /// None — удаление выключено или для языка нечего удалять
+    pub fn new(language: &str, config: &FillerRemovalConfig) -> Option<Self> {
+        let custom = config.custom_words_for(language)?;
+        let base = language.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        let (sounds, moderate, aggressive): (&[&str], &[&str], &[&str]) = match base.as_str() {
+            "en" => (EN_SOUNDS, EN_MODERATE, EN_AGGRESSIVE),
+            "ru" => (RU_SOUNDS, RU_MODERATE, RU_AGGRESSIVE),
+            _ => (&[], &[], &[]),
+        };
+
+        let mut phrases: Vec<&str> = Vec::new();
+        if config.aggressiveness != FillerAggressiveness::Conservative {
+            phrases.extend(moderate);
+        }
+        if config.aggressiveness == FillerAggressiveness::Aggressive {
+            phrases.extend(aggressive);
+        }
+        let mut phrases: Vec<Vec<String>> = phrases
+            .into_iter()
+            .map(str::to_string)
+            .chain(custom)
+            .map(|phrase| phrase.split_whitespace().map(|w| w.to_lowercase()).collect())
+            .collect();
+        // Длинные фразы первыми: "как бы" не должно потерять "бы" из-за "как"
+        phrases.sort_by_key(|words: &Vec<String>| std::cmp::Reverse(words.len()));
+
+        if sounds.is_empty() && phrases.is_empty() {
+            return None;
+        }
+        Some(Self {
+            sounds: sounds.to_vec(),
+            phrases,
+        })
+    }
+
+    pub fn remove(&self, text: &str) -> String {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let mut out: Vec<String> = Vec::with_capacity(tokens.len());
+        // Следующее оставленное слово начинает предложение вместо удалённого
+        let mut capitalize_next = false;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let Some(len) = self.filler_len(&tokens[i..]) else {
+                let mut word = tokens[i].to_string();
+                if capitalize_next {
+                    word = capitalize_first(&word);
+                    capitalize_next = false;
+                }
+                out.push(word);
+                i += 1;
+                continue;
+            };
+
+            let last = tokens[i + len - 1];
+            let trailing: String = last.chars().rev().take_while(|c| !c.is_alphanumeric()).collect();
+            let starts_sentence = out.last().map_or(true, |prev| prev.ends_with(SENTENCE_END));
+            if starts_sentence && tokens[i].chars().next().is_some_and(char::is_uppercase) {
+                capitalize_next = true;
+            }
+            // Вставка в запятых уходит вместе с ними: "should, uh, go" → "should go"
+            if trailing.contains(',') {
+                if let Some(prev) = out.last_mut().filter(|prev| prev.ends_with(',')) {
+                    prev.pop();
+                }
+            }
+            // Конец предложения переносим на предыдущее слово: "we should, uh." → "we should."
+            if let Some(end) = trailing.chars().find(|c| SENTENCE_END.contains(c)) {
+                if let Some(prev) = out.last_mut() {
+                    let trimmed = prev.trim_end_matches([',', ';', ':']).len();
+                    prev.truncate(trimmed);
+                    if !prev.ends_with(SENTENCE_END) {
+                        prev.push(end);
+                    }
+                    capitalize_next = true;
+                }
+            }
+            i += len;
+        }
+        out.join(" ")
+    }
+
+    /// Сколько слов с начала `tokens` занимает слово-паразит
+    fn filler_len(&self, tokens: &[&str]) -> Option<usize> {
+        let first = bare_word(tokens[0]);
+        if self.sounds.iter().any(|sound| is_sound(&first, sound)) {
+            return Some(1);
+        }
+        self.phrases
+            .iter()
+            .find(|phrase| {
+                phrase.len() <= tokens.len()
+                    && phrase.iter().zip(tokens).enumerate().all(|(n, (word, token))| {
+                        // Внутри фразы знаков нет: "как, бы" — это уже не "как бы"
+                        let inner = n + 1 < phrase.len();
+                        bare_word(token) == *word && (!inner || token.chars().all(char::is_alphanumeric))
+                    })
+            })
+            .map(Vec::len)
+    }
+}
+
+/// "ummm" ~ "um", "эээээ" ~ "э": повторы букв схлопываются, но короче образца слово быть не может
+/// (иначе союз "а" совпал бы с "аа")
+fn is_sound(word: &str, sound: &str) -> bool {
+    fn collapse(s: &str) -> String {
+        let mut out = String::new();
+        for c in s.chars() {
+            if !out.ends_with(c) {
+                out.push(c);
+            }
+        }
+        out
+    }
+    !word.is_empty() && word.chars().count() >= sound.chars().count() && collapse(word) == collapse(sound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remover(language: &str, aggressiveness: FillerAggressiveness) -> FillerRemover {
+        let config = FillerRemovalConfig {
+            enabled: true,
+            aggressiveness,
+            ..FillerRemovalConfig::default()
+        };
+        FillerRemover::new(language, &config).expect("language has built-in fillers")
+    }
+
+    #[test]
+    fn removes_hesitation_sounds_with_punctuation() {
+        let r = remover("en-US", FillerAggressiveness::Conservative);
+        assert_eq!(r.remove("Um, I think we should, uh, go."), "I think we should go.");
+        assert_eq!(r.remove("We should go, ummm."), "We should go.");
+        assert_eq!(r.remove("Umm... so what now"), "So what now");
+        // Умеренные вставки на этом уровне остаются
+        assert_eq!(r.remove("you know, it works"), "you know, it works");
+
+        let r = remover("ru", FillerAggressiveness::Conservative);
+        assert_eq!(r.remove("Эээ, давай начнём. Ммм, с чего?"), "Давай начнём. С чего?");
+        // Союз "а" — не растянутое "аа"
+        assert_eq!(r.remove("а потом аааа ушёл"), "а потом ушёл");
+    }
+
+    #[test]
+    fn aggressiveness_widens_the_list() {
+        let text = "Ну, я как бы вот думаю, что это, в общем, работает";
+        assert_eq!(
+            remover("ru", FillerAggressiveness::Moderate).remove(text),
+            "Я вот думаю, что это, в общем, работает"
+        );
+        assert_eq!(
+            remover("ru", FillerAggressiveness::Aggressive).remove(text),
+            "Я думаю, что это работает"
+        );
+        assert_eq!(
+            remover("en", FillerAggressiveness::Aggressive).remove("It's, like, basically done, you know?"),
+            "It's done?"
+        );
+        // Внутри фразы запятая: это уже не "как бы"
+        assert_eq!(remover("ru", FillerAggressiveness::Moderate).remove("как, бы ни было"), "как, бы ни было");
+    }
+
+    #[test]
+    fn custom_words_and_unknown_languages() {
+        let mut config = FillerRemovalConfig {
+            enabled: true,
+            ..FillerRemovalConfig::default()
+        };
+        assert!(FillerRemover::new("de", &config).is_none());
+
+        config.languages.insert("de".to_string(), vec!["äh".to_string(), "sozusagen".to_string()]);
+        let r = FillerRemover::new("de-DE", &config).unwrap();
+        assert_eq!(r.remove("Das ist, äh, sozusagen fertig"), "Das ist fertig");
+
+        config.enabled = false;
+        assert!(FillerRemover::new("en", &config).is_none());
+    }
+}
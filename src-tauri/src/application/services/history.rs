@@ -0,0 +1,265 @@
+use chrono::{DateTime, TimeZone};
+use std::fmt::Display;
+
+use crate::domain::{
+    HistoryFilter, HistoryFullText, HistoryItem, HistoryPage, HistoryTagCount, SessionStats, Transcription,
+    MAX_HISTORY_PAGE_SIZE,
+};
+
+/// Финальные фразы приходят и после остановки записи (провайдер дорабатывает хвост)
+const SESSION_FINALS_GRACE_SECS: i64 = 15;
+
+/// Добавляет финальную фразу в историю и возвращает id новой записи.
+///
+/// Сверх `max_items` вытесняются самые старые записи, кроме избранных: избранное пользователь
+/// отметил сам, и терять его из-за лимита нельзя (поэтому избранных может быть больше лимита).
+pub fn append_history(history: &mut Vec<HistoryItem>, transcription: Transcription, max_items: usize) -> u64 {
+    let id = history.iter().map(|item| item.id).max().unwrap_or(0) + 1;
+    history.push(HistoryItem::new(id, transcription));
+
+    let mut excess = history.len().saturating_sub(max_items);
+    if excess > 0 {
+        history.retain(|item| {
+            if excess > 0 && !item.favorite {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+    id
+}
+
+/// Записи под фильтром, новые первыми, не больше `limit`
+pub fn filter_history(history: &[HistoryItem], filter: &HistoryFilter, limit: Option<usize>) -> Vec<HistoryItem> {
+    history
+        .iter()
+        .rev()
+        .filter(|item| filter.matches(item))
+        .take(limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect()
+}
+
+/// Сжимает все записи, кроме последней, до превью; возвращает полные тексты сжатых записей
+/// (с журналом правок), которые нужно сохранить на диск.
+///
+/// Последняя запись остаётся целиком: replace_last_final подменяет её текст альтернативой.
+pub fn compact_history(history: &mut [HistoryItem]) -> Vec<(u64, HistoryFullText)> {
+    let Some((_, older)) = history.split_last_mut() else {
+        return Vec::new();
+    };
+    older
+        .iter_mut()
+        .filter_map(|item| item.compact().map(|full| (item.id, full)))
+        .collect()
+}
+
+/// Страница записей под фильтром, новые первыми; `limit` ограничен MAX_HISTORY_PAGE_SIZE.
+/// Тексты сжатых записей в странице остаются превью — догружает их вызывающий.
+pub fn page_history(history: &[HistoryItem], filter: &HistoryFilter, offset: usize, limit: usize) -> HistoryPage {
+    let matching: Vec<&HistoryItem> = history.iter().rev().filter(|item| filter.matches(item)).collect();
+    HistoryPage {
+        total: matching.len(),
+        items: matching
+            .into_iter()
+            .skip(offset)
+            .take(limit.clamp(1, MAX_HISTORY_PAGE_SIZE))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Все метки истории с числом записей: частые первыми, при равенстве — по алфавиту.
+/// Регистр не различается, показывается первая встреченная форма.
+pub fn list_history_tags(history: &[HistoryItem]) -> Vec<HistoryTagCount> {
+    let mut tags: Vec<HistoryTagCount> = Vec::new();
+    for tag in history.iter().flat_map(|item| item.tags.iter()) {
+        let lower = tag.to_lowercase();
+        match tags.iter_mut().find(|t| t.tag.to_lowercase() == lower) {
+            Some(existing) => existing.count += 1,
+            None => tags.push(HistoryTagCount {
+                tag: tag.clone(),
+                count: 1,
+            }),
+        }
+    }
+    tags.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.tag.to_lowercase().cmp(&b.tag.to_lowercase()))
+    });
+    tags
+}
+
+/// Фразы одной сессии записи по времени: история не помнит сессию фразы, поэтому
+/// берём всё, что пришло от старта записи до её конца (плюс SESSION_FINALS_GRACE_SECS)
+pub fn session_history(history: &[HistoryItem], stats: &SessionStats) -> Vec<HistoryItem> {
+    let from = stats.started_at.div_euclid(1000);
+    let to = from + stats.duration_secs.ceil() as i64 + SESSION_FINALS_GRACE_SECS;
+    let mut items: Vec<HistoryItem> = history
+        .iter()
+        .filter(|item| (from..=to).contains(&item.transcription.timestamp))
+        .cloned()
+        .collect();
+    items.sort_by_key(|item| (item.transcription.timestamp, item.id));
+    items
+}
+
+/// Текст для экспорта: записи по времени, каждая с датой и своими метками, через пустую строку
+pub fn export_history_text<Tz: TimeZone>(items: &[HistoryItem], tz: &Tz) -> String
+where
+    Tz::Offset: Display,
+{
+    let mut items: Vec<&HistoryItem> = items.iter().collect();
+    items.sort_by_key(|item| (item.transcription.timestamp, item.id));
+
+    items
+        .iter()
+        .map(|item| {
+            let mut header = DateTime::from_timestamp(item.transcription.timestamp, 0)
+                .map(|utc| utc.with_timezone(tz).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            if item.favorite {
+                header.push_str(" ★");
+            }
+            for tag in &item.tags {
+                header.push_str(" #");
+                header.push_str(tag);
+            }
+            format!("{}\n{}", header.trim_start(), item.transcription.text.trim())
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn phrase(text: &str, timestamp: i64) -> Transcription {
+        let mut transcription = Transcription::final_result(text.to_string());
+        transcription.timestamp = timestamp;
+        transcription
+    }
+
+    #[test]
+    fn append_assigns_ids_and_keeps_favorites_past_the_limit() {
+        let mut history = Vec::new();
+        assert_eq!(append_history(&mut history, phrase("один", 1), 2), 1);
+        assert_eq!(append_history(&mut history, phrase("два", 2), 2), 2);
+        history[0].favorite = true;
+
+        assert_eq!(append_history(&mut history, phrase("три", 3), 2), 3);
+        let texts: Vec<_> = history.iter().map(|i| i.transcription.text.as_str()).collect();
+        assert_eq!(texts, vec!["один", "три"]);
+
+        // id не переиспользуются после вытеснения
+        assert_eq!(append_history(&mut history, phrase("четыре", 4), 2), 4);
+        let ids: Vec<_> = history.iter().map(|i| i.id).collect();
+        assert_eq!(ids, vec![1, 4]);
+    }
+
+    #[test]
+    fn filters_newest_first_and_counts_tags() {
+        let mut history = Vec::new();
+        for (i, text) in ["a", "b", "c"].iter().enumerate() {
+            append_history(&mut history, phrase(text, i as i64), 10);
+        }
+        history[0].tags = vec!["blog".to_string()];
+        history[1].tags = vec!["Email".to_string(), "Blog".to_string()];
+        history[2].tags = vec!["email".to_string()];
+        history[2].favorite = true;
+
+        let filter = HistoryFilter {
+            tag: Some("BLOG".to_string()),
+            favorites_only: false,
+        };
+        let ids: Vec<_> = filter_history(&history, &filter, None).iter().map(|i| i.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(filter_history(&history, &HistoryFilter::default(), Some(1))[0].id, 3);
+
+        assert_eq!(
+            list_history_tags(&history),
+            vec![
+                HistoryTagCount {
+                    tag: "blog".to_string(),
+                    count: 2
+                },
+                HistoryTagCount {
+                    tag: "Email".to_string(),
+                    count: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn compacts_all_but_the_newest_item_and_pages_newest_first() {
+        let long = "слово ".repeat(100);
+        let mut history = Vec::new();
+        for i in 0..3 {
+            append_history(&mut history, phrase(&long, i), 10);
+        }
+        append_history(&mut history, phrase("коротко", 3), 10);
+        append_history(&mut history, phrase(&long, 4), 10);
+
+        let compacted: Vec<u64> = compact_history(&mut history).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(compacted, vec![1, 2, 3]);
+        assert!(!history[3].text_truncated);
+        assert!(!history[4].text_truncated);
+        assert!(compact_history(&mut history).is_empty());
+
+        let page = page_history(&history, &HistoryFilter::default(), 1, 2);
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.iter().map(|i| i.id).collect::<Vec<_>>(), vec![4, 3]);
+
+        let past_end = page_history(&history, &HistoryFilter::default(), 10, 2);
+        assert_eq!(past_end.total, 5);
+        assert!(past_end.items.is_empty());
+    }
+
+    #[test]
+    fn exports_in_chronological_order_with_tags() {
+        let mut history = Vec::new();
+        append_history(&mut history, phrase(" второй ", 1_700_000_060), 10);
+        append_history(&mut history, phrase("первый", 1_700_000_000), 10);
+        history[0].tags = vec!["blog".to_string()];
+        history[0].favorite = true;
+
+        assert_eq!(
+            export_history_text(&history, &Utc),
+            "2023-11-14 22:13\nпервый\n\n2023-11-14 22:14 ★ #blog\nвторой"
+        );
+    }
+
+    #[test]
+    fn session_history_takes_phrases_of_the_recording_window() {
+        let mut history = Vec::new();
+        append_history(&mut history, phrase("прошлая сессия", 1_700_000_000), 10);
+        append_history(&mut history, phrase("после остановки", 1_700_000_130), 10);
+        append_history(&mut history, phrase("начало", 1_700_000_100), 10);
+        append_history(&mut history, phrase("следующая сессия", 1_700_000_200), 10);
+
+        let stats = SessionStats {
+            session_id: 7,
+            started_at: 1_700_000_100_500,
+            duration_secs: 20.0,
+            speaking_secs: 0.0,
+            silence_secs: 0.0,
+            word_count: 3,
+            words_per_minute: 0.0,
+            speaking_ratio: 0.0,
+            language: None,
+            provider: crate::domain::SttProviderType::Deepgram,
+            app: None,
+        };
+        let texts: Vec<_> = session_history(&history, &stats)
+            .into_iter()
+            .map(|item| item.transcription.text)
+            .collect();
+        assert_eq!(texts, vec!["начало", "после остановки"]);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+
+use super::max_amplitude;
+
+/// Частота сигнала теста микрофона (после ресемплинга захвата)
+const SAMPLE_RATE: usize = 16_000;
+
+/// Сэмпл на этом уровне и выше на входе — устройство уже клиппирует, усиление не поможет
+const INPUT_CLIP_LEVEL: i32 = 32_700;
+
+/// Флаг клиппинга держим полсекунды, чтобы предупреждение не мигало на отдельных пиках
+const CLIP_HOLD_SAMPLES: usize = SAMPLE_RATE / 2;
+
+/// "Слишком тихо": за 2 секунды пик после усиления так и не поднялся выше ~-30 dBFS
+const QUIET_WINDOW_SAMPLES: usize = SAMPLE_RATE * 2;
+const QUIET_PEAK_LEVEL: i32 = 1_000;
+
+/// Warnings for the microphone test, computed from the raw input and the configured gain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputLevelFlags {
+    /// Вход клиппирует при текущем усилении (или уже на самом устройстве)
+    pub clipping: bool,
+    /// Сигнал слишком тихий даже после усиления
+    pub too_quiet: bool,
+    /// Пик чанка после усиления, dBFS
+    pub peak_dbfs: f32,
+}
+
+/// Tracks clipping / too-quiet state over a sliding window of 16kHz chunks
+pub struct InputLevelMonitor {
+    requested_gain: f32,
+    samples_since_clip: Option<usize>,
+    /// (длина чанка, пик после усиления)
+    recent_peaks: VecDeque<(usize, i32)>,
+    recent_samples: usize,
+}
+
+impl InputLevelMonitor {
+    pub fn new(requested_gain: f32) -> Self {
+        Self {
+            requested_gain,
+            samples_since_clip: None,
+            recent_peaks: VecDeque::new(),
+            recent_samples: 0,
+        }
+    }
+
+    /// `chunk` — сырой вход (до усиления)
+    pub fn push(&mut self, chunk: &[i16]) -> InputLevelFlags {
+        let raw_peak = max_amplitude(chunk);
+        let gained_peak = raw_peak as f32 * self.requested_gain;
+
+        // Без лимитера такое усиление упёрлось бы в полную шкалу
+        let clipped_now = raw_peak >= INPUT_CLIP_LEVEL || gained_peak >= 32767.0;
+        self.samples_since_clip = if clipped_now {
+            Some(0)
+        } else {
+            self.samples_since_clip.map(|n| n.saturating_add(chunk.len()))
+        };
+
+        let gained_peak_i32 = gained_peak.min(32767.0) as i32;
+        self.recent_peaks.push_back((chunk.len(), gained_peak_i32));
+        self.recent_samples += chunk.len();
+        while let Some(&(len, _)) = self.recent_peaks.front() {
+            if self.recent_samples - len < QUIET_WINDOW_SAMPLES {
+                break;
+            }
+            self.recent_samples -= len;
+            self.recent_peaks.pop_front();
+        }
+        let window_peak = self.recent_peaks.iter().map(|&(_, peak)| peak).max().unwrap_or(0);
+
+        InputLevelFlags {
+            clipping: self.samples_since_clip.is_some_and(|n| n < CLIP_HOLD_SAMPLES),
+            too_quiet: self.recent_samples >= QUIET_WINDOW_SAMPLES && window_peak < QUIET_PEAK_LEVEL,
+            peak_dbfs: if gained_peak > 0.0 {
+                (20.0 * (gained_peak / 32767.0).min(1.0).log10()).max(-120.0)
+            } else {
+                -120.0
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gain_that_would_clip_is_flagged_and_held_briefly() {
+        let mut monitor = InputLevelMonitor::new(5.0);
+
+        let flags = monitor.push(&[8000; 480]);
+        assert!(flags.clipping);
+        assert_eq!(flags.peak_dbfs, 0.0);
+
+        // Тихие чанки: флаг держится ~0.5 с, затем гаснет
+        let mut cleared_after = 0;
+        for i in 1..40 {
+            if !monitor.push(&[1000; 480]).clipping {
+                cleared_after = i;
+                break;
+            }
+        }
+        assert_eq!(cleared_after, CLIP_HOLD_SAMPLES.div_ceil(480));
+    }
+
+    #[test]
+    fn clipped_input_is_flagged_at_any_gain() {
+        let mut monitor = InputLevelMonitor::new(0.5);
+        assert!(monitor.push(&[0, 32767, -200]).clipping);
+    }
+
+    #[test]
+    fn too_quiet_needs_a_full_window_without_loud_peaks() {
+        let mut monitor = InputLevelMonitor::new(1.0);
+        let chunks = QUIET_WINDOW_SAMPLES.div_ceil(480);
+
+        for _ in 0..chunks - 1 {
+            assert!(!monitor.push(&[300; 480]).too_quiet);
+        }
+        assert!(monitor.push(&[300; 480]).too_quiet);
+
+        // Фраза нормальной громкости снимает флаг, пока она в окне
+        let flags = monitor.push(&[6000; 480]);
+        assert!(!flags.too_quiet);
+        assert!(!flags.clipping);
+
+        let mut quiet_again = false;
+        for _ in 0..chunks {
+            quiet_again = monitor.push(&[300; 480]).too_quiet;
+        }
+        assert!(quiet_again);
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use crate::application::PARAGRAPH_BREAK;
+use crate::domain::{smart_append_text, Transcription};
+
+/// Segment-level dedup for the instant paste mode.
+///
+/// Вставляем каждый стабилизированный сегмент (is_final) сразу, не дожидаясь конца фразы (speech_final).
+/// Провайдер может прислать тот же сегмент повторно (переподключение, финальный повтор при остановке),
+/// и повтор не должен попасть в документ второй раз. Сегменты с таймингами сверяем по началу,
+/// без таймингов (локальный Whisper) — по тексту предыдущего.
+#[derive(Debug, Default)]
+pub struct InstantPasteTracker {
+    /// Начала уже вставленных сегментов, в миллисекундах
+    pasted_starts: HashSet<i64>,
+    last_text: Option<String>,
+}
+
+impl InstantPasteTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Текст для вставки; None — сегмент ещё меняется, пустой или уже вставлен.
+    /// Перед всеми сегментами, кроме первого, ставится пробел (кроме начинающихся со знака препинания),
+    /// а перед сегментом, начинающим абзац, — разрыв абзаца
+    pub fn accept(&mut self, transcription: &Transcription) -> Option<String> {
+        if !transcription.is_final {
+            return None;
+        }
+        let text = transcription.text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        let duplicate = if transcription.duration > 0.0 {
+            !self.pasted_starts.insert((transcription.start * 1000.0).round() as i64)
+        } else {
+            self.last_text.as_deref() == Some(text)
+        };
+        if duplicate {
+            log::debug!("Instant paste: skipping repeated segment '{}'", text);
+            return None;
+        }
+
+        // Сборщик отмечает абзац разрывом строки в начале сегмента
+        let paragraph = transcription.text.trim_start_matches([' ', '\t']).starts_with('\n');
+        // Знак препинания в начале сегмента пишется слитно с предыдущим
+        let insert = match self.last_text.as_deref() {
+            Some(_) if paragraph => format!("{}{}", PARAGRAPH_BREAK, text),
+            Some(last) => smart_append_text(last.chars().last(), text),
+            None => text.to_string(),
+        };
+        self.last_text = Some(text.to_string());
+        Some(insert)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start: f64, duration: f64) -> Transcription {
+        let mut transcription = Transcription::final_result(text.to_string());
+        transcription.start = start;
+        transcription.duration = duration;
+        transcription
+    }
+
+    #[test]
+    fn pastes_stabilized_segments_once_with_spacing() {
+        let mut tracker = InstantPasteTracker::new();
+        assert_eq!(tracker.accept(&Transcription::partial("при".to_string())), None);
+        assert_eq!(tracker.accept(&segment("Привет,", 0.0, 1.2)).as_deref(), Some("Привет,"));
+        // Повтор того же сегмента после переподключения
+        assert_eq!(tracker.accept(&segment("Привет", 0.0, 1.2)), None);
+        assert_eq!(tracker.accept(&segment("как дела?", 1.2, 0.8)).as_deref(), Some(" как дела?"));
+        // Тот же текст в другом месте записи — это новая фраза
+        assert_eq!(tracker.accept(&segment("как дела?", 2.5, 0.8)).as_deref(), Some(" как дела?"));
+        assert_eq!(tracker.accept(&segment("  ", 3.5, 0.1)), None);
+        assert_eq!(tracker.accept(&segment(", и всё", 3.6, 0.5)).as_deref(), Some(", и всё"));
+    }
+
+    #[test]
+    fn segments_without_timings_are_compared_by_text() {
+        let mut tracker = InstantPasteTracker::new();
+        assert_eq!(tracker.accept(&segment("Раз.", 0.0, 0.0)).as_deref(), Some("Раз."));
+        assert_eq!(tracker.accept(&segment("Раз.", 0.0, 0.0)), None);
+        assert_eq!(tracker.accept(&segment("Два.", 0.0, 0.0)).as_deref(), Some(" Два."));
+    }
+
+    #[test]
+    fn paragraph_segments_start_on_a_new_paragraph() {
+        let mut tracker = InstantPasteTracker::new();
+        // Первый сегмент вставки разрыв не получает: перед ним чужой текст
+        assert_eq!(tracker.accept(&segment("\n\nРаз.", 0.0, 1.0)).as_deref(), Some("Раз."));
+        assert_eq!(tracker.accept(&segment("\n\nДва.", 5.0, 1.0)).as_deref(), Some("\n\nДва."));
+        assert_eq!(tracker.accept(&segment("Три.", 6.0, 1.0)).as_deref(), Some(" Три."));
+    }
+}
@@ -0,0 +1,307 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Сколько завершённых сессий держим в истории для диагностики
+const MAX_HISTORY_SESSIONS: usize = 10;
+
+/// Ограничение на количество замеров в одной сессии (защита от бесконечных записей)
+const MAX_SAMPLES_PER_SESSION: usize = 2000;
+
+/// Тип замера задержки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyKind {
+    /// audio sent → partial received
+    Partial,
+    /// audio sent → final received
+    Final,
+}
+
+/// Один замер задержки (для event stream в диагностической панели)
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySample {
+    pub session_id: u64,
+    pub kind: LatencyKind,
+    pub latency_ms: f64,
+    pub stats: LatencyStats,
+}
+
+pub type LatencyListener = Arc<dyn Fn(LatencySample) + Send + Sync>;
+
+/// Агрегированная статистика по одному типу задержки
+#[derive(Debug, Clone, Default, PartialEq, Serialize, schemars::JsonSchema)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub avg_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    pub last_ms: Option<f64>,
+}
+
+impl LatencyStats {
+    pub(crate) fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        // nearest-rank p95
+        let rank = ((sorted.len() as f64) * 0.95).ceil() as usize;
+        let p95 = sorted[rank.clamp(1, sorted.len()) - 1];
+        let sum: f64 = sorted.iter().sum();
+
+        Self {
+            count: samples.len(),
+            avg_ms: Some(sum / samples.len() as f64),
+            p95_ms: Some(p95),
+            max_ms: sorted.last().copied(),
+            last_ms: samples.last().copied(),
+        }
+    }
+}
+
+/// Метрики задержек одной сессии записи
+#[derive(Debug, Clone, Default, Serialize, schemars::JsonSchema)]
+pub struct TranscriptionMetrics {
+    pub session_id: u64,
+    /// Сколько чанков успешно отправлено провайдеру
+    pub chunks_sent: u64,
+    pub partial: LatencyStats,
+    #[serde(rename = "final")]
+    pub final_: LatencyStats,
+}
+
+#[derive(Default)]
+struct SessionLatency {
+    session_id: u64,
+    chunks_sent: u64,
+    // Момент отправки самого раннего чанка, на который ещё не пришёл ответ.
+    pending_partial_since: Option<Instant>,
+    pending_final_since: Option<Instant>,
+    partial_samples: Vec<f64>,
+    final_samples: Vec<f64>,
+}
+
+impl SessionLatency {
+    fn snapshot(&self) -> TranscriptionMetrics {
+        TranscriptionMetrics {
+            session_id: self.session_id,
+            chunks_sent: self.chunks_sent,
+            partial: LatencyStats::from_samples(&self.partial_samples),
+            final_: LatencyStats::from_samples(&self.final_samples),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.chunks_sent == 0 && self.partial_samples.is_empty() && self.final_samples.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct TrackerInner {
+    current: SessionLatency,
+    history: VecDeque<TranscriptionMetrics>,
+    listener: Option<LatencyListener>,
+}
+
+/// Трекер задержек STT: audio sent → partial/final received.
+///
+/// Меряем на границе TranscriptionService ↔ SttProvider, поэтому метрики одинаково
+/// работают для всех провайдеров и не требуют правок в каждом из них.
+///
+/// Модель замера: при отправке чанка запоминаем время, если "ожидающего" чанка ещё нет.
+/// Пришедший partial/final закрывает ожидание и даёт один замер. Это оценка сверху
+/// для самого старого неподтверждённого аудио — ровно то, что видит пользователь.
+#[derive(Default)]
+pub struct LatencyTracker {
+    inner: Mutex<TrackerInner>,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Начинает новую сессию. Предыдущая (если в ней что-то было) уходит в историю.
+    pub fn begin_session(&self, session_id: u64) {
+        let mut inner = self.lock();
+        let previous = std::mem::take(&mut inner.current);
+        if !previous.is_empty() {
+            inner.history.push_back(previous.snapshot());
+            while inner.history.len() > MAX_HISTORY_SESSIONS {
+                inner.history.pop_front();
+            }
+        }
+        inner.current.session_id = session_id;
+    }
+
+    pub fn set_listener(&self, listener: Option<LatencyListener>) {
+        self.lock().listener = listener;
+    }
+
+    /// Чанк успешно отправлен провайдеру
+    pub fn record_audio_sent(&self) {
+        self.record_audio_sent_at(Instant::now());
+    }
+
+    /// Пришёл partial — возвращает замер в мс (если было что мерить)
+    pub fn record_partial(&self) -> Option<f64> {
+        self.record_response_at(LatencyKind::Partial, Instant::now())
+    }
+
+    /// Пришёл final — возвращает замер в мс (если было что мерить)
+    pub fn record_final(&self) -> Option<f64> {
+        self.record_response_at(LatencyKind::Final, Instant::now())
+    }
+
+    /// Метрики текущей (или последней) сессии
+    pub fn current(&self) -> TranscriptionMetrics {
+        self.lock().current.snapshot()
+    }
+
+    /// Метрики предыдущих сессий (от старых к новым)
+    pub fn history(&self) -> Vec<TranscriptionMetrics> {
+        self.lock().history.iter().cloned().collect()
+    }
+
+    fn record_audio_sent_at(&self, now: Instant) {
+        let mut inner = self.lock();
+        let session = &mut inner.current;
+        session.chunks_sent += 1;
+        session.pending_partial_since.get_or_insert(now);
+        session.pending_final_since.get_or_insert(now);
+    }
+
+    fn record_response_at(&self, kind: LatencyKind, now: Instant) -> Option<f64> {
+        let (sample, listener) = {
+            let mut inner = self.lock();
+            let session = &mut inner.current;
+
+            let since = match kind {
+                // final подтверждает и всё, что ждало partial
+                LatencyKind::Final => {
+                    session.pending_partial_since = None;
+                    session.pending_final_since.take()
+                }
+                LatencyKind::Partial => session.pending_partial_since.take(),
+            }?;
+
+            let latency_ms = now.saturating_duration_since(since).as_micros() as f64 / 1000.0;
+            let samples = match kind {
+                LatencyKind::Partial => &mut session.partial_samples,
+                LatencyKind::Final => &mut session.final_samples,
+            };
+            if samples.len() >= MAX_SAMPLES_PER_SESSION {
+                samples.remove(0);
+            }
+            samples.push(latency_ms);
+
+            let sample = LatencySample {
+                session_id: session.session_id,
+                kind,
+                latency_ms,
+                stats: LatencyStats::from_samples(samples),
+            };
+            (sample, inner.listener.clone())
+        };
+
+        // Listener вызываем вне lock'а, чтобы не словить дедлок при обращении к трекеру из callback
+        let latency_ms = sample.latency_ms;
+        if let Some(listener) = listener {
+            listener(sample);
+        }
+        Some(latency_ms)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TrackerInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn stats_compute_avg_and_p95() {
+        let samples: Vec<f64> = (1..=100).map(|v| v as f64).collect();
+        let stats = LatencyStats::from_samples(&samples);
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.avg_ms, Some(50.5));
+        assert_eq!(stats.p95_ms, Some(95.0));
+        assert_eq!(stats.max_ms, Some(100.0));
+        assert_eq!(stats.last_ms, Some(100.0));
+        assert_eq!(LatencyStats::from_samples(&[]), LatencyStats::default());
+    }
+
+    #[test]
+    fn measures_from_oldest_unacknowledged_chunk() {
+        let tracker = LatencyTracker::new();
+        tracker.begin_session(1);
+
+        let t0 = Instant::now();
+        tracker.record_audio_sent_at(t0);
+        tracker.record_audio_sent_at(t0 + Duration::from_millis(100));
+
+        let partial = tracker.record_response_at(LatencyKind::Partial, t0 + Duration::from_millis(250));
+        assert_eq!(partial, Some(250.0));
+        // Повторный partial без нового аудио — замера нет
+        assert_eq!(tracker.record_response_at(LatencyKind::Partial, t0 + Duration::from_millis(300)), None);
+
+        let fin = tracker.record_response_at(LatencyKind::Final, t0 + Duration::from_millis(400));
+        assert_eq!(fin, Some(400.0));
+
+        let metrics = tracker.current();
+        assert_eq!(metrics.session_id, 1);
+        assert_eq!(metrics.chunks_sent, 2);
+        assert_eq!(metrics.partial.count, 1);
+        assert_eq!(metrics.final_.count, 1);
+    }
+
+    #[test]
+    fn begin_session_moves_previous_into_history() {
+        let tracker = LatencyTracker::new();
+        tracker.begin_session(1);
+        tracker.record_audio_sent();
+        tracker.record_final();
+
+        tracker.begin_session(2);
+        assert_eq!(tracker.current().session_id, 2);
+        assert_eq!(tracker.current().chunks_sent, 0);
+
+        let history = tracker.history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].session_id, 1);
+        assert_eq!(history[0].final_.count, 1);
+
+        // Пустые сессии в историю не попадают
+        tracker.begin_session(3);
+        assert_eq!(tracker.history().len(), 1);
+    }
+
+    #[test]
+    fn listener_receives_samples() {
+        let tracker = LatencyTracker::new();
+        tracker.begin_session(7);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        tracker.set_listener(Some(Arc::new(move |s: LatencySample| {
+            received_clone.lock().unwrap().push((s.session_id, s.kind));
+        })));
+
+        tracker.record_audio_sent();
+        tracker.record_partial();
+        tracker.record_audio_sent();
+        tracker.record_final();
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![(7, LatencyKind::Partial), (7, LatencyKind::Final)]
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use std::time::Instant;
+
+use crate::domain::{
+    MeetingConfig, MeetingSegment, MeetingTranscript, MeetingUtterance, TopicBreak, Transcription,
+    MEETING_TRANSCRIPT_FORMAT_VERSION,
+};
+
+/// Разбивает фразы встречи на темы.
+///
+/// Новая тема начинается, если пауза перед фразой не короче `topic_gap_secs`, а текущая тема
+/// уже длится `min_topic_secs`; либо если на этой фразе есть граница от советника (LLM) —
+/// такие границы применяются всегда. Название темы берётся из границы, с которой она началась.
+pub fn segment_meeting(
+    utterances: &[MeetingUtterance],
+    config: &MeetingConfig,
+    breaks: &[TopicBreak],
+) -> Vec<MeetingSegment> {
+    let mut segments = Vec::new();
+    let mut current: Vec<MeetingUtterance> = Vec::new();
+    let mut title = break_title(breaks, 0);
+
+    for (index, utterance) in utterances.iter().enumerate() {
+        if let (Some(first), Some(last)) = (current.first(), current.last()) {
+            let gap = utterance.start_secs - last.end_secs;
+            let topic_secs = last.end_secs - first.start_secs;
+            let advised = breaks.iter().any(|b| b.utterance_index == index);
+            if advised || (gap >= config.topic_gap_secs && topic_secs >= config.min_topic_secs) {
+                segments.push(build_segment(segments.len(), std::mem::take(&mut current), title.take()));
+                title = break_title(breaks, index);
+            }
+        }
+        current.push(utterance.clone());
+    }
+    if !current.is_empty() {
+        segments.push(build_segment(segments.len(), current, title));
+    }
+    segments
+}
+
+/// Границы речи встречи для обрезки тишины: (начало, конец) в секундах от старта встречи,
+/// с запасом `padding_secs` по краям. Фраз нет — обрезать не по чему.
+pub fn speech_bounds(utterances: &[MeetingUtterance], duration_secs: f64, padding_secs: f64) -> Option<(f64, f64)> {
+    let (first, last) = (utterances.first()?, utterances.last()?);
+    let start = (first.start_secs - padding_secs).max(0.0);
+    let end = (last.end_secs + padding_secs).min(duration_secs).max(start);
+    Some((start, end))
+}
+
+fn break_title(breaks: &[TopicBreak], index: usize) -> Option<String> {
+    breaks
+        .iter()
+        .filter(|b| b.utterance_index == index)
+        .filter_map(|b| b.title.as_deref())
+        .map(str::trim)
+        .find(|title| !title.is_empty())
+        .map(str::to_string)
+}
+
+fn build_segment(index: usize, utterances: Vec<MeetingUtterance>, title: Option<String>) -> MeetingSegment {
+    let mut speakers: Vec<u32> = utterances.iter().filter_map(|u| u.speaker).collect();
+    speakers.sort_unstable();
+    speakers.dedup();
+    MeetingSegment {
+        index,
+        start_secs: utterances.first().map_or(0.0, |u| u.start_secs),
+        end_secs: utterances.last().map_or(0.0, |u| u.end_secs),
+        title,
+        speakers,
+        utterances,
+    }
+}
+
+/// Накопитель фраз одной встречи.
+///
+/// Время фразы считается по часам встречи: конец — момент получения финала, начало — конец
+/// минус длительность аудио от провайдера. Так паузы между записями (встреча на паузе) тоже
+/// видны как паузы и разделяют темы.
+pub struct MeetingRecorder {
+    started: Instant,
+    started_at_ms: i64,
+    /// Длительность встречи на момент остановки (None — ещё идёт)
+    stopped_secs: Option<f64>,
+    config: MeetingConfig,
+    language: Option<String>,
+    utterances: Vec<MeetingUtterance>,
+}
+
+impl MeetingRecorder {
+    pub fn new(config: MeetingConfig) -> Self {
+        Self {
+            started: Instant::now(),
+            started_at_ms: chrono::Utc::now().timestamp_millis(),
+            stopped_secs: None,
+            config: config.normalized(),
+            language: None,
+            utterances: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &MeetingConfig {
+        &self.config
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    pub fn utterances(&self) -> &[MeetingUtterance] {
+        &self.utterances
+    }
+
+    /// Останавливает часы встречи: дальнейшая обработка (LLM, сохранение) не входит в длительность
+    pub fn stop(&mut self) {
+        if self.stopped_secs.is_none() {
+            self.stopped_secs = Some(self.started.elapsed().as_secs_f64());
+        }
+    }
+
+    /// Финальная фраза получена сейчас
+    pub fn push_final(&mut self, transcription: &Transcription) {
+        let received_secs = self.started.elapsed().as_secs_f64();
+        self.push_final_at(transcription, received_secs);
+    }
+
+    fn push_final_at(&mut self, transcription: &Transcription, received_secs: f64) {
+        let text = transcription.text.trim();
+        if text.is_empty() {
+            return;
+        }
+        if self.language.is_none() {
+            self.language = transcription.language.clone();
+        }
+
+        // Фразы не перекрываются: провайдер мог отдать длительность с запасом
+        let previous_end = self.utterances.last().map_or(0.0, |u| u.end_secs);
+        let end_secs = received_secs.max(previous_end);
+        let start_secs = (end_secs - transcription.duration.max(0.0)).max(previous_end);
+
+        self.utterances.push(MeetingUtterance {
+            text: text.to_string(),
+            start_secs,
+            end_secs,
+            speaker: transcription.speaker,
+            channel: transcription.channel,
+            confidence: transcription.confidence,
+        });
+    }
+
+    /// Транскрипт встречи на текущий момент. `advised_breaks` — границы от LLM (None — только паузы)
+    pub fn transcript(&self, advised_breaks: Option<&[TopicBreak]>) -> MeetingTranscript {
+        let duration_secs = self
+            .stopped_secs
+            .unwrap_or_else(|| self.started.elapsed().as_secs_f64());
+        self.transcript_with_duration(duration_secs, advised_breaks)
+    }
+
+    fn transcript_with_duration(&self, mut duration_secs: f64, advised_breaks: Option<&[TopicBreak]>) -> MeetingTranscript {
+        let mut started_at = self.started_at_ms;
+        let mut utterances = self.utterances.clone();
+
+        // Обрезка тишины по краям: время фраз сдвигается к началу речи, начало встречи — тоже
+        let bounds = self
+            .config
+            .trim_silence
+            .then(|| speech_bounds(&utterances, duration_secs, self.config.trim_padding_secs))
+            .flatten();
+        if let Some((start, end)) = bounds {
+            for utterance in &mut utterances {
+                utterance.start_secs -= start;
+                utterance.end_secs -= start;
+            }
+            started_at += (start * 1000.0) as i64;
+            duration_secs = end - start;
+        }
+
+        MeetingTranscript {
+            format_version: MEETING_TRANSCRIPT_FORMAT_VERSION,
+            started_at,
+            ended_at: started_at + (duration_secs * 1000.0) as i64,
+            duration_secs,
+            language: self.language.clone(),
+            diarized: utterances.iter().any(|u| u.speaker.is_some()),
+            llm_topic_breaks: advised_breaks.is_some(),
+            segments: segment_meeting(&utterances, &self.config, advised_breaks.unwrap_or(&[])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterance(text: &str, start_secs: f64, end_secs: f64, speaker: Option<u32>) -> MeetingUtterance {
+        MeetingUtterance {
+            text: text.to_string(),
+            start_secs,
+            end_secs,
+            speaker,
+            channel: None,
+            confidence: None,
+        }
+    }
+
+    fn config(topic_gap_secs: f64, min_topic_secs: f64) -> MeetingConfig {
+        MeetingConfig {
+            topic_gap_secs,
+            min_topic_secs,
+            ..MeetingConfig::default()
+        }
+    }
+
+    #[test]
+    fn splits_topics_on_long_silence() {
+        let utterances = vec![
+            utterance("бюджет на квартал", 0.0, 20.0, Some(0)),
+            utterance("согласен", 21.0, 35.0, Some(1)),
+            // 15 секунд тишины
+            utterance("теперь про найм", 50.0, 55.0, Some(0)),
+        ];
+        let segments = segment_meeting(&utterances, &config(8.0, 30.0), &[]);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].speakers, vec![0, 1]);
+        assert_eq!((segments[0].start_secs, segments[0].end_secs), (0.0, 35.0));
+        assert_eq!(segments[1].index, 1);
+        assert_eq!(segments[1].utterances[0].text, "теперь про найм");
+        assert_eq!(segments[1].speakers, vec![0]);
+    }
+
+    #[test]
+    fn short_topic_is_not_split_by_silence() {
+        let utterances = vec![
+            utterance("начнём", 0.0, 2.0, None),
+            utterance("так, повестка", 15.0, 20.0, None),
+        ];
+        assert_eq!(segment_meeting(&utterances, &config(8.0, 30.0), &[]).len(), 1);
+        assert_eq!(segment_meeting(&utterances, &config(8.0, 0.0), &[]).len(), 2);
+        assert!(segment_meeting(&[], &config(8.0, 0.0), &[]).is_empty());
+    }
+
+    #[test]
+    fn advised_breaks_split_and_name_topics() {
+        let utterances = vec![
+            utterance("бюджет", 0.0, 2.0, None),
+            utterance("итого сто тысяч", 2.5, 4.0, None),
+            utterance("по найму", 4.5, 6.0, None),
+        ];
+        let breaks = vec![
+            TopicBreak {
+                utterance_index: 0,
+                title: Some("Бюджет".to_string()),
+            },
+            TopicBreak {
+                utterance_index: 2,
+                title: Some("  ".to_string()),
+            },
+        ];
+        let segments = segment_meeting(&utterances, &config(8.0, 30.0), &breaks);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].title.as_deref(), Some("Бюджет"));
+        assert_eq!(segments[0].utterances.len(), 2);
+        assert_eq!(segments[1].title, None);
+    }
+
+    #[test]
+    fn recorder_times_phrases_by_meeting_clock() {
+        let mut recorder = MeetingRecorder::new(config(8.0, 0.0));
+        let first = Transcription::final_result("первая фраза".to_string())
+            .with_timing(0.0, 3.0)
+            .with_speaker(Some(1));
+        recorder.push_final_at(&first, 5.0);
+        // Длительность больше времени с прошлой фразы — не перекрываем
+        recorder.push_final_at(&Transcription::final_result("вторая".to_string()).with_timing(0.0, 4.0), 6.0);
+        recorder.push_final_at(&Transcription::final_result("  ".to_string()), 7.0);
+        recorder.push_final_at(&Transcription::final_result("после паузы".to_string()).with_timing(0.0, 1.0), 30.0);
+
+        let utterances = recorder.utterances();
+        assert_eq!(utterances.len(), 3);
+        assert_eq!((utterances[0].start_secs, utterances[0].end_secs), (2.0, 5.0));
+        assert_eq!(utterances[0].speaker, Some(1));
+        assert_eq!((utterances[1].start_secs, utterances[1].end_secs), (5.0, 6.0));
+
+        let transcript = recorder.transcript(None);
+        assert_eq!(transcript.format_version, MEETING_TRANSCRIPT_FORMAT_VERSION);
+        assert!(transcript.diarized);
+        assert!(!transcript.llm_topic_breaks);
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.utterances().count(), 3);
+    }
+
+    #[test]
+    fn trimmed_transcript_starts_at_first_speech() {
+        let mut recorder = MeetingRecorder::new(MeetingConfig {
+            trim_silence: true,
+            trim_padding_secs: 0.5,
+            ..config(8.0, 30.0)
+        });
+        // Минута тишины до первой фразы и полминуты после последней
+        recorder.push_final_at(&Transcription::final_result("начнём".to_string()).with_timing(0.0, 2.0), 62.0);
+        recorder.push_final_at(&Transcription::final_result("итоги".to_string()).with_timing(0.0, 3.0), 70.0);
+
+        let transcript = recorder.transcript_with_duration(100.0, None);
+        let utterances: Vec<_> = transcript.utterances().collect();
+        assert_eq!((utterances[0].start_secs, utterances[0].end_secs), (0.5, 2.5));
+        assert_eq!((utterances[1].start_secs, utterances[1].end_secs), (7.5, 10.5));
+        assert_eq!(transcript.segments[0].start_secs, 0.5);
+        assert_eq!(transcript.duration_secs, 11.0);
+        assert_eq!(transcript.started_at, recorder.started_at_ms + 59_500);
+        assert_eq!(transcript.ended_at, transcript.started_at + 11_000);
+
+        // Без опции время остаётся от старта встречи
+        recorder.config.trim_silence = false;
+        let transcript = recorder.transcript_with_duration(100.0, None);
+        assert_eq!(transcript.utterances().next().unwrap().start_secs, 60.0);
+        assert_eq!(transcript.duration_secs, 100.0);
+    }
+
+    #[test]
+    fn speech_bounds_keep_padding_inside_meeting() {
+        let utterances = vec![utterance("a", 0.2, 1.0, None), utterance("b", 5.0, 9.8, None)];
+        assert_eq!(speech_bounds(&utterances, 10.0, 0.5), Some((0.0, 10.0)));
+        assert_eq!(speech_bounds(&utterances, 20.0, 1.0), Some((0.0, 10.8)));
+        assert_eq!(speech_bounds(&[], 10.0, 0.5), None);
+    }
+}
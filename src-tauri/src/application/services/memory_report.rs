@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::domain::{MemoryPoolKind, MemoryPoolUsage, MemoryReport};
+
+/// Пул аудио: семплы i16
+pub fn audio_pool(name: &str, samples: usize) -> MemoryPoolUsage {
+    MemoryPoolUsage::new(name, MemoryPoolKind::AudioBuffer, samples, (samples * std::mem::size_of::<i16>()) as u64)
+}
+
+/// Пул записей: сами элементы плюс оценка их данных в куче по размеру JSON
+/// (строки и вложенные списки — основная часть памяти истории и очередей)
+pub fn items_pool<T: Serialize>(name: &str, kind: MemoryPoolKind, items: &[T]) -> MemoryPoolUsage {
+    let inline = std::mem::size_of_val(items) as u64;
+    let mut counter = ByteCounter(0);
+    let heap = match serde_json::to_writer(&mut counter, items) {
+        Ok(()) => counter.0,
+        Err(_) => 0,
+    };
+    MemoryPoolUsage::new(name, kind, items.len(), inline + heap)
+}
+
+/// Считает байты сериализации, не собирая её в памяти
+struct ByteCounter(u64);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Предупреждения о пулах выше порога: одно на выход за порог, пока пул не опустится ниже.
+///
+/// Растущий без границ буфер иначе предупреждал бы на каждой проверке.
+#[derive(Debug, Default)]
+pub struct MemoryAlarmMonitor {
+    alarmed: HashSet<String>,
+}
+
+impl MemoryAlarmMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Пулы, которые вышли за порог с прошлой проверки
+    pub fn check(&mut self, report: &MemoryReport) -> Vec<MemoryPoolUsage> {
+        self.alarmed.retain(|name| report.over_threshold.contains(name));
+        report
+            .pools
+            .iter()
+            .filter(|pool| pool.over_threshold() && self.alarmed.insert(pool.name.clone()))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MemoryAlarmConfig;
+
+    fn report(history_bytes: u64) -> MemoryReport {
+        let config = MemoryAlarmConfig {
+            history_mb: 1,
+            ..MemoryAlarmConfig::default()
+        };
+        MemoryReport::new(
+            vec![MemoryPoolUsage::new("history", MemoryPoolKind::History, 1, history_bytes)],
+            &config,
+        )
+    }
+
+    #[test]
+    fn alarms_once_per_threshold_crossing() {
+        let mut monitor = MemoryAlarmMonitor::new();
+        assert!(monitor.check(&report(1024)).is_empty());
+        assert_eq!(monitor.check(&report(2 * 1024 * 1024)).len(), 1);
+        assert!(monitor.check(&report(3 * 1024 * 1024)).is_empty());
+        // Опустился ниже порога и снова вырос — новое предупреждение
+        assert!(monitor.check(&report(1024)).is_empty());
+        assert_eq!(monitor.check(&report(2 * 1024 * 1024)).len(), 1);
+    }
+
+    #[test]
+    fn estimates_pool_sizes() {
+        let audio = audio_pool("recording.pre_roll", 16_000);
+        assert_eq!((audio.items, audio.bytes), (16_000, 32_000));
+
+        let texts = vec!["a".repeat(1000), "b".repeat(1000)];
+        let pool = items_pool("history", MemoryPoolKind::History, &texts);
+        assert_eq!(pool.items, 2);
+        assert!(pool.bytes >= 2000 + 2 * std::mem::size_of::<String>() as u64);
+    }
+}
@@ -1,5 +1,75 @@
+mod accuracy;
+mod audio_backlog;
+mod audio_gain;
 mod audio_spectrum;
+mod correction_engine;
+#[cfg(feature = "ensemble")]
+mod ensemble_merge;
+mod feedback;
+mod file_transcription;
+mod filler_remover;
+mod history;
+mod input_level;
+mod instant_paste;
+mod latency_metrics;
+mod meeting;
+mod memory_report;
+mod partial_coalescer;
+mod partial_stabilizer;
+mod paste_audit;
+mod pipeline_trace;
+mod pre_roll;
+mod provider_benchmark;
+mod provider_comparison;
+mod session_diagnostics;
+mod session_stats;
+mod session_timeline;
+mod spelling;
+mod stream_watchdog;
+mod telemetry;
+mod text_normalizer;
+mod text_utils;
+mod text_output_router;
+mod transcript_assembler;
 mod transcription_service;
+mod usage_analytics;
+mod voice_commands;
+mod warm_pool;
 
+pub use accuracy::*;
+pub use audio_backlog::*;
+pub use audio_gain::*;
 pub use audio_spectrum::*;
+pub use correction_engine::*;
+#[cfg(feature = "ensemble")]
+pub use ensemble_merge::*;
+pub use feedback::*;
+pub use file_transcription::*;
+pub use filler_remover::*;
+pub use history::*;
+pub use input_level::*;
+pub use instant_paste::*;
+pub use latency_metrics::*;
+pub use meeting::*;
+pub use memory_report::*;
+pub use partial_coalescer::*;
+pub use partial_stabilizer::*;
+pub use paste_audit::*;
+pub use pipeline_trace::*;
+pub use pre_roll::*;
+pub use provider_benchmark::*;
+pub use provider_comparison::*;
+pub use session_diagnostics::*;
+pub use session_stats::*;
+pub use session_timeline::*;
+pub use spelling::*;
+pub use stream_watchdog::*;
+pub use telemetry::*;
+pub use text_normalizer::*;
+pub use text_utils::*;
+pub use text_output_router::*;
+pub use transcript_assembler::*;
 pub use transcription_service::*;
+pub use usage_analytics::*;
+pub use voice_commands::*;
+pub use warm_pool::*;
@@ -0,0 +1,142 @@
+use tokio::time::{Duration, Instant};
+
+use crate::domain::Transcription;
+
+/// What to do with a partial after `PartialCoalescer::push`
+#[derive(Debug, Clone)]
+pub enum PartialEmission {
+    /// Отправить сразу
+    Now(Transcription),
+    /// Отложен; через указанное время забрать последний отложенный partial (take_pending)
+    FlushAfter(Duration),
+    /// Отложен, отправку уже ждёт запланированный flush
+    Buffered,
+}
+
+/// Rate-limits partial events to the webview: no more than one per `interval`,
+/// intermediate partials are replaced by the newest one.
+///
+/// При быстрой речи провайдер шлёт десятки partial'ов в секунду, и на слабых машинах
+/// перерисовка webview заметно грузит CPU. Последний partial окна всё равно доходит
+/// (trailing flush), а финализированные сегменты не задерживаются.
+#[derive(Debug)]
+pub struct PartialCoalescer {
+    interval: Duration,
+    last_emit: Option<Instant>,
+    pending: Option<Transcription>,
+    flush_scheduled: bool,
+}
+
+impl PartialCoalescer {
+    /// 0 — без ограничения, каждый partial уходит сразу
+    pub fn new(interval_ms: u64) -> Self {
+        Self {
+            interval: Duration::from_millis(interval_ms),
+            last_emit: None,
+            pending: None,
+            flush_scheduled: false,
+        }
+    }
+
+    pub fn push(&mut self, transcription: Transcription, now: Instant) -> PartialEmission {
+        let throttled = self
+            .last_emit
+            .is_some_and(|last| now.saturating_duration_since(last) < self.interval);
+        // Финализированный сегмент провайдер уже не поменяет — отложенный partial того же сегмента устарел
+        if transcription.is_final || !throttled {
+            self.pending = None;
+            self.last_emit = Some(now);
+            return PartialEmission::Now(transcription);
+        }
+
+        self.pending = Some(transcription);
+        if self.flush_scheduled {
+            return PartialEmission::Buffered;
+        }
+        self.flush_scheduled = true;
+        let last = self.last_emit.unwrap_or(now);
+        PartialEmission::FlushAfter((last + self.interval).saturating_duration_since(now))
+    }
+
+    /// Запланированный flush: последний отложенный partial, если его не вытеснил final
+    pub fn take_pending(&mut self, now: Instant) -> Option<Transcription> {
+        self.flush_scheduled = false;
+        let pending = self.pending.take()?;
+        self.last_emit = Some(now);
+        Some(pending)
+    }
+
+    /// Пришла финальная фраза: отложенный partial показывать уже поздно
+    pub fn discard_pending(&mut self) {
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(text: &str) -> Transcription {
+        Transcription::partial(text.to_string())
+    }
+
+    fn emitted(emission: PartialEmission) -> Option<String> {
+        match emission {
+            PartialEmission::Now(transcription) => Some(transcription.text),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn zero_interval_emits_every_partial() {
+        let mut coalescer = PartialCoalescer::new(0);
+        let now = Instant::now();
+        assert_eq!(emitted(coalescer.push(partial("a"), now)).as_deref(), Some("a"));
+        assert_eq!(emitted(coalescer.push(partial("ab"), now)).as_deref(), Some("ab"));
+    }
+
+    #[test]
+    fn keeps_only_the_newest_partial_within_the_interval() {
+        let mut coalescer = PartialCoalescer::new(200);
+        let start = Instant::now();
+        assert_eq!(emitted(coalescer.push(partial("a"), start)).as_deref(), Some("a"));
+
+        let at_50 = start + Duration::from_millis(50);
+        assert!(matches!(
+            coalescer.push(partial("ab"), at_50),
+            PartialEmission::FlushAfter(delay) if delay == Duration::from_millis(150)
+        ));
+        assert!(matches!(coalescer.push(partial("abc"), at_50), PartialEmission::Buffered));
+
+        let at_200 = start + Duration::from_millis(200);
+        assert_eq!(coalescer.take_pending(at_200).map(|t| t.text).as_deref(), Some("abc"));
+        assert!(coalescer.take_pending(at_200).is_none());
+
+        // Следующее окно отсчитывается от flush
+        assert!(matches!(
+            coalescer.push(partial("abcd"), at_200 + Duration::from_millis(10)),
+            PartialEmission::FlushAfter(_)
+        ));
+    }
+
+    #[test]
+    fn segment_finals_are_not_delayed_and_drop_the_pending_partial() {
+        let mut coalescer = PartialCoalescer::new(500);
+        let start = Instant::now();
+        coalescer.push(partial("a"), start);
+        coalescer.push(partial("ab"), start + Duration::from_millis(10));
+
+        let mut segment = partial("ab.");
+        segment.is_final = true;
+        assert_eq!(
+            emitted(coalescer.push(segment, start + Duration::from_millis(20))).as_deref(),
+            Some("ab.")
+        );
+        assert!(coalescer.take_pending(start + Duration::from_millis(500)).is_none());
+
+        coalescer.push(partial("c"), start + Duration::from_millis(510));
+        coalescer.push(partial("cd"), start + Duration::from_millis(520));
+        coalescer.discard_pending();
+        assert!(coalescer.take_pending(start + Duration::from_millis(1010)).is_none());
+    }
+}
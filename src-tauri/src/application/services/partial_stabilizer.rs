@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+
+use crate::domain::Transcription;
+
+/// Smooths flickering partials: the shown text only grows by words that agree across
+/// the last `required_agreement` partials of the current segment
+pub struct PartialStabilizer {
+    required_agreement: usize,
+    /// Слова последних partial'ов сегмента (не больше required_agreement)
+    recent: VecDeque<Vec<String>>,
+    /// Слова, уже показанные пользователю
+    shown: Vec<String>,
+}
+
+impl PartialStabilizer {
+    /// 0/1 — фильтр выключен, partial'ы идут как есть
+    pub fn new(required_agreement: u8) -> Self {
+        Self {
+            required_agreement: required_agreement.max(1) as usize,
+            recent: VecDeque::new(),
+            shown: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.required_agreement > 1
+    }
+
+    /// Новый сегмент: начинаем с пустого текста
+    pub fn reset(&mut self) {
+        self.recent.clear();
+        self.shown.clear();
+    }
+
+    /// Partial для показа; None — показанный текст не изменился, событие можно не слать
+    pub fn stabilize(&mut self, mut transcription: Transcription) -> Option<Transcription> {
+        if !self.is_enabled() {
+            return Some(transcription);
+        }
+        // Финализированный сегмент провайдер уже не поменяет — показываем целиком
+        if transcription.is_final {
+            self.reset();
+            return Some(transcription);
+        }
+
+        if self.recent.len() == self.required_agreement {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(transcription.text.split_whitespace().map(str::to_string).collect());
+        if self.recent.len() < self.required_agreement {
+            return None;
+        }
+
+        let agreed = self.agreed_prefix();
+        let matching = common_prefix_len(&agreed, &self.shown);
+        let changed = if matching == self.shown.len() {
+            // Продолжение показанного текста
+            agreed.len() > self.shown.len()
+        } else {
+            // Все последние partial'ы сошлись на другой версии показанных слов — исправляем,
+            // а если просто короче (провайдер обрезал хвост) — держим показанное
+            matching < agreed.len()
+        };
+        if !changed {
+            return None;
+        }
+
+        self.shown = agreed;
+        transcription.text = self.shown.join(" ");
+        Some(transcription)
+    }
+
+    /// Общее начало (по словам) последних partial'ов
+    fn agreed_prefix(&self) -> Vec<String> {
+        let mut partials = self.recent.iter();
+        let Some(first) = partials.next() else {
+            return Vec::new();
+        };
+        let len = partials.fold(first.len(), |len, words| len.min(common_prefix_len(first, words)));
+        first[..len].to_vec()
+    }
+}
+
+fn common_prefix_len(a: &[String], b: &[String]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(text: &str) -> Transcription {
+        Transcription::new(text.to_string(), false)
+    }
+
+    fn shown(stabilizer: &mut PartialStabilizer, text: &str) -> Option<String> {
+        stabilizer.stabilize(partial(text)).map(|t| t.text)
+    }
+
+    #[test]
+    fn disabled_filter_passes_partials_through() {
+        let mut stabilizer = PartialStabilizer::new(1);
+        assert_eq!(shown(&mut stabilizer, "hello wor"), Some("hello wor".to_string()));
+        assert_eq!(shown(&mut stabilizer, "hello"), Some("hello".to_string()));
+    }
+
+    #[test]
+    fn prefix_advances_only_when_stable() {
+        let mut stabilizer = PartialStabilizer::new(2);
+        assert_eq!(shown(&mut stabilizer, "I scream"), None);
+        assert_eq!(shown(&mut stabilizer, "ice cream"), None);
+        assert_eq!(shown(&mut stabilizer, "ice cream is"), Some("ice cream".to_string()));
+        // Провайдер на миг обрезал хвост — показанное не откатываем
+        assert_eq!(shown(&mut stabilizer, "ice"), None);
+        assert_eq!(shown(&mut stabilizer, "ice cream is cold"), None);
+        assert_eq!(shown(&mut stabilizer, "ice cream is cold"), Some("ice cream is cold".to_string()));
+    }
+
+    #[test]
+    fn stable_correction_replaces_shown_words() {
+        let mut stabilizer = PartialStabilizer::new(2);
+        shown(&mut stabilizer, "the whether");
+        assert_eq!(shown(&mut stabilizer, "the whether"), Some("the whether".to_string()));
+        // Одна версия — ещё не повод править
+        assert_eq!(shown(&mut stabilizer, "the weather"), None);
+        assert_eq!(shown(&mut stabilizer, "the weather today"), Some("the weather".to_string()));
+    }
+
+    #[test]
+    fn segment_final_is_shown_and_resets() {
+        let mut stabilizer = PartialStabilizer::new(3);
+        shown(&mut stabilizer, "good");
+        let segment = stabilizer.stabilize(Transcription::new("good morning".to_string(), true)).unwrap();
+        assert_eq!(segment.text, "good morning");
+        assert!(stabilizer.shown.is_empty());
+        assert!(stabilizer.recent.is_empty());
+    }
+}
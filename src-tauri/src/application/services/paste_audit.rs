@@ -0,0 +1,97 @@
+use crate::domain::{
+    paste_audit_preview, PasteAuditAction, PasteAuditEntry, SinkDeliveryOutcome, TextDelivery, TextOutputSinkConfig,
+};
+
+/// Сколько последних записей аудита вставок храним (старые отбрасываются)
+pub const MAX_PASTE_AUDIT_ENTRIES: usize = 1000;
+
+/// Записи аудита для результатов доставки: по одной на каждый auto-paste/typing sink.
+/// Остальные sinks (clipboard, файл, webhook) не трогают чужие окна и в аудит не попадают.
+pub fn paste_audit_entries(
+    delivery: &TextDelivery,
+    outcomes: &[SinkDeliveryOutcome],
+    target_app: Option<&str>,
+    timestamp_ms: i64,
+) -> Vec<PasteAuditEntry> {
+    outcomes
+        .iter()
+        .filter_map(|outcome| {
+            let action = match outcome.sink {
+                TextOutputSinkConfig::AutoPaste { .. } => PasteAuditAction::AutoPaste,
+                TextOutputSinkConfig::Typing => PasteAuditAction::Typing,
+                _ => return None,
+            };
+            Some(PasteAuditEntry {
+                timestamp: timestamp_ms,
+                action,
+                target_app: target_app.map(str::to_string),
+                text_length: delivery.text.chars().count(),
+                preview: paste_audit_preview(&delivery.text),
+                session_id: delivery.session_id,
+                error: outcome.error.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Добавляет записи в журнал, оставляя не больше MAX_PASTE_AUDIT_ENTRIES последних
+pub fn append_paste_audit(log: &mut Vec<PasteAuditEntry>, entries: Vec<PasteAuditEntry>) {
+    log.extend(entries);
+    if log.len() > MAX_PASTE_AUDIT_ENTRIES {
+        let excess = log.len() - MAX_PASTE_AUDIT_ENTRIES;
+        log.drain(0..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(sink: TextOutputSinkConfig, error: Option<&str>) -> SinkDeliveryOutcome {
+        SinkDeliveryOutcome {
+            sink,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn audits_only_insertion_sinks() {
+        let delivery = TextDelivery::new(" привет мир").with_session_id(4);
+        let outcomes = vec![
+            outcome(
+                TextOutputSinkConfig::AutoPaste {
+                    fallback_to_clipboard: true,
+                },
+                Some("Permission denied"),
+            ),
+            outcome(TextOutputSinkConfig::plain_clipboard(), None),
+            outcome(TextOutputSinkConfig::Typing, None),
+        ];
+
+        let entries = paste_audit_entries(&delivery, &outcomes, Some("com.apple.TextEdit"), 1_000);
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].action, PasteAuditAction::AutoPaste);
+        assert_eq!(entries[0].error.as_deref(), Some("Permission denied"));
+        assert_eq!(entries[0].target_app.as_deref(), Some("com.apple.TextEdit"));
+        assert_eq!(entries[0].text_length, 11);
+        assert_eq!(entries[0].preview, "привет мир");
+        assert_eq!(entries[0].session_id, Some(4));
+
+        assert_eq!(entries[1].action, PasteAuditAction::Typing);
+        assert!(entries[1].error.is_none());
+    }
+
+    #[test]
+    fn append_keeps_latest_entries() {
+        let delivery = TextDelivery::new("x");
+        let typing = [outcome(TextOutputSinkConfig::Typing, None)];
+
+        let mut log = Vec::new();
+        for i in 0..MAX_PASTE_AUDIT_ENTRIES as i64 + 3 {
+            append_paste_audit(&mut log, paste_audit_entries(&delivery, &typing, None, i));
+        }
+        assert_eq!(log.len(), MAX_PASTE_AUDIT_ENTRIES);
+        assert_eq!(log[0].timestamp, 3);
+    }
+}
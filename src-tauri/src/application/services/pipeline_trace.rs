@@ -0,0 +1,243 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::application::LatencyStats;
+
+/// Сколько завершённых сессий держим для `get_pipeline_trace`
+const MAX_TRACED_SESSIONS: usize = 10;
+
+/// Ограничение на количество замеров этапа в одной сессии (p95 считаем по последним)
+const MAX_SAMPLES_PER_STAGE: usize = 2000;
+
+/// Stage of the audio → text pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Чанк создан захватом (или VAD) → взят обработчиком: очередь между потоками
+    Capture,
+    /// Решение VAD по 30ms фрейму
+    Vad,
+    /// Уровень, усиление, спектр и журнал чанка
+    Gain,
+    /// Отправка чанка провайдеру
+    ProviderSend,
+    /// Аудио отправлено → пришёл ответ (partial/final)
+    ProviderResponse,
+    /// Обработка ответа: стабилизация, исправления, сборка текста и колбэк в UI
+    Callback,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 6] = [
+        PipelineStage::Capture,
+        PipelineStage::Vad,
+        PipelineStage::Gain,
+        PipelineStage::ProviderSend,
+        PipelineStage::ProviderResponse,
+        PipelineStage::Callback,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Timing of one pipeline stage in a session
+#[derive(Debug, Clone, PartialEq, Serialize, schemars::JsonSchema)]
+pub struct PipelineStageTiming {
+    pub stage: PipelineStage,
+    /// Суммарное время этапа за сессию
+    pub total_ms: f64,
+    #[serde(flatten)]
+    pub stats: LatencyStats,
+}
+
+/// Where latency was spent in one recording session
+#[derive(Debug, Clone, PartialEq, Serialize, schemars::JsonSchema)]
+pub struct PipelineTrace {
+    pub session_id: u64,
+    /// Этапы в порядке прохождения аудио (без замеров — не попадают)
+    pub stages: Vec<PipelineStageTiming>,
+    /// Этап с наибольшей средней задержкой
+    pub bottleneck: Option<PipelineStage>,
+}
+
+#[derive(Default)]
+struct SessionTrace {
+    session_id: u64,
+    samples: [Vec<f64>; PipelineStage::ALL.len()],
+    totals: [f64; PipelineStage::ALL.len()],
+}
+
+impl SessionTrace {
+    fn is_empty(&self) -> bool {
+        self.samples.iter().all(|samples| samples.is_empty())
+    }
+
+    fn snapshot(&self) -> PipelineTrace {
+        let stages: Vec<PipelineStageTiming> = PipelineStage::ALL
+            .iter()
+            .filter(|stage| !self.samples[stage.index()].is_empty())
+            .map(|&stage| PipelineStageTiming {
+                stage,
+                total_ms: self.totals[stage.index()],
+                stats: LatencyStats::from_samples(&self.samples[stage.index()]),
+            })
+            .collect();
+        let bottleneck = stages
+            .iter()
+            .filter_map(|timing| timing.stats.avg_ms.map(|avg| (timing.stage, avg)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(stage, _)| stage);
+        PipelineTrace {
+            session_id: self.session_id,
+            stages,
+            bottleneck,
+        }
+    }
+}
+
+#[derive(Default)]
+struct TracerInner {
+    current: SessionTrace,
+    history: VecDeque<PipelineTrace>,
+}
+
+/// Трассировка конвейера capture → VAD → gain → provider → callback по этапам.
+///
+/// Этапы меряются там, где проходят (VAD — в потоке захвата, остальное — в обработчике чанков
+/// и колбэках STT), и складываются в сессию записи, начатую `begin_session`.
+#[derive(Default)]
+pub struct PipelineTracer {
+    inner: Mutex<TracerInner>,
+}
+
+impl PipelineTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Начинает новую сессию. Предыдущая (если в ней что-то было) уходит в историю.
+    pub fn begin_session(&self, session_id: u64) {
+        let mut inner = self.lock();
+        let previous = std::mem::take(&mut inner.current);
+        if !previous.is_empty() {
+            let trace = previous.snapshot();
+            if let Some(stage) = trace.bottleneck {
+                log::debug!("Pipeline trace of session {}: bottleneck is {:?}", trace.session_id, stage);
+            }
+            inner.history.push_back(trace);
+            while inner.history.len() > MAX_TRACED_SESSIONS {
+                inner.history.pop_front();
+            }
+        }
+        inner.current.session_id = session_id;
+    }
+
+    pub fn record(&self, stage: PipelineStage, elapsed: Duration) {
+        self.record_ms(stage, elapsed.as_micros() as f64 / 1000.0);
+    }
+
+    pub fn record_ms(&self, stage: PipelineStage, ms: f64) {
+        let mut inner = self.lock();
+        let session = &mut inner.current;
+        let samples = &mut session.samples[stage.index()];
+        if samples.len() >= MAX_SAMPLES_PER_STAGE {
+            samples.remove(0);
+        }
+        samples.push(ms);
+        session.totals[stage.index()] += ms;
+    }
+
+    /// Замер этапа до конца области видимости
+    pub fn span(&self, stage: PipelineStage) -> PipelineSpan<'_> {
+        PipelineSpan {
+            tracer: self,
+            stage,
+            started: Instant::now(),
+        }
+    }
+
+    /// Трасса текущей сессии, если в ней уже есть замеры, иначе последней завершённой
+    pub fn last_session(&self) -> Option<PipelineTrace> {
+        let inner = self.lock();
+        if inner.current.is_empty() {
+            inner.history.back().cloned()
+        } else {
+            Some(inner.current.snapshot())
+        }
+    }
+
+    /// Трассы всех сохранённых сессий (от старых к новым), включая текущую
+    pub fn sessions(&self) -> Vec<PipelineTrace> {
+        let inner = self.lock();
+        let mut traces: Vec<PipelineTrace> = inner.history.iter().cloned().collect();
+        if !inner.current.is_empty() {
+            traces.push(inner.current.snapshot());
+        }
+        traces
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TracerInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Открытый замер этапа; время записывается при drop
+pub struct PipelineSpan<'a> {
+    tracer: &'a PipelineTracer,
+    stage: PipelineStage,
+    started: Instant,
+}
+
+impl Drop for PipelineSpan<'_> {
+    fn drop(&mut self) {
+        self.tracer.record(self.stage, self.started.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_stages_and_finds_bottleneck() {
+        let tracer = PipelineTracer::new();
+        tracer.begin_session(1);
+        tracer.record_ms(PipelineStage::Gain, 0.5);
+        tracer.record_ms(PipelineStage::Gain, 1.5);
+        tracer.record_ms(PipelineStage::ProviderResponse, 300.0);
+        {
+            let _span = tracer.span(PipelineStage::Callback);
+        }
+
+        let trace = tracer.last_session().unwrap();
+        assert_eq!(trace.session_id, 1);
+        let stages: Vec<PipelineStage> = trace.stages.iter().map(|t| t.stage).collect();
+        assert_eq!(
+            stages,
+            vec![PipelineStage::Gain, PipelineStage::ProviderResponse, PipelineStage::Callback]
+        );
+        assert_eq!(trace.stages[0].total_ms, 2.0);
+        assert_eq!(trace.stages[0].stats.avg_ms, Some(1.0));
+        assert_eq!(trace.bottleneck, Some(PipelineStage::ProviderResponse));
+    }
+
+    #[test]
+    fn keeps_finished_sessions_in_history() {
+        let tracer = PipelineTracer::new();
+        assert!(tracer.last_session().is_none());
+
+        tracer.begin_session(1);
+        tracer.record_ms(PipelineStage::Vad, 0.2);
+        tracer.begin_session(2);
+        // Новая сессия без замеров — последней остаётся завершённая
+        assert_eq!(tracer.last_session().unwrap().session_id, 1);
+
+        tracer.record_ms(PipelineStage::Capture, 3.0);
+        let sessions: Vec<u64> = tracer.sessions().iter().map(|t| t.session_id).collect();
+        assert_eq!(sessions, vec![1, 2]);
+    }
+}
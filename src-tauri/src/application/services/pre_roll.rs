@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+
+use crate::domain::AudioChunk;
+
+/// Audio captured while the provider stream is still opening.
+///
+/// Захват стартует раньше подключения, чанки копятся здесь и уходят провайдеру первыми,
+/// как только запись перешла в Recording. Окно ограничено: если подключение затянулось,
+/// выбрасываем самое старое аудио, чтобы потом не догонять речь секундами.
+#[derive(Debug)]
+pub struct PreRollBuffer {
+    max_ms: f64,
+    chunks: VecDeque<AudioChunk>,
+    buffered_ms: f64,
+}
+
+impl PreRollBuffer {
+    /// 0 — буфер выключен, аудио до открытия стрима теряется
+    pub fn new(max_ms: u32) -> Self {
+        Self {
+            max_ms: max_ms as f64,
+            chunks: VecDeque::new(),
+            buffered_ms: 0.0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    pub fn buffered_ms(&self) -> f64 {
+        self.buffered_ms
+    }
+
+    pub fn buffered_samples(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.data.len()).sum()
+    }
+
+    pub fn push(&mut self, chunk: AudioChunk) {
+        let chunk_ms = chunk_duration_ms(&chunk);
+        if chunk_ms > self.max_ms {
+            return;
+        }
+        self.buffered_ms += chunk_ms;
+        self.chunks.push_back(chunk);
+        while self.buffered_ms > self.max_ms {
+            match self.chunks.pop_front() {
+                Some(oldest) => self.buffered_ms -= chunk_duration_ms(&oldest),
+                None => break,
+            }
+        }
+    }
+
+    /// Самый старый чанк (в порядке записи)
+    pub fn pop(&mut self) -> Option<AudioChunk> {
+        let chunk = self.chunks.pop_front()?;
+        self.buffered_ms = (self.buffered_ms - chunk_duration_ms(&chunk)).max(0.0);
+        Some(chunk)
+    }
+}
+
+fn chunk_duration_ms(chunk: &AudioChunk) -> f64 {
+    chunk.data.len() as f64 * 1000.0 / (chunk.sample_rate.max(1) as f64 * chunk.channels.max(1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 10мс при 16 кГц моно; первый семпл — номер чанка
+    fn chunk(n: i16) -> AudioChunk {
+        let mut data = vec![0i16; 160];
+        data[0] = n;
+        AudioChunk::new(data, 16000, 1)
+    }
+
+    #[test]
+    fn keeps_only_the_newest_window_in_order() {
+        let mut buffer = PreRollBuffer::new(30);
+        for n in 1..=5 {
+            buffer.push(chunk(n));
+        }
+        assert!((buffer.buffered_ms() - 30.0).abs() < 1e-6);
+
+        let replayed: Vec<i16> = std::iter::from_fn(|| buffer.pop()).map(|c| c.data[0]).collect();
+        assert_eq!(replayed, vec![3, 4, 5]);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.buffered_ms(), 0.0);
+    }
+
+    #[test]
+    fn zero_window_buffers_nothing() {
+        let mut buffer = PreRollBuffer::new(0);
+        buffer.push(chunk(1));
+        assert!(buffer.is_empty());
+    }
+}
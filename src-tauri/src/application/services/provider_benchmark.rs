@@ -0,0 +1,113 @@
+use std::time::Instant;
+
+use crate::application::TranscriptionService;
+use crate::domain::{ProviderBenchmarkResult, SttConfig, SttSessionOverride};
+
+/// Конфиги для сравнения: каждый override поверх текущих настроек, все на языке сэмпла.
+/// Повторы (тот же провайдер и модель) отбрасываются — сравнивать их бессмысленно.
+pub fn benchmark_configs(base: &SttConfig, overrides: &[SttSessionOverride], language: &str) -> Vec<SttConfig> {
+    let mut configs: Vec<SttConfig> = Vec::new();
+    for session_override in overrides {
+        let mut config = base.clone();
+        session_override.apply_to(&mut config);
+        config.language = language.to_string();
+        config.auto_detect_language = false;
+        if !configs
+            .iter()
+            .any(|c| c.provider == config.provider && c.model == config.model)
+        {
+            configs.push(config);
+        }
+    }
+    configs
+}
+
+/// Прогоняет один и тот же сэмпл через каждый конфиг и возвращает тексты и задержки.
+///
+/// Провайдеры опрашиваются по очереди: параллельные соединения искажают задержку
+/// и могут упереться в лимит соединений. Ошибка одного провайдера не прерывает остальные.
+pub async fn benchmark_providers(
+    service: &TranscriptionService,
+    configs: &[SttConfig],
+    samples: &[i16],
+    sample_rate: u32,
+) -> Vec<ProviderBenchmarkResult> {
+    let audio_ms = samples.len() as u64 * 1000 / sample_rate.max(1) as u64;
+    let mut results = Vec::with_capacity(configs.len());
+    for config in configs {
+        log::info!("Benchmark: {:?} (model: {:?})", config.provider, config.model);
+        let started = Instant::now();
+        let result = service.transcribe_samples_with(config, samples, sample_rate).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        results.push(benchmark_result(config, result, latency_ms, audio_ms));
+    }
+    results
+}
+
+fn benchmark_result(
+    config: &SttConfig,
+    result: anyhow::Result<String>,
+    latency_ms: u64,
+    audio_ms: u64,
+) -> ProviderBenchmarkResult {
+    let (text, error) = match result {
+        Ok(text) => (text, None),
+        Err(e) => {
+            log::warn!("Benchmark: {:?} failed: {:#}", config.provider, e);
+            (String::new(), Some(format!("{:#}", e)))
+        }
+    };
+    ProviderBenchmarkResult {
+        provider: config.provider,
+        model: config.model.clone(),
+        text,
+        error,
+        latency_ms,
+        real_time_factor: if audio_ms > 0 {
+            latency_ms as f64 / audio_ms as f64
+        } else {
+            0.0
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SttProviderType;
+
+    #[test]
+    fn configs_use_sample_language_and_skip_duplicates() {
+        let mut base = SttConfig::new(SttProviderType::Deepgram).with_language("ru");
+        base.auto_detect_language = true;
+        let overrides = vec![
+            SttSessionOverride::default(),
+            SttSessionOverride {
+                provider: Some(SttProviderType::AssemblyAI),
+                ..Default::default()
+            },
+            // Тот же Deepgram, только с другим языком — язык всё равно берётся из сэмпла
+            SttSessionOverride::language("de"),
+        ];
+
+        let configs = benchmark_configs(&base, &overrides, "en");
+        let providers: Vec<_> = configs.iter().map(|c| c.provider).collect();
+        assert_eq!(providers, vec![SttProviderType::Deepgram, SttProviderType::AssemblyAI]);
+        assert!(configs.iter().all(|c| c.language == "en" && !c.auto_detect_language));
+    }
+
+    #[test]
+    fn result_keeps_error_and_real_time_factor() {
+        let config = SttConfig::new(SttProviderType::Deepgram);
+
+        let ok = benchmark_result(&config, Ok("hello".to_string()), 500, 2000);
+        assert_eq!(ok.text, "hello");
+        assert_eq!(ok.error, None);
+        assert_eq!(ok.real_time_factor, 0.25);
+
+        let failed = benchmark_result(&config, Err(anyhow::anyhow!("no key")), 10, 0);
+        assert_eq!(failed.text, "");
+        assert_eq!(failed.error.as_deref(), Some("no key"));
+        assert_eq!(failed.real_time_factor, 0.0);
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::{Arc, Mutex};
+
+use crate::domain::{SttProviderType, Transcription};
+
+/// Transcript of one provider in A/B mode (`SttConfig::comparison_provider`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonTranscript {
+    pub provider: SttProviderType,
+    /// Основной провайдер сессии (его текст идёт в окно и вставку)
+    pub primary: bool,
+    /// Текст сегмента
+    pub text: String,
+    pub is_final: bool,
+    /// Весь текст провайдера за сессию: финализированные сегменты и текущий промежуточный
+    pub transcript: String,
+}
+
+pub type ComparisonListener = Arc<dyn Fn(ComparisonTranscript) + Send + Sync>;
+
+/// Text merged from both providers of an A/B session (experimental, cargo feature `ensemble`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnsembleTranscript {
+    /// Весь слитый текст сессии
+    pub transcript: String,
+    /// Сколько слов взято у сравниваемого провайдера
+    pub secondary_words: usize,
+}
+
+pub type EnsembleListener = Arc<dyn Fn(EnsembleTranscript) + Send + Sync>;
+
+/// A/B сравнение двух провайдеров на одном аудио.
+///
+/// Сравниваем «сырой» текст провайдеров: исправления, нормализация и сборка предложений
+/// одинаковы для обоих и только скрыли бы разницу в распознавании.
+pub struct ProviderComparison {
+    primary: SttProviderType,
+    secondary: SttProviderType,
+    listener: ComparisonListener,
+    /// Финализированный текст за сессию: [основной, сравниваемый]
+    finals: Mutex<[String; 2]>,
+    #[cfg(feature = "ensemble")]
+    ensemble: Option<crate::application::EnsembleMerger>,
+}
+
+impl ProviderComparison {
+    pub fn new(primary: SttProviderType, secondary: SttProviderType, listener: ComparisonListener) -> Self {
+        Self {
+            primary,
+            secondary,
+            listener,
+            finals: Mutex::new([String::new(), String::new()]),
+            #[cfg(feature = "ensemble")]
+            ensemble: None,
+        }
+    }
+
+    /// Ансамбль: после каждого финала — текст, слитый из обоих провайдеров по уверенности слов
+    #[cfg(feature = "ensemble")]
+    pub fn with_ensemble(mut self, listener: Option<EnsembleListener>) -> Self {
+        self.ensemble = listener.map(crate::application::EnsembleMerger::new);
+        self
+    }
+
+    #[cfg(not(feature = "ensemble"))]
+    pub fn with_ensemble(self, listener: Option<EnsembleListener>) -> Self {
+        if listener.is_some() {
+            log::debug!("Ensemble merge is not available in this build (cargo feature \"ensemble\")");
+        }
+        self
+    }
+
+    pub fn secondary(&self) -> SttProviderType {
+        self.secondary
+    }
+
+    /// Сегмент от провайдера; `is_final` — сегмент больше не изменится
+    pub fn record(&self, primary: bool, t: &Transcription, is_final: bool) {
+        let text = t.text.trim();
+        if text.is_empty() && !is_final {
+            return;
+        }
+        let transcript = {
+            let Ok(mut finals) = self.finals.lock() else {
+                return;
+            };
+            let session = &mut finals[usize::from(!primary)];
+            if is_final {
+                append(session, text);
+                #[cfg(feature = "ensemble")]
+                if let Some(ensemble) = self.ensemble.as_ref() {
+                    ensemble.push_final(primary, text, t.confidence);
+                }
+                session.clone()
+            } else {
+                let mut current = session.clone();
+                append(&mut current, text);
+                current
+            }
+        };
+        (self.listener)(ComparisonTranscript {
+            provider: if primary { self.primary } else { self.secondary },
+            primary,
+            text: text.to_string(),
+            is_final,
+            transcript,
+        });
+    }
+}
+
+fn append(session: &mut String, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if !session.is_empty() {
+        session.push(' ');
+    }
+    session.push_str(text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcripts_are_tagged_and_accumulated_per_provider() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_cb = events.clone();
+        let comparison = ProviderComparison::new(
+            SttProviderType::Backend,
+            SttProviderType::WhisperLocal,
+            Arc::new(move |t| events_cb.lock().unwrap().push(t)),
+        );
+
+        comparison.record(true, &Transcription::new("Hello world.".to_string(), true), true);
+        comparison.record(false, &Transcription::new("hello word".to_string(), false), false);
+        comparison.record(false, &Transcription::new("Hello, word.".to_string(), true), true);
+        comparison.record(true, &Transcription::new("How are".to_string(), false), false);
+
+        let events = events.lock().unwrap();
+        let summary: Vec<(SttProviderType, bool, &str)> = events
+            .iter()
+            .map(|t| (t.provider, t.is_final, t.transcript.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SttProviderType::Backend, true, "Hello world."),
+                (SttProviderType::WhisperLocal, false, "hello word"),
+                (SttProviderType::WhisperLocal, true, "Hello, word."),
+                (SttProviderType::Backend, false, "Hello world. How are"),
+            ]
+        );
+        assert!(!events[1].primary);
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use crate::domain::{ErrorCallback, RecordingStatus, SttError, SttProviderDebugState};
+
+/// Snapshot of the transcription pipeline for get_debug_state
+///
+/// Для разбора "зависших" записей без чтения логов: что за провайдер, открыт ли стрим,
+/// сколько аудио ушло и жив ли обработчик чанков.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionDebugState {
+    pub status: RecordingStatus,
+    /// Имя провайдера (None — стрим не открыт)
+    pub provider: Option<String>,
+    /// Провайдер занят дольше таймаута опроса — обычно send_audio висит на сети
+    pub provider_locked: bool,
+    #[serde(flatten)]
+    pub provider_state: SttProviderDebugState,
+    /// Чанков и байт отправлено провайдеру за текущую сессию
+    pub sent_chunks: u64,
+    pub sent_bytes: u64,
+    pub last_error: Option<String>,
+    /// Обработчик аудио-чанков ещё работает
+    pub processor_running: bool,
+}
+
+/// Счётчики сессии, которые обновляются из обработчика чанков и колбэка ошибок
+#[derive(Debug, Default)]
+pub struct SessionDiagnostics {
+    sent_chunks: AtomicU64,
+    sent_bytes: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl SessionDiagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Новая сессия: счётчики и последняя ошибка с нуля
+    pub fn reset(&self) {
+        self.sent_chunks.store(0, Ordering::Relaxed);
+        self.sent_bytes.store(0, Ordering::Relaxed);
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    pub fn record_sent(&self, samples: usize) {
+        self.sent_chunks.fetch_add(1, Ordering::Relaxed);
+        self.sent_bytes
+            .fetch_add((samples * std::mem::size_of::<i16>()) as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_error(&self, error: &SttError) {
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error.to_string());
+    }
+
+    /// Колбэк ошибок, который сначала запоминает ошибку
+    pub fn wrap_error_callback(self: &Arc<Self>, on_error: ErrorCallback) -> ErrorCallback {
+        let diagnostics = self.clone();
+        Arc::new(move |error: SttError| {
+            diagnostics.record_error(&error);
+            on_error(error);
+        })
+    }
+
+    pub fn sent_chunks(&self) -> u64 {
+        self.sent_chunks.load(Ordering::Relaxed)
+    }
+
+    pub fn sent_bytes(&self) -> u64 {
+        self.sent_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_callback_records_errors_until_reset() {
+        let diagnostics = Arc::new(SessionDiagnostics::new());
+        let forwarded = Arc::new(AtomicU64::new(0));
+        let forwarded_in_callback = forwarded.clone();
+        let on_error = diagnostics.wrap_error_callback(Arc::new(move |_| {
+            forwarded_in_callback.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        diagnostics.record_sent(160);
+        on_error(SttError::Processing("socket closed".to_string()));
+
+        assert_eq!(diagnostics.sent_chunks(), 1);
+        assert_eq!(diagnostics.sent_bytes(), 320);
+        assert_eq!(diagnostics.last_error().as_deref(), Some("Processing error: socket closed"));
+        assert_eq!(forwarded.load(Ordering::Relaxed), 1);
+
+        diagnostics.reset();
+        assert_eq!(diagnostics.sent_chunks(), 0);
+        assert_eq!(diagnostics.last_error(), None);
+    }
+}
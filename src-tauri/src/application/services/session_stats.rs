@@ -0,0 +1,213 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::domain::{count_words, SessionStats, SttProviderType, Transcription};
+
+/// RMS чанка (i16), начиная с которого считаем его речью (~ -36 dBFS)
+pub const SPEECH_RMS_THRESHOLD: f64 = 500.0;
+
+/// Короче этого WPM не считаем — на паре секунд значение бессмысленное
+const MIN_DURATION_FOR_WPM_SECS: f64 = 1.0;
+
+pub type SessionStatsListener = Arc<dyn Fn(SessionStats) + Send + Sync>;
+
+/// RMS амплитуды чанка
+pub fn chunk_rms(samples: &[i16]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    (sum / samples.len() as f64).sqrt()
+}
+
+struct ActiveSession {
+    started: Instant,
+    started_at_ms: i64,
+    provider: SttProviderType,
+    language: Option<String>,
+    speaking_secs: f64,
+    silence_secs: f64,
+    word_count: u64,
+}
+
+#[derive(Default)]
+struct TrackerInner {
+    session_id: u64,
+    active: Option<ActiveSession>,
+    listener: Option<SessionStatsListener>,
+}
+
+/// Статистика сессии записи: длительность, слова, WPM, доля речи.
+///
+/// Считается на границе TranscriptionService ↔ SttProvider: аудио — по отправляемым чанкам,
+/// слова — по финальным фразам (уже после исправлений и нормализации).
+/// Итог отдаётся listener'у при остановке записи.
+#[derive(Default)]
+pub struct SessionStatsTracker {
+    inner: Mutex<TrackerInner>,
+}
+
+impl SessionStatsTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ID следующей сессии (как в LatencyTracker::begin_session)
+    pub fn begin_session(&self, session_id: u64) {
+        self.lock().session_id = session_id;
+    }
+
+    pub fn set_listener(&self, listener: Option<SessionStatsListener>) {
+        self.lock().listener = listener;
+    }
+
+    /// Запись началась
+    pub fn start(&self, provider: SttProviderType, language: Option<String>) {
+        self.start_at(provider, language, Instant::now());
+    }
+
+    /// Аудио-чанк длительностью `secs` отправлен провайдеру
+    pub fn record_audio(&self, secs: f64, is_speech: bool) {
+        let mut inner = self.lock();
+        let Some(session) = inner.active.as_mut() else {
+            return;
+        };
+        if is_speech {
+            session.speaking_secs += secs;
+        } else {
+            session.silence_secs += secs;
+        }
+    }
+
+    /// Пришла финальная фраза
+    pub fn record_final(&self, transcription: &Transcription) {
+        let mut inner = self.lock();
+        let Some(session) = inner.active.as_mut() else {
+            return;
+        };
+        session.word_count += count_words(&transcription.text);
+        if let Some(language) = transcription.language.as_ref().filter(|l| !l.is_empty()) {
+            session.language = Some(language.clone());
+        }
+    }
+
+    /// Запись остановлена: возвращает итог и отдаёт его listener'у.
+    /// None — сессия не была начата (повторный stop).
+    pub fn finish(&self) -> Option<SessionStats> {
+        self.finish_at(Instant::now())
+    }
+
+    fn start_at(&self, provider: SttProviderType, language: Option<String>, now: Instant) {
+        self.lock().active = Some(ActiveSession {
+            started: now,
+            started_at_ms: chrono::Utc::now().timestamp_millis(),
+            provider,
+            language,
+            speaking_secs: 0.0,
+            silence_secs: 0.0,
+            word_count: 0,
+        });
+    }
+
+    fn finish_at(&self, now: Instant) -> Option<SessionStats> {
+        let (stats, listener) = {
+            let mut inner = self.lock();
+            let session = inner.active.take()?;
+
+            let duration_secs = now.saturating_duration_since(session.started).as_secs_f64();
+            let audio_secs = session.speaking_secs + session.silence_secs;
+            let stats = SessionStats {
+                session_id: inner.session_id,
+                started_at: session.started_at_ms,
+                duration_secs,
+                speaking_secs: session.speaking_secs,
+                silence_secs: session.silence_secs,
+                word_count: session.word_count,
+                words_per_minute: if duration_secs >= MIN_DURATION_FOR_WPM_SECS {
+                    session.word_count as f64 * 60.0 / duration_secs
+                } else {
+                    0.0
+                },
+                speaking_ratio: if audio_secs > 0.0 {
+                    session.speaking_secs / audio_secs
+                } else {
+                    0.0
+                },
+                language: session.language,
+                provider: session.provider,
+                app: None,
+            };
+            (stats, inner.listener.clone())
+        };
+
+        if let Some(listener) = listener {
+            listener(stats.clone());
+        }
+        Some(stats)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TrackerInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn computes_wpm_and_speaking_ratio() {
+        let tracker = SessionStatsTracker::new();
+        tracker.begin_session(7);
+
+        let t0 = Instant::now();
+        tracker.start_at(SttProviderType::Deepgram, Some("ru".to_string()), t0);
+        for _ in 0..30 {
+            tracker.record_audio(0.1, true);
+        }
+        tracker.record_audio(1.0, false);
+        tracker.record_final(&Transcription::final_result("раз два три".to_string()));
+        tracker.record_final(
+            &Transcription::final_result("четыре, пять.".to_string()).with_language("en".to_string()),
+        );
+
+        let stats = tracker.finish_at(t0 + Duration::from_secs(30)).unwrap();
+        assert_eq!(stats.session_id, 7);
+        assert_eq!(stats.word_count, 5);
+        assert!((stats.words_per_minute - 10.0).abs() < 1e-9);
+        assert!((stats.speaking_secs - 3.0).abs() < 1e-9);
+        assert!((stats.speaking_ratio - 0.75).abs() < 1e-9);
+        assert_eq!(stats.language.as_deref(), Some("en"));
+        assert_eq!(stats.provider, SttProviderType::Deepgram);
+    }
+
+    #[test]
+    fn finish_notifies_listener_once() {
+        let tracker = SessionStatsTracker::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_cb = received.clone();
+        tracker.set_listener(Some(Arc::new(move |stats: SessionStats| {
+            received_cb.lock().unwrap().push(stats.word_count);
+        })));
+
+        // Без start ничего не считаем
+        tracker.record_final(&Transcription::final_result("мимо".to_string()));
+        assert!(tracker.finish().is_none());
+
+        tracker.start(SttProviderType::Backend, None);
+        tracker.record_final(&Transcription::final_result("привет".to_string()));
+        let stats = tracker.finish().unwrap();
+        assert_eq!(stats.words_per_minute, 0.0);
+        assert!(tracker.finish().is_none());
+
+        assert_eq!(*received.lock().unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn rms_of_silence_and_tone() {
+        assert_eq!(chunk_rms(&[]), 0.0);
+        assert_eq!(chunk_rms(&[0; 160]), 0.0);
+        assert!(chunk_rms(&[1000, -1000, 1000, -1000]) > SPEECH_RMS_THRESHOLD);
+    }
+}
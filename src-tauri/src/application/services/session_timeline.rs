@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::domain::{
+    SessionTimeline, SttProviderType, TimelineAudio, TimelineSegment, Transcription, SESSION_TIMELINE_FORMAT_VERSION,
+};
+
+/// Сколько завершённых сессий держим для `export_session_timeline`
+const MAX_TIMELINE_SESSIONS: usize = 20;
+
+struct ActiveTimeline {
+    started: Instant,
+    timeline: SessionTimeline,
+}
+
+#[derive(Default)]
+struct RecorderInner {
+    session_id: u64,
+    active: Option<ActiveTimeline>,
+    history: VecDeque<SessionTimeline>,
+}
+
+/// Таймлайн сессии записи: финальные фразы с временем от старта записи и смещениями в аудио.
+///
+/// Время фразы — как у встречи (`MeetingRecorder`): конец — когда фраза пришла, начало — конец минус
+/// длительность от провайдера. Часы провайдера для этого не годятся: keep-alive соединение
+/// переживает несколько сессий. Смещения указывают в моно PCM записи — ту же раскладку пишет журнал сессии.
+#[derive(Default)]
+pub struct SessionTimelineRecorder {
+    inner: Mutex<RecorderInner>,
+}
+
+impl SessionTimelineRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ID следующей сессии (как в SessionStatsTracker::begin_session)
+    pub fn begin_session(&self, session_id: u64) {
+        self.lock().session_id = session_id;
+    }
+
+    /// Запись началась; `sample_rate` — частота аудио, которое уходит провайдеру
+    pub fn start(&self, provider: SttProviderType, sample_rate: u32) {
+        self.start_at(provider, sample_rate, Instant::now());
+    }
+
+    /// Пришла финальная фраза (уже после исправлений и нормализации)
+    pub fn record_final(&self, transcription: &Transcription) {
+        let mut inner = self.lock();
+        let Some(active) = inner.active.as_mut() else {
+            return;
+        };
+        let received_secs = active.started.elapsed().as_secs_f64();
+        push_segment(&mut active.timeline, transcription, received_secs);
+    }
+
+    /// Запись остановлена. Таймлайн уходит в историю со следующим `start`:
+    /// последние фразы приходят, когда стрим закрывается — уже после остановки
+    pub fn finish(&self) {
+        self.finish_at(Instant::now());
+    }
+
+    /// Таймлайн сессии: текущей (пока идёт запись) или одной из последних завершённых
+    pub fn timeline(&self, session_id: u64) -> Option<SessionTimeline> {
+        let inner = self.lock();
+        if let Some(active) = inner.active.as_ref().filter(|a| a.timeline.session_id == session_id) {
+            let mut timeline = active.timeline.clone();
+            if timeline.in_progress {
+                timeline.duration_secs = active.started.elapsed().as_secs_f64();
+            }
+            return Some(timeline);
+        }
+        inner.history.iter().rev().find(|t| t.session_id == session_id).cloned()
+    }
+
+    fn start_at(&self, provider: SttProviderType, sample_rate: u32, now: Instant) {
+        let mut inner = self.lock();
+        if let Some(previous) = inner.active.take() {
+            let mut timeline = previous.timeline;
+            if timeline.in_progress {
+                timeline.duration_secs = now.saturating_duration_since(previous.started).as_secs_f64();
+                timeline.in_progress = false;
+            }
+            inner.history.push_back(timeline);
+            while inner.history.len() > MAX_TIMELINE_SESSIONS {
+                inner.history.pop_front();
+            }
+        }
+        let session_id = inner.session_id;
+        inner.active = Some(ActiveTimeline {
+            started: now,
+            timeline: SessionTimeline {
+                format_version: SESSION_TIMELINE_FORMAT_VERSION,
+                session_id,
+                started_at: chrono::Utc::now().timestamp_millis(),
+                duration_secs: 0.0,
+                provider,
+                in_progress: true,
+                audio: TimelineAudio::mono(sample_rate),
+                segments: Vec::new(),
+            },
+        });
+    }
+
+    fn finish_at(&self, now: Instant) {
+        let mut inner = self.lock();
+        let Some(active) = inner.active.as_mut().filter(|a| a.timeline.in_progress) else {
+            return;
+        };
+        active.timeline.duration_secs = now.saturating_duration_since(active.started).as_secs_f64();
+        active.timeline.in_progress = false;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecorderInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn push_segment(timeline: &mut SessionTimeline, transcription: &Transcription, received_secs: f64) {
+    let text = transcription.text.trim();
+    if text.is_empty() {
+        return;
+    }
+    // Фразы не перекрываются: провайдер мог отдать длительность с запасом
+    let previous_end = timeline.segments.last().map_or(0.0, |s| s.end_secs);
+    let end_secs = received_secs.max(previous_end);
+    let start_secs = (end_secs - transcription.duration.max(0.0)).max(previous_end);
+    let audio = timeline.audio.span(start_secs, end_secs);
+    timeline.segments.push(TimelineSegment {
+        index: timeline.segments.len(),
+        text: text.to_string(),
+        start_secs,
+        end_secs,
+        confidence: transcription.confidence,
+        speaker: transcription.speaker,
+        channel: transcription.channel,
+        language: transcription.language.clone(),
+        audio,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn segments_are_aligned_to_session_audio() {
+        let recorder = SessionTimelineRecorder::new();
+        recorder.begin_session(5);
+        let t0 = Instant::now();
+        recorder.start_at(SttProviderType::Deepgram, 16000, t0);
+        {
+            let mut inner = recorder.lock();
+            let timeline = &mut inner.active.as_mut().unwrap().timeline;
+            let first = Transcription::final_result("Привет.".to_string())
+                .with_timing(0.0, 1.5)
+                .with_confidence(0.9)
+                .with_speaker(Some(1));
+            push_segment(timeline, &first, 2.0);
+            // Длительность с запасом — начало не заходит на предыдущую фразу
+            push_segment(timeline, &Transcription::final_result("Как дела?".to_string()).with_timing(0.0, 5.0), 4.0);
+            push_segment(timeline, &Transcription::final_result("  ".to_string()), 4.5);
+        }
+
+        let live = recorder.timeline(5).unwrap();
+        assert!(live.in_progress);
+        assert_eq!(live.segments.len(), 2);
+        assert_eq!((live.segments[0].start_secs, live.segments[0].end_secs), (0.5, 2.0));
+        assert_eq!(live.segments[0].speaker, Some(1));
+        assert_eq!(live.segments[0].audio.start_byte, 16_000);
+        assert_eq!((live.segments[1].start_secs, live.segments[1].end_secs), (2.0, 4.0));
+        assert_eq!(live.segments[1].audio.end_sample, 64_000);
+
+        recorder.finish_at(t0 + Duration::from_secs(6));
+        // Фраза, дошедшая при закрытии стрима, ещё попадает в таймлайн
+        recorder.record_final(&Transcription::final_result("Пока.".to_string()));
+        recorder.begin_session(6);
+        recorder.start(SttProviderType::Deepgram, 16000);
+
+        let finished = recorder.timeline(5).unwrap();
+        assert!(!finished.in_progress);
+        assert_eq!(finished.duration_secs, 6.0);
+        assert_eq!(finished.segments.len(), 3);
+        assert!(recorder.timeline(6).unwrap().in_progress);
+        assert!(recorder.timeline(4).is_none());
+    }
+}
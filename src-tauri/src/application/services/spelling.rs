@@ -0,0 +1,339 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::bare_word;
+
+/// Spoken spelling mode: "alpha bravo one at example dot com" → "ab1@example.com".
+///
+/// Включается голосовой командой в начале фразы ("spelling mode", "по буквам") или хоткеем,
+/// выключается командой в конце фразы ("end spelling", "конец по буквам") или хоткеем.
+/// В режиме финальный текст не проходит пунктуацию/нормализацию: слова фонетического алфавита,
+/// цифры и названия символов превращаются в символы и пишутся слитно, остальные слова — как есть.
+#[derive(Default)]
+pub struct SpellingMode {
+    enabled: AtomicBool,
+    listener: Mutex<Option<SpellingModeListener>>,
+}
+
+/// Слушатель переключений режима (true — режим включён)
+pub type SpellingModeListener = Arc<dyn Fn(bool) + Send + Sync>;
+
+const START_COMMANDS: &[&str] = &[
+    "spelling mode",
+    "start spelling",
+    "spell mode",
+    "режим по буквам",
+    "диктовка по буквам",
+    "по буквам",
+];
+
+const STOP_COMMANDS: &[&str] = &[
+    "end spelling",
+    "stop spelling",
+    "exit spelling",
+    "spelling off",
+    "конец по буквам",
+    "хватит по буквам",
+    "обычный режим",
+];
+
+/// Фонетический алфавит ICAO/NATO (и как его записывает русское распознавание)
+const LETTERS: &[(&str, char)] = &[
+    ("alpha", 'a'),
+    ("alfa", 'a'),
+    ("bravo", 'b'),
+    ("charlie", 'c'),
+    ("delta", 'd'),
+    ("echo", 'e'),
+    ("foxtrot", 'f'),
+    ("golf", 'g'),
+    ("hotel", 'h'),
+    ("india", 'i'),
+    ("juliet", 'j'),
+    ("juliett", 'j'),
+    ("kilo", 'k'),
+    ("lima", 'l'),
+    ("mike", 'm'),
+    ("november", 'n'),
+    ("oscar", 'o'),
+    ("papa", 'p'),
+    ("quebec", 'q'),
+    ("romeo", 'r'),
+    ("sierra", 's'),
+    ("tango", 't'),
+    ("uniform", 'u'),
+    ("victor", 'v'),
+    ("whiskey", 'w'),
+    ("whisky", 'w'),
+    ("x-ray", 'x'),
+    ("xray", 'x'),
+    ("yankee", 'y'),
+    ("zulu", 'z'),
+    ("альфа", 'a'),
+    ("браво", 'b'),
+    ("чарли", 'c'),
+    ("дельта", 'd'),
+    ("эхо", 'e'),
+    ("фокстрот", 'f'),
+    ("гольф", 'g'),
+    ("отель", 'h'),
+    ("индия", 'i'),
+    ("джульетта", 'j'),
+    ("кило", 'k'),
+    ("лима", 'l'),
+    ("майк", 'm'),
+    ("ноябрь", 'n'),
+    ("оскар", 'o'),
+    ("папа", 'p'),
+    ("квебек", 'q'),
+    ("ромео", 'r'),
+    ("сьерра", 's'),
+    ("танго", 't'),
+    ("униформ", 'u'),
+    ("виктор", 'v'),
+    ("виски", 'w'),
+    ("икс-рей", 'x'),
+    ("янки", 'y'),
+    ("зулу", 'z'),
+];
+
+const DIGITS: &[(&str, char)] = &[
+    ("zero", '0'),
+    ("oh", '0'),
+    ("one", '1'),
+    ("two", '2'),
+    ("three", '3'),
+    ("four", '4'),
+    ("five", '5'),
+    ("six", '6'),
+    ("seven", '7'),
+    ("eight", '8'),
+    ("nine", '9'),
+    ("ноль", '0'),
+    ("нуль", '0'),
+    ("один", '1'),
+    ("одна", '1'),
+    ("два", '2'),
+    ("две", '2'),
+    ("три", '3'),
+    ("четыре", '4'),
+    ("пять", '5'),
+    ("шесть", '6'),
+    ("семь", '7'),
+    ("восемь", '8'),
+    ("девять", '9'),
+];
+
+const SYMBOLS: &[(&str, char)] = &[
+    ("at", '@'),
+    ("dot", '.'),
+    ("period", '.'),
+    ("point", '.'),
+    ("dash", '-'),
+    ("hyphen", '-'),
+    ("minus", '-'),
+    ("underscore", '_'),
+    ("slash", '/'),
+    ("plus", '+'),
+    ("hash", '#'),
+    ("dollar", '$'),
+    ("percent", '%'),
+    ("ampersand", '&'),
+    ("asterisk", '*'),
+    ("star", '*'),
+    ("colon", ':'),
+    ("exclamation", '!'),
+    ("space", ' '),
+    ("собака", '@'),
+    ("точка", '.'),
+    ("тире", '-'),
+    ("дефис", '-'),
+    ("минус", '-'),
+    ("подчёркивание", '_'),
+    ("подчеркивание", '_'),
+    ("слэш", '/'),
+    ("слеш", '/'),
+    ("плюс", '+'),
+    ("решётка", '#'),
+    ("решетка", '#'),
+    ("доллар", '$'),
+    ("процент", '%'),
+    ("звёздочка", '*'),
+    ("звездочка", '*'),
+    ("двоеточие", ':'),
+    ("пробел", ' '),
+];
+
+/// Следующая буква — заглавная
+const CAPITAL_MODIFIERS: &[&str] = &["capital", "uppercase", "cap", "big", "заглавная", "большая"];
+
+/// Знаки, которые провайдер расставляет сам ("Alpha, bravo.") — в режиме по буквам их нет
+const AUTO_PUNCTUATION: &[char] = &[',', '.', ';', ':', '!', '?', '…'];
+
+impl SpellingMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Включает/выключает режим; false — режим уже был в этом состоянии
+    pub fn set(&self, enabled: bool) -> bool {
+        if self.enabled.swap(enabled, Ordering::Relaxed) == enabled {
+            return false;
+        }
+        log::info!("Spelling mode {}", if enabled { "enabled" } else { "disabled" });
+        let listener = self.listener.lock().ok().and_then(|listener| listener.clone());
+        if let Some(listener) = listener {
+            listener(enabled);
+        }
+        true
+    }
+
+    pub fn set_listener(&self, listener: Option<SpellingModeListener>) {
+        if let Ok(mut current) = self.listener.lock() {
+            *current = listener;
+        }
+    }
+
+    /// Финализированный текст: выполняет голосовые команды и пишет по буквам.
+    /// None — режим выключен и команд нет, текст идёт обычной обработкой
+    pub fn apply(&self, text: &str) -> Option<String> {
+        let (spelled, next) = self.resolve(text)?;
+        if let Some(enabled) = next {
+            self.set(enabled);
+        }
+        Some(spelled)
+    }
+
+    /// То же для промежуточного текста: команды показываются уже выполненными, но режим не меняют
+    pub fn preview(&self, text: &str) -> Option<String> {
+        self.resolve(text).map(|(spelled, _)| spelled)
+    }
+
+    fn resolve(&self, text: &str) -> Option<(String, Option<bool>)> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let start = START_COMMANDS.iter().find_map(|command| command_len(&words, command, false));
+        let enabled = self.is_enabled() || start.is_some();
+        // Команду выключения без режима не ищем: "stop spelling" в обычной речи остаётся текстом
+        let stop = STOP_COMMANDS
+            .iter()
+            .find_map(|command| command_len(&words, command, true))
+            .filter(|_| enabled);
+        if !enabled {
+            return None;
+        }
+
+        let from = start.unwrap_or(0);
+        let to = words.len() - stop.unwrap_or(0);
+        let spelled = if from < to { spell_words(&words[from..to]) } else { String::new() };
+        let next = if stop.is_some() {
+            Some(false)
+        } else {
+            start.map(|_| true)
+        };
+        Some((spelled, next))
+    }
+}
+
+/// Текст, продиктованный по буквам, в символы
+pub fn spell(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    spell_words(&words)
+}
+
+fn spell_words(words: &[&str]) -> String {
+    let mut out = String::new();
+    let mut capital = false;
+    for word in words {
+        let bare = bare_word(word);
+        if bare.is_empty() {
+            // Символ, который провайдер уже записал знаком ("@", "-"), оставляем
+            out.extend(word.chars().filter(|c| !AUTO_PUNCTUATION.contains(c)));
+            continue;
+        }
+        if CAPITAL_MODIFIERS.contains(&bare.as_str()) {
+            capital = true;
+            continue;
+        }
+
+        let spelled = lookup(LETTERS, &bare)
+            .or_else(|| lookup(DIGITS, &bare))
+            .or_else(|| lookup(SYMBOLS, &bare))
+            .map(String::from)
+            .unwrap_or(bare);
+        if capital {
+            let mut chars = spelled.chars();
+            if let Some(first) = chars.next() {
+                out.extend(first.to_uppercase());
+                out.push_str(chars.as_str());
+            }
+            capital = false;
+        } else {
+            out.push_str(&spelled);
+        }
+    }
+    out
+}
+
+fn lookup(table: &[(&str, char)], word: &str) -> Option<char> {
+    table.iter().find(|(name, _)| *name == word).map(|(_, c)| *c)
+}
+
+/// Сколько слов с начала (или с конца) `words` занимает команда
+fn command_len(words: &[&str], command: &str, suffix: bool) -> Option<usize> {
+    let command: Vec<&str> = command.split_whitespace().collect();
+    if command.len() > words.len() {
+        return None;
+    }
+    let edge = if suffix {
+        &words[words.len() - command.len()..]
+    } else {
+        &words[..command.len()]
+    };
+    edge.iter()
+        .zip(&command)
+        .all(|(word, expected)| bare_word(word) == *expected)
+        .then_some(command.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spells_phonetic_alphabet_digits_and_symbols() {
+        assert_eq!(spell("Alpha, bravo, Charlie one two."), "abc12");
+        assert_eq!(spell("john dot doe at example dot com"), "john.doe@example.com");
+        assert_eq!(spell("capital kilo seven dash X-ray"), "K7-x");
+        assert_eq!(spell("Майк, собака, альфа точка ру"), "m@a.ру");
+        assert_eq!(spell("order 42 @ a"), "order42@a");
+    }
+
+    #[test]
+    fn voice_commands_switch_the_mode() {
+        let mode = SpellingMode::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_cb = events.clone();
+        mode.set_listener(Some(Arc::new(move |enabled| events_cb.lock().unwrap().push(enabled))));
+
+        assert_eq!(mode.apply("Please stop spelling it wrong"), None);
+        assert_eq!(mode.preview("Spelling mode alpha").as_deref(), Some("a"));
+        assert!(!mode.is_enabled());
+
+        assert_eq!(mode.apply("Spelling mode. Alpha bravo").as_deref(), Some("ab"));
+        assert!(mode.is_enabled());
+        assert_eq!(mode.apply("Charlie, one").as_deref(), Some("c1"));
+        assert_eq!(mode.apply("delta. End spelling.").as_deref(), Some("d"));
+        assert!(!mode.is_enabled());
+        assert_eq!(mode.apply("обычная речь"), None);
+
+        // Повтор того же финала не переключает режим второй раз
+        assert_eq!(mode.apply("По буквам").as_deref(), Some(""));
+        assert_eq!(mode.apply("По буквам").as_deref(), Some(""));
+        assert!(!mode.set(true));
+        assert_eq!(*events.lock().unwrap(), vec![true, false, true]);
+    }
+}
@@ -0,0 +1,104 @@
+use tokio::time::{Duration, Instant};
+
+use crate::domain::SttProviderDebugState;
+
+/// Сколько раз за сессию перезапускаем стрим с мёртвой задачей чтения ответов
+pub const MAX_STREAM_RESTARTS: u32 = 3;
+
+/// Как часто processor спрашивает провайдера о здоровье стрима
+const RECEIVER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watchdog for the provider's receiver task during recording.
+///
+/// Если задача чтения ответов упала или завершилась, а стрим всё ещё считается открытым,
+/// send_audio продолжает отправлять аудио в никуда: partial/final больше не придут.
+/// Processor периодически сверяется с провайдером и перезапускает стрим, но не чаще
+/// MAX_STREAM_RESTARTS раз за сессию — дальше это уже не случайный сбой.
+#[derive(Debug)]
+pub struct StreamWatchdog {
+    interval: Duration,
+    last_check: Option<Instant>,
+    restarts: u32,
+}
+
+impl Default for StreamWatchdog {
+    fn default() -> Self {
+        Self::new(RECEIVER_CHECK_INTERVAL)
+    }
+}
+
+impl StreamWatchdog {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_check: None,
+            restarts: 0,
+        }
+    }
+
+    /// Пора проверять (первая проверка — сразу); отмечает время проверки
+    pub fn due(&mut self, now: Instant) -> bool {
+        if self
+            .last_check
+            .is_some_and(|last| now.saturating_duration_since(last) < self.interval)
+        {
+            return false;
+        }
+        self.last_check = Some(now);
+        true
+    }
+
+    /// Стрим открыт, но ответы читать некому
+    pub fn is_receiver_dead(state: &SttProviderDebugState) -> bool {
+        state.streaming && state.receiver_alive == Some(false)
+    }
+
+    /// Номер очередного перезапуска; None — попытки исчерпаны
+    pub fn begin_restart(&mut self) -> Option<u32> {
+        if self.restarts >= MAX_STREAM_RESTARTS {
+            return None;
+        }
+        self.restarts += 1;
+        Some(self.restarts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_once_per_interval() {
+        let mut watchdog = StreamWatchdog::new(Duration::from_secs(1));
+        let start = Instant::now();
+        assert!(watchdog.due(start));
+        assert!(!watchdog.due(start + Duration::from_millis(500)));
+        assert!(watchdog.due(start + Duration::from_millis(1000)));
+    }
+
+    #[test]
+    fn only_a_streaming_provider_with_a_finished_receiver_is_dead() {
+        let dead = SttProviderDebugState {
+            streaming: true,
+            receiver_alive: Some(false),
+            ..Default::default()
+        };
+        assert!(StreamWatchdog::is_receiver_dead(&dead));
+        assert!(!StreamWatchdog::is_receiver_dead(&SttProviderDebugState {
+            streaming: false,
+            ..dead.clone()
+        }));
+        assert!(!StreamWatchdog::is_receiver_dead(&SttProviderDebugState {
+            receiver_alive: None,
+            ..dead
+        }));
+    }
+
+    #[test]
+    fn restarts_are_limited_per_session() {
+        let mut watchdog = StreamWatchdog::default();
+        let attempts: Vec<u32> = std::iter::from_fn(|| watchdog.begin_restart()).collect();
+        assert_eq!(attempts, vec![1, 2, 3]);
+        assert_eq!(watchdog.begin_restart(), None);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::domain::TelemetryEvent;
+
+/// Максимум событий в офлайн-очереди (старые отбрасываются)
+pub const MAX_TELEMETRY_QUEUE: usize = 500;
+
+/// Сколько событий отправляем за один запрос
+pub const TELEMETRY_BATCH_SIZE: usize = 50;
+
+/// Ставит событие в очередь, оставляя не больше MAX_TELEMETRY_QUEUE последних
+pub fn enqueue_telemetry(queue: &mut Vec<TelemetryEvent>, event: TelemetryEvent) {
+    queue.push(event);
+    if queue.len() > MAX_TELEMETRY_QUEUE {
+        let excess = queue.len() - MAX_TELEMETRY_QUEUE;
+        queue.drain(0..excess);
+    }
+}
+
+/// Следующая пачка на отправку (самые старые события)
+pub fn next_telemetry_batch(queue: &[TelemetryEvent]) -> Vec<TelemetryEvent> {
+    queue.iter().take(TELEMETRY_BATCH_SIZE).cloned().collect()
+}
+
+/// Убирает из очереди отправленную пачку. Пока шла отправка, очередь могли подрезать сверху —
+/// поэтому удаляем только совпадающий префикс.
+pub fn acknowledge_telemetry_batch(queue: &mut Vec<TelemetryEvent>, sent: &[TelemetryEvent]) {
+    let acknowledged = queue
+        .iter()
+        .zip(sent)
+        .take_while(|(queued, sent)| queued == sent)
+        .count();
+    queue.drain(0..acknowledged);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{SttProviderType, TelemetryEventKind};
+
+    fn event(hour: i64) -> TelemetryEvent {
+        TelemetryEvent::new(
+            TelemetryEventKind::Error {
+                provider: SttProviderType::Backend,
+                error_type: "connection".to_string(),
+            },
+            hour * 3600 * 1000,
+        )
+    }
+
+    #[test]
+    fn queue_is_capped_and_batches_are_acknowledged() {
+        let mut queue = Vec::new();
+        for hour in 0..(MAX_TELEMETRY_QUEUE as i64 + 10) {
+            enqueue_telemetry(&mut queue, event(hour));
+        }
+        assert_eq!(queue.len(), MAX_TELEMETRY_QUEUE);
+        assert_eq!(queue[0], event(10));
+
+        let batch = next_telemetry_batch(&queue);
+        assert_eq!(batch.len(), TELEMETRY_BATCH_SIZE);
+
+        acknowledge_telemetry_batch(&mut queue, &batch);
+        assert_eq!(queue.len(), MAX_TELEMETRY_QUEUE - TELEMETRY_BATCH_SIZE);
+        assert_eq!(queue[0], event(10 + TELEMETRY_BATCH_SIZE as i64));
+
+        // Очередь уже не начинается с отправленной пачки — ничего не удаляем
+        acknowledge_telemetry_batch(&mut queue, &batch);
+        assert_eq!(queue.len(), MAX_TELEMETRY_QUEUE - TELEMETRY_BATCH_SIZE);
+    }
+}
@@ -0,0 +1,716 @@
+use crate::domain::NormalizationRules;
+
+/// Нормализация "произнесённых" чисел, дат и сумм в письменную форму.
+///
+/// Провайдеры часто отдают числа словами ("twenty five dollars", "третье мая").
+/// Этот этап переписывает их в привычный письменный вид ("$25", "3 мая") по правилам языка.
+/// Применяется к финальным фразам; неподдерживаемые языки не трогаем.
+#[derive(Debug, Clone, Copy)]
+pub struct TextNormalizer {
+    locale: Locale,
+    rules: NormalizationRules,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Locale {
+    En,
+    Ru,
+}
+
+impl Locale {
+    fn from_language(language: &str) -> Option<Self> {
+        let base = language
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        match base.as_str() {
+            "en" => Some(Self::En),
+            "ru" => Some(Self::Ru),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberWord {
+    /// 0-19 и десятки
+    Value(u64),
+    /// "hundred" (множитель)
+    Hundred,
+    /// "двести", "триста", ... (слагаемое)
+    Hundreds(u64),
+    /// тысяча, миллион, ...
+    Scale(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Currency {
+    Dollar,
+    Euro,
+    Ruble,
+}
+
+impl Currency {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Dollar => "$",
+            Self::Euro => "€",
+            Self::Ruble => "₽",
+        }
+    }
+}
+
+const EN_MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September", "October",
+    "November", "December",
+];
+
+/// Названия месяцев, которые в английском чаще всего означают что-то другое ("may", "march")
+const EN_AMBIGUOUS_MONTHS: [&str; 2] = ["may", "march"];
+
+const RU_MONTHS_GENITIVE: [&str; 12] = [
+    "января", "февраля", "марта", "апреля", "мая", "июня", "июля", "августа", "сентября", "октября",
+    "ноября", "декабря",
+];
+
+/// Основы русских порядковых числительных; длинные основы раньше коротких ("пятнадцат" до "пят")
+const RU_ORDINAL_STEMS: [(&str, u64); 22] = [
+    ("восемнадцат", 18),
+    ("четырнадцат", 14),
+    ("одиннадцат", 11),
+    ("шестнадцат", 16),
+    ("девятнадцат", 19),
+    ("двенадцат", 12),
+    ("тринадцат", 13),
+    ("пятнадцат", 15),
+    ("семнадцат", 17),
+    ("двадцат", 20),
+    ("тридцат", 30),
+    ("четвёрт", 4),
+    ("четверт", 4),
+    ("восьм", 8),
+    ("девят", 9),
+    ("десят", 10),
+    ("перв", 1),
+    ("втор", 2),
+    ("трет", 3),
+    ("шест", 6),
+    ("седьм", 7),
+    ("пят", 5),
+];
+
+const RU_ORDINAL_ENDINGS: [&str; 16] = [
+    "ое", "ого", "ому", "ом", "ым", "ый", "ой", "ая", "ую", "ий", "ье", "ьего", "ьему", "ьем", "ьим", "ья",
+];
+
+struct Token<'a> {
+    text: &'a str,
+    is_word: bool,
+}
+
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut current_is_word: Option<bool> = None;
+
+    for (idx, ch) in text.char_indices() {
+        let is_word = ch.is_alphanumeric();
+        match current_is_word {
+            Some(prev) if prev == is_word => {}
+            Some(prev) => {
+                tokens.push(Token {
+                    text: &text[start..idx],
+                    is_word: prev,
+                });
+                start = idx;
+                current_is_word = Some(is_word);
+            }
+            None => current_is_word = Some(is_word),
+        }
+    }
+    if let Some(is_word) = current_is_word {
+        tokens.push(Token {
+            text: &text[start..],
+            is_word,
+        });
+    }
+    tokens
+}
+
+/// Слова фразы и разделители между ними
+struct Words<'a> {
+    tokens: Vec<Token<'a>>,
+    /// Индексы слов в tokens
+    positions: Vec<usize>,
+    lower: Vec<String>,
+}
+
+impl<'a> Words<'a> {
+    fn new(text: &'a str) -> Self {
+        let tokens = tokenize(text);
+        let positions: Vec<usize> = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.is_word)
+            .map(|(i, _)| i)
+            .collect();
+        let lower = positions.iter().map(|&i| tokens[i].text.to_lowercase()).collect();
+        Self {
+            tokens,
+            positions,
+            lower,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    fn word(&self, w: usize) -> Option<&str> {
+        self.lower.get(w).map(|s| s.as_str())
+    }
+
+    fn original(&self, w: usize) -> &str {
+        self.tokens[self.positions[w]].text
+    }
+
+    /// Слова w и w+1 стоят рядом (только пробелы или дефис между ними)
+    fn joined(&self, w: usize) -> bool {
+        if w + 1 >= self.positions.len() {
+            return false;
+        }
+        self.tokens[self.positions[w] + 1..self.positions[w + 1]]
+            .iter()
+            .all(|t| matches!(t.text.trim(), "" | "-"))
+    }
+}
+
+/// Найденный фрагмент: сколько слов заменить и на что
+struct Replacement {
+    consumed: usize,
+    text: String,
+}
+
+fn parse_digits(word: &str) -> Option<u64> {
+    if word.is_empty() || word.len() > 15 || !word.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    word.parse().ok()
+}
+
+impl TextNormalizer {
+    /// Нормализатор для языка; None — язык не поддерживается
+    pub fn new(language: &str, rules: NormalizationRules) -> Option<Self> {
+        Locale::from_language(language).map(|locale| Self { locale, rules })
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        let words = Words::new(text);
+        if words.positions.is_empty() {
+            return text.to_string();
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut next_token = 0;
+        let mut w = 0;
+        while w < words.len() {
+            let Some(replacement) = self.match_at(&words, w) else {
+                w += 1;
+                continue;
+            };
+
+            for token in &words.tokens[next_token..words.positions[w]] {
+                out.push_str(token.text);
+            }
+            out.push_str(&replacement.text);
+            next_token = words.positions[w + replacement.consumed - 1] + 1;
+            w += replacement.consumed;
+        }
+        for token in &words.tokens[next_token..] {
+            out.push_str(token.text);
+        }
+        out
+    }
+
+    fn match_at(&self, words: &Words, w: usize) -> Option<Replacement> {
+        if self.rules.dates {
+            if let Some(r) = self.match_date(words, w) {
+                return Some(r);
+            }
+        }
+        if self.rules.currency {
+            if let Some(r) = self.match_currency(words, w) {
+                return Some(r);
+            }
+        }
+        if self.rules.numbers {
+            if let Some((value, consumed)) = self.parse_number(words, w) {
+                // Одиночные маленькие числительные оставляем словами ("one of them", "три дня")
+                // Цифры уже в письменной форме
+                let is_digits = parse_digits(words.word(w)?).is_some();
+                if !is_digits && (consumed > 1 || value >= self.rules.min_standalone_number) {
+                    return Some(Replacement {
+                        consumed,
+                        text: value.to_string(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn number_word(&self, word: &str) -> Option<NumberWord> {
+        use NumberWord::*;
+        let value = match self.locale {
+            Locale::En => match word {
+                "zero" => Value(0),
+                "one" => Value(1),
+                "two" => Value(2),
+                "three" => Value(3),
+                "four" => Value(4),
+                "five" => Value(5),
+                "six" => Value(6),
+                "seven" => Value(7),
+                "eight" => Value(8),
+                "nine" => Value(9),
+                "ten" => Value(10),
+                "eleven" => Value(11),
+                "twelve" => Value(12),
+                "thirteen" => Value(13),
+                "fourteen" => Value(14),
+                "fifteen" => Value(15),
+                "sixteen" => Value(16),
+                "seventeen" => Value(17),
+                "eighteen" => Value(18),
+                "nineteen" => Value(19),
+                "twenty" => Value(20),
+                "thirty" => Value(30),
+                "forty" => Value(40),
+                "fifty" => Value(50),
+                "sixty" => Value(60),
+                "seventy" => Value(70),
+                "eighty" => Value(80),
+                "ninety" => Value(90),
+                "hundred" => Hundred,
+                "thousand" => Scale(1_000),
+                "million" => Scale(1_000_000),
+                "billion" => Scale(1_000_000_000),
+                _ => return None,
+            },
+            Locale::Ru => match word {
+                "ноль" => Value(0),
+                "один" | "одна" | "одно" => Value(1),
+                "два" | "две" => Value(2),
+                "три" => Value(3),
+                "четыре" => Value(4),
+                "пять" => Value(5),
+                "шесть" => Value(6),
+                "семь" => Value(7),
+                "восемь" => Value(8),
+                "девять" => Value(9),
+                "десять" => Value(10),
+                "одиннадцать" => Value(11),
+                "двенадцать" => Value(12),
+                "тринадцать" => Value(13),
+                "четырнадцать" => Value(14),
+                "пятнадцать" => Value(15),
+                "шестнадцать" => Value(16),
+                "семнадцать" => Value(17),
+                "восемнадцать" => Value(18),
+                "девятнадцать" => Value(19),
+                "двадцать" => Value(20),
+                "тридцать" => Value(30),
+                "сорок" => Value(40),
+                "пятьдесят" => Value(50),
+                "шестьдесят" => Value(60),
+                "семьдесят" => Value(70),
+                "восемьдесят" => Value(80),
+                "девяносто" => Value(90),
+                "сто" => Hundreds(100),
+                "двести" => Hundreds(200),
+                "триста" => Hundreds(300),
+                "четыреста" => Hundreds(400),
+                "пятьсот" => Hundreds(500),
+                "шестьсот" => Hundreds(600),
+                "семьсот" => Hundreds(700),
+                "восемьсот" => Hundreds(800),
+                "девятьсот" => Hundreds(900),
+                "тысяча" | "тысячи" | "тысяч" => Scale(1_000),
+                "миллион" | "миллиона" | "миллионов" => Scale(1_000_000),
+                "миллиард" | "миллиарда" | "миллиардов" => Scale(1_000_000_000),
+                _ => return None,
+            },
+        };
+        Some(value)
+    }
+
+    /// Разбирает количественное числительное, начиная со слова w. Возвращает (значение, число слов).
+    fn parse_number(&self, words: &Words, w: usize) -> Option<(u64, usize)> {
+        let first = words.word(w)?;
+        if let Some(n) = parse_digits(first) {
+            return Some((n, 1));
+        }
+
+        let mut total = 0u64;
+        let mut current = 0u64;
+        let mut last: Option<NumberWord> = None;
+        let mut last_scale: Option<u64> = None;
+        let mut i = w;
+
+        while let Some(word) = words.word(i) {
+            if i > w && !words.joined(i - 1) {
+                break;
+            }
+
+            // "two hundred and five": "and" связывает только внутри числа
+            if self.locale == Locale::En && word == "and" {
+                let after_multiplier = matches!(last, Some(NumberWord::Hundred | NumberWord::Scale(_)));
+                let value_follows = words.joined(i)
+                    && matches!(
+                        words.word(i + 1).and_then(|next| self.number_word(next)),
+                        Some(NumberWord::Value(v)) if v > 0
+                    );
+                if after_multiplier && value_follows {
+                    i += 1;
+                    continue;
+                }
+                break;
+            }
+
+            let Some(kind) = self.number_word(word) else {
+                break;
+            };
+
+            let accepted = match kind {
+                NumberWord::Value(0) => last.is_none(),
+                NumberWord::Value(v) => match last {
+                    None | Some(NumberWord::Hundred | NumberWord::Hundreds(_) | NumberWord::Scale(_)) => true,
+                    Some(NumberWord::Value(t)) => (20..=90).contains(&t) && t % 10 == 0 && v < 10,
+                },
+                NumberWord::Hundred => match last {
+                    None => true,
+                    Some(NumberWord::Value(v)) => v > 0 && current < 100,
+                    _ => false,
+                },
+                NumberWord::Hundreds(_) => matches!(last, None | Some(NumberWord::Scale(_))),
+                NumberWord::Scale(s) => {
+                    !matches!(last, Some(NumberWord::Scale(_)) | Some(NumberWord::Value(0)))
+                        && last_scale.map_or(true, |ls| s < ls)
+                }
+            };
+            if !accepted {
+                break;
+            }
+
+            match kind {
+                NumberWord::Value(v) | NumberWord::Hundreds(v) => current += v,
+                NumberWord::Hundred => current = current.max(1) * 100,
+                NumberWord::Scale(s) => {
+                    total += current.max(1) * s;
+                    current = 0;
+                    last_scale = Some(s);
+                }
+            }
+            last = Some(kind);
+            i += 1;
+            if kind == NumberWord::Value(0) {
+                break;
+            }
+        }
+
+        if i == w {
+            return None;
+        }
+        Some((total + current, i - w))
+    }
+
+    fn simple_ordinal(&self, word: &str) -> Option<u64> {
+        match self.locale {
+            Locale::En => {
+                let value = match word {
+                    "first" => 1,
+                    "second" => 2,
+                    "third" => 3,
+                    "fourth" => 4,
+                    "fifth" => 5,
+                    "sixth" => 6,
+                    "seventh" => 7,
+                    "eighth" => 8,
+                    "ninth" => 9,
+                    "tenth" => 10,
+                    "eleventh" => 11,
+                    "twelfth" => 12,
+                    "thirteenth" => 13,
+                    "fourteenth" => 14,
+                    "fifteenth" => 15,
+                    "sixteenth" => 16,
+                    "seventeenth" => 17,
+                    "eighteenth" => 18,
+                    "nineteenth" => 19,
+                    "twentieth" => 20,
+                    "thirtieth" => 30,
+                    _ => {
+                        // "3rd", "21st"
+                        let digits_end = word.find(|c: char| !c.is_ascii_digit())?;
+                        let (digits, suffix) = word.split_at(digits_end);
+                        if !matches!(suffix, "st" | "nd" | "rd" | "th") {
+                            return None;
+                        }
+                        return parse_digits(digits);
+                    }
+                };
+                Some(value)
+            }
+            Locale::Ru => RU_ORDINAL_STEMS.iter().find_map(|(stem, value)| {
+                let ending = word.strip_prefix(stem)?;
+                RU_ORDINAL_ENDINGS.contains(&ending).then_some(*value)
+            }),
+        }
+    }
+
+    /// Порядковое числительное (день месяца): "third", "twenty first", "двадцать третьего"
+    fn parse_ordinal(&self, words: &Words, w: usize) -> Option<(u64, usize)> {
+        let word = words.word(w)?;
+        if let Some(value) = self.simple_ordinal(word) {
+            return Some((value, 1));
+        }
+
+        if let Some(NumberWord::Value(tens)) = self.number_word(word) {
+            if (tens == 20 || tens == 30) && words.joined(w) {
+                let unit = self.simple_ordinal(words.word(w + 1)?)?;
+                if (1..10).contains(&unit) {
+                    return Some((tens + unit, 2));
+                }
+            }
+        }
+        None
+    }
+
+    fn month(&self, word: &str) -> Option<usize> {
+        match self.locale {
+            Locale::En => EN_MONTHS.iter().position(|m| m.to_lowercase() == word),
+            Locale::Ru => RU_MONTHS_GENITIVE.iter().position(|m| *m == word),
+        }
+    }
+
+    fn match_date(&self, words: &Words, w: usize) -> Option<Replacement> {
+        match self.locale {
+            Locale::En => {
+                // "May third", "May the third"
+                if let Some(month) = self.month(words.word(w)?) {
+                    let is_ambiguous = EN_AMBIGUOUS_MONTHS.contains(&words.word(w)?);
+                    let capitalized = words.original(w).chars().next().is_some_and(|c| c.is_uppercase());
+                    if !is_ambiguous || capitalized {
+                        let mut i = w + 1;
+                        if words.word(i) == Some("the") {
+                            i += 1;
+                        }
+                        if let Some((day, consumed)) = self.parse_ordinal(words, i) {
+                            if (1..=31).contains(&day) {
+                                return Some(Replacement {
+                                    consumed: i + consumed - w,
+                                    text: format!("{} {}", EN_MONTHS[month], day),
+                                });
+                            }
+                        }
+                    }
+                }
+
+                // "the third of May", "third of May"
+                let start = if words.word(w) == Some("the") { w + 1 } else { w };
+                let (day, consumed) = self.parse_ordinal(words, start)?;
+                let of = start + consumed;
+                if words.word(of) != Some("of") || !(1..=31).contains(&day) {
+                    return None;
+                }
+                let month = self.month(words.word(of + 1)?)?;
+                Some(Replacement {
+                    consumed: of + 2 - w,
+                    text: format!("{} {}", EN_MONTHS[month], day),
+                })
+            }
+            Locale::Ru => {
+                // "третье мая", "двадцать первого июня"
+                let (day, consumed) = self.parse_ordinal(words, w)?;
+                if !(1..=31).contains(&day) {
+                    return None;
+                }
+                let month_idx = w + consumed;
+                self.month(words.word(month_idx)?)?;
+                Some(Replacement {
+                    consumed: consumed + 1,
+                    text: format!("{} {}", day, words.original(month_idx)),
+                })
+            }
+        }
+    }
+
+    fn currency(&self, word: &str) -> Option<Currency> {
+        match self.locale {
+            Locale::En => match word {
+                "dollar" | "dollars" | "buck" | "bucks" => Some(Currency::Dollar),
+                "euro" | "euros" => Some(Currency::Euro),
+                _ => None,
+            },
+            Locale::Ru => match word {
+                "рубль" | "рубля" | "рублей" => Some(Currency::Ruble),
+                "доллар" | "доллара" | "долларов" => Some(Currency::Dollar),
+                "евро" => Some(Currency::Euro),
+                _ => None,
+            },
+        }
+    }
+
+    fn is_minor_unit(&self, word: &str) -> bool {
+        match self.locale {
+            Locale::En => matches!(word, "cent" | "cents"),
+            Locale::Ru => matches!(
+                word,
+                "копейка" | "копейки" | "копеек" | "цент" | "цента" | "центов"
+            ),
+        }
+    }
+
+    /// "twenty five dollars and fifty cents" → "$25.50", "двадцать пять рублей" → "25 ₽"
+    fn match_currency(&self, words: &Words, w: usize) -> Option<Replacement> {
+        let (amount, consumed) = self.parse_number(words, w)?;
+        let currency_idx = w + consumed;
+        if !words.joined(currency_idx - 1) {
+            return None;
+        }
+        let currency = self.currency(words.word(currency_idx)?)?;
+        let mut end = currency_idx + 1;
+
+        // Необязательная дробная часть: "and fifty cents" / "и пятьдесят копеек"
+        let mut minor = None;
+        let connector = match self.locale {
+            Locale::En => "and",
+            Locale::Ru => "и",
+        };
+        let mut minor_start = end;
+        if words.word(minor_start) == Some(connector) {
+            minor_start += 1;
+        }
+        if let Some((cents, cents_consumed)) = self.parse_number(words, minor_start) {
+            let unit_idx = minor_start + cents_consumed;
+            if cents < 100
+                && words.joined(unit_idx - 1)
+                && words.word(unit_idx).is_some_and(|u| self.is_minor_unit(u))
+            {
+                minor = Some(cents);
+                end = unit_idx + 1;
+            }
+        }
+
+        let text = match (self.locale, minor) {
+            (Locale::En, Some(cents)) => format!("{}{}.{:02}", currency.symbol(), amount, cents),
+            (Locale::En, None) => format!("{}{}", currency.symbol(), amount),
+            (Locale::Ru, Some(cents)) => format!("{},{:02} {}", amount, cents, currency.symbol()),
+            (Locale::Ru, None) => format!("{} {}", amount, currency.symbol()),
+        };
+        Some(Replacement {
+            consumed: end - w,
+            text,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(language: &str, text: &str) -> String {
+        TextNormalizer::new(language, NormalizationRules::default())
+            .expect("language must be supported")
+            .normalize(text)
+    }
+
+    #[test]
+    fn unsupported_language_is_skipped() {
+        assert!(TextNormalizer::new("de", NormalizationRules::default()).is_none());
+        assert!(TextNormalizer::new("en-US", NormalizationRules::default()).is_some());
+    }
+
+    mod en {
+        use super::*;
+
+        #[test]
+        fn numbers() {
+            assert_eq!(normalize("en", "I need twenty five copies"), "I need 25 copies");
+            assert_eq!(normalize("en", "about one hundred and twenty three people"), "about 123 people");
+            assert_eq!(normalize("en", "two thousand and five"), "2005");
+            assert_eq!(normalize("en", "forty-two is the answer"), "42 is the answer");
+            assert_eq!(normalize("en", "Twelve items, fifteen boxes."), "12 items, 15 boxes.");
+        }
+
+        #[test]
+        fn small_standalone_numbers_stay_words() {
+            assert_eq!(normalize("en", "one of them has three cats"), "one of them has three cats");
+            assert_eq!(normalize("en", "ten and two"), "10 and two");
+        }
+
+        #[test]
+        fn currency() {
+            assert_eq!(normalize("en", "it costs twenty five dollars"), "it costs $25");
+            assert_eq!(
+                normalize("en", "pay five dollars and fifty cents today"),
+                "pay $5.50 today"
+            );
+            assert_eq!(normalize("en", "about 300 euros"), "about €300");
+        }
+
+        #[test]
+        fn dates() {
+            assert_eq!(normalize("en", "see you on May third"), "see you on May 3");
+            assert_eq!(normalize("en", "on the twenty first of june"), "on June 21");
+            assert_eq!(normalize("en", "december the 2nd"), "December 2");
+            // "may" как глагол не трогаем
+            assert_eq!(normalize("en", "you may second that"), "you may second that");
+        }
+
+        #[test]
+        fn rules_can_be_disabled() {
+            let rules = NormalizationRules {
+                currency: false,
+                ..NormalizationRules::default()
+            };
+            let normalizer = TextNormalizer::new("en", rules).unwrap();
+            assert_eq!(normalizer.normalize("twenty five dollars"), "25 dollars");
+        }
+    }
+
+    mod ru {
+        use super::*;
+
+        #[test]
+        fn numbers() {
+            assert_eq!(normalize("ru", "нужно двадцать пять копий"), "нужно 25 копий");
+            assert_eq!(normalize("ru", "сто двадцать три человека"), "123 человека");
+            assert_eq!(normalize("ru", "две тысячи двадцать пять"), "2025");
+            assert_eq!(normalize("ru", "Сорок минут, пятнадцать секунд."), "40 минут, 15 секунд.");
+        }
+
+        #[test]
+        fn small_standalone_numbers_stay_words() {
+            assert_eq!(normalize("ru", "один из трёх вариантов за три дня"), "один из трёх вариантов за три дня");
+        }
+
+        #[test]
+        fn currency() {
+            assert_eq!(normalize("ru", "стоит двадцать пять долларов"), "стоит 25 $");
+            assert_eq!(normalize("ru", "пятьсот рублей и десять копеек"), "500,10 ₽");
+            assert_eq!(normalize("ru", "пять евро"), "5 €");
+        }
+
+        #[test]
+        fn dates() {
+            assert_eq!(normalize("ru", "встреча третьего мая"), "встреча 3 мая");
+            assert_eq!(normalize("ru", "Третье мая"), "3 мая");
+            assert_eq!(normalize("ru", "до двадцать первого июня"), "до 21 июня");
+            assert_eq!(normalize("ru", "пятнадцатое января"), "15 января");
+            // Порядковое без месяца не трогаем
+            assert_eq!(normalize("ru", "третье место"), "третье место");
+        }
+    }
+}
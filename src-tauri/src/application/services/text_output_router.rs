@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::domain::{
+    SinkDeliveryOutcome, TextDelivery, TextOutputRouter, TextOutputSink, TextOutputSinkConfig,
+};
+
+/// Роутер финального текста: раздаёт текст по sinks активного профиля.
+///
+/// Порядок sinks сохраняется (auto-paste до clipboard, чтобы вставка не зависела от содержимого буфера).
+/// Ошибка одного sink не мешает остальным. Для auto-paste с `fallback_to_clipboard` при ошибке
+/// фраза копируется в clipboard, если его нет среди sinks — так пользователь не теряет текст.
+pub struct SinkTextOutputRouter {
+    sinks: Vec<Arc<dyn TextOutputSink>>,
+    clipboard_fallback: Option<Arc<dyn TextOutputSink>>,
+}
+
+impl SinkTextOutputRouter {
+    pub fn new(sinks: Vec<Arc<dyn TextOutputSink>>) -> Self {
+        Self {
+            sinks,
+            clipboard_fallback: None,
+        }
+    }
+
+    /// Sink, который используется как фоллбек при неудачной вставке
+    pub fn with_clipboard_fallback(mut self, sink: Arc<dyn TextOutputSink>) -> Self {
+        self.clipboard_fallback = Some(sink);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    fn has_clipboard_sink(&self) -> bool {
+        self.sinks
+            .iter()
+            .any(|s| matches!(s.config(), TextOutputSinkConfig::Clipboard { .. }))
+    }
+}
+
+#[async_trait]
+impl TextOutputRouter for SinkTextOutputRouter {
+    async fn route(&self, delivery: &TextDelivery) -> Vec<SinkDeliveryOutcome> {
+        let mut outcomes = Vec::with_capacity(self.sinks.len());
+        if delivery.text.trim().is_empty() && delivery.full_text.trim().is_empty() {
+            return outcomes;
+        }
+
+        for sink in &self.sinks {
+            let result = sink.deliver(delivery).await;
+            let error = match result {
+                Ok(()) => None,
+                Err(e) => {
+                    log::warn!("Text output sink {:?} failed: {}", sink.config(), e);
+
+                    let wants_fallback = matches!(
+                        sink.config(),
+                        TextOutputSinkConfig::AutoPaste {
+                            fallback_to_clipboard: true
+                        }
+                    );
+                    if wants_fallback && !self.has_clipboard_sink() {
+                        if let Some(fallback) = self.clipboard_fallback.as_ref() {
+                            // Копируем именно фразу, которую не удалось вставить
+                            let fallback_delivery = TextDelivery {
+                                full_text: delivery.text.trim_start().to_string(),
+                                ..delivery.clone()
+                            };
+                            if let Err(e) = fallback.deliver(&fallback_delivery).await {
+                                log::warn!("Clipboard fallback failed: {}", e);
+                            }
+                        }
+                    }
+
+                    Some(e.to_string())
+                }
+            };
+
+            outcomes.push(SinkDeliveryOutcome {
+                sink: sink.config().clone(),
+                error,
+            });
+        }
+
+        outcomes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{TextOutputError, TextOutputResult};
+    use std::sync::Mutex;
+
+    struct RecordingSink {
+        config: TextOutputSinkConfig,
+        fail: bool,
+        received: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl RecordingSink {
+        fn new(config: TextOutputSinkConfig, fail: bool, received: Arc<Mutex<Vec<String>>>) -> Arc<Self> {
+            Arc::new(Self {
+                config,
+                fail,
+                received,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl TextOutputSink for RecordingSink {
+        fn config(&self) -> &TextOutputSinkConfig {
+            &self.config
+        }
+
+        async fn deliver(&self, delivery: &TextDelivery) -> TextOutputResult<()> {
+            if self.fail {
+                return Err(TextOutputError::PermissionDenied("no accessibility".to_string()));
+            }
+            let value = match self.config {
+                TextOutputSinkConfig::Clipboard { .. } => delivery.full_text.clone(),
+                _ => delivery.text.clone(),
+            };
+            self.received.lock().unwrap().push(value);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_to_all_sinks_in_order() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let router = SinkTextOutputRouter::new(vec![
+            RecordingSink::new(TextOutputSinkConfig::Typing, false, received.clone()),
+            RecordingSink::new(TextOutputSinkConfig::plain_clipboard(), false, received.clone()),
+        ]);
+
+        let outcomes = router
+            .route(&TextDelivery::new(" мир").with_full_text("привет мир"))
+            .await;
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|o| o.error.is_none()));
+        assert_eq!(*received.lock().unwrap(), vec![" мир".to_string(), "привет мир".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn failed_paste_falls_back_to_clipboard() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let clipboard = RecordingSink::new(TextOutputSinkConfig::plain_clipboard(), false, received.clone());
+        let router = SinkTextOutputRouter::new(vec![RecordingSink::new(
+            TextOutputSinkConfig::AutoPaste {
+                fallback_to_clipboard: true,
+            },
+            true,
+            received.clone(),
+        )])
+        .with_clipboard_fallback(clipboard);
+
+        let outcomes = router
+            .route(&TextDelivery::new(" мир").with_full_text("привет мир"))
+            .await;
+
+        assert_eq!(outcomes.len(), 1);
+        assert!(outcomes[0].error.as_deref().unwrap().contains("Permission denied"));
+        assert_eq!(*received.lock().unwrap(), vec!["мир".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn empty_text_is_not_delivered() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let router = SinkTextOutputRouter::new(vec![RecordingSink::new(
+            TextOutputSinkConfig::plain_clipboard(),
+            false,
+            received.clone(),
+        )]);
+
+        assert!(router.route(&TextDelivery::new("  ")).await.is_empty());
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
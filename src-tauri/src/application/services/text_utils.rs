@@ -0,0 +1,21 @@
+//! Общие операции над словами фразы для сервисов постобработки текста
+
+/// Слово без знаков по краям, в нижнем регистре
+pub fn bare_word(token: &str) -> String {
+    token
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_word_strips_edge_punctuation_and_lowercases() {
+        assert_eq!(bare_word("«Эээ,"), "эээ");
+        assert_eq!(bare_word("(Um)..."), "um");
+        assert_eq!(bare_word("e-mail!"), "e-mail");
+        assert_eq!(bare_word("—"), "");
+    }
+}
@@ -0,0 +1,309 @@
+use crate::domain::{capitalize_first, smart_append_text, Transcription};
+
+/// Continues sentences across finalized segments.
+///
+/// Провайдер (Deepgram) финализирует сегмент по паузе, а не по концу предложения: следующий сегмент
+/// начинается с заглавной посреди фразы ("мы пошли, И купили") или со строчной после точки,
+/// а знаки препинания приходят с лишним пробелом ("привет , как дела"). Сборщик помнит уже
+/// выданный текст сессии и приводит каждый новый сегмент к продолжению: регистр первого слова
+/// по концу предыдущего, пробелы вокруг знаков. Уже выданный текст не меняется.
+///
+/// После долгой паузы между сегментами (по таймингам провайдера) сегмент начинает новый абзац:
+/// выданный текст начинается с `PARAGRAPH_BREAK`.
+#[derive(Debug, Default)]
+pub struct TranscriptAssembler {
+    /// Финализированные сегменты сессии, склеенные в текст
+    text: String,
+    /// Пауза, после которой сегмент начинает абзац, в секундах (None — абзацы выключены)
+    paragraph_pause_secs: Option<f64>,
+    /// Конец последнего сегмента с таймингами, в секундах от начала стрима
+    last_end_secs: Option<f64>,
+}
+
+/// Разделитель абзацев в собранном тексте
+pub const PARAGRAPH_BREAK: &str = "\n\n";
+
+/// Чем закончился уже собранный текст
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Boundary {
+    /// Начало сессии: первый сегмент не трогаем
+    Start,
+    /// Конец предложения (. ! ? …) — следующий сегмент с заглавной
+    SentenceEnd,
+    /// Предложение продолжается (слово, запятая) — служебное слово со строчной
+    Continuation,
+    /// Сокращение, двоеточие, тире — регистр не угадать
+    Unknown,
+}
+
+/// Частые служебные слова: с заглавной посреди фразы они бывают только из-за границы сегмента.
+/// Остальные слова не трогаем — это могут быть имена и названия
+const CONTINUATION_WORDS: &[&str] = &[
+    // en
+    "a", "about", "also", "an", "and", "are", "as", "at", "because", "but", "by", "for", "from", "if", "in", "into",
+    "is", "it", "of", "on", "or", "so", "than", "that", "the", "then", "to", "was", "were", "which", "while",
+    "who", "with",
+    // ru
+    "а", "без", "будто", "в", "во", "где", "да", "для", "до", "если", "ещё", "же", "за", "и", "из", "или", "как",
+    "когда", "которая", "которые", "который", "ли", "на", "но", "о", "об", "от", "по", "потому", "при", "про", "с",
+    "со", "так", "также", "то", "тоже", "у", "уже", "чем", "что", "чтобы",
+];
+
+/// Сокращения с точкой, после которых предложение обычно продолжается
+const ABBREVIATIONS: &[&str] = &[
+    "mr.", "mrs.", "ms.", "dr.", "prof.", "vs.", "e.g.", "i.e.", "approx.", "т.е.", "т.к.", "т.н.", "напр.", "г.",
+    "ул.", "им.", "см.", "стр.", "руб.", "тыс.", "млн.", "млрд.",
+];
+
+/// Знаки, которые пишутся слитно с предыдущим словом
+const CLOSING_PUNCTUATION: &[char] = &['.', ',', '!', '?', ';', ':', '…', ')', ']', '}', '»'];
+
+impl TranscriptAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Абзац после паузы не короче `pause_ms` (0 — выключено)
+    pub fn with_paragraph_pause(mut self, pause_ms: u32) -> Self {
+        self.paragraph_pause_secs = (pause_ms > 0).then(|| f64::from(pause_ms) / 1000.0);
+        self
+    }
+
+    /// Текст сессии из финализированных сегментов
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Сегмент, приведённый к продолжению собранного текста (для промежуточных partial'ов, без запоминания)
+    pub fn continue_text(&self, segment: &str) -> String {
+        let mut text = tidy_spacing(segment);
+        if text.is_empty() {
+            return text;
+        }
+
+        // Провайдер повторил на стыке знак, которым закончился прошлый сегмент: "дела." + ". Потом"
+        let last = self.text.chars().last();
+        if let (Some(last), Some(first)) = (last, text.chars().next()) {
+            if first == last && matches!(first, '.' | ',' | '!' | '?' | ';' | ':' | '…') {
+                text = text[first.len_utf8()..].trim_start().to_string();
+            }
+        }
+
+        match boundary(&self.text) {
+            Boundary::SentenceEnd => capitalize_first(&text),
+            Boundary::Continuation => lowercase_continuation_word(&text),
+            Boundary::Start | Boundary::Unknown => text,
+        }
+    }
+
+    /// Финализированный сегмент: приводит к продолжению и дописывает к собранному тексту
+    pub fn commit(&mut self, segment: &str) -> String {
+        let text = self.continue_text(segment);
+        if !text.is_empty() {
+            let appended = smart_append_text(self.text.chars().last(), &text);
+            self.text.push_str(&appended);
+        }
+        text
+    }
+
+    /// Финализированный текст, который нельзя менять (продиктован по буквам): только дописывает
+    pub fn commit_verbatim(&mut self, text: &str) -> String {
+        let text = text.trim();
+        if !text.is_empty() {
+            let appended = smart_append_text(self.text.chars().last(), text);
+            self.text.push_str(&appended);
+        }
+        text.to_string()
+    }
+
+    /// Финализированный сегмент с таймингами провайдера: после долгой паузы — с нового абзаца
+    pub fn commit_segment(&mut self, transcription: &Transcription) -> String {
+        let paragraph = self.starts_paragraph(transcription);
+        let text = if paragraph {
+            // Новый абзац — всегда новое предложение
+            let text = capitalize_first(&tidy_spacing(&transcription.text));
+            if !text.is_empty() {
+                self.text.push_str(PARAGRAPH_BREAK);
+                self.text.push_str(&text);
+            }
+            text
+        } else {
+            self.commit(&transcription.text)
+        };
+        if text.is_empty() {
+            return text;
+        }
+
+        if transcription.duration > 0.0 {
+            self.last_end_secs = Some(transcription.start + transcription.duration);
+        }
+        if paragraph {
+            format!("{}{}", PARAGRAPH_BREAK, text)
+        } else {
+            text
+        }
+    }
+
+    /// Сегменты без таймингов (локальный Whisper) и сегменты после переподключения
+    /// (тайминги начались заново) абзац не начинают
+    fn starts_paragraph(&self, transcription: &Transcription) -> bool {
+        let (Some(pause), Some(last_end)) = (self.paragraph_pause_secs, self.last_end_secs) else {
+            return false;
+        };
+        !self.text.is_empty() && transcription.duration > 0.0 && transcription.start - last_end >= pause
+    }
+}
+
+fn boundary(text: &str) -> Boundary {
+    let text = text.trim_end();
+    // Закрывающие кавычки и скобки не меняют границу: «Готово.» — конец предложения
+    let Some(last) = text
+        .trim_end_matches(['"', '\'', '»', '”', ')', ']'])
+        .chars()
+        .last()
+    else {
+        return Boundary::Start;
+    };
+
+    match last {
+        '.' => {
+            let last_word = text.rsplit(char::is_whitespace).next().unwrap_or("").to_lowercase();
+            if ABBREVIATIONS.contains(&last_word.as_str()) {
+                Boundary::Unknown
+            } else {
+                Boundary::SentenceEnd
+            }
+        }
+        '!' | '?' | '…' => Boundary::SentenceEnd,
+        ',' | ';' => Boundary::Continuation,
+        c if c.is_alphanumeric() => Boundary::Continuation,
+        _ => Boundary::Unknown,
+    }
+}
+
+/// Один пробел между словами, без пробела перед знаками препинания и после открывающих скобок
+fn tidy_spacing(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for word in segment.split_whitespace() {
+        let attaches = word.starts_with(CLOSING_PUNCTUATION);
+        let after_opening = out.ends_with(['(', '[', '«']);
+        if !out.is_empty() && !attaches && !after_opening {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+/// "И купили" → "и купили"; имена ("Москва") и аббревиатуры ("NASA") остаются как есть
+fn lowercase_continuation_word(text: &str) -> String {
+    let word_len = text
+        .find(|c: char| !c.is_alphanumeric())
+        .unwrap_or(text.len());
+    let word = &text[..word_len];
+    let lowered = word.to_lowercase();
+    let capitalized = word.chars().next().is_some_and(char::is_uppercase)
+        && word.chars().skip(1).all(|c| !c.is_uppercase());
+    if !capitalized || !CONTINUATION_WORDS.contains(&lowered.as_str()) {
+        return text.to_string();
+    }
+    format!("{}{}", lowered, &text[word_len..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assemble(segments: &[&str]) -> String {
+        let mut assembler = TranscriptAssembler::new();
+        for segment in segments {
+            assembler.commit(segment);
+        }
+        assembler.text().to_string()
+    }
+
+    #[test]
+    fn continues_english_sentences_across_segments() {
+        assert_eq!(
+            assemble(&["We went to the store", "And bought some milk.", "then we drove home"]),
+            "We went to the store and bought some milk. Then we drove home"
+        );
+        // Имена посреди фразы не трогаем
+        assert_eq!(assemble(&["I talked to", "John yesterday"]), "I talked to John yesterday");
+        // После сокращения регистр не угадать
+        assert_eq!(assemble(&["Ask Dr.", "Smith about it"]), "Ask Dr. Smith about it");
+    }
+
+    #[test]
+    fn continues_russian_sentences_across_segments() {
+        assert_eq!(
+            assemble(&["Когда в админке её запускаешь,", "То индексация идёт долго!", "потом всё работает"]),
+            "Когда в админке её запускаешь, то индексация идёт долго! Потом всё работает"
+        );
+        assert_eq!(assemble(&["Мы поехали", "В Москву"]), "Мы поехали в Москву");
+        assert_eq!(assemble(&["Встреча в", "Москве"]), "Встреча в Москве");
+        // «Готово.» — конец предложения, хоть и в кавычках
+        assert_eq!(assemble(&["Он сказал «готово.»", "и ушёл"]), "Он сказал «готово.» И ушёл");
+    }
+
+    #[test]
+    fn fixes_spacing_around_punctuation() {
+        assert_eq!(assemble(&["привет", " , как   дела ?"]), "привет, как дела?");
+        assert_eq!(assemble(&["Hello.", ". How are you"]), "Hello. How are you");
+        assert_eq!(assemble(&["see the notes ( page two )"]), "see the notes (page two)");
+    }
+
+    #[test]
+    fn first_segment_and_acronyms_are_kept() {
+        assert_eq!(assemble(&["hello there"]), "hello there");
+        assert_eq!(assemble(&["we asked", "NASA about it"]), "we asked NASA about it");
+    }
+
+    fn timed(text: &str, start: f64, duration: f64) -> Transcription {
+        let mut transcription = Transcription::final_result(text.to_string());
+        transcription.start = start;
+        transcription.duration = duration;
+        transcription
+    }
+
+    #[test]
+    fn long_pause_starts_a_paragraph() {
+        let mut assembler = TranscriptAssembler::new().with_paragraph_pause(2000);
+        assert_eq!(assembler.commit_segment(&timed("Первый абзац,", 0.0, 1.5)), "Первый абзац,");
+        assert_eq!(assembler.commit_segment(&timed("И продолжение.", 2.0, 1.0)), "и продолжение.");
+        assert_eq!(assembler.commit_segment(&timed("второй абзац", 5.5, 1.0)), "\n\nВторой абзац");
+        // Тайминги начались заново (переподключение) — абзац не угадать
+        assert_eq!(assembler.commit_segment(&timed("и дальше", 0.2, 1.0)), "и дальше");
+        assert_eq!(
+            assembler.text(),
+            "Первый абзац, и продолжение.\n\nВторой абзац и дальше"
+        );
+
+        // Без порога и без таймингов абзацев нет
+        let mut assembler = TranscriptAssembler::new();
+        assembler.commit_segment(&timed("One.", 0.0, 1.0));
+        assert_eq!(assembler.commit_segment(&timed("Two.", 30.0, 1.0)), "Two.");
+        let mut assembler = TranscriptAssembler::new().with_paragraph_pause(2000);
+        assembler.commit_segment(&timed("One.", 0.0, 0.0));
+        assert_eq!(assembler.commit_segment(&timed("Two.", 0.0, 0.0)), "Two.");
+    }
+
+    #[test]
+    fn interim_text_does_not_advance_the_assembler() {
+        let mut assembler = TranscriptAssembler::new();
+        assembler.commit("Мы пошли");
+        assert_eq!(assembler.continue_text("И купи"), "и купи");
+        assert_eq!(assembler.commit("И купили хлеб."), "и купили хлеб.");
+        assert_eq!(assembler.continue_text("потом"), "Потом");
+        assert_eq!(assembler.text(), "Мы пошли и купили хлеб.");
+    }
+
+    #[test]
+    fn verbatim_text_keeps_its_case() {
+        let mut assembler = TranscriptAssembler::new();
+        assembler.commit("Пишите на");
+        assert_eq!(assembler.commit_verbatim("john.doe@example.com"), "john.doe@example.com");
+        assert_eq!(assembler.commit("И ответим."), "и ответим.");
+        assert_eq!(assembler.text(), "Пишите на john.doe@example.com и ответим.");
+    }
+}
@@ -1,18 +1,125 @@
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use tokio::sync::RwLock;
 use tokio::time::{Duration, Instant};
 
 use crate::domain::{
-    AudioCapture, AudioConfig, AudioLevelCallback, AudioSpectrumCallback, ConnectionQualityCallback,
-    ErrorCallback, RecordingStatus, SttConfig, SttError, SttProvider,
-    SttProviderFactory, SttProviderType, TranscriptionCallback,
+    AudioCapture, AudioChunk, AudioConfig, AudioLevelCallback, AudioSpectrumCallback, AudioSpool, BatchSttProvider,
+    ConnectionQualityCallback, ConnectionQualityReason, ErrorCallback, MemoryPoolUsage, Punctuator, RecordingStatus, SessionJournal, SttConfig,
+    SpooledAudio, SttError, SttProvider, SttProviderDebugState, SttProviderFactory, SttProviderType, SttSessionOverride, Transcription,
+    TranscriptionCallback, MAX_PRE_ROLL_MS,
 };
 
-use crate::application::AudioSpectrumAnalyzer;
+use crate::application::{
+    apply_gain, audio_pool, chunk_rms, limited_gain, sensitivity_gain, AudioBacklogMonitor, AudioSpectrumAnalyzer,
+    BackpressurePolicy, ComparisonListener, CorrectionEngine, EnsembleListener, FillerRemover, LatencyTracker, PartialStabilizer, PipelineStage, PipelineTracer, PreRollBuffer, ProviderComparison, SessionDiagnostics,
+    SessionStatsTracker, SessionTimelineRecorder, SpellingMode, SpellingModeListener, StreamWatchdog, TextNormalizer, TranscriptAssembler,
+    TranscriptionDebugState, VoiceCommandListener, VoiceCommandSpotter, MAX_STREAM_RESTARTS, SPEECH_RMS_THRESHOLD,
+};
 
 type Result<T> = anyhow::Result<T>;
 
+/// Защитный минимум для таймеров простоя (keep-alive TTL / энергосбережение)
+const MIN_IDLE_TTL_SECS: u64 = 10;
+
+/// Как часто сообщать о соединении, которое держится открытым без записи
+const IDLE_REPORT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Connection held open between recordings (keep-alive pause, prewarm)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdleConnectionReport {
+    pub provider: SttProviderType,
+    /// Сколько соединение уже открыто без записи
+    pub held_secs: u64,
+    /// Через сколько секунд простоя соединение закрывается
+    pub limit_secs: u64,
+    /// Провайдер тарифицирует время соединения (см. `IdleBillingPolicy`)
+    pub billed_while_idle: bool,
+    /// Соединение закрыто по таймеру простоя
+    pub closed: bool,
+}
+
+pub type IdleConnectionListener = Arc<dyn Fn(IdleConnectionReport) + Send + Sync>;
+
+/// Аудио короткой записи, которое копится в памяти вместо стрима (см. `SttConfig::short_clip_secs`).
+/// Если пользователь остановил запись до порога — весь буфер уходит одним REST запросом.
+struct ShortClipBuffer {
+    batch: Arc<dyn BatchSttProvider>,
+    samples: Vec<i16>,
+    sample_rate: u32,
+    channels: u16,
+    max_samples: usize,
+    on_final: TranscriptionCallback,
+    on_error: ErrorCallback,
+}
+
+/// Аудио, которое держит обработчик чанков (для отчёта о памяти)
+#[derive(Debug, Default)]
+struct ProcessorAudioGauge {
+    pre_roll_samples: AtomicUsize,
+    queued_samples: AtomicUsize,
+}
+
+/// Обнуляет счётчики, когда обработчик завершился или прерван (abort роняет future вместе с ним)
+struct ProcessorAudioGaugeReset(Arc<ProcessorAudioGauge>);
+
+impl Drop for ProcessorAudioGaugeReset {
+    fn drop(&mut self) {
+        self.0.pre_roll_samples.store(0, Ordering::Relaxed);
+        self.0.queued_samples.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Аудио сессии, которое не дошло до провайдера (см. `AudioSpool`).
+/// Разрыв распознаётся отдельно: когда отправка снова прошла, при остановке записи или когда запись оборвалась ошибкой.
+#[derive(Clone)]
+struct SpooledGapRecovery {
+    spool: Arc<dyn AudioSpool>,
+    factory: Arc<dyn SttProviderFactory>,
+    config: SttConfig,
+    offline_fallback: Arc<std::sync::Mutex<Option<SttSessionOverride>>>,
+    on_final: TranscriptionCallback,
+}
+
+impl SpooledGapRecovery {
+    fn has_pending(&self) -> bool {
+        self.spool.pending_samples() > 0
+    }
+
+    /// Распознаёт разрыв провайдером сессии, а если не вышло — офлайн провайдером; текст уходит финальной фразой
+    async fn recover(&self) {
+        let Some(gap) = self.spool.take() else {
+            return;
+        };
+        let duration = gap.samples.len() as f64 / gap.sample_rate.max(1) as f64;
+
+        let mut configs = vec![self.config.clone()];
+        let fallback = self.offline_fallback.lock().ok().and_then(|f| f.clone());
+        if let Some(fallback) = fallback.filter(|_| self.config.provider.requires_network()) {
+            let mut config = self.config.clone();
+            fallback.apply_to(&mut config);
+            configs.push(config);
+        }
+
+        for config in &configs {
+            log::info!("Transcribing {:.1}s of unsent audio via {:?}", duration, config.provider);
+            match transcribe_gap(self.factory.as_ref(), config, &gap).await {
+                Ok(text) => {
+                    let text = text.trim();
+                    if !text.is_empty() {
+                        let mut transcription = Transcription::final_result(text.to_string());
+                        transcription.duration = duration;
+                        (self.on_final)(transcription);
+                    }
+                    return;
+                }
+                Err(e) => log::warn!("Failed to transcribe unsent audio via {:?}: {:#}", config.provider, e),
+            }
+        }
+        log::error!("Unsent audio ({:.1}s) could not be transcribed and is lost", duration);
+    }
+}
+
 /// Main application service that orchestrates transcription workflow
 ///
 /// This service follows the Dependency Inversion Principle by depending on
@@ -26,6 +133,29 @@ pub struct TranscriptionService {
     microphone_sensitivity: Arc<RwLock<u8>>, // 0-200, default 100
     inactivity_timer_task: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>, // таймер для автоочистки соединения
     audio_processor_task: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>, // обработчик аудио-чанков → STT
+    latency: Arc<LatencyTracker>, // метрики задержек audio sent → partial/final
+    pipeline: Arc<PipelineTracer>, // время по этапам capture → VAD → gain → provider → callback
+    processor_audio: Arc<ProcessorAudioGauge>, // pre-roll и очередь отправки обработчика (get_memory_report)
+    session_stats: Arc<SessionStatsTracker>, // WPM, слова, доля речи за сессию
+    session_timeline: Arc<SessionTimelineRecorder>, // фразы сессии со временем и смещениями в аудио (export_session_timeline)
+    session_journal: Option<Arc<dyn SessionJournal>>, // журнал для восстановления после краша
+    audio_spool: Option<Arc<dyn AudioSpool>>, // недосланное провайдеру аудио на диске (разрывы сети)
+    gap_recovery: Arc<std::sync::Mutex<Option<SpooledGapRecovery>>>, // распознавание недосланного аудио текущей сессии
+    backpressure: BackpressurePolicy, // ограничение отставания отправки аудио
+    corrections: Option<Arc<CorrectionEngine>>, // словарь исправлений пользователя
+    team_glossary: Arc<std::sync::Mutex<Vec<String>>>, // общий словарь команды (синхронизируется с бэкенда)
+    punctuator: Option<Arc<dyn Punctuator>>, // локальная пунктуация (stt.punctuate_locally)
+    next_session_override: Arc<RwLock<Option<SttSessionOverride>>>, // провайдер/язык только для следующей сессии (deep link, профиль записи)
+    offline_fallback: Arc<std::sync::Mutex<Option<SttSessionOverride>>>, // локальный провайдер, пока нет сети
+    connection_overridden: Arc<AtomicBool>, // keep-alive соединение открыто не с сохранёнными настройками
+    short_clip: Arc<std::sync::Mutex<Option<ShortClipBuffer>>>, // короткая запись до открытия стрима
+    idle_listener: Arc<std::sync::Mutex<Option<IdleConnectionListener>>>, // отчёты о соединении, открытом без записи
+    diagnostics: Arc<SessionDiagnostics>, // отправлено за сессию и последняя ошибка (get_debug_state)
+    spelling: Arc<SpellingMode>, // диктовка по буквам (голосовая команда или хоткей)
+    voice_command_listener: Arc<std::sync::Mutex<Option<VoiceCommandListener>>>, // голосовые команды (stt.voice_commands)
+    comparison_stream: Arc<RwLock<Option<Box<dyn SttProvider>>>>, // второй провайдер A/B сравнения (stt.comparison_provider)
+    comparison_listener: Arc<std::sync::Mutex<Option<ComparisonListener>>>, // тексты обоих провайдеров A/B сравнения
+    ensemble_listener: Arc<std::sync::Mutex<Option<EnsembleListener>>>, // слитый текст A/B сравнения (feature "ensemble")
 }
 
 impl TranscriptionService {
@@ -42,9 +172,174 @@ impl TranscriptionService {
             microphone_sensitivity: Arc::new(RwLock::new(100)), // Default 100% (без усиления)
             inactivity_timer_task: Arc::new(RwLock::new(None)),
             audio_processor_task: Arc::new(RwLock::new(None)),
+            latency: Arc::new(LatencyTracker::new()),
+            pipeline: Arc::new(PipelineTracer::new()),
+            processor_audio: Arc::new(ProcessorAudioGauge::default()),
+            session_stats: Arc::new(SessionStatsTracker::new()),
+            session_timeline: Arc::new(SessionTimelineRecorder::new()),
+            session_journal: None,
+            audio_spool: None,
+            gap_recovery: Arc::new(std::sync::Mutex::new(None)),
+            backpressure: BackpressurePolicy::default(),
+            corrections: None,
+            team_glossary: Arc::new(std::sync::Mutex::new(Vec::new())),
+            punctuator: None,
+            next_session_override: Arc::new(RwLock::new(None)),
+            offline_fallback: Arc::new(std::sync::Mutex::new(None)),
+            connection_overridden: Arc::new(AtomicBool::new(false)),
+            short_clip: Arc::new(std::sync::Mutex::new(None)),
+            idle_listener: Arc::new(std::sync::Mutex::new(None)),
+            diagnostics: Arc::new(SessionDiagnostics::new()),
+            spelling: Arc::new(SpellingMode::new()),
+            voice_command_listener: Arc::new(std::sync::Mutex::new(None)),
+            comparison_stream: Arc::new(RwLock::new(None)),
+            comparison_listener: Arc::new(std::sync::Mutex::new(None)),
+            ensemble_listener: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    /// Подключает журнал сессии (crash-safe восстановление незавершённой записи)
+    pub fn with_session_journal(mut self, journal: Arc<dyn SessionJournal>) -> Self {
+        self.session_journal = Some(journal);
+        self
+    }
+
+    /// Подключает write-ahead spool: аудио, которое не удалось отправить, распознаётся после восстановления связи
+    pub fn with_audio_spool(mut self, spool: Arc<dyn AudioSpool>) -> Self {
+        self.audio_spool = Some(spool);
+        self
+    }
+
+    /// Задаёт политику backpressure (сколько неотправленного аудио держим в очереди)
+    pub fn with_backpressure_policy(mut self, policy: BackpressurePolicy) -> Self {
+        self.backpressure = policy;
+        self
+    }
+
+    /// Подключает словарь исправлений: применяется к финальным фразам и дополняет keyterms
+    pub fn with_correction_engine(mut self, engine: Arc<CorrectionEngine>) -> Self {
+        self.corrections = Some(engine);
+        self
+    }
+
+    /// Общий трассировщик конвейера: этапы вне сервиса (VAD в потоке захвата) пишут в него же
+    pub fn with_pipeline_tracer(mut self, tracer: Arc<PipelineTracer>) -> Self {
+        self.pipeline = tracer;
+        self
+    }
+
+    /// Подключает локальную пунктуацию; применяется к финальным фразам при `punctuate_locally`
+    pub fn with_punctuator(mut self, punctuator: Arc<dyn Punctuator>) -> Self {
+        self.punctuator = Some(punctuator);
+        self
+    }
+
+    /// Словарь исправлений (если подключён)
+    pub fn correction_engine(&self) -> Option<Arc<CorrectionEngine>> {
+        self.corrections.clone()
+    }
+
+    /// Термины общего словаря команды; попадают в keyterms со следующего соединения
+    pub fn set_team_glossary(&self, terms: Vec<String>) {
+        if let Ok(mut glossary) = self.team_glossary.lock() {
+            *glossary = terms;
+        }
+    }
+
+    /// Конфигурация для нового соединения: сохранённая + исправленные термины и словарь команды в keyterms
+    async fn session_config(&self) -> SttConfig {
+        let mut config = self.config.read().await.clone();
+        if let Some(engine) = self.corrections.as_ref() {
+            config.merge_keyterms(&engine.keyterms());
+        }
+        if let Ok(glossary) = self.team_glossary.lock() {
+            config.merge_keyterms(&glossary);
+        }
+        config
+    }
+
+    /// Провайдер/язык/модель только для следующей записи (без изменения сохранённого конфига).
+    /// Используется deep link'ом voicetotext://record?lang=.. и хоткеями профилей записи
+    pub async fn set_next_session_override(&self, session_override: Option<SttSessionOverride>) {
+        *self.next_session_override.write().await = session_override.filter(|o| !o.is_empty());
+    }
+
+    /// Провайдер для записей, пока нет сети (обычно локальный Whisper); None — сеть есть
+    /// или записывать без сети нечем. Подменяет только провайдеры, которым нужна сеть
+    pub fn set_offline_fallback(&self, fallback: Option<SttSessionOverride>) {
+        if let Ok(mut offline_fallback) = self.offline_fallback.lock() {
+            *offline_fallback = fallback.filter(|f| !f.is_empty());
         }
     }
 
+    /// Трекер задержек транскрипции (для диагностики)
+    pub fn latency_tracker(&self) -> Arc<LatencyTracker> {
+        self.latency.clone()
+    }
+
+    /// Трассировка конвейера по этапам (для диагностики)
+    pub fn pipeline_tracer(&self) -> Arc<PipelineTracer> {
+        self.pipeline.clone()
+    }
+
+    /// Аудио в памяти записи: очередь отправки, pre-roll и короткая запись до открытия стрима
+    pub fn audio_buffer_usage(&self) -> Vec<MemoryPoolUsage> {
+        let short_clip = self
+            .short_clip
+            .lock()
+            .map(|clip| clip.as_ref().map_or(0, |clip| clip.samples.len()))
+            .unwrap_or(0);
+        vec![
+            audio_pool("recording.send_queue", self.processor_audio.queued_samples.load(Ordering::Relaxed)),
+            audio_pool("recording.pre_roll", self.processor_audio.pre_roll_samples.load(Ordering::Relaxed)),
+            audio_pool("recording.short_clip", short_clip),
+        ]
+    }
+
+    /// Статистика сессий записи (итог отдаётся listener'у при остановке)
+    pub fn session_stats_tracker(&self) -> Arc<SessionStatsTracker> {
+        self.session_stats.clone()
+    }
+
+    /// Таймлайны последних сессий записи
+    pub fn session_timeline(&self) -> Arc<SessionTimelineRecorder> {
+        self.session_timeline.clone()
+    }
+
+    /// Слушатель отчётов о соединении, открытом между записями (для UI и контроля расходов)
+    pub fn set_idle_connection_listener(&self, listener: Option<IdleConnectionListener>) {
+        *self.idle_listener.lock().unwrap_or_else(|e| e.into_inner()) = listener;
+    }
+
+    /// Режим диктовки по буквам; true — режим переключился
+    pub fn set_spelling_mode(&self, enabled: bool) -> bool {
+        self.spelling.set(enabled)
+    }
+
+    pub fn spelling_mode(&self) -> bool {
+        self.spelling.is_enabled()
+    }
+
+    /// Слушатель переключений режима по буквам (в том числе голосовыми командами)
+    pub fn set_spelling_mode_listener(&self, listener: Option<SpellingModeListener>) {
+        self.spelling.set_listener(listener);
+    }
+
+    /// Исполнитель голосовых команд управления; без него команды не распознаются
+    pub fn set_voice_command_listener(&self, listener: Option<VoiceCommandListener>) {
+        *self.voice_command_listener.lock().unwrap_or_else(|e| e.into_inner()) = listener;
+    }
+
+    /// Получатель текстов A/B сравнения провайдеров; без него второй стрим не открывается
+    pub fn set_comparison_listener(&self, listener: Option<ComparisonListener>) {
+        *self.comparison_listener.lock().unwrap_or_else(|e| e.into_inner()) = listener;
+    }
+
+    /// Получатель ансамбля A/B сравнения; работает только в сборке с cargo feature `ensemble`
+    pub fn set_ensemble_listener(&self, listener: Option<EnsembleListener>) {
+        *self.ensemble_listener.lock().unwrap_or_else(|e| e.into_inner()) = listener;
+    }
+
     /// Update microphone sensitivity (0-200)
     pub async fn set_microphone_sensitivity(&self, sensitivity: u8) {
         *self.microphone_sensitivity.write().await = sensitivity.min(200);
@@ -70,6 +365,9 @@ impl TranscriptionService {
         *status = RecordingStatus::Starting;
         drop(status);
 
+        self.diagnostics.reset();
+        let on_error = self.diagnostics.wrap_error_callback(on_error);
+
         // Отменяем таймер неактивности если он запущен
         if let Some(timer) = self.inactivity_timer_task.write().await.take() {
             log::info!("Cancelling inactivity timer (user started recording before timeout)");
@@ -85,8 +383,211 @@ impl TranscriptionService {
             let _ = task.await;
         }
 
+        let mut config = self.session_config().await;
+        let mut session_override = self.next_session_override.write().await.take();
+        if let Some(session_override) = session_override.as_ref() {
+            log::info!("Using STT override for this session: {:?}", session_override);
+            session_override.apply_to(&mut config);
+        }
+        let offline_fallback = self.offline_fallback.lock().ok().and_then(|f| f.clone());
+        if let Some(fallback) = offline_fallback.filter(|_| config.provider.requires_network()) {
+            // Нет сети — облачный провайдер не подключится; язык сессии сохраняем
+            log::warn!("Network is offline: recording with {:?} instead of {:?}", fallback.provider, config.provider);
+            fallback.apply_to(&mut config);
+            session_override = Some(SttSessionOverride {
+                language: session_override.and_then(|o| o.language),
+                ..fallback
+            });
+        }
+
+        // Оборачиваем callbacks, чтобы замерять задержку ответа провайдера
+        // и сглаживать мигающие partial'ы (задержку меряем до фильтра — это задержка провайдера).
+        // У каналов стерео-интервью свои сегменты — и свой стабилизатор
+        let stabilizer = Arc::new(std::sync::Mutex::new([
+            PartialStabilizer::new(config.partial_stability),
+            PartialStabilizer::new(config.partial_stability),
+        ]));
+        // Сегменты склеиваются в предложения: регистр и пробелы на стыке (свой текст у каждого канала),
+        // после долгой паузы — новый абзац
+        let assembler = Arc::new(std::sync::Mutex::new([
+            TranscriptAssembler::new().with_paragraph_pause(config.paragraph_pause_ms),
+            TranscriptAssembler::new().with_paragraph_pause(config.paragraph_pause_ms),
+        ]));
+        // Слова-паразиты убираем и из сегментов: frontend склеивает фразу из них
+        let filler_removal = config.filler_removal.clone();
+        let filler_removal_for_partial = filler_removal.clone();
+        let session_language_for_partial = config.language.clone();
+        let latency_for_partial = self.latency.clone();
+        let pipeline_for_partial = self.pipeline.clone();
+        let stabilizer_for_partial = stabilizer.clone();
+        let assembler_for_partial = assembler.clone();
+        let spelling_for_partial = self.spelling.clone();
+        // Голосовые команды: свой spotter на сессию (выполнен ли стоп, что уже вставлено)
+        let voice_commands = if config.voice_commands {
+            let listener = self.voice_command_listener.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            listener.map(|listener| Arc::new(VoiceCommandSpotter::new(listener)))
+        } else {
+            None
+        };
+        let voice_commands_for_partial = voice_commands.clone();
+        // A/B сравнение: тот же звук второму провайдеру, тексты обоих — listener'у с меткой провайдера
+        let comparison = match config.comparison_provider.filter(|provider| *provider != config.provider) {
+            Some(secondary) if config.split_channels => {
+                log::warn!("Provider comparison with {:?} is not available with split channels", secondary);
+                None
+            }
+            Some(secondary) => {
+                let listener = self.comparison_listener.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                let ensemble = self.ensemble_listener.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                listener.map(|listener| {
+                    Arc::new(ProviderComparison::new(config.provider, secondary, listener).with_ensemble(ensemble))
+                })
+            }
+            None => None,
+        };
+        let comparison_for_partial = comparison.clone();
+        let on_partial: TranscriptionCallback = Arc::new(move |t: Transcription| {
+            if let Some(latency_ms) = latency_for_partial.record_partial() {
+                pipeline_for_partial.record_ms(PipelineStage::ProviderResponse, latency_ms);
+            }
+            let _span = pipeline_for_partial.span(PipelineStage::Callback);
+            if let Some(comparison) = comparison_for_partial.as_ref() {
+                comparison.record(true, &t, t.is_final);
+            }
+            let slot = t.channel_slot();
+            let shown = match stabilizer_for_partial.lock() {
+                Ok(mut stabilizers) => stabilizers[slot].stabilize(t),
+                Err(_) => Some(t),
+            };
+            if let Some(mut t) = shown {
+                let command = match voice_commands_for_partial.as_ref() {
+                    Some(spotter) if t.is_final => spotter.finalize(&mut t.text),
+                    Some(spotter) => {
+                        spotter.interim(&mut t.text);
+                        None
+                    }
+                    None => None,
+                };
+                // Команды режима по буквам выполняем только на финализированном сегменте:
+                // промежуточный текст ещё поменяется
+                let spelled = if t.is_final {
+                    spelling_for_partial.apply(&t.text)
+                } else {
+                    spelling_for_partial.preview(&t.text)
+                };
+                if let Some(spelled) = spelled {
+                    t.text = spelled;
+                    if t.is_final {
+                        if let Ok(mut assemblers) = assembler_for_partial.lock() {
+                            t.text = assemblers[slot].commit_verbatim(&t.text);
+                        }
+                    }
+                } else {
+                    let language = t.language.as_deref().unwrap_or(&session_language_for_partial);
+                    if let Some(remover) = FillerRemover::new(language, &filler_removal_for_partial) {
+                        t.text = remover.remove(&t.text);
+                    }
+                    if let Ok(mut assemblers) = assembler_for_partial.lock() {
+                        // Финализированный сегмент провайдер больше не поменяет — запоминаем его конец
+                        t.text = if t.is_final {
+                            assemblers[slot].commit_segment(&t)
+                        } else {
+                            assemblers[slot].continue_text(&t.text)
+                        };
+                    }
+                }
+                if let (Some(spotter), Some(command)) = (voice_commands_for_partial.as_ref(), command) {
+                    let session_text = assembler_for_partial
+                        .lock()
+                        .map(|assemblers| assemblers[slot].text().to_string())
+                        .unwrap_or_default();
+                    spotter.execute(command, slot, &session_text);
+                }
+                on_partial(t);
+            }
+        });
+        let latency_for_final = self.latency.clone();
+        let pipeline_for_final = self.pipeline.clone();
+        let stats_for_final = self.session_stats.clone();
+        let timeline_for_final = self.session_timeline.clone();
+        let journal_for_final = self.session_journal.clone();
+        let corrections_for_final = self.corrections.clone();
+        let punctuator_for_final = if config.punctuate_locally {
+            if self.punctuator.is_none() {
+                log::warn!("punctuate_locally is enabled, but local punctuation is not available in this build");
+            }
+            self.punctuator.clone()
+        } else {
+            None
+        };
+        let normalization = config.normalization.clone();
+        let session_language = config.language.clone();
+        let spelling_for_final = self.spelling.clone();
+        let comparison_for_final = comparison.clone();
+        let on_final: TranscriptionCallback = Arc::new(move |mut t: Transcription| {
+            if let Some(latency_ms) = latency_for_final.record_final() {
+                pipeline_for_final.record_ms(PipelineStage::ProviderResponse, latency_ms);
+            }
+            let _span = pipeline_for_final.span(PipelineStage::Callback);
+            if let Some(comparison) = comparison_for_final.as_ref() {
+                comparison.record(true, &t, true);
+            }
+            if let Ok(mut stabilizers) = stabilizer.lock() {
+                stabilizers[t.channel_slot()].reset();
+            }
+            let command = voice_commands.as_ref().and_then(|spotter| spotter.finalize(&mut t.text));
+            // По буквам: символы как продиктованы, без пунктуации, исправлений и нормализации
+            if let Some(spelled) = spelling_for_final.apply(&t.text) {
+                t.text = match assembler.lock() {
+                    Ok(mut assemblers) => assemblers[t.channel_slot()].commit_verbatim(&spelled),
+                    Err(_) => spelled,
+                };
+            } else {
+                // Пунктуация первой: модель ожидает "сырой" текст провайдера (фраза могла быть одной командой)
+                if let Some(punctuator) = punctuator_for_final.as_ref().filter(|_| !t.text.trim().is_empty()) {
+                    match punctuator.punctuate(&t.text) {
+                        Ok(text) => t.text = text,
+                        Err(e) => log::warn!("Local punctuation ({}) failed, keeping provider text: {}", punctuator.name(), e),
+                    }
+                }
+                if let Some(engine) = corrections_for_final.as_ref() {
+                    t.text = engine.apply(&t.text);
+                }
+                // Нормализуем по языку фразы (при автоопределении он может отличаться от настроек)
+                let language = t.language.as_deref().unwrap_or(&session_language);
+                if let Some(normalizer) = normalization
+                    .rules_for(language)
+                    .and_then(|rules| TextNormalizer::new(language, rules))
+                {
+                    t.text = normalizer.normalize(&t.text);
+                }
+                if let Some(remover) = FillerRemover::new(language, &filler_removal) {
+                    t.text = remover.remove(&t.text);
+                }
+                // Последней: регистр первого слова зависит от пунктуации, которую расставили выше
+                if let Ok(mut assemblers) = assembler.lock() {
+                    t.text = assemblers[t.channel_slot()].commit_segment(&t);
+                }
+            }
+            if let (Some(spotter), Some(command)) = (voice_commands.as_ref(), command) {
+                let slot = t.channel_slot();
+                let session_text = assembler
+                    .lock()
+                    .map(|assemblers| assemblers[slot].text().to_string())
+                    .unwrap_or_default();
+                spotter.execute(command, slot, &session_text);
+            }
+            if let Some(journal) = journal_for_final.as_ref() {
+                if !t.text.trim().is_empty() {
+                    journal.append_final(&t.text);
+                }
+            }
+            stats_for_final.record_final(&t);
+            timeline_for_final.record_final(&t);
+            on_final(t);
+        });
+
         // Проверяем можно ли переиспользовать существующее соединение
-        let config = self.config.read().await.clone();
         let mut can_reuse_connection = {
             let provider_opt = self.stt_provider.read().await;
             if let Some(provider) = provider_opt.as_ref() {
@@ -94,6 +595,9 @@ impl TranscriptionService {
                     && provider.is_connection_alive()
                     // Backend-only режим: keep-alive обязателен для UX (частые hotkey-сессии).
                     && (config.keep_connection_alive || config.provider == SttProviderType::Backend)
+                    // Соединение с другим провайдером/языком переиспользовать нельзя (они задаются при подключении)
+                    && session_override.is_none()
+                    && !self.connection_overridden.load(Ordering::Relaxed)
             } else {
                 false
             }
@@ -133,29 +637,61 @@ impl TranscriptionService {
             }
         }
 
-        if !can_reuse_connection {
-            // Создаем новое соединение (обычный старт с задержкой)
-            log::info!("Creating new STT connection");
-
-            let mut provider = match self.stt_factory.create(&config) {
-                Ok(p) => p,
-                Err(e) => {
-                    // Важно: статус откатываем СИНХРОННО. Иначе возможен race:
-                    // UI уже увидел Starting, но хоткей/команды будут думать что всё ещё Starting и игнорировать toggle.
-                    *self.status.write().await = RecordingStatus::Idle;
-                    return Err(anyhow::Error::new(e).context("Failed to create STT provider"));
+        // Стерео-интервью: захват отдаёт оба канала (L/R), иначе — моно
+        {
+            let channels = if config.split_channels { 2 } else { 1 };
+            let mut capture = self.audio_capture.write().await;
+            let mut audio_config = capture.config();
+            if audio_config.channels != channels {
+                audio_config.channels = channels;
+                if let Err(e) = capture.initialize(audio_config).await {
+                    log::warn!("Failed to switch audio capture to {} channel(s): {}", channels, e);
                 }
-            };
+            }
+        }
 
-            if let Err(e) = provider.initialize(&config).await {
-                    log::error!("Failed to initialize STT provider: {}", e);
-                *self.status.write().await = RecordingStatus::Idle;
-                let _ = provider.abort().await;
-                return Err(anyhow::Error::new(e).context("Failed to initialize STT provider"));
+        // Короткие записи — одним REST запросом: пока запись не переросла порог, стрим не открываем,
+        // а аудио копим в памяти. Уже живое keep-alive соединение выгоднее просто переиспользовать.
+        // Стерео в REST не отправляем — там нет разделения каналов.
+        let audio_config = self.audio_capture.read().await.config();
+        let short_clip = if can_reuse_connection || audio_config.channels != 1 || comparison.is_some() {
+            None
+        } else {
+            self.short_clip_batch(&config)
+        };
+        *self.short_clip.lock().unwrap_or_else(|e| e.into_inner()) = None;
+
+        if !can_reuse_connection {
+            // Keep-alive соединение с другими настройками закрываем, чтобы не оставить висящий WebSocket
+            if short_clip.is_some() || session_override.is_some() || self.connection_overridden.load(Ordering::Relaxed) {
+                if let Some(mut previous) = self.stt_provider.write().await.take() {
+                    let _ = previous.abort().await;
+                }
             }
+        }
 
-            if let Err(e) = provider
-                .start_stream(
+        // Pre-roll: микрофон стартует до подключения к провайдеру, а записанное пока открывается
+        // стрим досылается первым — иначе первые слога теряются на ~500мс установки соединения
+        let fast_start = config.pre_roll_ms > 0;
+        let needs_stream = !can_reuse_connection && short_clip.is_none();
+
+        if let Some((batch, secs)) = short_clip {
+            log::info!("Buffering up to {}s of audio for {} REST API before opening a stream", secs, batch.name());
+            *self.short_clip.lock().unwrap_or_else(|e| e.into_inner()) = Some(ShortClipBuffer {
+                batch,
+                samples: Vec::new(),
+                sample_rate: audio_config.sample_rate,
+                channels: audio_config.channels,
+                max_samples: secs as usize * audio_config.sample_rate as usize,
+                on_final: on_final.clone(),
+                on_error: on_error.clone(),
+            });
+        } else if needs_stream && !fast_start {
+            // Создаем новое соединение (обычный старт с задержкой)
+            if let Err(e) = self
+                .open_session_stream(
+                    &config,
+                    session_override.is_some(),
                     on_partial.clone(),
                     on_final.clone(),
                     on_error.clone(),
@@ -163,12 +699,17 @@ impl TranscriptionService {
                 )
                 .await
             {
+                // Важно: статус откатываем СИНХРОННО. Иначе возможен race:
+                // UI уже увидел Starting, но хоткей/команды будут думать что всё ещё Starting и игнорировать toggle.
                 *self.status.write().await = RecordingStatus::Idle;
-                let _ = provider.abort().await;
-                return Err(anyhow::Error::new(e).context("Failed to start STT stream"));
+                return Err(e);
             }
+        }
 
-            *self.stt_provider.write().await = Some(provider);
+        // Стрим сравнения открываем до старта микрофона, чтобы оба провайдера получили одинаковое аудио
+        self.close_comparison_stream().await;
+        if let Some(comparison) = comparison.as_ref() {
+            self.open_comparison_stream(&config, comparison).await;
         }
 
         // Канал для передачи аудио чанков из нативного потока в async контекст.
@@ -202,6 +743,20 @@ impl TranscriptionService {
             }
         });
 
+        // Недосланное аудио прошлой сессии уже не к чему приклеить
+        if let Some(stale) = self.audio_spool.as_ref().and_then(|spool| spool.take()) {
+            log::warn!("Discarding {} unsent samples left from the previous session", stale.samples.len());
+        }
+        let spool_gap = self.audio_spool.clone().map(|spool| SpooledGapRecovery {
+            spool,
+            factory: self.stt_factory.clone(),
+            config: config.clone(),
+            offline_fallback: self.offline_fallback.clone(),
+            on_final: on_final.clone(),
+        });
+        *self.gap_recovery.lock().unwrap_or_else(|e| e.into_inner()) = spool_gap.clone();
+        let gap_recovery = self.gap_recovery.clone();
+
         // Запускаем обработчик чанков в async контексте
         let stt_provider = self.stt_provider.clone();
         let status_arc = self.status.clone();
@@ -210,6 +765,21 @@ impl TranscriptionService {
         let audio_capture = self.audio_capture.clone();
         let on_connection_quality_for_processor = on_connection_quality.clone();
         let on_chunk_for_restart = on_chunk.clone();
+        let latency = self.latency.clone();
+        let pipeline = self.pipeline.clone();
+        let processor_audio = ProcessorAudioGaugeReset(self.processor_audio.clone());
+        let session_stats = self.session_stats.clone();
+        let journal = self.session_journal.clone();
+        let diagnostics = self.diagnostics.clone();
+        let mut backlog = AudioBacklogMonitor::new(self.backpressure);
+        let mut pre_roll = PreRollBuffer::new(config.pre_roll_ms.min(MAX_PRE_ROLL_MS));
+        let short_clip = self.short_clip.clone();
+        let stream_factory = self.stt_factory.clone();
+        let stream_config = config.clone();
+        let stream_callbacks = (on_partial.clone(), on_final.clone(), on_error.clone(), on_connection_quality.clone());
+        let connection_overridden = self.connection_overridden.clone();
+        let session_overridden = session_override.is_some();
+        let comparison_stream = self.comparison_stream.clone();
 
         let processor_task = tokio::spawn(async move {
             let mut chunk_count = 0;
@@ -221,6 +791,7 @@ impl TranscriptionService {
             let mut last_dropped_seen: usize = 0;
             let mut last_audio_at = Instant::now();
             let mut stall_restarts: u32 = 0;
+            let mut watchdog = StreamWatchdog::default();
 
             // На macOS/некоторых девайсах при отсутствии разрешения на микрофон или при "пустом" input
             // CoreAudio может отдавать строго нулевые семплы. Это выглядит как "всё работает", но речи нет.
@@ -233,69 +804,81 @@ impl TranscriptionService {
             const MAX_AUDIO_STALL_RESTARTS: u32 = 3;
 
             loop {
-                let maybe_chunk = tokio::select! {
-                    v = rx.recv() => v,
-                    _ = tokio::time::sleep(AUDIO_STALL_CHECK_INTERVAL) => {
-                        // Если долго не приходят чанки — захват аудио мог "отвалиться"
-                        // (например, микрофон был отключён/переключён на уровне ОС).
-                        let status = status_arc.read().await;
-                        if *status != RecordingStatus::Recording {
-                            continue;
-                        }
-                        drop(status);
+                // Сначала досылаем то, что записали, пока открывался стрим
+                let replayed = if !pre_roll.is_empty() && *status_arc.read().await == RecordingStatus::Recording {
+                    pre_roll.pop()
+                } else {
+                    None
+                };
+                let from_pre_roll = replayed.is_some();
 
-                        if last_audio_at.elapsed() < AUDIO_STALL_TIMEOUT {
-                            continue;
-                        }
+                let maybe_chunk = if replayed.is_some() {
+                    replayed
+                } else {
+                    tokio::select! {
+                        v = rx.recv() => v,
+                        _ = tokio::time::sleep(AUDIO_STALL_CHECK_INTERVAL) => {
+                            // Если долго не приходят чанки — захват аудио мог "отвалиться"
+                            // (например, микрофон был отключён/переключён на уровне ОС).
+                            let status = status_arc.read().await;
+                            if *status != RecordingStatus::Recording {
+                                continue;
+                            }
+                            drop(status);
 
-                        stall_restarts = stall_restarts.saturating_add(1);
-                        log::warn!(
-                            "Audio capture stalled (no chunks for {:?}). Restart attempt {}/{}",
-                            AUDIO_STALL_TIMEOUT,
-                            stall_restarts,
-                            MAX_AUDIO_STALL_RESTARTS
-                        );
+                            if last_audio_at.elapsed() < AUDIO_STALL_TIMEOUT {
+                                continue;
+                            }
 
-                        on_connection_quality_for_processor(
-                            "Poor".to_string(),
-                            Some("Потерян аудиопоток (микрофон недоступен?). Пробую восстановить...".to_string()),
-                        );
-                        last_quality = Some("Poor");
-                        good_streak = 0;
+                            stall_restarts = stall_restarts.saturating_add(1);
+                            log::warn!(
+                                "Audio capture stalled (no chunks for {:?}). Restart attempt {}/{}",
+                                AUDIO_STALL_TIMEOUT,
+                                stall_restarts,
+                                MAX_AUDIO_STALL_RESTARTS
+                            );
 
-                        // Пытаемся мягко перезапустить захват аудио.
-                        let restart_result = {
-                            let mut cap = audio_capture.write().await;
-                            let _ = cap.stop_capture().await;
-                            cap.start_capture(on_chunk_for_restart.clone()).await
-                        };
+                            on_connection_quality_for_processor(
+                                "Poor".to_string(),
+                                Some(ConnectionQualityReason::AudioStreamLost),
+                            );
+                            last_quality = Some("Poor");
+                            good_streak = 0;
 
-                        match restart_result {
-                            Ok(_) => {
-                                log::info!("Audio capture restarted successfully after stall");
-                                last_audio_at = Instant::now();
-                                stall_restarts = 0;
-                                on_connection_quality_for_processor(
-                                    "Recovering".to_string(),
-                                    Some("Аудио восстановлено".to_string()),
-                                );
-                                last_quality = Some("Recovering");
-                                continue;
-                            }
-                            Err(e) => {
-                                log::error!("Failed to restart audio capture after stall: {}", e);
-                                if stall_restarts < MAX_AUDIO_STALL_RESTARTS {
-                                    // Дадим шанс восстановиться (например, устройство вот-вот появится).
+                            // Пытаемся мягко перезапустить захват аудио.
+                            let restart_result = {
+                                let mut cap = audio_capture.write().await;
+                                let _ = cap.stop_capture().await;
+                                cap.start_capture(on_chunk_for_restart.clone()).await
+                            };
+
+                            match restart_result {
+                                Ok(_) => {
+                                    log::info!("Audio capture restarted successfully after stall");
+                                    last_audio_at = Instant::now();
+                                    stall_restarts = 0;
+                                    on_connection_quality_for_processor(
+                                        "Recovering".to_string(),
+                                        Some(ConnectionQualityReason::AudioStreamRestored),
+                                    );
+                                    last_quality = Some("Recovering");
                                     continue;
                                 }
+                                Err(e) => {
+                                    log::error!("Failed to restart audio capture after stall: {}", e);
+                                    if stall_restarts < MAX_AUDIO_STALL_RESTARTS {
+                                        // Дадим шанс восстановиться (например, устройство вот-вот появится).
+                                        continue;
+                                    }
 
-                                // Фатально: возвращаем сервис в Idle, чтобы UI/хоткей не залипали,
-                                // и отправляем ошибку в UI.
-                                let raw = format!("Audio device is no longer available: {}", e);
-                                on_error_for_processor(SttError::Processing(raw));
-                                *status_arc.write().await = RecordingStatus::Idle;
-                                let _ = audio_capture.write().await.stop_capture().await;
-                                break;
+                                    // Фатально: возвращаем сервис в Idle, чтобы UI/хоткей не залипали,
+                                    // и отправляем ошибку в UI.
+                                    let raw = format!("Audio device is no longer available: {}", e);
+                                    on_error_for_processor(SttError::Processing(raw));
+                                    *status_arc.write().await = RecordingStatus::Idle;
+                                    let _ = audio_capture.write().await.stop_capture().await;
+                                    break;
+                                }
                             }
                         }
                     }
@@ -304,16 +887,69 @@ impl TranscriptionService {
                 let Some(chunk) = maybe_chunk else {
                     break;
                 };
+                processor_audio.0.pre_roll_samples.store(pre_roll.buffered_samples(), Ordering::Relaxed);
+                processor_audio.0.queued_samples.store(rx.len() * chunk.data.len(), Ordering::Relaxed);
 
-                chunk_count += 1;
                 last_audio_at = Instant::now();
                 stall_restarts = 0;
 
-                let status = status_arc.read().await;
-                if *status != RecordingStatus::Recording {
+                let status = *status_arc.read().await;
+                if status != RecordingStatus::Recording {
+                    // Стрим ещё открывается — копим аудио, после старта оно уйдёт первым
+                    if status == RecordingStatus::Starting {
+                        pre_roll.push(chunk);
+                    }
+                    continue;
+                }
+                // Живой чанк встаёт за недосланным pre-roll, чтобы не нарушить порядок аудио
+                if !from_pre_roll && !pre_roll.is_empty() {
+                    pre_roll.push(chunk);
+                    continue;
+                }
+
+                chunk_count += 1;
+                // Pre-roll держали намеренно, пока открывался стрим — это не задержка захвата
+                if !from_pre_roll {
+                    let queued_ms = chrono::Utc::now().timestamp_millis().saturating_sub(chunk.timestamp);
+                    pipeline.record_ms(PipelineStage::Capture, queued_ms.max(0) as f64);
+                }
+
+                // Backpressure: если отправка не успевает за захватом, выбрасываем самое старое аудио
+                // (текущий чанк и начало очереди), чтобы отставание и память не росли бесконечно.
+                let chunk_ms = chunk.data.len() as f64 * 1000.0
+                    / (chunk.sample_rate.max(1) as f64 * chunk.channels.max(1) as f64);
+                let to_drop = backlog.chunks_to_drop(rx.len(), chunk_ms);
+                if to_drop > 0 {
+                    // Выброшенное аудио не уходит провайдеру, но в журнал попадает — хвост можно перераспознать
+                    let sensitivity = *sensitivity_arc.read().await;
+                    if let Some(journal) = journal.as_ref() {
+                        journal_dropped_audio(journal.as_ref(), &chunk, sensitivity);
+                    }
+                    for _ in 1..to_drop {
+                        let Ok(dropped) = rx.try_recv() else {
+                            break;
+                        };
+                        if let Some(journal) = journal.as_ref() {
+                            journal_dropped_audio(journal.as_ref(), &dropped, sensitivity);
+                        }
+                    }
+                    log::warn!(
+                        "Audio send backlog exceeded: dropped {} oldest chunks ({:.1}s total this session, backlog now {:.1}s)",
+                        to_drop,
+                        backlog.dropped_secs(),
+                        backlog.backlog_secs()
+                    );
+                }
+                if let Some(report) = backlog.poll_report() {
+                    on_connection_quality_for_processor(report.quality.to_string(), report.reason);
+                    last_quality = Some(report.quality);
+                    good_streak = 0;
+                }
+                if to_drop > 0 {
                     continue;
                 }
-                drop(status);
+
+                let gain_span = pipeline.span(PipelineStage::Gain);
 
                 // Вычисляем уровень громкости для визуализации
                 // Используем перцептивную нормализацию (корень квадратный) как в VU-метрах
@@ -335,10 +971,7 @@ impl TranscriptionService {
                 if consecutive_all_zero_chunks == ALL_ZERO_WARN_THRESHOLD {
                     on_connection_quality_for_processor(
                         "Poor".to_string(),
-                        Some(
-                            "Не поступает сигнал с микрофона (все семплы = 0). Проверьте выбранное устройство и разрешение на микрофон в macOS."
-                                .to_string(),
-                        ),
+                        Some(ConnectionQualityReason::MicrophoneSilent),
                     );
                     last_quality = Some("Poor");
                     good_streak = 0;
@@ -359,31 +992,10 @@ impl TranscriptionService {
                     on_audio_level(normalized_level);
                 }
 
-                // Применяем линейное усиление (gain) на основе чувствительности микрофона
-                // sensitivity: 0-200%
-                //   0%   = gain 0.0x (полная тишина)
-                //   100% = gain 1.0x (без изменений, как записывает микрофон)
-                //   200% = gain 5.0x (максимальное усиление для тихих микрофонов)
+                // Линейное усиление по чувствительности микрофона (0-200% → 0.0x-5.0x) + лимитер от клиппинга
                 let sensitivity = *sensitivity_arc.read().await;
-
-                // Простая линейная формула усиления
-                let requested_gain = if sensitivity <= 100 {
-                    // 0-100% → 0.0x-1.0x (приглушение/нормальный уровень)
-                    sensitivity as f32 / 100.0
-                } else {
-                    // 100-200% → 1.0x-5.0x (усиление для тихих микрофонов)
-                    1.0 + (sensitivity - 100) as f32 / 100.0 * 4.0
-                };
-
-                // Простой limiter: если requested_gain приводит к клиппингу — уменьшаем gain для этого чанка.
-                // Это сохраняет "помощь" тихим микрофонам и не ухудшает распознавание на нормальных уровнях.
-                let effective_gain = if max_amplitude <= 0 {
-                    requested_gain
-                } else {
-                    let headroom = 0.98_f32;
-                    let limiter_gain = (32767.0 * headroom) / (max_amplitude as f32);
-                    requested_gain.min(limiter_gain)
-                };
+                let requested_gain = sensitivity_gain(sensitivity);
+                let effective_gain = limited_gain(requested_gain, max_amplitude);
 
                 if chunk_count == 1 {
                     if effective_gain < requested_gain {
@@ -403,15 +1015,7 @@ impl TranscriptionService {
                     }
                 }
 
-                // Применяем gain к каждому сэмплу с защитой от clipping
-                let amplified_data: Vec<i16> = chunk
-                    .data
-                    .iter()
-                    .map(|&sample| {
-                        let amplified = (sample as f32 * effective_gain).clamp(-32767.0, 32767.0);
-                        amplified as i16
-                    })
-                    .collect();
+                let amplified_data = apply_gain(&chunk.data, effective_gain);
 
                 // Создаем новый чанк с усиленным аудио
                 let amplified_chunk = crate::domain::AudioChunk {
@@ -421,6 +1025,12 @@ impl TranscriptionService {
                     timestamp: chunk.timestamp,
                 };
 
+                // Сохраняем аудио в журнал ДО отправки: если отправка/процесс упадёт, хвост можно будет перераспознать.
+                // Журнал — моно: стерео-интервью перераспознаётся смешанным
+                if let Some(journal) = journal.as_ref() {
+                    journal.append_audio(&mono_samples(&amplified_chunk), amplified_chunk.sample_rate);
+                }
+
                 // Отправляем спектр (48 баров) в UI.
                 // Берем именно усиленный звук, чтобы визуализация соответствовала тому, что слышит STT.
                 if let Some(bars) = spectrum.push_samples(&amplified_chunk.data) {
@@ -438,6 +1048,7 @@ impl TranscriptionService {
                     log::debug!("Audio processing: chunk #{}, original_max={}, amplified_max={}, gain={:.2}x",
                         chunk_count, max_amplitude, amplified_max, effective_gain);
                 }
+                drop(gain_span);
 
                 // Если начали дропать аудио из-за backpressure — это почти всегда признак "плохой сети"
                 // или зависшей отправки. Показываем это пользователю через connection:quality.
@@ -447,13 +1058,88 @@ impl TranscriptionService {
                     if last_quality != Some("Poor") {
                         on_connection_quality_for_processor(
                             "Poor".to_string(),
-                            Some("Аудио не успевает отправляться (плохое соединение?)".to_string()),
+                            Some(ConnectionQualityReason::AudioSendTooSlow),
                         );
                         last_quality = Some("Poor");
                         good_streak = 0;
                     }
                 }
 
+                session_stats.record_audio(
+                    chunk_ms / 1000.0,
+                    chunk_rms(&amplified_chunk.data) >= SPEECH_RMS_THRESHOLD,
+                );
+
+                // Короткая запись: аудио копится в памяти, пока запись не переросла порог
+                let overflowed = {
+                    let mut clip_guard = short_clip.lock().unwrap_or_else(|e| e.into_inner());
+                    match clip_guard.as_mut() {
+                        Some(clip) => {
+                            clip.samples.extend_from_slice(&amplified_chunk.data);
+                            if clip.samples.len() < clip.max_samples {
+                                continue;
+                            }
+                            clip_guard.take()
+                        }
+                        None => None,
+                    }
+                };
+
+                if let Some(clip) = overflowed {
+                    log::info!("Recording is longer than the short clip threshold, switching to streaming");
+                    let (on_partial, on_final, on_error, on_connection_quality) = stream_callbacks.clone();
+                    match connect_stream(
+                        stream_factory.as_ref(),
+                        &stream_config,
+                        on_partial,
+                        on_final,
+                        on_error,
+                        on_connection_quality,
+                    )
+                    .await
+                    {
+                        Ok(mut provider) => {
+                            // Накопленное аудио догоняем кусками того же размера, что приходят с микрофона
+                            for part in clip.samples.chunks(amplified_chunk.data.len().max(1)) {
+                                let part = AudioChunk::new(part.to_vec(), clip.sample_rate, clip.channels);
+                                if let Err(e) = provider.send_audio(&part).await {
+                                    log::warn!("Failed to send buffered audio after short clip threshold: {}", e);
+                                    break;
+                                }
+                                diagnostics.record_sent(part.data.len());
+                            }
+                            latency.record_audio_sent();
+                            *stt_provider.write().await = Some(provider);
+                            connection_overridden.store(session_overridden, Ordering::Relaxed);
+                        }
+                        Err(e) => {
+                            log::error!("Failed to open STT stream after short clip threshold: {:#}", e);
+                            on_error_for_processor(
+                                e.downcast_ref::<SttError>()
+                                    .cloned()
+                                    .unwrap_or_else(|| SttError::Processing(format!("{:#}", e))),
+                            );
+                            *status_arc.write().await = RecordingStatus::Idle;
+                            let _ = audio_capture.write().await.stop_capture().await;
+                            break;
+                        }
+                    }
+                    continue;
+                }
+
+                // A/B сравнение: тот же чанк второму провайдеру; его ошибки запись не останавливают
+                {
+                    let mut comparison_guard = comparison_stream.write().await;
+                    if let Some(comparison) = comparison_guard.as_mut() {
+                        if let Err(e) = comparison.send_audio(&amplified_chunk).await {
+                            log::warn!("Comparison provider failed, continuing with the primary one only: {}", e);
+                            if let Some(mut comparison) = comparison_guard.take() {
+                                let _ = comparison.abort().await;
+                            }
+                        }
+                    }
+                }
+
                 let mut provider_guard = stt_provider.write().await;
 
                 // Провайдера нет → это уже "поломанное" состояние.
@@ -466,7 +1152,7 @@ impl TranscriptionService {
                     if last_quality != Some("Poor") {
                         on_connection_quality_for_processor(
                             "Poor".to_string(),
-                            Some("Соединение с провайдером потеряно".to_string()),
+                            Some(ConnectionQualityReason::ProviderConnectionLost),
                         );
                     }
                     *status_arc.write().await = RecordingStatus::Idle;
@@ -474,32 +1160,109 @@ impl TranscriptionService {
                     break;
                 }
 
-                if chunk_count == 1 || chunk_count % 50 == 0 {
-                    log::debug!(
-                        "Processing audio chunk #{}, {} samples, max_amp={}",
-                        chunk_count,
-                        amplified_chunk.data.len(),
-                        max_amplitude
-                    );
-                }
+                // Задача чтения ответов умерла при открытом стриме — аудио уходит в никуда, открываем заново
+                if watchdog.due(Instant::now())
+                    && StreamWatchdog::is_receiver_dead(&provider_guard.as_ref().expect("checked above").debug_state())
+                {
+                    let Some(attempt) = watchdog.begin_restart() else {
+                        log::error!("STT receiver task died again after {} restarts, stopping recording", MAX_STREAM_RESTARTS);
+                        on_error_for_processor(SttError::Processing(
+                            "STT stream stopped receiving results".to_string(),
+                        ));
+                        on_connection_quality_for_processor(
+                            "Poor".to_string(),
+                            Some(ConnectionQualityReason::ConnectionUnstable),
+                        );
+                        *status_arc.write().await = RecordingStatus::Idle;
+                        let _ = audio_capture.write().await.stop_capture().await;
+                        if let Some(mut old) = provider_guard.take() {
+                            let _ = old.abort().await;
+                        }
+                        break;
+                    };
 
-                let send_result = provider_guard
-                    .as_mut()
-                    .expect("checked above")
-                    .send_audio(&amplified_chunk)
-                    .await;
+                    log::warn!(
+                        "STT receiver task is dead while streaming, restarting stream ({}/{})",
+                        attempt,
+                        MAX_STREAM_RESTARTS
+                    );
+                    on_connection_quality_for_processor(
+                        "Recovering".to_string(),
+                        Some(ConnectionQualityReason::StreamRestarted {
+                            attempt,
+                            max_attempts: MAX_STREAM_RESTARTS,
+                        }),
+                    );
+                    last_quality = Some("Recovering");
+                    good_streak = 0;
+
+                    if let Some(mut old) = provider_guard.take() {
+                        let _ = old.abort().await;
+                    }
+                    let (on_partial, on_final, on_error, on_connection_quality) = stream_callbacks.clone();
+                    match connect_stream(
+                        stream_factory.as_ref(),
+                        &stream_config,
+                        on_partial,
+                        on_final,
+                        on_error,
+                        on_connection_quality,
+                    )
+                    .await
+                    {
+                        Ok(provider) => {
+                            *provider_guard = Some(provider);
+                            connection_overridden.store(session_overridden, Ordering::Relaxed);
+                        }
+                        Err(e) => {
+                            log::error!("Failed to reopen STT stream after receiver task died: {:#}", e);
+                            drop(provider_guard);
+                            on_error_for_processor(
+                                e.downcast_ref::<SttError>()
+                                    .cloned()
+                                    .unwrap_or_else(|| SttError::Processing(format!("{:#}", e))),
+                            );
+                            *status_arc.write().await = RecordingStatus::Idle;
+                            let _ = audio_capture.write().await.stop_capture().await;
+                            break;
+                        }
+                    }
+                }
+
+                if chunk_count == 1 || chunk_count % 50 == 0 {
+                    log::debug!(
+                        "Processing audio chunk #{}, {} samples, max_amp={}",
+                        chunk_count,
+                        amplified_chunk.data.len(),
+                        max_amplitude
+                    );
+                }
+
+                let send_span = pipeline.span(PipelineStage::ProviderSend);
+                let send_result = provider_guard
+                    .as_mut()
+                    .expect("checked above")
+                    .send_audio(&amplified_chunk)
+                    .await;
+                drop(send_span);
 
                 match send_result {
                         Ok(_) => {
+                            latency.record_audio_sent();
+                            diagnostics.record_sent(amplified_chunk.data.len());
                             // Успешная отправка — сбрасываем счётчик ошибок
                         if consecutive_errors > 0 {
                             // Мы только что восстановились после ошибок отправки.
                             on_connection_quality_for_processor(
                                 "Recovering".to_string(),
-                                Some("Соединение восстанавливается".to_string()),
+                                Some(ConnectionQualityReason::ConnectionRecovering),
                             );
                             last_quality = Some("Recovering");
                             good_streak = 0;
+                            // Разрыв распознаём в фоне, живой стрим не ждёт
+                            if let Some(gap) = spool_gap.clone().filter(|gap| gap.has_pending()) {
+                                tokio::spawn(async move { gap.recover().await });
+                            }
                         }
                             consecutive_errors = 0;
                         if last_quality == Some("Recovering") {
@@ -512,6 +1275,11 @@ impl TranscriptionService {
                         }
                         }
                         Err(e) => {
+                            // Чанк не дошёл до провайдера — не теряем его: пишем на диск до восстановления связи
+                            if let Some(gap) = spool_gap.as_ref() {
+                                gap.spool.append(&mono_samples(&amplified_chunk), amplified_chunk.sample_rate);
+                            }
+
                             // Определяем тип ошибки и критичность по ТИПУ, а не по парсингу строки.
                             let (error_type, is_critical) = match &e {
                                 SttError::Authentication(_) => ("authentication", true),
@@ -525,7 +1293,11 @@ impl TranscriptionService {
                                         ("connection", false)
                                     }
                                 }
-                                SttError::Processing(_) | SttError::Internal(_) => ("processing", false),
+                                SttError::RateLimited { .. } => ("connection", false),
+                                SttError::QuotaExceeded { .. } => ("limit_exceeded", true),
+                                SttError::Processing(_) | SttError::Internal(_) | SttError::ProviderBug(_) => {
+                                    ("processing", false)
+                                }
                                 SttError::Unsupported(_) => ("processing", true),
                             };
 
@@ -534,7 +1306,7 @@ impl TranscriptionService {
                                 on_error_for_processor(e.clone());
                             on_connection_quality_for_processor(
                                 "Poor".to_string(),
-                                Some("Критическая ошибка соединения".to_string()),
+                                Some(ConnectionQualityReason::CriticalConnectionError),
                             );
 
                             // Критическая ошибка — останавливаем запись аккуратно.
@@ -574,7 +1346,7 @@ impl TranscriptionService {
                             on_error_for_processor(e.clone());
                             on_connection_quality_for_processor(
                                 "Poor".to_string(),
-                                Some("Соединение нестабильно, запись остановлена".to_string()),
+                                Some(ConnectionQualityReason::ConnectionUnstable),
                             );
 
                             *status_arc.write().await = RecordingStatus::Idle;
@@ -593,28 +1365,84 @@ impl TranscriptionService {
                         if consecutive_errors == 1 && last_quality != Some("Poor") {
                             on_connection_quality_for_processor(
                                 "Poor".to_string(),
-                                Some(format!("{}: {}", error_type, e)),
+                                Some(ConnectionQualityReason::StreamError {
+                                    error_type: error_type.to_string(),
+                                    message: e.to_string(),
+                                }),
                             );
                             last_quality = Some("Poor");
                         }
                     }
                 }
             }
+            // Запись оборвалась ошибкой — стрим сравнения больше не нужен (штатная остановка закрывает его сама)
+            if let Some(mut comparison) = comparison_stream.write().await.take() {
+                let _ = comparison.abort().await;
+            }
+            // Оборвалась ошибкой (статус уже Idle) — недосланное аудио распознаём сейчас, штатная остановка делает это сама
+            let gap = if *status_arc.read().await == RecordingStatus::Idle {
+                gap_recovery.lock().unwrap_or_else(|e| e.into_inner()).take()
+            } else {
+                None
+            };
+            if let Some(gap) = gap {
+                gap.recover().await;
+            }
             log::info!("Audio chunk processor finished, total chunks: {}", chunk_count);
         });
 
         *self.audio_processor_task.write().await = Some(processor_task);
 
-        if let Err(e) = self.audio_capture.write().await.start_capture(on_chunk).await {
-            log::error!("Failed to start audio capture: {}", e);
+        if let Some(journal) = self.session_journal.as_ref() {
+            journal.begin(&format!("{:?}", config.provider));
+        }
+        self.session_stats.start(config.provider, Some(config.language.clone()));
+        self.session_timeline.start(config.provider, audio_config.sample_rate);
+
+        // С pre-roll стрим открываем уже при работающем микрофоне: аудио копится в буфере processor'а
+        let started = match self.audio_capture.write().await.start_capture(on_chunk).await {
+            Err(e) => Err(anyhow::anyhow!("Failed to start audio capture: {}", e)),
+            Ok(()) if fast_start && needs_stream => {
+                self.open_session_stream(
+                    &config,
+                    session_override.is_some(),
+                    on_partial.clone(),
+                    on_final.clone(),
+                    on_error.clone(),
+                    on_connection_quality.clone(),
+                )
+                .await
+            }
+            Ok(()) => Ok(()),
+        };
+
+        if let Err(e) = started {
+            log::error!("Failed to start recording: {:#}", e);
+
+            if let Some(journal) = self.session_journal.as_ref() {
+                journal.complete();
+            }
 
             // Возвращаем статус в Idle, чтобы UI мог восстановиться.
             *self.status.write().await = RecordingStatus::Idle;
+            *self.short_clip.lock().unwrap_or_else(|e| e.into_inner()) = None;
+            *self.gap_recovery.lock().unwrap_or_else(|e| e.into_inner()) = None;
+
+            // Стрим не открылся при уже запущенном микрофоне (pre-roll) — микрофон останавливаем.
+            {
+                let mut capture = self.audio_capture.write().await;
+                if capture.is_capturing() {
+                    let _ = capture.stop_capture().await;
+                }
+            }
 
             // Если audio capture не стартанул — STT соединение держать смысла нет.
             if let Some(mut provider) = self.stt_provider.write().await.take() {
                 let _ = provider.abort().await;
             }
+            if let Some(mut comparison) = self.comparison_stream.write().await.take() {
+                let _ = comparison.abort().await;
+            }
 
             // И прибиваем processor task, иначе он будет висеть в фоне, ожидая rx.
             if let Some(task) = self.audio_processor_task.write().await.take() {
@@ -622,7 +1450,7 @@ impl TranscriptionService {
                 let _ = task.await;
             }
 
-            return Err(anyhow::anyhow!("Failed to start audio capture: {}", e));
+            return Err(e);
         }
 
         // Только после успешного запуска audio capture устанавливаем статус Recording
@@ -632,6 +1460,72 @@ impl TranscriptionService {
         Ok(())
     }
 
+    /// Открывает новый стрим для сессии записи и сохраняет провайдера
+    async fn open_session_stream(
+        &self,
+        config: &SttConfig,
+        session_overridden: bool,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> Result<()> {
+        log::info!("Creating new STT connection");
+        let provider = connect_stream(
+            self.stt_factory.as_ref(),
+            config,
+            on_partial,
+            on_final,
+            on_error,
+            on_connection_quality,
+        )
+        .await?;
+
+        *self.stt_provider.write().await = Some(provider);
+        self.connection_overridden.store(session_overridden, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Второй стрим A/B сравнения; не открылся — запись идёт только с основным провайдером
+    async fn open_comparison_stream(&self, config: &SttConfig, comparison: &Arc<ProviderComparison>) {
+        let secondary = comparison.secondary();
+        let mut comparison_config = config.clone();
+        SttSessionOverride {
+            provider: Some(secondary),
+            ..Default::default()
+        }
+        .apply_to(&mut comparison_config);
+
+        let comparison_for_partial = comparison.clone();
+        let comparison_for_final = comparison.clone();
+        let result = connect_stream(
+            self.stt_factory.as_ref(),
+            &comparison_config,
+            Arc::new(move |t: Transcription| comparison_for_partial.record(false, &t, t.is_final)),
+            Arc::new(move |t: Transcription| comparison_for_final.record(false, &t, true)),
+            Arc::new(move |e: SttError| log::warn!("Comparison provider {:?} error: {}", secondary, e)),
+            Arc::new(|_quality: String, _reason: Option<ConnectionQualityReason>| {}),
+        )
+        .await;
+        match result {
+            Ok(provider) => {
+                log::info!("Comparing with {:?} in this session", secondary);
+                *self.comparison_stream.write().await = Some(provider);
+            }
+            Err(e) => log::warn!("Failed to open comparison stream ({:?}): {:#}", secondary, e),
+        }
+    }
+
+    /// Закрывает стрим A/B сравнения, дождавшись последних финалов
+    async fn close_comparison_stream(&self) {
+        if let Some(mut provider) = self.comparison_stream.write().await.take() {
+            if let Err(e) = provider.stop_stream().await {
+                log::debug!("Failed to stop comparison stream cleanly, aborting: {}", e);
+                let _ = provider.abort().await;
+            }
+        }
+    }
+
     /// Stop recording and finalize transcription
     pub async fn stop_recording(&self) -> Result<String> {
         let mut status = self.status.write().await;
@@ -651,6 +1545,17 @@ impl TranscriptionService {
             task.abort();
             let _ = task.await;
         }
+        let short_clip = self.short_clip.lock().unwrap_or_else(|e| e.into_inner()).take();
+        self.close_comparison_stream().await;
+
+        // Штатная остановка — журнал для восстановления больше не нужен.
+        if let Some(journal) = self.session_journal.as_ref() {
+            journal.complete();
+        }
+        self.session_stats.finish();
+        self.session_timeline.finish();
+        // Режим по буквам — до конца сессии: следующая запись начинается с обычной речи
+        self.spelling.set(false);
 
         // Если не смогли остановить захват аудио — считаем это критическим сценарием:
         // лучше упасть с ошибкой, но гарантированно вернуть сервис в Idle, чем зависнуть в Processing.
@@ -668,6 +1573,15 @@ impl TranscriptionService {
 
         // Проверяем нужно ли держать соединение открытым (keep-alive режим)
         let config = self.config.read().await.clone();
+
+        // Запись оказалась короткой — стрима не было, весь буфер уходит одним REST запросом
+        if let Some(clip) = short_clip {
+            self.transcribe_short_clip(clip).await;
+            *self.status.write().await = RecordingStatus::Idle;
+            self.schedule_audio_release(&config).await;
+            log::info!("Recording stopped");
+            return Ok("Transcription completed".to_string());
+        }
         let should_keep_alive = {
             let provider_opt = self.stt_provider.read().await;
             if let Some(provider) = provider_opt.as_ref() {
@@ -690,7 +1604,9 @@ impl TranscriptionService {
                 Some(p) => p,
                 None => {
                     // Провайдера нет, но захват аудио уже остановили — считаем что запись завершена.
+                    self.recover_spooled_gap().await;
                     *self.status.write().await = RecordingStatus::Idle;
+                    self.schedule_audio_release(&config).await;
                     return Ok("Recording stopped".to_string());
                 }
             };
@@ -704,41 +1620,24 @@ impl TranscriptionService {
                 // Фоллбек: закрываем соединение полностью, чтобы не держать "полуживой" провайдер.
                 let _ = provider.abort().await;
 
+                self.recover_spooled_gap().await;
                 *self.status.write().await = RecordingStatus::Idle;
+                self.schedule_audio_release(&config).await;
                 return Ok("Recording stopped".to_string());
             }
 
             // Возвращаем провайдера назад в состояние сервиса (keep-alive продолжается)
             *self.stt_provider.write().await = Some(provider);
+            self.recover_spooled_gap().await;
 
             // Запускаем таймер на TTL (keep_alive_ttl_secs) для автоматического закрытия соединения.
             //
             // Важно: keep-alive удерживает WS соединение открытым. Если держать слишком долго,
             // можно упереться в лимиты провайдера на параллельные соединения (например Deepgram).
             // Поэтому TTL должен быть коротким и конфигурируемым.
-            let stt_provider = self.stt_provider.clone();
-            let status_arc = self.status.clone();
-            let ttl_secs = config.keep_alive_ttl_secs.max(10); // защитный минимум
-            let inactivity_timer = tokio::spawn(async move {
-                log::info!("Inactivity timer started ({} seconds)", ttl_secs);
-                tokio::time::sleep(tokio::time::Duration::from_secs(ttl_secs)).await;
-
-                // Проверяем что статус все еще Idle (не началась новая запись)
-                let current_status = *status_arc.read().await;
-                if current_status == RecordingStatus::Idle {
-                    log::info!("Inactivity timeout reached ({}s) - closing persistent connection", ttl_secs);
-
-                    if let Some(mut provider) = stt_provider.write().await.take() {
-                        let _ = provider.stop_stream().await;
-                    }
-
-                    log::info!("Persistent connection closed");
-                } else {
-                    log::debug!("Inactivity timer cancelled - recording restarted before timeout");
-                }
-            });
-
-            *self.inactivity_timer_task.write().await = Some(inactivity_timer);
+            // В режиме энергосбережения (idle_teardown_secs) тот же таймер освобождает и микрофон.
+            let ttl_secs = Self::keep_alive_ttl_secs(&config);
+            self.schedule_idle_teardown(config.provider, ttl_secs, config.idle_teardown_secs.is_some()).await;
             *self.status.write().await = RecordingStatus::Idle;
 
             let ttl_secs_for_log = ttl_secs;
@@ -764,8 +1663,10 @@ impl TranscriptionService {
                     let _ = provider.abort().await;
                 }
             }
+            self.recover_spooled_gap().await;
 
             *self.status.write().await = RecordingStatus::Idle;
+            self.schedule_audio_release(&config).await;
 
             log::info!("Recording stopped");
             Ok("Transcription completed".to_string())
@@ -794,6 +1695,15 @@ impl TranscriptionService {
             task.abort();
             let _ = task.await;
         }
+        let short_clip = self.short_clip.lock().unwrap_or_else(|e| e.into_inner()).take();
+        self.close_comparison_stream().await;
+
+        if let Some(journal) = self.session_journal.as_ref() {
+            journal.complete();
+        }
+        self.session_stats.finish();
+        self.session_timeline.finish();
+        self.spelling.set(false);
 
         if let Err(e) = stop_capture_result {
             log::error!("Failed to stop audio capture: {}", e);
@@ -813,6 +1723,10 @@ impl TranscriptionService {
             let _ = timer.await;
         }
 
+        if let Some(clip) = short_clip {
+            self.transcribe_short_clip(clip).await;
+        }
+
         // Жёстко закрываем провайдера и соединение
         if let Some(mut provider) = self.stt_provider.write().await.take() {
             if let Err(e) = provider.stop_stream().await {
@@ -820,17 +1734,324 @@ impl TranscriptionService {
                 let _ = provider.abort().await;
             }
         }
+        // Недосланное аудио — наша же запись, его распознаём и при жёсткой остановке
+        self.recover_spooled_gap().await;
 
         *self.status.write().await = RecordingStatus::Idle;
+        let config = self.config.read().await.clone();
+        self.schedule_audio_release(&config).await;
+
         log::info!("Recording stopped (hard), provider connection closed");
         Ok("Transcription completed".to_string())
     }
 
+    /// Таймер простоя: через ttl_secs закрывает keep-alive соединение и (если release_audio)
+    /// освобождает аудио-устройство. Следующий start_recording лениво поднимает всё заново.
+    ///
+    /// Пока соединение открыто, раз в IDLE_REPORT_INTERVAL отдаёт listener'у отчёт о простое:
+    /// у провайдеров, которые тарифицируют время соединения, это прямые расходы.
+    async fn schedule_idle_teardown(&self, provider_type: SttProviderType, ttl_secs: u64, release_audio: bool) {
+        let stt_provider = self.stt_provider.clone();
+        let audio_capture = self.audio_capture.clone();
+        let status_arc = self.status.clone();
+        let idle_listener = self.idle_listener.clone();
+        let billed_while_idle = provider_type.idle_billing_policy().billed_while_idle;
+        let inactivity_timer = tokio::spawn(async move {
+            log::info!("Inactivity timer started ({} seconds)", ttl_secs);
+            let report = |held_secs: u64, closed: bool| {
+                let listener = idle_listener.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                if let Some(listener) = listener {
+                    listener(IdleConnectionReport {
+                        provider: provider_type,
+                        held_secs,
+                        limit_secs: ttl_secs,
+                        billed_while_idle,
+                        closed,
+                    });
+                }
+            };
+
+            let started = Instant::now();
+            let deadline = Duration::from_secs(ttl_secs);
+            loop {
+                let elapsed = started.elapsed();
+                if elapsed >= deadline {
+                    break;
+                }
+                tokio::time::sleep((deadline - elapsed).min(IDLE_REPORT_INTERVAL)).await;
+                if started.elapsed() < deadline && stt_provider.read().await.is_some() {
+                    let held_secs = started.elapsed().as_secs();
+                    if billed_while_idle {
+                        log::warn!("{:?} connection is billed while idle: held open for {}s", provider_type, held_secs);
+                    }
+                    report(held_secs, false);
+                }
+            }
+
+            // Проверяем что статус все еще Idle (не началась новая запись)
+            let current_status = *status_arc.read().await;
+            if current_status != RecordingStatus::Idle {
+                log::debug!("Inactivity timer cancelled - recording restarted before timeout");
+                return;
+            }
+
+            let closed_provider = stt_provider.write().await.take();
+            if let Some(mut provider) = closed_provider {
+                log::info!("Inactivity timeout reached ({}s) - closing persistent connection", ttl_secs);
+                let _ = provider.stop_stream().await;
+                log::info!("Persistent connection closed");
+                report(started.elapsed().as_secs(), true);
+            }
+
+            if release_audio {
+                if let Err(e) = audio_capture.write().await.release().await {
+                    log::warn!("Failed to release audio capture after idle timeout: {}", e);
+                }
+            }
+        });
+
+        *self.inactivity_timer_task.write().await = Some(inactivity_timer);
+    }
+
+    /// TTL keep-alive соединения с учётом режима энергосбережения и предела простоя провайдера
+    fn keep_alive_ttl_secs(config: &SttConfig) -> u64 {
+        let ttl_secs = match config.idle_teardown_secs {
+            Some(idle_secs) => idle_secs.max(MIN_IDLE_TTL_SECS),
+            None => config.keep_alive_ttl_secs.max(MIN_IDLE_TTL_SECS), // защитный минимум
+        };
+        config.provider.idle_billing_policy().cap_idle_secs(ttl_secs)
+    }
+
+    /// Warm-start: заранее открывает соединение с провайдером и готовит аудио-устройство,
+    /// чтобы следующий start_recording только возобновил поток (resume_stream).
+    ///
+    /// Соединение сразу ставится на паузу и живёт по тем же правилам, что и keep-alive после записи
+    /// (TTL, энергосбережение). Провайдеры, которые тарифицируют время соединения и не поддерживают
+    /// keep-alive, не прогреваются. Возвращает true, если соединение было открыто.
+    pub async fn prewarm(&self) -> Result<bool> {
+        if !self.config.read().await.prewarm_connection {
+            return Ok(false);
+        }
+        self.warm_connection().await
+    }
+
+    /// То же, что `prewarm`, но без проверки `prewarm_connection` — для warm pool,
+    /// который сам решает, когда пользователь, скорее всего, начнёт диктовать.
+    pub async fn warm_connection(&self) -> Result<bool> {
+        let config = self.session_config().await;
+        if *self.status.read().await != RecordingStatus::Idle {
+            return Ok(false);
+        }
+
+        // Микрофон готовим даже если соединение уже живое (после idle release)
+        if let Err(e) = self.audio_capture.write().await.prepare().await {
+            log::warn!("Failed to prepare audio capture during prewarm: {}", e);
+        }
+
+        if self.stt_provider.read().await.is_some() {
+            log::debug!("Prewarm skipped - connection already exists");
+            return Ok(false);
+        }
+
+        // Backend-only режим: keep-alive обязателен (см. update_config)
+        let keep_alive_allowed = config.keep_connection_alive || config.provider == SttProviderType::Backend;
+        if !keep_alive_allowed {
+            log::debug!("Prewarm skipped - keep-alive disabled for {:?}", config.provider);
+            return Ok(false);
+        }
+
+        let mut provider = self
+            .stt_factory
+            .create(&config)
+            .map_err(|e| anyhow::Error::new(e).context("Failed to create STT provider"))?;
+        if !provider.supports_keep_alive() {
+            log::debug!("Prewarm skipped - provider {} does not support keep-alive", provider.name());
+            return Ok(false);
+        }
+
+        provider
+            .initialize(&config)
+            .await
+            .map_err(|e| anyhow::Error::new(e).context("Failed to initialize STT provider"))?;
+
+        // До начала записи результаты никому не нужны — callbacks пустые,
+        // настоящие подставит resume_stream() в start_recording.
+        let started = provider
+            .start_stream(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_e: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await;
+        if let Err(e) = started {
+            let _ = provider.abort().await;
+            return Err(anyhow::Error::new(e).context("Failed to start STT stream"));
+        }
+        if let Err(e) = provider.pause_stream().await {
+            let _ = provider.abort().await;
+            return Err(anyhow::Error::new(e).context("Failed to pause prewarmed STT stream"));
+        }
+
+        // Пока мы подключались, пользователь мог уже начать запись (и создать своё соединение).
+        // Статус проверяем под write-lock провайдера: start_recording выставляет Starting
+        // до того, как смотрит на провайдера, поэтому гонки тут нет.
+        {
+            let mut provider_slot = self.stt_provider.write().await;
+            if provider_slot.is_some() || *self.status.read().await != RecordingStatus::Idle {
+                drop(provider_slot);
+                log::debug!("Prewarm result discarded - recording started meanwhile");
+                let _ = provider.abort().await;
+                return Ok(false);
+            }
+            *provider_slot = Some(provider);
+        }
+
+        if let Some(timer) = self.inactivity_timer_task.write().await.take() {
+            timer.abort();
+        }
+        let ttl_secs = Self::keep_alive_ttl_secs(&config);
+        self.schedule_idle_teardown(config.provider, ttl_secs, config.idle_teardown_secs.is_some()).await;
+
+        log::info!("STT connection prewarmed (paused until recording starts)");
+        Ok(true)
+    }
+
+    /// Энергосбережение без keep-alive: соединение уже закрыто, по таймеру отпускаем только микрофон
+    async fn schedule_audio_release(&self, config: &SttConfig) {
+        if let Some(idle_secs) = config.idle_teardown_secs {
+            self.schedule_idle_teardown(config.provider, idle_secs.max(MIN_IDLE_TTL_SECS), true).await;
+        }
+    }
+
+    /// Распознаёт готовый PCM буфер (i16 mono) отдельным соединением с текущим провайдером.
+    ///
+    /// Используется для перераспознавания "хвоста" восстановленной сессии после краша.
+    /// Не трогает активное/keep-alive соединение записи.
+    pub async fn transcribe_samples(&self, samples: &[i16], sample_rate: u32) -> Result<String> {
+        let config = self.config.read().await.clone();
+        self.transcribe_samples_with(&config, samples, sample_rate).await
+    }
+
+    /// REST клиент провайдера из `config` для готовых записей; None — у провайдера только стриминг
+    pub fn batch_provider(&self, config: &SttConfig) -> Result<Option<Arc<dyn BatchSttProvider>>> {
+        self.stt_factory
+            .create_batch(config)
+            .map_err(|e| anyhow::Error::new(e).context("Failed to create batch STT provider"))
+    }
+
+    /// REST клиент для коротких записей; None — порог выключен или у провайдера нет REST API
+    fn short_clip_batch(&self, config: &SttConfig) -> Option<(Arc<dyn BatchSttProvider>, u32)> {
+        let secs = config.short_clip_threshold_secs()?;
+        match self.batch_provider(config) {
+            Ok(batch) => batch.map(|batch| (batch, secs)),
+            Err(e) => {
+                log::warn!("Short clip REST path unavailable, streaming instead: {:#}", e);
+                None
+            }
+        }
+    }
+
+    /// Короткая запись целиком: один REST запрос → одна финальная фраза (ошибка — через on_error)
+    async fn transcribe_short_clip(&self, clip: ShortClipBuffer) {
+        let duration = clip.samples.len() as f64 / clip.sample_rate.max(1) as f64;
+        if clip.samples.is_empty() {
+            return;
+        }
+        log::info!("Transcribing {:.1}s short clip via {} REST API", duration, clip.batch.name());
+
+        match clip.batch.transcribe(&clip.samples, clip.sample_rate).await {
+            Ok(text) => {
+                let text = text.trim();
+                if !text.is_empty() {
+                    let mut transcription = Transcription::final_result(text.to_string());
+                    transcription.duration = duration;
+                    (clip.on_final)(transcription);
+                }
+            }
+            Err(e) => {
+                log::error!("Short clip transcription via {} failed: {}", clip.batch.name(), e);
+                (clip.on_error)(e);
+            }
+        }
+    }
+
+    /// Недосланное за сессию аудио распознаётся после финалов стрима: если связь так и не вернулась, это хвост записи
+    async fn recover_spooled_gap(&self) {
+        let gap = self.gap_recovery.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(gap) = gap {
+            gap.recover().await;
+        }
+    }
+
+    /// Кусок записи: через REST клиент, если он есть, иначе через стриминговый провайдер
+    pub async fn transcribe_chunk(
+        &self,
+        batch: Option<&dyn BatchSttProvider>,
+        config: &SttConfig,
+        samples: &[i16],
+        sample_rate: u32,
+    ) -> Result<String> {
+        match batch {
+            Some(batch) => batch
+                .transcribe(samples, sample_rate)
+                .await
+                .map_err(|e| anyhow::Error::new(e).context(format!("{} request failed", batch.name()))),
+            None => self.transcribe_samples_with(config, samples, sample_rate).await,
+        }
+    }
+
+    /// То же, что `transcribe_samples`, но с явным конфигом (сравнение провайдеров на одном сэмпле)
+    pub async fn transcribe_samples_with(&self, config: &SttConfig, samples: &[i16], sample_rate: u32) -> Result<String> {
+        transcribe_via_stream(self.stt_factory.as_ref(), config, samples, sample_rate).await
+    }
+
     /// Get current recording status
     pub async fn get_status(&self) -> RecordingStatus {
         *self.status.read().await
     }
 
+    /// Остаток квоты по последнему отчёту провайдера; None — неизвестен
+    /// или провайдер прямо сейчас занят отправкой аудио (не ждём, спросим позже)
+    pub fn remaining_quota_secs(&self) -> Option<f32> {
+        let provider = self.stt_provider.try_read().ok()?;
+        provider.as_ref()?.remaining_quota_secs()
+    }
+
+    /// Состояние провайдера и сессии для диагностики "зависших" записей.
+    ///
+    /// Провайдер не ждём дольше полсекунды: если он так долго занят, это и есть ответ (provider_locked).
+    pub async fn debug_state(&self) -> TranscriptionDebugState {
+        let status = *self.status.read().await;
+        let provider = tokio::time::timeout(Duration::from_millis(500), self.stt_provider.read()).await;
+        let (provider_name, provider_locked, provider_state) = match &provider {
+            Ok(guard) => match guard.as_ref() {
+                Some(provider) => (Some(provider.name().to_string()), false, provider.debug_state()),
+                None => (None, false, SttProviderDebugState::default()),
+            },
+            Err(_) => (None, true, SttProviderDebugState::default()),
+        };
+        drop(provider);
+
+        let processor_running = self
+            .audio_processor_task
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(|task| !task.is_finished());
+
+        TranscriptionDebugState {
+            status,
+            provider: provider_name,
+            provider_locked,
+            provider_state,
+            sent_chunks: self.diagnostics.sent_chunks(),
+            sent_bytes: self.diagnostics.sent_bytes(),
+            last_error: self.diagnostics.last_error(),
+            processor_running,
+        }
+    }
+
     /// Update STT configuration
     pub async fn update_config(&self, config: SttConfig) -> Result<()> {
         let prev_config = self.config.read().await.clone();
@@ -853,7 +2074,8 @@ impl TranscriptionService {
         let config_requires_new_connection =
             prev_config.provider != config.provider
                 || prev_config.language != config.language
-                || prev_config.deepgram_keyterms != config.deepgram_keyterms;
+                || prev_config.deepgram_keyterms != config.deepgram_keyterms
+                || prev_config.audio_encoding != config.audio_encoding;
 
         if config_requires_new_connection {
             let status = *self.status.read().await;
@@ -930,6 +2152,133 @@ impl TranscriptionService {
     }
 }
 
+/// Готовое аудио через отдельный стрим провайдера: чанки по 100ms, текст — собранные финальные фразы
+async fn transcribe_via_stream(
+    factory: &dyn SttProviderFactory,
+    config: &SttConfig,
+    samples: &[i16],
+    sample_rate: u32,
+) -> Result<String> {
+    if samples.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut provider = factory
+        .create(config)
+        .map_err(|e| anyhow::Error::new(e).context("Failed to create STT provider"))?;
+    if let Err(e) = provider.initialize(config).await {
+        let _ = provider.abort().await;
+        return Err(anyhow::Error::new(e).context("Failed to initialize STT provider"));
+    }
+
+    let finals = Arc::new(std::sync::Mutex::new(TranscriptAssembler::new()));
+    let finals_for_cb = finals.clone();
+    let on_final: TranscriptionCallback = Arc::new(move |t: Transcription| {
+        finals_for_cb.lock().unwrap_or_else(|e| e.into_inner()).commit(&t.text);
+    });
+    let on_partial: TranscriptionCallback = Arc::new(|_| {});
+    let on_error: ErrorCallback = Arc::new(|e| {
+        log::warn!("STT error while transcribing buffered audio: {}", e);
+    });
+    let on_connection_quality: ConnectionQualityCallback = Arc::new(|_, _| {});
+
+    if let Err(e) = provider
+        .start_stream(on_partial, on_final, on_error, on_connection_quality)
+        .await
+    {
+        let _ = provider.abort().await;
+        return Err(anyhow::Error::new(e).context("Failed to start STT stream"));
+    }
+
+    // Режем на чанки по 100ms, как при живой записи
+    let chunk_len = (sample_rate as usize / 10).max(1);
+    for part in samples.chunks(chunk_len) {
+        let chunk = AudioChunk::new(part.to_vec(), sample_rate, 1);
+        if let Err(e) = provider.send_audio(&chunk).await {
+            let _ = provider.abort().await;
+            return Err(anyhow::Error::new(e).context("Failed to send buffered audio"));
+        }
+    }
+
+    if let Err(e) = provider.stop_stream().await {
+        log::warn!("Failed to stop STT stream cleanly after buffered transcription: {}", e);
+        let _ = provider.abort().await;
+    }
+
+    let text = finals.lock().unwrap_or_else(|e| e.into_inner()).text().to_string();
+    Ok(text)
+}
+
+/// Разрыв из spool: REST клиентом провайдера, если он есть, иначе отдельным стримом
+async fn transcribe_gap(factory: &dyn SttProviderFactory, config: &SttConfig, gap: &SpooledAudio) -> Result<String> {
+    let batch = factory
+        .create_batch(config)
+        .map_err(|e| anyhow::Error::new(e).context("Failed to create batch STT provider"))?;
+    match batch {
+        Some(batch) => batch
+            .transcribe(&gap.samples, gap.sample_rate)
+            .await
+            .map_err(|e| anyhow::Error::new(e).context(format!("{} request failed", batch.name()))),
+        None => transcribe_via_stream(factory, config, &gap.samples, gap.sample_rate).await,
+    }
+}
+
+/// Пишет в журнал чанк, выброшенный backpressure, с тем же усилением, что и у отправленного аудио
+fn journal_dropped_audio(journal: &dyn SessionJournal, chunk: &AudioChunk, sensitivity: u8) {
+    let peak = chunk.data.iter().map(|&s| (s as i32).abs()).max().unwrap_or(0);
+    let gain = limited_gain(sensitivity_gain(sensitivity), peak);
+    let amplified = AudioChunk {
+        data: apply_gain(&chunk.data, gain),
+        ..chunk.clone()
+    };
+    journal.append_audio(&mono_samples(&amplified), amplified.sample_rate);
+}
+
+/// Моно-копия чанка для журнала и spool: каналы стерео смешиваются
+fn mono_samples(chunk: &AudioChunk) -> std::borrow::Cow<'_, [i16]> {
+    if chunk.channels <= 1 {
+        return std::borrow::Cow::Borrowed(&chunk.data);
+    }
+    let channels = chunk.channels as usize;
+    std::borrow::Cow::Owned(
+        chunk
+            .data
+            .chunks_exact(channels)
+            .map(|frame| (frame.iter().map(|&s| s as i32).sum::<i32>() / channels as i32) as i16)
+            .collect(),
+    )
+}
+
+/// Создаёт провайдера и открывает стрим; при ошибке провайдер закрывается
+async fn connect_stream(
+    factory: &dyn SttProviderFactory,
+    config: &SttConfig,
+    on_partial: TranscriptionCallback,
+    on_final: TranscriptionCallback,
+    on_error: ErrorCallback,
+    on_connection_quality: ConnectionQualityCallback,
+) -> Result<Box<dyn SttProvider>> {
+    let mut provider = factory
+        .create(config)
+        .map_err(|e| anyhow::Error::new(e).context("Failed to create STT provider"))?;
+
+    if let Err(e) = provider.initialize(config).await {
+        log::error!("Failed to initialize STT provider: {}", e);
+        let _ = provider.abort().await;
+        return Err(anyhow::Error::new(e).context("Failed to initialize STT provider"));
+    }
+
+    if let Err(e) = provider
+        .start_stream(on_partial, on_final, on_error, on_connection_quality)
+        .await
+    {
+        let _ = provider.abort().await;
+        return Err(anyhow::Error::new(e).context("Failed to start STT stream"));
+    }
+
+    Ok(provider)
+}
+
 // Ensure TranscriptionService is thread-safe
 unsafe impl Send for TranscriptionService {}
 unsafe impl Sync for TranscriptionService {}
@@ -1124,7 +2473,12 @@ mod tests {
                 }
                 SttError::Authentication(_) => "authentication",
                 SttError::Configuration(_) => "configuration",
-                SttError::Processing(_) | SttError::Internal(_) | SttError::Unsupported(_) => "processing",
+                SttError::RateLimited { .. } => "connection",
+                SttError::QuotaExceeded { .. } => "limit_exceeded",
+                SttError::Processing(_)
+                | SttError::Internal(_)
+                | SttError::Unsupported(_)
+                | SttError::ProviderBug(_) => "processing",
             }
             .to_string();
             let _ = err_tx.send((err.to_string(), typ));
@@ -1186,6 +2540,8 @@ mod tests {
             aborted: provider_aborted.clone(),
         });
         let service = TranscriptionService::new(Box::new(audio_capture), factory);
+        // Без pre-roll стрим открывается до старта микрофона
+        service.config.write().await.pre_roll_ms = 0;
 
         let on_partial: TranscriptionCallback = Arc::new(|_t| {});
         let on_final: TranscriptionCallback = Arc::new(|_t| {});
@@ -1291,4 +2647,1324 @@ mod tests {
         assert_eq!(service.get_status().await, RecordingStatus::Idle);
         assert!(provider_aborted.load(Ordering::SeqCst));
     }
+
+    #[derive(Default)]
+    struct RecordingJournal {
+        begun: AtomicBool,
+        completed: AtomicBool,
+        samples: std::sync::atomic::AtomicU64,
+    }
+
+    impl SessionJournal for RecordingJournal {
+        fn begin(&self, _provider: &str) {
+            self.begun.store(true, Ordering::SeqCst);
+        }
+
+        fn append_audio(&self, samples: &[i16], _sample_rate: u32) {
+            self.samples.fetch_add(samples.len() as u64, Ordering::SeqCst);
+        }
+
+        fn append_final(&self, _text: &str) {}
+
+        fn complete(&self) {
+            self.completed.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn journal_keeps_audio_when_recording_dies_without_clean_stop() {
+        let journal = Arc::new(RecordingJournal::default());
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 32);
+        let factory = Arc::new(TestFactory {
+            aborted: Arc::new(AtomicBool::new(false)),
+        });
+        let service = TranscriptionService::new(Box::new(audio_capture), factory)
+            .with_session_journal(journal.clone());
+
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await
+            .expect("recording must start");
+
+        tokio::time::timeout(Duration::from_secs(3), async {
+            loop {
+                if service.get_status().await == RecordingStatus::Idle {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("status must become Idle");
+
+        // Отправка падала, но аудио всё равно попало в журнал и он не закрыт — сессию можно восстановить.
+        assert!(journal.begun.load(Ordering::SeqCst));
+        assert!(journal.samples.load(Ordering::SeqCst) > 0);
+        assert!(!journal.completed.load(Ordering::SeqCst));
+    }
+
+    struct ReleaseTrackingCapture {
+        config: AudioConfig,
+        releases: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl AudioCapture for ReleaseTrackingCapture {
+        async fn initialize(&mut self, config: AudioConfig) -> AudioResult<()> {
+            self.config = config;
+            Ok(())
+        }
+
+        async fn start_capture(&mut self, _on_chunk: crate::domain::AudioChunkCallback) -> AudioResult<()> {
+            Ok(())
+        }
+
+        async fn stop_capture(&mut self) -> AudioResult<()> {
+            Ok(())
+        }
+
+        async fn release(&mut self) -> AudioResult<()> {
+            self.releases.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn is_capturing(&self) -> bool {
+            false
+        }
+
+        fn config(&self) -> AudioConfig {
+            self.config
+        }
+    }
+
+    #[tokio::test]
+    async fn idle_teardown_releases_audio_and_closes_provider() {
+        let releases = Arc::new(AtomicUsize::new(0));
+        let audio_capture = ReleaseTrackingCapture {
+            config: AudioConfig::default(),
+            releases: releases.clone(),
+        };
+        let factory = Arc::new(TestFactory {
+            aborted: Arc::new(AtomicBool::new(false)),
+        });
+        let service = TranscriptionService::new(Box::new(audio_capture), factory.clone());
+
+        // Имитируем keep-alive соединение, оставшееся после предыдущей записи
+        *service.stt_provider.write().await = Some(factory.create(&SttConfig::default()).unwrap());
+
+        service.schedule_idle_teardown(SttProviderType::Backend, 0, true).await;
+        let timer = service.inactivity_timer_task.write().await.take().unwrap();
+        timer.await.unwrap();
+
+        assert_eq!(releases.load(Ordering::SeqCst), 1);
+        assert!(service.stt_provider.read().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn idle_teardown_reports_closed_connection_with_billing_policy() {
+        let factory = Arc::new(TestFactory {
+            aborted: Arc::new(AtomicBool::new(false)),
+        });
+        let audio_capture = ReleaseTrackingCapture {
+            config: AudioConfig::default(),
+            releases: Arc::new(AtomicUsize::new(0)),
+        };
+        let service = TranscriptionService::new(Box::new(audio_capture), factory.clone());
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let reports_for_listener = reports.clone();
+        service.set_idle_connection_listener(Some(Arc::new(move |report: IdleConnectionReport| {
+            reports_for_listener.lock().unwrap().push(report);
+        })));
+
+        *service.stt_provider.write().await = Some(factory.create(&SttConfig::default()).unwrap());
+        service.schedule_idle_teardown(SttProviderType::AssemblyAI, 0, false).await;
+        let timer = service.inactivity_timer_task.write().await.take().unwrap();
+        timer.await.unwrap();
+
+        assert_eq!(
+            *reports.lock().unwrap(),
+            vec![IdleConnectionReport {
+                provider: SttProviderType::AssemblyAI,
+                held_secs: 0,
+                limit_secs: 0,
+                billed_while_idle: true,
+                closed: true,
+            }]
+        );
+
+        // Соединения нет — и отчитываться не о чем
+        service.schedule_idle_teardown(SttProviderType::AssemblyAI, 0, false).await;
+        let timer = service.inactivity_timer_task.write().await.take().unwrap();
+        timer.await.unwrap();
+        assert_eq!(reports.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn keep_alive_ttl_is_capped_for_providers_billed_while_idle() {
+        let mut config = SttConfig::new(SttProviderType::AssemblyAI);
+        config.keep_alive_ttl_secs = 600;
+        assert_eq!(TranscriptionService::keep_alive_ttl_secs(&config), crate::domain::ASSEMBLYAI_MAX_IDLE_SECS);
+
+        config.provider = SttProviderType::Deepgram;
+        assert_eq!(TranscriptionService::keep_alive_ttl_secs(&config), 600);
+    }
+
+    #[tokio::test]
+    async fn idle_teardown_skips_release_if_recording_restarted() {
+        let releases = Arc::new(AtomicUsize::new(0));
+        let audio_capture = ReleaseTrackingCapture {
+            config: AudioConfig::default(),
+            releases: releases.clone(),
+        };
+        let factory = Arc::new(TestFactory {
+            aborted: Arc::new(AtomicBool::new(false)),
+        });
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+
+        service.schedule_idle_teardown(SttProviderType::Backend, 0, true).await;
+        *service.status.write().await = RecordingStatus::Recording;
+        let timer = service.inactivity_timer_task.write().await.take().unwrap();
+        timer.await.unwrap();
+
+        assert_eq!(releases.load(Ordering::SeqCst), 0);
+
+        // Без энергосбережения микрофон не отпускаем вовсе
+        *service.status.write().await = RecordingStatus::Idle;
+        service.schedule_audio_release(&SttConfig::default()).await;
+        assert!(service.inactivity_timer_task.read().await.is_none());
+    }
+
+    struct KeepAliveProvider {
+        alive: bool,
+        resumed: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl SttProvider for KeepAliveProvider {
+        async fn initialize(&mut self, _config: &SttConfig) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            _on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            self.alive = true;
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, _chunk: &crate::domain::AudioChunk) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            self.alive = false;
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> SttResult<()> {
+            self.alive = false;
+            Ok(())
+        }
+
+        async fn pause_stream(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn resume_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            _on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            self.resumed.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "keep_alive"
+        }
+
+        fn supports_keep_alive(&self) -> bool {
+            true
+        }
+
+        fn is_connection_alive(&self) -> bool {
+            self.alive
+        }
+
+        fn is_online(&self) -> bool {
+            true
+        }
+    }
+
+    struct KeepAliveFactory {
+        created: Arc<AtomicUsize>,
+        resumed: Arc<AtomicUsize>,
+    }
+
+    impl SttProviderFactory for KeepAliveFactory {
+        fn create(&self, _config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            self.created.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::new(KeepAliveProvider {
+                alive: false,
+                resumed: self.resumed.clone(),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn prewarmed_connection_is_resumed_by_start_recording() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let resumed = Arc::new(AtomicUsize::new(0));
+        let factory = Arc::new(KeepAliveFactory {
+            created: created.clone(),
+            resumed: resumed.clone(),
+        });
+        let audio_capture = ReleaseTrackingCapture {
+            config: AudioConfig::default(),
+            releases: Arc::new(AtomicUsize::new(0)),
+        };
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+
+        // По умолчанию prewarm выключен
+        assert!(!service.prewarm().await.unwrap());
+        assert_eq!(created.load(Ordering::SeqCst), 0);
+
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.keep_connection_alive = true;
+        config.prewarm_connection = true;
+        *service.config.write().await = config;
+
+        assert!(service.prewarm().await.unwrap());
+        // Повторный prewarm не открывает второе соединение
+        assert!(!service.prewarm().await.unwrap());
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await
+            .expect("recording must start");
+
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+        assert_eq!(resumed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn session_language_override_does_not_reuse_keep_alive_connection() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let resumed = Arc::new(AtomicUsize::new(0));
+        let factory = Arc::new(KeepAliveFactory {
+            created: created.clone(),
+            resumed: resumed.clone(),
+        });
+        let audio_capture = ReleaseTrackingCapture {
+            config: AudioConfig::default(),
+            releases: Arc::new(AtomicUsize::new(0)),
+        };
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+
+        let mut config = SttConfig::new(SttProviderType::Deepgram).with_language("ru");
+        config.keep_connection_alive = true;
+        config.prewarm_connection = true;
+        *service.config.write().await = config;
+        assert!(service.prewarm().await.unwrap());
+
+        let start = || {
+            service.start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+        };
+
+        // Прогретое соединение на "ru" не подходит для сессии на "en"
+        service
+            .set_next_session_override(Some(SttSessionOverride::language("en")))
+            .await;
+        start().await.expect("recording must start");
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+        assert_eq!(resumed.load(Ordering::SeqCst), 0);
+        // Override только на одну сессию, сохранённый конфиг не меняется
+        assert_eq!(service.get_config().await.language, "ru");
+
+        // Следующая обычная сессия не должна подхватить соединение с языком "en"
+        *service.status.write().await = RecordingStatus::Idle;
+        start().await.expect("recording must start");
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+        assert_eq!(resumed.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn prewarm_skipped_when_keep_alive_is_disabled() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let factory = Arc::new(KeepAliveFactory {
+            created: created.clone(),
+            resumed: Arc::new(AtomicUsize::new(0)),
+        });
+        let audio_capture = ReleaseTrackingCapture {
+            config: AudioConfig::default(),
+            releases: Arc::new(AtomicUsize::new(0)),
+        };
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+
+        // AssemblyAI тарифицирует время соединения — без явного keep-alive не прогреваем
+        let mut config = SttConfig::new(SttProviderType::AssemblyAI);
+        config.prewarm_connection = true;
+        *service.config.write().await = config;
+
+        assert!(!service.prewarm().await.unwrap());
+        assert_eq!(created.load(Ordering::SeqCst), 0);
+    }
+
+    struct SlowSendProvider;
+
+    #[async_trait]
+    impl SttProvider for SlowSendProvider {
+        async fn initialize(&mut self, _config: &SttConfig) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            _on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, _chunk: &crate::domain::AudioChunk) -> SttResult<()> {
+            // Имитируем подвисший WS send
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "slow_send"
+        }
+
+        fn is_online(&self) -> bool {
+            true
+        }
+    }
+
+    struct SlowSendFactory;
+
+    impl SttProviderFactory for SlowSendFactory {
+        fn create(&self, _config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            Ok(Box::new(SlowSendProvider))
+        }
+    }
+
+    #[tokio::test]
+    async fn drops_oldest_audio_when_provider_is_slower_than_capture() {
+        // 32 чанка по 10мс приходят за ~70мс, а одна отправка занимает 150мс
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 32);
+        let journal = Arc::new(RecordingJournal::default());
+        let service = TranscriptionService::new(Box::new(audio_capture), Arc::new(SlowSendFactory))
+            .with_backpressure_policy(BackpressurePolicy {
+                max_backlog_ms: 100,
+                warn_backlog_ms: 50,
+            })
+            .with_session_journal(journal.clone());
+
+        let (quality_tx, mut quality_rx) = tokio::sync::mpsc::unbounded_channel::<(String, Option<ConnectionQualityReason>)>();
+        let on_quality: ConnectionQualityCallback = Arc::new(move |q, r| {
+            let _ = quality_tx.send((q, r));
+        });
+
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                on_quality,
+            )
+            .await
+            .expect("recording must start");
+
+        // Сначала может прийти предупреждение об отставании, затем — о выброшенном аудио
+        tokio::time::timeout(Duration::from_secs(3), async {
+            loop {
+                let (quality, reason) = quality_rx.recv().await.expect("quality payload");
+                assert_eq!(quality, "Poor");
+                if matches!(reason, Some(ConnectionQualityReason::AudioDropped { .. })) {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("must report dropped audio");
+
+        // Выброшенное провайдеру аудио всё равно целиком лежит в журнале
+        let expected = 32 * (160 / AudioConfig::default().channels.max(1) as u64);
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while journal.samples.load(Ordering::SeqCst) < expected {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("dropped audio must reach the journal");
+
+        let _ = service.stop_recording_hard().await;
+    }
+
+    #[derive(Default)]
+    struct MemoryCorrectionStore {
+        entries: std::sync::Mutex<Vec<crate::domain::CorrectionEntry>>,
+    }
+
+    impl crate::domain::CorrectionStore for MemoryCorrectionStore {
+        fn load(&self) -> crate::domain::CorrectionStoreResult<Vec<crate::domain::CorrectionEntry>> {
+            Ok(self.entries.lock().unwrap().clone())
+        }
+
+        fn save(&self, entries: &[crate::domain::CorrectionEntry]) -> crate::domain::CorrectionStoreResult<()> {
+            *self.entries.lock().unwrap() = entries.to_vec();
+            Ok(())
+        }
+    }
+
+    /// Провайдер, который запоминает keyterms и сразу отдаёт одну финальную фразу
+    struct EchoFinalProvider {
+        keyterms: Arc<std::sync::Mutex<Option<String>>>,
+        text: String,
+    }
+
+    #[async_trait]
+    impl SttProvider for EchoFinalProvider {
+        async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+            *self.keyterms.lock().unwrap() = config.deepgram_keyterms.clone();
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            on_final(Transcription::final_result(self.text.clone()));
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, _chunk: &crate::domain::AudioChunk) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "echo_final"
+        }
+
+        fn is_online(&self) -> bool {
+            true
+        }
+    }
+
+    struct EchoFinalFactory {
+        keyterms: Arc<std::sync::Mutex<Option<String>>>,
+        text: String,
+    }
+
+    impl SttProviderFactory for EchoFinalFactory {
+        fn create(&self, _config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            Ok(Box::new(EchoFinalProvider {
+                keyterms: self.keyterms.clone(),
+                text: self.text.clone(),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn corrections_are_applied_to_finals_and_boosted_as_keyterms() {
+        let engine = Arc::new(CorrectionEngine::new(Arc::new(MemoryCorrectionStore::default())));
+        engine.learn("кубер", "Kubernetes").unwrap();
+
+        let keyterms = Arc::new(std::sync::Mutex::new(None));
+        let factory = Arc::new(EchoFinalFactory {
+            keyterms: keyterms.clone(),
+            text: "Деплой в кубер".to_string(),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory)
+            .with_correction_engine(engine);
+
+        let finals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let finals_cb = finals.clone();
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(move |t: Transcription| finals_cb.lock().unwrap().push(t.text)),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await
+            .expect("recording must start");
+
+        assert_eq!(*finals.lock().unwrap(), vec!["Деплой в Kubernetes".to_string()]);
+        assert_eq!(keyterms.lock().unwrap().as_deref(), Some("Kubernetes"));
+
+        // Сохранённый конфиг не трогаем — keyterms добавляются только в соединение
+        assert_eq!(service.get_config().await.deepgram_keyterms, None);
+    }
+
+    #[tokio::test]
+    async fn team_glossary_is_merged_after_local_keyterms() {
+        let keyterms = Arc::new(std::sync::Mutex::new(None));
+        let factory = Arc::new(EchoFinalFactory {
+            keyterms: keyterms.clone(),
+            text: "Деплой".to_string(),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.deepgram_keyterms = Some("Kubernetes".to_string());
+        service.update_config(config).await.unwrap();
+        service.set_team_glossary(vec!["kubernetes".to_string(), "VoicetextAI".to_string()]);
+
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await
+            .expect("recording must start");
+
+        assert_eq!(keyterms.lock().unwrap().as_deref(), Some("Kubernetes, VoicetextAI"));
+        assert_eq!(service.get_config().await.deepgram_keyterms.as_deref(), Some("Kubernetes"));
+    }
+
+    struct ProviderTrackingFactory {
+        providers: Arc<std::sync::Mutex<Vec<SttProviderType>>>,
+    }
+
+    impl SttProviderFactory for ProviderTrackingFactory {
+        fn create(&self, config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            self.providers.lock().unwrap().push(config.provider);
+            Ok(Box::new(EchoFinalProvider {
+                keyterms: Arc::new(std::sync::Mutex::new(None)),
+                text: "Привет".to_string(),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn offline_fallback_replaces_only_network_providers() {
+        let providers = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let factory = Arc::new(ProviderTrackingFactory {
+            providers: providers.clone(),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+        service
+            .update_config(SttConfig::new(SttProviderType::Deepgram).with_language("ru"))
+            .await
+            .unwrap();
+        service.set_offline_fallback(Some(SttSessionOverride {
+            provider: Some(SttProviderType::WhisperLocal),
+            model: Some("base".to_string()),
+            ..Default::default()
+        }));
+
+        let start = || {
+            service.start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+        };
+        start().await.expect("recording must start");
+
+        // Сеть вернулась — снова сохранённый провайдер
+        *service.status.write().await = RecordingStatus::Idle;
+        service.set_offline_fallback(None);
+        start().await.expect("recording must start");
+
+        assert_eq!(
+            *providers.lock().unwrap(),
+            vec![SttProviderType::WhisperLocal, SttProviderType::Deepgram]
+        );
+        assert_eq!(service.get_config().await.provider, SttProviderType::Deepgram);
+    }
+
+    #[tokio::test]
+    async fn finals_are_normalized_when_enabled() {
+        let factory = Arc::new(EchoFinalFactory {
+            keyterms: Arc::new(std::sync::Mutex::new(None)),
+            text: "Встреча третьего мая, бюджет двести рублей".to_string(),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+
+        let mut config = SttConfig::new(SttProviderType::Deepgram).with_language("ru");
+        config.normalization.enabled = true;
+        service.update_config(config).await.unwrap();
+
+        let finals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let finals_cb = finals.clone();
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(move |t: Transcription| finals_cb.lock().unwrap().push(t.text)),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await
+            .expect("recording must start");
+
+        assert_eq!(
+            *finals.lock().unwrap(),
+            vec!["Встреча 3 мая, бюджет 200 ₽".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn fillers_are_removed_from_finals_when_enabled() {
+        let factory = Arc::new(EchoFinalFactory {
+            keyterms: Arc::new(std::sync::Mutex::new(None)),
+            text: "Ну, эээ, бюджет двести рублей".to_string(),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+
+        let mut config = SttConfig::new(SttProviderType::Deepgram).with_language("ru");
+        config.filler_removal.enabled = true;
+        service.update_config(config).await.unwrap();
+
+        assert_eq!(record_single_final(&service).await, vec!["Бюджет двести рублей".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn voice_commands_are_cut_from_finals_and_executed() {
+        let factory = Arc::new(EchoFinalFactory {
+            keyterms: Arc::new(std::sync::Mutex::new(None)),
+            text: "Бюджет утверждён, paste that".to_string(),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+        let commands = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let commands_cb = commands.clone();
+        service.set_voice_command_listener(Some(Arc::new(move |command| commands_cb.lock().unwrap().push(command))));
+
+        let mut config = SttConfig::new(SttProviderType::Deepgram).with_language("ru");
+        config.voice_commands = true;
+        service.update_config(config).await.unwrap();
+
+        assert_eq!(record_single_final(&service).await, vec!["Бюджет утверждён".to_string()]);
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec![crate::application::VoiceCommand::PasteText {
+                text: "Бюджет утверждён".to_string()
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn comparison_provider_gets_the_same_session_and_is_tagged() {
+        let factory = Arc::new(EchoFinalFactory {
+            keyterms: Arc::new(std::sync::Mutex::new(None)),
+            text: "Бюджет утверждён".to_string(),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+        let compared = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let compared_cb = compared.clone();
+        service.set_comparison_listener(Some(Arc::new(move |t: crate::application::ComparisonTranscript| {
+            compared_cb.lock().unwrap().push((t.provider, t.primary, t.transcript));
+        })));
+
+        let mut config = SttConfig::new(SttProviderType::Deepgram).with_language("ru");
+        config.comparison_provider = Some(SttProviderType::WhisperLocal);
+        service.update_config(config).await.unwrap();
+
+        // Основной текст идёт как обычно, в сравнение — текст обоих провайдеров с меткой
+        assert_eq!(record_single_final(&service).await, vec!["Бюджет утверждён".to_string()]);
+        let mut compared = compared.lock().unwrap().clone();
+        compared.sort_by_key(|(_, primary, _)| *primary);
+        assert_eq!(
+            compared,
+            vec![
+                (SttProviderType::WhisperLocal, false, "Бюджет утверждён".to_string()),
+                (SttProviderType::Deepgram, true, "Бюджет утверждён".to_string()),
+            ]
+        );
+
+        service.stop_recording().await.unwrap();
+        assert!(service.comparison_stream.read().await.is_none());
+    }
+
+    /// Punctuator-заглушка: ставит точку и заглавную букву
+    struct SentencePunctuator;
+
+    impl Punctuator for SentencePunctuator {
+        fn punctuate(&self, text: &str) -> crate::domain::PunctuationResult<String> {
+            let mut chars = text.chars();
+            let first = chars.next().map(|c| c.to_uppercase().collect::<String>()).unwrap_or_default();
+            Ok(format!("{}{}.", first, chars.as_str()))
+        }
+
+        fn name(&self) -> &str {
+            "sentence"
+        }
+    }
+
+    async fn record_single_final(service: &TranscriptionService) -> Vec<String> {
+        let finals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let finals_cb = finals.clone();
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(move |t: Transcription| finals_cb.lock().unwrap().push(t.text)),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await
+            .expect("recording must start");
+        finals.lock().map(|f| f.clone()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn local_punctuation_is_applied_only_when_enabled() {
+        let make_service = || {
+            let factory = Arc::new(EchoFinalFactory {
+                keyterms: Arc::new(std::sync::Mutex::new(None)),
+                text: "привет мир".to_string(),
+            });
+            let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+            TranscriptionService::new(Box::new(audio_capture), factory).with_punctuator(Arc::new(SentencePunctuator))
+        };
+
+        let disabled = make_service();
+        assert_eq!(record_single_final(&disabled).await, vec!["привет мир".to_string()]);
+
+        let enabled = make_service();
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.punctuate_locally = true;
+        enabled.update_config(config).await.unwrap();
+        assert_eq!(record_single_final(&enabled).await, vec!["Привет мир.".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn stop_recording_reports_session_stats() {
+        let factory = Arc::new(EchoFinalFactory {
+            keyterms: Arc::new(std::sync::Mutex::new(None)),
+            text: "раз два три".to_string(),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 0);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let reported_cb = reported.clone();
+        let tracker = service.session_stats_tracker();
+        tracker.begin_session(42);
+        tracker.set_listener(Some(Arc::new(move |stats: crate::domain::SessionStats| {
+            reported_cb.lock().unwrap().push(stats);
+        })));
+
+        assert_eq!(record_single_final(&service).await, vec!["раз два три".to_string()]);
+        service.stop_recording().await.expect("recording must stop");
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].session_id, 42);
+        assert_eq!(reported[0].word_count, 3);
+        assert_eq!(reported[0].language.as_deref(), Some("ru"));
+    }
+
+    struct FixedBatchProvider {
+        requested_samples: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl BatchSttProvider for FixedBatchProvider {
+        async fn transcribe(&self, samples: &[i16], _sample_rate: u32) -> SttResult<String> {
+            self.requested_samples.store(samples.len(), Ordering::SeqCst);
+            Ok(" короткая фраза ".to_string())
+        }
+
+        fn name(&self) -> &str {
+            "fixed_batch"
+        }
+    }
+
+    struct CountingSendProvider {
+        sent_samples: Arc<AtomicUsize>,
+        connect_delay: Duration,
+    }
+
+    #[async_trait]
+    impl SttProvider for CountingSendProvider {
+        async fn initialize(&mut self, _config: &SttConfig) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            _on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            tokio::time::sleep(self.connect_delay).await;
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, chunk: &crate::domain::AudioChunk) -> SttResult<()> {
+            self.sent_samples.fetch_add(chunk.data.len(), Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "counting_send"
+        }
+
+        fn is_online(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Default)]
+    struct ShortClipFactory {
+        created: Arc<AtomicUsize>,
+        sent_samples: Arc<AtomicUsize>,
+        requested_samples: Arc<AtomicUsize>,
+    }
+
+    impl SttProviderFactory for ShortClipFactory {
+        fn create(&self, _config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            self.created.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::new(CountingSendProvider {
+                sent_samples: self.sent_samples.clone(),
+                connect_delay: Duration::ZERO,
+            }))
+        }
+
+        fn create_batch(&self, _config: &SttConfig) -> SttResult<Option<Arc<dyn BatchSttProvider>>> {
+            Ok(Some(Arc::new(FixedBatchProvider {
+                requested_samples: self.requested_samples.clone(),
+            })))
+        }
+    }
+
+    async fn start_short_clip_session(service: &TranscriptionService, finals: Arc<std::sync::Mutex<Vec<String>>>) {
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(move |t: Transcription| finals.lock().unwrap().push(t.text)),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await
+            .expect("recording must start");
+    }
+
+    #[tokio::test]
+    async fn short_recording_is_transcribed_with_one_batch_request() {
+        let factory = Arc::new(ShortClipFactory::default());
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 10);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory.clone());
+        service.update_config(SttConfig::new(SttProviderType::Deepgram)).await.unwrap();
+
+        let finals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        start_short_clip_session(&service, finals.clone()).await;
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        service.stop_recording().await.expect("stop must succeed");
+
+        // Стрим так и не открывался: весь буфер ушёл одним запросом и вернулся одной финальной фразой
+        assert_eq!(factory.created.load(Ordering::SeqCst), 0);
+        assert_eq!(factory.requested_samples.load(Ordering::SeqCst), 10 * 160);
+        assert_eq!(*finals.lock().unwrap(), vec!["короткая фраза".to_string()]);
+        assert_eq!(service.get_status().await, RecordingStatus::Idle);
+    }
+
+    #[tokio::test]
+    async fn recording_longer_than_threshold_switches_to_streaming_with_buffered_audio() {
+        let factory = Arc::new(ShortClipFactory::default());
+        // 1 секунда при 16 кГц = 100 чанков по 160 семплов
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 120);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory.clone());
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.short_clip_secs = 1;
+        service.update_config(config).await.unwrap();
+
+        let finals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        start_short_clip_session(&service, finals.clone()).await;
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while factory.sent_samples.load(Ordering::SeqCst) < 120 * 160 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("all audio (buffered and live) must reach the stream");
+        service.stop_recording().await.expect("stop must succeed");
+
+        assert_eq!(factory.created.load(Ordering::SeqCst), 1);
+        assert_eq!(factory.requested_samples.load(Ordering::SeqCst), 0);
+        assert!(finals.lock().unwrap().is_empty());
+    }
+
+    struct SlowConnectFactory {
+        sent_samples: Arc<AtomicUsize>,
+        connect_delay: Duration,
+    }
+
+    impl SttProviderFactory for SlowConnectFactory {
+        fn create(&self, _config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            Ok(Box::new(CountingSendProvider {
+                sent_samples: self.sent_samples.clone(),
+                connect_delay: self.connect_delay,
+            }))
+        }
+    }
+
+    struct RejectingFactory;
+
+    impl SttProviderFactory for RejectingFactory {
+        fn create(&self, _config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            Err(SttError::Authentication("simulated invalid key".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn audio_recorded_while_stream_opens_is_sent_first() {
+        let sent_samples = Arc::new(AtomicUsize::new(0));
+        let factory = Arc::new(SlowConnectFactory {
+            sent_samples: sent_samples.clone(),
+            // Все 20 чанков приходят, пока стрим ещё подключается
+            connect_delay: Duration::from_millis(200),
+        });
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 20);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory);
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.short_clip_secs = 0;
+        service.update_config(config).await.unwrap();
+
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await
+            .expect("recording must start");
+
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while sent_samples.load(Ordering::SeqCst) < 20 * 160 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("pre-roll audio must reach the stream");
+
+        let _ = service.stop_recording_hard().await;
+    }
+
+    #[tokio::test]
+    async fn microphone_is_stopped_if_stream_fails_to_open_after_fast_start() {
+        let capture_stopped = Arc::new(AtomicBool::new(false));
+        let audio_capture = BurstAudioCapture::new(capture_stopped.clone(), 20);
+        let service = TranscriptionService::new(Box::new(audio_capture), Arc::new(RejectingFactory));
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.short_clip_secs = 0;
+        service.update_config(config).await.unwrap();
+
+        let result = service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(|_q, _r| {}),
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert!(capture_stopped.load(Ordering::SeqCst));
+        assert_eq!(service.get_status().await, RecordingStatus::Idle);
+    }
+
+    /// Стрим, у которого задача чтения ответов уже умерла (receiver_dead) или жива
+    struct ReceiverHealthProvider {
+        receiver_dead: bool,
+    }
+
+    #[async_trait]
+    impl SttProvider for ReceiverHealthProvider {
+        async fn initialize(&mut self, _config: &SttConfig) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            _on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, _chunk: &crate::domain::AudioChunk) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "receiver_health"
+        }
+
+        fn is_online(&self) -> bool {
+            true
+        }
+
+        fn debug_state(&self) -> SttProviderDebugState {
+            SttProviderDebugState {
+                streaming: true,
+                receiver_alive: Some(!self.receiver_dead),
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Первый стрим открывается с мёртвой задачей чтения, следующие — здоровые
+    #[derive(Default)]
+    struct DeadReceiverOnceFactory {
+        created: Arc<AtomicUsize>,
+    }
+
+    impl SttProviderFactory for DeadReceiverOnceFactory {
+        fn create(&self, _config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            let created = self.created.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::new(ReceiverHealthProvider { receiver_dead: created == 0 }))
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_with_dead_receiver_task_is_reopened() {
+        let factory = Arc::new(DeadReceiverOnceFactory::default());
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 20);
+        let service = TranscriptionService::new(Box::new(audio_capture), factory.clone());
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.short_clip_secs = 0;
+        service.update_config(config).await.unwrap();
+
+        let reasons = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let reasons_in_callback = reasons.clone();
+        service
+            .start_recording(
+                Arc::new(|_t| {}),
+                Arc::new(|_t| {}),
+                Arc::new(|_l| {}),
+                Arc::new(|_b| {}),
+                Arc::new(|_err: SttError| {}),
+                Arc::new(move |_q, reason| reasons_in_callback.lock().unwrap().extend(reason)),
+            )
+            .await
+            .expect("recording must start");
+
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while factory.created.load(Ordering::SeqCst) < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("stream with a dead receiver must be reopened");
+
+        assert_eq!(service.get_status().await, RecordingStatus::Recording);
+        assert!(reasons.lock().unwrap().contains(&ConnectionQualityReason::StreamRestarted {
+            attempt: 1,
+            max_attempts: MAX_STREAM_RESTARTS,
+        }));
+
+        let _ = service.stop_recording_hard().await;
+    }
+
+    #[derive(Default)]
+    struct MemoryAudioSpool {
+        samples: std::sync::Mutex<Vec<i16>>,
+    }
+
+    impl AudioSpool for MemoryAudioSpool {
+        fn append(&self, samples: &[i16], _sample_rate: u32) {
+            self.samples.lock().unwrap().extend_from_slice(samples);
+        }
+
+        fn pending_samples(&self) -> usize {
+            self.samples.lock().unwrap().len()
+        }
+
+        fn take(&self) -> Option<SpooledAudio> {
+            let samples = std::mem::take(&mut *self.samples.lock().unwrap());
+            (!samples.is_empty()).then_some(SpooledAudio { samples, sample_rate: 16000 })
+        }
+    }
+
+    /// Первые `failures` отправок падают (сеть «подвисла»), дальше стрим снова принимает аудио
+    struct StallingSendProvider {
+        failures: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl SttProvider for StallingSendProvider {
+        async fn initialize(&mut self, _config: &SttConfig) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            _on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, _chunk: &crate::domain::AudioChunk) -> SttResult<()> {
+            if self.failures.load(Ordering::SeqCst) > 0 {
+                self.failures.fetch_sub(1, Ordering::SeqCst);
+                return Err(SttError::Connection(crate::domain::SttConnectionError::simple("simulated stall")));
+            }
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "stalling_send"
+        }
+
+        fn is_online(&self) -> bool {
+            true
+        }
+    }
+
+    struct StallingFactory {
+        failures: Arc<AtomicUsize>,
+        requested_samples: Arc<AtomicUsize>,
+    }
+
+    impl SttProviderFactory for StallingFactory {
+        fn create(&self, _config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
+            Ok(Box::new(StallingSendProvider {
+                failures: self.failures.clone(),
+            }))
+        }
+
+        fn create_batch(&self, _config: &SttConfig) -> SttResult<Option<Arc<dyn BatchSttProvider>>> {
+            Ok(Some(Arc::new(FixedBatchProvider {
+                requested_samples: self.requested_samples.clone(),
+            })))
+        }
+    }
+
+    #[tokio::test]
+    async fn audio_unsent_during_stall_is_spooled_and_transcribed_after_recovery() {
+        let factory = Arc::new(StallingFactory {
+            failures: Arc::new(AtomicUsize::new(3)),
+            requested_samples: Arc::new(AtomicUsize::new(0)),
+        });
+        let spool = Arc::new(MemoryAudioSpool::default());
+        let audio_capture = BurstAudioCapture::new(Arc::new(AtomicBool::new(false)), 10);
+        let service =
+            TranscriptionService::new(Box::new(audio_capture), factory.clone()).with_audio_spool(spool.clone());
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.short_clip_secs = 0;
+        service.update_config(config).await.unwrap();
+
+        let finals = Arc::new(std::sync::Mutex::new(Vec::new()));
+        start_short_clip_session(&service, finals.clone()).await;
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while finals.lock().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("gap must be transcribed once the stream recovers");
+        service.stop_recording().await.expect("stop must succeed");
+
+        // Три неотправленных чанка ушли одним REST запросом, spool пуст
+        assert_eq!(factory.requested_samples.load(Ordering::SeqCst), 3 * 160);
+        assert_eq!(*finals.lock().unwrap(), vec!["короткая фраза".to_string()]);
+        assert_eq!(spool.pending_samples(), 0);
+    }
 }
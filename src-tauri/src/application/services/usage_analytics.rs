@@ -0,0 +1,264 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::domain::SessionStats;
+
+/// Сколько дней статистики сессий храним на диске (с запасом на годовой график)
+pub const SESSION_STATS_RETENTION_DAYS: i64 = 400;
+
+/// Период аналитики: последние N дней, включая сегодняшний
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsRange {
+    #[default]
+    Week,
+    Month,
+    Year,
+}
+
+impl AnalyticsRange {
+    pub fn days(self) -> i64 {
+        match self {
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::Year => 365,
+        }
+    }
+}
+
+/// Итоги за один день
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DailyUsage {
+    /// Локальная дата "YYYY-MM-DD"
+    pub date: String,
+    pub sessions: u64,
+    pub minutes: f64,
+    pub words: u64,
+}
+
+/// Доля языка/провайдера за период
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageShare {
+    pub key: String,
+    pub sessions: u64,
+    pub minutes: f64,
+}
+
+/// Аналитика диктовки за период: по дням (без пропусков) и итоги
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageAnalytics {
+    pub range: AnalyticsRange,
+    pub days: Vec<DailyUsage>,
+    pub total_sessions: u64,
+    pub total_minutes: f64,
+    pub total_words: u64,
+    /// Средний темп за период (слова / минуты записи)
+    pub average_wpm: f64,
+    /// Языки по убыванию минут
+    pub top_languages: Vec<UsageShare>,
+    /// Провайдеры по убыванию минут
+    pub providers: Vec<UsageShare>,
+    /// Приложения, в которых диктовали, по убыванию минут (сессии без приложения не учитываются)
+    pub apps: Vec<UsageShare>,
+}
+
+fn local_date<Tz: TimeZone>(timestamp_ms: i64, tz: &Tz) -> Option<NaiveDate> {
+    DateTime::from_timestamp_millis(timestamp_ms).map(|utc| utc.with_timezone(tz).date_naive())
+}
+
+fn provider_key(stats: &SessionStats) -> String {
+    serde_json::to_value(stats.provider)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", stats.provider).to_lowercase())
+}
+
+fn add_share(shares: &mut BTreeMap<String, (u64, f64)>, key: String, minutes: f64) {
+    let entry = shares.entry(key).or_insert((0, 0.0));
+    entry.0 += 1;
+    entry.1 += minutes;
+}
+
+fn sorted_shares(shares: BTreeMap<String, (u64, f64)>) -> Vec<UsageShare> {
+    let mut out: Vec<UsageShare> = shares
+        .into_iter()
+        .map(|(key, (sessions, minutes))| UsageShare { key, sessions, minutes })
+        .collect();
+    out.sort_by(|a, b| b.minutes.total_cmp(&a.minutes).then_with(|| b.sessions.cmp(&a.sessions)));
+    out
+}
+
+/// Собирает аналитику по статистике сессий. День считается в часовом поясе `now`.
+pub fn compute_usage_analytics<Tz: TimeZone>(
+    stats: &[SessionStats],
+    range: AnalyticsRange,
+    now: &DateTime<Tz>,
+) -> UsageAnalytics {
+    let tz = now.timezone();
+    let today = now.date_naive();
+    let first_day = today - Duration::days(range.days() - 1);
+
+    let mut days: Vec<DailyUsage> = (0..range.days())
+        .map(|offset| DailyUsage {
+            date: (first_day + Duration::days(offset)).format("%Y-%m-%d").to_string(),
+            ..DailyUsage::default()
+        })
+        .collect();
+    let mut languages = BTreeMap::new();
+    let mut providers = BTreeMap::new();
+    let mut apps = BTreeMap::new();
+
+    for session in stats {
+        let Some(date) = local_date(session.started_at, &tz) else {
+            continue;
+        };
+        if date < first_day || date > today {
+            continue;
+        }
+
+        let minutes = session.duration_secs / 60.0;
+        let day = &mut days[date.signed_duration_since(first_day).num_days() as usize];
+        day.sessions += 1;
+        day.minutes += minutes;
+        day.words += session.word_count;
+
+        let language = session
+            .language
+            .as_deref()
+            .map(|l| l.split(['-', '_']).next().unwrap_or(l).to_lowercase())
+            .filter(|l| !l.is_empty())
+            .unwrap_or_else(|| "unknown".to_string());
+        add_share(&mut languages, language, minutes);
+        add_share(&mut providers, provider_key(session), minutes);
+        if let Some(app) = session.app.as_ref().filter(|a| !a.is_empty()) {
+            add_share(&mut apps, app.clone(), minutes);
+        }
+    }
+
+    let total_sessions = days.iter().map(|d| d.sessions).sum();
+    let total_minutes: f64 = days.iter().map(|d| d.minutes).sum();
+    let total_words = days.iter().map(|d| d.words).sum();
+
+    UsageAnalytics {
+        range,
+        days,
+        total_sessions,
+        total_minutes,
+        total_words,
+        average_wpm: if total_minutes > 0.0 {
+            total_words as f64 / total_minutes
+        } else {
+            0.0
+        },
+        top_languages: sorted_shares(languages),
+        providers: sorted_shares(providers),
+        apps: sorted_shares(apps),
+    }
+}
+
+/// Оставляет статистику за последние SESSION_STATS_RETENTION_DAYS дней
+pub fn retain_recent_session_stats(stats: &mut Vec<SessionStats>, now_ms: i64) {
+    let cutoff = now_ms - SESSION_STATS_RETENTION_DAYS * 24 * 60 * 60 * 1000;
+    stats.retain(|s| s.started_at >= cutoff);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SttProviderType;
+    use chrono::FixedOffset;
+
+    fn session(
+        started_at: DateTime<FixedOffset>,
+        minutes: f64,
+        words: u64,
+        language: &str,
+        provider: SttProviderType,
+    ) -> SessionStats {
+        SessionStats {
+            session_id: 0,
+            started_at: started_at.timestamp_millis(),
+            duration_secs: minutes * 60.0,
+            speaking_secs: 0.0,
+            silence_secs: 0.0,
+            word_count: words,
+            words_per_minute: 0.0,
+            speaking_ratio: 0.0,
+            language: Some(language.to_string()),
+            provider,
+            app: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_by_local_day_and_fills_gaps() {
+        let tz = FixedOffset::east_opt(3 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap();
+
+        let mut stats = vec![
+            session(tz.with_ymd_and_hms(2026, 5, 10, 9, 0, 0).unwrap(), 2.0, 200, "ru", SttProviderType::Backend),
+            // 00:30 по местному времени — это ещё 9 мая по UTC, но 10-е локально
+            session(tz.with_ymd_and_hms(2026, 5, 10, 0, 30, 0).unwrap(), 1.0, 100, "ru-RU", SttProviderType::Backend),
+            session(tz.with_ymd_and_hms(2026, 5, 8, 18, 0, 0).unwrap(), 4.0, 300, "en", SttProviderType::Deepgram),
+            // За пределами недели
+            session(tz.with_ymd_and_hms(2026, 5, 1, 18, 0, 0).unwrap(), 10.0, 999, "en", SttProviderType::Deepgram),
+        ];
+
+        stats[0].app = Some("com.tinyspeck.slackmacgap".to_string());
+        stats[2].app = Some("com.tinyspeck.slackmacgap".to_string());
+
+        let analytics = compute_usage_analytics(&stats, AnalyticsRange::Week, &now);
+        assert_eq!(analytics.days.len(), 7);
+        assert_eq!(analytics.days[0].date, "2026-05-04");
+
+        let today = analytics.days.last().unwrap();
+        assert_eq!(today.date, "2026-05-10");
+        assert_eq!(today.sessions, 2);
+        assert_eq!(today.words, 300);
+        assert!((today.minutes - 3.0).abs() < 1e-9);
+        assert_eq!(analytics.days[5].sessions, 0);
+        assert_eq!(analytics.days[4].words, 300);
+
+        assert_eq!(analytics.total_sessions, 3);
+        assert_eq!(analytics.total_words, 600);
+        assert!((analytics.average_wpm - 600.0 / 7.0).abs() < 1e-9);
+
+        assert_eq!(analytics.top_languages[0].key, "en");
+        assert_eq!(analytics.top_languages[1].key, "ru");
+        assert_eq!(analytics.top_languages[1].sessions, 2);
+        assert_eq!(analytics.providers[0].key, "deepgram");
+        assert_eq!(analytics.providers[1].key, "backend");
+        assert_eq!(analytics.apps.len(), 1);
+        assert_eq!(analytics.apps[0].key, "com.tinyspeck.slackmacgap");
+        assert_eq!(analytics.apps[0].sessions, 2);
+    }
+
+    #[test]
+    fn empty_stats_give_zero_totals() {
+        let now = chrono::Utc::now();
+        let analytics = compute_usage_analytics(&[], AnalyticsRange::Month, &now);
+        assert_eq!(analytics.days.len(), 30);
+        assert_eq!(analytics.total_minutes, 0.0);
+        assert_eq!(analytics.average_wpm, 0.0);
+        assert!(analytics.top_languages.is_empty());
+    }
+
+    #[test]
+    fn retention_drops_old_sessions() {
+        let now = chrono::Utc::now();
+        let tz = FixedOffset::east_opt(0).unwrap();
+        let mut stats = vec![
+            session(now.with_timezone(&tz), 1.0, 1, "ru", SttProviderType::Backend),
+            session(
+                (now - Duration::days(SESSION_STATS_RETENTION_DAYS + 1)).with_timezone(&tz),
+                1.0,
+                1,
+                "ru",
+                SttProviderType::Backend,
+            ),
+        ];
+        retain_recent_session_stats(&mut stats, now.timestamp_millis());
+        assert_eq!(stats.len(), 1);
+    }
+}
@@ -0,0 +1,274 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::bare_word;
+
+/// App-control command spoken at the end of a phrase (`SttConfig::voice_commands`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoiceCommand {
+    /// "stop recording", "останови запись"
+    StopRecording,
+    /// "switch to English", "переключись на русский" — код языка STT
+    SwitchLanguage { language: String },
+    /// "paste that", "вставь это" — вставить надиктованное с прошлой вставки
+    PasteText { text: String },
+}
+
+impl VoiceCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StopRecording => "stop_recording",
+            Self::SwitchLanguage { .. } => "switch_language",
+            Self::PasteText { .. } => "paste_text",
+        }
+    }
+}
+
+pub type VoiceCommandListener = Arc<dyn Fn(VoiceCommand) + Send + Sync>;
+
+const STOP_COMMANDS: &[&str] = &[
+    "stop recording",
+    "stop dictation",
+    "остановить запись",
+    "останови запись",
+    "стоп запись",
+];
+
+const PASTE_COMMANDS: &[&str] = &["paste that", "paste it", "вставь это", "вставить это"];
+
+const SWITCH_PREFIXES: &[&str] = &["switch to", "переключись на", "переключи на", "переключиться на"];
+
+/// Необязательное слово после языка: "switch to English language", "на русский язык"
+const LANGUAGE_SUFFIXES: &[&str] = &["language", "язык"];
+
+const LANGUAGES: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("russian", "ru"),
+    ("german", "de"),
+    ("french", "fr"),
+    ("spanish", "es"),
+    ("italian", "it"),
+    ("portuguese", "pt"),
+    ("ukrainian", "uk"),
+    ("chinese", "zh"),
+    ("japanese", "ja"),
+    ("английский", "en"),
+    ("русский", "ru"),
+    ("немецкий", "de"),
+    ("французский", "fr"),
+    ("испанский", "es"),
+    ("итальянский", "it"),
+    ("португальский", "pt"),
+    ("украинский", "uk"),
+    ("китайский", "zh"),
+    ("японский", "ja"),
+];
+
+/// Команда, найденная в конце фразы
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Spotted {
+    Stop,
+    Switch(&'static str),
+    Paste,
+}
+
+/// Keyword spotter of one recording session.
+///
+/// Команда ищется только в конце фразы и только целиком: "stop recording podcasts" — обычная речь.
+/// Стоп выполняется уже на промежуточном тексте (не ждём финализации сегмента),
+/// остальные команды — на финализированном: им нужен окончательный текст.
+pub struct VoiceCommandSpotter {
+    listener: VoiceCommandListener,
+    /// Стоп уже выполнен по промежуточному тексту текущего сегмента
+    stop_fired: AtomicBool,
+    /// Сколько байт текста сессии (по каналам) уже вставлено командой
+    pasted: Mutex<[usize; 2]>,
+}
+
+impl VoiceCommandSpotter {
+    pub fn new(listener: VoiceCommandListener) -> Self {
+        Self {
+            listener,
+            stop_fired: AtomicBool::new(false),
+            pasted: Mutex::new([0; 2]),
+        }
+    }
+
+    /// Промежуточный текст: команда не показывается, стоп выполняется сразу
+    pub fn interim(&self, text: &mut String) {
+        let Some((spotted, rest)) = spot(text) else {
+            return;
+        };
+        *text = rest;
+        if spotted == Spotted::Stop && !self.stop_fired.swap(true, Ordering::Relaxed) {
+            self.fire(VoiceCommand::StopRecording);
+        }
+    }
+
+    /// Финализированный сегмент: убирает команду из текста. Выполнить её — `execute`,
+    /// когда сегмент уже собран в текст сессии
+    pub fn finalize(&self, text: &mut String) -> Option<PendingVoiceCommand> {
+        let stop_fired = self.stop_fired.swap(false, Ordering::Relaxed);
+        let (spotted, rest) = spot(text)?;
+        *text = rest;
+        match spotted {
+            Spotted::Stop if stop_fired => None,
+            spotted => Some(PendingVoiceCommand(spotted)),
+        }
+    }
+
+    /// `session_text` — текст сессии канала `slot` вместе с сегментом команды
+    pub fn execute(&self, command: PendingVoiceCommand, slot: usize, session_text: &str) {
+        let command = match command.0 {
+            Spotted::Stop => VoiceCommand::StopRecording,
+            Spotted::Switch(language) => VoiceCommand::SwitchLanguage {
+                language: language.to_string(),
+            },
+            Spotted::Paste => {
+                let Ok(mut pasted) = self.pasted.lock() else {
+                    return;
+                };
+                let from = pasted[slot].min(session_text.len());
+                let text = session_text.get(from..).unwrap_or_default().trim().to_string();
+                pasted[slot] = session_text.len();
+                if text.is_empty() {
+                    log::info!("Voice command 'paste that': nothing new to paste");
+                    return;
+                }
+                VoiceCommand::PasteText { text }
+            }
+        };
+        self.fire(command);
+    }
+
+    fn fire(&self, command: VoiceCommand) {
+        log::info!("Voice command: {}", command.name());
+        (self.listener)(command);
+    }
+}
+
+/// Команда финализированного сегмента, которая ждёт сборки текста сессии
+#[derive(Debug)]
+pub struct PendingVoiceCommand(Spotted);
+
+/// Команда в конце текста и текст без неё
+fn spot(text: &str) -> Option<(Spotted, String)> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let bare: Vec<String> = words.iter().map(|word| bare_word(word)).collect();
+
+    let (spotted, len) = STOP_COMMANDS
+        .iter()
+        .find_map(|command| suffix_len(&bare, command).map(|len| (Spotted::Stop, len)))
+        .or_else(|| {
+            PASTE_COMMANDS
+                .iter()
+                .find_map(|command| suffix_len(&bare, command).map(|len| (Spotted::Paste, len)))
+        })
+        .or_else(|| spot_language_switch(&bare))?;
+
+    let rest = words[..words.len() - len].join(" ");
+    let rest = rest.trim_end_matches([',', ';', ':', '-', '—']).trim_end().to_string();
+    Some((spotted, rest))
+}
+
+fn spot_language_switch(bare: &[String]) -> Option<(Spotted, usize)> {
+    let mut end = bare.len();
+    if end > 0 && LANGUAGE_SUFFIXES.contains(&bare[end - 1].as_str()) {
+        end -= 1;
+    }
+    let language = bare[..end].last()?;
+    let &(_, code) = LANGUAGES.iter().find(|(name, _)| name == language)?;
+    SWITCH_PREFIXES.iter().find_map(|prefix| {
+        let len = suffix_len(&bare[..end - 1], prefix)?;
+        Some((Spotted::Switch(code), len + 1 + bare.len() - end))
+    })
+}
+
+/// Сколько слов в конце `bare` занимает фраза `command`
+fn suffix_len(bare: &[String], command: &str) -> Option<usize> {
+    let command: Vec<&str> = command.split_whitespace().collect();
+    if command.len() > bare.len() {
+        return None;
+    }
+    bare[bare.len() - command.len()..]
+        .iter()
+        .zip(&command)
+        .all(|(word, expected)| word == expected)
+        .then_some(command.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spotter() -> (VoiceCommandSpotter, Arc<Mutex<Vec<VoiceCommand>>>) {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let fired_cb = fired.clone();
+        let spotter = VoiceCommandSpotter::new(Arc::new(move |command| fired_cb.lock().unwrap().push(command)));
+        (spotter, fired)
+    }
+
+    #[test]
+    fn spots_commands_at_the_end_of_a_phrase() {
+        assert_eq!(spot("Привет мир, stop recording."), Some((Spotted::Stop, "Привет мир".to_string())));
+        assert_eq!(spot("Останови запись"), Some((Spotted::Stop, String::new())));
+        assert_eq!(spot("Switch to English."), Some((Spotted::Switch("en"), String::new())));
+        assert_eq!(
+            spot("Всё, переключись на английский язык"),
+            Some((Spotted::Switch("en"), "Всё".to_string()))
+        );
+        assert_eq!(spot("okay paste that"), Some((Spotted::Paste, "okay".to_string())));
+
+        // Не в конце фразы или не целиком — обычная речь
+        assert_eq!(spot("stop recording podcasts"), None);
+        assert_eq!(spot("switch to Klingon"), None);
+        assert_eq!(spot("that"), None);
+    }
+
+    #[test]
+    fn stop_fires_once_on_interim_text() {
+        let (spotter, fired) = spotter();
+        let mut interim = "Готово stop".to_string();
+        spotter.interim(&mut interim);
+        assert_eq!(interim, "Готово stop");
+
+        let mut interim = "Готово stop recording".to_string();
+        spotter.interim(&mut interim);
+        assert_eq!(interim, "Готово");
+        spotter.interim(&mut "Готово stop recording".to_string());
+
+        // Финал того же сегмента: команда убрана, но второй раз не выполняется
+        let mut last = "Готово. Stop recording.".to_string();
+        assert!(spotter.finalize(&mut last).is_none());
+        assert_eq!(last, "Готово.");
+        assert_eq!(*fired.lock().unwrap(), vec![VoiceCommand::StopRecording]);
+    }
+
+    #[test]
+    fn paste_inserts_text_since_previous_paste() {
+        let (spotter, fired) = spotter();
+        let mut segment = "Первая фраза. Paste that.".to_string();
+        let command = spotter.finalize(&mut segment).unwrap();
+        spotter.execute(command, 0, "Первая фраза.");
+
+        let mut segment = "вставь это".to_string();
+        let command = spotter.finalize(&mut segment).unwrap();
+        assert_eq!(segment, "");
+        spotter.execute(command, 0, "Первая фраза. Вторая фраза.");
+        // Нового текста нет — вставлять нечего
+        let command = spotter.finalize(&mut "paste it".to_string()).unwrap();
+        spotter.execute(command, 0, "Первая фраза. Вторая фраза.");
+
+        assert_eq!(
+            *fired.lock().unwrap(),
+            vec![
+                VoiceCommand::PasteText {
+                    text: "Первая фраза.".to_string()
+                },
+                VoiceCommand::PasteText {
+                    text: "Вторая фраза.".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Warm pool: эвристика "когда пользователь обычно диктует".
+//!
+//! Храним моменты нажатий хоткея записи за последние дни. Соединение прогреваем, если
+//! пользователь диктовал совсем недавно (серия коротких диктовок, keep-alive мог уже истечь)
+//! или если в это же время суток он диктовал хотя бы в WARM_POOL_MIN_MATCHING_DAYS разных дней.
+
+use chrono::{DateTime, NaiveDate, TimeZone, Timelike};
+use std::collections::BTreeSet;
+
+/// Сколько дней активности хранить (эвристике больше двух недель не нужно)
+pub const HOTKEY_ACTIVITY_RETENTION_DAYS: i64 = 14;
+
+/// Верхняя граница числа записей активности на диске
+const MAX_HOTKEY_ACTIVITY_ENTRIES: usize = 1000;
+
+/// Окно вокруг ожидаемого момента диктовки (минуты, в обе стороны)
+const TIME_OF_DAY_WINDOW_MINUTES: i64 = 20;
+
+/// Сколько разных прошлых дней с диктовкой в это время суток нужно для прогрева
+pub const WARM_POOL_MIN_MATCHING_DAYS: usize = 2;
+
+/// Серия диктовок: после последнего нажатия держим соединение тёплым столько секунд
+const ACTIVE_STREAK_SECS: i64 = 15 * 60;
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// Добавляет нажатие хоткея и выбрасывает устаревшие записи (timestamps — unix ms)
+pub fn record_hotkey_activity(activity: &mut Vec<i64>, now_ms: i64) {
+    activity.push(now_ms);
+    let cutoff = now_ms - HOTKEY_ACTIVITY_RETENTION_DAYS * 24 * 60 * 60 * 1000;
+    activity.retain(|&ts| ts >= cutoff);
+    if activity.len() > MAX_HOTKEY_ACTIVITY_ENTRIES {
+        let excess = activity.len() - MAX_HOTKEY_ACTIVITY_ENTRIES;
+        activity.drain(..excess);
+    }
+}
+
+fn minute_of_day<Tz: TimeZone>(at: &DateTime<Tz>) -> i64 {
+    (at.hour() * 60 + at.minute()) as i64
+}
+
+/// Пора ли прогреть соединение: пользователь, скорее всего, начнёт диктовать в ближайшие `lead_secs`.
+/// День и время суток считаются в часовом поясе `now`.
+pub fn should_prewarm<Tz: TimeZone>(activity: &[i64], now: &DateTime<Tz>, lead_secs: u64) -> bool {
+    let now_ms = now.timestamp_millis();
+    if activity
+        .iter()
+        .any(|&ts| ts <= now_ms && now_ms - ts <= ACTIVE_STREAK_SECS * 1000)
+    {
+        return true;
+    }
+
+    let tz = now.timezone();
+    let target = now.clone() + chrono::Duration::seconds(lead_secs as i64);
+    let target_minute = minute_of_day(&target);
+    let today = now.date_naive();
+
+    let matching_days: BTreeSet<NaiveDate> = activity
+        .iter()
+        .filter_map(|&ts| DateTime::from_timestamp_millis(ts).map(|utc| utc.with_timezone(&tz)))
+        .filter(|at| at.date_naive() < today)
+        .filter(|at| {
+            let diff = (minute_of_day(at) - target_minute).rem_euclid(MINUTES_PER_DAY);
+            diff.min(MINUTES_PER_DAY - diff) <= TIME_OF_DAY_WINDOW_MINUTES
+        })
+        .map(|at| at.date_naive())
+        .collect();
+    matching_days.len() >= WARM_POOL_MIN_MATCHING_DAYS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(3 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 3, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn prewarms_before_habitual_time_of_day() {
+        // Две прошлые утренние диктовки около 9:00 в разные дни
+        let activity = vec![at(3, 9, 5).timestamp_millis(), at(4, 8, 55).timestamp_millis()];
+
+        assert!(should_prewarm(&activity, &at(5, 8, 58), 90));
+        assert!(!should_prewarm(&activity, &at(5, 13, 0), 90));
+        // Одного дня мало
+        assert!(!should_prewarm(&activity[..1], &at(5, 8, 58), 90));
+        // Сегодняшние нажатия (кроме недавней серии) привычку не образуют
+        let today_only = vec![at(5, 7, 0).timestamp_millis(), at(5, 7, 10).timestamp_millis()];
+        assert!(!should_prewarm(&today_only, &at(5, 7, 30), 90));
+    }
+
+    #[test]
+    fn prewarms_during_active_streak_and_window_wraps_midnight() {
+        let now = at(5, 14, 0);
+        assert!(should_prewarm(&[at(5, 13, 50).timestamp_millis()], &now, 90));
+        assert!(!should_prewarm(&[at(5, 13, 30).timestamp_millis()], &now, 90));
+
+        let late = vec![at(3, 23, 55).timestamp_millis(), at(4, 0, 5).timestamp_millis()];
+        assert!(should_prewarm(&late, &at(5, 23, 58), 60));
+    }
+
+    #[test]
+    fn activity_is_trimmed_to_retention() {
+        let now = at(20, 12, 0).timestamp_millis();
+        let mut activity = vec![at(1, 12, 0).timestamp_millis(), at(19, 12, 0).timestamp_millis()];
+        record_hotkey_activity(&mut activity, now);
+        assert_eq!(activity, vec![at(19, 12, 0).timestamp_millis(), now]);
+    }
+}
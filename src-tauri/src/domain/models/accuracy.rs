@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use super::SttProviderType;
+
+/// Reference paragraph the user reads aloud during the accuracy self-test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccuracyScript {
+    pub id: String,
+    pub language: String,
+    pub title: String,
+    pub text: String,
+}
+
+/// Edit-distance counts of a hypothesis against the reference (words or characters)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorCounts {
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+    /// Длина эталона (слов или символов)
+    pub reference_len: usize,
+}
+
+impl ErrorCounts {
+    pub fn errors(&self) -> usize {
+        self.substitutions + self.deletions + self.insertions
+    }
+
+    /// WER/CER: ошибки / длина эталона. Может быть больше 1.0 при множестве вставок
+    pub fn rate(&self) -> f64 {
+        if self.reference_len == 0 {
+            return if self.errors() == 0 { 0.0 } else { 1.0 };
+        }
+        self.errors() as f64 / self.reference_len as f64
+    }
+}
+
+/// What the user could change to get better accuracy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AccuracySuggestion {
+    /// Распознана малая часть текста: тихий/не тот микрофон или запись оборвалась
+    CheckMicrophone,
+    /// Локальная модель побольше (Whisper)
+    LargerModel { model: String },
+    /// Другой провайдер
+    SwitchProvider { provider: SttProviderType },
+    /// Ошибки в основном в отдельных словах — их можно добавить в словарь исправлений
+    AddCorrections,
+}
+
+/// One accuracy self-test run (stored to track improvement over time)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccuracyTestResult {
+    /// Время теста (unix seconds)
+    pub timestamp: i64,
+    pub script_id: String,
+    pub language: String,
+    pub provider: SttProviderType,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Что распознал провайдер
+    pub transcript: String,
+    pub word_errors: ErrorCounts,
+    pub char_errors: ErrorCounts,
+    pub word_error_rate: f64,
+    pub char_error_rate: f64,
+    /// Длительность записи, мс
+    pub audio_ms: u64,
+    #[serde(default)]
+    pub suggestions: Vec<AccuracySuggestion>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_rate_handles_empty_reference() {
+        assert_eq!(ErrorCounts::default().rate(), 0.0);
+
+        let only_insertions = ErrorCounts {
+            insertions: 2,
+            ..Default::default()
+        };
+        assert_eq!(only_insertions.rate(), 1.0);
+
+        let counts = ErrorCounts {
+            substitutions: 1,
+            deletions: 1,
+            insertions: 0,
+            reference_len: 8,
+        };
+        assert_eq!(counts.errors(), 2);
+        assert_eq!(counts.rate(), 0.25);
+    }
+}
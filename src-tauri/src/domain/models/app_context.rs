@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Foreground app at recording start (`AppConfig::capture_app_context`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AppContext {
+    /// Имя приложения ("Slack", "Code")
+    pub app_name: String,
+    /// Bundle ID на macOS, имя exe на Windows
+    #[serde(default)]
+    pub app_id: Option<String>,
+    /// Заголовок активного окна (None — ОС не дала доступ)
+    #[serde(default)]
+    pub window_title: Option<String>,
+}
+
+impl AppContext {
+    /// Ключ для правил по приложениям и аналитики: bundle ID / exe, иначе имя
+    pub fn key(&self) -> &str {
+        self.app_id.as_deref().unwrap_or(&self.app_name)
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use super::SttProviderType;
+
+/// Сколько аудио из сэмпла отправляем провайдерам (длинные файлы обрезаются, чтобы не тратить минуты)
+pub const MAX_BENCHMARK_AUDIO_SECS: u32 = 60;
+
+/// Audio used to compare STT providers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BenchmarkSample {
+    /// Встроенная английская фраза
+    #[default]
+    Bundled,
+    /// Файл пользователя (WAV/MP3)
+    File { path: String },
+}
+
+/// Result of running the sample through one provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderBenchmarkResult {
+    pub provider: SttProviderType,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Финальный текст (пустой при ошибке)
+    pub text: String,
+    /// Ошибка подключения/распознавания; None — провайдер отработал
+    #[serde(default)]
+    pub error: Option<String>,
+    /// От подключения до последней финальной фразы, мс
+    pub latency_ms: u64,
+    /// latency / длительность аудио: меньше 1.0 — быстрее реального времени
+    pub real_time_factor: f64,
+}
+
+/// Side-by-side comparison of providers on one sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderBenchmarkReport {
+    /// Язык, с которым распознавался сэмпл
+    pub language: String,
+    /// Длительность отправленного аудио, мс
+    pub audio_ms: u64,
+    pub results: Vec<ProviderBenchmarkResult>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_deserializes_from_tagged_json() {
+        let sample: BenchmarkSample = serde_json::from_str(r#"{"type":"bundled"}"#).unwrap();
+        assert_eq!(sample, BenchmarkSample::Bundled);
+
+        let sample: BenchmarkSample = serde_json::from_str(r#"{"type":"file","path":"/tmp/a.wav"}"#).unwrap();
+        assert_eq!(
+            sample,
+            BenchmarkSample::File {
+                path: "/tmp/a.wav".to_string()
+            }
+        );
+    }
+}
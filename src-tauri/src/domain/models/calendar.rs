@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+/// Where calendar events for session titles come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarSource {
+    /// Системный календарь (EventKit, только macOS)
+    #[default]
+    System,
+    /// Подписка на календарь по ссылке .ics (Google/Outlook "secret address", webcal://)
+    IcsUrl,
+}
+
+/// Calendar-aware session titles: a session is titled after the meeting active when recording started
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// По умолчанию выключено: календарь — личные данные, читаем только по явному согласию
+    pub enabled: bool,
+    pub source: CalendarSource,
+    /// Ссылка на .ics (для source = ics_url)
+    pub ics_url: Option<String>,
+}
+
+impl CalendarConfig {
+    pub fn normalized(mut self) -> Self {
+        self.ics_url = self
+            .ics_url
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty());
+        self
+    }
+}
+
+/// One calendar event (a single occurrence for recurring events)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub title: String,
+    /// Начало (unix ms)
+    pub start_ms: i64,
+    /// Конец (unix ms, не включительно)
+    pub end_ms: i64,
+    #[serde(default)]
+    pub all_day: bool,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Категории/метки события
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+impl CalendarEvent {
+    pub fn is_active_at(&self, at_ms: i64) -> bool {
+        self.start_ms <= at_ms && at_ms < self.end_ms
+    }
+}
+
+/// Calendar title and tags attached to the entries of one recording session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SessionCalendarTag {
+    pub title: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub event_id: Option<String>,
+}
+
+impl SessionCalendarTag {
+    pub fn from_event(event: &CalendarEvent) -> Self {
+        let mut tags: Vec<String> = Vec::new();
+        for category in event.categories.iter().map(|c| c.trim()).filter(|c| !c.is_empty()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(category)) {
+                tags.push(category.to_string());
+            }
+        }
+        Self {
+            title: event.title.trim().to_string(),
+            tags,
+            event_id: event.id.clone(),
+        }
+    }
+}
+
+/// Событие, которым называем сессию, начатую в `at_ms`.
+///
+/// Весь день (отпуск, дни рождения) и события без названия не подходят. Из нескольких
+/// пересекающихся встреч берём начавшуюся последней — на неё, скорее всего, и перешли.
+pub fn session_event_at(events: &[CalendarEvent], at_ms: i64) -> Option<&CalendarEvent> {
+    events
+        .iter()
+        .filter(|e| !e.all_day && e.is_active_at(at_ms) && !e.title.trim().is_empty())
+        .max_by_key(|e| (e.start_ms, std::cmp::Reverse(e.end_ms)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, start_ms: i64, end_ms: i64) -> CalendarEvent {
+        CalendarEvent {
+            id: None,
+            title: title.to_string(),
+            start_ms,
+            end_ms,
+            all_day: false,
+            location: None,
+            categories: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn picks_latest_started_timed_event() {
+        let mut all_day = event("Отпуск", 0, 100_000);
+        all_day.all_day = true;
+        let events = vec![
+            all_day,
+            event("Планёрка", 1_000, 5_000),
+            event("1:1 с Анной", 3_000, 4_000),
+            event("Ретро", 6_000, 9_000),
+        ];
+        assert_eq!(session_event_at(&events, 3_500).map(|e| e.title.as_str()), Some("1:1 с Анной"));
+        assert_eq!(session_event_at(&events, 4_000).map(|e| e.title.as_str()), Some("Планёрка"));
+        assert_eq!(session_event_at(&events, 5_500), None);
+    }
+
+    #[test]
+    fn tag_deduplicates_categories() {
+        let mut e = event("  Weekly sync ", 0, 1);
+        e.categories = vec!["Work".to_string(), " work".to_string(), "".to_string(), "Team".to_string()];
+        let tag = SessionCalendarTag::from_event(&e);
+        assert_eq!(tag.title, "Weekly sync");
+        assert_eq!(tag.tags, vec!["Work".to_string(), "Team".to_string()]);
+    }
+}
@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{
+    paste_strategy_for, text_casing_for, CalendarConfig, FeedbackConfig, MemoryAlarmConfig, PasteAppRule, PasteStrategy, TextCasing, TextOutputProfile,
+    TextOutputSinkConfig, Transcription,
+    WarmPoolConfig,
+};
 
 /// Supported STT provider types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SttProviderType {
     /// Local Whisper.cpp implementation (offline)
@@ -24,6 +31,370 @@ impl Default for SttProviderType {
     }
 }
 
+impl SttProviderType {
+    /// Как провайдер тарифицирует соединение, открытое между записями (keep-alive пауза, prewarm)
+    pub fn idle_billing_policy(self) -> IdleBillingPolicy {
+        match self {
+            // Universal-Streaming тарифицирует длительность сессии, а не отправленное аудио
+            Self::AssemblyAI => IdleBillingPolicy {
+                billed_while_idle: true,
+                max_idle_secs: Some(ASSEMBLYAI_MAX_IDLE_SECS),
+            },
+            _ => IdleBillingPolicy::default(),
+        }
+    }
+
+    /// Прайс потокового распознавания в USD за минуту для оценки стоимости записи;
+    /// None — минуты списываются с тарифа (Backend), остаток виден по квоте
+    pub fn streaming_usd_per_minute(self) -> Option<f64> {
+        match self {
+            Self::WhisperLocal => Some(0.0),
+            // $0.15/час
+            Self::AssemblyAI => Some(0.0025),
+            // Nova-3 streaming
+            Self::Deepgram => Some(0.0077),
+            Self::GoogleCloud => Some(0.016),
+            // $1/час
+            Self::Azure => Some(0.0167),
+            Self::Backend => None,
+        }
+    }
+
+    /// Провайдеру нужна сеть (без неё запись не начнётся)
+    pub fn requires_network(self) -> bool {
+        !matches!(self, Self::WhisperLocal)
+    }
+
+    /// Сколько аудио провайдер по умолчанию отправляет одним сообщением, мс
+    pub fn default_chunk_ms(self) -> u32 {
+        match self {
+            // Backend принимает кадры VAD (30ms) как есть
+            Self::Backend => 30,
+            _ => 50,
+        }
+    }
+
+    /// Допустимый размер сообщения с аудио, мс
+    pub fn chunk_ms_range(self) -> std::ops::RangeInclusive<u32> {
+        match self {
+            // Universal-Streaming отклоняет сообщения короче 50ms и длиннее 1000ms
+            Self::AssemblyAI => 50..=1000,
+            _ => MIN_AUDIO_CHUNK_MS..=MAX_AUDIO_CHUNK_MS,
+        }
+    }
+}
+
+/// Меньше — сообщений больше, чем кадров VAD (30ms), которыми аудио приходит к провайдеру
+pub const MIN_AUDIO_CHUNK_MS: u32 = 30;
+
+/// Больше — текст заметно отстаёт от речи
+pub const MAX_AUDIO_CHUNK_MS: u32 = 1000;
+
+/// Модели Whisper для записи без сети, в порядке предпочтения: быстрые первыми, чтобы запись
+/// оставалась интерактивной и на слабом ноутбуке
+pub const OFFLINE_WHISPER_MODELS: &[&str] = &["base", "small", "tiny", "medium", "large"];
+
+/// Сколько держать открытым простаивающее соединение AssemblyAI, даже если keep-alive TTL больше
+pub const ASSEMBLYAI_MAX_IDLE_SECS: u64 = 60;
+
+/// Idle billing policy of a provider connection that is open but not recording
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IdleBillingPolicy {
+    /// Provider bills connection time, so an idle open connection costs money
+    pub billed_while_idle: bool,
+    /// Hard limit on idle connection time on top of the keep-alive TTL (None — no limit)
+    pub max_idle_secs: Option<u64>,
+}
+
+impl IdleBillingPolicy {
+    /// TTL простоя с учётом предела провайдера
+    pub fn cap_idle_secs(&self, ttl_secs: u64) -> u64 {
+        match self.max_idle_secs {
+            Some(max) => ttl_secs.min(max),
+            None => ttl_secs,
+        }
+    }
+}
+
+/// Audio encoding used for streaming audio to the STT provider
+///
+/// Сжатие полезно на медленных/лимитных соединениях. Сейчас учитывается Deepgram провайдером,
+/// остальные провайдеры всегда отправляют linear16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioEncoding {
+    /// Uncompressed 16-bit PCM (default)
+    #[default]
+    Linear16,
+    /// Lossless FLAC (~40-50% less traffic for speech)
+    Flac,
+    /// Ogg/Opus (lossy, ~10x less traffic). Requires build with `opus` feature
+    Opus,
+}
+
+/// What to do with a final transcription whose confidence is below `min_confidence`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LowConfidenceAction {
+    /// Deliver as usual, but mark the event with `low_confidence: true` (default)
+    #[default]
+    Flag,
+    /// Drop the phrase: no event, no history
+    Suppress,
+    /// Keep the phrase aside until the user releases or discards it
+    Hold,
+}
+
+/// Where the recording overlay ("pill") appears
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingOverlayPosition {
+    /// Сверху по центру экрана, под строкой меню (как системная диктовка macOS)
+    #[default]
+    MenuBar,
+    /// Рядом с курсором мыши в момент старта записи
+    Cursor,
+}
+
+/// Small always-on-top indicator shown while recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingOverlayConfig {
+    /// По умолчанию выключен: основное окно записи и так показывает состояние
+    pub enabled: bool,
+    pub position: RecordingOverlayPosition,
+}
+
+/// Дольше этого держать окно после остановки нет смысла — проще выбрать keep_window
+pub const MAX_WINDOW_HIDE_DELAY_SECS: u32 = 60;
+
+/// What happens to the recording window when recording is stopped with the hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum WindowBehaviorOnStop {
+    /// Окно остаётся с текстом сессии
+    KeepWindow,
+    /// Скрыть сразу (по умолчанию)
+    #[default]
+    HideImmediately,
+    /// Скрыть после вставки текста сессии (без auto-paste — сразу)
+    HideAfterPaste,
+    /// Скрыть через `secs` секунд, если новая запись не началась
+    HideAfterDelay { secs: u32 },
+}
+
+impl WindowBehaviorOnStop {
+    pub fn normalized(self) -> Self {
+        match self {
+            Self::HideAfterDelay { secs } => Self::HideAfterDelay {
+                secs: secs.min(MAX_WINDOW_HIDE_DELAY_SECS),
+            },
+            behavior => behavior,
+        }
+    }
+}
+
+/// Where the live captions strip is placed on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptionsPosition {
+    /// Внизу по центру, как субтитры в видеоплеере
+    #[default]
+    Bottom,
+    Top,
+}
+
+pub const MIN_CAPTIONS_FONT_SIZE: u16 = 14;
+pub const MAX_CAPTIONS_FONT_SIZE: u16 = 72;
+
+/// Live captions mode: rolling partials in a click-through window on top of everything
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptionsConfig {
+    /// Размер шрифта субтитров (логические пиксели)
+    pub font_size: u16,
+    pub position: CaptionsPosition,
+    /// Сколько строк текста видно одновременно
+    pub max_lines: u8,
+    /// Устройство для субтитров (loopback/monitor системного звука). None — текущий микрофон
+    pub source_device: Option<String>,
+}
+
+impl Default for CaptionsConfig {
+    fn default() -> Self {
+        Self {
+            font_size: 28,
+            position: CaptionsPosition::Bottom,
+            max_lines: 2,
+            source_device: None,
+        }
+    }
+}
+
+impl CaptionsConfig {
+    /// Значения в допустимых пределах (настройки могли прийти с фронта/из файла какими угодно)
+    pub fn normalized(mut self) -> Self {
+        self.font_size = self.font_size.clamp(MIN_CAPTIONS_FONT_SIZE, MAX_CAPTIONS_FONT_SIZE);
+        self.max_lines = self.max_lines.clamp(1, 5);
+        self.source_device = self.source_device.filter(|d| !d.trim().is_empty());
+        self
+    }
+}
+
+/// Пауза меньше этой не может разделять темы (иначе каждая фраза — отдельная тема)
+pub const MIN_MEETING_TOPIC_GAP_SECS: f64 = 2.0;
+
+/// Больше этого запаса вокруг речи при обрезке тишины не оставляем
+pub const MAX_MEETING_TRIM_PADDING_SECS: f64 = 5.0;
+
+/// Meeting mode: one long session segmented into topics for export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeetingConfig {
+    /// Пауза между фразами (секунды), после которой начинается новая тема
+    pub topic_gap_secs: f64,
+    /// Тема короче этого не закрывается по паузе — короткие паузы в начале темы не дробят её
+    pub min_topic_secs: f64,
+    /// Дополнительно просить у бэкенда (LLM) границы и названия тем по тексту встречи
+    pub llm_topic_breaks: bool,
+    /// Обрезать тишину до первой и после последней фразы: время фраз в экспорте отсчитывается
+    /// от начала речи (микрофон включили заранее — SRT/заметки не начинаются с минуты тишины)
+    pub trim_silence: bool,
+    /// Сколько тишины оставить вокруг речи при обрезке (секунды)
+    pub trim_padding_secs: f64,
+}
+
+impl Default for MeetingConfig {
+    fn default() -> Self {
+        Self {
+            topic_gap_secs: 8.0,
+            min_topic_secs: 30.0,
+            llm_topic_breaks: false,
+            trim_silence: false,
+            trim_padding_secs: 0.5,
+        }
+    }
+}
+
+impl MeetingConfig {
+    /// Значения в допустимых пределах (NaN/отрицательные из файла или с фронта)
+    pub fn normalized(mut self) -> Self {
+        let defaults = Self::default();
+        self.topic_gap_secs = if self.topic_gap_secs.is_finite() {
+            self.topic_gap_secs.max(MIN_MEETING_TOPIC_GAP_SECS)
+        } else {
+            defaults.topic_gap_secs
+        };
+        self.min_topic_secs = if self.min_topic_secs.is_finite() {
+            self.min_topic_secs.max(0.0)
+        } else {
+            defaults.min_topic_secs
+        };
+        self.trim_padding_secs = if self.trim_padding_secs.is_finite() {
+            self.trim_padding_secs.clamp(0.0, MAX_MEETING_TRIM_PADDING_SECS)
+        } else {
+            defaults.trim_padding_secs
+        };
+        self
+    }
+}
+
+/// Per-language rules for spoken number/date/currency normalization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NormalizationRules {
+    /// "twenty five" → "25"
+    pub numbers: bool,
+    /// "третье мая" → "3 мая", "May third" → "May 3"
+    pub dates: bool,
+    /// "twenty five dollars" → "$25"
+    pub currency: bool,
+    /// Одиночные числительные меньше порога остаются словами ("one of them", "три дня")
+    pub min_standalone_number: u64,
+}
+
+impl Default for NormalizationRules {
+    fn default() -> Self {
+        Self {
+            numbers: true,
+            dates: true,
+            currency: true,
+            min_standalone_number: 10,
+        }
+    }
+}
+
+/// Post-processing of final text: spoken numbers, dates and currency to written form
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextNormalizationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Переопределения правил по языку ("en", "ru"); для остальных языков — правила по умолчанию
+    #[serde(default)]
+    pub languages: BTreeMap<String, NormalizationRules>,
+}
+
+impl TextNormalizationConfig {
+    /// Правила для языка фразы; None — нормализация выключена
+    pub fn rules_for(&self, language: &str) -> Option<NormalizationRules> {
+        if !self.enabled {
+            return None;
+        }
+        let base = language.split(['-', '_']).next().unwrap_or(language).to_lowercase();
+        Some(
+            self.languages
+                .get(language)
+                .or_else(|| self.languages.get(&base))
+                .copied()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+/// How many kinds of filler words to strip from final text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillerAggressiveness {
+    /// Только звуки-заминки: "um", "uh", "эээ", "ммм"
+    Conservative,
+    /// Плюс устойчивые вставки: "you know", "I mean", "ну", "как бы", "типа"
+    #[default]
+    Moderate,
+    /// Плюс слова, которые бывают и значимыми: "like", "basically", "вот", "значит", "в общем"
+    Aggressive,
+}
+
+/// Post-processing of final text: filler words removal
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FillerRemovalConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default)]
+    pub aggressiveness: FillerAggressiveness,
+
+    /// Свои слова и фразы-паразиты по языку ("en", "ru") — дополняют встроенные списки
+    #[serde(default)]
+    pub languages: BTreeMap<String, Vec<String>>,
+}
+
+impl FillerRemovalConfig {
+    /// Свои слова для языка фразы ("ru-RU" → и "ru-RU", и "ru"); None — удаление выключено
+    pub fn custom_words_for(&self, language: &str) -> Option<Vec<String>> {
+        if !self.enabled {
+            return None;
+        }
+        let base = language.split(['-', '_']).next().unwrap_or(language).to_lowercase();
+        let mut words: Vec<String> = self.languages.get(language).cloned().unwrap_or_default();
+        if base != language {
+            words.extend(self.languages.get(&base).cloned().unwrap_or_default());
+        }
+        words.retain(|word| !word.trim().is_empty());
+        Some(words)
+    }
+}
+
 /// Configuration for STT provider
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SttConfig {
@@ -76,12 +447,250 @@ pub struct SttConfig {
     /// Например: "Kubernetes, VoicetextAI, Deepgram"
     #[serde(default)]
     pub deepgram_keyterms: Option<String>,
+
+    /// Кодек для отправки аудио (linear16 / flac / opus)
+    #[serde(default)]
+    pub audio_encoding: AudioEncoding,
+
+    /// Энергосбережение: через сколько секунд простоя освобождать микрофон и закрывать соединение.
+    ///
+    /// None — политика по умолчанию (соединение живёт keep_alive_ttl_secs, аудио-устройство не трогаем).
+    /// Some(n) — через n секунд после остановки записи закрываем и WS, и аудио-устройство;
+    /// следующий старт записи лениво поднимает их заново.
+    #[serde(default)]
+    pub idle_teardown_secs: Option<u64>,
+
+    /// Warm-start: открывать соединение заранее (при показе окна), чтобы старт записи был мгновенным.
+    ///
+    /// Работает только при разрешённом keep-alive: прогретое соединение живёт по тому же TTL.
+    #[serde(default)]
+    pub prewarm_connection: bool,
+
+    /// Сколько гипотез (n-best) запрашивать у провайдера для финальных фраз.
+    ///
+    /// 0/1 — только лучшая гипотеза. Провайдеры без поддержки n-best параметр игнорируют.
+    #[serde(default)]
+    pub max_alternatives: u8,
+
+    /// Нормализация чисел/дат/сумм в финальных фразах
+    #[serde(default)]
+    pub normalization: TextNormalizationConfig,
+
+    /// Удаление слов-паразитов ("um", "ну", "эээ") из финальных фраз — до вывода и экспорта
+    #[serde(default)]
+    pub filler_removal: FillerRemovalConfig,
+
+    /// Восстанавливать пунктуацию и регистр локальной моделью (ONNX).
+    ///
+    /// Нужно для провайдеров/моделей, которые отдают текст без пунктуации (например, маленькие Whisper).
+    /// Требует сборки с feature `punctuation` и скачанной модели; иначе финальные фразы не меняются.
+    #[serde(default)]
+    pub punctuate_locally: bool,
+
+    /// Диаризация: провайдер помечает финальные фразы номером говорящего (`Transcription::speaker`).
+    ///
+    /// Нужна в основном для режима встречи; провайдеры без поддержки параметр игнорируют.
+    #[serde(default)]
+    pub diarize: bool,
+
+    /// Сколько кусков файла расшифровывать одновременно через REST API провайдера (Deepgram/AssemblyAI).
+    ///
+    /// Упирается в лимит провайдера на параллельные запросы; 1 — по очереди.
+    #[serde(default = "default_batch_concurrency")]
+    pub batch_concurrency: u8,
+
+    /// Записи короче стольких секунд расшифровываются одним REST запросом вместо стрима (0 — выключено).
+    ///
+    /// Для коротких фраз это дешевле и точнее, но partial'ов до конца записи нет; работает только
+    /// у провайдеров с REST API (Deepgram/AssemblyAI). Если запись дольше порога — переходим на стрим.
+    #[serde(default = "default_short_clip_secs")]
+    pub short_clip_secs: u32,
+
+    /// Модель под конкретный язык у провайдера (en→nova-3, ru→nova-2, ...); перекрывает `model`.
+    ///
+    /// Проверяется по каталогу моделей (`model_support`) при сохранении настроек.
+    #[serde(default)]
+    pub language_models: Vec<LanguageModelOverride>,
+
+    /// Стабилизация partial'ов: слово показывается, когда совпало в стольких подряд partial'ах (0/1 — выключено).
+    ///
+    /// Убирает мигание слов в живом тексте ценой небольшой задержки; финальные фразы не затрагивает.
+    #[serde(default)]
+    pub partial_stability: u8,
+
+    /// Сколько миллисекунд речи, записанной пока открывается стрим, досылать провайдеру (0 — выключено).
+    ///
+    /// Микрофон стартует до подключения к провайдеру, поэтому первые слога не теряются
+    /// на ~500мс установки соединения; что старше окна — выбрасывается.
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+
+    /// Параметры декодирования whisper.cpp (только локальный провайдер)
+    #[serde(default)]
+    pub whisper: WhisperDecodingConfig,
+
+    /// Интервью со стерео-интерфейса: левый и правый каналы расшифровываются отдельными стримами,
+    /// фразы помечаются каналом (`Transcription::channel`).
+    ///
+    /// Моно-устройство в этом режиме работает как обычно — всё идёт в канал 0.
+    #[serde(default)]
+    pub split_channels: bool,
+
+    /// Пауза между фразами, после которой текст начинается с нового абзаца (0 — выключено).
+    ///
+    /// Считается по таймингам сегментов провайдера; у провайдеров без таймингов абзацев нет.
+    #[serde(default)]
+    pub paragraph_pause_ms: u32,
+
+    /// Голосовые команды управления в конце фразы ("stop recording", "switch to English", "paste that").
+    ///
+    /// Распознаются в приложении по тексту любого провайдера (в том числе локального без сети);
+    /// стоп срабатывает уже на промежуточном тексте. Слова команды в текст не попадают.
+    #[serde(default)]
+    pub voice_commands: bool,
+
+    /// A/B сравнение: то же аудио параллельно стримится второму провайдеру (None — выключено).
+    ///
+    /// Текст сравниваемого провайдера только показывается рядом с основным (событие по каждому провайдеру),
+    /// в вставку и историю идёт текст основного. Не работает вместе со стерео-интервью.
+    #[serde(default)]
+    pub comparison_provider: Option<SttProviderType>,
+
+    /// Сколько аудио отправлять стриминговому провайдеру одним сообщением, по провайдерам
+    /// (нет записи — `SttProviderType::default_chunk_ms`).
+    ///
+    /// На каналах с большой задержкой крупные сообщения снижают их число ценой задержки текста.
+    #[serde(default)]
+    pub audio_chunking: Vec<AudioChunkingOverride>,
 }
 
+/// whisper.cpp decoding parameters of the local provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhisperDecodingConfig {
+    /// Подсказка модели: тематика и термины ("медицинские термины: ...") — заметно повышает точность
+    #[serde(default)]
+    pub initial_prompt: Option<String>,
+
+    /// 0.0 — детерминированное декодирование; выше — разнообразнее, но чаще галлюцинации
+    #[serde(default)]
+    pub temperature: f32,
+
+    /// Ширина beam search; 0/1 — жадное декодирование (быстрее)
+    #[serde(default)]
+    pub beam_size: u8,
+
+    /// Окно с вероятностью "нет речи" выше порога считается тишиной и не расшифровывается
+    #[serde(default = "default_whisper_no_speech_threshold")]
+    pub no_speech_threshold: f32,
+
+    /// Замедление записи перед расшифровкой (WSOLA, высота голоса не меняется): 0.9 — в 0.9 раза
+    /// медленнее. Точнее на очень быстрой речи, но расшифровка пропорционально дольше; 1.0 — выключено
+    #[serde(default = "default_whisper_tempo")]
+    pub tempo: f32,
+}
+
+impl Default for WhisperDecodingConfig {
+    fn default() -> Self {
+        Self {
+            initial_prompt: None,
+            temperature: 0.0,
+            beam_size: 0,
+            no_speech_threshold: default_whisper_no_speech_threshold(),
+            tempo: default_whisper_tempo(),
+        }
+    }
+}
+
+impl WhisperDecodingConfig {
+    /// Подсказка без пробелов по краям; None — не задана
+    pub fn initial_prompt(&self) -> Option<&str> {
+        self.initial_prompt.as_deref().map(str::trim).filter(|prompt| !prompt.is_empty())
+    }
+
+    /// Beam search нужен только при ширине больше 1
+    pub fn beam_search_width(&self) -> Option<u8> {
+        (self.beam_size > 1).then(|| self.beam_size.min(MAX_WHISPER_BEAM_SIZE))
+    }
+
+    /// Темп замедления, если оно включено
+    pub fn time_stretch_tempo(&self) -> Option<f32> {
+        (self.tempo < 1.0).then(|| self.tempo.max(MIN_WHISPER_TEMPO))
+    }
+}
+
+/// Model to use for one language of one provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageModelOverride {
+    pub provider: SttProviderType,
+    /// Language code as in `SttConfig::language`
+    pub language: String,
+    /// Model id from the provider catalog (`provider_models`)
+    pub model: String,
+}
+
+/// Audio per message to one streaming provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioChunkingOverride {
+    pub provider: SttProviderType,
+    pub chunk_ms: u32,
+}
+
+/// Верхняя граница n-best: больше гипотез UI всё равно не покажет
+pub const MAX_TRANSCRIPTION_ALTERNATIVES: u8 = 5;
+
+/// Верхняя граница параллельных REST запросов при расшифровке файла
+pub const MAX_BATCH_CONCURRENCY: u8 = 16;
+
+/// Верхняя граница стабилизации partial'ов: дальше текст заметно отстаёт от речи
+pub const MAX_PARTIAL_STABILITY: u8 = 5;
+
+/// Верхняя граница pre-roll: столько ждать подключения и потом догонять речь уже неудобно
+pub const MAX_PRE_ROLL_MS: u32 = 5000;
+
+/// Верхняя граница паузы для абзаца: дольше молчат уже между разными записями
+pub const MAX_PARAGRAPH_PAUSE_MS: u32 = 60_000;
+
+/// Верхняя граница beam search Whisper: шире — кратно медленнее без заметного выигрыша
+pub const MAX_WHISPER_BEAM_SIZE: u8 = 8;
+
+/// Подсказка Whisper ограничена половиной контекста модели (~224 токена)
+pub const MAX_WHISPER_PROMPT_CHARS: usize = 500;
+
+/// Нижняя граница темпа Whisper: при сильном замедлении WSOLA слышно "плывёт", и точность падает
+pub const MIN_WHISPER_TEMPO: f32 = 0.75;
+
+/// Верхняя граница порога коротких записей: дольше без partial'ов ждать неудобно
+pub const MAX_SHORT_CLIP_SECS: u32 = 60;
+
+/// Допустимый диапазон keep-alive TTL, который можно выставить в настройках
+pub const MIN_KEEP_ALIVE_TTL_SECS: u64 = 10;
+pub const MAX_KEEP_ALIVE_TTL_SECS: u64 = 3600;
+
 fn default_keep_alive_ttl_secs() -> u64 {
     300
 }
 
+fn default_batch_concurrency() -> u8 {
+    4
+}
+
+fn default_short_clip_secs() -> u32 {
+    10
+}
+
+fn default_pre_roll_ms() -> u32 {
+    1500
+}
+
+// Значение по умолчанию в whisper.cpp
+fn default_whisper_no_speech_threshold() -> f32 {
+    0.6
+}
+
+fn default_whisper_tempo() -> f32 {
+    1.0
+}
+
 impl Default for SttConfig {
     fn default() -> Self {
         Self {
@@ -98,6 +707,25 @@ impl Default for SttConfig {
             keep_connection_alive: false, // Безопасно по умолчанию для всех провайдеров
             keep_alive_ttl_secs: default_keep_alive_ttl_secs(),
             deepgram_keyterms: None,
+            audio_encoding: AudioEncoding::default(),
+            idle_teardown_secs: None,
+            prewarm_connection: false,
+            max_alternatives: 0,
+            normalization: TextNormalizationConfig::default(),
+            filler_removal: FillerRemovalConfig::default(),
+            punctuate_locally: false,
+            diarize: false,
+            batch_concurrency: default_batch_concurrency(),
+            short_clip_secs: default_short_clip_secs(),
+            language_models: Vec::new(),
+            partial_stability: 0,
+            pre_roll_ms: default_pre_roll_ms(),
+            whisper: WhisperDecodingConfig::default(),
+            split_channels: false,
+            paragraph_pause_ms: 0,
+            voice_commands: false,
+            comparison_provider: None,
+            audio_chunking: Vec::new(),
         }
     }
 }
@@ -119,8 +747,172 @@ impl SttConfig {
         self.model = Some(model.into());
         self
     }
+
+    pub fn with_language_model(mut self, language: impl Into<String>, model: impl Into<String>) -> Self {
+        let language = language.into();
+        self.language_models.retain(|o| !(o.provider == self.provider && o.language == language));
+        self.language_models.push(LanguageModelOverride {
+            provider: self.provider,
+            language,
+            model: model.into(),
+        });
+        self
+    }
+
+    /// Аудио в одном сообщении текущему провайдеру, мс (в допустимых для него пределах)
+    pub fn chunk_ms(&self) -> u32 {
+        let range = self.provider.chunk_ms_range();
+        self.audio_chunking
+            .iter()
+            .find(|o| o.provider == self.provider)
+            .map_or(self.provider.default_chunk_ms(), |o| o.chunk_ms)
+            .clamp(*range.start(), *range.end())
+    }
+
+    /// То же в семплах (моно)
+    pub fn chunk_samples(&self, sample_rate: u32) -> usize {
+        (self.chunk_ms() as u64 * sample_rate as u64 / 1000) as usize
+    }
+
+    /// Модель для текущих провайдера и языка: override из `language_models`, иначе `model`
+    pub fn effective_model(&self) -> Option<&str> {
+        self.language_models
+            .iter()
+            .find(|o| o.provider == self.provider && o.language == self.language)
+            .map(|o| o.model.as_str())
+            .or(self.model.as_deref())
+    }
+
+    pub fn with_audio_encoding(mut self, encoding: AudioEncoding) -> Self {
+        self.audio_encoding = encoding;
+        self
+    }
+
+    pub fn with_idle_teardown_secs(mut self, secs: u64) -> Self {
+        self.idle_teardown_secs = Some(secs);
+        self
+    }
+
+    pub fn with_max_alternatives(mut self, count: u8) -> Self {
+        self.max_alternatives = count;
+        self
+    }
+
+    /// Добавляет термины в deepgram_keyterms (без дублей, регистр не учитывается)
+    pub fn merge_keyterms(&mut self, extra: &[String]) {
+        let mut terms: Vec<String> = self
+            .deepgram_keyterms
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        for term in extra.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            // Запятая — разделитель в deepgram_keyterms, такой термин не передать
+            if term.contains(',') {
+                continue;
+            }
+            if !terms.iter().any(|t| t.to_lowercase() == term.to_lowercase()) {
+                terms.push(term.to_string());
+            }
+        }
+
+        self.deepgram_keyterms = if terms.is_empty() { None } else { Some(terms.join(", ")) };
+    }
+
+    /// Сколько REST запросов держать в полёте при расшифровке файла (1..=MAX_BATCH_CONCURRENCY)
+    pub fn effective_batch_concurrency(&self) -> usize {
+        self.batch_concurrency.clamp(1, MAX_BATCH_CONCURRENCY) as usize
+    }
+
+    /// Порог короткой записи в секундах; None — короткие записи тоже идут стримом
+    pub fn short_clip_threshold_secs(&self) -> Option<u32> {
+        match self.short_clip_secs {
+            0 => None,
+            secs => Some(secs.min(MAX_SHORT_CLIP_SECS)),
+        }
+    }
+
+    /// Количество гипотез для запроса к провайдеру; None — n-best не нужен
+    pub fn requested_alternatives(&self) -> Option<u8> {
+        if self.max_alternatives > 1 {
+            Some(self.max_alternatives.min(MAX_TRANSCRIPTION_ALTERNATIVES))
+        } else {
+            None
+        }
+    }
 }
 
+/// STT settings overridden for a single recording session (deep link, recording profile hotkey)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SttSessionOverride {
+    pub provider: Option<SttProviderType>,
+    pub language: Option<String>,
+    pub model: Option<String>,
+}
+
+impl SttSessionOverride {
+    /// Только язык (voicetotext://record?lang=..)
+    pub fn language(language: impl Into<String>) -> Self {
+        Self {
+            language: Some(language.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.provider.is_none() && self.language.is_none() && self.model.is_none()
+    }
+
+    /// Накладывает override на конфиг сессии.
+    ///
+    /// Явный язык выключает автоопределение. Модель сохранённого конфига относится к его провайдеру,
+    /// поэтому при смене провайдера без своей модели берётся модель провайдера по умолчанию.
+    pub fn apply_to(&self, config: &mut SttConfig) {
+        if let Some(provider) = self.provider {
+            if provider != config.provider {
+                config.provider = provider;
+                config.model = None;
+            }
+        }
+        if let Some(language) = self.language.as_ref() {
+            config.language = language.clone();
+            config.auto_detect_language = false;
+        }
+        if let Some(model) = self.model.as_ref() {
+            config.model = Some(model.clone());
+        }
+    }
+}
+
+/// Recording profile: records with its own provider/language/model, usually bound to a hotkey
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingProfile {
+    pub name: String,
+
+    /// Хоткей "записать с этим профилем" (например "CmdOrCtrl+Shift+1"). None — без хоткея
+    #[serde(default)]
+    pub hotkey: Option<String>,
+
+    /// Что переопределить в STT-настройках на время записи (пустые поля — как в сохранённом конфиге)
+    #[serde(flatten)]
+    pub stt: SttSessionOverride,
+
+    /// Профиль доставки текста на эту запись (см. AppConfig::output_profiles)
+    #[serde(default)]
+    pub output_profile: Option<String>,
+
+    /// Свои звуки обратной связи на эту запись; None — как в AppConfig::feedback
+    #[serde(default)]
+    pub feedback: Option<FeedbackConfig>,
+}
+
+/// Максимальный интервал между partial-событиями в webview
+pub const MAX_PARTIAL_UPDATE_INTERVAL_MS: u64 = 1000;
+
 /// Application-wide configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -159,6 +951,90 @@ pub struct AppConfig {
 
     /// Maximum number of history items
     pub max_history_items: usize,
+
+    /// Профили доставки финального текста (набор sinks на профиль)
+    pub output_profiles: Vec<TextOutputProfile>,
+
+    /// Имя активного профиля доставки. None — sinks из auto_copy_to_clipboard/auto_paste_text
+    pub active_output_profile: Option<String>,
+
+    /// Профили записи со своим провайдером/языком и хоткеем
+    pub recording_profiles: Vec<RecordingProfile>,
+
+    /// Минимальная уверенность распознавания (0.0-1.0) для финальных фраз. None — без фильтра
+    pub min_confidence: Option<f32>,
+
+    /// Что делать с фразами ниже min_confidence
+    pub low_confidence_action: LowConfidenceAction,
+
+    /// Оверлей-индикатор записи поверх всех окон
+    pub recording_overlay: RecordingOverlayConfig,
+
+    /// Запускать приложение при входе в систему
+    pub launch_at_login: bool,
+
+    /// При автозапуске не показывать окна — приложение ждёт хоткея в трее
+    pub start_minimized: bool,
+
+    /// Анонимная телеметрия (провайдер, длительность сессий, типы ошибок). Только по согласию
+    pub telemetry_enabled: bool,
+
+    /// Как вставлять текст (auto-paste/typing): в курсор, вместо выделения или дописывать
+    pub paste_strategy: PasteStrategy,
+
+    /// Стратегия вставки для отдельных приложений (перекрывает paste_strategy)
+    pub paste_app_rules: Vec<PasteAppRule>,
+
+    /// Регистр финального текста при доставке (профиль доставки и правило приложения перекрывают)
+    pub text_casing: TextCasing,
+
+    /// Режим живых субтитров (шрифт, позиция, источник звука)
+    pub captions: CaptionsConfig,
+
+    /// Режим встречи: разбиение на темы и экспорт
+    pub meeting: MeetingConfig,
+
+    /// Называть сессии по встрече из календаря (выключено по умолчанию)
+    pub calendar: CalendarConfig,
+
+    /// Прогрев соединения перед обычным временем диктовки (выключено по умолчанию)
+    pub warm_pool: WarmPoolConfig,
+
+    /// Папка моделей Whisper (например, на внешнем диске); None — в данных приложения
+    pub models_dir: Option<String>,
+
+    /// Не чаще одного partial-события в webview за этот интервал (0 — каждый partial).
+    /// Разгружает слабые машины при быстрой речи
+    pub partial_update_interval_ms: u64,
+
+    /// Auto-paste вставляет каждый стабилизированный сегмент сразу, не дожидаясь конца фразы.
+    /// Заметно меньше задержка, но провайдер иногда правит сегмент уже после вставки
+    pub instant_paste: bool,
+
+    /// Что делать с окном записи после остановки хоткеем
+    pub window_behavior_on_stop: WindowBehaviorOnStop,
+
+    /// При включённом режиме фокусировки ОС (Focus, Focus Assist, "не беспокоить") запись по хоткею
+    /// не показывает окно, оверлей и не играет звуки — текст распознаётся и вставляется как обычно
+    pub respect_focus_mode: bool,
+
+    /// Запоминать приложение и заголовок окна, в котором начата запись (история и аналитика по приложениям).
+    /// Хранится только локально и в телеметрию не попадает
+    pub capture_app_context: bool,
+
+    /// Хоткей, включающий и выключающий диктовку по буквам ("alpha bravo" → "ab");
+    /// None — режим включается только голосовой командой
+    pub spelling_hotkey: Option<String>,
+
+    /// Звуки старта/остановки/ошибки записи (профиль записи может задать свои)
+    pub feedback: FeedbackConfig,
+
+    /// Пороги памяти буферов аудио, истории и очередей: выше порога — предупреждение в лог и событие
+    pub memory_alarms: MemoryAlarmConfig,
+
+    /// Отладка: WAV/MP3 файл, который проигрывается вместо микрофона (демо, проверка без железа);
+    /// None — обычный вход. Задаётся через set_mock_audio_file, который проверяет файл
+    pub mock_audio_file: Option<String>,
 }
 
 impl Default for AppConfig {
@@ -174,7 +1050,98 @@ impl Default for AppConfig {
             selected_audio_device: None, // По умолчанию используем системное устройство
             keep_history: true,
             max_history_items: 20,
+            output_profiles: Vec::new(),
+            active_output_profile: None,
+            recording_profiles: Vec::new(),
+            min_confidence: None,
+            low_confidence_action: LowConfidenceAction::Flag,
+            recording_overlay: RecordingOverlayConfig::default(),
+            launch_at_login: false,
+            start_minimized: true,
+            telemetry_enabled: false,
+            paste_strategy: PasteStrategy::default(),
+            paste_app_rules: Vec::new(),
+            text_casing: TextCasing::default(),
+            captions: CaptionsConfig::default(),
+            meeting: MeetingConfig::default(),
+            calendar: CalendarConfig::default(),
+            warm_pool: WarmPoolConfig::default(),
+            models_dir: None,
+            partial_update_interval_ms: 0,
+            instant_paste: false,
+            window_behavior_on_stop: WindowBehaviorOnStop::default(),
+            respect_focus_mode: false,
+            capture_app_context: true,
+            spelling_hotkey: None,
+            feedback: FeedbackConfig::default(),
+            memory_alarms: MemoryAlarmConfig::default(),
+            mock_audio_file: None,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Действие для финальной фразы с уверенностью ниже порога; None — фраза проходит как есть.
+    ///
+    /// Фразы без confidence (провайдер его не прислал) не фильтруются: "неизвестно" не значит "плохо".
+    pub fn low_confidence_verdict(&self, transcription: &Transcription) -> Option<LowConfidenceAction> {
+        let threshold = self.min_confidence?;
+        let confidence = transcription.confidence?;
+        if confidence < threshold {
+            Some(self.low_confidence_action)
+        } else {
+            None
+        }
+    }
+
+    /// Стратегия вставки для приложения (macOS bundle ID; None — приложение неизвестно)
+    pub fn paste_strategy_for_app(&self, app_id: Option<&str>) -> PasteStrategy {
+        paste_strategy_for(&self.paste_app_rules, app_id, self.paste_strategy)
+    }
+
+    /// Регистр текста при доставке: правило приложения → профиль доставки → общая настройка.
+    ///
+    /// `profile` — профиль этой записи (deep link / профиль записи), иначе активный профиль.
+    pub fn text_casing_for(&self, profile: Option<&str>, app_id: Option<&str>) -> TextCasing {
+        let profile_casing = profile
+            .or(self.active_output_profile.as_deref())
+            .and_then(|name| self.output_profiles.iter().find(|p| p.name == name))
+            .and_then(|p| p.casing);
+        text_casing_for(&self.paste_app_rules, app_id, profile_casing.unwrap_or(self.text_casing))
+    }
+
+    /// Профиль записи по имени; None — такого профиля нет
+    pub fn recording_profile(&self, name: &str) -> Option<&RecordingProfile> {
+        self.recording_profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Sinks профиля доставки по имени; None — такого профиля нет
+    pub fn profile_sinks(&self, name: &str) -> Option<Vec<TextOutputSinkConfig>> {
+        self.output_profiles
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.sinks.clone())
+    }
+
+    /// Sinks активного профиля доставки текста.
+    ///
+    /// Если профиль не выбран (или не найден) — собираем sinks из старых флагов
+    /// auto_paste_text / auto_copy_to_clipboard, чтобы существующие настройки продолжали работать.
+    pub fn active_output_sinks(&self) -> Vec<TextOutputSinkConfig> {
+        if let Some(sinks) = self.active_output_profile.as_deref().and_then(|name| self.profile_sinks(name)) {
+            return sinks;
+        }
+
+        let mut sinks = Vec::new();
+        if self.auto_paste_text {
+            sinks.push(TextOutputSinkConfig::AutoPaste {
+                fallback_to_clipboard: true,
+            });
+        }
+        if self.auto_copy_to_clipboard {
+            sinks.push(TextOutputSinkConfig::plain_clipboard());
         }
+        sinks
     }
 }
 
@@ -197,6 +1164,39 @@ impl Default for UiPreferences {
     }
 }
 
+/// Канал обновлений приложения
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+/// Настройки обновлений: канал и отложенные/пропущенные версии
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdatePreferences {
+    pub channel: UpdateChannel,
+
+    /// Версия, о которой пользователь попросил больше не напоминать
+    pub skipped_version: Option<String>,
+
+    /// "Напомнить позже": до этого момента (unix ms) фоновая проверка молчит
+    pub remind_after_ms: Option<i64>,
+}
+
+impl UpdatePreferences {
+    /// Показывать ли уведомление о версии при фоновой проверке.
+    /// Ручная проверка ("Проверить обновления") эти настройки не учитывает.
+    pub fn should_notify(&self, version: &str, now_ms: i64) -> bool {
+        if self.skipped_version.as_deref() == Some(version) {
+            return false;
+        }
+        self.remind_after_ms.map_or(true, |after| now_ms >= after)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +1206,30 @@ mod tests {
         assert_eq!(SttProviderType::default(), SttProviderType::Backend);
     }
 
+    #[test]
+    fn only_local_whisper_works_without_network() {
+        use crate::domain::{model_support, ModelSupport};
+
+        assert!(!SttProviderType::WhisperLocal.requires_network());
+        assert!(SttProviderType::Backend.requires_network());
+        assert!(SttProviderType::Deepgram.requires_network());
+        assert!(OFFLINE_WHISPER_MODELS
+            .iter()
+            .all(|model| model_support(SttProviderType::WhisperLocal, model, "ru") == ModelSupport::Supported));
+    }
+
+    #[test]
+    fn idle_billing_policy_caps_only_billed_providers() {
+        let assemblyai = SttProviderType::AssemblyAI.idle_billing_policy();
+        assert!(assemblyai.billed_while_idle);
+        assert_eq!(assemblyai.cap_idle_secs(300), ASSEMBLYAI_MAX_IDLE_SECS);
+        assert_eq!(assemblyai.cap_idle_secs(15), 15);
+
+        let deepgram = SttProviderType::Deepgram.idle_billing_policy();
+        assert!(!deepgram.billed_while_idle);
+        assert_eq!(deepgram.cap_idle_secs(300), 300);
+    }
+
     #[test]
     fn test_stt_config_default() {
         let config = SttConfig::default();
@@ -221,6 +1245,28 @@ mod tests {
         assert!(config.backend_url.is_none());
         assert!(!config.keep_connection_alive);
         assert_eq!(config.keep_alive_ttl_secs, 300);
+        assert_eq!(config.partial_stability, 0);
+        assert_eq!(config.pre_roll_ms, 1500);
+        assert_eq!(config.whisper, WhisperDecodingConfig::default());
+    }
+
+    #[test]
+    fn whisper_decoding_defaults_to_greedy_without_prompt() {
+        let whisper: WhisperDecodingConfig = serde_json::from_str(r#"{"initial_prompt": "  "}"#).unwrap();
+        assert_eq!(whisper.initial_prompt(), None);
+        assert_eq!(whisper.beam_search_width(), None);
+        assert_eq!(whisper.no_speech_threshold, 0.6);
+        assert_eq!(whisper.time_stretch_tempo(), None);
+
+        let whisper = WhisperDecodingConfig {
+            initial_prompt: Some(" медицинские термины ".to_string()),
+            beam_size: 20,
+            tempo: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(whisper.initial_prompt(), Some("медицинские термины"));
+        assert_eq!(whisper.beam_search_width(), Some(MAX_WHISPER_BEAM_SIZE));
+        assert_eq!(whisper.time_stretch_tempo(), Some(MIN_WHISPER_TEMPO));
     }
 
     #[test]
@@ -265,6 +1311,15 @@ mod tests {
         assert_eq!(config.microphone_sensitivity, 100);
         assert!(config.keep_history);
         assert_eq!(config.max_history_items, 20);
+        assert!(!config.launch_at_login);
+        assert!(config.start_minimized);
+        assert!(!config.telemetry_enabled);
+        assert_eq!(config.paste_strategy, PasteStrategy::ReplaceSelection);
+        assert!(config.paste_app_rules.is_empty());
+        assert!(!config.meeting.llm_topic_breaks);
+        assert!(!config.stt.diarize);
+        assert!(!config.calendar.enabled);
+        assert!(!config.warm_pool.enabled);
     }
 
     #[test]
@@ -282,6 +1337,375 @@ mod tests {
         assert_eq!(config1.language, config2.language);
     }
 
+    #[test]
+    fn test_audio_encoding_default_and_legacy_config() {
+        assert_eq!(SttConfig::default().audio_encoding, AudioEncoding::Linear16);
+
+        // Старые конфиги без поля audio_encoding должны читаться
+        let mut value = serde_json::to_value(SttConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("audio_encoding");
+        let config: SttConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.audio_encoding, AudioEncoding::Linear16);
+
+        let config = SttConfig::new(SttProviderType::Deepgram).with_audio_encoding(AudioEncoding::Flac);
+        assert_eq!(serde_json::to_value(config.audio_encoding).unwrap(), "flac");
+    }
+
+    #[test]
+    fn test_language_model_overrides() {
+        let config = SttConfig::new(SttProviderType::Deepgram)
+            .with_model("nova-3")
+            .with_language_model("ru", "nova-2")
+            .with_language_model("ru", "nova-3")
+            .with_language("ru");
+        assert_eq!(config.language_models.len(), 1);
+        assert_eq!(config.effective_model(), Some("nova-3"));
+
+        let config = config.with_language_model("ru", "nova-2");
+        assert_eq!(config.effective_model(), Some("nova-2"));
+        assert_eq!(config.clone().with_language("en").effective_model(), Some("nova-3"));
+
+        // Override другого провайдера не применяется
+        let mut whisper = config.clone();
+        whisper.provider = SttProviderType::WhisperLocal;
+        whisper.model = None;
+        assert_eq!(whisper.effective_model(), None);
+
+        // Старый конфиг без поля
+        let mut value = serde_json::to_value(SttConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("language_models");
+        let config: SttConfig = serde_json::from_value(value).unwrap();
+        assert!(config.language_models.is_empty());
+    }
+
+    #[test]
+    fn test_audio_chunking_per_provider() {
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        assert_eq!(config.chunk_ms(), 50);
+        assert_eq!(config.chunk_samples(16000), 800);
+
+        config.audio_chunking = vec![
+            AudioChunkingOverride {
+                provider: SttProviderType::Deepgram,
+                chunk_ms: 250,
+            },
+            AudioChunkingOverride {
+                provider: SttProviderType::AssemblyAI,
+                chunk_ms: 40,
+            },
+        ];
+        assert_eq!(config.chunk_samples(16000), 4000);
+        config.provider = SttProviderType::Backend;
+        assert_eq!(config.chunk_ms(), 30);
+        // Меньше минимума провайдера не отправляем даже со старым конфигом
+        config.provider = SttProviderType::AssemblyAI;
+        assert_eq!(config.chunk_ms(), 50);
+    }
+
+    #[test]
+    fn test_idle_teardown_default_and_legacy_config() {
+        assert_eq!(SttConfig::default().idle_teardown_secs, None);
+
+        let mut value = serde_json::to_value(SttConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("idle_teardown_secs");
+        let config: SttConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.idle_teardown_secs, None);
+
+        let config = SttConfig::new(SttProviderType::Deepgram).with_idle_teardown_secs(30);
+        assert_eq!(config.idle_teardown_secs, Some(30));
+    }
+
+    #[test]
+    fn test_prewarm_connection_disabled_by_default() {
+        assert!(!SttConfig::default().prewarm_connection);
+
+        let mut value = serde_json::to_value(SttConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("prewarm_connection");
+        let config: SttConfig = serde_json::from_value(value).unwrap();
+        assert!(!config.prewarm_connection);
+    }
+
+    #[test]
+    fn test_requested_alternatives() {
+        let config = SttConfig::default();
+        assert_eq!(config.max_alternatives, 0);
+        assert_eq!(config.requested_alternatives(), None);
+        assert_eq!(SttConfig::default().with_max_alternatives(1).requested_alternatives(), None);
+        assert_eq!(SttConfig::default().with_max_alternatives(3).requested_alternatives(), Some(3));
+        assert_eq!(
+            SttConfig::default().with_max_alternatives(50).requested_alternatives(),
+            Some(MAX_TRANSCRIPTION_ALTERNATIVES)
+        );
+    }
+
+    #[test]
+    fn test_merge_keyterms_skips_duplicates() {
+        let mut config = SttConfig::default();
+        config.merge_keyterms(&[]);
+        assert_eq!(config.deepgram_keyterms, None);
+
+        config.deepgram_keyterms = Some("Kubernetes, VoicetextAI".to_string());
+        config.merge_keyterms(&[
+            "kubernetes".to_string(),
+            "Deepgram".to_string(),
+            " ".to_string(),
+            "a, b".to_string(),
+        ]);
+        assert_eq!(
+            config.deepgram_keyterms.as_deref(),
+            Some("Kubernetes, VoicetextAI, Deepgram")
+        );
+    }
+
+    #[test]
+    fn test_active_output_sinks_legacy_flags_and_profiles() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.active_output_sinks(), vec![TextOutputSinkConfig::plain_clipboard()]);
+
+        config.auto_paste_text = true;
+        assert_eq!(
+            config.active_output_sinks(),
+            vec![
+                TextOutputSinkConfig::AutoPaste {
+                    fallback_to_clipboard: true
+                },
+                TextOutputSinkConfig::plain_clipboard(),
+            ]
+        );
+
+        config.output_profiles.push(TextOutputProfile {
+            name: "notes".to_string(),
+            sinks: vec![TextOutputSinkConfig::FileAppend {
+                path: "/tmp/notes.txt".to_string(),
+            }],
+            casing: None,
+        });
+        config.active_output_profile = Some("notes".to_string());
+        assert_eq!(config.active_output_sinks().len(), 1);
+
+        // Неизвестный профиль — фоллбек на флаги
+        config.active_output_profile = Some("missing".to_string());
+        assert_eq!(config.active_output_sinks().len(), 2);
+
+        assert_eq!(config.profile_sinks("notes").map(|s| s.len()), Some(1));
+        assert_eq!(config.profile_sinks("missing"), None);
+    }
+
+    #[test]
+    fn test_text_casing_precedence() {
+        let mut config = AppConfig {
+            text_casing: TextCasing::Sentence,
+            ..Default::default()
+        };
+        assert_eq!(config.text_casing_for(None, None), TextCasing::Sentence);
+
+        config.output_profiles.push(TextOutputProfile {
+            name: "shouting".to_string(),
+            sinks: Vec::new(),
+            casing: Some(TextCasing::Upper),
+        });
+        config.active_output_profile = Some("shouting".to_string());
+        assert_eq!(config.text_casing_for(None, None), TextCasing::Upper);
+        // Профиль записи важнее активного, даже если в нём регистр не задан
+        assert_eq!(config.text_casing_for(Some("missing"), None), TextCasing::Sentence);
+
+        config.paste_app_rules.push(PasteAppRule {
+            app_id: "com.googlecode.iterm2".to_string(),
+            strategy: PasteStrategy::InsertAtCursor,
+            casing: Some(TextCasing::Lower),
+        });
+        assert_eq!(config.text_casing_for(None, Some("com.googlecode.iterm2")), TextCasing::Lower);
+        assert_eq!(config.text_casing_for(None, Some("com.apple.Notes")), TextCasing::Upper);
+    }
+
+    #[test]
+    fn test_low_confidence_verdict() {
+        let mut config = AppConfig::default();
+        let weak = Transcription::final_result("мм".to_string()).with_confidence(0.4);
+        let strong = Transcription::final_result("привет".to_string()).with_confidence(0.95);
+        let unknown = Transcription::final_result("без оценки".to_string());
+
+        // Без порога ничего не фильтруем
+        assert_eq!(config.low_confidence_verdict(&weak), None);
+
+        config.min_confidence = Some(0.6);
+        assert_eq!(config.low_confidence_verdict(&weak), Some(LowConfidenceAction::Flag));
+        assert_eq!(config.low_confidence_verdict(&strong), None);
+        assert_eq!(config.low_confidence_verdict(&unknown), None);
+
+        config.low_confidence_action = LowConfidenceAction::Hold;
+        assert_eq!(config.low_confidence_verdict(&weak), Some(LowConfidenceAction::Hold));
+
+        // Старый конфиг без новых полей
+        let legacy: AppConfig = serde_json::from_str(r#"{"recording_hotkey":"Ctrl+X"}"#).unwrap();
+        assert_eq!(legacy.min_confidence, None);
+        assert_eq!(legacy.low_confidence_action, LowConfidenceAction::Flag);
+
+        let action: LowConfidenceAction = serde_json::from_str(r#""suppress""#).unwrap();
+        assert_eq!(action, LowConfidenceAction::Suppress);
+    }
+
+    #[test]
+    fn test_normalization_rules_for_language() {
+        let mut config = TextNormalizationConfig::default();
+        assert_eq!(config.rules_for("en"), None);
+
+        config.enabled = true;
+        assert_eq!(config.rules_for("de"), Some(NormalizationRules::default()));
+
+        let ru = NormalizationRules {
+            currency: false,
+            ..NormalizationRules::default()
+        };
+        config.languages.insert("ru".to_string(), ru);
+        assert_eq!(config.rules_for("ru"), Some(ru));
+        assert_eq!(config.rules_for("ru-RU"), Some(ru));
+
+        // Старый конфиг без секции нормализации
+        let legacy: SttConfig = serde_json::from_str(
+            r#"{"provider":"deepgram","language":"en","auto_detect_language":false,"enable_punctuation":true,"filter_profanity":false,"keep_connection_alive":false}"#,
+        )
+        .unwrap();
+        assert!(!legacy.normalization.enabled);
+        assert!(!legacy.filler_removal.enabled);
+    }
+
+    #[test]
+    fn test_filler_removal_custom_words_for_language() {
+        let mut config = FillerRemovalConfig::default();
+        assert_eq!(config.custom_words_for("ru"), None);
+
+        config.enabled = true;
+        config.languages.insert("ru".to_string(), vec!["блин".to_string(), " ".to_string()]);
+        config.languages.insert("ru-RU".to_string(), vec!["короче говоря".to_string()]);
+        assert_eq!(config.custom_words_for("de"), Some(Vec::new()));
+        assert_eq!(config.custom_words_for("ru"), Some(vec!["блин".to_string()]));
+        assert_eq!(
+            config.custom_words_for("ru-RU"),
+            Some(vec!["короче говоря".to_string(), "блин".to_string()])
+        );
+
+        let parsed: FillerRemovalConfig = serde_json::from_str(r#"{"enabled":true}"#).unwrap();
+        assert_eq!(parsed.aggressiveness, FillerAggressiveness::Moderate);
+    }
+
+    #[test]
+    fn test_recording_profile_override() {
+        let profile: RecordingProfile = serde_json::from_str(
+            r#"{"name":"EN","hotkey":"CmdOrCtrl+Shift+1","provider":"deepgram","language":"en"}"#,
+        )
+        .unwrap();
+        assert_eq!(profile.stt.provider, Some(SttProviderType::Deepgram));
+        assert_eq!(profile.output_profile, None);
+        assert_eq!(profile.feedback, None);
+
+        let mut config = SttConfig::new(SttProviderType::WhisperLocal).with_language("ru").with_model("base");
+        config.auto_detect_language = true;
+        profile.stt.apply_to(&mut config);
+        assert_eq!(config.provider, SttProviderType::Deepgram);
+        assert_eq!(config.language, "en");
+        assert!(!config.auto_detect_language);
+        // Модель Whisper не переносится на Deepgram
+        assert_eq!(config.model, None);
+
+        // Пустой override ничего не меняет
+        let mut config = SttConfig::new(SttProviderType::WhisperLocal).with_model("base");
+        assert!(SttSessionOverride::default().is_empty());
+        SttSessionOverride::default().apply_to(&mut config);
+        assert_eq!(config.model.as_deref(), Some("base"));
+
+        let mut app = AppConfig::default();
+        assert!(app.recording_profile("EN").is_none());
+        app.recording_profiles.push(profile);
+        assert!(app.recording_profile("EN").is_some());
+    }
+
+    #[test]
+    fn test_recording_overlay_config_serialization() {
+        assert!(!AppConfig::default().recording_overlay.enabled);
+
+        let overlay: RecordingOverlayConfig = serde_json::from_str(r#"{"enabled":true}"#).unwrap();
+        assert_eq!(overlay.position, RecordingOverlayPosition::MenuBar);
+
+        let overlay: RecordingOverlayConfig =
+            serde_json::from_str(r#"{"enabled":true,"position":"cursor"}"#).unwrap();
+        assert_eq!(overlay.position, RecordingOverlayPosition::Cursor);
+    }
+
+    #[test]
+    fn test_window_behavior_on_stop_serialization() {
+        assert_eq!(AppConfig::default().window_behavior_on_stop, WindowBehaviorOnStop::HideImmediately);
+
+        let behavior: WindowBehaviorOnStop = serde_json::from_str(r#"{"mode":"hide_after_delay","secs":600}"#).unwrap();
+        assert_eq!(
+            behavior.normalized(),
+            WindowBehaviorOnStop::HideAfterDelay {
+                secs: MAX_WINDOW_HIDE_DELAY_SECS
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&WindowBehaviorOnStop::KeepWindow).unwrap(),
+            r#"{"mode":"keep_window"}"#
+        );
+    }
+
+    #[test]
+    fn test_captions_config_defaults_and_normalization() {
+        let captions: CaptionsConfig = serde_json::from_str(r#"{"position":"top"}"#).unwrap();
+        assert_eq!(captions.position, CaptionsPosition::Top);
+        assert_eq!(captions.font_size, 28);
+        assert_eq!(captions.source_device, None);
+
+        let captions = CaptionsConfig {
+            font_size: 200,
+            max_lines: 0,
+            source_device: Some("  ".to_string()),
+            ..CaptionsConfig::default()
+        }
+        .normalized();
+        assert_eq!(captions.font_size, MAX_CAPTIONS_FONT_SIZE);
+        assert_eq!(captions.max_lines, 1);
+        assert_eq!(captions.source_device, None);
+    }
+
+    #[test]
+    fn test_meeting_config_normalization() {
+        let meeting: MeetingConfig = serde_json::from_str(r#"{"topic_gap_secs":0.5}"#).unwrap();
+        assert_eq!(meeting.min_topic_secs, 30.0);
+
+        let meeting = meeting.normalized();
+        assert_eq!(meeting.topic_gap_secs, MIN_MEETING_TOPIC_GAP_SECS);
+
+        assert!(!meeting.trim_silence);
+
+        let meeting = MeetingConfig {
+            topic_gap_secs: f64::NAN,
+            min_topic_secs: -5.0,
+            trim_padding_secs: 60.0,
+            ..MeetingConfig::default()
+        }
+        .normalized();
+        assert_eq!(meeting.topic_gap_secs, 8.0);
+        assert_eq!(meeting.min_topic_secs, 0.0);
+        assert_eq!(meeting.trim_padding_secs, MAX_MEETING_TRIM_PADDING_SECS);
+    }
+
+    #[test]
+    fn test_update_preferences_should_notify() {
+        let prefs: UpdatePreferences = serde_json::from_str("{}").unwrap();
+        assert_eq!(prefs.channel, UpdateChannel::Stable);
+        assert!(prefs.should_notify("1.2.0", 0));
+
+        let prefs = UpdatePreferences {
+            channel: UpdateChannel::Beta,
+            skipped_version: Some("1.2.0".to_string()),
+            remind_after_ms: Some(1_000),
+        };
+        assert!(!prefs.should_notify("1.2.0", 5_000));
+        assert!(!prefs.should_notify("1.3.0", 999));
+        assert!(prefs.should_notify("1.3.0", 1_000));
+    }
+
     #[test]
     fn test_app_config_clone() {
         let config1 = AppConfig::default();
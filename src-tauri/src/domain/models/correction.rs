@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Learned correction: the recognizer keeps producing `wrong`, the user means `right`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorrectionEntry {
+    /// Слово или фраза, как её распознал провайдер
+    pub wrong: String,
+    /// Исправленный пользователем вариант
+    pub right: String,
+    /// Когда пара была выучена (unix seconds)
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+impl CorrectionEntry {
+    pub fn new(wrong: impl Into<String>, right: impl Into<String>) -> Self {
+        Self {
+            wrong: wrong.into(),
+            right: right.into(),
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_else(|_| std::time::Duration::from_secs(0))
+                .as_secs() as i64,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// Recording event the user gets sound (or, later, haptic) feedback for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackEvent {
+    RecordingStarted,
+    RecordingStopped,
+    Error,
+}
+
+/// Максимальная громкость звуков обратной связи, в процентах
+pub const MAX_FEEDBACK_VOLUME: u8 = 100;
+
+/// Sound feedback for recording events, played natively by the backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedbackConfig {
+    /// По умолчанию выключено: окно записи играет свои UI-звуки
+    pub sounds_enabled: bool,
+
+    /// Громкость, 0-100%
+    pub volume: u8,
+
+    pub start_sound: bool,
+    pub stop_sound: bool,
+    pub error_sound: bool,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            sounds_enabled: false,
+            volume: 60,
+            start_sound: true,
+            stop_sound: true,
+            error_sound: true,
+        }
+    }
+}
+
+impl FeedbackConfig {
+    /// Громкость звука для события (0.0-1.0); None — звук не нужен
+    pub fn sound_volume(&self, event: FeedbackEvent) -> Option<f32> {
+        let wanted = match event {
+            FeedbackEvent::RecordingStarted => self.start_sound,
+            FeedbackEvent::RecordingStopped => self.stop_sound,
+            FeedbackEvent::Error => self.error_sound,
+        };
+        let volume = self.volume.min(MAX_FEEDBACK_VOLUME);
+        (self.sounds_enabled && wanted && volume > 0).then(|| volume as f32 / MAX_FEEDBACK_VOLUME as f32)
+    }
+
+    pub fn normalized(self) -> Self {
+        Self {
+            volume: self.volume.min(MAX_FEEDBACK_VOLUME),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sound_volume_respects_switches() {
+        assert_eq!(FeedbackConfig::default().sound_volume(FeedbackEvent::RecordingStarted), None);
+
+        let config = FeedbackConfig {
+            sounds_enabled: true,
+            volume: 150,
+            stop_sound: false,
+            ..FeedbackConfig::default()
+        };
+        assert_eq!(config.sound_volume(FeedbackEvent::RecordingStarted), Some(1.0));
+        assert_eq!(config.sound_volume(FeedbackEvent::RecordingStopped), None);
+        assert_eq!(config.normalized().volume, MAX_FEEDBACK_VOLUME);
+
+        let muted = FeedbackConfig { volume: 0, ..config };
+        assert_eq!(muted.sound_volume(FeedbackEvent::Error), None);
+
+        let legacy: FeedbackConfig = serde_json::from_str(r#"{"sounds_enabled":true}"#).unwrap();
+        assert_eq!(legacy.sound_volume(FeedbackEvent::Error), Some(0.6));
+    }
+}
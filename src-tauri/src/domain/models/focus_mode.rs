@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// OS focus / do-not-disturb mode that keeps a recording quiet (no window, no sounds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusMode {
+    /// macOS Focus ("Не беспокоить", "Работа" и т.д.)
+    MacosFocus,
+    /// Windows Focus Assist, презентация или полноэкранное приложение
+    WindowsFocusAssist,
+    /// "Не беспокоить" в GNOME (баннеры уведомлений выключены)
+    LinuxDoNotDisturb,
+}
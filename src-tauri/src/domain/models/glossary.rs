@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Shared vocabulary of a licensed team, synced from the backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamGlossary {
+    /// Ревизия на бэкенде: растёт при каждом изменении словаря командой
+    pub revision: u64,
+    pub terms: Vec<String>,
+    pub synced_at_ms: i64,
+}
+
+impl TeamGlossary {
+    /// Термины без пустых и повторов (без учёта регистра), в исходном порядке
+    pub fn unique_terms(&self) -> Vec<String> {
+        let mut terms: Vec<String> = Vec::new();
+        for term in self.terms.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if !terms.iter().any(|t| t.to_lowercase() == term.to_lowercase()) {
+                terms.push(term.to_string());
+            }
+        }
+        terms
+    }
+}
+
+/// Team glossary sync status (`get_glossary_status`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GlossaryStatus {
+    /// Пользователь в команде с лицензией и бэкенд отдаёт общий словарь
+    pub available: bool,
+    pub revision: Option<u64>,
+    /// Сколько терминов команды добавляется к keyterms
+    pub term_count: usize,
+    pub last_synced_at_ms: Option<i64>,
+    pub last_attempt_at_ms: Option<i64>,
+    /// Ошибка последней синхронизации (словарь из кэша продолжает работать)
+    pub last_error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_terms_skip_blanks_and_case_duplicates() {
+        let glossary = TeamGlossary {
+            revision: 3,
+            terms: vec![
+                "Kubernetes".to_string(),
+                " ".to_string(),
+                "kubernetes".to_string(),
+                " VoicetextAI ".to_string(),
+            ],
+            synced_at_ms: 0,
+        };
+        assert_eq!(glossary.unique_terms(), vec!["Kubernetes".to_string(), "VoicetextAI".to_string()]);
+    }
+}
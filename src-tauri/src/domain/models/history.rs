@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+
+use super::Transcription;
+
+/// Сколько меток можно повесить на одну запись истории
+pub const MAX_HISTORY_TAGS: usize = 16;
+
+/// Максимальная длина метки в символах
+pub const MAX_HISTORY_TAG_CHARS: usize = 40;
+
+/// Сколько символов текста остаётся в памяти у записи, полный текст которой лежит на диске
+pub const HISTORY_PREVIEW_CHARS: usize = 200;
+
+/// Максимальный размер страницы get_history_page
+pub const MAX_HISTORY_PAGE_SIZE: usize = 100;
+
+/// Сколько прежних версий текста хранит запись истории (старые правки отбрасываются)
+pub const MAX_HISTORY_EDITS: usize = 10;
+
+/// Previous version of a history item's text, kept when the user edits it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEdit {
+    /// Текст до правки
+    pub text: String,
+    /// Когда текст заменили (unix seconds)
+    pub edited_at: i64,
+}
+
+/// Final phrase kept in the dictation history, with user tags and a favorite flag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryItem {
+    pub id: u64,
+    #[serde(flatten)]
+    pub transcription: Transcription,
+    /// Метки пользователя ("blog", "email"), уже нормализованные
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Избранное не вытесняется из истории по max_history_items
+    #[serde(default)]
+    pub favorite: bool,
+    /// В `transcription` только превью текста без альтернатив, полная запись и `edits` — на диске
+    #[serde(default)]
+    pub text_truncated: bool,
+    /// Последняя правка текста в редакторе (unix seconds); None — текст как распознан
+    #[serde(default)]
+    pub edited_at: Option<i64>,
+    /// Прежние версии текста, старые первыми (не больше MAX_HISTORY_EDITS).
+    /// У сжатой записи пусто: журнал правок лежит на диске вместе с полным текстом
+    #[serde(default)]
+    pub edits: Vec<HistoryEdit>,
+}
+
+/// Full text of a compacted history item with its edit log, stored next to history.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryFullText {
+    #[serde(flatten)]
+    pub transcription: Transcription,
+    /// Файлы, сохранённые до журнала правок, содержат только транскрипцию
+    #[serde(default)]
+    pub edits: Vec<HistoryEdit>,
+}
+
+impl HistoryItem {
+    pub fn new(id: u64, transcription: Transcription) -> Self {
+        Self {
+            id,
+            transcription,
+            tags: Vec::new(),
+            favorite: false,
+            text_truncated: false,
+            edited_at: None,
+            edits: Vec::new(),
+        }
+    }
+
+    /// Оставляет в записи только превью текста (HISTORY_PREVIEW_CHARS символов, без альтернатив
+    /// и журнала правок) и возвращает полную запись для сохранения на диск.
+    /// None — запись и так короткая, без правок, или уже сжата.
+    pub fn compact(&mut self) -> Option<HistoryFullText> {
+        if self.text_truncated {
+            return None;
+        }
+        let text = &self.transcription.text;
+        if text.chars().count() <= HISTORY_PREVIEW_CHARS
+            && self.transcription.alternatives.is_empty()
+            && self.edits.is_empty()
+        {
+            return None;
+        }
+        let full = HistoryFullText {
+            transcription: self.transcription.clone(),
+            edits: std::mem::take(&mut self.edits),
+        };
+        self.transcription.text = text.chars().take(HISTORY_PREVIEW_CHARS).collect();
+        self.transcription.alternatives.clear();
+        self.text_truncated = true;
+        Some(full)
+    }
+
+    /// Возвращает в запись полный текст и журнал правок, загруженные с диска
+    pub fn restore(&mut self, full: HistoryFullText) {
+        self.transcription = full.transcription;
+        self.edits = full.edits;
+        self.text_truncated = false;
+    }
+
+    /// Заменяет текст правкой пользователя; прежний текст уходит в `edits`.
+    /// Возвращает прежний текст, None — текст не изменился.
+    ///
+    /// Запись должна быть с полным текстом (см. `restore`), иначе в `edits` попадёт превью.
+    pub fn edit_text(&mut self, text: &str, now: i64) -> Option<String> {
+        if self.transcription.text == text {
+            return None;
+        }
+        let previous = std::mem::replace(&mut self.transcription.text, text.to_string());
+        self.edits.push(HistoryEdit {
+            text: previous.clone(),
+            edited_at: now,
+        });
+        if self.edits.len() > MAX_HISTORY_EDITS {
+            let excess = self.edits.len() - MAX_HISTORY_EDITS;
+            self.edits.drain(..excess);
+        }
+        self.edited_at = Some(now);
+        Some(previous)
+    }
+
+    /// Есть ли метка (без учёта регистра)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        self.tags.iter().any(|t| t.to_lowercase() == tag)
+    }
+}
+
+/// Filter for history queries and exports
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryFilter {
+    /// Только записи с этой меткой
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub favorites_only: bool,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, item: &HistoryItem) -> bool {
+        if self.favorites_only && !item.favorite {
+            return false;
+        }
+        match self.tag.as_deref().map(str::trim) {
+            Some(tag) if !tag.is_empty() => item.has_tag(tag),
+            _ => true,
+        }
+    }
+}
+
+/// One page of history for get_history_page, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryPage {
+    /// Записи страницы с полным текстом
+    pub items: Vec<HistoryItem>,
+    /// Сколько всего записей под фильтром
+    pub total: usize,
+}
+
+/// Tag with the number of history items carrying it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryTagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Приводит метки к виду для хранения: без '#' и лишних пробелов, без пустых и повторов
+/// (без учёта регистра, первая встреченная форма сохраняется), не больше MAX_HISTORY_TAGS.
+pub fn normalize_history_tags<S: AsRef<str>>(tags: &[S]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag
+            .as_ref()
+            .trim()
+            .trim_start_matches('#')
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let tag: String = tag.chars().take(MAX_HISTORY_TAG_CHARS).collect();
+        if tag.is_empty() {
+            continue;
+        }
+        let lower = tag.to_lowercase();
+        if normalized.iter().any(|t| t.to_lowercase() == lower) {
+            continue;
+        }
+        normalized.push(tag);
+        if normalized.len() == MAX_HISTORY_TAGS {
+            break;
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(tags: &[&str], favorite: bool) -> HistoryItem {
+        let mut item = HistoryItem::new(1, Transcription::final_result("привет".to_string()));
+        item.tags = normalize_history_tags(tags);
+        item.favorite = favorite;
+        item
+    }
+
+    #[test]
+    fn normalizes_tags() {
+        assert_eq!(
+            normalize_history_tags(&[" #Blog ", "blog", "", "  e-mail   draft ", "Блог", "блог"]),
+            vec!["Blog".to_string(), "e-mail draft".to_string(), "Блог".to_string()]
+        );
+
+        let many: Vec<String> = (0..40).map(|i| format!("tag{}", i)).collect();
+        assert_eq!(normalize_history_tags(&many).len(), MAX_HISTORY_TAGS);
+
+        let long = "x".repeat(100);
+        assert_eq!(normalize_history_tags(&[long])[0].chars().count(), MAX_HISTORY_TAG_CHARS);
+    }
+
+    #[test]
+    fn filter_matches_tag_case_insensitively_and_favorites() {
+        let blog = item(&["Блог"], false);
+        let starred = item(&["email"], true);
+
+        let by_tag = HistoryFilter {
+            tag: Some("#блог".to_string()),
+            favorites_only: false,
+        };
+        assert!(by_tag.matches(&blog));
+        assert!(!by_tag.matches(&starred));
+
+        let favorites = HistoryFilter {
+            tag: None,
+            favorites_only: true,
+        };
+        assert!(!favorites.matches(&blog));
+        assert!(favorites.matches(&starred));
+
+        assert!(HistoryFilter::default().matches(&blog));
+    }
+
+    #[test]
+    fn items_without_tags_deserialize_with_defaults() {
+        let json = serde_json::json!({
+            "id": 3,
+            "text": "привет",
+            "is_final": true,
+            "confidence": null,
+            "language": "ru",
+            "timestamp": 1_700_000_000,
+            "start": 0.0,
+            "duration": 1.2
+        });
+        let item: HistoryItem = serde_json::from_value(json).unwrap();
+        assert_eq!(item.id, 3);
+        assert_eq!(item.transcription.text, "привет");
+        assert!(item.tags.is_empty());
+        assert!(!item.favorite);
+        assert!(!item.text_truncated);
+        assert_eq!(item.edited_at, None);
+        assert!(item.edits.is_empty());
+    }
+
+    #[test]
+    fn edits_keep_previous_versions_up_to_the_limit() {
+        let mut item = HistoryItem::new(1, Transcription::final_result("задеплой в кубер".to_string()));
+        assert_eq!(item.edit_text("задеплой в кубер", 10), None);
+        assert_eq!(item.edited_at, None);
+
+        assert_eq!(item.edit_text("Задеплой в Kubernetes", 20).as_deref(), Some("задеплой в кубер"));
+        assert_eq!(item.transcription.text, "Задеплой в Kubernetes");
+        assert_eq!(item.edited_at, Some(20));
+        assert_eq!(
+            item.edits,
+            vec![HistoryEdit {
+                text: "задеплой в кубер".to_string(),
+                edited_at: 20,
+            }]
+        );
+
+        for i in 0..MAX_HISTORY_EDITS as i64 {
+            item.edit_text(&format!("версия {}", i), 30 + i);
+        }
+        assert_eq!(item.edits.len(), MAX_HISTORY_EDITS);
+        // Самая старая версия вытеснена
+        assert_eq!(item.edits[0].text, "Задеплой в Kubernetes");
+    }
+
+    #[test]
+    fn compact_keeps_a_preview_and_restore_brings_back_the_full_text() {
+        let long = "я".repeat(HISTORY_PREVIEW_CHARS + 50);
+        let mut transcription = Transcription::final_result(long.clone());
+        transcription.alternatives = vec![crate::domain::AlternativeText::new("другой", None)];
+        let mut item = HistoryItem::new(1, transcription);
+
+        let full = item.compact().expect("long text is compacted");
+        assert_eq!(full.transcription.text, long);
+        assert_eq!(item.transcription.text.chars().count(), HISTORY_PREVIEW_CHARS);
+        assert!(item.transcription.alternatives.is_empty());
+        assert!(item.text_truncated);
+        assert!(item.compact().is_none());
+
+        item.restore(full);
+        assert_eq!(item.transcription.text, long);
+        assert_eq!(item.transcription.alternatives.len(), 1);
+        assert!(!item.text_truncated);
+
+        let mut short = HistoryItem::new(2, Transcription::final_result("коротко".to_string()));
+        assert!(short.compact().is_none());
+        assert!(!short.text_truncated);
+    }
+
+    #[test]
+    fn compacted_edited_item_keeps_earlier_texts_only_on_disk() {
+        let first = "первая версия ".repeat(20);
+        let mut item = HistoryItem::new(1, Transcription::final_result(first.clone()));
+        item.edit_text("вторая версия", 10);
+        item.edit_text("коротко", 20);
+
+        let full = item.compact().expect("edited item is compacted even with a short text");
+        assert!(item.edits.is_empty());
+        assert!(item.text_truncated);
+        assert_eq!(item.transcription.text, "коротко");
+        let summary = serde_json::to_string(&item).unwrap();
+        assert!(!summary.contains("первая версия"));
+        assert!(!summary.contains("вторая версия"));
+
+        // Файл на диске: полный текст вместе с журналом правок
+        let json = serde_json::to_string(&full).unwrap();
+        let loaded: HistoryFullText = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.edits.len(), 2);
+        assert_eq!(loaded.edits[0].text, first);
+
+        item.restore(loaded);
+        assert_eq!(item.edits.len(), 2);
+        assert_eq!(item.edits[1].text, "вторая версия");
+        assert!(!item.text_truncated);
+    }
+
+    #[test]
+    fn full_text_saved_before_the_edit_log_loads_without_edits() {
+        let json = serde_json::to_string(&Transcription::final_result("старый файл".to_string())).unwrap();
+        let loaded: HistoryFullText = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.transcription.text, "старый файл");
+        assert!(loaded.edits.is_empty());
+    }
+}
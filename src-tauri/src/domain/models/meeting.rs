@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+/// Версия формата экспорта встречи (меняется при несовместимых изменениях JSON)
+pub const MEETING_TRANSCRIPT_FORMAT_VERSION: u32 = 1;
+
+/// One final phrase of a meeting, timed relative to the meeting start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MeetingUtterance {
+    pub text: String,
+    /// Начало фразы от старта встречи, секунды
+    pub start_secs: f64,
+    /// Конец фразы от старта встречи, секунды
+    pub end_secs: f64,
+    /// Номер говорящего (только при включённой диаризации)
+    #[serde(default)]
+    pub speaker: Option<u32>,
+    /// Канал стерео-интервью (0 — левый, 1 — правый; None — обычная запись)
+    #[serde(default)]
+    pub channel: Option<u32>,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+}
+
+/// Topic break suggested by an external advisor (LLM): a new topic starts at `utterance_index`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicBreak {
+    pub utterance_index: usize,
+    /// Короткое название темы, если советник его предложил
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// Consecutive utterances about one topic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MeetingSegment {
+    pub index: usize,
+    pub start_secs: f64,
+    pub end_secs: f64,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Говорящие сегмента по возрастанию номера (пусто без диаризации)
+    #[serde(default)]
+    pub speakers: Vec<u32>,
+    pub utterances: Vec<MeetingUtterance>,
+}
+
+/// Structured transcript of a finished meeting (the export format)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MeetingTranscript {
+    pub format_version: u32,
+    /// Начало встречи (unix ms)
+    pub started_at: i64,
+    /// Конец встречи (unix ms)
+    pub ended_at: i64,
+    pub duration_secs: f64,
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Были ли фразы размечены по говорящим
+    pub diarized: bool,
+    /// Использовались ли границы тем от LLM (иначе — только паузы)
+    pub llm_topic_breaks: bool,
+    pub segments: Vec<MeetingSegment>,
+}
+
+impl MeetingTranscript {
+    /// Все фразы встречи по порядку
+    pub fn utterances(&self) -> impl Iterator<Item = &MeetingUtterance> {
+        self.segments.iter().flat_map(|segment| segment.utterances.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utterance_without_speaker_deserializes() {
+        let utterance: MeetingUtterance =
+            serde_json::from_str(r#"{"text":"привет","start_secs":1.0,"end_secs":2.5}"#).unwrap();
+        assert_eq!(utterance.speaker, None);
+        assert_eq!(utterance.channel, None);
+        assert_eq!(utterance.confidence, None);
+    }
+}
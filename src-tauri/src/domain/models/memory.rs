@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+/// Порог выше этого не имеет смысла: до него процесс упрётся в память машины
+pub const MAX_MEMORY_ALARM_MB: u32 = 4096;
+
+/// Kind of an in-memory pool; pools of one kind share an alarm threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryPoolKind {
+    /// Аудио в памяти: очередь отправки, pre-roll, короткая запись, тесты микрофона
+    AudioBuffer,
+    /// История диктовок, статистика сессий, журнал вставок
+    History,
+    /// Очереди пакетной обработки: телеметрия, задания транскрипции, отложенные фразы
+    Queue,
+}
+
+/// Memory alarm thresholds per pool kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct MemoryAlarmConfig {
+    pub enabled: bool,
+    /// Порог одного пула, МБ (0 — без предупреждения для этого вида)
+    pub audio_buffer_mb: u32,
+    pub history_mb: u32,
+    pub queue_mb: u32,
+}
+
+impl Default for MemoryAlarmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            // ~35 минут 16kHz моно: столько аудио в памяти не держит ни один режим
+            audio_buffer_mb: 64,
+            history_mb: 32,
+            queue_mb: 16,
+        }
+    }
+}
+
+impl MemoryAlarmConfig {
+    pub fn normalized(self) -> Self {
+        Self {
+            audio_buffer_mb: self.audio_buffer_mb.min(MAX_MEMORY_ALARM_MB),
+            history_mb: self.history_mb.min(MAX_MEMORY_ALARM_MB),
+            queue_mb: self.queue_mb.min(MAX_MEMORY_ALARM_MB),
+            ..self
+        }
+    }
+
+    /// Порог пула в байтах; None — предупреждений нет
+    pub fn threshold_bytes(&self, kind: MemoryPoolKind) -> Option<u64> {
+        if !self.enabled {
+            return None;
+        }
+        let mb = match kind {
+            MemoryPoolKind::AudioBuffer => self.audio_buffer_mb,
+            MemoryPoolKind::History => self.history_mb,
+            MemoryPoolKind::Queue => self.queue_mb,
+        };
+        (mb > 0).then(|| mb as u64 * 1024 * 1024)
+    }
+}
+
+/// Approximate size of one in-memory pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+pub struct MemoryPoolUsage {
+    /// Имя пула, например "history" или "recording.send_queue"
+    pub name: String,
+    pub kind: MemoryPoolKind,
+    /// Элементов в пуле (семплов для аудио)
+    pub items: usize,
+    /// Оценка занятой памяти (данные, без накладных расходов аллокатора)
+    pub bytes: u64,
+    /// Порог предупреждения для пула; None — без порога
+    pub threshold_bytes: Option<u64>,
+}
+
+impl MemoryPoolUsage {
+    pub fn new(name: impl Into<String>, kind: MemoryPoolKind, items: usize, bytes: u64) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            items,
+            bytes,
+            threshold_bytes: None,
+        }
+    }
+
+    pub fn over_threshold(&self) -> bool {
+        self.threshold_bytes.is_some_and(|threshold| self.bytes > threshold)
+    }
+}
+
+/// Sizes of in-memory pools (audio buffers, history, batching queues)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+pub struct MemoryReport {
+    pub pools: Vec<MemoryPoolUsage>,
+    pub total_bytes: u64,
+    /// Имена пулов выше порога
+    pub over_threshold: Vec<String>,
+}
+
+impl MemoryReport {
+    pub fn new(mut pools: Vec<MemoryPoolUsage>, config: &MemoryAlarmConfig) -> Self {
+        for pool in pools.iter_mut() {
+            pool.threshold_bytes = config.threshold_bytes(pool.kind);
+        }
+        let total_bytes = pools.iter().map(|pool| pool.bytes).sum();
+        let over_threshold = pools
+            .iter()
+            .filter(|pool| pool.over_threshold())
+            .map(|pool| pool.name.clone())
+            .collect();
+        Self {
+            pools,
+            total_bytes,
+            over_threshold,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pools_are_checked_against_their_kind_threshold() {
+        let config = MemoryAlarmConfig {
+            history_mb: 1,
+            queue_mb: 0,
+            ..MemoryAlarmConfig::default()
+        };
+        let report = MemoryReport::new(
+            vec![
+                MemoryPoolUsage::new("history", MemoryPoolKind::History, 10, 2 * 1024 * 1024),
+                MemoryPoolUsage::new("telemetry_queue", MemoryPoolKind::Queue, 5, 100 * 1024 * 1024),
+                MemoryPoolUsage::new("recording.pre_roll", MemoryPoolKind::AudioBuffer, 8000, 16_000),
+            ],
+            &config,
+        );
+        assert_eq!(report.total_bytes, 102 * 1024 * 1024 + 16_000);
+        // Порог 0 — предупреждений для очередей нет
+        assert_eq!(report.over_threshold, vec!["history".to_string()]);
+        assert_eq!(report.pools[1].threshold_bytes, None);
+
+        let disabled = MemoryAlarmConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(MemoryReport::new(report.pools, &disabled).over_threshold.is_empty());
+    }
+}
@@ -3,7 +3,55 @@
 mod transcription;
 mod audio_chunk;
 mod config;
+mod text_output;
+mod correction;
+mod session_stats;
+mod paste_audit;
+mod history;
+mod telemetry;
+mod meeting;
+mod calendar;
+mod rich_text;
+mod benchmark;
+mod accuracy;
+mod transcription_job;
+mod warm_pool;
+mod stt_catalog;
+mod focus_mode;
+mod feedback;
+mod share;
+mod glossary;
+mod permission;
+mod app_context;
+mod memory;
+mod timeline;
+mod storage;
+mod text;
 
 pub use transcription::*;
 pub use audio_chunk::*;
 pub use config::*;
+pub use text_output::*;
+pub use correction::*;
+pub use session_stats::*;
+pub use paste_audit::*;
+pub use history::*;
+pub use telemetry::*;
+pub use meeting::*;
+pub use calendar::*;
+pub use rich_text::*;
+pub use benchmark::*;
+pub use accuracy::*;
+pub use transcription_job::*;
+pub use warm_pool::*;
+pub use stt_catalog::*;
+pub use focus_mode::*;
+pub use feedback::*;
+pub use share::*;
+pub use glossary::*;
+pub use permission::*;
+pub use app_context::*;
+pub use memory::*;
+pub use timeline::*;
+pub use storage::*;
+pub use text::*;
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// Сколько символов текста сохраняем в превью записи аудита
+pub const PASTE_AUDIT_PREVIEW_CHARS: usize = 40;
+
+/// Kind of text insertion performed through the accessibility automation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteAuditAction {
+    /// Переключение в последнее активное приложение + вставка
+    AutoPaste,
+    /// Печать в текущую позицию курсора
+    Typing,
+}
+
+/// One auto-paste/auto-type action, as shown in the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasteAuditEntry {
+    /// Время действия (unix ms)
+    pub timestamp: i64,
+    pub action: PasteAuditAction,
+    /// Приложение, в которое вставляли текст (macOS bundle ID), если известно
+    #[serde(default)]
+    pub target_app: Option<String>,
+    /// Длина вставленного текста в символах
+    pub text_length: usize,
+    /// Начало текста (не больше PASTE_AUDIT_PREVIEW_CHARS символов)
+    pub preview: String,
+    #[serde(default)]
+    pub session_id: Option<u64>,
+    /// Ошибка вставки (None — успешно)
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Обрезает текст до превью: пробелы схлопываются, длинный текст заканчивается на "…"
+pub fn paste_audit_preview(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= PASTE_AUDIT_PREVIEW_CHARS {
+        return collapsed;
+    }
+    let mut preview: String = collapsed.chars().take(PASTE_AUDIT_PREVIEW_CHARS).collect();
+    preview.push('…');
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_truncates_by_chars() {
+        assert_eq!(paste_audit_preview("  привет\n мир "), "привет мир");
+
+        let long = "ы".repeat(PASTE_AUDIT_PREVIEW_CHARS + 5);
+        let preview = paste_audit_preview(&long);
+        assert_eq!(preview.chars().count(), PASTE_AUDIT_PREVIEW_CHARS + 1);
+        assert!(preview.ends_with('…'));
+    }
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+/// OS permission a feature depends on (macOS Privacy & Security panes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionCapability {
+    /// Запись с микрофона
+    Microphone,
+    /// Автовставка текста
+    Accessibility,
+    /// Глобальное прослушивание клавиатуры (wake word)
+    InputMonitoring,
+    /// Захват системного звука (loopback) и заголовки окна активного приложения
+    ScreenRecording,
+}
+
+impl PermissionCapability {
+    pub const ALL: [PermissionCapability; 4] = [
+        PermissionCapability::Microphone,
+        PermissionCapability::Accessibility,
+        PermissionCapability::InputMonitoring,
+        PermissionCapability::ScreenRecording,
+    ];
+
+    /// Deep link на раздел System Settings > Privacy & Security
+    pub fn settings_url(self) -> &'static str {
+        match self {
+            PermissionCapability::Microphone => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone"
+            }
+            PermissionCapability::Accessibility => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+            }
+            PermissionCapability::InputMonitoring => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent"
+            }
+            PermissionCapability::ScreenRecording => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture"
+            }
+        }
+    }
+
+    /// Функции приложения, которые без этого разрешения не работают
+    pub fn features(self) -> &'static [&'static str] {
+        match self {
+            PermissionCapability::Microphone => &["recording"],
+            PermissionCapability::Accessibility => &["auto_paste"],
+            PermissionCapability::InputMonitoring => &["wake_word"],
+            PermissionCapability::ScreenRecording => &["loopback_capture", "active_app_detection"],
+        }
+    }
+}
+
+/// Grant state of one permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// Пользователя ещё не спрашивали (система покажет запрос при первом использовании)
+    NotDetermined,
+    /// На этой платформе разрешение не нужно
+    NotRequired,
+}
+
+impl PermissionState {
+    /// Функции с этим разрешением можно использовать
+    pub fn is_usable(self) -> bool {
+        matches!(self, PermissionState::Granted | PermissionState::NotRequired)
+    }
+}
+
+/// Status of one permission (`check_permissions`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+pub struct PermissionStatus {
+    pub capability: PermissionCapability,
+    pub state: PermissionState,
+    /// Функции, которые зависят от разрешения
+    pub features: Vec<String>,
+    /// Куда отправить пользователя, чтобы выдать разрешение (None — выдавать нечего)
+    pub settings_url: Option<String>,
+}
+
+impl PermissionStatus {
+    pub fn new(capability: PermissionCapability, state: PermissionState) -> Self {
+        Self {
+            capability,
+            state,
+            features: capability.features().iter().map(|f| f.to_string()).collect(),
+            settings_url: (state != PermissionState::NotRequired).then(|| capability.settings_url().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_link_is_given_only_when_permission_applies() {
+        let status = PermissionStatus::new(PermissionCapability::InputMonitoring, PermissionState::Denied);
+        assert_eq!(
+            status.settings_url.as_deref(),
+            Some("x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent")
+        );
+        assert_eq!(status.features, vec!["wake_word".to_string()]);
+        assert!(!status.state.is_usable());
+
+        let status = PermissionStatus::new(PermissionCapability::ScreenRecording, PermissionState::NotRequired);
+        assert_eq!(status.settings_url, None);
+        assert!(status.state.is_usable());
+    }
+}
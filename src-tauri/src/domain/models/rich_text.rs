@@ -0,0 +1,190 @@
+//! Plain text и Markdown → HTML для rich-формата буфера обмена.
+//!
+//! Поддерживается только то, что реально даёт пост-обработка диктовки: абзацы, заголовки,
+//! маркированные и нумерованные списки, **жирный**, *курсив* и `код`. Всё остальное остаётся текстом.
+
+/// Экранирует спецсимволы HTML
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Абзацы (через пустую строку) → `<p>`, переводы строк внутри абзаца → `<br>`
+pub fn plain_text_to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim).chain(std::iter::once("")) {
+        if !line.is_empty() {
+            paragraph.push(escape_html(line));
+        } else if !paragraph.is_empty() {
+            html.push_str(&format!("<p>{}</p>", paragraph.join("<br>")));
+            paragraph.clear();
+        }
+    }
+    html
+}
+
+/// Markdown (подмножество, см. описание модуля) → HTML
+pub fn markdown_to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut list: Option<(&str, Vec<&str>)> = None;
+
+    fn flush_paragraph(html: &mut String, paragraph: &mut Vec<&str>) {
+        if !paragraph.is_empty() {
+            let lines: Vec<String> = paragraph.drain(..).map(inline_markdown).collect();
+            html.push_str(&format!("<p>{}</p>", lines.join("<br>")));
+        }
+    }
+    fn flush_list(html: &mut String, list: &mut Option<(&str, Vec<&str>)>) {
+        if let Some((tag, items)) = list.take() {
+            html.push_str(&format!("<{}>", tag));
+            for item in items {
+                html.push_str(&format!("<li>{}</li>", inline_markdown(item)));
+            }
+            html.push_str(&format!("</{}>", tag));
+        }
+    }
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            flush_paragraph(&mut html, &mut paragraph);
+            flush_list(&mut html, &mut list);
+        } else if let Some((level, heading)) = heading(line) {
+            flush_paragraph(&mut html, &mut paragraph);
+            flush_list(&mut html, &mut list);
+            html.push_str(&format!("<h{0}>{1}</h{0}>", level, inline_markdown(heading)));
+        } else if let Some((tag, item)) = list_item(line) {
+            flush_paragraph(&mut html, &mut paragraph);
+            if !matches!(&list, Some((current, _)) if *current == tag) {
+                flush_list(&mut html, &mut list);
+                list = Some((tag, Vec::new()));
+            }
+            if let Some((_, items)) = list.as_mut() {
+                items.push(item);
+            }
+        } else {
+            flush_list(&mut html, &mut list);
+            paragraph.push(line);
+        }
+    }
+    flush_paragraph(&mut html, &mut paragraph);
+    flush_list(&mut html, &mut list);
+    html
+}
+
+/// "## Заголовок" → (2, "Заголовок")
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..].strip_prefix(' ').map(|rest| (level, rest.trim()))
+}
+
+/// "- пункт" / "• пункт" / "* пункт" → ul, "1. пункт" / "2) пункт" → ol
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    for marker in ["- ", "• ", "* "] {
+        if let Some(item) = line.strip_prefix(marker) {
+            return Some(("ul", item.trim()));
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    let rest = &line[digits..];
+    rest.strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))
+        .map(|item| ("ol", item.trim()))
+}
+
+/// Экранирование + `код`, **жирный**, *курсив*
+fn inline_markdown(text: &str) -> String {
+    let escaped = escape_html(text);
+    let parts: Vec<&str> = escaped.split('`').collect();
+    // Нечётное число backtick'ов: последний без пары — обычный символ
+    let paired = if parts.len() % 2 == 0 { parts.len() - 1 } else { parts.len() };
+
+    let mut html = String::with_capacity(escaped.len());
+    for (i, part) in parts.iter().enumerate() {
+        if i >= paired {
+            html.push('`');
+            html.push_str(&emphasis(part));
+        } else if i % 2 == 1 {
+            html.push_str(&format!("<code>{}</code>", part));
+        } else {
+            html.push_str(&emphasis(part));
+        }
+    }
+    html
+}
+
+fn emphasis(text: &str) -> String {
+    wrap_pairs(&wrap_pairs(text, "**", "strong"), "*", "em")
+}
+
+/// Оборачивает текст между парными маркерами в тег; пробел сразу за открывающим
+/// или перед закрывающим маркером — не разметка ("2 * 3 * 4")
+fn wrap_pairs(text: &str, marker: &str, tag: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(marker) {
+        let after = &rest[start + marker.len()..];
+        match after.find(marker) {
+            Some(end) if end > 0 && !after[..end].starts_with(' ') && !after[..end].ends_with(' ') => {
+                out.push_str(&rest[..start]);
+                out.push_str(&format!("<{0}>{1}</{0}>", tag, &after[..end]));
+                rest = &after[end + marker.len()..];
+            }
+            _ => {
+                out.push_str(&rest[..start + marker.len()]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_becomes_escaped_paragraphs() {
+        assert_eq!(
+            plain_text_to_html("Привет, <мир> & все\nвторая строка\n\nНовый абзац"),
+            "<p>Привет, &lt;мир&gt; &amp; все<br>вторая строка</p><p>Новый абзац</p>"
+        );
+        assert_eq!(plain_text_to_html("  \n\n "), "");
+    }
+
+    #[test]
+    fn markdown_blocks() {
+        let md = "# План\nВступление **важно**\n\n- первый\n- второй *пункт*\n1. раз\n2) два\nИтог `код`";
+        assert_eq!(
+            markdown_to_html(md),
+            "<h1>План</h1><p>Вступление <strong>важно</strong></p>\
+             <ul><li>первый</li><li>второй <em>пункт</em></li></ul>\
+             <ol><li>раз</li><li>два</li></ol><p>Итог <code>код</code></p>"
+        );
+    }
+
+    #[test]
+    fn markdown_leaves_non_markup_alone() {
+        assert_eq!(markdown_to_html("2 * 3 * 4 = 24"), "<p>2 * 3 * 4 = 24</p>");
+        assert_eq!(markdown_to_html("#хэштег и **не закрыто"), "<p>#хэштег и **не закрыто</p>");
+        assert_eq!(markdown_to_html("`a < b`"), "<p><code>a &lt; b</code></p>");
+        assert_eq!(markdown_to_html("цена 5$ `"), "<p>цена 5$ `</p>");
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use super::SttProviderType;
+
+/// Statistics of one finished recording session (for the productivity dashboard)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SessionStats {
+    pub session_id: u64,
+    /// Начало записи (unix ms)
+    pub started_at: i64,
+    /// Длительность записи в секундах
+    pub duration_secs: f64,
+    /// Сколько секунд аудио было с речью
+    pub speaking_secs: f64,
+    /// Сколько секунд аудио было тишиной
+    pub silence_secs: f64,
+    /// Слов в финальных фразах сессии
+    pub word_count: u64,
+    /// Слов в минуту по всей длительности записи
+    pub words_per_minute: f64,
+    /// Доля речи в аудио (0.0..1.0)
+    pub speaking_ratio: f64,
+    /// Язык сессии (определённый провайдером или из настроек)
+    #[serde(default)]
+    pub language: Option<String>,
+    pub provider: SttProviderType,
+    /// Приложение, в котором диктовали (ключ `AppContext::key`; None — не записывается или неизвестно)
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+/// Количество слов в тексте (токены без букв/цифр — например, "—" — не считаем)
+pub fn count_words(text: &str) -> u64 {
+    text.split_whitespace()
+        .filter(|w| w.chars().any(|c| c.is_alphanumeric()))
+        .count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_words_without_punctuation_tokens() {
+        assert_eq!(count_words("Привет, мир — это тест."), 4);
+        assert_eq!(count_words("   "), 0);
+        assert_eq!(count_words("25 $"), 1);
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// Срок жизни ссылки по умолчанию и максимальный, в часах
+pub const DEFAULT_SHARE_TTL_HOURS: u32 = 24;
+pub const MAX_SHARE_TTL_HOURS: u32 = 7 * 24;
+
+/// Session transcript shared through an expiring link
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionShare {
+    /// Id ссылки на бэкенде (для отзыва)
+    pub share_id: String,
+    pub session_id: u64,
+    /// Ссылка вместе с ключом расшифровки во fragment (`#key=...`) — бэкенд ключа не видит
+    pub url: String,
+    pub created_at_ms: i64,
+    pub expires_at_ms: i64,
+}
+
+impl SessionShare {
+    pub fn is_expired(&self, now_ms: i64) -> bool {
+        now_ms >= self.expires_at_ms
+    }
+}
@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+/// Where the app keeps user data on this machine (`get_storage_paths`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+pub struct StoragePaths {
+    /// Папка данных пользователя: настройки, история, статистика, исправления
+    pub config_dir: String,
+    pub history_file: String,
+    /// Полные тексты длинных записей истории
+    pub history_texts_dir: String,
+    /// Журнал записи: аудио незавершённой сессии для восстановления после сбоя
+    pub session_audio_dir: String,
+    /// Аудио, не отправленное провайдеру при подвисании сети
+    pub audio_spool_dir: String,
+    pub models_dir: String,
+    /// Папку моделей выбрал пользователь (AppConfig::models_dir) — она может быть общей
+    pub custom_models_dir: bool,
+    pub meetings_dir: String,
+    pub logs_dir: String,
+    /// Портативный режим: всё в `data/` рядом с исполняемым файлом (portable.txt или --portable)
+    pub portable: bool,
+}
@@ -0,0 +1,95 @@
+use super::SttProviderType;
+
+/// Languages a model can recognize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelLanguages {
+    /// Every language the app offers (including "multi")
+    Any,
+    /// Only the listed language codes
+    Only(&'static [&'static str]),
+}
+
+impl ModelLanguages {
+    pub fn supports(self, language: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Only(codes) => codes.contains(&language),
+        }
+    }
+}
+
+/// Model a provider can be asked to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SttModelCapability {
+    /// Model id as sent to the provider ("nova-3", "large", ...)
+    pub id: &'static str,
+    pub languages: ModelLanguages,
+}
+
+/// Result of checking a model against the catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelSupport {
+    Supported,
+    /// The provider has no such model (or doesn't let you choose one)
+    UnknownModel,
+    /// The model exists but doesn't recognize this language
+    UnsupportedLanguage,
+}
+
+/// Модель Deepgram, если ни `model`, ни override для языка не заданы
+pub const DEFAULT_DEEPGRAM_MODEL: &str = "nova-3";
+
+// Nova-2 не знает часть языков Nova-3 (hr, sr, be, ar, ...); multi у неё только en/es
+const NOVA2_LANGUAGES: &[&str] = &[
+    "en", "ru", "uk", "es", "fr", "de", "ja", "ko", "pt", "it", "nl", "pl", "cs", "sk", "hu", "ro", "bg",
+    "el", "tr", "da", "sv", "no", "fi", "et", "lv", "lt", "hi", "id", "ms", "vi", "ca",
+];
+
+const DEEPGRAM_MODELS: &[SttModelCapability] = &[
+    SttModelCapability { id: "nova-3", languages: ModelLanguages::Any },
+    SttModelCapability { id: "nova-2", languages: ModelLanguages::Only(NOVA2_LANGUAGES) },
+];
+
+// Whisper многоязычный: язык задаёт подсказку, модель любая
+const WHISPER_MODELS: &[SttModelCapability] = &[
+    SttModelCapability { id: "tiny", languages: ModelLanguages::Any },
+    SttModelCapability { id: "base", languages: ModelLanguages::Any },
+    SttModelCapability { id: "small", languages: ModelLanguages::Any },
+    SttModelCapability { id: "medium", languages: ModelLanguages::Any },
+    SttModelCapability { id: "large", languages: ModelLanguages::Any },
+];
+
+/// Модели, которые можно выбрать у провайдера (пусто — выбор модели не поддерживается)
+pub fn provider_models(provider: SttProviderType) -> &'static [SttModelCapability] {
+    match provider {
+        // Backend проксирует в Deepgram и передаёт ему модель как есть
+        SttProviderType::Deepgram | SttProviderType::Backend => DEEPGRAM_MODELS,
+        SttProviderType::WhisperLocal => WHISPER_MODELS,
+        SttProviderType::AssemblyAI | SttProviderType::GoogleCloud | SttProviderType::Azure => &[],
+    }
+}
+
+/// Можно ли распознавать `language` моделью `model` у провайдера
+pub fn model_support(provider: SttProviderType, model: &str, language: &str) -> ModelSupport {
+    match provider_models(provider).iter().find(|m| m.id == model) {
+        None => ModelSupport::UnknownModel,
+        Some(m) if m.languages.supports(language) => ModelSupport::Supported,
+        Some(_) => ModelSupport::UnsupportedLanguage,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_models_per_provider_and_language() {
+        assert_eq!(model_support(SttProviderType::Deepgram, "nova-3", "hr"), ModelSupport::Supported);
+        assert_eq!(model_support(SttProviderType::Backend, "nova-2", "ru"), ModelSupport::Supported);
+        assert_eq!(model_support(SttProviderType::Deepgram, "nova-2", "ar"), ModelSupport::UnsupportedLanguage);
+        assert_eq!(model_support(SttProviderType::Deepgram, "nova-2", "multi"), ModelSupport::UnsupportedLanguage);
+        assert_eq!(model_support(SttProviderType::WhisperLocal, "large", "de"), ModelSupport::Supported);
+        assert_eq!(model_support(SttProviderType::Deepgram, "large", "de"), ModelSupport::UnknownModel);
+        assert_eq!(model_support(SttProviderType::AssemblyAI, "nova-3", "en"), ModelSupport::UnknownModel);
+    }
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use super::{SessionStats, SttProviderType};
+
+/// Session duration rounded to a coarse bucket (exact durations are never sent)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionDurationBucket {
+    /// < 10s
+    UnderTenSeconds,
+    /// 10s – 1m
+    UnderOneMinute,
+    /// 1m – 5m
+    UnderFiveMinutes,
+    /// 5m+
+    FiveMinutesOrMore,
+}
+
+impl SessionDurationBucket {
+    pub fn from_secs(secs: f64) -> Self {
+        if secs < 10.0 {
+            Self::UnderTenSeconds
+        } else if secs < 60.0 {
+            Self::UnderOneMinute
+        } else if secs < 300.0 {
+            Self::UnderFiveMinutes
+        } else {
+            Self::FiveMinutesOrMore
+        }
+    }
+}
+
+/// What happened (no text, no audio, no device or account identifiers)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryEventKind {
+    SessionCompleted {
+        provider: SttProviderType,
+        duration: SessionDurationBucket,
+    },
+    Error {
+        provider: SttProviderType,
+        /// Category only ("connection", "timeout", ...), never the error message
+        error_type: String,
+    },
+}
+
+/// Anonymous telemetry event waiting in the local queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryEvent {
+    /// Начало часа события (unix ms) — точное время не отправляем
+    pub hour_ms: i64,
+    #[serde(flatten)]
+    pub kind: TelemetryEventKind,
+}
+
+const HOUR_MS: i64 = 3600 * 1000;
+
+impl TelemetryEvent {
+    pub fn new(kind: TelemetryEventKind, timestamp_ms: i64) -> Self {
+        Self {
+            hour_ms: timestamp_ms - timestamp_ms.rem_euclid(HOUR_MS),
+            kind,
+        }
+    }
+
+    pub fn session_completed(stats: &SessionStats, timestamp_ms: i64) -> Self {
+        Self::new(
+            TelemetryEventKind::SessionCompleted {
+                provider: stats.provider,
+                duration: SessionDurationBucket::from_secs(stats.duration_secs),
+            },
+            timestamp_ms,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telemetry_event_is_coarse_and_anonymous() {
+        assert_eq!(SessionDurationBucket::from_secs(3.0), SessionDurationBucket::UnderTenSeconds);
+        assert_eq!(SessionDurationBucket::from_secs(59.9), SessionDurationBucket::UnderOneMinute);
+        assert_eq!(SessionDurationBucket::from_secs(300.0), SessionDurationBucket::FiveMinutesOrMore);
+
+        let event = TelemetryEvent::new(
+            TelemetryEventKind::Error {
+                provider: SttProviderType::Deepgram,
+                error_type: "timeout".to_string(),
+            },
+            7_265_123,
+        );
+        assert_eq!(event.hour_ms, 7_200_000);
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["provider"], "deepgram");
+        assert_eq!(json["error_type"], "timeout");
+    }
+}
@@ -0,0 +1,23 @@
+/// Первая буква текста — заглавная; текст, который начинается не со строчной буквы
+/// (заглавная, цифра, знак), возвращается как есть
+pub fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if first.is_lowercase() => first.to_uppercase().chain(chars).collect(),
+        _ => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capitalize_first_only_touches_a_leading_lowercase_letter() {
+        assert_eq!(capitalize_first("привет мир"), "Привет мир");
+        assert_eq!(capitalize_first("Kubernetes"), "Kubernetes");
+        assert_eq!(capitalize_first("«цитата»"), "«цитата»");
+        assert_eq!(capitalize_first("ßig"), "SSig");
+        assert_eq!(capitalize_first(""), "");
+    }
+}
@@ -0,0 +1,456 @@
+use serde::{Deserialize, Serialize};
+
+use super::{markdown_to_html, plain_text_to_html};
+
+/// Destination for final transcription text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TextOutputSinkConfig {
+    /// Копировать накопленный текст сессии в clipboard
+    Clipboard {
+        /// Кроме plain text записать HTML-вариант (для вставки в rich-редакторы)
+        #[serde(default)]
+        format: ClipboardFormat,
+    },
+    /// Вернуть фокус в приложение, где был курсор до показа окна, и напечатать фразу
+    AutoPaste {
+        /// Если вставка не удалась (нет Accessibility и т.п.) — скопировать фразу в clipboard
+        #[serde(default = "default_true")]
+        fallback_to_clipboard: bool,
+    },
+    /// Напечатать фразу в текущую позицию курсора (без переключения приложений)
+    Typing,
+    /// Дописывать каждую фразу отдельной строкой в файл
+    FileAppend { path: String },
+    /// POST JSON с текстом на внешний URL
+    Webhook { url: String },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl TextOutputSinkConfig {
+    /// Clipboard только с plain text (как до появления rich-форматов)
+    pub fn plain_clipboard() -> Self {
+        TextOutputSinkConfig::Clipboard {
+            format: ClipboardFormat::PlainText,
+        }
+    }
+}
+
+/// Flavors written to the clipboard by the clipboard sink
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardFormat {
+    #[default]
+    PlainText,
+    /// Plain text + HTML: абзацы и переводы строк сохраняются при вставке в rich-редакторы
+    Html,
+    /// Plain text + HTML из Markdown, который даёт пост-обработка (списки, заголовки, выделение)
+    Markdown,
+}
+
+impl ClipboardFormat {
+    /// HTML-вариант текста (None — только plain text)
+    pub fn html(self, text: &str) -> Option<String> {
+        match self {
+            ClipboardFormat::PlainText => None,
+            ClipboardFormat::Html => Some(plain_text_to_html(text)),
+            ClipboardFormat::Markdown => Some(markdown_to_html(text)),
+        }
+    }
+}
+
+/// How inserted text is combined with what is already in the target field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteStrategy {
+    /// Вставить в позицию курсора; выделение (если есть) не трогаем — вставляем после него
+    InsertAtCursor,
+    /// Печатать поверх выделения (как при обычном вводе) — поведение до появления стратегий
+    #[default]
+    ReplaceSelection,
+    /// Дописать после курсора с пробелом/переводом строки по контексту (см. `smart_append_text`)
+    SmartAppend,
+}
+
+/// Letter case applied to final text before delivery
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextCasing {
+    /// Как прислал провайдер
+    #[default]
+    AsIs,
+    /// Заглавная буква в начале каждого предложения (остальные буквы не трогаем: имена и аббревиатуры)
+    Sentence,
+    /// всё строчными (удобно для терминала)
+    Lower,
+    /// ВСЁ ЗАГЛАВНЫМИ
+    Upper,
+    /// Заглавная Буква В Каждом Слове
+    Title,
+}
+
+impl TextCasing {
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            TextCasing::AsIs => text.to_string(),
+            TextCasing::Lower => text.to_lowercase(),
+            TextCasing::Upper => text.to_uppercase(),
+            TextCasing::Sentence => capitalize_after(text, |c| matches!(c, '.' | '!' | '?' | '…' | '\n')),
+            TextCasing::Title => capitalize_after(text, char::is_whitespace),
+        }
+    }
+}
+
+/// Поднимает первую букву текста и первую букву после каждой границы `is_boundary`.
+/// Цифра перед буквой отменяет подъём ("2024 год"), скобки и кавычки — нет ("(пример" → "(Пример")
+fn capitalize_after(text: &str, is_boundary: impl Fn(char) -> bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut capitalize = true;
+    for c in text.chars() {
+        if c.is_alphabetic() {
+            if capitalize {
+                result.extend(c.to_uppercase());
+            } else {
+                result.push(c);
+            }
+            capitalize = false;
+        } else {
+            result.push(c);
+            if is_boundary(c) {
+                capitalize = true;
+            } else if c.is_numeric() {
+                capitalize = false;
+            }
+        }
+    }
+    result
+}
+
+/// Per-app paste strategy override (matched by macOS bundle ID)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasteAppRule {
+    pub app_id: String,
+    pub strategy: PasteStrategy,
+    /// Регистр текста для этого приложения (None — как у профиля/в общих настройках)
+    #[serde(default)]
+    pub casing: Option<TextCasing>,
+}
+
+/// Стратегия для приложения: правило по app_id, иначе `default`
+pub fn paste_strategy_for(rules: &[PasteAppRule], app_id: Option<&str>, default: PasteStrategy) -> PasteStrategy {
+    app_id
+        .and_then(|app_id| rules.iter().find(|rule| rule.app_id.eq_ignore_ascii_case(app_id)))
+        .map_or(default, |rule| rule.strategy)
+}
+
+/// Регистр для приложения: правило по app_id (если в нём задан регистр), иначе `default`
+pub fn text_casing_for(rules: &[PasteAppRule], app_id: Option<&str>, default: TextCasing) -> TextCasing {
+    app_id
+        .and_then(|app_id| rules.iter().find(|rule| rule.app_id.eq_ignore_ascii_case(app_id)))
+        .and_then(|rule| rule.casing)
+        .unwrap_or(default)
+}
+
+/// Текст для SmartAppend: `before` — символ перед курсором (None — начало поля/неизвестно).
+///
+/// Собственные ведущие пробелы фразы отбрасываются и заменяются разделителем по контексту:
+/// перевод строки — если фраза начинает пункт списка, пробел — после обычного текста,
+/// ничего — в начале строки, после открывающей скобки/кавычки и перед знаком препинания.
+pub fn smart_append_text(before: Option<char>, text: &str) -> String {
+    let text = text.trim_start();
+    let Some(first) = text.chars().next() else {
+        return String::new();
+    };
+
+    let separator = match before {
+        None => "",
+        Some(c) if c.is_whitespace() => "",
+        Some('(' | '[' | '{' | '«' | '"' | '\'' | '/') => "",
+        Some(_) if matches!(first, '.' | ',' | '!' | '?' | ';' | ':' | ')' | ']' | '}' | '»' | '…') => "",
+        Some(_) if starts_list_item(text) => "\n",
+        Some(_) => " ",
+    };
+    format!("{}{}", separator, text)
+}
+
+/// "- пункт", "• пункт", "* пункт", "1. пункт", "2) пункт"
+fn starts_list_item(text: &str) -> bool {
+    if ["- ", "• ", "* "].iter().any(|marker| text.starts_with(marker)) {
+        return true;
+    }
+    let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && (text[digits..].starts_with(". ") || text[digits..].starts_with(") "))
+}
+
+/// Named set of sinks (e.g. "Заметки" → file + webhook, "Чат" → auto-paste)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextOutputProfile {
+    pub name: String,
+    #[serde(default)]
+    pub sinks: Vec<TextOutputSinkConfig>,
+    /// Регистр текста для этого профиля (None — из общих настроек)
+    #[serde(default)]
+    pub casing: Option<TextCasing>,
+}
+
+/// One piece of final text to deliver
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TextDelivery {
+    /// Новый фрагмент (фраза), который нужно вставить/дописать
+    pub text: String,
+    /// Весь накопленный текст сессии (для clipboard)
+    pub full_text: String,
+    /// Сессия записи, к которой относится текст
+    pub session_id: Option<u64>,
+}
+
+impl TextDelivery {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            full_text: text.clone(),
+            text,
+            session_id: None,
+        }
+    }
+
+    pub fn with_full_text(mut self, full_text: impl Into<String>) -> Self {
+        self.full_text = full_text.into();
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: u64) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+}
+
+/// Whether simulated input (paste / typing) reaches the target window (`get_paste_compatibility`)
+///
+/// Windows не пропускает SendInput из обычного процесса в окно администратора (UIPI), причём молча.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PasteCompatibility {
+    /// Процесс целевого окна (имя exe); None — определить не удалось
+    pub target: Option<String>,
+    /// Целевое окно принадлежит процессу с правами администратора
+    pub target_elevated: bool,
+    /// Приложение само запущено с правами администратора
+    pub self_elevated: bool,
+    /// Сборка с uiAccess="true" в манифесте: вводит в любые окна
+    pub ui_access: bool,
+    /// Вставка и печать дойдут до окна; false — текст доставляется только через clipboard
+    pub can_inject_input: bool,
+    /// Как включить вставку в такие окна (None — всё работает)
+    pub guidance: Option<String>,
+}
+
+impl PasteCompatibility {
+    pub fn evaluate(target: Option<String>, target_elevated: bool, self_elevated: bool, ui_access: bool) -> Self {
+        Self {
+            target,
+            target_elevated,
+            self_elevated,
+            ui_access,
+            can_inject_input: !target_elevated || self_elevated || ui_access,
+            guidance: None,
+        }
+    }
+
+    /// Sinks для этого окна: если ввод не дойдёт, вставка и печать заменяются одним clipboard
+    pub fn restrict_sinks(&self, sinks: &[TextOutputSinkConfig]) -> Vec<TextOutputSinkConfig> {
+        if self.can_inject_input {
+            return sinks.to_vec();
+        }
+
+        let has_clipboard = sinks
+            .iter()
+            .any(|sink| matches!(sink, TextOutputSinkConfig::Clipboard { .. }));
+        let mut restricted: Vec<TextOutputSinkConfig> = Vec::with_capacity(sinks.len());
+        for sink in sinks {
+            match sink {
+                TextOutputSinkConfig::AutoPaste { .. } | TextOutputSinkConfig::Typing => {
+                    let clipboard = TextOutputSinkConfig::plain_clipboard();
+                    if !has_clipboard && !restricted.contains(&clipboard) {
+                        restricted.push(clipboard);
+                    }
+                }
+                other => restricted.push(other.clone()),
+            }
+        }
+        restricted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sink_config_serialization() {
+        let sinks = vec![
+            TextOutputSinkConfig::plain_clipboard(),
+            TextOutputSinkConfig::FileAppend {
+                path: "/tmp/notes.txt".to_string(),
+            },
+        ];
+        let json = serde_json::to_value(&sinks).unwrap();
+        assert_eq!(json[0]["type"], "clipboard");
+        assert_eq!(json[1]["type"], "file_append");
+        assert_eq!(json[1]["path"], "/tmp/notes.txt");
+
+        // fallback_to_clipboard по умолчанию включён
+        let sink: TextOutputSinkConfig = serde_json::from_str(r#"{"type":"auto_paste"}"#).unwrap();
+        assert_eq!(
+            sink,
+            TextOutputSinkConfig::AutoPaste {
+                fallback_to_clipboard: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_clipboard_format() {
+        // Старые конфиги без format — только plain text
+        let sink: TextOutputSinkConfig = serde_json::from_str(r#"{"type":"clipboard"}"#).unwrap();
+        assert_eq!(sink, TextOutputSinkConfig::plain_clipboard());
+
+        let sink: TextOutputSinkConfig =
+            serde_json::from_str(r#"{"type":"clipboard","format":"markdown"}"#).unwrap();
+        assert_eq!(
+            sink,
+            TextOutputSinkConfig::Clipboard {
+                format: ClipboardFormat::Markdown
+            }
+        );
+
+        assert_eq!(ClipboardFormat::PlainText.html("**a**"), None);
+        assert_eq!(ClipboardFormat::Html.html("**a**").as_deref(), Some("<p>**a**</p>"));
+        assert_eq!(
+            ClipboardFormat::Markdown.html("**a**").as_deref(),
+            Some("<p><strong>a</strong></p>")
+        );
+    }
+
+    #[test]
+    fn test_smart_append_text() {
+        assert_eq!(smart_append_text(None, " привет"), "привет");
+        assert_eq!(smart_append_text(Some('\n'), "привет"), "привет");
+        assert_eq!(smart_append_text(Some('.'), "  Привет"), " Привет");
+        assert_eq!(smart_append_text(Some('т'), ", мир"), ", мир");
+        assert_eq!(smart_append_text(Some('('), "пример"), "пример");
+        assert_eq!(smart_append_text(Some(':'), "- молоко"), "\n- молоко");
+        assert_eq!(smart_append_text(Some('о'), "2. хлеб"), "\n2. хлеб");
+        assert_eq!(smart_append_text(Some('о'), "2024 год"), " 2024 год");
+        assert_eq!(smart_append_text(Some('о'), "   "), "");
+    }
+
+    #[test]
+    fn test_paste_strategy_for_app_rules() {
+        let rules = vec![PasteAppRule {
+            app_id: "com.apple.Notes".to_string(),
+            strategy: PasteStrategy::SmartAppend,
+            casing: None,
+        }];
+        assert_eq!(
+            paste_strategy_for(&rules, Some("com.apple.notes"), PasteStrategy::InsertAtCursor),
+            PasteStrategy::SmartAppend
+        );
+        assert_eq!(
+            paste_strategy_for(&rules, Some("com.tinyspeck.slackmacgap"), PasteStrategy::ReplaceSelection),
+            PasteStrategy::ReplaceSelection
+        );
+        assert_eq!(
+            paste_strategy_for(&rules, None, PasteStrategy::InsertAtCursor),
+            PasteStrategy::InsertAtCursor
+        );
+    }
+
+    #[test]
+    fn test_text_casing() {
+        let text = "привет, мир. как дела? 2024 год (пример) iPhone";
+        assert_eq!(TextCasing::AsIs.apply(text), text);
+        assert_eq!(TextCasing::Lower.apply("Hello NASA"), "hello nasa");
+        assert_eq!(TextCasing::Upper.apply("Привет"), "ПРИВЕТ");
+        assert_eq!(
+            TextCasing::Sentence.apply(text),
+            "Привет, мир. Как дела? 2024 год (пример) iPhone"
+        );
+        assert_eq!(TextCasing::Sentence.apply("\"ok\" said NASA"), "\"Ok\" said NASA");
+        assert_eq!(
+            TextCasing::Title.apply("the quick (brown) 2nd fox"),
+            "The Quick (Brown) 2nd Fox"
+        );
+
+        let casing: TextCasing = serde_json::from_str(r#""sentence""#).unwrap();
+        assert_eq!(casing, TextCasing::Sentence);
+    }
+
+    #[test]
+    fn test_text_casing_for_app_rules() {
+        let rules = vec![
+            PasteAppRule {
+                app_id: "com.apple.Terminal".to_string(),
+                strategy: PasteStrategy::InsertAtCursor,
+                casing: Some(TextCasing::Lower),
+            },
+            PasteAppRule {
+                app_id: "com.apple.Notes".to_string(),
+                strategy: PasteStrategy::SmartAppend,
+                casing: None,
+            },
+        ];
+        assert_eq!(text_casing_for(&rules, Some("com.apple.terminal"), TextCasing::Sentence), TextCasing::Lower);
+        // Правило без регистра не перекрывает профиль
+        assert_eq!(text_casing_for(&rules, Some("com.apple.Notes"), TextCasing::Sentence), TextCasing::Sentence);
+        assert_eq!(text_casing_for(&rules, None, TextCasing::AsIs), TextCasing::AsIs);
+
+        // Старые правила без поля casing
+        let rule: PasteAppRule =
+            serde_json::from_str(r#"{"app_id":"com.apple.Notes","strategy":"smart_append"}"#).unwrap();
+        assert_eq!(rule.casing, None);
+    }
+
+    #[test]
+    fn test_delivery_defaults_full_text_to_text() {
+        let delivery = TextDelivery::new("привет");
+        assert_eq!(delivery.full_text, "привет");
+
+        let delivery = TextDelivery::new(" мир").with_full_text("привет мир").with_session_id(3);
+        assert_eq!(delivery.text, " мир");
+        assert_eq!(delivery.full_text, "привет мир");
+        assert_eq!(delivery.session_id, Some(3));
+    }
+
+    #[test]
+    fn test_elevated_target_gets_clipboard_instead_of_input() {
+        let sinks = vec![
+            TextOutputSinkConfig::AutoPaste {
+                fallback_to_clipboard: true,
+            },
+            TextOutputSinkConfig::Typing,
+            TextOutputSinkConfig::Webhook {
+                url: "https://example.com".to_string(),
+            },
+        ];
+
+        let elevated = PasteCompatibility::evaluate(Some("regedit.exe".to_string()), true, false, false);
+        assert!(!elevated.can_inject_input);
+        assert_eq!(
+            elevated.restrict_sinks(&sinks),
+            vec![TextOutputSinkConfig::plain_clipboard(), sinks[2].clone()]
+        );
+
+        // Clipboard уже есть среди sinks — второй не добавляем
+        let with_clipboard = vec![sinks[0].clone(), TextOutputSinkConfig::Clipboard { format: ClipboardFormat::Html }];
+        assert_eq!(elevated.restrict_sinks(&with_clipboard), vec![with_clipboard[1].clone()]);
+
+        // Администратор или uiAccess — ввод доходит
+        assert!(PasteCompatibility::evaluate(None, true, true, false).can_inject_input);
+        assert!(PasteCompatibility::evaluate(None, true, false, true).can_inject_input);
+        let normal = PasteCompatibility::evaluate(None, false, false, false);
+        assert_eq!(normal.restrict_sinks(&sinks), sinks);
+    }
+}
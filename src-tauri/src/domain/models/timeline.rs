@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use super::SttProviderType;
+
+/// Версия формата экспорта таймлайна (меняется при несовместимых изменениях JSON)
+pub const SESSION_TIMELINE_FORMAT_VERSION: u32 = 1;
+
+/// Раскладка аудио сессии, в которую указывают смещения сегментов: сырой PCM как в журнале записи
+pub const TIMELINE_AUDIO_FORMAT: &str = "pcm_s16le";
+
+/// Layout of the session recording that segment offsets point into
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TimelineAudio {
+    /// Всегда `pcm_s16le`: 16-bit little-endian, без заголовка
+    pub format: String,
+    pub sample_rate: u32,
+    /// Стерео-интервью тоже смешивается в моно, поэтому 1
+    pub channels: u16,
+}
+
+impl TimelineAudio {
+    pub fn mono(sample_rate: u32) -> Self {
+        Self {
+            format: TIMELINE_AUDIO_FORMAT.to_string(),
+            sample_rate,
+            channels: 1,
+        }
+    }
+
+    /// Диапазон секунд → смещения в семплах и байтах
+    pub fn span(&self, start_secs: f64, end_secs: f64) -> TimelineAudioSpan {
+        let sample_at = |secs: f64| (secs.max(0.0) * self.sample_rate as f64).round() as u64;
+        let (start_sample, end_sample) = (sample_at(start_secs), sample_at(end_secs));
+        let bytes_per_sample = 2 * self.channels.max(1) as u64;
+        TimelineAudioSpan {
+            start_sample,
+            end_sample,
+            start_byte: start_sample * bytes_per_sample,
+            end_byte: end_sample * bytes_per_sample,
+        }
+    }
+}
+
+/// Where a segment lies in the session recording (end is exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TimelineAudioSpan {
+    pub start_sample: u64,
+    pub end_sample: u64,
+    pub start_byte: u64,
+    pub end_byte: u64,
+}
+
+/// One final phrase of a session, aligned to the session audio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TimelineSegment {
+    pub index: usize,
+    pub text: String,
+    /// Начало фразы от старта записи, секунды
+    pub start_secs: f64,
+    /// Конец фразы от старта записи, секунды
+    pub end_secs: f64,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    /// Номер говорящего (только при включённой диаризации)
+    #[serde(default)]
+    pub speaker: Option<u32>,
+    /// Канал стерео-интервью (0 — левый, 1 — правый; None — обычная запись)
+    #[serde(default)]
+    pub channel: Option<u32>,
+    #[serde(default)]
+    pub language: Option<String>,
+    pub audio: TimelineAudioSpan,
+}
+
+/// Timed segments of one recording session (the `export_session_timeline` format)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SessionTimeline {
+    pub format_version: u32,
+    pub session_id: u64,
+    /// Начало записи (unix ms)
+    pub started_at: i64,
+    pub duration_secs: f64,
+    pub provider: SttProviderType,
+    /// Запись ещё идёт — сегменты будут добавляться
+    pub in_progress: bool,
+    pub audio: TimelineAudio,
+    pub segments: Vec<TimelineSegment>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn span_maps_seconds_to_sample_and_byte_offsets() {
+        let audio = TimelineAudio::mono(16000);
+        assert_eq!(
+            audio.span(1.5, 2.25),
+            TimelineAudioSpan {
+                start_sample: 24_000,
+                end_sample: 36_000,
+                start_byte: 48_000,
+                end_byte: 72_000,
+            }
+        );
+        assert_eq!(audio.span(-0.1, 0.0).start_byte, 0);
+    }
+}
@@ -1,5 +1,23 @@
 use serde::{Deserialize, Serialize};
 
+use super::{AppContext, SessionCalendarTag};
+
+/// Alternative hypothesis (n-best) for the same audio segment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AlternativeText {
+    pub text: String,
+    pub confidence: Option<f32>,
+}
+
+impl AlternativeText {
+    pub fn new(text: impl Into<String>, confidence: Option<f32>) -> Self {
+        Self {
+            text: text.into(),
+            confidence,
+        }
+    }
+}
+
 /// Represents the result of a speech-to-text transcription
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transcription {
@@ -23,6 +41,26 @@ pub struct Transcription {
 
     /// Duration of the audio segment in seconds (from Deepgram)
     pub duration: f64,
+
+    /// Other hypotheses for the same segment, best first (без основной гипотезы `text`)
+    #[serde(default)]
+    pub alternatives: Vec<AlternativeText>,
+
+    /// Speaker index from diarization (None — диаризация выключена или провайдер не вернул)
+    #[serde(default)]
+    pub speaker: Option<u32>,
+
+    /// Audio channel in split-channel mode (0 — левый, 1 — правый; None — обычная запись)
+    #[serde(default)]
+    pub channel: Option<u32>,
+
+    /// Встреча из календаря, шедшая при старте записи (название и метки сессии)
+    #[serde(default)]
+    pub calendar: Option<SessionCalendarTag>,
+
+    /// Активное приложение и окно при старте записи (если включено `capture_app_context`)
+    #[serde(default)]
+    pub app: Option<AppContext>,
 }
 
 impl Transcription {
@@ -38,6 +76,11 @@ impl Transcription {
                 .as_secs() as i64,
             start: 0.0,
             duration: 0.0,
+            alternatives: Vec::new(),
+            speaker: None,
+            channel: None,
+            calendar: None,
+            app: None,
         }
     }
 
@@ -57,6 +100,38 @@ impl Transcription {
         self
     }
 
+    pub fn with_speaker(mut self, speaker: Option<u32>) -> Self {
+        self.speaker = speaker;
+        self
+    }
+
+    pub fn with_channel(mut self, channel: Option<u32>) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Слот для состояния по каналам: 0 — обычная запись и левый канал, 1 — правый
+    pub fn channel_slot(&self) -> usize {
+        self.channel.map_or(0, |channel| channel.min(1) as usize)
+    }
+
+    pub fn with_alternatives(mut self, alternatives: Vec<AlternativeText>) -> Self {
+        self.alternatives = alternatives;
+        self
+    }
+
+    /// Подставляет альтернативу `index` вместо основной гипотезы.
+    ///
+    /// Бывшая основная гипотеза встаёт на место альтернативы, поэтому повторный вызов
+    /// с тем же индексом возвращает исходный текст. Возвращает текст, который был заменён.
+    pub fn swap_alternative(&mut self, index: usize) -> Option<String> {
+        let alternative = self.alternatives.get_mut(index)?;
+        let previous = std::mem::replace(&mut self.text, std::mem::take(&mut alternative.text));
+        alternative.text = previous.clone();
+        std::mem::swap(&mut self.confidence, &mut alternative.confidence);
+        Some(previous)
+    }
+
     /// Creates a partial transcription result
     pub fn partial(text: String) -> Self {
         Self::new(text, false)
@@ -69,7 +144,7 @@ impl Transcription {
 }
 
 /// Recording status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum RecordingStatus {
     Idle,
     Starting, // Запись инициализируется (WebSocket подключается, audio capture запускается)
@@ -144,6 +219,34 @@ mod tests {
         assert_eq!(t1.is_final, t2.is_final);
     }
 
+    #[test]
+    fn test_swap_alternative_is_reversible() {
+        let mut t = Transcription::final_result("ice cream".to_string())
+            .with_confidence(0.7)
+            .with_alternatives(vec![
+                AlternativeText::new("I scream", Some(0.6)),
+                AlternativeText::new("eye scream", Some(0.3)),
+            ]);
+
+        assert_eq!(t.swap_alternative(0), Some("ice cream".to_string()));
+        assert_eq!(t.text, "I scream");
+        assert_eq!(t.confidence, Some(0.6));
+        assert_eq!(t.alternatives[0], AlternativeText::new("ice cream", Some(0.7)));
+
+        assert_eq!(t.swap_alternative(0), Some("I scream".to_string()));
+        assert_eq!(t.text, "ice cream");
+
+        assert_eq!(t.swap_alternative(5), None);
+        assert_eq!(t.text, "ice cream");
+    }
+
+    #[test]
+    fn test_transcription_without_alternatives_deserializes() {
+        let json = r#"{"text":"hi","is_final":true,"confidence":null,"language":null,"timestamp":1,"start":0.0,"duration":0.0}"#;
+        let t: Transcription = serde_json::from_str(json).unwrap();
+        assert!(t.alternatives.is_empty());
+    }
+
     #[test]
     fn test_recording_status_default() {
         assert_eq!(RecordingStatus::default(), RecordingStatus::Idle);
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+/// State of a file transcription job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionJobStatus {
+    /// Ждёт своей очереди (или прервана выходом из приложения и будет продолжена)
+    Queued,
+    Running,
+    /// Остановлена пользователем после очередной главы
+    Paused,
+    Completed,
+    /// Глава не расшифровалась; resume повторит её
+    Failed,
+    Cancelled,
+}
+
+/// One transcribed piece of the file (chunks are cut at quiet points)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TranscriptionChapter {
+    pub index: usize,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// Long-running transcription of one audio file.
+///
+/// Progress is persisted after every chapter, so an interrupted job continues
+/// from `processed_samples` instead of starting over.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptionJob {
+    pub id: String,
+    pub path: String,
+    /// Unix timestamp (секунды)
+    pub created_at: i64,
+    pub status: TranscriptionJobStatus,
+    /// Длительность файла, мс (0 — файл ещё не декодирован)
+    #[serde(default)]
+    pub total_ms: u64,
+    /// Сколько сэмплов (16kHz mono) уже расшифровано — с этого места продолжается работа
+    #[serde(default)]
+    pub processed_samples: usize,
+    #[serde(default)]
+    pub chapters: Vec<TranscriptionChapter>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl TranscriptionJob {
+    pub fn new(id: impl Into<String>, path: impl Into<String>, created_at: i64) -> Self {
+        Self {
+            id: id.into(),
+            path: path.into(),
+            created_at,
+            status: TranscriptionJobStatus::Queued,
+            total_ms: 0,
+            processed_samples: 0,
+            chapters: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Полный текст по уже расшифрованным главам
+    pub fn text(&self) -> String {
+        self.chapters
+            .iter()
+            .map(|c| c.text.trim())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Задача ещё в работе (ждёт очереди или расшифровывается)
+    pub fn is_active(&self) -> bool {
+        matches!(self.status, TranscriptionJobStatus::Queued | TranscriptionJobStatus::Running)
+    }
+
+    pub fn is_resumable(&self) -> bool {
+        matches!(self.status, TranscriptionJobStatus::Paused | TranscriptionJobStatus::Failed)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, TranscriptionJobStatus::Completed | TranscriptionJobStatus::Cancelled)
+    }
+
+    /// Пауза активной задачи (идущая глава дорасшифровывается). false — задача не активна
+    pub fn pause(&mut self) -> bool {
+        if !self.is_active() {
+            return false;
+        }
+        self.status = TranscriptionJobStatus::Paused;
+        true
+    }
+
+    /// Обратно в очередь с первой нерасшифрованной главы. false — продолжать нечего
+    pub fn resume(&mut self) -> bool {
+        if !self.is_resumable() {
+            return false;
+        }
+        self.status = TranscriptionJobStatus::Queued;
+        self.error = None;
+        true
+    }
+
+    /// Отмена; готовые главы остаются. false — задача уже завершена
+    pub fn cancel(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.status = TranscriptionJobStatus::Cancelled;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_text_and_state() {
+        let mut job = TranscriptionJob::new("1", "/tmp/a.mp3", 0);
+        assert!(job.is_active());
+        assert_eq!(job.text(), "");
+
+        for (index, text) in ["Глава один.", " ", "Глава два."].into_iter().enumerate() {
+            job.chapters.push(TranscriptionChapter {
+                index,
+                start_ms: index as u64 * 1000,
+                end_ms: (index as u64 + 1) * 1000,
+                text: text.to_string(),
+            });
+        }
+        assert_eq!(job.text(), "Глава один. Глава два.");
+
+        job.status = TranscriptionJobStatus::Failed;
+        job.error = Some("timeout".to_string());
+        assert!(job.is_resumable() && !job.is_active() && !job.is_finished());
+        assert!(!job.pause());
+        assert!(job.resume());
+        assert_eq!((job.status, job.error.as_deref()), (TranscriptionJobStatus::Queued, None));
+        assert!(job.pause() && job.cancel());
+        assert!(!job.cancel() && !job.resume());
+
+        // Поля прогресса необязательны
+        let json = r#"{"id":"2","path":"b.wav","created_at":1,"status":"running"}"#;
+        let job: TranscriptionJob = serde_json::from_str(json).unwrap();
+        assert_eq!(job.status, TranscriptionJobStatus::Running);
+        assert_eq!(job.processed_samples, 0);
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Самое раннее/позднее упреждение прогрева (сек)
+pub const MIN_WARM_POOL_LEAD_SECS: u64 = 15;
+pub const MAX_WARM_POOL_LEAD_SECS: u64 = 600;
+
+/// Warm pool: reconnect the paused provider connection shortly before the user usually dictates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WarmPoolConfig {
+    /// По умолчанию выключено: прогретое соединение держит слот у провайдера
+    pub enabled: bool,
+    /// За сколько секунд до ожидаемой диктовки открывать соединение
+    pub lead_secs: u64,
+}
+
+impl Default for WarmPoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lead_secs: 90,
+        }
+    }
+}
+
+impl WarmPoolConfig {
+    pub fn normalized(mut self) -> Self {
+        self.lead_secs = self.lead_secs.clamp(MIN_WARM_POOL_LEAD_SECS, MAX_WARM_POOL_LEAD_SECS);
+        self
+    }
+}
@@ -46,6 +46,21 @@ pub trait AudioCapture: Send + Sync {
     /// Stop capturing audio
     async fn stop_capture(&mut self) -> AudioResult<()>;
 
+    /// Release idle OS audio resources (streams, device handles) between recordings.
+    ///
+    /// Called by the idle lifecycle policy to save power. Implementations must
+    /// lazily re-acquire everything on the next `start_capture`.
+    async fn release(&mut self) -> AudioResult<()> {
+        Ok(())
+    }
+
+    /// Prepare the device ahead of `start_capture` (warm start).
+    ///
+    /// Re-acquires resources dropped by `release` so that starting capture is cheap.
+    async fn prepare(&mut self) -> AudioResult<()> {
+        Ok(())
+    }
+
     /// Check if currently capturing
     fn is_capturing(&self) -> bool;
 
@@ -0,0 +1,24 @@
+/// Audio read back from the spool: one continuous gap of unsent audio
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpooledAudio {
+    /// i16 PCM, mono
+    pub samples: Vec<i16>,
+    pub sample_rate: u32,
+}
+
+/// Trait defining the contract for write-ahead persistence of audio that did not reach the STT provider
+///
+/// While sends fail mid-session (network stall, provider error) the audio is appended to a temporary
+/// on-disk spool instead of being dropped. Once the connection recovers, or a fallback provider is
+/// available, the gap is taken back, transcribed separately and the spool is deleted.
+/// Like `SessionJournal`, spooling is best-effort and never breaks the recording itself.
+pub trait AudioSpool: Send + Sync {
+    /// Append unsent audio (i16 PCM, mono) to the current gap
+    fn append(&self, samples: &[i16], sample_rate: u32);
+
+    /// Samples waiting in the spool
+    fn pending_samples(&self) -> usize;
+
+    /// Take the whole gap and delete the spool; None if nothing is spooled
+    fn take(&self) -> Option<SpooledAudio>;
+}
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+
+use crate::domain::models::CalendarEvent;
+
+/// Result type for calendar operations
+pub type CalendarResult<T> = Result<T, CalendarError>;
+
+/// Errors that can occur while reading calendar events
+#[derive(Debug, thiserror::Error)]
+pub enum CalendarError {
+    #[error("Calendar access denied: {0}")]
+    AccessDenied(String),
+
+    #[error("Calendar unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Invalid calendar data: {0}")]
+    Format(String),
+}
+
+/// Trait defining a read-only source of calendar events (EventKit, ICS subscription, ...)
+#[async_trait]
+pub trait CalendarProvider: Send + Sync {
+    /// Events (occurrences of recurring events included) that are in progress at `at_ms`
+    async fn events_at(&self, at_ms: i64) -> CalendarResult<Vec<CalendarEvent>>;
+
+    /// Ask the user for calendar access if the source needs it; returns whether access is granted
+    async fn request_access(&self) -> CalendarResult<bool> {
+        Ok(true)
+    }
+
+    /// Get provider name (for logs)
+    fn name(&self) -> &str;
+}
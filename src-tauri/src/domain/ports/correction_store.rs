@@ -0,0 +1,26 @@
+use crate::domain::models::CorrectionEntry;
+
+/// Result type for correction store operations
+pub type CorrectionStoreResult<T> = Result<T, CorrectionStoreError>;
+
+/// Errors that can occur while reading or writing learned corrections
+#[derive(Debug, thiserror::Error)]
+pub enum CorrectionStoreError {
+    #[error("Storage error: {0}")]
+    Io(String),
+
+    #[error("Invalid corrections data: {0}")]
+    Format(String),
+}
+
+/// Trait defining persistence for the learned corrections dictionary
+///
+/// The dictionary is small (tens to hundreds of pairs) and is always read and
+/// written as a whole, so the contract is synchronous.
+pub trait CorrectionStore: Send + Sync {
+    /// Load all saved corrections (empty list if nothing was saved yet)
+    fn load(&self) -> CorrectionStoreResult<Vec<CorrectionEntry>>;
+
+    /// Replace the saved corrections with `entries`
+    fn save(&self, entries: &[CorrectionEntry]) -> CorrectionStoreResult<()>;
+}
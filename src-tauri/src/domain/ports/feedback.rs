@@ -0,0 +1,13 @@
+use crate::domain::models::{FeedbackConfig, FeedbackEvent};
+
+/// Trait defining a device that gives the user feedback on recording events
+///
+/// Сейчас это звук; вибромоторы, педали с отдачей и т.п. подключаются своей реализацией.
+/// Вызывается из потока команд, поэтому реализация не должна блокировать (проигрывание — в своём потоке).
+pub trait FeedbackSink: Send + Sync {
+    /// Name for logs
+    fn name(&self) -> &str;
+
+    /// Отреагировать на событие; что именно включено, реализация решает по `config`
+    fn notify(&self, event: FeedbackEvent, config: &FeedbackConfig);
+}
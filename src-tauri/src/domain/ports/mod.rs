@@ -3,6 +3,22 @@
 
 mod stt_provider;
 mod audio_capture;
+mod session_journal;
+mod text_output;
+mod correction_store;
+mod punctuator;
+mod topic_advisor;
+mod calendar_provider;
+mod feedback;
+mod audio_spool;
 
 pub use stt_provider::*;
 pub use audio_capture::*;
+pub use session_journal::*;
+pub use text_output::*;
+pub use correction_store::*;
+pub use punctuator::*;
+pub use topic_advisor::*;
+pub use calendar_provider::*;
+pub use feedback::*;
+pub use audio_spool::*;
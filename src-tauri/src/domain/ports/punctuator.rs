@@ -0,0 +1,24 @@
+/// Result type for local punctuation operations
+pub type PunctuationResult<T> = Result<T, PunctuationError>;
+
+/// Errors that can occur while restoring punctuation locally
+#[derive(Debug, thiserror::Error)]
+pub enum PunctuationError {
+    #[error("Punctuation model unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Punctuation inference failed: {0}")]
+    Inference(String),
+}
+
+/// Trait defining a local punctuation/truecasing stage for final text
+///
+/// Used for providers (e.g. small Whisper models) that return unpunctuated,
+/// lowercase text. The call is synchronous: it runs on short final phrases.
+pub trait Punctuator: Send + Sync {
+    /// Restore punctuation and capitalization in `text`
+    fn punctuate(&self, text: &str) -> PunctuationResult<String>;
+
+    /// Get punctuator name (for logs)
+    fn name(&self) -> &str;
+}
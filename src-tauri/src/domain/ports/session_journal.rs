@@ -0,0 +1,19 @@
+/// Trait defining the contract for crash-safe persistence of an in-flight recording session
+///
+/// The journal is written incrementally while recording (raw audio + accumulated finals),
+/// so that if the app crashes or is killed mid-recording the session can be recovered on
+/// the next launch. Implementations must be best-effort: journaling failures must never
+/// break the recording itself, so methods do not return errors.
+pub trait SessionJournal: Send + Sync {
+    /// Start a new session journal (overwrites the previous in-flight journal)
+    fn begin(&self, provider: &str);
+
+    /// Append captured audio (i16 PCM, mono) to the raw audio buffer
+    fn append_audio(&self, samples: &[i16], sample_rate: u32);
+
+    /// Append a final transcription segment
+    fn append_final(&self, text: &str);
+
+    /// Mark the session as cleanly finished (journal is no longer needed)
+    fn complete(&self);
+}
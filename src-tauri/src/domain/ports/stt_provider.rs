@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::domain::models::{AudioChunk, SttConfig, Transcription};
 
@@ -7,6 +8,10 @@ use crate::domain::models::{AudioChunk, SttConfig, Transcription};
 pub type SttResult<T> = Result<T, SttError>;
 
 /// Errors that can occur during speech-to-text operations
+///
+/// Таксономия: Authentication (ключ/токен), RateLimited, QuotaExceeded, Connection (сеть —
+/// повторяемость по категории), ProviderBug (провайдер ответил не по протоколу).
+/// UI показывает не сырые строки, а текст по `message_key()`.
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum SttError {
     #[error("Configuration error: {0}")]
@@ -18,6 +23,26 @@ pub enum SttError {
     #[error("Authentication error: {0}")]
     Authentication(String),
 
+    /// Too many requests/sessions; retry after a pause
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        /// Retry-After от сервера, если пришёл
+        retry_after_secs: Option<u64>,
+        details: SttConnectionDetails,
+    },
+
+    /// Usage limit of the plan/account is exhausted; retrying will not help
+    #[error("Usage limit exceeded: {message}")]
+    QuotaExceeded {
+        message: String,
+        details: SttConnectionDetails,
+    },
+
+    /// Provider answered something we cannot handle (schema drift, unknown error) — not the user's fault
+    #[error("Provider error: {0}")]
+    ProviderBug(String),
+
     #[error("Processing error: {0}")]
     Processing(String),
 
@@ -28,6 +53,88 @@ pub enum SttError {
     Internal(String),
 }
 
+impl SttError {
+    /// Сетевая ошибка с категорией лимита → структурный вариант (RateLimited/QuotaExceeded)
+    pub fn from_connection(conn: SttConnectionError) -> Self {
+        match conn.details.category {
+            Some(SttConnectionCategory::LimitExceeded) => SttError::QuotaExceeded {
+                message: conn.message,
+                details: conn.details,
+            },
+            Some(SttConnectionCategory::RateLimited) => SttError::RateLimited {
+                message: conn.message,
+                retry_after_secs: None,
+                details: conn.details,
+            },
+            _ => SttError::Connection(conn),
+        }
+    }
+
+    /// Есть ли смысл повторить подключение/запрос
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            SttError::Connection(conn) => !matches!(&conn.details.category, Some(c) if !c.is_retryable()),
+            SttError::RateLimited { .. } => true,
+            SttError::Processing(_) => true,
+            SttError::Configuration(_)
+            | SttError::Authentication(_)
+            | SttError::QuotaExceeded { .. }
+            | SttError::ProviderBug(_)
+            | SttError::Unsupported(_)
+            | SttError::Internal(_) => false,
+        }
+    }
+
+    /// Сколько подождать перед повтором (только для RateLimited с Retry-After)
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            SttError::RateLimited { retry_after_secs, .. } => retry_after_secs.map(Duration::from_secs),
+            _ => None,
+        }
+    }
+
+    /// Сетевые детали для UI (категория, HTTP статус, close code, код сервера)
+    pub fn connection_details(&self) -> Option<&SttConnectionDetails> {
+        match self {
+            SttError::Connection(conn) => Some(&conn.details),
+            SttError::RateLimited { details, .. } | SttError::QuotaExceeded { details, .. } => Some(details),
+            _ => None,
+        }
+    }
+
+    /// Ключ локализованного сообщения (`errors.<key>` во фронтенде).
+    ///
+    /// None — ошибка без структуры (конфигурация, обработка аудио): фронт подбирает текст сам.
+    pub fn message_key(&self) -> Option<&'static str> {
+        match self {
+            SttError::Authentication(_) => Some("authentication"),
+            SttError::RateLimited { .. } => Some("rateLimited"),
+            SttError::QuotaExceeded { .. } => Some("limitExceeded"),
+            SttError::ProviderBug(_) => Some("providerError"),
+            SttError::Connection(conn) => {
+                let key = match conn.details.category.as_ref()? {
+                    SttConnectionCategory::Offline => "connectionOffline",
+                    SttConnectionCategory::Dns => "connectionDns",
+                    SttConnectionCategory::Tls => "connectionTls",
+                    SttConnectionCategory::Timeout => "timeout",
+                    SttConnectionCategory::Http if conn.details.http_status.is_some() => "connectionHttp",
+                    SttConnectionCategory::RateLimited => "rateLimited",
+                    SttConnectionCategory::LimitExceeded => "limitExceeded",
+                    SttConnectionCategory::ServerUnavailable
+                    | SttConnectionCategory::Refused
+                    | SttConnectionCategory::Reset
+                    | SttConnectionCategory::Closed => "connectionServerUnavailable",
+                    SttConnectionCategory::Http | SttConnectionCategory::Unknown => "connection",
+                };
+                Some(key)
+            }
+            SttError::Configuration(_) | SttError::Processing(_) | SttError::Unsupported(_) | SttError::Internal(_) => {
+                None
+            }
+        }
+    }
+}
+
 /// Более структурированная информация о сетевой/WS ошибке.
 /// Нужна, чтобы UI мог показывать точную причину не на основе парсинга строки.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -62,6 +169,16 @@ pub enum SttConnectionCategory {
     Unknown,
 }
 
+impl SttConnectionCategory {
+    /// Временная проблема сети/сервера — переподключение может помочь
+    pub fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            SttConnectionCategory::Tls | SttConnectionCategory::Http | SttConnectionCategory::LimitExceeded
+        )
+    }
+}
+
 #[derive(Debug, Clone, thiserror::Error)]
 #[error("{message}")]
 pub struct SttConnectionError {
@@ -100,10 +217,55 @@ pub type AudioSpectrumCallback = Arc<dyn Fn([f32; 48]) + Send + Sync>;
 /// Callback type for receiving errors (error message, error type)
 pub type ErrorCallback = Arc<dyn Fn(SttError) + Send + Sync>;
 
+/// Provider internals for diagnostics (get_debug_state)
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct SttProviderDebugState {
+    pub streaming: bool,
+    /// Keep-alive: соединение живо, но аудио не обрабатывается
+    pub paused: bool,
+    /// Накоплено, но ещё не отправлено (минимальный размер чанка, буфер переподключения, локальный Whisper)
+    pub buffered_samples: usize,
+    /// Задача чтения ответов ещё работает; None — у провайдера её нет
+    pub receiver_alive: Option<bool>,
+}
+
+/// Why connection quality changed
+///
+/// Текст для пользователя собирает presentation слой на языке интерфейса,
+/// поэтому здесь только причина и её параметры. В UI уходит и сам код причины,
+/// чтобы фронтенд мог показать её на любой своей локали.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, schemars::JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConnectionQualityReason {
+    /// Аудиопоток с микрофона пропал, пробуем восстановить
+    AudioStreamLost,
+    AudioStreamRestored,
+    /// С микрофона приходят только нули
+    MicrophoneSilent,
+    /// Очередь отправки растёт
+    AudioSendBacklog { backlog_secs: f64 },
+    /// Часть старого аудио выброшена, чтобы догнать запись
+    AudioDropped { backlog_secs: f64, dropped_secs: f64 },
+    AudioBacklogCleared,
+    /// Отправка чанка не укладывается в таймаут
+    AudioSendTooSlow,
+    ProviderConnectionLost,
+    ConnectionRecovering,
+    CriticalConnectionError,
+    /// Слишком много ошибок подряд — запись остановлена
+    ConnectionUnstable,
+    /// Ошибка отправки (тип и текст ошибки провайдера)
+    StreamError { error_type: String, message: String },
+    Reconnecting { attempt: u32, max_attempts: u32 },
+    NoServerResponse { secs: u64 },
+    /// Стрим перестал получать ответы (упала задача чтения) — открыт заново
+    StreamRestarted { attempt: u32, max_attempts: u32 },
+}
+
 /// Callback type for receiving connection quality updates
-/// Параметры: (quality: String, reason: Option<String>)
+/// Параметры: (quality: String, reason: Option<ConnectionQualityReason>)
 /// quality может быть: "Good", "Poor", "Recovering"
-pub type ConnectionQualityCallback = Arc<dyn Fn(String, Option<String>) + Send + Sync>;
+pub type ConnectionQualityCallback = Arc<dyn Fn(String, Option<ConnectionQualityReason>) + Send + Sync>;
 
 /// Trait defining the contract for speech-to-text providers
 ///
@@ -184,13 +346,131 @@ pub trait SttProvider: Send + Sync {
         false
     }
 
+    /// Remaining usage quota in seconds as last reported by the provider (None — not reported)
+    fn remaining_quota_secs(&self) -> Option<f32> {
+        None
+    }
+
+    /// Stream flags, buffered audio and receiver task health for diagnostics
+    fn debug_state(&self) -> SttProviderDebugState {
+        SttProviderDebugState::default()
+    }
+
     /// Check if provider is online (cloud-based)
     fn is_online(&self) -> bool;
 }
 
+/// Non-streaming STT: the whole recording goes to the provider's prerecorded (REST) endpoint
+///
+/// Один HTTP запрос на запись. Подходит для файлов (куски можно отправлять параллельно)
+/// и коротких фраз, где стриминг дороже и точность ниже.
+#[async_trait]
+pub trait BatchSttProvider: Send + Sync {
+    /// Расшифровывает 16-bit mono PCM целиком; возвращает итоговый текст
+    async fn transcribe(&self, samples: &[i16], sample_rate: u32) -> SttResult<String>;
+
+    /// Get provider name for identification
+    fn name(&self) -> &str;
+}
+
 /// Factory trait for creating STT providers
 ///
 /// This allows dependency injection and makes testing easier
 pub trait SttProviderFactory: Send + Sync {
     fn create(&self, config: &SttConfig) -> SttResult<Box<dyn SttProvider>>;
+
+    /// REST клиент для готовых записей; Ok(None) — у провайдера только стриминг
+    fn create_batch(&self, _config: &SttConfig) -> SttResult<Option<Arc<dyn BatchSttProvider>>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryability_by_variant_and_category() {
+        assert!(!SttError::Authentication("401".into()).is_retryable());
+        assert!(!SttError::ProviderBug("bad json".into()).is_retryable());
+
+        let quota = SttError::QuotaExceeded {
+            message: "limit".into(),
+            details: SttConnectionDetails::default(),
+        };
+        assert!(!quota.is_retryable());
+        assert_eq!(quota.retry_after(), None);
+
+        let rate = SttError::RateLimited {
+            message: "429".into(),
+            retry_after_secs: Some(7),
+            details: SttConnectionDetails::default(),
+        };
+        assert!(rate.is_retryable());
+        assert_eq!(rate.retry_after(), Some(Duration::from_secs(7)));
+
+        let reset = SttError::Connection(SttConnectionError::with_category("reset", SttConnectionCategory::Reset));
+        assert!(reset.is_retryable());
+        let tls = SttError::Connection(SttConnectionError::with_category("tls", SttConnectionCategory::Tls));
+        assert!(!tls.is_retryable());
+        // Без категории — не знаем причину, пробуем ещё раз
+        assert!(SttError::Connection(SttConnectionError::simple("eof")).is_retryable());
+    }
+
+    #[test]
+    fn test_message_keys() {
+        let http = SttError::Connection(SttConnectionError {
+            message: "WS connection failed: HTTP error: 400".into(),
+            details: SttConnectionDetails {
+                category: Some(SttConnectionCategory::Http),
+                http_status: Some(400),
+                ..Default::default()
+            },
+        });
+        assert_eq!(http.message_key(), Some("connectionHttp"));
+
+        let refused = SttError::Connection(SttConnectionError::with_category("refused", SttConnectionCategory::Refused));
+        assert_eq!(refused.message_key(), Some("connectionServerUnavailable"));
+
+        assert_eq!(SttError::Connection(SttConnectionError::simple("eof")).message_key(), None);
+        assert_eq!(SttError::Processing("capture".into()).message_key(), None);
+        assert_eq!(SttError::ProviderBug("bad json".into()).message_key(), Some("providerError"));
+    }
+
+    #[test]
+    fn test_from_connection_routes_limit_categories() {
+        let quota = SttError::from_connection(SttConnectionError::with_category(
+            "closed",
+            SttConnectionCategory::LimitExceeded,
+        ));
+        assert!(matches!(quota, SttError::QuotaExceeded { .. }));
+        assert_eq!(quota.message_key(), Some("limitExceeded"));
+        // Категория сохраняется в деталях — фронт по ней узнаёт лимит
+        assert_eq!(
+            quota.connection_details().and_then(|d| d.category.clone()),
+            Some(SttConnectionCategory::LimitExceeded)
+        );
+
+        let rate = SttError::from_connection(SttConnectionError::with_category("429", SttConnectionCategory::RateLimited));
+        assert!(matches!(rate, SttError::RateLimited { retry_after_secs: None, .. }));
+
+        let reset = SttError::from_connection(SttConnectionError::with_category("reset", SttConnectionCategory::Reset));
+        assert!(matches!(reset, SttError::Connection(_)));
+    }
+
+    #[test]
+    fn test_connection_quality_reason_serializes_as_tagged_code() {
+        let dropped = serde_json::to_value(ConnectionQualityReason::AudioDropped {
+            backlog_secs: 1.0,
+            dropped_secs: 2.5,
+        })
+        .unwrap();
+        assert_eq!(
+            dropped,
+            serde_json::json!({ "kind": "audio_dropped", "backlog_secs": 1.0, "dropped_secs": 2.5 })
+        );
+
+        let silent = serde_json::to_value(ConnectionQualityReason::MicrophoneSilent).unwrap();
+        assert_eq!(silent, serde_json::json!({ "kind": "microphone_silent" }));
+    }
 }
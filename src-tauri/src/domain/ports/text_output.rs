@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::domain::models::{TextDelivery, TextOutputSinkConfig};
+
+/// Result type for text output operations
+pub type TextOutputResult<T> = Result<T, TextOutputError>;
+
+/// Errors that can occur while delivering text to a sink
+#[derive(Debug, thiserror::Error)]
+pub enum TextOutputError {
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Sink unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Delivery failed: {0}")]
+    Delivery(String),
+}
+
+/// Trait defining a single destination for final transcription text
+/// (clipboard, auto-paste, typing, file, webhook, ...)
+#[async_trait]
+pub trait TextOutputSink: Send + Sync {
+    /// Sink configuration this instance was built from
+    fn config(&self) -> &TextOutputSinkConfig;
+
+    /// Deliver text to the destination
+    async fn deliver(&self, delivery: &TextDelivery) -> TextOutputResult<()>;
+}
+
+/// Outcome of delivering text to one sink
+#[derive(Debug, Clone, Serialize)]
+pub struct SinkDeliveryOutcome {
+    pub sink: TextOutputSinkConfig,
+    pub error: Option<String>,
+}
+
+/// Trait defining the contract for routing final text to the configured sinks
+///
+/// The router is the single place that decides where text goes. Presentation code
+/// (commands, frontend) only hands over the text and never branches on the
+/// individual delivery options.
+#[async_trait]
+pub trait TextOutputRouter: Send + Sync {
+    /// Deliver text to every configured sink; a failing sink does not stop the others
+    async fn route(&self, delivery: &TextDelivery) -> Vec<SinkDeliveryOutcome>;
+}
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::domain::models::{MeetingUtterance, TopicBreak};
+
+/// Result type for topic segmentation requests
+pub type TopicAdvisorResult<T> = Result<T, TopicAdvisorError>;
+
+/// Errors that can occur while asking for topic breaks
+#[derive(Debug, thiserror::Error)]
+pub enum TopicAdvisorError {
+    #[error("Not authenticated")]
+    Unauthenticated,
+
+    #[error("Topic advisor request failed: {0}")]
+    Request(String),
+}
+
+/// Trait defining an external (LLM) source of topic breaks for a meeting transcript
+///
+/// Дополняет разбиение по паузам: советник видит текст целиком и может найти
+/// смену темы без длинной паузы, а также предложить названия тем.
+#[async_trait]
+pub trait TopicBreakAdvisor: Send + Sync {
+    /// Suggest where new topics start (indices into `utterances`)
+    async fn suggest_breaks(
+        &self,
+        utterances: &[MeetingUtterance],
+        language: Option<&str>,
+    ) -> TopicAdvisorResult<Vec<TopicBreak>>;
+}
@@ -0,0 +1,167 @@
+//! Активное приложение и заголовок его окна — контекст сессии записи (история, аналитика по приложениям).
+//!
+//! Своё окно контекстом не считается: если активен VoicetextAI, возвращаем None,
+//! и вызывающий берёт приложение, запомненное перед показом окна.
+//! На macOS заголовок окна читается через Accessibility; без разрешения остаётся только имя приложения.
+
+// Подавляем warnings от старой версии objc crate
+#![cfg_attr(target_os = "macos", allow(unexpected_cfgs))]
+
+use crate::domain::AppContext;
+
+/// Приложение на переднем плане (None — не удалось определить или это наше окно)
+pub fn foreground_app() -> Option<AppContext> {
+    let context = detect()?;
+    log::debug!("Foreground app: {} ({:?})", context.app_name, context.app_id);
+    Some(context)
+}
+
+/// Имя приложения из пути к exe: `C:\Program Files\Slack\slack.exe` → `slack`
+pub fn app_name_from_exe(exe: &str) -> Option<String> {
+    let file = exe.rsplit(['\\', '/']).next()?;
+    let stem = match file.rsplit_once('.') {
+        Some((stem, ext)) if ext.eq_ignore_ascii_case("exe") => stem,
+        _ => file,
+    };
+    let stem = stem.trim();
+    (!stem.is_empty()).then(|| stem.to_string())
+}
+
+#[cfg(any(target_os = "macos", windows))]
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> Option<AppContext> {
+    use cocoa::base::{id, nil};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let app: id = msg_send![workspace, frontmostApplication];
+        if app == nil {
+            return None;
+        }
+
+        let pid: i32 = msg_send![app, processIdentifier];
+        if pid as u32 == std::process::id() {
+            return None;
+        }
+
+        let app_id = mac::ns_string(msg_send![app, bundleIdentifier]);
+        let app_name = mac::ns_string(msg_send![app, localizedName])
+            .or_else(|| app_id.clone())?;
+        Some(AppContext {
+            app_name,
+            app_id,
+            window_title: mac::focused_window_title(pid),
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod mac {
+    use std::ffi::c_void;
+
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::{msg_send, sel, sel_impl};
+
+    type AXUIElementRef = *const c_void;
+    type CFTypeRef = *const c_void;
+
+    /// kAXErrorSuccess
+    const AX_SUCCESS: i32 = 0;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateApplication(pid: i32) -> AXUIElementRef;
+        fn AXUIElementCopyAttributeValue(element: AXUIElementRef, attribute: CFTypeRef, value: *mut CFTypeRef) -> i32;
+        fn CFRelease(value: CFTypeRef);
+    }
+
+    pub unsafe fn ns_string(value: id) -> Option<String> {
+        if value == nil {
+            return None;
+        }
+        let utf8: *const std::os::raw::c_char = msg_send![value, UTF8String];
+        if utf8.is_null() {
+            return None;
+        }
+        super::non_empty(std::ffi::CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    }
+
+    /// Атрибут AX-элемента (владение результатом — у вызывающего)
+    unsafe fn copy_attribute(element: AXUIElementRef, name: &str) -> Option<CFTypeRef> {
+        // NSString бесплатно приводится к CFStringRef (toll-free bridging)
+        let attribute: id = NSString::alloc(nil).init_str(name);
+        let mut value: CFTypeRef = std::ptr::null();
+        let result = AXUIElementCopyAttributeValue(element, attribute as CFTypeRef, &mut value);
+        let _: () = msg_send![attribute, release];
+        (result == AX_SUCCESS && !value.is_null()).then_some(value)
+    }
+
+    /// Заголовок окна в фокусе (нужно разрешение Accessibility)
+    pub fn focused_window_title(pid: i32) -> Option<String> {
+        unsafe {
+            let app = AXUIElementCreateApplication(pid);
+            if app.is_null() {
+                return None;
+            }
+            let window = copy_attribute(app, "AXFocusedWindow");
+            CFRelease(app);
+            let window = window?;
+
+            let title = copy_attribute(window, "AXTitle");
+            CFRelease(window);
+            let title = title?;
+            let text = ns_string(title as id);
+            CFRelease(title);
+            text
+        }
+    }
+}
+
+#[cfg(windows)]
+fn detect() -> Option<AppContext> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW};
+
+    let (pid, exe) = crate::infrastructure::window_elevation::foreground_process()?;
+    if pid == std::process::id() {
+        return None;
+    }
+
+    let window_title = unsafe {
+        let hwnd = GetForegroundWindow();
+        let mut buffer = [0u16; 512];
+        let len = if hwnd.is_null() { 0 } else { GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32) };
+        non_empty(String::from_utf16_lossy(&buffer[..len.max(0) as usize]))
+    };
+
+    Some(AppContext {
+        app_name: app_name_from_exe(&exe).unwrap_or_else(|| exe.clone()),
+        app_id: Some(exe),
+        window_title,
+    })
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn detect() -> Option<AppContext> {
+    // На Linux активное окно зависит от оконного менеджера (X11/Wayland) — не определяем
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app_name_is_the_exe_stem() {
+        assert_eq!(app_name_from_exe(r"C:\Program Files\Slack\slack.exe").as_deref(), Some("slack"));
+        assert_eq!(app_name_from_exe("WINWORD.EXE").as_deref(), Some("WINWORD"));
+        assert_eq!(app_name_from_exe("/usr/bin/code").as_deref(), Some("code"));
+        assert_eq!(app_name_from_exe(r"C:\Apps\").as_deref(), None);
+    }
+}
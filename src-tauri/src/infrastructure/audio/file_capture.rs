@@ -0,0 +1,524 @@
+use async_trait::async_trait;
+use rubato::{
+    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+
+use crate::domain::{AudioCapture, AudioChunk, AudioChunkCallback, AudioConfig, AudioError, AudioResult};
+
+const TARGET_SAMPLE_RATE: u32 = 16000;
+const TARGET_CHANNELS: u16 = 1;
+const CHUNK_DURATION_MS: u64 = 100;
+
+/// Расширения файлов, которые умеет декодировать `decode_audio_file`
+pub const SUPPORTED_AUDIO_FILE_EXTENSIONS: &[&str] = &["wav", "wave", "mp3"];
+
+/// Встроенный сэмпл для сравнения провайдеров (английская речь, MP3)
+const BUNDLED_BENCHMARK_SAMPLE_MP3: &[u8] = include_bytes!("../../../assets/benchmark/sample_en.mp3");
+
+/// Язык встроенного сэмпла
+pub const BUNDLED_BENCHMARK_SAMPLE_LANGUAGE: &str = "en";
+
+/// File-backed audio capture (replay source)
+///
+/// Decodes a WAV or MP3 file once, converts it to 16kHz mono i16 PCM
+/// and plays it on loop in real time as if it were microphone input.
+/// Used for demos and for integration tests that must run without audio hardware.
+pub struct FileAudioCapture {
+    path: PathBuf,
+    samples: Arc<Vec<i16>>,
+    config: AudioConfig,
+    is_capturing: Arc<AtomicBool>,
+    looped: bool,
+}
+
+impl FileAudioCapture {
+    /// Загружает и декодирует файл (формат определяется по расширению)
+    pub fn from_file(path: impl AsRef<Path>) -> AudioResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let pcm = read_audio_file(&path)?;
+        Ok(Self::from_samples(path, pcm))
+    }
+
+    /// Создаёт capture из уже подготовленных 16kHz mono сэмплов
+    pub fn from_samples(path: impl Into<PathBuf>, samples: Vec<i16>) -> Self {
+        Self {
+            path: path.into(),
+            samples: Arc::new(samples),
+            config: AudioConfig::default(),
+            is_capturing: Arc::new(AtomicBool::new(false)),
+            looped: true,
+        }
+    }
+
+    /// Проигрывать файл по кругу (по умолчанию) или один раз.
+    /// После окончания однократного проигрывания отдаются чанки тишины,
+    /// чтобы VAD мог сработать так же, как на реальном микрофоне.
+    pub fn with_looping(mut self, looped: bool) -> Self {
+        self.looped = looped;
+        self
+    }
+
+    /// Путь к файлу-источнику
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Длительность декодированного аудио в миллисекундах
+    pub fn duration_ms(&self) -> u64 {
+        self.samples.len() as u64 * 1000 / TARGET_SAMPLE_RATE as u64
+    }
+}
+
+#[async_trait]
+impl AudioCapture for FileAudioCapture {
+    async fn initialize(&mut self, config: AudioConfig) -> AudioResult<()> {
+        log::info!("FileAudioCapture: Initializing with config: {:?}", config);
+        // Выход всегда 16kHz mono — остальные поля берём из переданного конфига
+        self.config = AudioConfig {
+            sample_rate: TARGET_SAMPLE_RATE,
+            channels: TARGET_CHANNELS,
+            ..config
+        };
+        Ok(())
+    }
+
+    async fn start_capture(&mut self, on_chunk: AudioChunkCallback) -> AudioResult<()> {
+        if self.is_capturing.swap(true, Ordering::SeqCst) {
+            return Err(AudioError::Capture("Already capturing".to_string()));
+        }
+
+        log::info!("FileAudioCapture: Starting playback of {:?}", self.path);
+
+        let is_capturing = self.is_capturing.clone();
+        let samples = self.samples.clone();
+        let looped = self.looped;
+        let samples_per_chunk = (TARGET_SAMPLE_RATE as u64 * CHUNK_DURATION_MS / 1000) as usize;
+
+        tokio::spawn(async move {
+            let mut timer = interval(Duration::from_millis(CHUNK_DURATION_MS));
+            let mut position = 0usize;
+
+            loop {
+                timer.tick().await;
+
+                if !is_capturing.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let mut data = Vec::with_capacity(samples_per_chunk);
+                while data.len() < samples_per_chunk {
+                    if position >= samples.len() {
+                        if !looped {
+                            // Файл закончился — добиваем тишиной
+                            data.resize(samples_per_chunk, 0);
+                            break;
+                        }
+                        position = 0;
+                    }
+                    let take = (samples_per_chunk - data.len()).min(samples.len() - position);
+                    data.extend_from_slice(&samples[position..position + take]);
+                    position += take;
+                }
+
+                on_chunk(AudioChunk::new(data, TARGET_SAMPLE_RATE, TARGET_CHANNELS));
+            }
+
+            log::info!("FileAudioCapture: Playback loop ended");
+        });
+
+        Ok(())
+    }
+
+    async fn stop_capture(&mut self) -> AudioResult<()> {
+        log::info!("FileAudioCapture: Stopping playback");
+        self.is_capturing.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.is_capturing.load(Ordering::SeqCst)
+    }
+
+    fn config(&self) -> AudioConfig {
+        self.config
+    }
+}
+
+/// Читает и декодирует WAV/MP3 файл в 16kHz mono i16 PCM
+pub fn read_audio_file(path: &Path) -> AudioResult<Vec<i16>> {
+    let bytes = std::fs::read(path).map_err(|e| {
+        AudioError::Configuration(format!("Cannot read audio file {:?}: {}", path, e))
+    })?;
+    decode_audio_file(path, &bytes)
+}
+
+/// Расширение файла в нижнем регистре ("" если его нет)
+pub fn audio_file_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// Формат файла поддерживается `decode_audio_file` (проверка только по расширению)
+pub fn is_supported_audio_file(path: &Path) -> bool {
+    SUPPORTED_AUDIO_FILE_EXTENSIONS.contains(&audio_file_extension(path).as_str())
+}
+
+/// Декодирует WAV/MP3 (формат по расширению пути) в 16kHz mono i16 PCM
+pub fn decode_audio_file(path: &Path, bytes: &[u8]) -> AudioResult<Vec<i16>> {
+    let extension = audio_file_extension(path);
+
+    let (samples, sample_rate, channels) = match extension.as_str() {
+        "wav" | "wave" => decode_wav(bytes)?,
+        "mp3" => decode_mp3(bytes)?,
+        other => {
+            return Err(AudioError::Configuration(format!(
+                "Unsupported audio file format: '{}' (expected wav or mp3)",
+                other
+            )))
+        }
+    };
+
+    let mono = downmix_to_mono(samples, channels);
+    let pcm = resample_to_target(mono, sample_rate)?;
+
+    if pcm.is_empty() {
+        return Err(AudioError::Configuration(format!(
+            "Audio file {:?} contains no samples",
+            path
+        )));
+    }
+
+    log::info!(
+        "Decoded audio {:?} ({} Hz, {} ch → 16000 Hz mono, {:.1}s)",
+        path,
+        sample_rate,
+        channels,
+        pcm.len() as f32 / TARGET_SAMPLE_RATE as f32
+    );
+    Ok(pcm)
+}
+
+/// Встроенный сэмпл для сравнения провайдеров в 16kHz mono i16 PCM
+pub fn bundled_benchmark_sample() -> AudioResult<Vec<i16>> {
+    decode_audio_file(Path::new("sample_en.mp3"), BUNDLED_BENCHMARK_SAMPLE_MP3)
+}
+
+/// Разбирает RIFF/WAVE контейнер (PCM 8/16/24/32 bit и IEEE float 32 bit)
+fn decode_wav(bytes: &[u8]) -> AudioResult<(Vec<i16>, u32, u16)> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(AudioError::Configuration("Not a RIFF/WAVE file".to_string()));
+    }
+
+    let mut format: Option<(u16, u16, u32, u16)> = None; // (format_tag, channels, sample_rate, bits)
+    let mut data: Option<&[u8]> = None;
+    let mut offset = 12;
+
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes([
+            bytes[offset + 4],
+            bytes[offset + 5],
+            bytes[offset + 6],
+            bytes[offset + 7],
+        ]) as usize;
+        let body_start = offset + 8;
+        let body_end = (body_start + size).min(bytes.len());
+        let body = &bytes[body_start..body_end];
+
+        match id {
+            b"fmt " if body.len() >= 16 => {
+                let mut tag = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let sample_rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                // WAVE_FORMAT_EXTENSIBLE: реальный формат лежит в первых байтах SubFormat GUID
+                if tag == 0xFFFE && body.len() >= 26 {
+                    tag = u16::from_le_bytes([body[24], body[25]]);
+                }
+                format = Some((tag, channels, sample_rate, bits));
+            }
+            b"data" => data = Some(body),
+            _ => {}
+        }
+
+        // Чанки выровнены по 2 байта
+        offset = body_start + size + (size & 1);
+    }
+
+    let (tag, channels, sample_rate, bits) =
+        format.ok_or_else(|| AudioError::Configuration("WAV file has no fmt chunk".to_string()))?;
+    let data = data.ok_or_else(|| AudioError::Configuration("WAV file has no data chunk".to_string()))?;
+
+    if channels == 0 || sample_rate == 0 {
+        return Err(AudioError::Configuration(format!(
+            "Invalid WAV format: {} channels, {} Hz",
+            channels, sample_rate
+        )));
+    }
+
+    let samples: Vec<i16> = match (tag, bits) {
+        (1, 8) => data.iter().map(|&b| ((b as i16) - 128) << 8).collect(),
+        (1, 16) => data
+            .chunks_exact(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]))
+            .collect(),
+        (1, 24) => data
+            .chunks_exact(3)
+            .map(|c| (i32::from_le_bytes([0, c[0], c[1], c[2]]) >> 16) as i16)
+            .collect(),
+        (1, 32) => data
+            .chunks_exact(4)
+            .map(|c| (i32::from_le_bytes([c[0], c[1], c[2], c[3]]) >> 16) as i16)
+            .collect(),
+        (3, 32) => data
+            .chunks_exact(4)
+            .map(|c| {
+                let s = f32::from_le_bytes([c[0], c[1], c[2], c[3]]).clamp(-1.0, 1.0);
+                (s * i16::MAX as f32) as i16
+            })
+            .collect(),
+        _ => {
+            return Err(AudioError::Configuration(format!(
+                "Unsupported WAV encoding: format tag {}, {} bits",
+                tag, bits
+            )))
+        }
+    };
+
+    Ok((samples, sample_rate, channels))
+}
+
+/// Декодирует MP3 через minimp3
+fn decode_mp3(bytes: &[u8]) -> AudioResult<(Vec<i16>, u32, u16)> {
+    let mut decoder = minimp3::Decoder::new(bytes);
+    let mut samples = Vec::new();
+    let mut sample_rate = 0u32;
+    let mut channels = 0u16;
+
+    loop {
+        match decoder.next_frame() {
+            Ok(frame) => {
+                sample_rate = frame.sample_rate as u32;
+                channels = frame.channels as u16;
+                samples.extend_from_slice(&frame.data);
+            }
+            Err(minimp3::Error::Eof) => break,
+            Err(e) => {
+                return Err(AudioError::Configuration(format!("Failed to decode MP3: {}", e)))
+            }
+        }
+    }
+
+    if sample_rate == 0 || channels == 0 {
+        return Err(AudioError::Configuration("MP3 file contains no frames".to_string()));
+    }
+
+    Ok((samples, sample_rate, channels))
+}
+
+fn downmix_to_mono(samples: Vec<i16>, channels: u16) -> Vec<i16> {
+    if channels <= 1 {
+        return samples;
+    }
+    samples
+        .chunks_exact(channels as usize)
+        .map(|frame| {
+            let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+            (sum / channels as i32) as i16
+        })
+        .collect()
+}
+
+fn resample_to_target(samples: Vec<i16>, sample_rate: u32) -> AudioResult<Vec<i16>> {
+    if sample_rate == TARGET_SAMPLE_RATE || samples.is_empty() {
+        return Ok(samples);
+    }
+
+    let params = SincInterpolationParameters {
+        sinc_len: 256,
+        f_cutoff: 0.95,
+        interpolation: SincInterpolationType::Linear,
+        oversampling_factor: 256,
+        window: WindowFunction::BlackmanHarris2,
+    };
+
+    let mut resampler = SincFixedIn::<f32>::new(
+        TARGET_SAMPLE_RATE as f64 / sample_rate as f64,
+        2.0,
+        params,
+        samples.len(),
+        1,
+    )
+    .map_err(|e| AudioError::Configuration(format!("Failed to create resampler: {}", e)))?;
+
+    let input = vec![samples.iter().map(|&s| s as f32 / 32768.0).collect::<Vec<f32>>()];
+    let output = resampler
+        .process(&input, None)
+        .map_err(|e| AudioError::Internal(format!("Resampling failed: {}", e)))?;
+
+    Ok(output[0]
+        .iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn wav_bytes(samples: &[i16], sample_rate: u32, channels: u16) -> Vec<u8> {
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVE");
+        out.extend_from_slice(b"fmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&channels.to_le_bytes());
+        out.extend_from_slice(&sample_rate.to_le_bytes());
+        out.extend_from_slice(&(sample_rate * channels as u32 * 2).to_le_bytes());
+        out.extend_from_slice(&(channels * 2).to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(&data);
+        out
+    }
+
+    #[test]
+    fn test_decode_wav_pcm16_mono() {
+        let bytes = wav_bytes(&[1, -2, 300, -400], 16000, 1);
+        let (samples, rate, channels) = decode_wav(&bytes).unwrap();
+        assert_eq!(samples, vec![1, -2, 300, -400]);
+        assert_eq!(rate, 16000);
+        assert_eq!(channels, 1);
+    }
+
+    #[test]
+    fn test_decode_wav_rejects_garbage() {
+        assert!(decode_wav(b"not a wav file at all").is_err());
+    }
+
+    #[test]
+    fn test_downmix_stereo() {
+        let mono = downmix_to_mono(vec![100, 300, -100, -300], 2);
+        assert_eq!(mono, vec![200, -200]);
+    }
+
+    #[test]
+    fn test_resample_changes_length() {
+        let samples = vec![0i16; 48000];
+        let out = resample_to_target(samples, 48000).unwrap();
+        // ~1 секунда при 16kHz (ресемплер может дать небольшую погрешность)
+        assert!((15000..=17000).contains(&out.len()), "got {}", out.len());
+    }
+
+    #[test]
+    fn test_bundled_benchmark_sample_decodes() {
+        let pcm = bundled_benchmark_sample().unwrap();
+        // Больше секунды речи
+        assert!(pcm.len() > TARGET_SAMPLE_RATE as usize);
+    }
+
+    #[test]
+    fn test_from_file_unsupported_extension() {
+        let dir = std::env::temp_dir().join(format!("file_capture_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audio.ogg");
+        std::fs::write(&path, b"OggS").unwrap();
+
+        let result = FileAudioCapture::from_file(&path);
+        assert!(matches!(result, Err(AudioError::Configuration(_))));
+        assert!(!is_supported_audio_file(&path));
+        assert!(is_supported_audio_file(Path::new("Запись.MP3")));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_from_file_missing_is_configuration_error() {
+        let path = std::env::temp_dir().join(format!("file_capture_{}.wav", uuid::Uuid::new_v4()));
+        let result = FileAudioCapture::from_file(&path);
+        assert!(matches!(result, Err(AudioError::Configuration(_))));
+    }
+
+    #[test]
+    fn test_from_file_wav() {
+        let dir = std::env::temp_dir().join(format!("file_capture_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tone.wav");
+        std::fs::write(&path, wav_bytes(&vec![1000i16; 16000], 16000, 1)).unwrap();
+
+        let capture = FileAudioCapture::from_file(&path).unwrap();
+        assert_eq!(capture.duration_ms(), 1000);
+        assert_eq!(capture.path(), path.as_path());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_playback_loops_over_samples() {
+        // 50ms файла — за один 100ms чанк он должен проиграться дважды
+        let samples: Vec<i16> = (0..800).map(|i| i as i16).collect();
+        let mut capture = FileAudioCapture::from_samples("memory", samples);
+        capture.initialize(AudioConfig::default()).await.unwrap();
+
+        let chunks = Arc::new(Mutex::new(Vec::<AudioChunk>::new()));
+        let chunks_clone = chunks.clone();
+        capture
+            .start_capture(Arc::new(move |chunk| chunks_clone.lock().unwrap().push(chunk)))
+            .await
+            .unwrap();
+        assert!(capture.is_capturing());
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        capture.stop_capture().await.unwrap();
+        assert!(!capture.is_capturing());
+
+        let chunks = chunks.lock().unwrap();
+        assert!(!chunks.is_empty());
+        let first = &chunks[0];
+        assert_eq!(first.data.len(), 1600);
+        assert_eq!(first.sample_rate, 16000);
+        assert_eq!(first.data[0], 0);
+        assert_eq!(first.data[800], 0); // начало второго круга
+    }
+
+    #[tokio::test]
+    async fn test_playback_without_loop_pads_with_silence() {
+        let mut capture = FileAudioCapture::from_samples("memory", vec![500i16; 800]).with_looping(false);
+
+        let chunks = Arc::new(Mutex::new(Vec::<AudioChunk>::new()));
+        let chunks_clone = chunks.clone();
+        capture
+            .start_capture(Arc::new(move |chunk| chunks_clone.lock().unwrap().push(chunk)))
+            .await
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        capture.stop_capture().await.unwrap();
+
+        let chunks = chunks.lock().unwrap();
+        let first = &chunks[0];
+        assert_eq!(first.data[799], 500);
+        assert!(first.data[800..].iter().all(|&s| s == 0));
+    }
+
+    #[tokio::test]
+    async fn test_double_start_fails() {
+        let mut capture = FileAudioCapture::from_samples("memory", vec![0i16; 1600]);
+        let on_chunk: AudioChunkCallback = Arc::new(|_chunk: AudioChunk| {});
+
+        capture.start_capture(on_chunk.clone()).await.unwrap();
+        assert!(capture.start_capture(on_chunk).await.is_err());
+        capture.stop_capture().await.unwrap();
+    }
+}
@@ -1,11 +1,18 @@
 /// Audio capture implementations
 
 mod mock_capture;
+mod file_capture;
 mod vad_processor;
 mod system_capture;
 mod vad_capture_wrapper;
+mod playback;
 
 pub use mock_capture::MockAudioCapture;
-pub use vad_processor::{VadProcessor, VadResult};
-pub use system_capture::SystemAudioCapture;
-pub use vad_capture_wrapper::VadCaptureWrapper;
+pub use file_capture::{
+    audio_file_extension, bundled_benchmark_sample, is_supported_audio_file, read_audio_file, FileAudioCapture,
+    BUNDLED_BENCHMARK_SAMPLE_LANGUAGE, SUPPORTED_AUDIO_FILE_EXTENSIONS,
+};
+pub use vad_processor::{VadProcessor, VadResult, VadSummary};
+pub use system_capture::{is_loopback_device_name, SystemAudioCapture};
+pub use vad_capture_wrapper::{VadActivity, VadActivityCallback, VadActivityKind, VadCaptureWrapper, VadTimingCallback};
+pub use playback::{output_device_names, play_pcm_blocking};
@@ -0,0 +1,188 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, FromSample, SampleFormat, SizedSample, Stream, StreamConfig};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::domain::{AudioError, AudioResult};
+
+/// Сколько ждём после последнего сэмпла: буфер устройства (особенно Bluetooth) ещё доигрывает
+const DRAIN_TAIL: Duration = Duration::from_millis(300);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Names of all output devices
+pub fn output_device_names() -> AudioResult<Vec<String>> {
+    let host = cpal::default_host();
+    let devices = host
+        .output_devices()
+        .map_err(|e| AudioError::DeviceNotFound(format!("Failed to enumerate output devices: {}", e)))?
+        .filter_map(|device| device.name().ok())
+        .collect();
+    Ok(devices)
+}
+
+fn select_output_device(device_name: Option<&str>) -> AudioResult<Device> {
+    let host = cpal::default_host();
+    match device_name {
+        Some(name) => host
+            .output_devices()
+            .map_err(|e| AudioError::DeviceNotFound(format!("Failed to enumerate output devices: {}", e)))?
+            .find(|d| d.name().ok().as_deref() == Some(name))
+            .ok_or_else(|| AudioError::DeviceNotFound(format!("Output device '{}' not found", name))),
+        None => host
+            .default_output_device()
+            .ok_or_else(|| AudioError::DeviceNotFound("No output device available".to_string())),
+    }
+}
+
+/// Linear interpolation resample of i16 PCM into f32 (-1..1)
+fn resample_linear(samples: &[i16], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    let normalized = |s: i16| s as f32 / 32767.0;
+    if samples.is_empty() || from_rate == 0 || to_rate == 0 {
+        return Vec::new();
+    }
+    if from_rate == to_rate {
+        return samples.iter().map(|&s| normalized(s)).collect();
+    }
+
+    let out_len = (samples.len() as u64 * to_rate as u64 / from_rate as u64) as usize;
+    let step = from_rate as f64 / to_rate as f64;
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * step;
+            let index = pos as usize;
+            let frac = (pos - index as f64) as f32;
+            let a = normalized(samples[index.min(samples.len() - 1)]);
+            let b = normalized(samples[(index + 1).min(samples.len() - 1)]);
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+fn build_stream<T>(
+    device: &Device,
+    config: &StreamConfig,
+    frames: Arc<Vec<f32>>,
+    position: Arc<AtomicUsize>,
+) -> AudioResult<Stream>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let channels = config.channels.max(1) as usize;
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                let mut pos = position.load(Ordering::Relaxed);
+                for frame in data.chunks_mut(channels) {
+                    let value = frames.get(pos).copied().unwrap_or(0.0);
+                    for sample in frame.iter_mut() {
+                        *sample = T::from_sample(value);
+                    }
+                    pos = pos.saturating_add(1);
+                }
+                position.store(pos.min(frames.len()), Ordering::Relaxed);
+            },
+            |err| log::error!("Playback stream error: {}", err),
+            None,
+        )
+        .map_err(|e| AudioError::Configuration(format!("Failed to build output stream: {}", e)))
+}
+
+/// Native playback of a short mono PCM buffer (microphone test) on a chosen output device
+///
+/// Flow:
+/// 1. Resolve output device by name (or default output)
+/// 2. Linear resample to the native rate of the device
+/// 3. Duplicate mono into every output channel
+/// 4. Block until the buffer is drained or `cancel` is set
+///
+/// cpal::Stream не Send на macOS, поэтому всё воспроизведение живёт в одном потоке (spawn_blocking).
+pub fn play_pcm_blocking(
+    samples: &[i16],
+    sample_rate: u32,
+    device_name: Option<&str>,
+    cancel: &AtomicBool,
+) -> AudioResult<()> {
+    let device = select_output_device(device_name)?;
+    let device_label = device.name().unwrap_or_else(|_| "Unknown".to_string());
+    let supported = device
+        .default_output_config()
+        .map_err(|e| AudioError::Configuration(format!("Failed to get default output config: {}", e)))?;
+    let config: StreamConfig = supported.config();
+
+    let frames = Arc::new(resample_linear(samples, sample_rate, config.sample_rate.0));
+    let total = frames.len();
+    let position = Arc::new(AtomicUsize::new(0));
+
+    log::info!(
+        "Playing {} samples on '{}' ({} Hz, {} channels, {:?})",
+        samples.len(),
+        device_label,
+        config.sample_rate.0,
+        config.channels,
+        supported.sample_format()
+    );
+
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => build_stream::<f32>(&device, &config, frames, position.clone())?,
+        SampleFormat::I16 => build_stream::<i16>(&device, &config, frames, position.clone())?,
+        SampleFormat::U16 => build_stream::<u16>(&device, &config, frames, position.clone())?,
+        other => {
+            return Err(AudioError::Configuration(format!("Unsupported output sample format: {:?}", other)));
+        }
+    };
+    stream
+        .play()
+        .map_err(|e| AudioError::Capture(format!("Failed to start playback: {}", e)))?;
+
+    // Страховка от зависшего устройства: не дольше длины записи + запас
+    let duration = Duration::from_secs_f64(total as f64 / config.sample_rate.0.max(1) as f64);
+    let deadline = Instant::now() + duration + Duration::from_secs(2);
+    while position.load(Ordering::Relaxed) < total {
+        if cancel.load(Ordering::Relaxed) {
+            log::info!("Playback cancelled");
+            return Ok(());
+        }
+        if Instant::now() > deadline {
+            log::warn!("Playback did not finish in time on '{}'", device_label);
+            return Ok(());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    let tail_until = Instant::now() + DRAIN_TAIL;
+    while Instant::now() < tail_until && !cancel.load(Ordering::Relaxed) {
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    drop(stream);
+    log::info!("Playback finished on '{}'", device_label);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample_same_rate_only_normalizes() {
+        let out = resample_linear(&[0, 32767, -32767], 16000, 16000);
+        assert_eq!(out, vec![0.0, 1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_resample_upsamples_with_interpolation() {
+        let out = resample_linear(&[0, 32767], 16000, 48000);
+        assert_eq!(out.len(), 6);
+        assert_eq!(out[0], 0.0);
+        assert!((out[1] - 1.0 / 3.0).abs() < 1e-4);
+        assert_eq!(out[3], 1.0);
+        // За последним сэмплом держим его значение
+        assert_eq!(out[5], 1.0);
+    }
+
+    #[test]
+    fn test_resample_empty_input() {
+        assert!(resample_linear(&[], 16000, 44100).is_empty());
+    }
+}
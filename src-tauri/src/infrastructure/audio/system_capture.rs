@@ -21,7 +21,7 @@ use crate::domain::{AudioCapture, AudioChunk, AudioChunkCallback, AudioConfig, A
 ///
 /// Target format:
 /// - 16kHz sample rate
-/// - Mono channel
+/// - Mono channel (стерео L/R, если в AudioConfig запрошено 2 канала и устройство их отдаёт)
 /// - i16 PCM samples
 const TARGET_SAMPLE_RATE: u32 = 16000;
 const TARGET_CHANNELS: u16 = 1;
@@ -35,6 +35,8 @@ pub struct SystemAudioCapture {
     native_config: SupportedStreamConfig,
     audio_config: AudioConfig,
     is_capturing: bool,
+    // Ресурсы освобождены политикой простоя — при следующем старте заново резолвим устройство
+    released: bool,
 }
 
 impl SystemAudioCapture {
@@ -56,6 +58,7 @@ impl SystemAudioCapture {
             native_config,
             audio_config: AudioConfig::default(),
             is_capturing: false,
+            released: false,
         })
     }
 
@@ -144,6 +147,16 @@ impl SystemAudioCapture {
         }
     }
 
+    fn reacquire_if_released(&mut self) {
+        // После release() устройство могло смениться (док-станция, наушники) — резолвим заново.
+        if self.released {
+            if let Err(e) = self.refresh_device_and_config() {
+                log::warn!("Failed to re-acquire audio device after idle release, using previous handle: {}", e);
+            }
+            self.released = false;
+        }
+    }
+
     fn force_default_device_and_config(&mut self) -> AudioResult<()> {
         let host = cpal::default_host();
         let (device, cfg) = Self::select_device_and_config(&host, None)?;
@@ -224,6 +237,45 @@ impl SystemAudioCapture {
             })
             .collect()
     }
+
+    /// Keep the first two channels (L/R) of N-channel PCM, interleaved
+    #[inline]
+    fn take_stereo(samples: &[i16], channels: usize) -> Vec<i16> {
+        if channels == 2 {
+            return samples.to_vec();
+        }
+
+        samples
+            .chunks_exact(channels)
+            .flat_map(|frame| [frame[0], frame[1]])
+            .collect()
+    }
+
+    /// Interleaved i16 PCM → один f32 буфер на канал (вход rubato)
+    fn deinterleave_f32(samples: &[i16], channels: usize) -> Vec<Vec<f32>> {
+        (0..channels)
+            .map(|channel| {
+                samples
+                    .iter()
+                    .skip(channel)
+                    .step_by(channels)
+                    .map(|&s| s as f32 / 32767.0)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Буферы каналов после rubato → interleaved i16 PCM
+    fn interleave_to_i16(channels: &[Vec<f32>]) -> Vec<i16> {
+        let frames = channels.iter().map(Vec::len).min().unwrap_or(0);
+        let mut interleaved = Vec::with_capacity(frames * channels.len());
+        for frame in 0..frames {
+            for channel in channels {
+                interleaved.push(channel[frame]);
+            }
+        }
+        Self::f32_to_i16(&interleaved)
+    }
 }
 
 // SAFETY: SystemAudioCapture содержит cpal::Stream который не Send/Sync на macOS.
@@ -249,18 +301,27 @@ impl AudioCapture for SystemAudioCapture {
             ));
         }
 
+        self.reacquire_if_released();
+
         // На некоторых устройствах (особенно на macOS) stream может не собраться с первого раза,
         // если конфиг/девайс изменился "под ногами". Делаем 1 безопасный ретрай с рефрешем.
         for attempt in 0..=1 {
             let native_sample_rate = self.native_config.sample_rate().0;
             let native_channels = self.native_config.channels() as usize;
 
+            // Стерео-интервью: отдаём L/R как есть; у моно-устройства — обычный моно-поток
+            let keep_stereo = self.audio_config.channels == 2 && native_channels >= 2;
+            if self.audio_config.channels == 2 && !keep_stereo {
+                log::warn!("Stereo capture requested, but the input device has a single channel; capturing mono");
+            }
+            let output_channels: u16 = if keep_stereo { 2 } else { TARGET_CHANNELS };
+
             log::info!(
                 "Starting audio capture: {} Hz → {} Hz, {} channels → {} channel",
                 native_sample_rate,
                 TARGET_SAMPLE_RATE,
                 native_channels,
-                TARGET_CHANNELS
+                output_channels
             );
 
             // Create resampler if needed (wrapped in Arc<Mutex<>> for thread safety)
@@ -268,7 +329,7 @@ impl AudioCapture for SystemAudioCapture {
             let resampler: Option<Arc<Mutex<SincFixedIn<f32>>>> = if needs_resampling {
                 Some(Arc::new(Mutex::new(Self::create_resampler(
                     native_sample_rate,
-                    1, // mono after conversion
+                    output_channels as usize, // каналы после downmix/выбора L/R
                 )?)))
             } else {
                 None
@@ -286,9 +347,12 @@ impl AudioCapture for SystemAudioCapture {
             let sample_format = self.native_config.sample_format();
 
             let on_chunk_cb = on_chunk.clone();
+            let frame_len = RESAMPLER_CHUNK_SIZE * output_channels as usize;
             let process_pcm = move |mut pcm_samples: Vec<i16>| {
                 // Downmix to mono if needed
-                if native_channels > 1 {
+                if keep_stereo {
+                    pcm_samples = Self::take_stereo(&pcm_samples, native_channels);
+                } else if native_channels > 1 {
                     pcm_samples = Self::downmix_to_mono(&pcm_samples, native_channels);
                 }
 
@@ -303,12 +367,11 @@ impl AudioCapture for SystemAudioCapture {
 
                 buffer.extend_from_slice(&pcm_samples);
 
-                while buffer.len() >= RESAMPLER_CHUNK_SIZE {
-                    let chunk: Vec<i16> = buffer.drain(..RESAMPLER_CHUNK_SIZE).collect();
+                while buffer.len() >= frame_len {
+                    let chunk: Vec<i16> = buffer.drain(..frame_len).collect();
 
                     let final_samples = if let Some(ref rs) = resampler_clone {
-                        let float_chunk: Vec<f32> = chunk.iter().map(|&s| s as f32 / 32767.0).collect();
-                        let resampler_input = vec![float_chunk];
+                        let resampler_input = Self::deinterleave_f32(&chunk, output_channels as usize);
 
                         let mut resampler_guard = match rs.lock() {
                             Ok(r) => r,
@@ -320,7 +383,7 @@ impl AudioCapture for SystemAudioCapture {
                         };
 
                         match resampler_guard.process(&resampler_input, None) {
-                            Ok(output) => Self::f32_to_i16(&output[0]),
+                            Ok(output) => Self::interleave_to_i16(&output),
                             Err(e) => {
                                 log::error!("Resampling error: {}", e);
                                 continue;
@@ -330,7 +393,7 @@ impl AudioCapture for SystemAudioCapture {
                         chunk
                     };
 
-                    let audio_chunk = AudioChunk::new(final_samples, TARGET_SAMPLE_RATE, TARGET_CHANNELS);
+                    let audio_chunk = AudioChunk::new(final_samples, TARGET_SAMPLE_RATE, output_channels);
                     on_chunk_cb(audio_chunk);
                 }
             };
@@ -441,6 +504,23 @@ impl AudioCapture for SystemAudioCapture {
         Ok(())
     }
 
+    async fn release(&mut self) -> AudioResult<()> {
+        if self.is_capturing {
+            self.stop_capture().await?;
+        }
+
+        self.stream = None;
+        self.released = true;
+        log::info!("Audio capture resources released (idle)");
+
+        Ok(())
+    }
+
+    async fn prepare(&mut self) -> AudioResult<()> {
+        self.reacquire_if_released();
+        Ok(())
+    }
+
     fn is_capturing(&self) -> bool {
         self.is_capturing
     }
@@ -450,6 +530,26 @@ impl AudioCapture for SystemAudioCapture {
     }
 }
 
+/// Похоже ли входное устройство на захват системного звука (loopback/monitor), а не на микрофон.
+///
+/// Отдельного loopback API у нас нет: используем то, что ОС/драйверы отдают как обычный вход —
+/// "Monitor of ..." (PulseAudio/PipeWire), "Stereo Mix" (Windows), BlackHole/Soundflower/VB-Cable.
+pub fn is_loopback_device_name(name: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "monitor of",
+        ".monitor",
+        "stereo mix",
+        "what u hear",
+        "wave out mix",
+        "loopback",
+        "blackhole",
+        "soundflower",
+        "cable output",
+    ];
+    let name = name.to_lowercase();
+    MARKERS.iter().any(|marker| name.contains(marker))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -505,6 +605,16 @@ mod tests {
         assert_eq!(output[3], 32767);  // 2.0 clamped to 1.0
     }
 
+    #[test]
+    fn test_is_loopback_device_name() {
+        assert!(is_loopback_device_name("Monitor of Built-in Audio Analog Stereo"));
+        assert!(is_loopback_device_name("alsa_output.pci-0000_00_1f.3.analog-stereo.monitor"));
+        assert!(is_loopback_device_name("Stereo Mix (Realtek(R) Audio)"));
+        assert!(is_loopback_device_name("BlackHole 2ch"));
+        assert!(!is_loopback_device_name("MacBook Pro Microphone"));
+        assert!(!is_loopback_device_name("USB Audio Device"));
+    }
+
     #[test]
     fn test_stereo_to_mono_empty() {
         let empty: Vec<i16> = vec![];
@@ -524,6 +634,20 @@ mod tests {
         assert_eq!(mono[1], i16::MIN);
     }
 
+    #[test]
+    fn test_stereo_keeps_left_and_right_channels() {
+        // 4 канала: берём только первые два
+        let quad = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(SystemAudioCapture::take_stereo(&quad, 4), vec![1, 2, 5, 6]);
+
+        let stereo = vec![i16::MAX, 0, 0, i16::MIN + 1];
+        let split = SystemAudioCapture::deinterleave_f32(&stereo, 2);
+        assert_eq!(split.len(), 2);
+        assert_eq!(split[0], vec![1.0, 0.0]);
+        assert_eq!(split[1], vec![0.0, -1.0]);
+        assert_eq!(SystemAudioCapture::interleave_to_i16(&split), stereo);
+    }
+
     #[tokio::test]
     async fn test_capture_state_transitions() {
         let result = SystemAudioCapture::new();
@@ -542,4 +666,17 @@ mod tests {
             assert!(init_result.is_ok());
         }
     }
+
+    #[tokio::test]
+    async fn test_release_marks_device_for_reacquire() {
+        if let Ok(mut capture) = SystemAudioCapture::new() {
+            assert!(capture.release().await.is_ok());
+            assert!(capture.released);
+            assert!(capture.stream.is_none());
+            assert!(!capture.is_capturing());
+
+            assert!(capture.prepare().await.is_ok());
+            assert!(!capture.released);
+        }
+    }
 }
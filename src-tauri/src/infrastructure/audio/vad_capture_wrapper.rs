@@ -1,4 +1,6 @@
 use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -8,6 +10,93 @@ use crate::infrastructure::audio::{VadProcessor, VadResult};
 /// Callback type for silence timeout events
 pub type SilenceTimeoutCallback = Arc<dyn Fn() + Send + Sync>;
 
+/// Callback type for VAD decisions (speech/silence bands in the UI)
+pub type VadActivityCallback = Arc<dyn Fn(VadActivity) + Send + Sync>;
+
+/// Callback type for VAD processing time of one 30ms frame (pipeline tracing)
+pub type VadTimingCallback = Arc<dyn Fn(std::time::Duration) + Send + Sync>;
+
+const VAD_FRAME_MS: u64 = 30;
+/// Окно сглаживания вероятности речи: 10 фреймов × 30ms
+const ACTIVITY_WINDOW_FRAMES: usize = 10;
+/// Уровень без смены состояния отправляем раз в 150ms — UI хватает, канал не забивается
+const ACTIVITY_REPORT_EVERY_FRAMES: u64 = 5;
+/// Гистерезис: речь начинается на половине окна, заканчивается, когда в окне не осталось речи
+const SPEECH_START_PROBABILITY: f32 = 0.5;
+const SPEECH_END_PROBABILITY: f32 = 0.1;
+
+/// Kind of a VAD decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VadActivityKind {
+    SpeechStart,
+    SpeechEnd,
+    /// Periodic probability update without a state change
+    Level,
+    /// Silence lasted for the auto-stop timeout
+    SilenceTimeout,
+}
+
+/// One VAD decision during recording
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VadActivity {
+    pub kind: VadActivityKind,
+    /// От начала захвата, мс (по обработанным фреймам)
+    pub offset_ms: u64,
+    /// Доля речевых фреймов в окне 300ms (0.0..1.0) — WebRTC VAD сам вероятность не отдаёт
+    pub probability: f32,
+    /// Непрерывная тишина на этот момент, мс (то, что считает авто-стоп)
+    pub silence_ms: u64,
+}
+
+/// Превращает решения VAD по фреймам в поток событий для UI
+#[derive(Debug, Default)]
+struct VadActivityTracker {
+    window: VecDeque<bool>,
+    frames: u64,
+    speaking: bool,
+}
+
+impl VadActivityTracker {
+    fn push(&mut self, is_speech: bool, silence_ms: u64) -> Option<VadActivity> {
+        self.frames += 1;
+        if self.window.len() == ACTIVITY_WINDOW_FRAMES {
+            self.window.pop_front();
+        }
+        self.window.push_back(is_speech);
+        let probability = self.probability();
+
+        let kind = if !self.speaking && probability >= SPEECH_START_PROBABILITY {
+            self.speaking = true;
+            VadActivityKind::SpeechStart
+        } else if self.speaking && probability < SPEECH_END_PROBABILITY {
+            self.speaking = false;
+            VadActivityKind::SpeechEnd
+        } else if self.frames % ACTIVITY_REPORT_EVERY_FRAMES == 0 {
+            VadActivityKind::Level
+        } else {
+            return None;
+        };
+        Some(self.activity(kind, silence_ms))
+    }
+
+    fn activity(&self, kind: VadActivityKind, silence_ms: u64) -> VadActivity {
+        VadActivity {
+            kind,
+            offset_ms: self.frames * VAD_FRAME_MS,
+            probability: self.probability(),
+            silence_ms,
+        }
+    }
+
+    fn probability(&self) -> f32 {
+        if self.window.is_empty() {
+            return 0.0;
+        }
+        self.window.iter().filter(|speech| **speech).count() as f32 / self.window.len() as f32
+    }
+}
+
 /// VAD-aware audio capture wrapper
 ///
 /// Wraps any AudioCapture implementation and adds Voice Activity Detection:
@@ -23,6 +112,8 @@ pub struct VadCaptureWrapper {
     inner: Box<dyn AudioCapture>,
     vad: Arc<Mutex<VadProcessor>>,
     on_silence_timeout: Option<SilenceTimeoutCallback>,
+    on_activity: Option<VadActivityCallback>,
+    on_timing: Option<VadTimingCallback>,
     audio_config: AudioConfig,
     silence_timeout_triggered: Arc<Mutex<bool>>, // Флаг для одноразового вызова callback
     running: Arc<AtomicBool>, // Защита от "хвостов" callback после stop_capture
@@ -39,6 +130,8 @@ impl VadCaptureWrapper {
             inner,
             vad: Arc::new(Mutex::new(vad)),
             on_silence_timeout: None,
+            on_activity: None,
+            on_timing: None,
             audio_config: AudioConfig::default(),
             silence_timeout_triggered: Arc::new(Mutex::new(false)),
             running: Arc::new(AtomicBool::new(false)),
@@ -51,6 +144,16 @@ impl VadCaptureWrapper {
     pub fn set_silence_timeout_callback(&mut self, callback: SilenceTimeoutCallback) {
         self.on_silence_timeout = Some(callback);
     }
+
+    /// Set callback for VAD decisions: speech start/end edges, periodic probability and the silence timeout
+    pub fn set_activity_callback(&mut self, callback: VadActivityCallback) {
+        self.on_activity = Some(callback);
+    }
+
+    /// Set callback for VAD processing time of each frame
+    pub fn set_timing_callback(&mut self, callback: VadTimingCallback) {
+        self.on_timing = Some(callback);
+    }
 }
 
 #[async_trait]
@@ -77,8 +180,13 @@ impl AudioCapture for VadCaptureWrapper {
 
         let vad = self.vad.clone();
         let silence_callback = self.on_silence_timeout.clone();
+        let activity_callback = self.on_activity.clone();
+        let timing_callback = self.on_timing.clone();
+        let activity_tracker = Mutex::new(VadActivityTracker::default());
         let timeout_flag = self.silence_timeout_triggered.clone();
         let running = self.running.clone();
+        // Стерео (режим интервью) приходит каждым чанком — пишем в лог один раз за запись
+        let stereo_logged = AtomicBool::new(false);
 
         // Frame buffer for accumulating exactly 480 samples (30ms @ 16kHz)
         // Shared between callback invocations via Arc<Mutex<>>
@@ -103,10 +211,12 @@ impl AudioCapture for VadCaptureWrapper {
             }
 
             if chunk.channels != 1 {
-                log::error!(
-                    "VAD requires mono audio, got {} channels. Skipping VAD.",
-                    chunk.channels
-                );
+                if !stereo_logged.swap(true, Ordering::Relaxed) {
+                    log::warn!(
+                        "VAD requires mono audio, got {} channels. Skipping VAD.",
+                        chunk.channels
+                    );
+                }
                 on_chunk(chunk); // Pass through without VAD
                 return;
             }
@@ -140,7 +250,12 @@ impl AudioCapture for VadCaptureWrapper {
                     }
                 };
 
-                let vad_result = match vad_guard.process_samples(&frame) {
+                let started = std::time::Instant::now();
+                let processed = vad_guard.process_samples(&frame);
+                if let Some(ref callback) = timing_callback {
+                    callback(started.elapsed());
+                }
+                let vad_result = match processed {
                     Ok(result) => result,
                     Err(e) => {
                         log::error!("VAD processing error: {}", e);
@@ -149,8 +264,23 @@ impl AudioCapture for VadCaptureWrapper {
                         continue;
                     }
                 };
+                let silence_ms = vad_guard.silence_duration().as_millis() as u64;
                 drop(vad_guard); // Release VAD lock before callback
 
+                // Решения VAD для полос речь/тишина в UI (Buffering сюда не доходит: фрейм полный)
+                if let Some(ref callback) = activity_callback {
+                    let activity = match vad_result {
+                        VadResult::Buffering => None,
+                        result => activity_tracker
+                            .lock()
+                            .ok()
+                            .and_then(|mut tracker| tracker.push(result == VadResult::Speech, silence_ms)),
+                    };
+                    if let Some(activity) = activity {
+                        callback(activity);
+                    }
+                }
+
                 match vad_result {
                     VadResult::Speech => {
                         // Speech detected - pass chunk through
@@ -189,6 +319,15 @@ impl AudioCapture for VadCaptureWrapper {
                             if let Some(ref callback) = silence_callback {
                                 callback();
                             }
+                            if let Some(ref callback) = activity_callback {
+                                let activity = activity_tracker
+                                    .lock()
+                                    .ok()
+                                    .map(|tracker| tracker.activity(VadActivityKind::SilenceTimeout, silence_ms));
+                                if let Some(activity) = activity {
+                                    callback(activity);
+                                }
+                            }
 
                             *already_triggered = true;
                         }
@@ -219,6 +358,14 @@ impl AudioCapture for VadCaptureWrapper {
         self.inner.stop_capture().await
     }
 
+    async fn release(&mut self) -> AudioResult<()> {
+        self.inner.release().await
+    }
+
+    async fn prepare(&mut self) -> AudioResult<()> {
+        self.inner.prepare().await
+    }
+
     fn is_capturing(&self) -> bool {
         self.inner.is_capturing()
     }
@@ -354,4 +501,45 @@ mod tests {
         wrapper.stop_capture().await.unwrap();
         assert!(!wrapper.is_capturing());
     }
+
+    #[test]
+    fn test_activity_tracker_emits_speech_edges_with_hysteresis() {
+        let mut tracker = VadActivityTracker::default();
+
+        // Первый речевой фрейм: окно из одного фрейма, вероятность 1.0
+        let start = tracker.push(true, 0).unwrap();
+        assert_eq!(start.kind, VadActivityKind::SpeechStart);
+        assert_eq!(start.offset_ms, 30);
+        assert_eq!(start.probability, 1.0);
+
+        // Одиночные паузы между словами не заканчивают речь
+        for _ in 0..3 {
+            assert_ne!(tracker.push(false, 30).map(|a| a.kind), Some(VadActivityKind::SpeechEnd));
+        }
+
+        // Речь заканчивается, когда в окне 300ms не осталось речевых фреймов
+        let mut ended = None;
+        for i in 0..ACTIVITY_WINDOW_FRAMES {
+            if let Some(activity) = tracker.push(false, (i as u64 + 4) * 30) {
+                if activity.kind == VadActivityKind::SpeechEnd {
+                    ended = Some(activity);
+                    break;
+                }
+            }
+        }
+        let ended = ended.expect("speech end");
+        assert_eq!(ended.probability, 0.0);
+        assert!(ended.silence_ms > 0);
+    }
+
+    #[test]
+    fn test_activity_tracker_reports_level_periodically() {
+        let mut tracker = VadActivityTracker::default();
+        let kinds: Vec<_> = (0..10).filter_map(|_| tracker.push(false, 0)).map(|a| a.kind).collect();
+        assert_eq!(kinds, vec![VadActivityKind::Level, VadActivityKind::Level]);
+
+        let timeout = tracker.activity(VadActivityKind::SilenceTimeout, 3000);
+        assert_eq!(timeout.offset_ms, 300);
+        assert_eq!(timeout.silence_ms, 3000);
+    }
 }
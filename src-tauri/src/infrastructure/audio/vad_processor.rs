@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::time::Duration;
 use webrtc_vad::{Vad, VadMode, SampleRate};
 
@@ -19,6 +20,21 @@ const FALLBACK_ACTIVITY_MAX_ABS_I16: u32 = 220;
 const FALLBACK_ACTIVITY_RMS_I16: u32 = 65;
 const NO_ACTIVITY_TIMEOUT_MS: u64 = 15_000;
 
+// Адаптивный порог активности: первую секунду сессии меряем шумовой фон и ставим порог над ним.
+// В кафе статичный порог срабатывает на фон (запись не останавливается), в тихой комнате пропускает тихую речь.
+const NOISE_CALIBRATION_FRAMES: usize = 1000 / FRAME_SIZE_MS; // ~1 секунда
+// Фон — по самым тихим фреймам калибровки, чтобы речь в первую секунду его не завышала
+const NOISE_FLOOR_PERCENTILE: usize = 20;
+// Порог RMS = фон × 3 (~10 dB над шумом), в разумных пределах
+const NOISE_RMS_MARGIN: u32 = 3;
+const MIN_ACTIVITY_RMS_I16: u32 = 25;
+const MAX_ACTIVITY_RMS_I16: u32 = 1_500;
+// Речь по WebRTC VAD засчитываем, только если RMS фрейма выше фона × 2 (~6 dB): иначе ровный шум
+// кафе/вентилятора VAD принимает за голос, и запись не останавливается по тишине
+const NOISE_SPEECH_MARGIN: u32 = 2;
+// После калибровки фон следует за не-речевыми фреймами: сдвиг на 1/32 разницы за фрейм (~1 секунда)
+const NOISE_TRACKING_SHIFT: u32 = 5;
+
 /// Result of VAD processing
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VadResult {
@@ -32,6 +48,77 @@ pub enum VadResult {
     Buffering,
 }
 
+/// Result of running VAD over a whole buffer (processing chain preview)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VadSummary {
+    /// Полных 30ms фреймов (хвост короче фрейма не анализируется)
+    pub frames: usize,
+    /// Фреймы, засчитанные как речь (WebRTC VAD или fallback по амплитуде — как для авто-стопа)
+    pub speech_frames: usize,
+    pub speech_ratio: f32,
+    pub first_speech_ms: Option<u64>,
+    pub last_speech_ms: Option<u64>,
+    pub silence_timeout_ms: u64,
+    /// Когда запись остановилась бы по тишине (None — не остановилась бы)
+    pub auto_stop_at_ms: Option<u64>,
+    /// Оценка шумового фона (RMS i16) после калибровки; None — запись короче секунды
+    pub noise_floor_rms: Option<u32>,
+}
+
+/// Шумовой фон сессии и порог активности над ним
+#[derive(Debug, Default)]
+struct NoiseFloor {
+    /// RMS фреймов первой секунды (пока идёт калибровка)
+    calibration: Vec<u32>,
+    floor: Option<u32>,
+}
+
+impl NoiseFloor {
+    fn observe(&mut self, frame_rms: u32, is_speech: bool) {
+        match self.floor {
+            None => {
+                self.calibration.push(frame_rms);
+                if self.calibration.len() >= NOISE_CALIBRATION_FRAMES {
+                    self.calibration.sort_unstable();
+                    let index = self.calibration.len() * NOISE_FLOOR_PERCENTILE / 100;
+                    self.floor = Some(self.calibration[index]);
+                    self.calibration.clear();
+                }
+            }
+            // Речь фон не двигает, иначе порог уползёт вслед за голосом
+            Some(_) if is_speech => {}
+            Some(floor) => {
+                let next = if frame_rms > floor {
+                    floor + ((frame_rms - floor) >> NOISE_TRACKING_SHIFT)
+                } else {
+                    floor - ((floor - frame_rms) >> NOISE_TRACKING_SHIFT)
+                };
+                self.floor = Some(next);
+            }
+        }
+    }
+
+    /// Может ли фрейм с таким RMS быть речью; до конца калибровки — любой
+    fn admits_speech(&self, frame_rms: u32) -> bool {
+        self.floor
+            .map_or(true, |floor| frame_rms > floor.saturating_mul(NOISE_SPEECH_MARGIN))
+    }
+
+    /// (порог пика, порог RMS); до конца калибровки — статичные значения
+    fn activity_thresholds(&self) -> (u32, u32) {
+        match self.floor {
+            None => (FALLBACK_ACTIVITY_MAX_ABS_I16, FALLBACK_ACTIVITY_RMS_I16),
+            Some(floor) => {
+                let rms = floor
+                    .saturating_mul(NOISE_RMS_MARGIN)
+                    .clamp(MIN_ACTIVITY_RMS_I16, MAX_ACTIVITY_RMS_I16);
+                // Пик держим в той же пропорции к RMS, что и у статичных порогов
+                (rms * FALLBACK_ACTIVITY_MAX_ABS_I16 / FALLBACK_ACTIVITY_RMS_I16, rms)
+            }
+        }
+    }
+}
+
 /// VAD processor with fixed-size frame buffering
 pub struct VadProcessor {
     /// WebRTC VAD instance
@@ -44,6 +131,8 @@ pub struct VadProcessor {
     saw_activity: bool,
     /// Timeout threshold for stopping
     timeout: Duration,
+    /// Шумовой фон текущей сессии (порог активности подстраивается под него)
+    noise_floor: NoiseFloor,
 }
 
 impl VadProcessor {
@@ -66,6 +155,7 @@ impl VadProcessor {
             silence_duration: Duration::from_millis(0),
             saw_activity: false,
             timeout: Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_SILENCE_TIMEOUT_MS)),
+            noise_floor: NoiseFloor::default(),
         })
     }
 
@@ -115,19 +205,23 @@ impl VadProcessor {
         } else {
             sum_sq / frame.len() as u64
         };
-        let rms_sq_threshold = (FALLBACK_ACTIVITY_RMS_I16 as u64) * (FALLBACK_ACTIVITY_RMS_I16 as u64);
-        let has_activity = max_abs >= FALLBACK_ACTIVITY_MAX_ABS_I16 || mean_sq >= rms_sq_threshold;
+        let (max_abs_threshold, rms_threshold) = self.noise_floor.activity_thresholds();
+        let rms_sq_threshold = (rms_threshold as u64) * (rms_threshold as u64);
+        let has_activity = max_abs >= max_abs_threshold || mean_sq >= rms_sq_threshold;
 
         // Run VAD detection.
         // Защита: нулевые/почти нулевые фреймы считаем тишиной всегда, иначе webrtc_vad иногда даёт ложный Speech.
+        // Фреймы на уровне шумового фона — тоже: webrtc_vad не знает о фоне и путает ровный шум с голосом.
+        let frame_rms = (mean_sq as f64).sqrt() as u32;
         let is_trivial_silence = max_abs <= 12 && mean_sq <= 12;
-        let is_speech = if is_trivial_silence {
+        let is_speech = if is_trivial_silence || !self.noise_floor.admits_speech(frame_rms) {
             false
         } else {
             self.vad
                 .is_voice_segment(&frame)
                 .map_err(|_| SttError::Processing("VAD error".to_string()))?
         };
+        self.noise_floor.observe(frame_rms, is_speech);
 
         if is_speech || has_activity {
             // Speech detected - reset silence counter
@@ -152,11 +246,57 @@ impl VadProcessor {
         }
     }
 
+    /// Run VAD over a whole buffer frame by frame, starting from a clean state
+    pub fn summarize(&mut self, samples: &[i16]) -> SttResult<VadSummary> {
+        self.reset();
+
+        let mut summary = VadSummary {
+            frames: 0,
+            speech_frames: 0,
+            speech_ratio: 0.0,
+            first_speech_ms: None,
+            last_speech_ms: None,
+            silence_timeout_ms: self.timeout.as_millis() as u64,
+            auto_stop_at_ms: None,
+            noise_floor_rms: None,
+        };
+
+        for frame in samples.chunks_exact(FRAME_SIZE_SAMPLES) {
+            let at_ms = (summary.frames * FRAME_SIZE_MS) as u64;
+            summary.frames += 1;
+            match self.process_samples(frame)? {
+                VadResult::Speech => {
+                    summary.speech_frames += 1;
+                    summary.first_speech_ms.get_or_insert(at_ms);
+                    summary.last_speech_ms = Some(at_ms);
+                }
+                VadResult::SilenceTimeout => {
+                    summary.auto_stop_at_ms.get_or_insert(at_ms + FRAME_SIZE_MS as u64);
+                }
+                VadResult::Silence | VadResult::Buffering => {}
+            }
+        }
+
+        if summary.frames > 0 {
+            summary.speech_ratio = summary.speech_frames as f32 / summary.frames as f32;
+        }
+        summary.noise_floor_rms = self.noise_floor_rms();
+        self.reset();
+        Ok(summary)
+    }
+
     /// Reset silence counter (useful when manually restarting recording)
     pub fn reset(&mut self) {
         self.silence_duration = Duration::from_millis(0);
         self.buffer.clear();
         self.saw_activity = false;
+        // Каждая сессия калибруется заново: устройство и обстановка могли смениться
+        self.noise_floor = NoiseFloor::default();
+    }
+
+    /// Шумовой фон текущей сессии (RMS i16); None — калибровка ещё идёт
+    pub fn noise_floor_rms(&self) -> Option<u32> {
+        self.noise_floor.floor
     }
 
     /// Get current silence duration
@@ -323,6 +463,113 @@ mod tests {
         assert_eq!(vad.buffered_samples(), 1); // Один sample остался
     }
 
+    #[test]
+    fn test_summarize_reports_speech_and_auto_stop() {
+        let mut vad = VadProcessor::new(Some(90), None).unwrap();
+
+        let mut samples = vec![0i16; 480]; // 0-30ms тишина
+        samples.extend(vec![300i16; 480 * 2]); // 30-90ms активность
+        samples.extend(vec![0i16; 480 * 4]); // тишина → авто-стоп на третьем фрейме
+        samples.extend(vec![0i16; 100]); // неполный фрейм не считается
+
+        let summary = vad.summarize(&samples).unwrap();
+
+        assert_eq!(summary.frames, 7);
+        assert_eq!(summary.speech_frames, 2);
+        assert_eq!(summary.first_speech_ms, Some(30));
+        assert_eq!(summary.last_speech_ms, Some(60));
+        assert_eq!(summary.silence_timeout_ms, 90);
+        assert_eq!(summary.auto_stop_at_ms, Some(180));
+        assert_eq!(summary.noise_floor_rms, None);
+        assert_eq!(vad.buffered_samples(), 0);
+    }
+
+    #[test]
+    fn test_noise_floor_ignores_speech_during_calibration() {
+        let mut noise = NoiseFloor::default();
+        assert_eq!(noise.activity_thresholds(), (FALLBACK_ACTIVITY_MAX_ABS_I16, FALLBACK_ACTIVITY_RMS_I16));
+
+        // Кафе: фон ~300, пользователь начал говорить сразу
+        for i in 0..NOISE_CALIBRATION_FRAMES {
+            let rms = if i % 3 == 0 { 300 } else { 2_000 };
+            noise.observe(rms, rms > 1_000);
+        }
+        assert_eq!(noise.floor, Some(300));
+        assert_eq!(noise.activity_thresholds(), (3_046, 900));
+
+        // Речь фон не двигает, тишина двигает медленно
+        noise.observe(5_000, true);
+        assert_eq!(noise.floor, Some(300));
+        noise.observe(620, false);
+        assert_eq!(noise.floor, Some(310));
+    }
+
+    #[test]
+    fn test_noise_floor_thresholds_are_clamped() {
+        // Тихая комната: порог ниже статичного, но не до нуля
+        let quiet = NoiseFloor { calibration: Vec::new(), floor: Some(2) };
+        assert_eq!(quiet.activity_thresholds(), (84, MIN_ACTIVITY_RMS_I16));
+
+        let loud = NoiseFloor { calibration: Vec::new(), floor: Some(4_000) };
+        assert_eq!(loud.activity_thresholds().1, MAX_ACTIVITY_RMS_I16);
+    }
+
+    #[test]
+    fn test_noise_floor_calibrates_per_session() {
+        let mut vad = VadProcessor::default().unwrap();
+        let hum = vec![8i16; 480];
+        for _ in 0..NOISE_CALIBRATION_FRAMES {
+            assert_eq!(vad.noise_floor_rms(), None);
+            vad.process_samples(&hum).unwrap();
+        }
+        assert_eq!(vad.noise_floor_rms(), Some(8));
+
+        vad.reset();
+        assert_eq!(vad.noise_floor_rms(), None);
+    }
+
+    #[test]
+    fn test_noisy_floor_frames_are_not_speech_after_calibration() {
+        // Ровный широкополосный шум (вентилятор, кафе) RMS ~580: детерминированный LCG
+        let mut seed: u32 = 0x2545_f491;
+        let mut noise_frame = || -> Vec<i16> {
+            (0..FRAME_SIZE_SAMPLES)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    ((seed >> 16) % 2_001) as i16 - 1_000
+                })
+                .collect()
+        };
+
+        let mut vad = VadProcessor::new(Some(60_000), None).unwrap();
+        for _ in 0..NOISE_CALIBRATION_FRAMES {
+            vad.process_samples(&noise_frame()).unwrap();
+        }
+        let floor = vad.noise_floor_rms().expect("calibrated after a second");
+        assert!((450..=650).contains(&floor), "floor {}", floor);
+
+        // Тот же фон дальше — ни одного фрейма речи, тишина копится к авто-стопу
+        for _ in 0..100 {
+            assert_ne!(vad.process_samples(&noise_frame()).unwrap(), VadResult::Speech);
+        }
+        assert_eq!(vad.silence_duration(), Duration::from_millis(100 * FRAME_SIZE_MS as u64));
+
+        // Громче фона в разы — снова речь/активность
+        let loud: Vec<i16> = noise_frame().iter().map(|s| s.saturating_mul(4)).collect();
+        assert_eq!(vad.process_samples(&loud).unwrap(), VadResult::Speech);
+    }
+
+    #[test]
+    fn test_noise_floor_gates_speech_only_after_calibration() {
+        let calibrating = NoiseFloor::default();
+        assert!(calibrating.admits_speech(0));
+
+        let noisy = NoiseFloor { calibration: Vec::new(), floor: Some(500) };
+        assert!(!noisy.admits_speech(500));
+        assert!(!noisy.admits_speech(1_000));
+        assert!(noisy.admits_speech(1_001));
+    }
+
     #[test]
     fn test_vad_modes() {
         // Тестируем разные режимы VAD
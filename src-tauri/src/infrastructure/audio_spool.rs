@@ -0,0 +1,201 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::Result;
+
+use crate::domain::{AudioSpool, SpooledAudio};
+use crate::infrastructure::storage::restrict_to_owner;
+
+const SPOOL_PREFIX: &str = "unsent_audio_";
+
+/// Дольше сеть не «подвисает» — это уже обрыв, запись остановится раньше. Лимит защищает диск.
+const MAX_SPOOL_SECS: usize = 10 * 60;
+
+#[derive(Default)]
+struct SpoolState {
+    writer: Option<BufWriter<File>>,
+    sample_rate: u32,
+    samples: usize,
+    /// Лимит достигнут — остальное аудио этого разрыва теряется (предупреждаем один раз)
+    overflowed: bool,
+}
+
+/// Временный файл с аудио, которое не удалось отправить провайдеру (write-ahead spool).
+///
+/// Файл создаётся на первой неудачной отправке и удаляется в `take()`, когда разрыв распознан.
+/// Имя содержит pid: файлы, оставшиеся от упавших процессов, удаляются при открытии
+/// (само аудио сессии при краше сохраняет журнал записи, см. `FileSessionJournal`).
+pub struct FileAudioSpool {
+    path: PathBuf,
+    state: Mutex<SpoolState>,
+}
+
+impl FileAudioSpool {
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        // Аудио с микрофона не должно быть видно другим пользователям машины
+        restrict_to_owner(&dir)?;
+        let path = dir.join(format!("{}{}.pcm", SPOOL_PREFIX, std::process::id()));
+        Self::remove_stale(&dir, &path);
+        Ok(Self {
+            path,
+            state: Mutex::new(SpoolState::default()),
+        })
+    }
+
+    fn remove_stale(dir: &Path, own: &Path) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_spool = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(SPOOL_PREFIX));
+            if is_spool && path != own {
+                log::debug!("Removing stale audio spool {:?}", path);
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    fn read_samples(&self) -> Result<Vec<i16>> {
+        let mut bytes = Vec::new();
+        File::open(&self.path)?.read_to_end(&mut bytes)?;
+        Ok(bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SpoolState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl AudioSpool for FileAudioSpool {
+    fn append(&self, samples: &[i16], sample_rate: u32) {
+        let mut state = self.lock();
+        if state.writer.is_some() && state.sample_rate != sample_rate {
+            // Устройство сменилось посреди разрыва: склеить аудио с разной частотой нельзя
+            log::warn!(
+                "Audio spool: sample rate changed {} -> {}, dropping {} spooled samples",
+                state.sample_rate,
+                sample_rate,
+                state.samples
+            );
+            *state = SpoolState::default();
+        }
+        if state.writer.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&self.path);
+            match file {
+                Ok(file) => {
+                    *state = SpoolState {
+                        writer: Some(BufWriter::new(file)),
+                        sample_rate,
+                        samples: 0,
+                        overflowed: false,
+                    };
+                }
+                Err(e) => {
+                    log::warn!("Audio spool: failed to create {:?}: {}", self.path, e);
+                    return;
+                }
+            }
+        }
+
+        if state.samples + samples.len() > MAX_SPOOL_SECS * sample_rate as usize {
+            if !state.overflowed {
+                state.overflowed = true;
+                log::warn!("Audio spool is full ({} min), further unsent audio is dropped", MAX_SPOOL_SECS / 60);
+            }
+            return;
+        }
+
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let written = state.writer.as_mut().map(|writer| writer.write_all(&bytes));
+        match written {
+            Some(Ok(())) => state.samples += samples.len(),
+            Some(Err(e)) => {
+                log::warn!("Audio spool: failed to append audio: {}", e);
+                state.overflowed = true;
+            }
+            None => {}
+        }
+    }
+
+    fn pending_samples(&self) -> usize {
+        self.lock().samples
+    }
+
+    fn take(&self) -> Option<SpooledAudio> {
+        let mut state = self.lock();
+        let mut writer = state.writer.take()?;
+        let sample_rate = state.sample_rate;
+        *state = SpoolState::default();
+
+        let flushed = writer.flush();
+        drop(writer);
+        let samples = flushed.map_err(anyhow::Error::from).and_then(|_| self.read_samples());
+        let _ = std::fs::remove_file(&self.path);
+        match samples {
+            Ok(samples) if !samples.is_empty() => Some(SpooledAudio { samples, sample_rate }),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("Audio spool: failed to read back unsent audio: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("voice-to-text-spool-{}", Uuid::new_v4()))
+    }
+
+    #[test]
+    fn spooled_gap_is_read_back_and_deleted() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let stale = dir.join(format!("{}1.pcm", SPOOL_PREFIX));
+        std::fs::write(&stale, [0u8; 4]).unwrap();
+
+        let spool = FileAudioSpool::open(&dir).unwrap();
+        assert!(!stale.exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+        assert!(spool.take().is_none());
+
+        spool.append(&[1, 2, 3], 16000);
+        spool.append(&[-4], 16000);
+        assert_eq!(spool.pending_samples(), 4);
+        assert!(spool.path.exists());
+
+        let gap = spool.take().unwrap();
+        assert_eq!(gap, SpooledAudio { samples: vec![1, 2, 3, -4], sample_rate: 16000 });
+        assert_eq!(spool.pending_samples(), 0);
+        assert!(!spool.path.exists());
+
+        // Следующий разрыв — новый файл
+        spool.append(&[7], 48000);
+        assert_eq!(spool.take().unwrap().sample_rate, 48000);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -93,12 +93,21 @@ impl AuthStore {
     }
 
     fn config_dir() -> Result<PathBuf> {
+        // Портативный режим: сессия лежит вместе с остальными данными в data/ (см. ConfigStore)
+        if let Some(dir) = crate::infrastructure::storage::portable_data_dir() {
+            std::fs::create_dir_all(dir)?;
+            return Ok(dir.to_path_buf());
+        }
+
         let config_dir = dirs::config_dir()
             .ok_or_else(|| anyhow::anyhow!("Failed to get config directory"))?;
         let app_config_dir = Self::scoped_config_dir(&config_dir);
 
         // Важно: create_dir_all идемпотентен и надёжнее, чем exists() (race).
         std::fs::create_dir_all(&app_config_dir)?;
+        if let Err(e) = crate::infrastructure::storage::restrict_to_owner(&app_config_dir) {
+            log::warn!("Failed to restrict access to {:?}: {}", app_config_dir, e);
+        }
         Self::migrate_legacy_store_once(&config_dir)?;
         Ok(app_config_dir)
     }
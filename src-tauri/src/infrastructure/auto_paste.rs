@@ -2,7 +2,12 @@
 #![allow(unexpected_cfgs)]
 
 use anyhow::{Context, Result};
-use enigo::{Enigo, Keyboard, Settings};
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+
+use crate::domain::{smart_append_text, PasteStrategy};
+
+/// Сколько ждём, пока приложение обработает Cmd/Ctrl+C и обновит clipboard
+const COPY_SETTLE_MS: u64 = 120;
 
 /// Проверяет, есть ли у приложения разрешение Accessibility на macOS
 /// На других платформах всегда возвращает true (разрешение не требуется)
@@ -193,3 +198,98 @@ pub fn paste_text(text: &str) -> Result<()> {
     log::info!("✅ Text typed successfully at cursor position!");
     Ok(())
 }
+
+#[cfg(target_os = "macos")]
+const SHORTCUT_MODIFIER: Key = Key::Meta;
+#[cfg(not(target_os = "macos"))]
+const SHORTCUT_MODIFIER: Key = Key::Control;
+
+fn press_key(enigo: &mut Enigo, key: Key, direction: Direction) -> Result<()> {
+    enigo
+        .key(key, direction)
+        .with_context(|| format!("Failed to send key {:?}", key))
+}
+
+/// Копирует текущее выделение через Cmd/Ctrl+C и возвращает его ("" — выделения нет).
+/// Содержимое clipboard пользователя восстанавливается.
+///
+/// Внимание: в терминалах Linux Ctrl+C — это SIGINT, поэтому "зондирующие" стратегии
+/// (InsertAtCursor, SmartAppend) для терминалов лучше не включать.
+fn copy_selection(enigo: &mut Enigo) -> Result<String> {
+    let mut clipboard = arboard::Clipboard::new().context("Failed to initialize clipboard")?;
+    let saved = clipboard.get_text().ok();
+    clipboard.set_text("").context("Failed to clear clipboard")?;
+
+    press_key(enigo, SHORTCUT_MODIFIER, Direction::Press)?;
+    let copied = press_key(enigo, Key::Unicode('c'), Direction::Click);
+    press_key(enigo, SHORTCUT_MODIFIER, Direction::Release)?;
+    copied?;
+    std::thread::sleep(std::time::Duration::from_millis(COPY_SETTLE_MS));
+
+    let selection = clipboard.get_text().unwrap_or_default();
+    if let Some(saved) = saved {
+        if let Err(e) = clipboard.set_text(saved) {
+            log::warn!("Failed to restore clipboard after selection probe: {}", e);
+        }
+    }
+    Ok(selection)
+}
+
+/// Снимает выделение, оставляя курсор в его конце (если выделения нет — ничего не делает)
+fn collapse_selection_to_end(enigo: &mut Enigo) -> Result<()> {
+    if !copy_selection(enigo)?.is_empty() {
+        log::debug!("Collapsing selection before insert");
+        press_key(enigo, Key::RightArrow, Direction::Click)?;
+    }
+    Ok(())
+}
+
+/// Символ перед курсором: Shift+Left, копируем, возвращаем курсор на место.
+/// None — курсор в начале поля (Shift+Left ничего не выделил) или приложение не отдаёт текст.
+fn char_before_cursor(enigo: &mut Enigo) -> Result<Option<char>> {
+    press_key(enigo, Key::Shift, Direction::Press)?;
+    let selected = press_key(enigo, Key::LeftArrow, Direction::Click);
+    press_key(enigo, Key::Shift, Direction::Release)?;
+    selected?;
+
+    let before = copy_selection(enigo)?;
+    if before.is_empty() {
+        return Ok(None);
+    }
+    press_key(enigo, Key::RightArrow, Direction::Click)?;
+    Ok(before.chars().last())
+}
+
+/// Вставляет текст в активное окно с учётом стратегии (см. PasteStrategy)
+pub fn paste_text_with_strategy(text: &str, strategy: PasteStrategy) -> Result<()> {
+    log::info!("Pasting with strategy {:?}", strategy);
+
+    let text = match strategy {
+        PasteStrategy::ReplaceSelection => text.to_string(),
+        PasteStrategy::InsertAtCursor | PasteStrategy::SmartAppend => {
+            #[cfg(target_os = "macos")]
+            {
+                if !check_accessibility_permission() {
+                    anyhow::bail!("Accessibility permission not granted. Please enable it in System Settings > Privacy & Security > Accessibility");
+                }
+            }
+
+            let mut enigo = Enigo::new(&Settings::default())
+                .context("Failed to initialize Enigo keyboard controller")?;
+            collapse_selection_to_end(&mut enigo)?;
+
+            if strategy == PasteStrategy::SmartAppend {
+                let before = char_before_cursor(&mut enigo)?;
+                log::debug!("Smart append: char before cursor = {:?}", before);
+                smart_append_text(before, text)
+            } else {
+                text.to_string()
+            }
+        }
+    };
+
+    if text.is_empty() {
+        return Ok(());
+    }
+    paste_text(&text)
+}
@@ -0,0 +1,74 @@
+use anyhow::{bail, Context, Result};
+use tauri::{plugin::TauriPlugin, AppHandle, Runtime};
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+
+/// Аргумент, с которым ОС запускает приложение при входе в систему.
+/// По нему отличаем автозапуск от запуска пользователем.
+pub const LAUNCH_MINIMIZED_ARG: &str = "--minimized";
+
+/// Плагин автозапуска (LaunchAgent на macOS, реестр на Windows, .desktop в autostart на Linux)
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec![LAUNCH_MINIMIZED_ARG]))
+}
+
+/// Можно ли настраивать автозапуск.
+///
+/// В портативном режиме нет: копия с флешки не должна прописываться в систему (путь пропадёт
+/// вместе с флешкой), а запись автозапуска общая с установленной копией — трогать её нельзя.
+pub fn is_available() -> bool {
+    crate::infrastructure::storage::portable_data_dir().is_none()
+}
+
+/// Запущено ли приложение автозапуском (с LAUNCH_MINIMIZED_ARG)
+pub fn launched_minimized<I, S>(args: I) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    args.into_iter().any(|arg| arg.as_ref() == LAUNCH_MINIMIZED_ARG)
+}
+
+/// Зарегистрирован ли автозапуск в системе
+pub fn is_launch_at_login_enabled<R: Runtime>(app: &AppHandle<R>) -> Result<bool> {
+    // Запись в системе может принадлежать установленной копии — к портативной она не относится
+    if !is_available() {
+        return Ok(false);
+    }
+    app.autolaunch()
+        .is_enabled()
+        .context("Failed to query launch at login")
+}
+
+/// Включает/выключает автозапуск при входе в систему
+pub fn set_launch_at_login<R: Runtime>(app: &AppHandle<R>, enabled: bool) -> Result<()> {
+    if !is_available() {
+        if enabled {
+            bail!("Launch at login is not available in portable mode");
+        }
+        return Ok(());
+    }
+    let manager = app.autolaunch();
+    // disable() без регистрации падает на части платформ — сверяемся с текущим состоянием
+    if manager.is_enabled().unwrap_or(!enabled) == enabled {
+        return Ok(());
+    }
+    if enabled {
+        manager.enable().context("Failed to enable launch at login")?;
+    } else {
+        manager.disable().context("Failed to disable launch at login")?;
+    }
+    log::info!("Launch at login {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_autostart_launch_by_arg() {
+        assert!(launched_minimized(["/Applications/VoicetextAI.app/Contents/MacOS/app", "--minimized"]));
+        assert!(!launched_minimized(["app"]));
+        assert!(!launched_minimized(["app", "--minimized-later"]));
+    }
+}
@@ -0,0 +1,174 @@
+// Подавляем warnings от старой версии objc crate (см. auto_paste.rs).
+#![allow(unexpected_cfgs)]
+
+//! Системный календарь macOS через EventKit.
+//!
+//! Все вызовы EventKit синхронные, поэтому идут в spawn_blocking. Доступ запрашивается
+//! системным диалогом (нужны NSCalendarsUsageDescription в Info.plist и entitlement календаря).
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use block::ConcreteBlock;
+use cocoa::base::{id, nil};
+use objc::runtime::{BOOL, YES};
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::domain::{CalendarError, CalendarEvent, CalendarProvider, CalendarResult};
+
+#[link(name = "EventKit", kind = "framework")]
+extern "C" {}
+
+/// EKEntityTypeEvent
+const ENTITY_TYPE_EVENT: usize = 0;
+
+/// Сколько ждём ответа пользователя в системном диалоге доступа
+const ACCESS_PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// EKAuthorizationStatus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthorizationStatus {
+    NotDetermined,
+    Restricted,
+    Denied,
+    /// Authorized (до macOS 14) / FullAccess
+    FullAccess,
+    WriteOnly,
+    Unknown(isize),
+}
+
+fn authorization_status() -> AuthorizationStatus {
+    let raw: isize = unsafe { msg_send![class!(EKEventStore), authorizationStatusForEntityType: ENTITY_TYPE_EVENT] };
+    match raw {
+        0 => AuthorizationStatus::NotDetermined,
+        1 => AuthorizationStatus::Restricted,
+        2 => AuthorizationStatus::Denied,
+        3 => AuthorizationStatus::FullAccess,
+        4 => AuthorizationStatus::WriteOnly,
+        other => AuthorizationStatus::Unknown(other),
+    }
+}
+
+unsafe fn nsstring_to_string(value: id) -> Option<String> {
+    if value == nil {
+        return None;
+    }
+    let utf8: *const std::os::raw::c_char = msg_send![value, UTF8String];
+    if utf8.is_null() {
+        return None;
+    }
+    Some(std::ffi::CStr::from_ptr(utf8).to_string_lossy().to_string())
+}
+
+unsafe fn nsdate_to_ms(date: id) -> Option<i64> {
+    if date == nil {
+        return None;
+    }
+    let secs: f64 = msg_send![date, timeIntervalSince1970];
+    Some((secs * 1000.0) as i64)
+}
+
+fn request_access_blocking() -> bool {
+    match authorization_status() {
+        AuthorizationStatus::FullAccess => return true,
+        AuthorizationStatus::NotDetermined => {}
+        status => {
+            log::warn!("Calendar access is not granted: {:?}", status);
+            return false;
+        }
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel::<bool>();
+    unsafe {
+        let store: id = msg_send![class!(EKEventStore), new];
+        let completion = ConcreteBlock::new(move |granted: BOOL, _error: id| {
+            let _ = tx.send(granted == YES);
+        })
+        .copy();
+
+        // macOS 14+: отдельный запрос полного доступа; старый метод там только для write-only
+        let has_full_access_api: BOOL =
+            msg_send![store, respondsToSelector: sel!(requestFullAccessToEventsWithCompletion:)];
+        if has_full_access_api == YES {
+            let _: () = msg_send![store, requestFullAccessToEventsWithCompletion: &*completion];
+        } else {
+            let _: () = msg_send![store, requestAccessToEntityType: ENTITY_TYPE_EVENT completion: &*completion];
+        }
+
+        let granted = rx.recv_timeout(ACCESS_PROMPT_TIMEOUT).unwrap_or(false);
+        let _: () = msg_send![store, release];
+        log::info!("Calendar access request finished: granted={}", granted);
+        granted
+    }
+}
+
+fn events_at_blocking(at_ms: i64) -> CalendarResult<Vec<CalendarEvent>> {
+    let status = authorization_status();
+    if status != AuthorizationStatus::FullAccess {
+        return Err(CalendarError::AccessDenied(format!("{:?}", status)));
+    }
+
+    unsafe {
+        // Вызов идёт не из главного потока: без своего пула autorelease-объекты утекут
+        let pool: id = msg_send![class!(NSAutoreleasePool), new];
+        let store: id = msg_send![class!(EKEventStore), new];
+
+        let at_secs = at_ms as f64 / 1000.0;
+        let start: id = msg_send![class!(NSDate), dateWithTimeIntervalSince1970: at_secs];
+        let end: id = msg_send![class!(NSDate), dateWithTimeIntervalSince1970: at_secs + 1.0];
+        let predicate: id = msg_send![store, predicateForEventsWithStartDate: start endDate: end calendars: nil];
+        let matches: id = msg_send![store, eventsMatchingPredicate: predicate];
+
+        let mut events = Vec::new();
+        let count: usize = if matches == nil { 0 } else { msg_send![matches, count] };
+        for index in 0..count {
+            let event: id = msg_send![matches, objectAtIndex: index];
+            let (Some(start_ms), Some(end_ms)) = (
+                nsdate_to_ms(msg_send![event, startDate]),
+                nsdate_to_ms(msg_send![event, endDate]),
+            ) else {
+                continue;
+            };
+            let all_day: BOOL = msg_send![event, isAllDay];
+            let calendar: id = msg_send![event, calendar];
+            // У EKEvent нет категорий — меткой служит название календаря ("Работа", "Семья")
+            let calendar_title = if calendar == nil { None } else { nsstring_to_string(msg_send![calendar, title]) };
+
+            events.push(CalendarEvent {
+                id: nsstring_to_string(msg_send![event, eventIdentifier]),
+                title: nsstring_to_string(msg_send![event, title]).unwrap_or_default(),
+                start_ms,
+                end_ms,
+                all_day: all_day == YES,
+                location: nsstring_to_string(msg_send![event, location]).filter(|l| !l.is_empty()),
+                categories: calendar_title.into_iter().collect(),
+            });
+        }
+
+        let _: () = msg_send![store, release];
+        let _: () = msg_send![pool, drain];
+        Ok(events)
+    }
+}
+
+/// macOS system calendar (all calendars the user has in Calendar.app)
+pub struct EventKitCalendarProvider;
+
+#[async_trait]
+impl CalendarProvider for EventKitCalendarProvider {
+    async fn events_at(&self, at_ms: i64) -> CalendarResult<Vec<CalendarEvent>> {
+        tokio::task::spawn_blocking(move || events_at_blocking(at_ms))
+            .await
+            .map_err(|e| CalendarError::Unavailable(e.to_string()))?
+    }
+
+    async fn request_access(&self) -> CalendarResult<bool> {
+        tokio::task::spawn_blocking(request_access_blocking)
+            .await
+            .map_err(|e| CalendarError::Unavailable(e.to_string()))
+    }
+
+    fn name(&self) -> &str {
+        "EventKit"
+    }
+}
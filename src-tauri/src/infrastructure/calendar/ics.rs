@@ -0,0 +1,540 @@
+//! Разбор iCalendar (.ics, RFC 5545) — ровно то, что нужно, чтобы найти текущую встречу.
+//!
+//! Поддерживается: VEVENT с DTSTART/DTEND/DURATION, SUMMARY, UID, LOCATION, CATEGORIES,
+//! STATUS:CANCELLED, EXDATE и RRULE с FREQ=DAILY/WEEKLY (INTERVAL, BYDAY, UNTIL, COUNT).
+//! Упрощения: время с TZID считается локальным (без базы часовых поясов), другие частоты
+//! повторения (MONTHLY/YEARLY) дают только первое вхождение, перенесённые вхождения
+//! (RECURRENCE-ID) видны как отдельные события.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Weekday};
+
+use crate::domain::CalendarEvent;
+
+/// Момент времени из .ics в той форме, в которой он записан
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IcsTime {
+    /// `20240101T100000Z`
+    Utc(NaiveDateTime),
+    /// `20240101T100000` и `TZID=...:20240101T100000` — по локальным часам
+    Floating(NaiveDateTime),
+    /// `VALUE=DATE:20240101` — событие на весь день
+    Date(NaiveDate),
+}
+
+fn local_ms(wall: NaiveDateTime) -> Option<i64> {
+    Local.from_local_datetime(&wall).earliest().map(|dt| dt.timestamp_millis())
+}
+
+impl IcsTime {
+    fn parse(params: &str, value: &str) -> Option<Self> {
+        let value = value.trim();
+        let is_date = params
+            .split(';')
+            .any(|p| p.eq_ignore_ascii_case("VALUE=DATE"))
+            || value.len() == 8;
+        if is_date {
+            return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(Self::Date);
+        }
+        match value.strip_suffix('Z') {
+            Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok().map(Self::Utc),
+            None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok().map(Self::Floating),
+        }
+    }
+
+    /// Время "по часам" события (для дат — полночь)
+    fn wall(&self) -> NaiveDateTime {
+        match *self {
+            Self::Utc(wall) | Self::Floating(wall) => wall,
+            Self::Date(date) => date.and_time(chrono::NaiveTime::MIN),
+        }
+    }
+
+    fn to_ms(self) -> Option<i64> {
+        self.wall_to_ms(self.wall())
+    }
+
+    /// Другое время в тех же часах (UTC или локальных), что и это
+    fn wall_to_ms(&self, wall: NaiveDateTime) -> Option<i64> {
+        match self {
+            Self::Utc(_) => Some(wall.and_utc().timestamp_millis()),
+            Self::Floating(_) | Self::Date(_) => local_ms(wall),
+        }
+    }
+
+    fn ms_to_wall(&self, ms: i64) -> Option<NaiveDateTime> {
+        let utc = DateTime::from_timestamp_millis(ms)?;
+        Some(match self {
+            Self::Utc(_) => utc.naive_utc(),
+            Self::Floating(_) | Self::Date(_) => utc.with_timezone(&Local).naive_local(),
+        })
+    }
+}
+
+/// `PT1H30M`, `P1D`, `P2W`; отрицательные длительности не поддерживаем
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let value = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            'W' | 'D' | 'H' | 'M' | 'S' => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (c, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+            _ => return None,
+        }
+    }
+    number.is_empty().then_some(total)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RecurrenceRule {
+    frequency: Frequency,
+    interval: i64,
+    by_day: Vec<Weekday>,
+    until_ms: Option<i64>,
+    count: Option<u32>,
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    // Порядковый номер ("1MO", "-1FR") есть только у MONTHLY/YEARLY — берём сам день
+    let code = code.trim();
+    let day = code.get(code.len().checked_sub(2)?..)?;
+    Some(match day.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+impl RecurrenceRule {
+    /// None — частота, которую мы не разворачиваем
+    fn parse(value: &str) -> Option<Self> {
+        let mut frequency = None;
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            until_ms: None,
+            count: None,
+        };
+        for part in value.split(';') {
+            let Some((key, val)) = part.split_once('=') else {
+                continue;
+            };
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = match val.trim().to_ascii_uppercase().as_str() {
+                        "DAILY" => Some(Frequency::Daily),
+                        "WEEKLY" => Some(Frequency::Weekly),
+                        _ => None,
+                    }
+                }
+                "INTERVAL" => rule.interval = val.trim().parse::<i64>().ok().filter(|n| *n > 0).unwrap_or(1),
+                "BYDAY" => rule.by_day = val.split(',').filter_map(parse_weekday).collect(),
+                "UNTIL" => {
+                    rule.until_ms = IcsTime::parse("", val).and_then(|until| match until {
+                        // UNTIL-дата включает весь день
+                        IcsTime::Date(date) => local_ms(date.succ_opt()?.and_time(chrono::NaiveTime::MIN)).map(|ms| ms - 1),
+                        other => other.to_ms(),
+                    })
+                }
+                "COUNT" => rule.count = val.trim().parse().ok(),
+                _ => {}
+            }
+        }
+        rule.frequency = frequency?;
+        Some(rule)
+    }
+
+    /// Подходит ли дата под правило без учёта COUNT/UNTIL
+    fn matches_pattern(&self, first: NaiveDate, date: NaiveDate) -> bool {
+        if date == first {
+            return true;
+        }
+        if date < first {
+            return false;
+        }
+        match self.frequency {
+            Frequency::Daily => (date - first).num_days() % self.interval == 0,
+            Frequency::Weekly => {
+                let weekday_ok = if self.by_day.is_empty() {
+                    date.weekday() == first.weekday()
+                } else {
+                    self.by_day.contains(&date.weekday())
+                };
+                let week_start = |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+                let weeks = (week_start(date) - week_start(first)).num_days() / 7;
+                weekday_ok && weeks % self.interval == 0
+            }
+        }
+    }
+
+    fn matches(&self, first: NaiveDate, date: NaiveDate) -> bool {
+        if !self.matches_pattern(first, date) {
+            return false;
+        }
+        let Some(count) = self.count else {
+            return true;
+        };
+        // Номер вхождения: перебор ограничен — выходим, как только вхождений стало count
+        let mut seen = 0u32;
+        let mut day = first;
+        while day < date {
+            if self.matches_pattern(first, day) {
+                seen += 1;
+                if seen >= count {
+                    return false;
+                }
+            }
+            day = match day.succ_opt() {
+                Some(next) => next,
+                None => return false,
+            };
+        }
+        true
+    }
+}
+
+/// One VEVENT with its recurrence rule (occurrences are computed on demand)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsEvent {
+    id: Option<String>,
+    title: String,
+    start: IcsTime,
+    duration: Duration,
+    location: Option<String>,
+    categories: Vec<String>,
+    rule: Option<RecurrenceRule>,
+    excluded_ms: Vec<i64>,
+}
+
+impl IcsEvent {
+    fn occurrence(&self, start_ms: i64) -> CalendarEvent {
+        CalendarEvent {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            start_ms,
+            end_ms: start_ms + self.duration.num_milliseconds(),
+            all_day: matches!(self.start, IcsTime::Date(_)),
+            location: self.location.clone(),
+            categories: self.categories.clone(),
+        }
+    }
+
+    /// Вхождение события, которое идёт в момент `at_ms`
+    pub fn occurrence_at(&self, at_ms: i64) -> Option<CalendarEvent> {
+        let first_ms = self.start.to_ms()?;
+        if at_ms < first_ms {
+            return None;
+        }
+        let Some(rule) = &self.rule else {
+            return Some(self.occurrence(first_ms)).filter(|e| e.is_active_at(at_ms));
+        };
+
+        let first = self.start.wall();
+        let at = self.start.ms_to_wall(at_ms)?;
+        // Вхождение могло начаться в один из предыдущих дней (длинные события)
+        for back in 0..=self.duration.num_days() + 1 {
+            let date = at.date() - Duration::days(back);
+            if date < first.date() {
+                break;
+            }
+            if !rule.matches(first.date(), date) {
+                continue;
+            }
+            let Some(start_ms) = self.start.wall_to_ms(date.and_time(first.time())) else {
+                continue;
+            };
+            if rule.until_ms.is_some_and(|until| start_ms > until) || self.excluded_ms.contains(&start_ms) {
+                continue;
+            }
+            let occurrence = self.occurrence(start_ms);
+            if occurrence.is_active_at(at_ms) {
+                return Some(occurrence);
+            }
+        }
+        None
+    }
+}
+
+/// Разворачивает перенесённые строки (RFC 5545 §3.1): продолжение начинается с пробела/таба
+fn unfold_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAM=x:value` → (NAME, PARAM=x, value); двоеточие внутри кавычек параметров не считается
+fn split_property(line: &str) -> Option<(String, &str, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.trim().to_ascii_uppercase(), params, value))
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Список через запятую с учётом экранирования (`\,` — часть значения)
+fn split_text_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            ',' if !escaped => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    items.push(current);
+    items
+        .iter()
+        .map(|item| unescape_text(item).trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+#[derive(Default)]
+struct EventBuilder {
+    id: Option<String>,
+    title: Option<String>,
+    start: Option<IcsTime>,
+    end: Option<IcsTime>,
+    duration: Option<Duration>,
+    location: Option<String>,
+    categories: Vec<String>,
+    rule: Option<RecurrenceRule>,
+    excluded: Vec<IcsTime>,
+    cancelled: bool,
+}
+
+impl EventBuilder {
+    fn property(&mut self, name: &str, params: &str, value: &str) {
+        match name {
+            "UID" => self.id = Some(value.trim().to_string()),
+            "SUMMARY" => self.title = Some(unescape_text(value).trim().to_string()),
+            "DTSTART" => self.start = IcsTime::parse(params, value),
+            "DTEND" => self.end = IcsTime::parse(params, value),
+            "DURATION" => self.duration = parse_duration(value),
+            "LOCATION" => self.location = Some(unescape_text(value).trim().to_string()).filter(|l| !l.is_empty()),
+            "CATEGORIES" => self.categories.extend(split_text_list(value)),
+            "RRULE" => self.rule = RecurrenceRule::parse(value),
+            "EXDATE" => self
+                .excluded
+                .extend(value.split(',').filter_map(|v| IcsTime::parse(params, v))),
+            "STATUS" => self.cancelled = value.trim().eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+
+    fn build(self) -> Option<IcsEvent> {
+        if self.cancelled {
+            return None;
+        }
+        let start = self.start?;
+        let duration = match (self.end.and_then(|end| end.to_ms()), self.duration) {
+            (Some(end_ms), _) => Duration::milliseconds(end_ms - start.to_ms()?),
+            (None, Some(duration)) => duration,
+            // RFC 5545: событие-дата без конца длится день, событие-время — ноль
+            (None, None) if matches!(start, IcsTime::Date(_)) => Duration::days(1),
+            (None, None) => Duration::zero(),
+        };
+        let first = start.wall();
+        let excluded_ms = self
+            .excluded
+            .into_iter()
+            .filter_map(|ex| match ex {
+                // EXDATE-дата исключает вхождение этого дня
+                IcsTime::Date(date) => start.wall_to_ms(date.and_time(first.time())),
+                other => other.to_ms(),
+            })
+            .collect();
+        Some(IcsEvent {
+            id: self.id,
+            title: self.title.unwrap_or_default(),
+            start,
+            duration,
+            location: self.location,
+            categories: self.categories,
+            rule: self.rule,
+            excluded_ms,
+        })
+    }
+}
+
+/// Все события календаря (отменённые и без DTSTART пропускаются)
+pub fn parse_ics(text: &str) -> Vec<IcsEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<EventBuilder> = None;
+    // Вложенные компоненты (VALARM) внутри VEVENT: их SUMMARY/DESCRIPTION не относятся к событию
+    let mut nested = 0usize;
+
+    for line in unfold_lines(text) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        let component = value.trim().to_ascii_uppercase();
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if component == "VEVENT" => current = Some(EventBuilder::default()),
+            ("BEGIN", Some(_)) => nested += 1,
+            ("END", Some(_)) if nested > 0 => nested -= 1,
+            ("END", Some(_)) if component == "VEVENT" => {
+                events.extend(current.take().and_then(EventBuilder::build));
+            }
+            (_, Some(builder)) if nested == 0 => builder.property(&name, params, value),
+            _ => {}
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc_ms(value: &str) -> i64 {
+        IcsTime::parse("", value).and_then(IcsTime::to_ms).unwrap()
+    }
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup-1\r\n\
+SUMMARY:Daily standup\r\n\
+DTSTART:20240108T090000Z\r\n\
+DTEND:20240108T091500Z\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;UNTIL=20240131T235959Z\r\n\
+EXDATE:20240110T090000Z\r\n\
+CATEGORIES:Work,Team\\, Core\r\n\
+BEGIN:VALARM\r\n\
+SUMMARY:Reminder\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:review\r\n\
+SUMMARY:Design review with a very long\r\n \x20title\r\n\
+DTSTART:20240109T140000Z\r\n\
+DURATION:PT1H30M\r\n\
+LOCATION:Room 4\\, 2nd floor\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Cancelled sync\r\n\
+STATUS:CANCELLED\r\n\
+DTSTART:20240109T140000Z\r\n\
+DTEND:20240109T150000Z\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    fn active_titles(events: &[IcsEvent], at: &str) -> Vec<String> {
+        let at_ms = utc_ms(at);
+        events.iter().filter_map(|e| e.occurrence_at(at_ms)).map(|e| e.title).collect()
+    }
+
+    #[test]
+    fn parses_events_and_skips_alarms_and_cancelled() {
+        let events = parse_ics(CALENDAR);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].title, "Daily standup");
+        assert_eq!(events[0].categories, vec!["Work".to_string(), "Team, Core".to_string()]);
+        assert_eq!(events[1].title, "Design review with a very long title");
+        assert_eq!(events[1].location.as_deref(), Some("Room 4, 2nd floor"));
+        assert_eq!(events[1].duration, Duration::minutes(90));
+    }
+
+    #[test]
+    fn expands_weekly_rule_with_exdate_and_until() {
+        let events = parse_ics(CALENDAR);
+        // Пятница 12 января — вхождение
+        assert_eq!(active_titles(&events, "20240112T090500Z"), vec!["Daily standup"]);
+        let occurrence = events[0].occurrence_at(utc_ms("20240112T090500Z")).unwrap();
+        assert_eq!(occurrence.start_ms, utc_ms("20240112T090000Z"));
+        assert_eq!(occurrence.end_ms, utc_ms("20240112T091500Z"));
+        // Среда 10 января исключена EXDATE, вторник не по правилу, после конца встречи — нет
+        assert!(active_titles(&events, "20240110T090500Z").is_empty());
+        assert!(active_titles(&events, "20240116T090500Z").is_empty());
+        assert!(active_titles(&events, "20240112T092000Z").is_empty());
+        // После UNTIL
+        assert!(active_titles(&events, "20240202T090500Z").is_empty());
+        // Разовое событие
+        assert_eq!(active_titles(&events, "20240109T152000Z"), vec!["Design review with a very long title"]);
+    }
+
+    #[test]
+    fn daily_rule_respects_interval_and_count() {
+        let text = "BEGIN:VEVENT\nSUMMARY:Sync\nDTSTART:20240101T100000Z\nDTEND:20240101T103000Z\n\
+RRULE:FREQ=DAILY;INTERVAL=2;COUNT=3\nEND:VEVENT\n";
+        let events = parse_ics(text);
+        assert_eq!(active_titles(&events, "20240103T101000Z"), vec!["Sync"]);
+        assert!(active_titles(&events, "20240102T101000Z").is_empty());
+        // Третье вхождение — 5 января, четвёртого (7 января) уже нет
+        assert_eq!(active_titles(&events, "20240105T101000Z"), vec!["Sync"]);
+        assert!(active_titles(&events, "20240107T101000Z").is_empty());
+    }
+
+    #[test]
+    fn parses_dates_and_durations() {
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P1W"), Some(Duration::weeks(1)));
+        assert_eq!(parse_duration("P1DT2H"), Some(Duration::hours(26)));
+        assert_eq!(parse_duration("-PT5M"), None);
+        assert_eq!(parse_duration("PT5"), None);
+
+        let all_day = IcsTime::parse("VALUE=DATE", "20240105").unwrap();
+        assert_eq!(all_day, IcsTime::Date(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap()));
+        assert!(matches!(IcsTime::parse("TZID=Europe/Berlin", "20240105T100000"), Some(IcsTime::Floating(_))));
+        assert_eq!(parse_weekday("-1FR"), Some(Weekday::Fri));
+    }
+}
@@ -0,0 +1,109 @@
+//! Источники событий календаря для названий сессий: системный календарь (EventKit) и подписка .ics
+
+mod ics;
+#[cfg(target_os = "macos")]
+mod eventkit;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::domain::{CalendarConfig, CalendarError, CalendarEvent, CalendarProvider, CalendarResult, CalendarSource};
+
+pub use ics::{parse_ics, IcsEvent};
+
+/// Календарь по ссылке перекачиваем не чаще этого (запись может стартовать много раз подряд)
+const ICS_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+const ICS_FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// Calendar subscription by URL (.ics, including webcal:// links)
+pub struct IcsCalendarProvider {
+    url: String,
+    cache: Mutex<Option<(Instant, Arc<Vec<IcsEvent>>)>>,
+}
+
+impl IcsCalendarProvider {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// webcal:// — та же ссылка по https
+    fn http_url(&self) -> String {
+        match self.url.strip_prefix("webcal://") {
+            Some(rest) => format!("https://{}", rest),
+            None => self.url.clone(),
+        }
+    }
+
+    async fn events(&self) -> CalendarResult<Arc<Vec<IcsEvent>>> {
+        let mut cache = self.cache.lock().await;
+        if let Some((fetched_at, events)) = cache.as_ref() {
+            if fetched_at.elapsed() < ICS_CACHE_TTL {
+                return Ok(events.clone());
+            }
+        }
+
+        let response = reqwest::Client::new()
+            .get(self.http_url())
+            .timeout(Duration::from_secs(ICS_FETCH_TIMEOUT_SECS))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| CalendarError::Unavailable(e.to_string()))?;
+        let text = response
+            .text()
+            .await
+            .map_err(|e| CalendarError::Unavailable(e.to_string()))?;
+        if !text.contains("BEGIN:VCALENDAR") {
+            return Err(CalendarError::Format("response is not an iCalendar file".to_string()));
+        }
+
+        let events = Arc::new(parse_ics(&text));
+        log::debug!("ICS calendar fetched: {} events", events.len());
+        *cache = Some((Instant::now(), events.clone()));
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl CalendarProvider for IcsCalendarProvider {
+    async fn events_at(&self, at_ms: i64) -> CalendarResult<Vec<CalendarEvent>> {
+        let events = self.events().await?;
+        Ok(events.iter().filter_map(|e| e.occurrence_at(at_ms)).collect())
+    }
+
+    fn name(&self) -> &str {
+        "ICS"
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_calendar_provider() -> Option<Arc<dyn CalendarProvider>> {
+    Some(Arc::new(eventkit::EventKitCalendarProvider))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn system_calendar_provider() -> Option<Arc<dyn CalendarProvider>> {
+    log::warn!("System calendar is only available on macOS - use an ICS URL instead");
+    None
+}
+
+/// Создаёт источник событий по настройкам (None — источник не настроен/недоступен на этой ОС)
+pub fn create_calendar_provider(config: &CalendarConfig) -> Option<Arc<dyn CalendarProvider>> {
+    match config.source {
+        CalendarSource::System => system_calendar_provider(),
+        CalendarSource::IcsUrl => match config.ics_url.as_deref() {
+            Some(url) => Some(Arc::new(IcsCalendarProvider::new(url))),
+            None => {
+                log::warn!("Calendar source is ics_url, but ics_url is not set");
+                None
+            }
+        },
+    }
+}
@@ -18,6 +18,21 @@ pub fn copy_to_clipboard(text: &str) -> Result<()> {
     Ok(())
 }
 
+/// Записывает в clipboard HTML и plain text одновременно: rich-редакторы берут HTML,
+/// остальные приложения и менеджеры истории clipboard — обычный текст
+pub fn copy_rich_to_clipboard(text: &str, html: &str) -> Result<()> {
+    log::info!("📋 Копирую текст в clipboard как HTML + plain text ({} символов)", text.len());
+
+    let mut clipboard = Clipboard::new()
+        .context("Не удалось инициализировать clipboard")?;
+
+    clipboard.set_html(html, Some(text))
+        .context("Не удалось записать HTML в clipboard")?;
+
+    log::info!("✅ Текст успешно скопирован в clipboard (HTML + plain text)");
+    Ok(())
+}
+
 /// Читает текст из системного clipboard (опциональная функция)
 #[allow(dead_code)]
 pub fn read_from_clipboard() -> Result<String> {
@@ -0,0 +1,390 @@
+//! Companion mode: трансляция распознавания на второе устройство в локальной сети.
+//!
+//! Один TCP порт обслуживает и страницу просмотра (GET /?token=...), и WebSocket (/ws?token=...),
+//! по которому клиентам уходят partial/final фразы. Сервер анонсируется через mDNS
+//! (`_voicetext-companion._tcp`), но токен в TXT-записи не публикуется: подключиться можно
+//! только по ссылке из приложения, иначе любой в сети читал бы диктовку.
+
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Порт по умолчанию (если занят — можно передать свой или 0 для случайного)
+pub const DEFAULT_COMPANION_PORT: u16 = 47821;
+
+pub const COMPANION_SERVICE_TYPE: &str = "_voicetext-companion._tcp.local.";
+
+/// Сколько сообщений может отстать медленный клиент, прежде чем пропустит часть partial
+const COMPANION_CHANNEL_CAPACITY: usize = 256;
+
+/// Сколько ждём заголовки HTTP запроса, прежде чем решить, что это за клиент
+const REQUEST_HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+const VIEWER_HTML: &str = include_str!("companion_viewer.html");
+
+/// Событие для companion-клиентов (JSON с полем `type`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CompanionEvent {
+    Partial {
+        session_id: u64,
+        text: String,
+        is_segment_final: bool,
+    },
+    Final {
+        session_id: u64,
+        text: String,
+    },
+}
+
+/// Состояние запущенного сервера для UI (ссылку показываем пользователю/QR-кодом)
+#[derive(Debug, Clone, Serialize)]
+pub struct CompanionServerInfo {
+    pub port: u16,
+    /// Ссылка на страницу просмотра; None — не удалось определить адрес в локальной сети
+    pub viewer_url: Option<String>,
+    pub service_name: String,
+    /// false — mDNS недоступен, подключение только по ссылке
+    pub mdns_announced: bool,
+    pub clients: usize,
+}
+
+struct RunningServer {
+    tx: broadcast::Sender<String>,
+    accept_task: JoinHandle<()>,
+    mdns: Option<(ServiceDaemon, String)>,
+    info: CompanionServerInfo,
+}
+
+impl RunningServer {
+    fn info(&self) -> CompanionServerInfo {
+        CompanionServerInfo {
+            // сам RunningServer подписчиком не является — все receiver'ы это клиенты
+            clients: self.tx.receiver_count(),
+            ..self.info.clone()
+        }
+    }
+
+    fn shutdown(self) {
+        self.accept_task.abort();
+        if let Some((daemon, fullname)) = self.mdns {
+            if let Err(e) = daemon.unregister(&fullname) {
+                log::debug!("Failed to unregister companion mDNS service: {}", e);
+            }
+            let _ = daemon.shutdown();
+        }
+        // tx дропается здесь: клиентские задачи получают Closed и закрывают соединения
+    }
+}
+
+/// Точка публикации событий; сервер поднимается/останавливается командами
+#[derive(Default)]
+pub struct CompanionHub {
+    server: Mutex<Option<RunningServer>>,
+}
+
+impl CompanionHub {
+    /// Запускает сервер; если уже запущен — возвращает текущее состояние
+    pub async fn start(&self, port: Option<u16>) -> Result<CompanionServerInfo> {
+        if let Some(info) = self.info() {
+            return Ok(info);
+        }
+
+        let port = port.unwrap_or(DEFAULT_COMPANION_PORT);
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .await
+            .with_context(|| format!("Failed to bind companion server on port {}", port))?;
+        let port = listener.local_addr()?.port();
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let (tx, _) = broadcast::channel(COMPANION_CHANNEL_CAPACITY);
+
+        let accept_task = tokio::spawn(accept_loop(listener, tx.clone(), token.clone()));
+
+        let service_name = format!("VoicetextAI on {}", host_label());
+        let mdns = match announce_mdns(&service_name, port) {
+            Ok(mdns) => Some(mdns),
+            Err(e) => {
+                log::warn!("Companion mDNS announcement failed (link still works): {}", e);
+                None
+            }
+        };
+
+        let info = CompanionServerInfo {
+            port,
+            viewer_url: local_lan_ip().map(|ip| viewer_url(ip, port, &token)),
+            service_name,
+            mdns_announced: mdns.is_some(),
+            clients: 0,
+        };
+        log::info!(
+            "Companion server listening on port {} (mDNS: {})",
+            port,
+            info.mdns_announced
+        );
+
+        let mut server = self.server.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(existing) = server.as_ref() {
+            // Параллельный start успел раньше — наш экземпляр не нужен
+            let info = existing.info();
+            drop(server);
+            RunningServer { tx, accept_task, mdns, info: info.clone() }.shutdown();
+            return Ok(info);
+        }
+        *server = Some(RunningServer { tx, accept_task, mdns, info: info.clone() });
+        Ok(info)
+    }
+
+    /// Останавливает сервер и отключает клиентов. Возвращает false, если сервер не был запущен
+    pub fn stop(&self) -> bool {
+        let server = self.server.lock().unwrap_or_else(|e| e.into_inner()).take();
+        match server {
+            Some(server) => {
+                server.shutdown();
+                log::info!("Companion server stopped");
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn info(&self) -> Option<CompanionServerInfo> {
+        self.server
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(RunningServer::info)
+    }
+
+    /// Рассылает событие подключённым клиентам (без сервера/клиентов — ничего не делает)
+    pub fn publish(&self, event: &CompanionEvent) {
+        let server = self.server.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(server) = server.as_ref() else {
+            return;
+        };
+        if server.tx.receiver_count() == 0 {
+            return;
+        }
+        match serde_json::to_string(event) {
+            Ok(json) => {
+                let _ = server.tx.send(json);
+            }
+            Err(e) => log::warn!("Failed to serialize companion event: {}", e),
+        }
+    }
+}
+
+async fn accept_loop(listener: TcpListener, tx: broadcast::Sender<String>, token: String) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::warn!("Companion server accept error: {}", e);
+                continue;
+            }
+        };
+
+        let rx = tx.subscribe();
+        let token = token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, peer, rx, token).await {
+                log::debug!("Companion connection from {} ended with error: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    peer: SocketAddr,
+    rx: broadcast::Receiver<String>,
+    token: String,
+) -> Result<()> {
+    let head = tokio::time::timeout(REQUEST_HEAD_TIMEOUT, peek_request_head(&stream))
+        .await
+        .context("Timed out waiting for request")??;
+
+    if is_websocket_upgrade(&head) {
+        log::info!("Companion client connected: {}", peer);
+        let result = serve_websocket(stream, rx, token).await;
+        log::info!("Companion client disconnected: {}", peer);
+        return result;
+    }
+
+    drop(rx);
+    let authorized = request_path(&head)
+        .and_then(query_token)
+        .is_some_and(|t| t == token);
+    serve_viewer_page(stream, authorized).await
+}
+
+/// Читает заголовки запроса без потребления (дальше их разберёт tungstenite)
+async fn peek_request_head(stream: &TcpStream) -> Result<String> {
+    let mut buf = vec![0u8; 4096];
+    loop {
+        let n = stream.peek(&mut buf).await?;
+        if n == 0 {
+            anyhow::bail!("Connection closed before request");
+        }
+        let head = String::from_utf8_lossy(&buf[..n]);
+        if head.contains("\r\n\r\n") || n == buf.len() {
+            return Ok(head.into_owned());
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+async fn serve_websocket(stream: TcpStream, mut rx: broadcast::Receiver<String>, token: String) -> Result<()> {
+    let callback = move |req: &Request, resp: Response| -> Result<Response, ErrorResponse> {
+        let path = req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("");
+        if query_token(path) == Some(token.as_str()) {
+            Ok(resp)
+        } else {
+            let mut forbidden = http::Response::new(Some("Invalid companion token".to_string()));
+            *forbidden.status_mut() = http::StatusCode::FORBIDDEN;
+            Err(forbidden)
+        }
+    };
+    let ws = tokio_tungstenite::accept_hdr_async(stream, callback)
+        .await
+        .context("Companion WebSocket handshake failed")?;
+    let (mut sink, mut source) = ws.split();
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => match event {
+                Ok(json) => sink.send(Message::Text(json)).await?,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::debug!("Companion client lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    let _ = sink.send(Message::Close(None)).await;
+                    return Ok(());
+                }
+            },
+            incoming = source.next() => match incoming {
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Err(e)) => return Err(e.into()),
+                // Клиент только смотрит; ping/pong tungstenite обрабатывает сам
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn serve_viewer_page(mut stream: TcpStream, authorized: bool) -> Result<()> {
+    let (status, content_type, body) = if authorized {
+        ("200 OK", "text/html; charset=utf-8", VIEWER_HTML)
+    } else {
+        ("403 Forbidden", "text/plain; charset=utf-8", "Open the link shown in VoicetextAI")
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn is_websocket_upgrade(head: &str) -> bool {
+    head.lines().skip(1).any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("upgrade") && value.trim().eq_ignore_ascii_case("websocket")
+        })
+    })
+}
+
+/// "GET /ws?token=abc HTTP/1.1" → "/ws?token=abc"
+fn request_path(head: &str) -> Option<&str> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    let _method = parts.next()?;
+    parts.next()
+}
+
+fn query_token(path: &str) -> Option<&str> {
+    let (_, query) = path.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+}
+
+fn viewer_url(ip: IpAddr, port: u16, token: &str) -> String {
+    format!("http://{}/?token={}", SocketAddr::new(ip, port), token)
+}
+
+/// Адрес этой машины в локальной сети (UDP connect ничего не отправляет — только выбирает интерфейс)
+fn local_lan_ip() -> Option<IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.168.0.1:9").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified() && !ip.is_loopback()).then_some(ip)
+}
+
+fn host_label() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "this computer".to_string())
+}
+
+fn announce_mdns(service_name: &str, port: u16) -> Result<(ServiceDaemon, String)> {
+    let daemon = ServiceDaemon::new().context("Failed to start mDNS daemon")?;
+    let host = format!("voicetext-{}.local.", port);
+    let properties = [("path", "/"), ("version", env!("CARGO_PKG_VERSION"))];
+    let service = ServiceInfo::new(COMPANION_SERVICE_TYPE, service_name, &host, "", port, &properties[..])
+        .context("Invalid mDNS service info")?
+        .enable_addr_auto();
+    let fullname = service.get_fullname().to_string();
+    daemon.register(service).context("Failed to register mDNS service")?;
+    Ok((daemon, fullname))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_request_head() {
+        let head = "GET /ws?v=1&token=abc123 HTTP/1.1\r\nHost: 192.168.1.5:47821\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n";
+        assert!(is_websocket_upgrade(head));
+        assert_eq!(request_path(head), Some("/ws?v=1&token=abc123"));
+        assert_eq!(request_path(head).and_then(query_token), Some("abc123"));
+
+        let page = "GET /?token=abc123 HTTP/1.1\r\nHost: x\r\n\r\n";
+        assert!(!is_websocket_upgrade(page));
+        assert_eq!(query_token("/"), None);
+
+        assert_eq!(
+            viewer_url("192.168.1.5".parse().unwrap(), 47821, "abc"),
+            "http://192.168.1.5:47821/?token=abc"
+        );
+    }
+
+    #[test]
+    fn companion_event_json() {
+        let json = serde_json::to_value(CompanionEvent::Final {
+            session_id: 3,
+            text: "привет".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json["type"], "final");
+        assert_eq!(json["text"], "привет");
+        assert_eq!(json["session_id"], 3);
+    }
+}
@@ -1,7 +1,12 @@
 use std::path::{Path, PathBuf};
 use anyhow::Result;
 
-use crate::domain::{SttConfig, AppConfig, UiPreferences};
+use crate::domain::{
+    AccuracyTestResult, HistoryFullText, HistoryItem, MeetingTranscript, PasteAuditEntry, SessionShare, SessionStats, SttConfig, AppConfig, StoragePaths, TeamGlossary,
+    TelemetryEvent, TranscriptionJob, UiPreferences, UpdatePreferences,
+};
+use crate::infrastructure::models::{get_models_dir, models_dir_is_custom};
+use crate::infrastructure::storage::{portable_data_dir, restrict_to_owner};
 
 /// Маркер "приложение только что обновилось".
 ///
@@ -138,17 +143,66 @@ impl ConfigStore {
             }
         }
 
+        // Портативный режим: data/ рядом с исполняемым файлом. Права не трогаем — папку выбрал
+        // пользователь, а на флешке с FAT их и нет.
+        if let Some(dir) = portable_data_dir() {
+            std::fs::create_dir_all(dir)?;
+            return Ok(dir.to_path_buf());
+        }
+
         let config_dir = dirs::config_dir()
             .ok_or_else(|| anyhow::anyhow!("Failed to get config directory"))?;
         let app_config_dir = Self::scoped_config_dir(&config_dir);
 
         // Важно: create_dir_all идемпотентен и надёжнее, чем exists() (race).
         std::fs::create_dir_all(&app_config_dir)?;
+        // Папка, созданная старыми версиями с правами по умолчанию, закрывается здесь же
+        if let Err(e) = restrict_to_owner(&app_config_dir) {
+            log::warn!("Failed to restrict access to {:?}: {}", app_config_dir, e);
+        }
         Self::migrate_legacy_settings_once(&config_dir)?;
 
         Ok(app_config_dir)
     }
 
+    /// Где лежат данные пользователя (для get_storage_paths)
+    pub fn storage_paths() -> Result<StoragePaths> {
+        let display = |path: PathBuf| path.to_string_lossy().to_string();
+        Ok(StoragePaths {
+            config_dir: display(Self::config_dir()?),
+            history_file: display(Self::history_path()?),
+            history_texts_dir: display(Self::history_texts_dir()?),
+            session_audio_dir: display(Self::recovery_dir()?),
+            audio_spool_dir: display(Self::audio_spool_dir()?),
+            models_dir: display(get_models_dir()?),
+            custom_models_dir: models_dir_is_custom(),
+            portable: portable_data_dir().is_some(),
+            meetings_dir: display(Self::meetings_dir()?),
+            logs_dir: display(Self::logs_dir()?),
+        })
+    }
+
+    /// Директория журнала незавершённой записи (crash recovery)
+    pub fn recovery_dir() -> Result<PathBuf> {
+        let dir = Self::config_dir()?.join("recovery");
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// Директория аудио, не отправленного провайдеру при подвисании сети.
+    ///
+    /// Не temp: на Linux /tmp общий для всех пользователей машины.
+    pub fn audio_spool_dir() -> Result<PathBuf> {
+        let dir = Self::config_dir()?.join("spool");
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// Файл словаря исправлений пользователя
+    pub fn corrections_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("corrections.json"))
+    }
+
     /// Получить путь к файлу конфигурации STT
     fn config_path() -> Result<PathBuf> {
         Ok(Self::config_dir()?.join("stt_config.json"))
@@ -288,6 +342,11 @@ impl ConfigStore {
         Ok(config)
     }
 
+    /// Директория логов приложения
+    pub fn logs_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("logs"))
+    }
+
     /// Получить путь к файлу UI-настроек
     fn ui_preferences_path() -> Result<PathBuf> {
         Ok(Self::config_dir()?.join("ui_preferences.json"))
@@ -389,6 +448,420 @@ impl ConfigStore {
         Ok(prefs)
     }
 
+    /// Директория экспортированных встреч
+    pub fn meetings_dir() -> Result<PathBuf> {
+        let dir = Self::config_dir()?.join("meetings");
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// Сохранить транскрипт встречи отдельным JSON-файлом; возвращает путь к файлу
+    pub async fn save_meeting_transcript(transcript: &MeetingTranscript) -> Result<PathBuf> {
+        let started = chrono::DateTime::from_timestamp_millis(transcript.started_at)
+            .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d_%H-%M-%S").to_string())
+            .unwrap_or_else(|| transcript.started_at.to_string());
+        let path = Self::meetings_dir()?.join(format!("meeting-{}.json", started));
+        let json = serde_json::to_string_pretty(transcript)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::info!("Meeting transcript saved to {:?} ({} segments)", path, transcript.segments.len());
+        Ok(path)
+    }
+
+    /// Получить путь к файлу статистики сессий записи
+    fn session_stats_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("session_stats.json"))
+    }
+
+    /// Сохранить статистику сессий (для аналитики диктовки)
+    pub async fn save_session_stats(stats: &[SessionStats]) -> Result<()> {
+        let path = Self::session_stats_path()?;
+        let json = serde_json::to_string(stats)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Session stats saved to disk ({} sessions)", stats.len());
+        Ok(())
+    }
+
+    /// Загрузить статистику сессий
+    pub async fn load_session_stats() -> Result<Vec<SessionStats>> {
+        let path = Self::session_stats_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let stats: Vec<SessionStats> = serde_json::from_str(&json)?;
+        log::info!("Session stats loaded from disk ({} sessions)", stats.len());
+        Ok(stats)
+    }
+
+    /// Получить путь к истории нажатий хоткея записи (warm pool)
+    fn hotkey_activity_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("hotkey_activity.json"))
+    }
+
+    /// Сохранить моменты нажатий хоткея записи (unix ms)
+    pub async fn save_hotkey_activity(activity: &[i64]) -> Result<()> {
+        let path = Self::hotkey_activity_path()?;
+        let json = serde_json::to_string(activity)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Hotkey activity saved to disk ({} entries)", activity.len());
+        Ok(())
+    }
+
+    /// Загрузить моменты нажатий хоткея записи
+    pub async fn load_hotkey_activity() -> Result<Vec<i64>> {
+        let path = Self::hotkey_activity_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let activity: Vec<i64> = serde_json::from_str(&json)?;
+        Ok(activity)
+    }
+
+    /// Получить путь к журналу автовставок
+    fn paste_audit_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("paste_audit.json"))
+    }
+
+    /// Сохранить журнал автовставок (auto-paste / typing)
+    pub async fn save_paste_audit(entries: &[PasteAuditEntry]) -> Result<()> {
+        let path = Self::paste_audit_path()?;
+        let json = serde_json::to_string(entries)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Paste audit log saved to disk ({} entries)", entries.len());
+        Ok(())
+    }
+
+    /// Загрузить журнал автовставок
+    pub async fn load_paste_audit() -> Result<Vec<PasteAuditEntry>> {
+        let path = Self::paste_audit_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let entries: Vec<PasteAuditEntry> = serde_json::from_str(&json)?;
+        log::info!("Paste audit log loaded from disk ({} entries)", entries.len());
+        Ok(entries)
+    }
+
+    /// Удалить журнал автовставок
+    pub async fn delete_paste_audit() -> Result<()> {
+        let path = Self::paste_audit_path()?;
+
+        if path.exists() {
+            tokio::fs::remove_file(path).await?;
+            log::info!("Paste audit log deleted");
+        }
+
+        Ok(())
+    }
+
+    /// Получить путь к истории диктовок
+    fn history_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("history.json"))
+    }
+
+    /// Папка с полными текстами записей истории (в history.json у них только превью)
+    fn history_texts_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("history"))
+    }
+
+    fn history_text_path(id: u64) -> Result<PathBuf> {
+        Ok(Self::history_texts_dir()?.join(format!("{}.json", id)))
+    }
+
+    /// Сохранить историю диктовок (с метками и избранным).
+    ///
+    /// Полные тексты записей, которых больше нет в истории (вытеснены лимитом), удаляются.
+    pub async fn save_history(items: &[HistoryItem]) -> Result<()> {
+        let path = Self::history_path()?;
+        let json = serde_json::to_string(items)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("History saved to disk ({} items)", items.len());
+
+        if let Err(e) = Self::prune_history_texts(items).await {
+            log::warn!("Failed to prune history texts: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Сохранить полный текст записи истории вместе с журналом правок
+    pub async fn save_history_text(id: u64, full: &HistoryFullText) -> Result<()> {
+        tokio::fs::create_dir_all(Self::history_texts_dir()?).await?;
+        let path = Self::history_text_path(id)?;
+        let json = serde_json::to_string(full)?;
+        Self::write_file_atomic(&path, &json).await
+    }
+
+    /// Загрузить полный текст записи истории с журналом правок (None — файла нет)
+    pub async fn load_history_text(id: u64) -> Result<Option<HistoryFullText>> {
+        let path = Self::history_text_path(id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    /// Удалить полные тексты записей, которых нет в `items`
+    async fn prune_history_texts(items: &[HistoryItem]) -> Result<()> {
+        let dir = Self::history_texts_dir()?;
+        if !dir.exists() {
+            return Ok(());
+        }
+
+        let keep: std::collections::HashSet<u64> = items.iter().map(|item| item.id).collect();
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let id = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok());
+            if id.is_some_and(|id| !keep.contains(&id)) {
+                tokio::fs::remove_file(&path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Загрузить историю диктовок
+    pub async fn load_history() -> Result<Vec<HistoryItem>> {
+        let path = Self::history_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let items: Vec<HistoryItem> = serde_json::from_str(&json)?;
+        log::info!("History loaded from disk ({} items)", items.len());
+        Ok(items)
+    }
+
+    /// Удалить историю диктовок с диска
+    pub async fn delete_history() -> Result<()> {
+        let path = Self::history_path()?;
+
+        if path.exists() {
+            tokio::fs::remove_file(path).await?;
+            log::info!("History deleted");
+        }
+
+        let texts_dir = Self::history_texts_dir()?;
+        if texts_dir.exists() {
+            tokio::fs::remove_dir_all(texts_dir).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Получить путь к результатам тестов точности
+    fn accuracy_results_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("accuracy_tests.json"))
+    }
+
+    /// Сохранить результаты тестов точности (для графика прогресса)
+    pub async fn save_accuracy_results(results: &[AccuracyTestResult]) -> Result<()> {
+        let path = Self::accuracy_results_path()?;
+        let json = serde_json::to_string(results)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Accuracy test results saved to disk ({} results)", results.len());
+        Ok(())
+    }
+
+    /// Загрузить результаты тестов точности
+    pub async fn load_accuracy_results() -> Result<Vec<AccuracyTestResult>> {
+        let path = Self::accuracy_results_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let results: Vec<AccuracyTestResult> = serde_json::from_str(&json)?;
+        log::info!("Accuracy test results loaded from disk ({} results)", results.len());
+        Ok(results)
+    }
+
+    /// Удалить результаты тестов точности
+    pub async fn delete_accuracy_results() -> Result<()> {
+        let path = Self::accuracy_results_path()?;
+
+        if path.exists() {
+            tokio::fs::remove_file(path).await?;
+            log::info!("Accuracy test results deleted");
+        }
+
+        Ok(())
+    }
+
+    fn session_shares_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("session_shares.json"))
+    }
+
+    /// Сохранить выданные ссылки на сессии (нужны для отзыва)
+    pub async fn save_session_shares(shares: &[SessionShare]) -> Result<()> {
+        let path = Self::session_shares_path()?;
+        let json = serde_json::to_string(shares)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Session shares saved to disk ({} shares)", shares.len());
+        Ok(())
+    }
+
+    /// Загрузить выданные ссылки на сессии
+    pub async fn load_session_shares() -> Result<Vec<SessionShare>> {
+        let path = Self::session_shares_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let shares: Vec<SessionShare> = serde_json::from_str(&json)?;
+        Ok(shares)
+    }
+
+    fn team_glossary_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("team_glossary.json"))
+    }
+
+    /// Сохранить словарь команды (работает офлайн до следующей синхронизации)
+    pub async fn save_team_glossary(glossary: &TeamGlossary) -> Result<()> {
+        let path = Self::team_glossary_path()?;
+        let json = serde_json::to_string(glossary)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Team glossary saved to disk (revision {}, {} terms)", glossary.revision, glossary.terms.len());
+        Ok(())
+    }
+
+    /// Загрузить словарь команды из кэша
+    pub async fn load_team_glossary() -> Result<Option<TeamGlossary>> {
+        let path = Self::team_glossary_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let glossary: TeamGlossary = serde_json::from_str(&json)?;
+        Ok(Some(glossary))
+    }
+
+    /// Удалить кэш словаря команды (пользователь вышел из команды или из аккаунта)
+    pub async fn delete_team_glossary() -> Result<()> {
+        let path = Self::team_glossary_path()?;
+
+        if path.exists() {
+            tokio::fs::remove_file(path).await?;
+            log::info!("Team glossary deleted");
+        }
+
+        Ok(())
+    }
+
+    fn transcription_jobs_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("transcription_jobs.json"))
+    }
+
+    /// Сохранить задачи расшифровки файлов (вместе с готовыми главами — для продолжения после перезапуска)
+    pub async fn save_transcription_jobs(jobs: &[TranscriptionJob]) -> Result<()> {
+        let path = Self::transcription_jobs_path()?;
+        let json = serde_json::to_string(jobs)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Transcription jobs saved to disk ({} jobs)", jobs.len());
+        Ok(())
+    }
+
+    /// Загрузить задачи расшифровки файлов
+    pub async fn load_transcription_jobs() -> Result<Vec<TranscriptionJob>> {
+        let path = Self::transcription_jobs_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let jobs: Vec<TranscriptionJob> = serde_json::from_str(&json)?;
+        log::info!("Transcription jobs loaded from disk ({} jobs)", jobs.len());
+        Ok(jobs)
+    }
+
+    /// Удалить задачи расшифровки файлов
+    pub async fn delete_transcription_jobs() -> Result<()> {
+        let path = Self::transcription_jobs_path()?;
+
+        if path.exists() {
+            tokio::fs::remove_file(path).await?;
+            log::info!("Transcription jobs deleted");
+        }
+
+        Ok(())
+    }
+
+    /// Получить путь к офлайн-очереди телеметрии
+    fn telemetry_queue_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("telemetry_queue.json"))
+    }
+
+    /// Сохранить очередь неотправленных событий телеметрии
+    pub async fn save_telemetry_queue(events: &[TelemetryEvent]) -> Result<()> {
+        let path = Self::telemetry_queue_path()?;
+        let json = serde_json::to_string(events)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Telemetry queue saved to disk ({} events)", events.len());
+        Ok(())
+    }
+
+    /// Загрузить очередь телеметрии
+    pub async fn load_telemetry_queue() -> Result<Vec<TelemetryEvent>> {
+        let path = Self::telemetry_queue_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let events: Vec<TelemetryEvent> = serde_json::from_str(&json)?;
+        Ok(events)
+    }
+
+    /// Удалить очередь телеметрии (при отключении телеметрии)
+    pub async fn delete_telemetry_queue() -> Result<()> {
+        let path = Self::telemetry_queue_path()?;
+
+        if path.exists() {
+            tokio::fs::remove_file(path).await?;
+            log::info!("Telemetry queue deleted");
+        }
+
+        Ok(())
+    }
+
+    /// Получить путь к настройкам обновлений
+    fn update_preferences_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("update_preferences.json"))
+    }
+
+    /// Сохранить настройки обновлений (канал, пропущенная версия, "напомнить позже")
+    pub async fn save_update_preferences(prefs: &UpdatePreferences) -> Result<()> {
+        let path = Self::update_preferences_path()?;
+        let json = serde_json::to_string_pretty(prefs)?;
+        Self::write_file_atomic(&path, &json).await?;
+        log::debug!("Update preferences saved to disk");
+        Ok(())
+    }
+
+    /// Загрузить настройки обновлений
+    pub async fn load_update_preferences() -> Result<UpdatePreferences> {
+        let path = Self::update_preferences_path()?;
+        if !path.exists() {
+            return Ok(UpdatePreferences::default());
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let prefs: UpdatePreferences = serde_json::from_str(&json)?;
+        Ok(prefs)
+    }
+
     /// Удалить сохраненную конфигурацию приложения
     pub async fn delete_app_config() -> Result<()> {
         let path = Self::app_config_path()?;
@@ -514,6 +987,27 @@ mod tests {
         assert!(marker2.is_none());
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn storage_paths_are_inside_the_user_config_dir() {
+        let guard = TestConfigDir::new();
+        let paths = ConfigStore::storage_paths().unwrap();
+        let root = guard.dir.to_string_lossy().to_string();
+
+        assert_eq!(paths.config_dir, root);
+        for path in [
+            &paths.history_file,
+            &paths.history_texts_dir,
+            &paths.session_audio_dir,
+            &paths.audio_spool_dir,
+            &paths.meetings_dir,
+            &paths.logs_dir,
+        ] {
+            assert!(path.starts_with(&root), "{} is outside {}", path, root);
+        }
+        assert!(Path::new(&paths.audio_spool_dir).is_dir());
+    }
+
     #[test]
     fn app_dir_name_matches_build_profile() {
         #[cfg(debug_assertions)]
@@ -0,0 +1,71 @@
+//! Проверка доступности сети: резолвим адрес и открываем TCP-соединение (без HTTP-запроса).
+//!
+//! Сеть считается пропавшей, только если недоступны и наш API, и запасной хост:
+//! упавший API — не повод переключать пользователя на локальное распознавание.
+
+use std::time::Duration;
+
+use tokio::net::{lookup_host, TcpStream};
+
+/// Запасной хост на случай, если недоступен только наш API
+const FALLBACK_PROBE_HOST: (&str, u16) = ("dns.google", 443);
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Хост и порт для проверки из базового URL API (`https://api.voicetext.site` → api.voicetext.site:443)
+pub fn probe_target(api_base_url: &str) -> Option<(String, u16)> {
+    let url = reqwest::Url::parse(api_base_url).ok()?;
+    let host = url.host_str()?.to_string();
+    let port = url.port_or_known_default()?;
+    Some((host, port))
+}
+
+/// Есть ли маршрут хотя бы до одного из хостов (DNS + TCP handshake)
+pub async fn is_network_available(api_base_url: &str) -> bool {
+    if let Some((host, port)) = probe_target(api_base_url) {
+        if probe(&host, port).await {
+            return true;
+        }
+    }
+    probe(FALLBACK_PROBE_HOST.0, FALLBACK_PROBE_HOST.1).await
+}
+
+async fn probe(host: &str, port: u16) -> bool {
+    let addrs = match tokio::time::timeout(PROBE_TIMEOUT, lookup_host((host, port))).await {
+        Ok(Ok(addrs)) => addrs.collect::<Vec<_>>(),
+        Ok(Err(e)) => {
+            log::debug!("Connectivity probe: cannot resolve {}: {}", host, e);
+            return false;
+        }
+        Err(_) => {
+            log::debug!("Connectivity probe: DNS timeout for {}", host);
+            return false;
+        }
+    };
+
+    for addr in addrs {
+        if let Ok(Ok(_)) = tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(addr)).await {
+            return true;
+        }
+    }
+    log::debug!("Connectivity probe: no route to {}:{}", host, port);
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_target_uses_the_api_host_and_scheme_port() {
+        assert_eq!(
+            probe_target("https://api.voicetext.site"),
+            Some(("api.voicetext.site".to_string(), 443))
+        );
+        assert_eq!(
+            probe_target("http://localhost:8080/api"),
+            Some(("localhost".to_string(), 8080))
+        );
+        assert_eq!(probe_target("not a url"), None);
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{CorrectionEntry, CorrectionStore, CorrectionStoreError, CorrectionStoreResult};
+
+/// Формат файла словаря (версия нужна для будущих миграций)
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CorrectionsFile {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    entries: Vec<CorrectionEntry>,
+}
+
+const CORRECTIONS_FILE_VERSION: u32 = 1;
+
+/// Словарь исправлений в JSON-файле (рядом с остальными конфигами)
+pub struct FileCorrectionStore {
+    path: PathBuf,
+}
+
+impl FileCorrectionStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl CorrectionStore for FileCorrectionStore {
+    fn load(&self) -> CorrectionStoreResult<Vec<CorrectionEntry>> {
+        let json = match std::fs::read_to_string(&self.path) {
+            Ok(v) => v,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(CorrectionStoreError::Io(format!("{}: {}", self.path.display(), e))),
+        };
+
+        let file: CorrectionsFile = serde_json::from_str(&json)
+            .map_err(|e| CorrectionStoreError::Format(format!("{}: {}", self.path.display(), e)))?;
+        Ok(file.entries)
+    }
+
+    fn save(&self, entries: &[CorrectionEntry]) -> CorrectionStoreResult<()> {
+        let io_err = |e: std::io::Error| CorrectionStoreError::Io(format!("{}: {}", self.path.display(), e));
+
+        let file = CorrectionsFile {
+            version: CORRECTIONS_FILE_VERSION,
+            entries: entries.to_vec(),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| CorrectionStoreError::Format(e.to_string()))?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        // Пишем во временный файл и переименовываем, чтобы не оставить обрезанный JSON при краше
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(io_err)?;
+        let _ = std::fs::remove_file(&self.path);
+        std::fs::rename(&tmp, &self.path).map_err(io_err)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("voice-to-text-corrections-{}", Uuid::new_v4()))
+            .join("corrections.json")
+    }
+
+    #[test]
+    fn missing_file_is_empty_dictionary() {
+        let store = FileCorrectionStore::new(temp_path());
+        assert!(store.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn saved_entries_are_loaded_back() {
+        let path = temp_path();
+        let store = FileCorrectionStore::new(&path);
+        let entries = vec![
+            CorrectionEntry::new("кубер", "Kubernetes"),
+            CorrectionEntry::new("дип грам", "Deepgram"),
+        ];
+        store.save(&entries).unwrap();
+
+        assert_eq!(FileCorrectionStore::new(&path).load().unwrap(), entries);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn corrupted_file_is_reported() {
+        let path = temp_path();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{not json").unwrap();
+
+        let err = FileCorrectionStore::new(&path).load().unwrap_err();
+        assert!(matches!(err, CorrectionStoreError::Format(_)));
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,5 +1,12 @@
-use crate::domain::{SttConfig, SttError, SttProvider, SttProviderFactory, SttProviderType, SttResult};
-use crate::infrastructure::stt::{AssemblyAIProvider, BackendProvider, DeepgramProvider, WhisperLocalProvider};
+use std::sync::Arc;
+
+use crate::domain::{
+    BatchSttProvider, SttConfig, SttError, SttProvider, SttProviderFactory, SttProviderType, SttResult,
+};
+use crate::infrastructure::stt::{
+    AssemblyAIBatchProvider, AssemblyAIProvider, BackendProvider, DeepgramBatchProvider, DeepgramProvider,
+    SplitChannelProvider, WhisperLocalProvider,
+};
 
 /// Factory for creating STT providers based on configuration
 ///
@@ -22,6 +29,13 @@ impl SttProviderFactory for DefaultSttProviderFactory {
     fn create(&self, config: &SttConfig) -> SttResult<Box<dyn SttProvider>> {
         log::info!("Creating STT provider: {:?}", config.provider);
 
+        // Стерео-интервью: по стриму того же провайдера на каждый канал
+        if config.split_channels {
+            let mut single = config.clone();
+            single.split_channels = false;
+            return Ok(Box::new(SplitChannelProvider::new(self.create(&single)?, self.create(&single)?)));
+        }
+
         match config.provider {
             SttProviderType::WhisperLocal => Ok(Box::new(WhisperLocalProvider::new())),
 
@@ -40,6 +54,15 @@ impl SttProviderFactory for DefaultSttProviderFactory {
             )),
         }
     }
+
+    fn create_batch(&self, config: &SttConfig) -> SttResult<Option<Arc<dyn BatchSttProvider>>> {
+        match config.provider {
+            SttProviderType::Deepgram => Ok(Some(Arc::new(DeepgramBatchProvider::from_config(config)?))),
+            SttProviderType::AssemblyAI => Ok(Some(Arc::new(AssemblyAIBatchProvider::from_config(config)?))),
+            // Backend и локальный Whisper — только стриминг
+            _ => Ok(None),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -91,6 +114,16 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_create_split_channels_wraps_provider() {
+        let factory = DefaultSttProviderFactory::new();
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.split_channels = true;
+        let provider = factory.create(&config).unwrap();
+        assert_eq!(provider.name(), factory.create(&SttConfig::new(SttProviderType::Deepgram)).unwrap().name());
+        assert!(!provider.supports_keep_alive());
+    }
+
     #[test]
     fn test_create_google_cloud_unsupported() {
         let factory = DefaultSttProviderFactory::new();
@@ -106,4 +139,15 @@ mod tests {
         let result = factory.create(&config);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_create_batch_only_for_rest_providers() {
+        let factory = DefaultSttProviderFactory::new();
+        let mut config = SttConfig::new(SttProviderType::Deepgram);
+        config.deepgram_api_key = Some("key".to_string());
+        assert!(factory.create_batch(&config).unwrap().is_some());
+
+        let config = SttConfig::new(SttProviderType::Backend);
+        assert!(factory.create_batch(&config).unwrap().is_none());
+    }
 }
@@ -0,0 +1,127 @@
+//! Звуки обратной связи записи (старт / остановка / ошибка), проигрываемые через rodio.
+//!
+//! Звуки синтезируются на лету — файлов в бандле нет. Проигрывание идёт в отдельном потоке
+//! на системное устройство вывода, мимо захвата звука: в запись и в STT они не попадают.
+
+use std::f32::consts::PI;
+use std::sync::mpsc;
+use std::sync::OnceLock;
+
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, OutputStreamHandle, Sink};
+
+use crate::domain::{FeedbackConfig, FeedbackEvent, FeedbackSink};
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Нарастание/затухание ноты, чтобы не было щелчков
+const FADE_MS: u32 = 6;
+
+/// Громкость ноты до регулировки пользователем
+const NOTE_AMPLITUDE: f32 = 0.4;
+
+/// Native sound feedback; the output device is opened on the first sound
+#[derive(Default)]
+pub struct SoundFeedback {
+    player: OnceLock<mpsc::Sender<(FeedbackEvent, f32)>>,
+}
+
+impl SoundFeedback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FeedbackSink for SoundFeedback {
+    fn name(&self) -> &str {
+        "sound"
+    }
+
+    fn notify(&self, event: FeedbackEvent, config: &FeedbackConfig) {
+        let Some(volume) = config.sound_volume(event) else {
+            return;
+        };
+        let player = self.player.get_or_init(spawn_player);
+        if player.send((event, volume)).is_err() {
+            log::warn!("Feedback sound player is not running, skipping {:?}", event);
+        }
+    }
+}
+
+/// OutputStream не Send, поэтому живёт в своём потоке вместе с очередью звуков
+fn spawn_player() -> mpsc::Sender<(FeedbackEvent, f32)> {
+    let (tx, rx) = mpsc::channel::<(FeedbackEvent, f32)>();
+    let spawned = std::thread::Builder::new()
+        .name("feedback-sound".to_string())
+        .spawn(move || {
+            let mut output: Option<(OutputStream, OutputStreamHandle)> = None;
+            while let Ok((event, volume)) = rx.recv() {
+                if output.is_none() {
+                    output = OutputStream::try_default()
+                        .map_err(|e| log::warn!("Failed to open output device for feedback sounds: {}", e))
+                        .ok();
+                }
+                let Some((_, handle)) = output.as_ref() else {
+                    continue;
+                };
+                match Sink::try_new(handle) {
+                    Ok(sink) => {
+                        sink.set_volume(volume);
+                        sink.append(SamplesBuffer::new(1, SAMPLE_RATE, tone(event)));
+                        sink.detach();
+                    }
+                    Err(e) => {
+                        // Устройство вывода могло смениться — переоткроем на следующем звуке
+                        log::warn!("Failed to play feedback sound {:?}: {}", event, e);
+                        output = None;
+                    }
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        log::error!("Failed to start feedback sound thread: {}", e);
+    }
+    tx
+}
+
+/// Короткая мелодия события: старт — вверх, остановка — вниз, ошибка — два низких сигнала
+fn tone(event: FeedbackEvent) -> Vec<f32> {
+    // (частота Гц, длительность мс); частота 0 — пауза
+    let notes: &[(f32, u32)] = match event {
+        FeedbackEvent::RecordingStarted => &[(660.0, 70), (880.0, 90)],
+        FeedbackEvent::RecordingStopped => &[(880.0, 70), (660.0, 90)],
+        FeedbackEvent::Error => &[(330.0, 110), (0.0, 60), (330.0, 110)],
+    };
+    notes.iter().flat_map(|&(hz, ms)| note(hz, ms)).collect()
+}
+
+fn note(hz: f32, ms: u32) -> Vec<f32> {
+    let len = (SAMPLE_RATE * ms / 1000) as usize;
+    let fade = (SAMPLE_RATE * FADE_MS / 1000) as usize;
+    (0..len)
+        .map(|i| {
+            let envelope = (i.min(len - 1 - i) as f32 / fade as f32).min(1.0);
+            let phase = 2.0 * PI * hz * i as f32 / SAMPLE_RATE as f32;
+            NOTE_AMPLITUDE * envelope * phase.sin()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tones_are_short_and_click_free() {
+        for event in [FeedbackEvent::RecordingStarted, FeedbackEvent::RecordingStopped, FeedbackEvent::Error] {
+            let samples = tone(event);
+            assert!(!samples.is_empty());
+            assert!(samples.len() < SAMPLE_RATE as usize / 2, "{:?} is too long", event);
+            assert!(samples.iter().all(|s| s.abs() <= NOTE_AMPLITUDE));
+            // Ноты начинаются и заканчиваются в тишине
+            assert_eq!(samples[0], 0.0);
+            assert!(samples[samples.len() - 1].abs() < 0.01);
+        }
+        assert_ne!(tone(FeedbackEvent::RecordingStarted), tone(FeedbackEvent::RecordingStopped));
+    }
+}
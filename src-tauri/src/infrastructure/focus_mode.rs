@@ -0,0 +1,86 @@
+//! Режимы фокусировки / "не беспокоить" ОС.
+//!
+//! Проверка best-effort: нет доступа или API — считаем, что режим выключен,
+//! и запись ведёт себя как обычно.
+
+use crate::domain::FocusMode;
+
+/// Включённый сейчас режим фокусировки; None — выключен или определить не удалось
+pub fn active_focus_mode() -> Option<FocusMode> {
+    let mode = detect();
+    log::debug!("OS focus mode: {:?}", mode);
+    mode
+}
+
+/// macOS пишет включённые вручную Focus-режимы в Assertions.json; расписания туда не попадают
+#[cfg(target_os = "macos")]
+fn detect() -> Option<FocusMode> {
+    let path = dirs::home_dir()?.join("Library/DoNotDisturb/DB/Assertions.json");
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| log::debug!("Focus assertions are not readable ({}): {}", path.display(), e))
+        .ok()?;
+    has_focus_assertions(&json).then_some(FocusMode::MacosFocus)
+}
+
+#[cfg(windows)]
+fn detect() -> Option<FocusMode> {
+    use windows_sys::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME,
+        QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    let mut state = 0;
+    let hr = unsafe { SHQueryUserNotificationState(&mut state) };
+    if hr != 0 {
+        log::debug!("SHQueryUserNotificationState failed: {:#x}", hr);
+        return None;
+    }
+    matches!(
+        state,
+        QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE | QUNS_QUIET_TIME
+    )
+    .then_some(FocusMode::WindowsFocusAssist)
+}
+
+/// GNOME: "Не беспокоить" выключает баннеры уведомлений
+#[cfg(all(unix, not(target_os = "macos")))]
+fn detect() -> Option<FocusMode> {
+    let output = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .ok()?;
+    let banners_hidden = output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "false";
+    banners_hidden.then_some(FocusMode::LinuxDoNotDisturb)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn detect() -> Option<FocusMode> {
+    None
+}
+
+/// Есть хотя бы одна запись об активном режиме (`data[].storeAssertionRecords`)
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn has_focus_assertions(json: &str) -> bool {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+        return false;
+    };
+    value["data"].as_array().is_some_and(|data| {
+        data.iter()
+            .any(|entry| entry["storeAssertionRecords"].as_array().is_some_and(|records| !records.is_empty()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_is_active_only_with_assertion_records() {
+        let active = r#"{"data":[{"storeAssertionRecords":[{"assertionDetails":
+            {"assertionDetailsModeIdentifier":"com.apple.donotdisturb.mode.default"}}]}]}"#;
+        assert!(has_focus_assertions(active));
+        assert!(!has_focus_assertions(r#"{"data":[{"storeAssertionRecords":[]}]}"#));
+        assert!(!has_focus_assertions(r#"{"data":[{}]}"#));
+        assert!(!has_focus_assertions("not json"));
+    }
+}
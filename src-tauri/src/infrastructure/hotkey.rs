@@ -76,10 +76,134 @@ fn map_part_to_parser_token(token: &str) -> String {
     }
 }
 
+/// Системные/общепринятые сочетания текущей ОС, которые нельзя отдавать под запись.
+/// Чужие приложения так не узнать — для них есть probe через регистрацию (см. presentation).
+#[cfg(target_os = "macos")]
+const RESERVED_SHORTCUTS: &[(&str, &str)] = &[
+    ("Super+Space", "Spotlight"),
+    ("Super+Alt+Space", "Finder search"),
+    ("Control+Space", "Input source switching"),
+    ("Control+Alt+Space", "Input source switching"),
+    ("Super+Tab", "App switcher"),
+    ("Super+Shift+Digit3", "Screenshot"),
+    ("Super+Shift+Digit4", "Screenshot"),
+    ("Super+Shift+Digit5", "Screenshot"),
+    ("Super+Alt+Escape", "Force Quit"),
+    ("Control+Super+KeyQ", "Lock Screen"),
+    ("Super+Shift+KeyQ", "Log Out"),
+    ("Super+KeyQ", "Quit"),
+    ("Super+KeyW", "Close window"),
+    ("Super+KeyH", "Hide"),
+    ("Super+KeyM", "Minimize"),
+    ("Super+KeyC", "Copy"),
+    ("Super+KeyV", "Paste"),
+    ("Super+KeyX", "Cut"),
+    ("Super+KeyZ", "Undo"),
+    ("Super+KeyA", "Select all"),
+];
+
+#[cfg(target_os = "windows")]
+const RESERVED_SHORTCUTS: &[(&str, &str)] = &[
+    ("Alt+Tab", "App switcher"),
+    ("Alt+F4", "Close window"),
+    ("Control+Shift+Escape", "Task Manager"),
+    ("Control+Escape", "Start menu"),
+    ("Super+KeyL", "Lock"),
+    ("Super+KeyD", "Show desktop"),
+    ("Super+KeyH", "Voice typing"),
+    ("Super+Shift+KeyS", "Snipping Tool"),
+    ("Super+Space", "Input language switching"),
+    ("Control+KeyC", "Copy"),
+    ("Control+KeyV", "Paste"),
+    ("Control+KeyX", "Cut"),
+    ("Control+KeyZ", "Undo"),
+    ("Control+KeyA", "Select all"),
+];
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const RESERVED_SHORTCUTS: &[(&str, &str)] = &[
+    ("Alt+Tab", "App switcher"),
+    ("Alt+F4", "Close window"),
+    ("Control+Alt+KeyT", "Terminal"),
+    ("Control+Alt+KeyL", "Lock"),
+    ("Control+Alt+Delete", "Log Out"),
+    ("Super+Space", "Input source switching"),
+    ("Super+KeyL", "Lock"),
+    ("Control+KeyC", "Copy"),
+    ("Control+KeyV", "Paste"),
+    ("Control+KeyX", "Cut"),
+    ("Control+KeyZ", "Undo"),
+    ("Control+KeyA", "Select all"),
+];
+
+/// Кандидаты для подсказок, от более удобных к менее
+pub const HOTKEY_SUGGESTION_CANDIDATES: &[&str] = &[
+    DEFAULT_RECORDING_HOTKEY,
+    "CmdOrCtrl+Shift+Space",
+    "CmdOrCtrl+Alt+Space",
+    "CmdOrCtrl+Alt+X",
+    "CmdOrCtrl+Alt+R",
+    "CmdOrCtrl+Shift+D",
+    "CmdOrCtrl+Alt+V",
+    "CmdOrCtrl+Shift+E",
+    "Alt+Shift+Space",
+    "CmdOrCtrl+Shift+F12",
+    "F13",
+    "F14",
+];
+
+/// Чем занято сочетание в системе (если известно); None — не в списке зарезервированных
+pub fn reserved_shortcut_owner(shortcut: &Shortcut) -> Option<&'static str> {
+    RESERVED_SHORTCUTS.iter().find_map(|(combo, owner)| {
+        combo
+            .parse::<Shortcut>()
+            .ok()
+            .filter(|reserved| reserved == shortcut)
+            .map(|_| *owner)
+    })
+}
+
+/// Первые `limit` кандидатов, которые проходят `is_available` (порядок кандидатов сохраняется)
+pub fn suggest_hotkeys(
+    candidates: &[&str],
+    limit: usize,
+    mut is_available: impl FnMut(&str, &Shortcut) -> bool,
+) -> Vec<String> {
+    candidates
+        .iter()
+        .filter_map(|candidate| candidate.parse::<Shortcut>().ok().map(|sc| (*candidate, sc)))
+        .filter(|(_, sc)| reserved_shortcut_owner(sc).is_none())
+        .filter(|(candidate, sc)| is_available(candidate, sc))
+        .map(|(candidate, _)| candidate.to_string())
+        .take(limit)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn detects_reserved_system_shortcuts() {
+        let default: Shortcut = DEFAULT_RECORDING_HOTKEY.parse().unwrap();
+        assert_eq!(reserved_shortcut_owner(&default), None);
+
+        // CmdOrCtrl = Cmd на macOS и Ctrl на остальных — в обоих случаях это "Copy"
+        let copy: Shortcut = "CmdOrCtrl+C".parse().unwrap();
+        assert_eq!(reserved_shortcut_owner(&copy), Some("Copy"));
+    }
+
+    #[test]
+    fn suggestions_skip_taken_and_invalid_candidates() {
+        let candidates = ["CmdOrCtrl+C", "not a hotkey", "CmdOrCtrl+Shift+X", "CmdOrCtrl+Alt+R", "F13"];
+        let suggestions = suggest_hotkeys(&candidates, 2, |candidate, _| candidate != "CmdOrCtrl+Shift+X");
+        assert_eq!(suggestions, vec!["CmdOrCtrl+Alt+R", "F13"]);
+
+        assert!(HOTKEY_SUGGESTION_CANDIDATES
+            .iter()
+            .all(|candidate| candidate.parse::<Shortcut>().is_ok()));
+    }
+
     #[test]
     fn normalize_keeps_valid_shortcut() {
         let s = "CmdOrCtrl+Shift+X";
@@ -0,0 +1,281 @@
+//! Структурированное логирование: JSON-записи в файл с ротацией по размеру,
+//! цветной вывод в консоль и кольцевой буфер последних записей для панели диагностики.
+
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use log::LevelFilter;
+use serde::Serialize;
+use tauri::{plugin::TauriPlugin, Runtime};
+use tauri_plugin_log::{fern, Target, TargetKind};
+
+use super::config_store::ConfigStore;
+
+const LOG_FILE_NAME: &str = "voicetext.log";
+
+/// Ротация: текущий файл до 5 МБ + 3 предыдущих (voicetext.log.1 … .3)
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_LOG_FILES: usize = 3;
+
+/// Сколько последних записей держим в памяти для get_recent_logs
+const RECENT_LOGS_CAPACITY: usize = 1000;
+
+/// Сессия записи, к которой относятся текущие логи (0 — записи ещё не было)
+static LOG_SESSION_ID: AtomicU64 = AtomicU64::new(0);
+
+static RECENT_LOGS: Mutex<VecDeque<LogRecord>> = Mutex::new(VecDeque::new());
+
+/// Одна запись лога (строка JSON в файле)
+#[derive(Debug, Clone, Serialize)]
+pub struct LogRecord {
+    pub timestamp_ms: i64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<u64>,
+}
+
+impl LogRecord {
+    fn from_record(record: &log::Record) -> Self {
+        let session_id = LOG_SESSION_ID.load(Ordering::Relaxed);
+        Self {
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+            session_id: (session_id != 0).then_some(session_id),
+        }
+    }
+}
+
+/// Файл лога с ротацией по размеру
+struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn new(path: PathBuf, max_bytes: u64, max_files: usize) -> Self {
+        let written = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            path,
+            max_bytes,
+            max_files,
+            file: None,
+            written,
+        }
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    /// voicetext.log → .1, .1 → .2, …; самый старый удаляется
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        if self.max_files == 0 {
+            let _ = std::fs::remove_file(&self.path);
+        } else {
+            let _ = std::fs::remove_file(self.rotated_path(self.max_files));
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated_path(index + 1))?;
+                }
+            }
+            if self.path.exists() {
+                std::fs::rename(&self.path, self.rotated_path(1))?;
+            }
+        }
+        self.written = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > self.max_bytes {
+            self.rotate()?;
+        }
+
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            self.file = Some(OpenOptions::new().create(true).append(true).open(&self.path)?);
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(line.as_bytes())?;
+            file.write_all(b"\n")?;
+        }
+        self.written += len;
+        Ok(())
+    }
+}
+
+fn push_recent(record: LogRecord) {
+    if let Ok(mut recent) = RECENT_LOGS.lock() {
+        if recent.len() >= RECENT_LOGS_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(record);
+    }
+}
+
+/// Уровень логов по умолчанию (до set_log_level)
+pub fn default_log_level() -> LevelFilter {
+    if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+/// Плагин логирования: консоль (цветная) + JSON-файл с ротацией + буфер последних записей
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    let log_file = ConfigStore::logs_dir()
+        .map(|dir| Mutex::new(RotatingFile::new(dir.join(LOG_FILE_NAME), MAX_LOG_FILE_BYTES, MAX_ROTATED_LOG_FILES)))
+        .map_err(|e| eprintln!("Failed to resolve logs directory, file logging disabled: {}", e))
+        .ok();
+
+    let structured = fern::Output::call(move |record| {
+        let entry = LogRecord::from_record(record);
+        if let Some(log_file) = log_file.as_ref() {
+            if let Ok(line) = serde_json::to_string(&entry) {
+                if let Ok(mut log_file) = log_file.lock() {
+                    let _ = log_file.write_line(&line);
+                }
+            }
+        }
+        push_recent(entry);
+    });
+
+    let console = fern::Dispatch::new()
+        .format(|out, message, record| {
+            use tauri_plugin_log::fern::colors::{Color, ColoredLevelConfig};
+
+            // Цвета для уровней логирования
+            let colors = ColoredLevelConfig::new()
+                .error(Color::Red)
+                .warn(Color::Yellow)
+                .info(Color::Green)
+                .debug(Color::Cyan)
+                .trace(Color::Magenta);
+
+            // Укорачиваем путь модуля - берём только последнюю часть
+            let target = record.target();
+            let short_target = target.rsplit("::").next().unwrap_or(target);
+
+            // Время в локальном формате
+            let now = chrono::Local::now();
+            let time_str = now.format("%H:%M:%S");
+
+            // Форматируем лог: время серым, уровень цветной, модуль серым, сообщение белым
+            out.finish(format_args!(
+                "\x1b[90m{}\x1b[0m {} \x1b[90m{}\x1b[0m  {}",
+                time_str,
+                colors.color(record.level()),
+                short_target,
+                message
+            ))
+        })
+        .chain(io::stdout());
+
+    tauri_plugin_log::Builder::default()
+        .clear_targets()
+        .target(Target::new(TargetKind::Dispatch(console)))
+        .target(Target::new(TargetKind::Dispatch(fern::Dispatch::new().chain(structured))))
+        // Пропускаем всё: фактический уровень — log::max_level(), его меняет set_log_level
+        .level(LevelFilter::Trace)
+        // Глушим слишком многословные модули (огромные JSON в DEBUG)
+        .level_for("tauri_plugin_updater", LevelFilter::Info)
+        .level_for("reqwest", LevelFilter::Warn)
+        .level_for("hyper", LevelFilter::Warn)
+        // Форматируют сами targets
+        .format(|out, message, _record| out.finish(format_args!("{}", message)))
+        .build()
+}
+
+/// Привязывает последующие записи к сессии записи
+pub fn set_log_session_id(session_id: u64) {
+    LOG_SESSION_ID.store(session_id, Ordering::Relaxed);
+}
+
+/// Меняет уровень логирования на лету ("error", "warn", "info", "debug", "trace", "off")
+pub fn set_log_level(level: &str) -> Result<LevelFilter, String> {
+    let filter = LevelFilter::from_str(level.trim()).map_err(|_| format!("Unknown log level: {}", level))?;
+    log::set_max_level(filter);
+    Ok(filter)
+}
+
+pub fn current_log_level() -> LevelFilter {
+    log::max_level()
+}
+
+/// Последние `limit` записей (от старых к новым)
+pub fn recent_logs(limit: usize) -> Vec<LogRecord> {
+    RECENT_LOGS
+        .lock()
+        .map(|recent| recent.iter().skip(recent.len().saturating_sub(limit)).cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Путь к текущему файлу лога
+pub fn log_file_path() -> Option<PathBuf> {
+    ConfigStore::logs_dir().ok().map(|dir| dir.join(LOG_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn read_lines(path: &Path) -> Vec<String> {
+        std::fs::read_to_string(path)
+            .map(|s| s.lines().map(|l| l.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("voice-to-text-logs-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_limited_history() {
+        let dir = temp_dir();
+        let path = dir.join(LOG_FILE_NAME);
+        let mut file = RotatingFile::new(path.clone(), 20, 2);
+
+        for line in ["aaaaaaaaaa", "bbbbbbbbbb", "cccccccccc", "dddddddddd"] {
+            file.write_line(line).unwrap();
+        }
+
+        assert_eq!(read_lines(&path), vec!["dddddddddd"]);
+        assert_eq!(read_lines(&file.rotated_path(1)), vec!["cccccccccc"]);
+        assert_eq!(read_lines(&file.rotated_path(2)), vec!["bbbbbbbbbb"]);
+        assert!(!file.rotated_path(3).exists());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn parses_log_levels() {
+        assert!(set_log_level("verbose").is_err());
+        assert_eq!(LevelFilter::from_str("DEBUG").unwrap(), LevelFilter::Debug);
+    }
+}
@@ -6,6 +6,7 @@ pub mod audio;
 pub mod factory;
 pub mod config_store;
 pub mod updater;
+pub mod update_manifest; // latest.json: delta-артефакты, проверка подписи
 pub mod models;
 pub mod embedded_keys; // API ключи встроенные в build
 pub mod auto_paste; // Автоматическая вставка текста
@@ -13,8 +14,35 @@ pub mod microphone_permission; // Проверка разрешения на м
 pub mod clipboard; // Кроссплатформенная работа с clipboard
 pub mod hotkey; // Нормализация/миграция хоткеев
 pub mod auth_store; // Auth session + device_id (Rust SoT)
+pub mod session_journal; // Журнал незавершённой записи (восстановление после краша)
+pub mod audio_spool; // Недосланное провайдеру аудио на диске (разрывы сети посреди записи)
+pub mod text_output; // Sinks доставки финального текста
+pub mod correction_store; // Словарь исправлений пользователя
+pub mod punctuation; // Локальная пунктуация (ONNX)
+pub mod autostart; // Автозапуск при входе в систему
+pub mod logging; // Структурированные логи (JSON, ротация)
+pub mod telemetry; // Анонимная телеметрия (opt-in)
+pub mod companion; // Трансляция распознавания на второе устройство (LAN, mDNS + WS)
+pub mod topic_advisor; // Границы тем встречи от бэкенда (LLM)
+pub mod calendar; // Текущая встреча из календаря (EventKit / .ics) для названий сессий
+pub mod focus_mode; // Режимы фокусировки / "не беспокоить" ОС
+pub mod feedback; // Звуки старта/остановки/ошибки записи (rodio)
+pub mod speech; // Озвучивание текста системным синтезатором речи
+pub mod session_share; // Ссылки на расшифровку сессии (шифрование + /api/v1/shares)
+pub mod team_glossary; // Общий словарь команды (/api/v1/team/glossary)
+pub mod connectivity; // Проверка доступности сети (DNS + TCP)
+pub mod permissions; // Разрешения macOS (Accessibility, Input Monitoring, Screen Recording)
+pub mod window_elevation; // Права процесса целевого окна (Windows UIPI) для вставки
+pub mod active_window; // Активное приложение и заголовок окна (контекст сессии записи)
+pub mod storage; // Размещение данных пользователя: доступ только владельцу
 
 pub use factory::*;
 pub use config_store::ConfigStore;
 pub use auth_store::{AuthSession, AuthStore, AuthStoreData, AuthUser};
-pub use clipboard::copy_to_clipboard;
+pub use clipboard::{copy_rich_to_clipboard, copy_to_clipboard};
+pub use session_journal::{FileSessionJournal, RecoveredSession, SessionJournalMeta};
+pub use audio_spool::FileAudioSpool;
+pub use text_output::{create_text_output_sinks, ClipboardSink, TextOutputContext};
+pub use correction_store::FileCorrectionStore;
+pub use punctuation::create_punctuator;
+pub use feedback::SoundFeedback;
@@ -3,5 +3,9 @@
 /// Отвечает за загрузку, хранение и управление моделями Whisper
 
 mod whisper_models;
+mod model_updates; // Целостность и новые версии скачанных моделей
 
 pub use whisper_models::*;
+pub use model_updates::{
+    start_background_model_check, verify_model, ModelIntegrity, ModelUpdateAvailable, ModelUpdateReason, ModelVerification,
+};
@@ -0,0 +1,316 @@
+//! Проверка установленных моделей Whisper: не повреждён ли файл и не вышла ли новая версия.
+//!
+//! При загрузке sha256 файла записывается в `checksums.json` рядом с моделями. Повторная
+//! проверка сравнивает с ним файл на диске (целостность) и sha256 текущего релиза на
+//! HuggingFace (для LFS-файлов он приходит в заголовке `X-Linked-Etag`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::whisper_models::{get_available_models, get_model_path, get_models_dir, is_model_downloaded, WhisperModelInfo};
+
+const CHECKSUMS_FILE: &str = "checksums.json";
+
+/// Чтение-изменение-запись checksums.json из загрузки и фоновой проверки одновременно
+static CHECKSUMS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Состояние файла модели относительно sha256, записанного при загрузке
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelIntegrity {
+    Verified,
+    /// Файл не совпадает с тем, что был скачан — модель нужно перекачать
+    Corrupted,
+    /// Не с чем сравнить: модель скачана старой версией приложения, а HuggingFace недоступен
+    Unverified,
+    Missing,
+}
+
+/// Результат verify_model
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelVerification {
+    pub model: String,
+    pub integrity: ModelIntegrity,
+    /// sha256 файла на диске
+    pub sha256: Option<String>,
+    /// sha256 текущего релиза на HuggingFace (None — не удалось узнать)
+    pub latest_sha256: Option<String>,
+    /// На HuggingFace лежит другой файл, чем скачанный
+    pub update_available: bool,
+}
+
+/// Почему модель стоит перекачать
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelUpdateReason {
+    NewRelease,
+    IntegrityFailed,
+}
+
+/// Скачанную модель стоит перекачать (находит фоновая проверка)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelUpdateAvailable {
+    pub model: String,
+    pub reason: ModelUpdateReason,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ModelChecksum {
+    sha256: String,
+    size: u64,
+}
+
+/// Запускает фоновую проверку моделей: через минуту после старта, далее раз в сутки
+pub fn start_background_model_check<F>(on_update: F)
+where
+    F: Fn(ModelUpdateAvailable) + Send + Sync + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        // Хеширование гигабайтных файлов не должно совпадать со стартом приложения
+        tokio::time::sleep(Duration::from_secs(60)).await;
+
+        loop {
+            log::info!("Checking installed Whisper models (background check)");
+
+            for info in get_available_models() {
+                if !is_model_downloaded(&info.name) {
+                    continue;
+                }
+                let verification = match verify_model(&info.name).await {
+                    Ok(verification) => verification,
+                    Err(e) => {
+                        log::warn!("Failed to verify model '{}': {}", info.name, e);
+                        continue;
+                    }
+                };
+                let Some(reason) = update_reason(&verification) else {
+                    log::debug!("Model '{}' is up to date", info.name);
+                    continue;
+                };
+
+                log::info!("Model '{}' should be re-downloaded: {:?}", info.name, reason);
+                on_update(ModelUpdateAvailable {
+                    model: info.name.clone(),
+                    reason,
+                    size_bytes: info.size_bytes,
+                });
+            }
+
+            // Ждем сутки до следующей проверки
+            tokio::time::sleep(Duration::from_secs(24 * 3600)).await;
+        }
+    });
+}
+
+fn update_reason(verification: &ModelVerification) -> Option<ModelUpdateReason> {
+    if verification.integrity == ModelIntegrity::Corrupted {
+        Some(ModelUpdateReason::IntegrityFailed)
+    } else if verification.update_available {
+        Some(ModelUpdateReason::NewRelease)
+    } else {
+        None
+    }
+}
+
+/// Пересчитывает sha256 скачанной модели и сверяет с записанным при загрузке и с текущим релизом
+pub async fn verify_model(model_name: &str) -> anyhow::Result<ModelVerification> {
+    let info = find_model(model_name)?;
+    let path = get_model_path(model_name)?;
+
+    let sha256 = if path.exists() {
+        let hash_path = path.clone();
+        Some(tokio::task::spawn_blocking(move || file_sha256(&hash_path)).await??)
+    } else {
+        None
+    };
+
+    let recorded = load_checksums().remove(model_name).map(|c| c.sha256);
+    let latest_sha256 = match remote_model_sha256(&info).await {
+        Ok(latest) => latest,
+        Err(e) => {
+            log::warn!("Cannot fetch latest checksum of model '{}': {}", model_name, e);
+            None
+        }
+    };
+
+    let (integrity, update_available) = assess(sha256.as_deref(), recorded.as_deref(), latest_sha256.as_deref());
+
+    // Модель, скачанная до появления checksums.json, совпала с релизом — запоминаем эталон
+    if recorded.is_none() && integrity == ModelIntegrity::Verified {
+        if let (Some(sha256), Ok(metadata)) = (sha256.as_deref(), fs::metadata(&path)) {
+            if let Err(e) = record_model_checksum(model_name, sha256, metadata.len()) {
+                log::warn!("Failed to record checksum of model '{}': {}", model_name, e);
+            }
+        }
+    }
+
+    Ok(ModelVerification {
+        model: model_name.to_string(),
+        integrity,
+        sha256,
+        latest_sha256,
+        update_available,
+    })
+}
+
+/// (целостность, есть обновление) по sha256 файла, записанному при загрузке и релизному
+fn assess(local: Option<&str>, recorded: Option<&str>, latest: Option<&str>) -> (ModelIntegrity, bool) {
+    let Some(local) = local else {
+        return (ModelIntegrity::Missing, false);
+    };
+    match (recorded, latest) {
+        (Some(recorded), latest) => {
+            let integrity = if local == recorded {
+                ModelIntegrity::Verified
+            } else {
+                ModelIntegrity::Corrupted
+            };
+            (integrity, latest.is_some_and(|latest| latest != recorded))
+        }
+        (None, Some(latest)) if local == latest => (ModelIntegrity::Verified, false),
+        // Без эталона не отличить порчу от старой версии — в обоих случаях помогает перекачка
+        (None, Some(_)) => (ModelIntegrity::Unverified, true),
+        (None, None) => (ModelIntegrity::Unverified, false),
+    }
+}
+
+fn find_model(model_name: &str) -> anyhow::Result<WhisperModelInfo> {
+    get_available_models()
+        .into_iter()
+        .find(|m| m.name == model_name)
+        .ok_or_else(|| anyhow::anyhow!("Model '{}' not found", model_name))
+}
+
+/// sha256 текущего релиза модели на HuggingFace; None — файл не в LFS или заголовка нет
+pub async fn remote_model_sha256(info: &WhisperModelInfo) -> anyhow::Result<Option<String>> {
+    // X-Linked-Etag есть только в ответе resolve до редиректа на CDN
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_secs(15))
+        .build()?;
+    let response = client.head(&info.download_url).send().await?;
+    if response.status().is_client_error() || response.status().is_server_error() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+    Ok(response
+        .headers()
+        .get("x-linked-etag")
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_sha256_etag))
+}
+
+/// ETag LFS-файла — его sha256 (в кавычках, иногда со слабым префиксом W/)
+fn parse_sha256_etag(etag: &str) -> Option<String> {
+    let value = etag.trim().trim_start_matches("W/").trim_matches('"').to_ascii_lowercase();
+    (value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())).then_some(value)
+}
+
+/// Потоковый sha256 файла (модели весят до 3 GB — в память целиком не читаем)
+pub fn file_sha256(path: &Path) -> std::io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex_digest(hasher))
+}
+
+pub fn hex_digest(hasher: Sha256) -> String {
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn checksums_path() -> anyhow::Result<PathBuf> {
+    Ok(get_models_dir()?.join(CHECKSUMS_FILE))
+}
+
+fn load_checksums() -> BTreeMap<String, ModelChecksum> {
+    let Ok(path) = checksums_path() else {
+        return BTreeMap::new();
+    };
+    read_checksums(&path)
+}
+
+fn read_checksums(path: &Path) -> BTreeMap<String, ModelChecksum> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn update_checksums(path: &Path, update: impl FnOnce(&mut BTreeMap<String, ModelChecksum>)) -> anyhow::Result<()> {
+    let _guard = CHECKSUMS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut checksums = read_checksums(path);
+    update(&mut checksums);
+    fs::write(path, serde_json::to_string_pretty(&checksums)?)?;
+    Ok(())
+}
+
+/// Запоминает sha256 скачанной модели — эталон для последующих проверок
+pub fn record_model_checksum(model_name: &str, sha256: &str, size: u64) -> anyhow::Result<()> {
+    let checksum = ModelChecksum {
+        sha256: sha256.to_string(),
+        size,
+    };
+    update_checksums(&checksums_path()?, |checksums| {
+        checksums.insert(model_name.to_string(), checksum);
+    })
+}
+
+/// Забывает sha256 удалённой модели
+pub fn forget_model_checksum(model_name: &str) -> anyhow::Result<()> {
+    update_checksums(&checksums_path()?, |checksums| {
+        checksums.remove(model_name);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    const A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+    #[test]
+    fn assess_separates_corruption_from_new_releases() {
+        assert_eq!(assess(None, Some(A), Some(A)), (ModelIntegrity::Missing, false));
+        assert_eq!(assess(Some(A), Some(A), Some(A)), (ModelIntegrity::Verified, false));
+        assert_eq!(assess(Some(A), Some(A), Some(B)), (ModelIntegrity::Verified, true));
+        assert_eq!(assess(Some(B), Some(A), None), (ModelIntegrity::Corrupted, false));
+        assert_eq!(assess(Some(A), None, Some(A)), (ModelIntegrity::Verified, false));
+        assert_eq!(assess(Some(A), None, Some(B)), (ModelIntegrity::Unverified, true));
+        assert_eq!(assess(Some(A), None, None), (ModelIntegrity::Unverified, false));
+    }
+
+    #[test]
+    fn parses_lfs_etag_as_sha256() {
+        assert_eq!(parse_sha256_etag(&format!("\"{}\"", A.to_uppercase())).as_deref(), Some(A));
+        assert_eq!(parse_sha256_etag(&format!("W/\"{}\"", A)).as_deref(), Some(A));
+        // Git-ETag обычных файлов — sha1, не sha256
+        assert_eq!(parse_sha256_etag("\"4b825dc642cb6eb9a060e54bf8d69288fbee4904\""), None);
+    }
+
+    #[test]
+    fn file_sha256_matches_known_digest() {
+        let path = std::env::temp_dir().join(format!("voice-to-text-sha-{}", Uuid::new_v4()));
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            file_sha256(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let _ = fs::remove_file(path);
+    }
+}
@@ -1,6 +1,18 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Папка моделей, выбранная пользователем (AppConfig::models_dir); None — в данных приложения
+static MODELS_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Идущие загрузки: пока они есть, папку не переносим (временный файл осел бы в старой)
+static ACTIVE_DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Запас сверх размера модели: на том же диске пишутся временные файлы и всё остальное
+const FREE_SPACE_MARGIN_BYTES: u64 = 200 * 1024 * 1024;
 
 fn app_data_dir_name() -> &'static str {
     if cfg!(debug_assertions) {
@@ -130,14 +142,45 @@ pub const AVAILABLE_MODELS: &[(&str, &str, u64, f32, f32)] = &[
     ),
 ];
 
-/// Получает путь к директории хранения моделей
-pub fn get_models_dir() -> anyhow::Result<PathBuf> {
+/// Стандартная папка моделей в данных приложения
+pub fn default_models_dir() -> anyhow::Result<PathBuf> {
+    if let Some(dir) = crate::infrastructure::storage::portable_data_dir() {
+        return Ok(dir.join("models"));
+    }
+
     let app_data_dir = dirs::data_dir()
         .ok_or_else(|| anyhow::anyhow!("Cannot determine app data directory"))?;
 
     migrate_legacy_models_dir_once(&app_data_dir)?;
 
-    let models_dir = scoped_app_data_dir(&app_data_dir).join("models");
+    let scoped_dir = scoped_app_data_dir(&app_data_dir);
+    fs::create_dir_all(&scoped_dir)?;
+    if let Err(e) = crate::infrastructure::storage::restrict_to_owner(&scoped_dir) {
+        log::warn!("Failed to restrict access to {}: {}", scoped_dir.display(), e);
+    }
+    Ok(scoped_dir.join("models"))
+}
+
+/// Папка из настроек (вызывается при загрузке AppConfig); None — стандартная
+pub fn set_models_dir_override(dir: Option<PathBuf>) {
+    *MODELS_DIR_OVERRIDE.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+fn models_dir_override() -> Option<PathBuf> {
+    MODELS_DIR_OVERRIDE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Модели лежат в папке, выбранной пользователем (её правами мы не управляем)
+pub fn models_dir_is_custom() -> bool {
+    models_dir_override().is_some()
+}
+
+/// Получает путь к директории хранения моделей
+pub fn get_models_dir() -> anyhow::Result<PathBuf> {
+    let models_dir = match models_dir_override() {
+        Some(dir) => dir,
+        None => default_models_dir()?,
+    };
 
     // Создаем директорию если не существует
     if !models_dir.exists() {
@@ -211,6 +254,181 @@ fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Свободное место (в байтах) на томе, где лежит path
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // типы полей statvfs различаются между платформами
+pub fn available_space(path: &Path) -> anyhow::Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Свободное место (в байтах) на томе, где лежит path
+#[cfg(windows)]
+pub fn available_space(path: &Path) -> anyhow::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available: u64 = 0;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut())
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn available_space(_path: &Path) -> anyhow::Result<u64> {
+    anyhow::bail!("Free space check is not supported on this platform")
+}
+
+/// Ошибка, если на диске с dir не помещается required байт (с запасом).
+///
+/// Если свободное место узнать не удалось — не мешаем: лучше попробовать, чем запретить зря.
+pub fn ensure_free_space(dir: &Path, required: u64) -> anyhow::Result<()> {
+    // Папка может ещё не существовать (новый внешний диск) — меряем ближайшую существующую
+    let probe = dir.ancestors().find(|p| p.exists()).unwrap_or(dir);
+    match available_space(probe) {
+        Ok(available) => check_free_space(dir, required, available),
+        Err(e) => {
+            log::warn!("Cannot determine free space at {}: {}", probe.display(), e);
+            Ok(())
+        }
+    }
+}
+
+fn check_free_space(dir: &Path, required: u64, available: u64) -> anyhow::Result<()> {
+    let needed = required.saturating_add(FREE_SPACE_MARGIN_BYTES);
+    if available < needed {
+        anyhow::bail!(
+            "Not enough disk space in {}: {} required, {} available",
+            dir.display(),
+            format_size(needed),
+            format_size(available)
+        );
+    }
+    Ok(())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Переносит модели в новую папку (например, на внешний диск); None — обратно в стандартную.
+///
+/// Проверяет, что в папку можно писать и места хватает, переносит содержимое старой папки
+/// и запоминает новую. Возвращает итоговую папку моделей.
+pub fn relocate_models_dir(new_dir: Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    if ACTIVE_DOWNLOADS.load(Ordering::SeqCst) > 0 {
+        anyhow::bail!("Cannot move models while a download is in progress");
+    }
+
+    let old_dir = get_models_dir()?;
+    let target = match &new_dir {
+        Some(dir) => dir.clone(),
+        None => default_models_dir()?,
+    };
+
+    if target != old_dir {
+        fs::create_dir_all(&target)
+            .map_err(|e| anyhow::anyhow!("Cannot create models directory {}: {}", target.display(), e))?;
+        let probe = target.join(".write-test");
+        fs::write(&probe, b"")
+            .map_err(|e| anyhow::anyhow!("Models directory {} is not writable: {}", target.display(), e))?;
+        let _ = fs::remove_file(&probe);
+
+        ensure_free_space(&target, dir_size(&old_dir))?;
+        let moved = migrate_models_dir(&old_dir, &target)?;
+        log::info!("Moved {} model entries from {} to {}", moved, old_dir.display(), target.display());
+    }
+
+    set_models_dir_override(new_dir);
+    Ok(target)
+}
+
+/// Переносит содержимое папки моделей (Whisper, пунктуация) в target; возвращает число записей.
+///
+/// То, что уже лежит в target, не перезаписываем: старая копия остаётся на месте.
+fn migrate_models_dir(source: &Path, target: &Path) -> anyhow::Result<usize> {
+    if !source.exists() {
+        return Ok(0);
+    }
+
+    let mut moved = 0;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let source_path = entry.path();
+        // Недокачанное не переносим — загрузка начнётся заново
+        if source_path.extension().is_some_and(|ext| ext == "tmp") {
+            continue;
+        }
+        let target_path = target.join(entry.file_name());
+        if target_path.exists() {
+            log::warn!("Skipping {}: already exists in {}", source_path.display(), target.display());
+            continue;
+        }
+        move_entry(&source_path, &target_path)?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+fn move_entry(source: &Path, target: &Path) -> anyhow::Result<()> {
+    // Между томами (внешний диск) rename не работает — копируем и удаляем исходник
+    if fs::rename(source, target).is_ok() {
+        return Ok(());
+    }
+
+    if source.is_dir() {
+        if let Err(e) = copy_dir_recursive_once(source, target) {
+            let _ = fs::remove_dir_all(target);
+            return Err(e);
+        }
+        fs::remove_dir_all(source)?;
+    } else {
+        if let Err(e) = fs::copy(source, target) {
+            let _ = fs::remove_file(target);
+            return Err(e.into());
+        }
+        fs::remove_file(source)?;
+    }
+    Ok(())
+}
+
+/// Считает загрузку идущей, пока жив (см. relocate_models_dir)
+struct DownloadGuard;
+
+impl DownloadGuard {
+    fn begin() -> Self {
+        ACTIVE_DOWNLOADS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for DownloadGuard {
+    fn drop(&mut self) {
+        ACTIVE_DOWNLOADS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// Скачивает модель Whisper с HuggingFace
 ///
 /// Использует streaming для экономии памяти и поддержки больших файлов.
@@ -227,7 +445,20 @@ where
         .find(|m| m.name == model_name)
         .ok_or_else(|| anyhow::anyhow!("Model '{}' not found", model_name))?;
 
+    let _download = DownloadGuard::begin();
     let model_path = get_model_path(model_name)?;
+    let models_dir = get_models_dir()?;
+
+    // Проверяем место до запроса, чтобы не упасть на середине многогигабайтной загрузки
+    ensure_free_space(&models_dir, model_info.size_bytes)?;
+
+    // sha256 релиза — сверим с тем, что реально скачалось
+    let expected_sha256 = super::model_updates::remote_model_sha256(&model_info)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Cannot fetch checksum of model '{}': {}", model_name, e);
+            None
+        });
 
     log::info!("Downloading model '{}' from {}", model_name, model_info.download_url);
     log::info!("Target path: {}", model_path.display());
@@ -246,6 +477,9 @@ where
     }
 
     let total_size = response.content_length().unwrap_or(model_info.size_bytes);
+    if total_size > model_info.size_bytes {
+        ensure_free_space(&models_dir, total_size)?;
+    }
     let mut downloaded: u64 = 0;
 
     // Создаем временный файл
@@ -255,18 +489,30 @@ where
     // Скачиваем по частям
     use futures_util::StreamExt;
     let mut stream = response.bytes_stream();
+    let mut hasher = Sha256::new();
 
     while let Some(chunk_result) = stream.next().await {
         let chunk = chunk_result?;
         use std::io::Write;
         file.write_all(&chunk)?;
+        hasher.update(&chunk);
 
         downloaded += chunk.len() as u64;
         progress_callback(downloaded, total_size);
     }
 
+    drop(file);
+    let sha256 = super::model_updates::hex_digest(hasher);
+    if let Some(expected) = expected_sha256.filter(|expected| *expected != sha256) {
+        let _ = fs::remove_file(&temp_path);
+        anyhow::bail!("Checksum mismatch for model '{}': expected {}, got {}", model_name, expected, sha256);
+    }
+
     // Переименовываем временный файл в финальный
     fs::rename(&temp_path, &model_path)?;
+    if let Err(e) = super::model_updates::record_model_checksum(model_name, &sha256, downloaded) {
+        log::warn!("Failed to record checksum of model '{}': {}", model_name, e);
+    }
 
     log::info!("Model '{}' downloaded successfully to {}", model_name, model_path.display());
     Ok(model_path)
@@ -280,6 +526,9 @@ pub fn delete_model(model_name: &str) -> anyhow::Result<()> {
         fs::remove_file(&model_path)?;
         log::info!("Model '{}' deleted", model_name);
     }
+    if let Err(e) = super::model_updates::forget_model_checksum(model_name) {
+        log::warn!("Failed to forget checksum of model '{}': {}", model_name, e);
+    }
 
     Ok(())
 }
@@ -315,4 +564,37 @@ mod tests {
 
         let _ = fs::remove_dir_all(root);
     }
+
+    #[test]
+    fn free_space_check_keeps_a_margin() {
+        let dir = Path::new("/models");
+        assert!(check_free_space(dir, 100, 100 + FREE_SPACE_MARGIN_BYTES).is_ok());
+
+        let err = check_free_space(dir, 100, 100 + FREE_SPACE_MARGIN_BYTES - 1).unwrap_err();
+        assert!(err.to_string().starts_with("Not enough disk space in /models"));
+    }
+
+    #[test]
+    fn migrate_models_dir_moves_models_and_skips_partial_downloads() {
+        let root = std::env::temp_dir().join(format!("voice-to-text-relocate-{}", Uuid::new_v4()));
+        let source = root.join("old");
+        let target = root.join("new");
+        fs::create_dir_all(source.join("punctuation")).unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(source.join("ggml-base.bin"), b"base").unwrap();
+        fs::write(source.join("ggml-small.tmp"), b"partial").unwrap();
+        fs::write(source.join("punctuation").join("model.onnx"), b"onnx").unwrap();
+        fs::write(source.join("ggml-tiny.bin"), b"old-tiny").unwrap();
+        fs::write(target.join("ggml-tiny.bin"), b"new-tiny").unwrap();
+
+        assert_eq!(migrate_models_dir(&source, &target).unwrap(), 2);
+
+        assert_eq!(fs::read(target.join("ggml-base.bin")).unwrap(), b"base");
+        assert_eq!(fs::read(target.join("punctuation").join("model.onnx")).unwrap(), b"onnx");
+        assert_eq!(fs::read(target.join("ggml-tiny.bin")).unwrap(), b"new-tiny");
+        assert!(!target.join("ggml-small.tmp").exists());
+        assert!(!source.join("ggml-base.bin").exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
 }
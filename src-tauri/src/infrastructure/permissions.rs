@@ -0,0 +1,110 @@
+//! Разрешения macOS (Privacy & Security), от которых зависят функции приложения.
+//!
+//! Проверки без побочных эффектов: системный запрос пользователю здесь не показывается.
+//! На Windows/Linux таких разрешений нет — всё `NotRequired`.
+
+use anyhow::Result;
+
+use crate::domain::{PermissionCapability, PermissionState, PermissionStatus};
+
+/// Статус всех разрешений
+pub fn check_permissions() -> Vec<PermissionStatus> {
+    PermissionCapability::ALL
+        .into_iter()
+        .map(|capability| PermissionStatus::new(capability, permission_state(capability)))
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+pub fn permission_state(capability: PermissionCapability) -> PermissionState {
+    use crate::infrastructure::microphone_permission::{microphone_permission_status, MicrophonePermissionStatus};
+
+    match capability {
+        PermissionCapability::Microphone => match microphone_permission_status() {
+            MicrophonePermissionStatus::Authorized => PermissionState::Granted,
+            MicrophonePermissionStatus::NotDetermined => PermissionState::NotDetermined,
+            _ => PermissionState::Denied,
+        },
+        PermissionCapability::Accessibility => {
+            if crate::infrastructure::auto_paste::check_accessibility_permission() {
+                PermissionState::Granted
+            } else {
+                PermissionState::Denied
+            }
+        }
+        PermissionCapability::InputMonitoring => input_monitoring_state(),
+        PermissionCapability::ScreenRecording => screen_recording_state(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn permission_state(_capability: PermissionCapability) -> PermissionState {
+    PermissionState::NotRequired
+}
+
+/// IOHIDCheckAccess(kIOHIDRequestTypeListenEvent): 0 — granted, 1 — denied, 2 — unknown
+#[cfg(target_os = "macos")]
+fn input_monitoring_state() -> PermissionState {
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOHIDCheckAccess(request_type: u32) -> u32;
+    }
+    const LISTEN_EVENT: u32 = 1;
+
+    match unsafe { IOHIDCheckAccess(LISTEN_EVENT) } {
+        0 => PermissionState::Granted,
+        1 => PermissionState::Denied,
+        _ => PermissionState::NotDetermined,
+    }
+}
+
+/// CGPreflightScreenCaptureAccess не отличает "отказано" от "ещё не спрашивали"
+#[cfg(target_os = "macos")]
+fn screen_recording_state() -> PermissionState {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+    }
+
+    if unsafe { CGPreflightScreenCaptureAccess() } {
+        PermissionState::Granted
+    } else {
+        PermissionState::Denied
+    }
+}
+
+/// Открывает нужный раздел System Settings > Privacy & Security
+#[cfg(target_os = "macos")]
+pub fn open_permission_settings(capability: PermissionCapability) -> Result<()> {
+    use anyhow::Context;
+    use std::process::Command;
+
+    log::info!("Opening macOS settings for {:?} permission", capability);
+    let status = Command::new("open")
+        .arg(capability.settings_url())
+        .status()
+        .context("Failed to open System Settings")?;
+
+    if !status.success() {
+        anyhow::bail!("Failed to open {:?} settings", capability);
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn open_permission_settings(capability: PermissionCapability) -> Result<()> {
+    log::warn!("open_permission_settings({:?}) called on non-macOS platform", capability);
+    Ok(())
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permissions_are_not_required_outside_macos() {
+        let statuses = check_permissions();
+        assert_eq!(statuses.len(), PermissionCapability::ALL.len());
+        assert!(statuses.iter().all(|s| s.state == PermissionState::NotRequired && s.settings_url.is_none()));
+    }
+}
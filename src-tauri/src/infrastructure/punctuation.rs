@@ -0,0 +1,280 @@
+//! Локальное восстановление пунктуации и регистра (ONNX модель token classification).
+//!
+//! Ожидаемый формат модели (директория `models/punctuation/`):
+//! - `model.onnx` — входы `input_ids`, `attention_mask` (i64, [1, seq]);
+//!   выходы `punct_logits` ([1, seq, 4]: нет / запятая / точка / вопрос)
+//!   и `cap_logits` ([1, seq, 2]: как есть / с заглавной);
+//! - `tokenizer.json` — токенизатор HuggingFace.
+//!
+//! Метка слова берётся по его первому sub-token.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::domain::{capitalize_first, Punctuator};
+
+/// Знак, который модель ставит после слова
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PunctuationMark {
+    #[default]
+    None,
+    Comma,
+    Period,
+    Question,
+}
+
+impl PunctuationMark {
+    /// Порядок классов в выходе `punct_logits`
+    pub fn from_class(class: usize) -> Self {
+        match class {
+            1 => Self::Comma,
+            2 => Self::Period,
+            3 => Self::Question,
+            _ => Self::None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Comma => ",",
+            Self::Period => ".",
+            Self::Question => "?",
+        }
+    }
+}
+
+/// Предсказание модели для одного слова
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WordLabel {
+    pub mark: PunctuationMark,
+    pub capitalize: bool,
+}
+
+/// Слова фразы без "хвостовой" пунктуации — в таком виде их видит модель
+pub fn split_words(text: &str) -> Vec<&str> {
+    text.split_whitespace()
+        .map(|w| w.trim_end_matches(|c: char| matches!(c, ',' | '.' | '?' | '!' | ';' | ':')))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Собирает фразу из слов и меток модели.
+///
+/// Регистр, который уже был в исходном тексте, не понижаем ("Kubernetes" остаётся как есть).
+/// Если модель не поставила знак в конце фразы — добавляем точку.
+pub fn apply_word_labels(words: &[&str], labels: &[WordLabel]) -> String {
+    let mut out = String::new();
+    for (idx, word) in words.iter().enumerate() {
+        let label = labels.get(idx).copied().unwrap_or_default();
+        if idx > 0 {
+            out.push(' ');
+        }
+        if label.capitalize || idx == 0 {
+            out.push_str(&capitalize_first(word));
+        } else {
+            out.push_str(word);
+        }
+
+        let mark = if idx + 1 == words.len() && matches!(label.mark, PunctuationMark::None | PunctuationMark::Comma)
+        {
+            PunctuationMark::Period
+        } else {
+            label.mark
+        };
+        out.push_str(mark.as_str());
+    }
+    out
+}
+
+/// Директория с моделью пунктуации
+pub fn punctuation_model_dir() -> anyhow::Result<PathBuf> {
+    Ok(crate::infrastructure::models::get_models_dir()?.join("punctuation"))
+}
+
+/// Создаёт локальный punctuator, если он доступен в этой сборке.
+///
+/// Модель загружается лениво — при первой фразе с включённой `punctuate_locally`.
+pub fn create_punctuator() -> Option<Arc<dyn Punctuator>> {
+    #[cfg(feature = "punctuation")]
+    {
+        match punctuation_model_dir() {
+            Ok(dir) => return Some(Arc::new(onnx_impl::OnnxPunctuator::new(dir))),
+            Err(e) => log::warn!("Local punctuation is unavailable: {}", e),
+        }
+    }
+    #[cfg(not(feature = "punctuation"))]
+    log::debug!("App is built without `punctuation` feature - local punctuation is disabled");
+
+    None
+}
+
+// Полная реализация на ONNX Runtime (требуется feature "punctuation")
+#[cfg(feature = "punctuation")]
+mod onnx_impl {
+    use super::*;
+    use std::sync::Mutex;
+
+    use ort::session::Session;
+    use ort::value::Tensor;
+    use tokenizers::Tokenizer;
+
+    use crate::domain::{PunctuationError, PunctuationResult};
+
+    struct LoadedModel {
+        session: Session,
+        tokenizer: Tokenizer,
+    }
+
+    pub struct OnnxPunctuator {
+        model_dir: PathBuf,
+        model: Mutex<Option<LoadedModel>>,
+    }
+
+    impl OnnxPunctuator {
+        pub fn new(model_dir: PathBuf) -> Self {
+            Self {
+                model_dir,
+                model: Mutex::new(None),
+            }
+        }
+
+        fn load(&self) -> PunctuationResult<LoadedModel> {
+            let model_path = self.model_dir.join("model.onnx");
+            let tokenizer_path = self.model_dir.join("tokenizer.json");
+            if !model_path.exists() || !tokenizer_path.exists() {
+                return Err(PunctuationError::Unavailable(format!(
+                    "model files not found in {}",
+                    self.model_dir.display()
+                )));
+            }
+
+            log::info!("Loading punctuation model from {}", self.model_dir.display());
+            let session = Session::builder()
+                .and_then(|b| b.commit_from_file(&model_path))
+                .map_err(|e| PunctuationError::Unavailable(e.to_string()))?;
+            let tokenizer = Tokenizer::from_file(&tokenizer_path)
+                .map_err(|e| PunctuationError::Unavailable(e.to_string()))?;
+
+            Ok(LoadedModel { session, tokenizer })
+        }
+
+        fn argmax(row: &[f32]) -> usize {
+            row.iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| i)
+                .unwrap_or(0)
+        }
+
+        fn infer(model: &mut LoadedModel, words: &[&str]) -> PunctuationResult<Vec<WordLabel>> {
+            let lowered: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+            let encoding = model
+                .tokenizer
+                .encode(lowered, true)
+                .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+
+            let ids: Vec<i64> = encoding.get_ids().iter().map(|&id| id as i64).collect();
+            let mask: Vec<i64> = encoding.get_attention_mask().iter().map(|&m| m as i64).collect();
+            let seq = ids.len();
+
+            let input_ids = Tensor::from_array(([1usize, seq], ids))
+                .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+            let attention_mask = Tensor::from_array(([1usize, seq], mask))
+                .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+
+            let outputs = model
+                .session
+                .run(ort::inputs!["input_ids" => input_ids, "attention_mask" => attention_mask])
+                .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+
+            let (_, punct) = outputs["punct_logits"]
+                .try_extract_tensor::<f32>()
+                .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+            let (_, cap) = outputs["cap_logits"]
+                .try_extract_tensor::<f32>()
+                .map_err(|e| PunctuationError::Inference(e.to_string()))?;
+
+            let mut labels = vec![WordLabel::default(); words.len()];
+            let mut seen = vec![false; words.len()];
+            for (token_idx, word_id) in encoding.get_word_ids().iter().enumerate() {
+                let Some(word_idx) = word_id.map(|w| w as usize) else {
+                    continue;
+                };
+                if word_idx >= words.len() || seen[word_idx] {
+                    continue;
+                }
+                seen[word_idx] = true;
+                labels[word_idx] = WordLabel {
+                    mark: PunctuationMark::from_class(Self::argmax(&punct[token_idx * 4..token_idx * 4 + 4])),
+                    capitalize: Self::argmax(&cap[token_idx * 2..token_idx * 2 + 2]) == 1,
+                };
+            }
+            Ok(labels)
+        }
+    }
+
+    impl Punctuator for OnnxPunctuator {
+        fn punctuate(&self, text: &str) -> PunctuationResult<String> {
+            let words = split_words(text);
+            if words.is_empty() {
+                return Ok(text.to_string());
+            }
+
+            let mut guard = self
+                .model
+                .lock()
+                .map_err(|_| PunctuationError::Inference("model lock poisoned".to_string()))?;
+            if guard.is_none() {
+                *guard = Some(self.load()?);
+            }
+            let model = guard.as_mut().expect("model loaded above");
+
+            let labels = Self::infer(model, &words)?;
+            Ok(apply_word_labels(&words, &labels))
+        }
+
+        fn name(&self) -> &str {
+            "onnx_punctuation"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_marks_and_capitalization_per_word() {
+        let words = split_words("привет как дела");
+        let labels = [
+            WordLabel {
+                mark: PunctuationMark::Comma,
+                capitalize: false,
+            },
+            WordLabel::default(),
+            WordLabel {
+                mark: PunctuationMark::Question,
+                capitalize: false,
+            },
+        ];
+        assert_eq!(apply_word_labels(&words, &labels), "Привет, как дела?");
+    }
+
+    #[test]
+    fn keeps_existing_case_and_closes_sentence() {
+        let words = split_words("deploy to Kubernetes, then london");
+        assert_eq!(words, vec!["deploy", "to", "Kubernetes", "then", "london"]);
+
+        let mut labels = vec![WordLabel::default(); words.len()];
+        labels[4].capitalize = true;
+        assert_eq!(apply_word_labels(&words, &labels), "Deploy to Kubernetes then London.");
+    }
+
+    #[test]
+    fn unknown_class_means_no_mark() {
+        assert_eq!(PunctuationMark::from_class(0), PunctuationMark::None);
+        assert_eq!(PunctuationMark::from_class(2), PunctuationMark::Period);
+        assert_eq!(PunctuationMark::from_class(42), PunctuationMark::None);
+    }
+}
@@ -0,0 +1,345 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::SessionJournal;
+
+const IN_FLIGHT_META: &str = "in_flight_session.json";
+const IN_FLIGHT_AUDIO: &str = "in_flight_audio.pcm";
+const RECOVERED_META: &str = "recovered_session.json";
+const RECOVERED_AUDIO: &str = "recovered_audio.pcm";
+
+/// Метаданные незавершённой сессии записи (пишутся инкрементально)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionJournalMeta {
+    pub started_at_ms: i64,
+    pub updated_at_ms: i64,
+    pub provider: String,
+    pub sample_rate: u32,
+    /// Сколько семплов было записано на момент последнего final.
+    /// Всё, что после — "хвост", который можно перераспознать.
+    pub samples_at_last_final: u64,
+    pub finals: Vec<String>,
+}
+
+/// Сессия, оставшаяся от предыдущего (аварийно завершённого) запуска
+#[derive(Debug, Clone)]
+pub struct RecoveredSession {
+    pub meta: SessionJournalMeta,
+    pub audio_path: Option<PathBuf>,
+}
+
+impl RecoveredSession {
+    /// Текст, собранный из final-сегментов
+    pub fn text(&self) -> String {
+        self.meta.finals.join(" ")
+    }
+
+    /// Общая длительность сохранённого аудио (мс)
+    pub fn audio_duration_ms(&self) -> u64 {
+        let Some(path) = self.audio_path.as_ref() else {
+            return 0;
+        };
+        let bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        samples_to_ms(bytes / 2, self.meta.sample_rate)
+    }
+
+    /// Длительность "хвоста" после последнего final (мс)
+    pub fn tail_duration_ms(&self) -> u64 {
+        let total = self.audio_duration_ms();
+        total.saturating_sub(samples_to_ms(self.meta.samples_at_last_final, self.meta.sample_rate))
+    }
+
+    /// Читает аудио после последнего final (i16 mono)
+    pub fn read_tail_samples(&self) -> Result<Vec<i16>> {
+        let Some(path) = self.audio_path.as_ref() else {
+            return Ok(Vec::new());
+        };
+
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(self.meta.samples_at_last_final * 2))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        // Последний семпл мог быть записан наполовину (краш посреди записи) — отбрасываем его.
+        Ok(bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect())
+    }
+}
+
+fn samples_to_ms(samples: u64, sample_rate: u32) -> u64 {
+    if sample_rate == 0 {
+        return 0;
+    }
+    samples * 1000 / sample_rate as u64
+}
+
+#[derive(Default)]
+struct JournalState {
+    active: bool,
+    meta: SessionJournalMeta,
+    /// Без буфера: каждый чанк — одна запись в файл, при краше процесса в памяти ничего не остаётся
+    audio: Option<File>,
+    samples_written: u64,
+}
+
+/// Файловый журнал незавершённой сессии записи (crash-safe recovery).
+///
+/// Раскладка в директории:
+/// - `in_flight_session.json` + `in_flight_audio.pcm` — текущая запись (удаляются при штатной остановке)
+/// - `recovered_session.json` + `recovered_audio.pcm` — то, что осталось от упавшего запуска
+///
+/// При открытии журнала "in-flight" файлы от прошлого процесса переносятся в "recovered",
+/// чтобы новая запись их не перезаписала до того, как пользователь решит, что с ними делать.
+pub struct FileSessionJournal {
+    dir: PathBuf,
+    state: Mutex<JournalState>,
+}
+
+impl FileSessionJournal {
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Self::rescue_in_flight(&dir)?;
+        Ok(Self {
+            dir,
+            state: Mutex::new(JournalState::default()),
+        })
+    }
+
+    /// Сессия от предыдущего запуска (если есть)
+    pub fn load_recovered(dir: &Path) -> Result<Option<RecoveredSession>> {
+        let meta_path = dir.join(RECOVERED_META);
+        if !meta_path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read_to_string(&meta_path)?;
+        let meta: SessionJournalMeta = serde_json::from_str(&json)?;
+        let audio_path = dir.join(RECOVERED_AUDIO);
+        Ok(Some(RecoveredSession {
+            meta,
+            audio_path: audio_path.exists().then_some(audio_path),
+        }))
+    }
+
+    /// Удаляет восстановленную сессию (пользователь её принял или отказался)
+    pub fn discard_recovered(dir: &Path) -> Result<()> {
+        for name in [RECOVERED_META, RECOVERED_AUDIO] {
+            let path = dir.join(name);
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn rescue_in_flight(dir: &Path) -> Result<()> {
+        let meta = dir.join(IN_FLIGHT_META);
+        let audio = dir.join(IN_FLIGHT_AUDIO);
+        if !meta.exists() {
+            // Аудио без метаданных восстановить нельзя (не знаем sample rate)
+            let _ = std::fs::remove_file(&audio);
+            return Ok(());
+        }
+
+        let audio_len = std::fs::metadata(&audio).map(|m| m.len()).unwrap_or(0);
+        let has_finals = std::fs::read_to_string(&meta)
+            .ok()
+            .and_then(|json| serde_json::from_str::<SessionJournalMeta>(&json).ok())
+            .map(|m| !m.finals.is_empty())
+            .unwrap_or(false);
+
+        if audio_len < 2 && !has_finals {
+            // Пустая сессия — восстанавливать нечего
+            let _ = std::fs::remove_file(&meta);
+            let _ = std::fs::remove_file(&audio);
+            return Ok(());
+        }
+
+        log::warn!("Found unfinished recording session from previous launch - keeping it for recovery");
+        Self::discard_recovered(dir)?;
+        std::fs::rename(&meta, dir.join(RECOVERED_META))?;
+        if audio.exists() {
+            std::fs::rename(&audio, dir.join(RECOVERED_AUDIO))?;
+        }
+        Ok(())
+    }
+
+    fn write_meta(&self, meta: &SessionJournalMeta) -> Result<()> {
+        let path = self.dir.join(IN_FLIGHT_META);
+        let tmp = self.dir.join(format!("{}.tmp", IN_FLIGHT_META));
+        std::fs::write(&tmp, serde_json::to_string(meta)?)?;
+        let _ = std::fs::remove_file(&path);
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, JournalState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SessionJournal for FileSessionJournal {
+    fn begin(&self, provider: &str) {
+        let mut state = self.lock();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let audio = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.dir.join(IN_FLIGHT_AUDIO));
+
+        *state = JournalState {
+            active: true,
+            meta: SessionJournalMeta {
+                started_at_ms: now,
+                updated_at_ms: now,
+                provider: provider.to_string(),
+                ..Default::default()
+            },
+            audio: match audio {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    log::warn!("Session journal: failed to open audio buffer: {}", e);
+                    None
+                }
+            },
+            samples_written: 0,
+        };
+
+        if let Err(e) = self.write_meta(&state.meta) {
+            log::warn!("Session journal: failed to write metadata: {}", e);
+        }
+    }
+
+    fn append_audio(&self, samples: &[i16], sample_rate: u32) {
+        let mut state = self.lock();
+        if !state.active {
+            return;
+        }
+
+        if state.meta.sample_rate == 0 {
+            state.meta.sample_rate = sample_rate;
+            if let Err(e) = self.write_meta(&state.meta) {
+                log::warn!("Session journal: failed to write metadata: {}", e);
+            }
+        }
+
+        let Some(writer) = state.audio.as_mut() else {
+            return;
+        };
+
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        if let Err(e) = writer.write_all(&bytes) {
+            log::warn!("Session journal: failed to append audio, disabling audio journal: {}", e);
+            state.audio = None;
+            return;
+        }
+
+        state.samples_written += samples.len() as u64;
+    }
+
+    fn append_final(&self, text: &str) {
+        let mut state = self.lock();
+        if !state.active {
+            return;
+        }
+
+        state.meta.samples_at_last_final = state.samples_written;
+        state.meta.finals.push(text.trim().to_string());
+        state.meta.updated_at_ms = chrono::Utc::now().timestamp_millis();
+
+        if let Err(e) = self.write_meta(&state.meta) {
+            log::warn!("Session journal: failed to write metadata: {}", e);
+        }
+    }
+
+    fn complete(&self) {
+        let mut state = self.lock();
+        if !state.active {
+            return;
+        }
+        *state = JournalState::default();
+
+        let _ = std::fs::remove_file(self.dir.join(IN_FLIGHT_META));
+        let _ = std::fs::remove_file(self.dir.join(IN_FLIGHT_AUDIO));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("voice-to-text-journal-{}", Uuid::new_v4()))
+    }
+
+    /// Имитируем краш: без complete() и без деструкторов — на диске только то, что уже записано
+    fn crash(journal: FileSessionJournal) {
+        std::mem::forget(journal);
+    }
+
+    #[test]
+    fn unfinished_session_is_recovered_on_next_open() {
+        let dir = temp_dir();
+        let journal = FileSessionJournal::open(&dir).unwrap();
+        journal.begin("Deepgram");
+        journal.append_audio(&[1, 2, 3, 4], 16000);
+        journal.append_final("hello world");
+        journal.append_audio(&[5, 6], 16000);
+        crash(journal);
+
+        let _journal = FileSessionJournal::open(&dir).unwrap();
+        let recovered = FileSessionJournal::load_recovered(&dir).unwrap().unwrap();
+        assert_eq!(recovered.text(), "hello world");
+        assert_eq!(recovered.meta.provider, "Deepgram");
+        assert_eq!(recovered.meta.sample_rate, 16000);
+        assert_eq!(recovered.read_tail_samples().unwrap(), vec![5, 6]);
+
+        FileSessionJournal::discard_recovered(&dir).unwrap();
+        assert!(FileSessionJournal::load_recovered(&dir).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn completed_session_leaves_nothing_to_recover() {
+        let dir = temp_dir();
+        let journal = FileSessionJournal::open(&dir).unwrap();
+        journal.begin("Backend");
+        journal.append_audio(&[1, 2, 3], 16000);
+        journal.append_final("done");
+        journal.complete();
+
+        // После complete() поздние final игнорируются
+        journal.append_final("late");
+        drop(journal);
+
+        let _journal = FileSessionJournal::open(&dir).unwrap();
+        assert!(FileSessionJournal::load_recovered(&dir).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn empty_unfinished_session_is_dropped() {
+        let dir = temp_dir();
+        let journal = FileSessionJournal::open(&dir).unwrap();
+        journal.begin("Deepgram");
+        crash(journal);
+
+        let _journal = FileSessionJournal::open(&dir).unwrap();
+        assert!(FileSessionJournal::load_recovered(&dir).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,163 @@
+//! Ссылки на расшифровку сессии через наш бэкенд.
+//!
+//! Текст шифруется на устройстве (AES-256-GCM, новый ключ на каждую ссылку): бэкенд хранит
+//! только шифротекст, а ключ уходит получателю во fragment ссылки (`#key=...`), который браузер
+//! на сервер не отправляет. Ссылка живёт ограниченное время и может быть отозвана раньше.
+//! Аудио сессий приложение не хранит (журнал записи удаляется при остановке) — делимся только текстом.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+const SHARE_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Errors of the share backend
+#[derive(Debug, thiserror::Error)]
+pub enum ShareError {
+    #[error("Not authenticated")]
+    Unauthenticated,
+
+    #[error("Share not found")]
+    NotFound,
+
+    #[error("Share request failed: {0}")]
+    Request(String),
+}
+
+/// Зашифрованная расшифровка; ключ на бэкенд не отправляется
+pub struct EncryptedTranscript {
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub key: Vec<u8>,
+}
+
+pub fn encrypt_transcript(text: &str) -> Result<EncryptedTranscript, ShareError> {
+    let key = Aes256Gcm::generate_key(OsRng);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(&key)
+        .encrypt(&nonce, text.as_bytes())
+        .map_err(|e| ShareError::Request(format!("encryption failed: {}", e)))?;
+    Ok(EncryptedTranscript {
+        ciphertext,
+        nonce: nonce.to_vec(),
+        key: key.to_vec(),
+    })
+}
+
+/// Ссылка для получателя: ключ во fragment, чтобы он не попал ни на сервер, ни в его логи
+pub fn share_url_with_key(url: &str, key: &[u8]) -> String {
+    format!("{}#key={}", url, URL_SAFE_NO_PAD.encode(key))
+}
+
+#[derive(Serialize)]
+struct CreateShareRequest {
+    algorithm: &'static str,
+    ciphertext: String,
+    nonce: String,
+    expires_in_secs: u64,
+}
+
+/// envelope: { data: { id, url } }
+#[derive(Deserialize)]
+struct CreateShareResponse {
+    data: CreatedShare,
+}
+
+/// Share created by the backend (URL without the decryption key)
+#[derive(Debug, Deserialize)]
+pub struct CreatedShare {
+    pub id: String,
+    pub url: String,
+}
+
+/// Client of `/api/v1/shares`
+pub struct BackendShareClient {
+    api_base_url: String,
+    access_token: String,
+}
+
+impl BackendShareClient {
+    pub fn new(api_base_url: impl Into<String>, access_token: impl Into<String>) -> Self {
+        Self {
+            api_base_url: api_base_url.into(),
+            access_token: access_token.into(),
+        }
+    }
+
+    pub async fn create(&self, transcript: &EncryptedTranscript, expires_in_secs: u64) -> Result<CreatedShare, ShareError> {
+        let request = CreateShareRequest {
+            algorithm: "aes-256-gcm",
+            ciphertext: STANDARD.encode(&transcript.ciphertext),
+            nonce: STANDARD.encode(&transcript.nonce),
+            expires_in_secs,
+        };
+        let response = reqwest::Client::new()
+            .post(format!("{}/api/v1/shares", self.api_base_url))
+            .timeout(std::time::Duration::from_secs(SHARE_REQUEST_TIMEOUT_SECS))
+            .bearer_auth(&self.access_token)
+            .header("X-Client-Type", "native")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| ShareError::Request(e.to_string()))?;
+
+        let response = check_status(response)?;
+        let body: CreateShareResponse = response
+            .json()
+            .await
+            .map_err(|e| ShareError::Request(format!("invalid response: {}", e)))?;
+        Ok(body.data)
+    }
+
+    pub async fn revoke(&self, share_id: &str) -> Result<(), ShareError> {
+        let response = reqwest::Client::new()
+            .delete(format!(
+                "{}/api/v1/shares/{}",
+                self.api_base_url,
+                urlencoding::encode(share_id)
+            ))
+            .timeout(std::time::Duration::from_secs(SHARE_REQUEST_TIMEOUT_SECS))
+            .bearer_auth(&self.access_token)
+            .header("X-Client-Type", "native")
+            .send()
+            .await
+            .map_err(|e| ShareError::Request(e.to_string()))?;
+        check_status(response).map(|_| ())
+    }
+}
+
+fn check_status(response: reqwest::Response) -> Result<reqwest::Response, ShareError> {
+    match response.status() {
+        reqwest::StatusCode::UNAUTHORIZED => Err(ShareError::Unauthenticated),
+        reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => Err(ShareError::NotFound),
+        status if !status.is_success() => Err(ShareError::Request(format!("HTTP {}", status))),
+        _ => Ok(response),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcript_is_encrypted_with_a_fresh_key() {
+        let first = encrypt_transcript("Привет, мир").unwrap();
+        let second = encrypt_transcript("Привет, мир").unwrap();
+        assert_eq!(first.key.len(), 32);
+        assert_ne!(first.key, second.key);
+        assert_ne!(first.ciphertext, "Привет, мир".as_bytes());
+
+        let cipher = Aes256Gcm::new_from_slice(&first.key).unwrap();
+        let nonce = aes_gcm::Nonce::from_slice(&first.nonce);
+        let plain = cipher.decrypt(nonce, first.ciphertext.as_slice()).unwrap();
+        assert_eq!(String::from_utf8(plain).unwrap(), "Привет, мир");
+    }
+
+    #[test]
+    fn key_goes_into_the_url_fragment() {
+        let url = share_url_with_key("https://voicetext.site/s/abc", &[0xfb, 0xff]);
+        assert_eq!(url, "https://voicetext.site/s/abc#key=-_8");
+    }
+}
@@ -0,0 +1,199 @@
+// Подавляем warnings от старой версии objc crate (см. auto_paste.rs).
+#![allow(unexpected_cfgs)]
+
+//! Озвучивание текста системным синтезатором речи (проверка распознанного "на слух").
+//!
+//! macOS — AVSpeechSynthesizer, Windows — SAPI (System.Speech через PowerShell),
+//! Linux — speech-dispatcher (`spd-say`). Новая фраза прерывает предыдущую.
+
+use anyhow::Result;
+
+/// Язык голоса из языка распознавания; None — голос системы по умолчанию
+pub fn speech_language(stt_language: &str) -> Option<String> {
+    let language = stt_language.trim();
+    match language.to_ascii_lowercase().as_str() {
+        "" | "auto" | "multi" => None,
+        _ => Some(language.to_string()),
+    }
+}
+
+/// Начинает озвучивать `text` и сразу возвращается; уже звучащая фраза прерывается
+pub fn speak(text: &str, language: Option<&str>) -> Result<()> {
+    log::info!("Speaking {} chars (language: {:?})", text.chars().count(), language);
+    platform::speak(text, language)
+}
+
+/// Останавливает озвучивание (если что-то звучит)
+pub fn stop_speaking() {
+    platform::stop();
+}
+
+/// Процесс озвучивания получает текст через stdin: ни кавычек, ни экранирования в аргументах
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+fn spawn_with_stdin(mut command: std::process::Command, text: &str) -> Result<std::process::Child> {
+    use anyhow::Context;
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to start {:?}", command.get_program()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).context("Failed to pass text to the speech process")?;
+    }
+    Ok(child)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::sync::OnceLock;
+
+    use anyhow::Result;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::runtime::BOOL;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {}
+
+    /// AVSpeechBoundaryImmediate
+    const BOUNDARY_IMMEDIATE: isize = 0;
+
+    /// Синтезатор живёт всё время работы приложения: освобождённый синтезатор замолкает
+    static SYNTHESIZER: OnceLock<usize> = OnceLock::new();
+
+    fn synthesizer() -> id {
+        *SYNTHESIZER.get_or_init(|| unsafe {
+            let synthesizer: id = msg_send![class!(AVSpeechSynthesizer), new];
+            synthesizer as usize
+        }) as id
+    }
+
+    pub fn speak(text: &str, language: Option<&str>) -> Result<()> {
+        unsafe {
+            // Вызов идёт не из главного потока: без своего пула autorelease-объекты утекут
+            let pool: id = msg_send![class!(NSAutoreleasePool), new];
+            let synthesizer = synthesizer();
+            let _: BOOL = msg_send![synthesizer, stopSpeakingAtBoundary: BOUNDARY_IMMEDIATE];
+
+            let ns_text = NSString::alloc(nil).init_str(text);
+            let utterance: id = msg_send![class!(AVSpeechUtterance), speechUtteranceWithString: ns_text];
+            if let Some(language) = language {
+                let ns_language = NSString::alloc(nil).init_str(language);
+                let voice: id = msg_send![class!(AVSpeechSynthesisVoice), voiceWithLanguage: ns_language];
+                if voice != nil {
+                    let _: () = msg_send![utterance, setVoice: voice];
+                } else {
+                    log::debug!("No system voice for '{}', using the default one", language);
+                }
+                let _: () = msg_send![ns_language, release];
+            }
+            let _: () = msg_send![synthesizer, speakUtterance: utterance];
+
+            let _: () = msg_send![ns_text, release];
+            let _: () = msg_send![pool, drain];
+        }
+        Ok(())
+    }
+
+    pub fn stop() {
+        if SYNTHESIZER.get().is_none() {
+            return;
+        }
+        unsafe {
+            let _: BOOL = msg_send![synthesizer(), stopSpeakingAtBoundary: BOUNDARY_IMMEDIATE];
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::os::windows::process::CommandExt;
+    use std::process::{Child, Command};
+    use std::sync::Mutex;
+
+    use anyhow::Result;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    /// Голос выбирается по культуре, если такой установлен; текст читается из stdin в UTF-8
+    const SPEAK_SCRIPT: &str = "Add-Type -AssemblyName System.Speech; \
+        [Console]::InputEncoding = [Text.Encoding]::UTF8; \
+        $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+        if ($env:VOICETEXT_TTS_LANG) { try { $s.SelectVoiceByHints('NotSet', 'NotSet', 0, \
+            [Globalization.CultureInfo]::GetCultureInfo($env:VOICETEXT_TTS_LANG)) } catch {} }; \
+        $s.Speak([Console]::In.ReadToEnd())";
+
+    /// Процесс PowerShell, который сейчас говорит (убиваем его, чтобы замолчать)
+    static SPEAKING: Mutex<Option<Child>> = Mutex::new(None);
+
+    pub fn speak(text: &str, language: Option<&str>) -> Result<()> {
+        stop();
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command", SPEAK_SCRIPT])
+            .env("VOICETEXT_TTS_LANG", language.unwrap_or_default())
+            .creation_flags(CREATE_NO_WINDOW);
+        let child = super::spawn_with_stdin(command, text)?;
+        if let Ok(mut speaking) = SPEAKING.lock() {
+            *speaking = Some(child);
+        }
+        Ok(())
+    }
+
+    pub fn stop() {
+        let Some(mut child) = SPEAKING.lock().ok().and_then(|mut speaking| speaking.take()) else {
+            return;
+        };
+        if matches!(child.try_wait(), Ok(None)) {
+            let _ = child.kill();
+        }
+        let _ = child.wait();
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+mod platform {
+    use std::process::Command;
+
+    use anyhow::Result;
+
+    pub fn speak(text: &str, language: Option<&str>) -> Result<()> {
+        stop();
+        let mut command = Command::new("spd-say");
+        command.arg("-e");
+        if let Some(language) = language {
+            command.args(["-l", language]);
+        }
+        let mut child = super::spawn_with_stdin(command, text)?;
+        // spd-say только ставит текст в очередь speech-dispatcher и сразу завершается
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(())
+    }
+
+    pub fn stop() {
+        if let Err(e) = Command::new("spd-say").arg("-C").status() {
+            log::debug!("Failed to cancel speech-dispatcher output: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_detected_language_uses_the_default_voice() {
+        assert_eq!(speech_language("ru").as_deref(), Some("ru"));
+        assert_eq!(speech_language(" en-US ").as_deref(), Some("en-US"));
+        assert_eq!(speech_language("multi"), None);
+        assert_eq!(speech_language("Auto"), None);
+        assert_eq!(speech_language(""), None);
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Файл рядом с исполняемым файлом, включающий портативный режим (содержимое не важно)
+pub const PORTABLE_FLAG_FILE: &str = "portable.txt";
+
+/// Аргумент командной строки, включающий портативный режим
+pub const PORTABLE_ARG: &str = "--portable";
+
+/// Папка данных портативного режима, рядом с исполняемым файлом
+pub const PORTABLE_DATA_DIR_NAME: &str = "data";
+
+static PORTABLE_DATA_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Папка данных портативного режима; None — обычный режим (данные в профиле пользователя ОС).
+///
+/// В портативном режиме настройки, история, журнал записи и модели лежат в `data/` рядом
+/// с исполняемым файлом — приложение запускается с флешки и ничего не оставляет на машине.
+/// Режим определяется один раз за запуск.
+pub fn portable_data_dir() -> Option<&'static Path> {
+    PORTABLE_DATA_DIR
+        .get_or_init(|| {
+            let exe = std::env::current_exe().ok()?;
+            detect_portable_data_dir(exe.parent()?, std::env::args())
+        })
+        .as_deref()
+}
+
+fn detect_portable_data_dir<I, S>(exe_dir: &Path, args: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let enabled = args.into_iter().any(|arg| arg.as_ref() == PORTABLE_ARG) || exe_dir.join(PORTABLE_FLAG_FILE).is_file();
+    enabled.then(|| exe_dir.join(PORTABLE_DATA_DIR_NAME))
+}
+
+/// Закрывает папку с данными приложения от других пользователей машины.
+///
+/// На Unix домашняя папка часто открыта на чтение (0755), а с ней и созданные нами папки:
+/// история и аудио сессий были бы видны другим учётным записям. Ставим 0700, только если
+/// у группы или остальных есть хоть какие-то права. На Windows данные лежат в профиле
+/// пользователя (%APPDATA%), ACL которого уже закрыт от остальных.
+#[cfg(unix)]
+pub fn restrict_to_owner(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = std::fs::metadata(dir)?.permissions();
+    if permissions.mode() & 0o077 == 0 {
+        return Ok(());
+    }
+    permissions.set_mode(0o700);
+    std::fs::set_permissions(dir, permissions)?;
+    log::info!("Restricted access to {:?} to the current user", dir);
+    Ok(())
+}
+
+/// Закрывает папку с данными приложения от других пользователей машины.
+#[cfg(not(unix))]
+pub fn restrict_to_owner(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn portable_mode_is_enabled_by_flag_file_or_arg() {
+        let exe_dir = std::env::temp_dir().join(format!("voice-to-text-portable-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&exe_dir).unwrap();
+        let data_dir = exe_dir.join(PORTABLE_DATA_DIR_NAME);
+
+        assert_eq!(detect_portable_data_dir(&exe_dir, ["app"]), None);
+        assert_eq!(detect_portable_data_dir(&exe_dir, ["app", "--portable"]), Some(data_dir.clone()));
+
+        std::fs::write(exe_dir.join(PORTABLE_FLAG_FILE), "").unwrap();
+        assert_eq!(detect_portable_data_dir(&exe_dir, ["app"]), Some(data_dir));
+
+        let _ = std::fs::remove_dir_all(exe_dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shared_directory_becomes_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("voice-to-text-storage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        restrict_to_owner(&dir).unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        // Повторный вызов ничего не меняет
+        restrict_to_owner(&dir).unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,18 +1,16 @@
 use async_trait::async_trait;
-use futures_util::{SinkExt, StreamExt};
-use http::Request;
 use serde_json::{json, Value};
 use std::sync::Arc;
 use tokio::sync::Notify;
-use tokio::task::JoinHandle;
-use tokio_tungstenite::{connect_async, tungstenite::Message, WebSocketStream, MaybeTlsStream};
-use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
 
 use crate::domain::{
     AudioChunk, SttConfig, SttConnectionCategory, SttConnectionError, SttError, SttProvider,
     SttResult, Transcription, TranscriptionCallback,
 };
 use crate::infrastructure::embedded_keys;
+use super::stream_state::{StreamLifecycle, StreamState};
+use super::ws_transport::{SttMessageCodec, WsSttTransport};
 
 /// AssemblyAI Universal-Streaming STT provider (v3)
 ///
@@ -27,30 +25,34 @@ use crate::infrastructure::embedded_keys;
 /// 4. Receive: SessionBegins, PartialTranscript, FinalTranscript, SessionTerminated
 const ASSEMBLYAI_WS_URL: &str = "wss://streaming.assemblyai.com/v3/ws";
 
-type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
-
 pub struct AssemblyAIProvider {
     config: Option<SttConfig>,
-    is_streaming: bool,
+    lifecycle: StreamLifecycle,
     api_key: Option<String>,
-    ws_write: Option<futures_util::stream::SplitSink<WsStream, Message>>,
-    receiver_task: Option<JoinHandle<()>>,
+    transport: Option<WsSttTransport>,
     session_ready: Arc<Notify>,
     audio_buffer: Vec<i16>, // Буфер для накопления аудио до минимального размера
+    endpoint: String, // WebSocket endpoint (переопределяется для mock-сервера в тестах)
 }
 
 impl AssemblyAIProvider {
     pub fn new() -> Self {
         Self {
             config: None,
-            is_streaming: false,
+            lifecycle: StreamLifecycle::new(),
             api_key: None,
-            ws_write: None,
-            receiver_task: None,
+            transport: None,
             session_ready: Arc::new(Notify::new()),
             audio_buffer: Vec::new(),
+            endpoint: ASSEMBLYAI_WS_URL.to_string(),
         }
     }
+
+    /// Подменяет WebSocket endpoint (например, на локальный mock-сервер)
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
 }
 
 impl Default for AssemblyAIProvider {
@@ -95,166 +97,25 @@ impl SttProvider for AssemblyAIProvider {
     ) -> SttResult<()> {
         log::info!("AssemblyAI Provider: Starting stream (v3 endpoint)");
 
-        if self.is_streaming {
-            return Err(SttError::Processing(
-                "Stream already active".to_string(),
-            ));
-        }
-
-        let api_key = self.api_key.as_ref()
-            .ok_or_else(|| SttError::Configuration("API key not set".to_string()))?
-            .clone();
-
-        // Получаем язык из конфига для использования в транскрипциях
-        let configured_language = self.config.as_ref()
-            .and_then(|c| Some(c.language.clone()))
-            .unwrap_or_else(|| "en".to_string());
-
-        // 1. Build URL with query parameters
-        let language = configured_language.clone();
-
-        // Конвертируем короткие коды языков в полные BCP-47 для AssemblyAI
-        let language_code = match language.as_str() {
-            "ru" => "ru",  // Russian
-            "en" => "en",  // English (global)
-            "es" => "es",  // Spanish
-            "fr" => "fr",  // French
-            "de" => "de",  // German
-            "it" => "it",  // Italian
-            "pt" => "pt",  // Portuguese
-            "nl" => "nl",  // Dutch
-            "ja" => "ja",  // Japanese
-            "ko" => "ko",  // Korean
-            "zh" => "zh",  // Chinese
-            other => other, // Pass as-is
-        };
-
-        let url = format!(
-            "{}?sample_rate=16000&encoding=pcm_s16le&language_code={}",
-            ASSEMBLYAI_WS_URL,
-            language_code
-        );
-
-        log::debug!("Connecting to {}", url);
-
-        let request = Request::builder()
-            .method("GET")
-            .uri(&url)
-            .header("Host", "streaming.assemblyai.com")
-            .header("Connection", "Upgrade")
-            .header("Upgrade", "websocket")
-            .header("Sec-WebSocket-Version", "13")
-            .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
-            .header("Authorization", &api_key)
-            .body(())
-            .map_err(|e| {
-                SttError::Connection(SttConnectionError::simple(format!(
-                    "Failed to build WS request: {}",
-                    e
-                )))
-            })?;
-
-        let (ws_stream, _response) = connect_async(request)
-            .await
-            .map_err(|e| {
-                SttError::Connection(SttConnectionError::simple(format!(
-                    "WS connection failed: {}",
-                    e
-                )))
-            })?;
-
-        log::info!("AssemblyAI WebSocket connected");
-
-        // Split stream for concurrent read/write
-        let (write, mut read) = ws_stream.split();
-
-        // Пересоздаем Notify для новой сессии (фикс повторного использования)
-        self.session_ready = Arc::new(Notify::new());
-
-        // 2. Spawn background task for receiving messages
-        let session_notify = self.session_ready.clone();
-        let lang_for_transcription = configured_language.clone();
-        let receiver_task = tokio::spawn(async move {
-            log::debug!("AssemblyAI receiver task started");
-
-            while let Some(msg_result) = read.next().await {
-                match msg_result {
-                    Ok(Message::Text(text)) => {
-                        log::debug!("AssemblyAI received text message: {}", text);
-                        // Parse JSON message
-                        match serde_json::from_str::<Value>(&text) {
-                            Ok(json) => {
-                                let msg_type = json["type"].as_str();
-                                log::debug!("AssemblyAI message type: {:?}", msg_type);
-
-                                // Уведомляем что сессия готова при получении Begin
-                                if msg_type == Some("Begin") {
-                                    log::info!("AssemblyAI session began, ready to send audio");
-                                    session_notify.notify_one();
-                                }
-
-                                Self::handle_message(json, &on_partial, &on_final, &lang_for_transcription);
-                            }
-                            Err(e) => {
-                                log::error!("Failed to parse AssemblyAI message: {}", e);
-                                log::error!("Raw message: {}", text);
-                            }
-                        }
-                    }
-                    Ok(Message::Close(frame)) => {
-                        log::info!("AssemblyAI WebSocket closed: {:?}", frame);
-                        break;
-                    }
-                    Ok(Message::Binary(data)) => {
-                        log::debug!("AssemblyAI received binary message: {} bytes", data.len());
-                    }
-                    Ok(Message::Ping(_)) => {
-                        log::trace!("AssemblyAI received Ping");
-                    }
-                    Ok(Message::Pong(_)) => {
-                        log::trace!("AssemblyAI received Pong");
-                    }
-                    Err(e) => {
-                        log::error!("AssemblyAI WebSocket error: {}", e);
-                        break;
-                    }
-                    Ok(msg) => {
-                        log::warn!("AssemblyAI received unexpected message type: {:?}", msg);
-                    }
-                }
+        self.lifecycle.transition(StreamState::Connecting)?;
+        match self.open_stream(on_partial, on_final).await {
+            Ok(()) => {
+                self.lifecycle.transition(StreamState::Streaming)?;
+                Ok(())
             }
-
-            log::debug!("AssemblyAI receiver task ended");
-        });
-
-        self.ws_write = Some(write);
-        self.receiver_task = Some(receiver_task);
-        self.is_streaming = true;
-
-        // Ждем пока сессия будет готова (получим SessionBegins)
-        log::info!("Waiting for session to be ready...");
-        tokio::time::timeout(
-            std::time::Duration::from_secs(5),
-            self.session_ready.notified()
-        )
-        .await
-        .map_err(|_| {
-            SttError::Connection(SttConnectionError::with_category(
-                "Timeout waiting for SessionBegins".to_string(),
-                SttConnectionCategory::Timeout,
-            ))
-        })?;
-
-        log::info!("AssemblyAI stream started successfully");
-        Ok(())
+            Err(e) => {
+                self.lifecycle.force_close();
+                Err(e)
+            }
+        }
     }
 
     async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
-        if !self.is_streaming {
-            return Err(SttError::Processing("Not streaming".to_string()));
+        if !self.lifecycle.is_open() {
+            return Err(SttError::Processing(format!("Not streaming ({:?})", self.lifecycle.state())));
         }
 
-        let write = self.ws_write.as_mut()
+        let transport = self.transport.as_ref()
             .ok_or_else(|| SttError::Processing("WebSocket write handle not available".to_string()))?;
 
         // Проверяем уровень сигнала для детекции тишины
@@ -268,12 +129,14 @@ impl SttProvider for AssemblyAIProvider {
         // Добавляем чанк в буфер
         self.audio_buffer.extend_from_slice(&chunk.data);
 
-        // AssemblyAI требует минимум 50ms аудио
-        // 50ms @ 16kHz = 800 samples
-        const MIN_SAMPLES: usize = 800;
+        // AssemblyAI требует от 50ms до 1000ms аудио в сообщении (stt.audio_chunking, по умолчанию 50ms = 800 samples)
+        let min_samples = self
+            .config
+            .as_ref()
+            .map_or(800, |config| config.chunk_samples(16000));
 
         // Отправляем когда накопилось достаточно
-        if self.audio_buffer.len() >= MIN_SAMPLES {
+        if self.audio_buffer.len() >= min_samples {
             // Convert i16 samples to bytes (little-endian PCM)
             let bytes: Vec<u8> = self.audio_buffer
                 .iter()
@@ -291,7 +154,7 @@ impl SttProvider for AssemblyAIProvider {
             self.audio_buffer.clear();
 
             // Send as binary message (AssemblyAI v3 expects raw PCM binary data)
-            write
+            transport
                 .send(Message::Binary(bytes))
                 .await
                 .map_err(|e| SttError::Processing(format!("Failed to send audio: {}", e)))?;
@@ -303,43 +166,37 @@ impl SttProvider for AssemblyAIProvider {
     async fn stop_stream(&mut self) -> SttResult<()> {
         log::info!("AssemblyAI Provider: Stopping stream");
 
-        if !self.is_streaming {
-            log::warn!("Stream not active");
+        if let Err(e) = self.lifecycle.transition(StreamState::Closing) {
+            log::warn!("Stream not active: {}", e);
             return Ok(());
         }
 
         // Отправляем остатки из буфера если есть
         if !self.audio_buffer.is_empty() {
-            if let Some(write) = self.ws_write.as_mut() {
+            if let Some(transport) = self.transport.as_ref() {
                 let bytes: Vec<u8> = self.audio_buffer
                     .iter()
                     .flat_map(|&sample| sample.to_le_bytes())
                     .collect();
 
                 log::debug!("Flushing remaining {} samples from buffer", self.audio_buffer.len());
-                let _ = write.send(Message::Binary(bytes)).await;
+                let _ = transport.send(Message::Binary(bytes)).await;
                 self.audio_buffer.clear();
             }
         }
 
         // Send terminate message (optional for v3, but good practice)
-        if let Some(write) = self.ws_write.as_mut() {
+        if let Some(transport) = self.transport.take() {
             let terminate_msg = json!({
                 "terminate_session": true
             });
 
-            let _ = write.send(Message::Text(terminate_msg.to_string())).await;
-            let _ = write.send(Message::Close(None)).await;
+            let _ = transport.send(Message::Text(terminate_msg.to_string())).await;
+            let _ = transport.send(Message::Close(None)).await;
+            transport.shutdown().await;
         }
 
-        // Abort receiver task
-        if let Some(task) = self.receiver_task.take() {
-            task.abort();
-            let _ = task.await; // Ignore cancellation error
-        }
-
-        self.ws_write = None;
-        self.is_streaming = false;
+        self.lifecycle.force_close();
 
         log::info!("AssemblyAI stream stopped");
         Ok(())
@@ -349,13 +206,11 @@ impl SttProvider for AssemblyAIProvider {
         log::info!("AssemblyAI Provider: Aborting stream");
 
         // Immediate shutdown - abort task without graceful close
-        if let Some(task) = self.receiver_task.take() {
-            task.abort();
-            let _ = task.await;
+        if let Some(transport) = self.transport.take() {
+            transport.shutdown().await;
         }
 
-        self.ws_write = None;
-        self.is_streaming = false;
+        self.lifecycle.force_close();
         self.audio_buffer.clear();
 
         log::info!("AssemblyAI stream aborted");
@@ -366,12 +221,100 @@ impl SttProvider for AssemblyAIProvider {
         "AssemblyAI Universal-Streaming (v3)"
     }
 
+    fn debug_state(&self) -> crate::domain::SttProviderDebugState {
+        crate::domain::SttProviderDebugState {
+            streaming: self.lifecycle.is_streaming(),
+            paused: self.lifecycle.is_paused(),
+            buffered_samples: self.audio_buffer.len(),
+            receiver_alive: self.transport.as_ref().map(|t| t.is_alive()),
+        }
+    }
+
     fn is_online(&self) -> bool {
         true
     }
 }
 
 impl AssemblyAIProvider {
+    /// Подключается и ждёт SessionBegins (состояние ведёт start_stream)
+    async fn open_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+    ) -> SttResult<()> {
+        let api_key = self.api_key.as_ref()
+            .ok_or_else(|| SttError::Configuration("API key not set".to_string()))?
+            .clone();
+
+        // Получаем язык из конфига для использования в транскрипциях
+        let configured_language = self.config.as_ref()
+            .and_then(|c| Some(c.language.clone()))
+            .unwrap_or_else(|| "en".to_string());
+
+        // 1. Build URL with query parameters
+        let language = configured_language.clone();
+
+        // Конвертируем короткие коды языков в полные BCP-47 для AssemblyAI
+        let language_code = match language.as_str() {
+            "ru" => "ru",  // Russian
+            "en" => "en",  // English (global)
+            "es" => "es",  // Spanish
+            "fr" => "fr",  // French
+            "de" => "de",  // German
+            "it" => "it",  // Italian
+            "pt" => "pt",  // Portuguese
+            "nl" => "nl",  // Dutch
+            "ja" => "ja",  // Japanese
+            "ko" => "ko",  // Korean
+            "zh" => "zh",  // Chinese
+            other => other, // Pass as-is
+        };
+
+        let url = format!(
+            "{}?sample_rate=16000&encoding=pcm_s16le&language_code={}",
+            self.endpoint,
+            language_code
+        );
+
+        log::debug!("Connecting to {}", url);
+
+        let host = self.endpoint
+            .parse::<http::Uri>()
+            .ok()
+            .and_then(|uri| uri.authority().map(|a| a.to_string()))
+            .unwrap_or_else(|| "streaming.assemblyai.com".to_string());
+
+        let request = WsSttTransport::request(&url, &host, &api_key)?;
+
+        // Пересоздаем Notify для новой сессии (фикс повторного использования)
+        self.session_ready = Arc::new(Notify::new());
+
+        let codec = AssemblyAICodec {
+            on_partial,
+            on_final,
+            session_notify: self.session_ready.clone(),
+            language: configured_language,
+        };
+        self.transport = Some(WsSttTransport::connect(request, None, codec).await?);
+
+        // Ждем пока сессия будет готова (получим SessionBegins)
+        log::info!("Waiting for session to be ready...");
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            self.session_ready.notified()
+        )
+        .await
+        .map_err(|_| {
+            SttError::Connection(SttConnectionError::with_category(
+                "Timeout waiting for SessionBegins".to_string(),
+                SttConnectionCategory::Timeout,
+            ))
+        })?;
+
+        log::info!("AssemblyAI stream started successfully");
+        Ok(())
+    }
+
     /// Обрабатываем входящее сообщение от AssemblyAI
     fn handle_message(
         json: Value,
@@ -418,6 +361,11 @@ impl AssemblyAIProvider {
                                     .as_secs() as i64,
                                 start: 0.0, // AssemblyAI не предоставляет start время
                                 duration: 0.0, // AssemblyAI не предоставляет duration
+                                alternatives: Vec::new(), // Universal-Streaming не отдаёт n-best
+                                speaker: None,
+                                channel: None,
+                                calendar: None,
+                                app: None,
                             };
 
                             on_final(transcription);
@@ -435,6 +383,11 @@ impl AssemblyAIProvider {
                                     .as_secs() as i64,
                                 start: 0.0, // AssemblyAI не предоставляет start время
                                 duration: 0.0, // AssemblyAI не предоставляет duration
+                                alternatives: Vec::new(), // Universal-Streaming не отдаёт n-best
+                                speaker: None,
+                                channel: None,
+                                calendar: None,
+                                app: None,
                             };
 
                             on_partial(transcription);
@@ -464,3 +417,40 @@ impl AssemblyAIProvider {
         }
     }
 }
+
+/// Разбор сообщений AssemblyAI v3 в receiver task
+struct AssemblyAICodec {
+    on_partial: TranscriptionCallback,
+    on_final: TranscriptionCallback,
+    session_notify: Arc<Notify>,
+    language: String,
+}
+
+#[async_trait]
+impl SttMessageCodec for AssemblyAICodec {
+    fn provider_name(&self) -> &'static str {
+        "AssemblyAI"
+    }
+
+    async fn on_text(&mut self, text: String) {
+        match serde_json::from_str::<Value>(&text) {
+            Ok(json) => {
+                let msg_type = json["type"].as_str();
+                log::debug!("AssemblyAI message type: {:?}", msg_type);
+
+                // Уведомляем что сессия готова при получении Begin
+                if msg_type == Some("Begin") {
+                    log::info!("AssemblyAI session began, ready to send audio");
+                    self.session_notify.notify_one();
+                }
+
+                AssemblyAIProvider::handle_message(json, &self.on_partial, &self.on_final, &self.language);
+            }
+            Err(e) => {
+                log::error!("Failed to parse AssemblyAI message: {}", e);
+                log::error!("Raw message: {}", text);
+            }
+        }
+    }
+}
+
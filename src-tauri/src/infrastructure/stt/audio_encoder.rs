@@ -0,0 +1,727 @@
+use crate::domain::AudioEncoding;
+
+/// Кодировщик аудио для streaming STT.
+///
+/// Один экземпляр = один поток (одно WebSocket соединение): FLAC/Ogg требуют заголовок
+/// в начале потока, поэтому при переподключении создаётся новый кодировщик.
+pub trait AudioEncoder: Send + Sync {
+    /// Фактический формат (может отличаться от запрошенного, если кодек недоступен в сборке)
+    fn encoding(&self) -> AudioEncoding;
+
+    /// Кодирует PCM (i16 mono). Возвращает 0..N готовых бинарных сообщений.
+    fn encode(&mut self, samples: &[i16]) -> Vec<Vec<u8>>;
+
+    /// Дописывает буферизованный остаток (конец потока)
+    fn flush(&mut self) -> Vec<Vec<u8>>;
+
+    /// Выбрасывает буферизованный остаток (пауза в keep-alive режиме)
+    fn discard_pending(&mut self);
+}
+
+/// Значение query-параметра `encoding` для Deepgram
+pub fn deepgram_encoding_param(encoding: AudioEncoding) -> &'static str {
+    match encoding {
+        AudioEncoding::Linear16 => "linear16",
+        AudioEncoding::Flac => "flac",
+        // Deepgram ожидает Opus в Ogg контейнере
+        AudioEncoding::Opus => "opus",
+    }
+}
+
+/// Создаёт кодировщик для потока. Если кодек недоступен — откатываемся на linear16.
+pub fn create_audio_encoder(encoding: AudioEncoding, sample_rate: u32) -> Box<dyn AudioEncoder> {
+    match encoding {
+        AudioEncoding::Linear16 => Box::new(Linear16Encoder),
+        AudioEncoding::Flac => Box::new(FlacEncoder::new(sample_rate)),
+        AudioEncoding::Opus => {
+            #[cfg(feature = "opus")]
+            {
+                match OggOpusEncoder::new(sample_rate) {
+                    Ok(encoder) => return Box::new(encoder),
+                    Err(e) => log::warn!("Failed to create Opus encoder ({}), falling back to linear16", e),
+                }
+            }
+            #[cfg(not(feature = "opus"))]
+            log::warn!("Opus encoding requested, but app is built without `opus` feature - falling back to linear16");
+
+            Box::new(Linear16Encoder)
+        }
+    }
+}
+
+/// Несжатый PCM 16-bit little-endian
+pub struct Linear16Encoder;
+
+impl AudioEncoder for Linear16Encoder {
+    fn encoding(&self) -> AudioEncoding {
+        AudioEncoding::Linear16
+    }
+
+    fn encode(&mut self, samples: &[i16]) -> Vec<Vec<u8>> {
+        if samples.is_empty() {
+            return Vec::new();
+        }
+        vec![samples.iter().flat_map(|s| s.to_le_bytes()).collect()]
+    }
+
+    fn flush(&mut self) -> Vec<Vec<u8>> {
+        Vec::new()
+    }
+
+    fn discard_pending(&mut self) {}
+}
+
+/// Запись целиком в WAV (PCM 16-bit mono) — для prerecorded REST API провайдеров
+pub fn wav_bytes(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.extend(samples.iter().flat_map(|s| s.to_le_bytes()));
+    wav
+}
+
+// ============================================================================
+// Bit writer (MSB-first, как требует FLAC)
+// ============================================================================
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, count: u32) {
+        debug_assert!(count <= 32);
+        if count == 0 {
+            return;
+        }
+        let mask = (1u64 << count) - 1;
+        self.acc = (self.acc << count) | (value & mask);
+        self.bits += count;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+        self.acc &= (1u64 << self.bits) - 1;
+    }
+
+    fn write_signed(&mut self, value: i32, count: u32) {
+        self.write(value as u32 as u64, count);
+    }
+
+    fn write_unary_zeros(&mut self, zeros: u32) {
+        let mut left = zeros;
+        while left >= 32 {
+            self.write(0, 32);
+            left -= 32;
+        }
+        self.write(1, left + 1);
+    }
+
+    fn align_to_byte(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align_to_byte();
+        self.bytes
+    }
+}
+
+// ============================================================================
+// FLAC
+// ============================================================================
+
+/// Размер блока (50ms @ 16kHz, как и порции linear16). Последний блок потока может быть короче.
+const FLAC_BLOCK_SIZE: usize = 800;
+const FLAC_MAX_FIXED_ORDER: usize = 4;
+const FLAC_MAX_RICE_PARAM: u32 = 14;
+
+/// Минимальный потоковый FLAC энкодер (fixed predictors + Rice coding).
+///
+/// Для речи даёт ~40-50% экономии относительно linear16 без потери качества,
+/// при этом не требует внешних C библиотек.
+pub struct FlacEncoder {
+    sample_rate: u32,
+    pending: Vec<i16>,
+    frame_number: u32,
+    header_sent: bool,
+}
+
+impl FlacEncoder {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            pending: Vec::with_capacity(FLAC_BLOCK_SIZE * 2),
+            frame_number: 0,
+            header_sent: false,
+        }
+    }
+
+    /// "fLaC" + STREAMINFO
+    fn stream_header(&self) -> Vec<u8> {
+        let mut w = BitWriter::default();
+        for b in b"fLaC" {
+            w.write(*b as u64, 8);
+        }
+        // last-metadata-block=1, type=0 (STREAMINFO), length=34
+        w.write(1, 1);
+        w.write(0, 7);
+        w.write(34, 24);
+
+        w.write(FLAC_BLOCK_SIZE as u64, 16); // min block size
+        w.write(FLAC_BLOCK_SIZE as u64, 16); // max block size
+        w.write(0, 24); // min frame size (unknown)
+        w.write(0, 24); // max frame size (unknown)
+        w.write(self.sample_rate as u64, 20);
+        w.write(0, 3); // channels - 1
+        w.write(15, 5); // bits per sample - 1
+        w.write(0, 4); // total samples (unknown, stream) — старшие 4 бита
+        w.write(0, 32); // total samples — младшие 32 бита
+        for _ in 0..4 {
+            w.write(0, 32); // MD5 (unknown)
+        }
+        w.into_bytes()
+    }
+
+    fn encode_frame(&mut self, block: &[i16]) -> Vec<u8> {
+        let mut w = BitWriter::default();
+
+        // --- Frame header ---
+        w.write(0b11111111111110, 14); // sync
+        w.write(0, 1); // reserved
+        w.write(0, 1); // fixed block size strategy
+        w.write(0b0111, 4); // block size: 16 bit (n-1) в конце заголовка
+        let rate_in_header = self.sample_rate <= 0xFFFF;
+        w.write(if rate_in_header { 0b1101 } else { 0b0000 }, 4); // sample rate: 16 bit Hz в конце / из STREAMINFO
+        w.write(0b0000, 4); // mono
+        w.write(0b100, 3); // 16 bits per sample
+        w.write(0, 1); // reserved
+        write_utf8_number(&mut w, self.frame_number);
+        w.write((block.len() - 1) as u64, 16);
+        if rate_in_header {
+            w.write(self.sample_rate as u64, 16);
+        }
+        let crc = crc8(&w.bytes);
+        w.write(crc as u64, 8);
+
+        // --- Subframe ---
+        write_best_subframe(&mut w, block);
+
+        // --- Footer ---
+        w.align_to_byte();
+        let crc = crc16(&w.bytes);
+        w.write(crc as u64, 16);
+
+        self.frame_number = self.frame_number.wrapping_add(1);
+        w.into_bytes()
+    }
+
+    fn with_header(&mut self, mut messages: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+        if !self.header_sent && !messages.is_empty() {
+            self.header_sent = true;
+            let mut first = self.stream_header();
+            first.extend_from_slice(&messages[0]);
+            messages[0] = first;
+        }
+        messages
+    }
+}
+
+impl AudioEncoder for FlacEncoder {
+    fn encoding(&self) -> AudioEncoding {
+        AudioEncoding::Flac
+    }
+
+    fn encode(&mut self, samples: &[i16]) -> Vec<Vec<u8>> {
+        self.pending.extend_from_slice(samples);
+
+        let mut frames = Vec::new();
+        let full_blocks = self.pending.len() / FLAC_BLOCK_SIZE;
+        if full_blocks == 0 {
+            return frames;
+        }
+
+        let pending = std::mem::take(&mut self.pending);
+        let mut frame_bytes = Vec::new();
+        for block in pending[..full_blocks * FLAC_BLOCK_SIZE].chunks(FLAC_BLOCK_SIZE) {
+            frame_bytes.extend(self.encode_frame(block));
+        }
+        self.pending = pending[full_blocks * FLAC_BLOCK_SIZE..].to_vec();
+        frames.push(frame_bytes);
+
+        self.with_header(frames)
+    }
+
+    fn flush(&mut self) -> Vec<Vec<u8>> {
+        if self.pending.is_empty() {
+            return Vec::new();
+        }
+        let pending = std::mem::take(&mut self.pending);
+        let frame = self.encode_frame(&pending);
+        self.with_header(vec![frame])
+    }
+
+    fn discard_pending(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// Выбирает лучший вариант subframe: FIXED порядка 0..4 или VERBATIM
+fn write_best_subframe(w: &mut BitWriter, block: &[i16]) {
+    let samples: Vec<i32> = block.iter().map(|&s| s as i32).collect();
+    let verbatim_bits = 16 * samples.len() as u64;
+
+    let mut best: Option<(usize, u32, u64, Vec<i32>)> = None;
+    for order in 0..=FLAC_MAX_FIXED_ORDER.min(samples.len().saturating_sub(1)) {
+        let residual = fixed_residual(&samples, order);
+        let (param, residual_bits) = best_rice_param(&residual);
+        // warmup + residual header (2 + 4 + 4) + residual
+        let bits = 16 * order as u64 + 10 + residual_bits;
+        if best.as_ref().map(|b| bits < b.2).unwrap_or(true) {
+            best = Some((order, param, bits, residual));
+        }
+    }
+
+    match best {
+        Some((order, param, bits, residual)) if bits < verbatim_bits => {
+            w.write(0, 1); // padding
+            w.write(0b001000 | order as u64, 6); // FIXED
+            w.write(0, 1); // no wasted bits
+            for &s in &samples[..order] {
+                w.write_signed(s, 16);
+            }
+            w.write(0b00, 2); // Rice, 4-bit params
+            w.write(0, 4); // partition order 0
+            w.write(param as u64, 4);
+            for &r in &residual {
+                let u = zigzag(r);
+                w.write_unary_zeros(u >> param);
+                w.write((u & ((1u32 << param) - 1)) as u64, param);
+            }
+        }
+        _ => {
+            w.write(0, 1);
+            w.write(0b000001, 6); // VERBATIM
+            w.write(0, 1);
+            for &s in &samples {
+                w.write_signed(s, 16);
+            }
+        }
+    }
+}
+
+fn fixed_residual(x: &[i32], order: usize) -> Vec<i32> {
+    (order..x.len())
+        .map(|n| match order {
+            0 => x[n],
+            1 => x[n] - x[n - 1],
+            2 => x[n] - 2 * x[n - 1] + x[n - 2],
+            3 => x[n] - 3 * x[n - 1] + 3 * x[n - 2] - x[n - 3],
+            _ => x[n] - 4 * x[n - 1] + 6 * x[n - 2] - 4 * x[n - 3] + x[n - 4],
+        })
+        .collect()
+}
+
+fn zigzag(v: i32) -> u32 {
+    ((v << 1) ^ (v >> 31)) as u32
+}
+
+/// Подбирает Rice параметр с минимальным размером (в битах)
+fn best_rice_param(residual: &[i32]) -> (u32, u64) {
+    let unsigned: Vec<u32> = residual.iter().map(|&r| zigzag(r)).collect();
+    (0..=FLAC_MAX_RICE_PARAM)
+        .map(|k| {
+            let bits: u64 = unsigned.iter().map(|&u| (u >> k) as u64 + 1 + k as u64).sum();
+            (k, bits)
+        })
+        .min_by_key(|&(_, bits)| bits)
+        .unwrap_or((0, 0))
+}
+
+/// UTF-8 подобное кодирование номера кадра (FLAC frame header)
+fn write_utf8_number(w: &mut BitWriter, value: u32) {
+    let v = value as u64;
+    if v < 0x80 {
+        w.write(v, 8);
+        return;
+    }
+    let (len, first_prefix) = match v {
+        0..=0x7FF => (2, 0xC0),
+        0x800..=0xFFFF => (3, 0xE0),
+        0x1_0000..=0x1F_FFFF => (4, 0xF0),
+        0x20_0000..=0x3FF_FFFF => (5, 0xF8),
+        _ => (6, 0xFC),
+    };
+    let first_bits = 6 * (len - 1);
+    w.write(first_prefix | (v >> first_bits), 8);
+    for i in (0..len - 1).rev() {
+        w.write(0x80 | ((v >> (6 * i)) & 0x3F), 8);
+    }
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+// ============================================================================
+// Ogg/Opus
+// ============================================================================
+
+/// Ogg CRC32 (poly 0x04C11DB7, без отражения)
+#[cfg_attr(not(feature = "opus"), allow(dead_code))]
+fn ogg_crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0;
+    for &byte in data {
+        crc ^= (byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 { (crc << 1) ^ 0x04C1_1DB7 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Минимальный Ogg page writer (одна логическая битовая последовательность)
+#[cfg_attr(not(feature = "opus"), allow(dead_code))]
+struct OggWriter {
+    serial: u32,
+    sequence: u32,
+}
+
+#[cfg_attr(not(feature = "opus"), allow(dead_code))]
+impl OggWriter {
+    const FLAG_BOS: u8 = 0x02;
+    const FLAG_EOS: u8 = 0x04;
+
+    fn new(serial: u32) -> Self {
+        Self { serial, sequence: 0 }
+    }
+
+    /// Собирает страницу из целых пакетов (каждый пакет < 255*255 байт)
+    fn page(&mut self, packets: &[Vec<u8>], granule: u64, flags: u8) -> Vec<u8> {
+        let mut lacing = Vec::new();
+        for packet in packets {
+            let mut len = packet.len();
+            while len >= 255 {
+                lacing.push(255u8);
+                len -= 255;
+            }
+            lacing.push(len as u8);
+        }
+
+        let mut page = Vec::with_capacity(27 + lacing.len() + packets.iter().map(|p| p.len()).sum::<usize>());
+        page.extend_from_slice(b"OggS");
+        page.push(0); // version
+        page.push(flags);
+        page.extend_from_slice(&granule.to_le_bytes());
+        page.extend_from_slice(&self.serial.to_le_bytes());
+        page.extend_from_slice(&self.sequence.to_le_bytes());
+        page.extend_from_slice(&[0, 0, 0, 0]); // CRC (заполним ниже)
+        page.push(lacing.len() as u8);
+        page.extend_from_slice(&lacing);
+        for packet in packets {
+            page.extend_from_slice(packet);
+        }
+
+        let crc = ogg_crc32(&page);
+        page[22..26].copy_from_slice(&crc.to_le_bytes());
+        self.sequence = self.sequence.wrapping_add(1);
+        page
+    }
+}
+
+/// Opus в Ogg контейнере (RFC 7845). Доступен только при сборке с feature `opus`.
+#[cfg(feature = "opus")]
+pub struct OggOpusEncoder {
+    // Mutex — чтобы кодировщик был Sync (провайдеры должны быть Send + Sync)
+    encoder: std::sync::Mutex<audiopus::coder::Encoder>,
+    ogg: OggWriter,
+    sample_rate: u32,
+    frame_size: usize,
+    pending: Vec<i16>,
+    granule: u64,
+    headers_sent: bool,
+}
+
+#[cfg(feature = "opus")]
+impl OggOpusEncoder {
+    /// 20ms кадры — стандарт для речи
+    const FRAME_MS: usize = 20;
+    const BITRATE: i32 = 24_000;
+    const PRE_SKIP: u16 = 312;
+
+    pub fn new(sample_rate: u32) -> Result<Self, String> {
+        use audiopus::{coder::Encoder, Application, Bitrate, Channels, SampleRate};
+
+        let rate = match sample_rate {
+            8000 => SampleRate::Hz8000,
+            12000 => SampleRate::Hz12000,
+            16000 => SampleRate::Hz16000,
+            24000 => SampleRate::Hz24000,
+            48000 => SampleRate::Hz48000,
+            other => return Err(format!("unsupported sample rate for Opus: {}", other)),
+        };
+        let mut encoder = Encoder::new(rate, Channels::Mono, Application::Voip).map_err(|e| e.to_string())?;
+        encoder
+            .set_bitrate(Bitrate::BitsPerSecond(Self::BITRATE))
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            encoder: std::sync::Mutex::new(encoder),
+            ogg: OggWriter::new(uuid::Uuid::new_v4().as_u128() as u32),
+            sample_rate,
+            frame_size: sample_rate as usize * Self::FRAME_MS / 1000,
+            pending: Vec::new(),
+            granule: 0,
+            headers_sent: false,
+        })
+    }
+
+    fn header_pages(&mut self) -> Vec<u8> {
+        let mut head = Vec::with_capacity(19);
+        head.extend_from_slice(b"OpusHead");
+        head.push(1); // version
+        head.push(1); // channels
+        head.extend_from_slice(&Self::PRE_SKIP.to_le_bytes());
+        head.extend_from_slice(&self.sample_rate.to_le_bytes());
+        head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+        head.push(0); // mapping family
+
+        let vendor = b"voice-to-text";
+        let mut tags = Vec::new();
+        tags.extend_from_slice(b"OpusTags");
+        tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        tags.extend_from_slice(vendor);
+        tags.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut out = self.ogg.page(&[head], 0, OggWriter::FLAG_BOS);
+        out.extend(self.ogg.page(&[tags], 0, 0));
+        out
+    }
+
+    fn encode_frames(&mut self, frames: &[i16]) -> Vec<Vec<u8>> {
+        let mut packets = Vec::new();
+        let mut out = [0u8; 4000];
+        let encoder = self.encoder.lock().unwrap_or_else(|e| e.into_inner());
+        for frame in frames.chunks(self.frame_size) {
+            match encoder.encode(frame, &mut out) {
+                Ok(len) => {
+                    packets.push(out[..len].to_vec());
+                    // granule position в Ogg/Opus всегда в единицах 48kHz
+                    self.granule += (frame.len() * 48_000 / self.sample_rate as usize) as u64;
+                }
+                Err(e) => log::warn!("Opus encode failed, dropping frame: {}", e),
+            }
+        }
+        packets
+    }
+
+    fn emit(&mut self, packets: Vec<Vec<u8>>, flags: u8) -> Vec<Vec<u8>> {
+        if packets.is_empty() {
+            return Vec::new();
+        }
+        let mut message = if self.headers_sent {
+            Vec::new()
+        } else {
+            self.headers_sent = true;
+            self.header_pages()
+        };
+        message.extend(self.ogg.page(&packets, self.granule, flags));
+        vec![message]
+    }
+}
+
+#[cfg(feature = "opus")]
+impl AudioEncoder for OggOpusEncoder {
+    fn encoding(&self) -> AudioEncoding {
+        AudioEncoding::Opus
+    }
+
+    fn encode(&mut self, samples: &[i16]) -> Vec<Vec<u8>> {
+        self.pending.extend_from_slice(samples);
+        let full = self.pending.len() / self.frame_size * self.frame_size;
+        if full == 0 {
+            return Vec::new();
+        }
+        let ready: Vec<i16> = self.pending.drain(..full).collect();
+        let packets = self.encode_frames(&ready);
+        self.emit(packets, 0)
+    }
+
+    fn flush(&mut self) -> Vec<Vec<u8>> {
+        if self.pending.is_empty() {
+            return Vec::new();
+        }
+        // Добиваем последний кадр тишиной (Opus кодирует только кадры фиксированного размера)
+        let mut last = std::mem::take(&mut self.pending);
+        last.resize(self.frame_size, 0);
+        let packets = self.encode_frames(&last);
+        self.emit(packets, OggWriter::FLAG_EOS)
+    }
+
+    fn discard_pending(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speech_like(len: usize) -> Vec<i16> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / 16000.0;
+                ((t * 220.0 * std::f32::consts::TAU).sin() * 8000.0
+                    + (t * 570.0 * std::f32::consts::TAU).sin() * 3000.0) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn linear16_is_passthrough() {
+        let mut encoder = create_audio_encoder(AudioEncoding::Linear16, 16000);
+        assert_eq!(encoder.encode(&[1, -2]), vec![vec![1, 0, 0xFE, 0xFF]]);
+        assert!(encoder.flush().is_empty());
+    }
+
+    #[test]
+    fn wav_header_describes_pcm16_mono() {
+        let wav = wav_bytes(&[1, -2], 16000);
+        assert_eq!(wav.len(), 48);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 40);
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 16000);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(&wav[44..], &[1, 0, 0xFE, 0xFF]);
+    }
+
+    #[test]
+    fn deepgram_params_match_encoding() {
+        assert_eq!(deepgram_encoding_param(AudioEncoding::Linear16), "linear16");
+        assert_eq!(deepgram_encoding_param(AudioEncoding::Flac), "flac");
+        assert_eq!(deepgram_encoding_param(AudioEncoding::Opus), "opus");
+    }
+
+    #[test]
+    fn crc_reference_values() {
+        // Контрольные значения для "123456789"
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xFEE8);
+        assert_eq!(ogg_crc32(b"123456789"), 0x89A1_897F);
+    }
+
+    #[test]
+    fn flac_stream_starts_with_header_and_buffers_partial_blocks() {
+        let mut encoder = FlacEncoder::new(16000);
+
+        // Меньше блока — ничего не отправляем
+        assert!(encoder.encode(&speech_like(400)).is_empty());
+
+        let messages = encoder.encode(&speech_like(800));
+        assert_eq!(messages.len(), 1);
+        let first = &messages[0];
+        assert_eq!(&first[..4], b"fLaC");
+        assert_eq!(first[4], 0x80); // last metadata block + STREAMINFO
+        // Сразу после STREAMINFO (4 + 4 + 34 байт) — sync первого кадра
+        assert_eq!(&first[42..44], &[0xFF, 0xF8]);
+
+        // Остаток (400 семплов) уходит при flush, заголовок повторно не шлём
+        let tail = encoder.flush();
+        assert_eq!(tail.len(), 1);
+        assert_eq!(&tail[0][..2], &[0xFF, 0xF8]);
+        assert!(encoder.flush().is_empty());
+    }
+
+    #[test]
+    fn flac_compresses_speech_like_audio() {
+        let samples = speech_like(16000);
+        let mut encoder = FlacEncoder::new(16000);
+        let mut total: usize = encoder.encode(&samples).iter().map(|m| m.len()).sum();
+        total += encoder.flush().iter().map(|m| m.len()).sum::<usize>();
+
+        assert!(total < samples.len() * 2 * 3 / 4, "flac size {} vs pcm {}", total, samples.len() * 2);
+    }
+
+    #[test]
+    fn flac_silence_uses_tiny_frames() {
+        let mut encoder = FlacEncoder::new(16000);
+        encoder.header_sent = true;
+        let messages = encoder.encode(&vec![0i16; 800]);
+        assert!(messages[0].len() < 150, "silence frame too large: {}", messages[0].len());
+
+        // После discard_pending остаток не попадает в поток
+        assert!(encoder.encode(&[1, 2, 3]).is_empty());
+        encoder.discard_pending();
+        assert!(encoder.flush().is_empty());
+    }
+
+    #[test]
+    fn utf8_frame_numbers() {
+        let encode = |n: u32| {
+            let mut w = BitWriter::default();
+            write_utf8_number(&mut w, n);
+            w.into_bytes()
+        };
+        assert_eq!(encode(0x41), vec![0x41]);
+        assert_eq!(encode(0x80), vec![0xC2, 0x80]);
+        assert_eq!(encode(0x800), vec![0xE0, 0xA0, 0x80]);
+    }
+
+    #[test]
+    fn ogg_page_layout() {
+        let mut ogg = OggWriter::new(7);
+        let page = ogg.page(&[vec![1, 2, 3]], 960, OggWriter::FLAG_BOS);
+        assert_eq!(&page[..4], b"OggS");
+        assert_eq!(page[5], OggWriter::FLAG_BOS);
+        assert_eq!(u64::from_le_bytes(page[6..14].try_into().unwrap()), 960);
+        assert_eq!(page[26], 1); // segments
+        assert_eq!(page[27], 3); // lacing
+        assert_eq!(&page[28..], &[1, 2, 3]);
+
+        // CRC считается с нулями на месте CRC
+        let mut check = page.clone();
+        check[22..26].copy_from_slice(&[0, 0, 0, 0]);
+        assert_eq!(ogg_crc32(&check).to_le_bytes(), page[22..26]);
+    }
+
+    #[cfg(not(feature = "opus"))]
+    #[test]
+    fn opus_falls_back_to_linear16_without_feature() {
+        let encoder = create_audio_encoder(AudioEncoding::Opus, 16000);
+        assert_eq!(encoder.encoding(), AudioEncoding::Linear16);
+    }
+}
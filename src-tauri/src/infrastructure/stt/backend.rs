@@ -4,23 +4,21 @@
 //! Все транскрипции идут через наш бэкенд с лицензией и usage tracking.
 
 use async_trait::async_trait;
-use futures_util::{SinkExt, StreamExt};
-use http::Request;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
 use tokio::time::Duration;
-use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
-use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::{self, protocol::CloseFrame, Message};
 
 use crate::domain::{
-    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
+    AlternativeText, AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
     SttConnectionDetails, SttConnectionError, SttError, SttProvider, SttResult, Transcription,
     TranscriptionCallback,
 };
 
 use super::backend_messages::{ClientMessage, ServerMessage};
+use super::stream_state::{StreamLifecycle, StreamState};
+use super::ws_transport::{parse_retry_after, SttMessageCodec, WsKeepAlive, WsSttTransport, WsTransportError};
 
 /// URL бэкенда для production
 const PROD_BACKEND_URL: &str = "wss://api.voicetext.site";
@@ -33,6 +31,9 @@ const DEV_BACKEND_URL: &str = "ws://localhost:8080";
 const WS_CONNECT_TIMEOUT_SECS: u64 = 8;
 const WS_SEND_TIMEOUT_SECS: u64 = 3;
 
+/// Остаток меньше этого при закрытии/обрыве — считаем, что сервер закрыл по лимиту
+const LIMIT_REMAINING_THRESHOLD: f32 = 5.0;
+
 /// Проверяем, что URL указывает на локальный бэкенд (localhost/loopback).
 ///
 /// Нужен для dev-режима: если у пользователя сохранён "боевой" токен, но он запускает
@@ -70,26 +71,19 @@ fn get_default_backend_url() -> String {
     }
 }
 
-type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
-
 /// Callback для обновления usage (seconds_used, seconds_remaining_total_or_plan)
 pub type UsageUpdateCallback = Arc<dyn Fn(f32, f32) + Send + Sync>;
 
 /// Backend STT provider — подключается к нашему API вместо прямого Deepgram
 pub struct BackendProvider {
     config: Option<SttConfig>,
-    is_streaming: bool,
-    is_paused: bool,
+    /// Idle → Connecting → Streaming ⇄ Paused → Closing → Closed (shared с receiver/keepalive tasks).
+    /// Обрыв соединения переводит в Closed из любого состояния; Closing отличает наше закрытие от обрыва.
+    lifecycle: StreamLifecycle,
     auth_token: Option<String>,
     backend_url: String,
     session_id: Option<String>,
-    ws_write: Option<Arc<Mutex<futures_util::stream::SplitSink<WsStream, Message>>>>,
-    receiver_task: Option<JoinHandle<()>>,
-    keepalive_task: Option<JoinHandle<()>>,
-
-    /// Флаг закрытия соединения (атомарный для thread-safety)
-    /// Используется для предотвращения race condition при закрытии WebSocket
-    is_closed: Arc<AtomicBool>,
+    transport: Option<WsSttTransport>,
 
     /// Последний известный остаток секунд (из UsageUpdate), хранится как f32 bits.
     /// Доступен и из receiver task, и из send_audio() — нужен чтобы при закрытии
@@ -139,15 +133,11 @@ impl BackendProvider {
     pub fn new() -> Self {
         Self {
             config: None,
-            is_streaming: false,
-            is_paused: false,
+            lifecycle: StreamLifecycle::new(),
             auth_token: None,
             backend_url: get_default_backend_url(),
             session_id: None,
-            ws_write: None,
-            receiver_task: None,
-            keepalive_task: None,
-            is_closed: Arc::new(AtomicBool::new(true)), // Изначально закрыто
+            transport: None,
             last_remaining_secs: Arc::new(AtomicU32::new(f32::MAX.to_bits())),
             callbacks: Arc::new(Mutex::new(CallbackState::default())),
             on_usage_update_callback: None,
@@ -168,31 +158,29 @@ impl BackendProvider {
     /// Отправить JSON сообщение через WebSocket
     async fn send_json(&self, msg: &ClientMessage) -> SttResult<()> {
         // Не пытаемся отправить если соединение уже закрыто
-        if self.is_closed.load(Ordering::SeqCst) {
+        if self.lifecycle.is_closed() {
             return Ok(()); // Игнорируем — соединение уже закрыто
         }
 
-        if let Some(ref ws_write) = self.ws_write {
+        if let Some(ref transport) = self.transport {
             let json = serde_json::to_string(msg)
                 .map_err(|e| SttError::Processing(format!("JSON serialize error: {}", e)))?;
 
-            let send_fut = async {
-                let mut guard = ws_write.lock().await;
-                guard.send(Message::Text(json)).await
-            };
-
-            match tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), send_fut).await {
-                Ok(Ok(())) => {}
-                Ok(Err(e)) => {
+            match transport
+                .send_with_timeout(Message::Text(json), Duration::from_secs(WS_SEND_TIMEOUT_SECS))
+                .await
+            {
+                Ok(()) => {}
+                Err(WsTransportError::Ws(e)) => {
                     // Если не можем отправлять — считаем соединение "поломанным", чтобы send_audio быстро фейлился.
-                    self.is_closed.store(true, Ordering::SeqCst);
+                    self.lifecycle.force_close();
                     return Err(SttError::Connection(SttConnectionError {
                         message: format!("WS send error: {}", e),
                         details: SttConnectionDetails::default(),
                     }));
                 }
-                Err(_) => {
-                    self.is_closed.store(true, Ordering::SeqCst);
+                Err(WsTransportError::Timeout) => {
+                    self.lifecycle.force_close();
                     return Err(SttError::Connection(SttConnectionError {
                         message: "WS send timeout".to_string(),
                         details: SttConnectionDetails {
@@ -208,87 +196,15 @@ impl BackendProvider {
             Err(SttError::Processing("WebSocket not connected".to_string()))
         }
     }
-}
-
-impl Default for BackendProvider {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-#[async_trait]
-impl SttProvider for BackendProvider {
-    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
-        log::info!("BackendProvider: Initializing");
-
-        // Получаем URL бэкенда (из конфига или авто-детект по окружению)
-        let backend_url = config
-            .backend_url
-            .clone()
-            .unwrap_or_else(get_default_backend_url);
 
-        log::info!("BackendProvider: Using backend URL: {}", backend_url);
-
-        // Получаем auth token из конфига
-        //
-        // В dev режиме для локального бэкенда (localhost) всегда используем dev-local-token.
-        // Это защищает от ситуации "я уже логинился в прод, а сейчас запускаю local" → 401.
-        log::info!(
-            "BackendProvider: config.backend_auth_token present: {}, len: {}",
-            config.backend_auth_token.is_some(),
-            config.backend_auth_token.as_ref().map(|t| t.len()).unwrap_or(0)
-        );
-
-        let auth_token = if cfg!(debug_assertions) {
-            if is_local_backend_url(&backend_url) {
-                if config.backend_auth_token.as_deref() != Some("dev-local-token") {
-                    log::info!(
-                        "DEV MODE: Local backend detected ({}). Using dev-local-token instead of saved token",
-                        backend_url
-                    );
-                } else {
-                    log::info!(
-                        "DEV MODE: Local backend detected ({}). Using dev-local-token",
-                        backend_url
-                    );
-                }
-                "dev-local-token".to_string()
-            } else {
-                config.backend_auth_token.clone().unwrap_or_else(|| {
-                    log::info!("DEV MODE: Using dev-local-token (no real token configured)");
-                    "dev-local-token".to_string()
-                })
-            }
-        } else {
-            config.backend_auth_token.clone().ok_or_else(|| {
-                SttError::Configuration(
-                    "Backend auth token is required. Please activate your license.".to_string(),
-                )
-            })?
-        };
-
-        log::info!("BackendProvider: auth_token len: {}", auth_token.len());
-
-        self.auth_token = Some(auth_token);
-        self.backend_url = backend_url;
-        self.config = Some(config.clone());
-
-        Ok(())
-    }
-
-    async fn start_stream(
+    /// Подключается, отправляет Config и запускает receiver/keepalive (состояние ведёт start_stream)
+    async fn open_stream(
         &mut self,
         on_partial: TranscriptionCallback,
         on_final: TranscriptionCallback,
         on_error: ErrorCallback,
         on_connection_quality: ConnectionQualityCallback,
     ) -> SttResult<()> {
-        log::info!("BackendProvider: Starting stream");
-
-        if self.is_streaming {
-            return Err(SttError::Processing("Stream already active".to_string()));
-        }
-
         let auth_token = self
             .auth_token
             .as_ref()
@@ -306,43 +222,100 @@ impl SttProvider for BackendProvider {
 
         log::debug!("Connecting to backend: {}", ws_url);
 
-        // Формируем WebSocket запрос с Authorization header
-        let request = Request::builder()
-            .method("GET")
-            .uri(&ws_url)
-            .header("Host", self.backend_url.replace("wss://", "").replace("ws://", ""))
-            .header("Connection", "Upgrade")
-            .header("Upgrade", "websocket")
-            .header("Sec-WebSocket-Version", "13")
-            .header(
-                "Sec-WebSocket-Key",
-                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
-            )
-            .header("Authorization", format!("Bearer {}", auth_token))
-            .body(())
-            .map_err(|e| {
-                SttError::Connection(SttConnectionError::simple(format!(
-                    "Failed to build WS request: {}",
-                    e
-                )))
-            })?;
-
-        let (ws_stream, _response) = tokio::time::timeout(
-            Duration::from_secs(WS_CONNECT_TIMEOUT_SECS),
-            connect_async(request),
+        let host = self.backend_url.replace("wss://", "").replace("ws://", "");
+        let request = WsSttTransport::request(&ws_url, &host, &format!("Bearer {}", auth_token))?;
+
+        // Сбрасываем remaining на старте нового соединения
+        self.last_remaining_secs.store(f32::MAX.to_bits(), Ordering::SeqCst);
+
+        // Receiver берёт callbacks из self.callbacks, чтобы они могли обновляться при resume_stream.
+        let codec = BackendCodec {
+            callbacks: self.callbacks.clone(),
+            on_usage: self.on_usage_update_callback.clone(),
+            lifecycle: self.lifecycle.clone(),
+            remaining: self.last_remaining_secs.clone(),
+        };
+
+        let mut transport = WsSttTransport::connect(
+            request,
+            Some(Duration::from_secs(WS_CONNECT_TIMEOUT_SECS)),
+            codec,
         )
         .await
-        .map_err(|_| {
-            SttError::Connection(SttConnectionError {
-                message: "WS connection timeout".to_string(),
-                details: SttConnectionDetails {
-                    category: Some(SttConnectionCategory::Timeout),
-                    ..Default::default()
-                },
-            })
-        })?
         .map_err(|e| match e {
-            tokio_tungstenite::tungstenite::Error::Http(resp) => {
+            WsTransportError::Ws(e) => self.connect_error(e),
+            other => other.into(),
+        })?;
+
+        // KeepAlive (best-effort): поддерживает соединение живым, когда пользователь
+        // быстро старт/стопит запись или просто прячет окно на пару секунд.
+        //
+        // Важно: само наличие открытого WS-соединения может держать ресурсы провайдера (Deepgram) на сервере.
+        // Поэтому держим TTL коротким и всегда закрываем соединение по таймеру в TranscriptionService.
+        transport.start_keepalive(WsKeepAlive {
+            interval: Duration::from_secs(20),
+            message: || Message::Ping(Vec::new()),
+            send_timeout: Some(Duration::from_secs(WS_SEND_TIMEOUT_SECS)),
+            lifecycle: Some(self.lifecycle.clone()),
+        });
+        self.transport = Some(transport);
+
+        // Сохраняем callbacks как "active" (для receiver task).
+        {
+            let mut state = self.callbacks.lock().await;
+            state.active = Some(CallbackSet {
+                on_partial: on_partial.clone(),
+                on_final: on_final.clone(),
+                on_error: on_error.clone(),
+                on_connection_quality: on_connection_quality.clone(),
+            });
+            state.pending = None;
+            state.swap_on_next_ack = false;
+            state.swap_after_seq = 0;
+        }
+
+        // Отправляем Config message
+        let provider_name = match config.provider {
+            crate::domain::SttProviderType::Deepgram => "deepgram",
+            crate::domain::SttProviderType::AssemblyAI => "assemblyai",
+            _ => "deepgram", // fallback
+        };
+
+        // Парсим keyterms из конфига (строка через запятую → Vec<String>)
+        let keyterms = config.deepgram_keyterms.as_ref().and_then(|raw| {
+            let terms: Vec<String> = raw
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+            if terms.is_empty() { None } else { Some(terms) }
+        });
+
+        let config_msg = ClientMessage::Config {
+            protocol_v: 1,
+            provider: provider_name.to_string(),
+            language: config.language.clone(),
+            model: config.effective_model().map(str::to_string),
+            sample_rate: 16000,
+            channels: 1,
+            encoding: "pcm_s16le".to_string(),
+            keyterms,
+            alternatives: config.requested_alternatives(),
+            diarize: config.diarize.then_some(true),
+        };
+
+        self.send_json(&config_msg).await?;
+        log::debug!("Config message sent");
+
+        self.sent_chunks_count = 0;
+        self.sent_bytes_total = 0;
+        Ok(())
+    }
+
+    /// Ошибка handshake → SttError с категорией (401/429/5xx, TLS, сеть)
+    fn connect_error(&self, e: tungstenite::Error) -> SttError {
+        match e {
+            tungstenite::Error::Http(resp) => {
                 let status = resp.status();
 
                 if status == http::StatusCode::UNAUTHORIZED {
@@ -419,7 +392,7 @@ impl SttProvider for BackendProvider {
                             .headers()
                             .get("Retry-After")
                             .and_then(|v| v.to_str().ok())
-                            .and_then(|s| s.parse::<u64>().ok());
+                            .and_then(parse_retry_after);
                     }
 
                     let display_message = match (&server_message, &server_code, retry_after_secs) {
@@ -453,15 +426,24 @@ impl SttProvider for BackendProvider {
                         _ => SttConnectionCategory::RateLimited,
                     };
 
-                    return SttError::Connection(SttConnectionError {
-                        message: display_message,
-                        details: SttConnectionDetails {
-                            category: Some(category),
-                            http_status: Some(429),
-                            server_code,
-                            ..Default::default()
-                        },
-                    });
+                    let details = SttConnectionDetails {
+                        category: Some(category.clone()),
+                        http_status: Some(429),
+                        server_code,
+                        ..Default::default()
+                    };
+                    return if category == SttConnectionCategory::LimitExceeded {
+                        SttError::QuotaExceeded {
+                            message: display_message,
+                            details,
+                        }
+                    } else {
+                        SttError::RateLimited {
+                            message: display_message,
+                            retry_after_secs,
+                            details,
+                        }
+                    };
                 }
 
                 {
@@ -481,14 +463,14 @@ impl SttProvider for BackendProvider {
                     })
                 }
             }
-            tokio_tungstenite::tungstenite::Error::Tls(other) => SttError::Connection(SttConnectionError {
+            tungstenite::Error::Tls(other) => SttError::Connection(SttConnectionError {
                 message: format!("WS connection failed: {}", other),
                 details: SttConnectionDetails {
                     category: Some(SttConnectionCategory::Tls),
                     ..Default::default()
                 },
             }),
-            tokio_tungstenite::tungstenite::Error::Io(ioe) => {
+            tungstenite::Error::Io(ioe) => {
                 let kind = ioe.kind();
                 let kind_str = format!("{:?}", kind);
                 let os_error = ioe.raw_os_error();
@@ -516,414 +498,103 @@ impl SttProvider for BackendProvider {
                 message: format!("WS connection failed: {}", other),
                 details: SttConnectionDetails::default(),
             }),
-        })?;
-
-        log::info!("Backend WebSocket connected");
-
-        // Сбрасываем флаг закрытия — соединение установлено
-        self.is_closed.store(false, Ordering::SeqCst);
-
-        let (write, mut read) = ws_stream.split();
-        let ws_write = Arc::new(Mutex::new(write));
-        self.ws_write = Some(ws_write.clone());
-
-        // Сохраняем callbacks как "active" (для receiver task).
-        {
-            let mut state = self.callbacks.lock().await;
-            state.active = Some(CallbackSet {
-                on_partial: on_partial.clone(),
-                on_final: on_final.clone(),
-                on_error: on_error.clone(),
-                on_connection_quality: on_connection_quality.clone(),
-            });
-            state.pending = None;
-            state.swap_on_next_ack = false;
-            state.swap_after_seq = 0;
         }
+    }
+}
 
-        // Отправляем Config message
-        let provider_name = match config.provider {
-            crate::domain::SttProviderType::Deepgram => "deepgram",
-            crate::domain::SttProviderType::AssemblyAI => "assemblyai",
-            _ => "deepgram", // fallback
-        };
-
-        // Парсим keyterms из конфига (строка через запятую → Vec<String>)
-        let keyterms = config.deepgram_keyterms.as_ref().and_then(|raw| {
-            let terms: Vec<String> = raw
-                .split(',')
-                .map(|t| t.trim().to_string())
-                .filter(|t| !t.is_empty())
-                .collect();
-            if terms.is_empty() { None } else { Some(terms) }
-        });
-
-        let config_msg = ClientMessage::Config {
-            protocol_v: 1,
-            provider: provider_name.to_string(),
-            language: config.language.clone(),
-            sample_rate: 16000,
-            channels: 1,
-            encoding: "pcm_s16le".to_string(),
-            keyterms,
-        };
-
-        self.send_json(&config_msg).await?;
-        log::debug!("Config message sent");
-
-        // Запускаем receiver task для обработки сообщений от сервера.
-        // Берём callbacks из self.callbacks, чтобы они могли обновляться при resume_stream.
-        let callbacks_state = self.callbacks.clone();
-        let on_usage_cb = self.on_usage_update_callback.clone();
-        let is_closed_flag = self.is_closed.clone();
-        let shared_remaining = self.last_remaining_secs.clone();
-
-        // Сбрасываем remaining на старте нового соединения
-        shared_remaining.store(f32::MAX.to_bits(), Ordering::SeqCst);
-
-        let receiver_task = tokio::spawn(async move {
-            log::debug!("Backend receiver task started");
-
-            const LIMIT_REMAINING_THRESHOLD: f32 = 5.0;
-
-            while let Some(msg_result) = read.next().await {
-                match msg_result {
-                    Ok(Message::Text(text)) => {
-                        match serde_json::from_str::<ServerMessage>(&text) {
-                            Ok(server_msg) => {
-                                match server_msg {
-                                    ServerMessage::Ready { session_id } => {
-                                        log::info!("Session ready: {}", session_id);
-                                        // Уведомляем о хорошем качестве связи
-                                        let cb = {
-                                            let state = callbacks_state.lock().await;
-                                            state
-                                                .active
-                                                .as_ref()
-                                                .map(|c| c.on_connection_quality.clone())
-                                        };
-                                        if let Some(cb) = cb {
-                                            cb("Good".to_string(), None);
-                                        }
-                                    }
-
-                                    ServerMessage::Ack { seq } => {
-                                        log::trace!("Ack received: seq={}", seq);
-                                        // Если есть pending callbacks (новая UI-сессия) — активируем их на первом ACK.
-                                        // Это даёт чёткую границу между "старыми" и "новыми" результатами.
-                                        let swapped = {
-                                            let mut state = callbacks_state.lock().await;
-                                            if state.swap_on_next_ack && seq > state.swap_after_seq {
-                                                state.swap_on_next_ack = false;
-                                                state.swap_after_seq = 0;
-                                                if state.pending.is_some() {
-                                                    state.active = state.pending.take();
-                                                }
-                                                true
-                                            } else {
-                                                false
-                                            }
-                                        };
-                                        if swapped {
-                                            log::debug!("Callbacks switched after first ACK (new recording session)");
-                                        }
-                                    }
-
-                                    ServerMessage::Partial { text, confidence } => {
-                                        log::debug!("Partial: {} (conf: {:?})", text, confidence);
-                                        let mut transcription = Transcription::partial(text);
-                                        if let Some(conf) = confidence {
-                                            transcription = transcription.with_confidence(conf);
-                                        }
-                                        let cb = {
-                                            let state = callbacks_state.lock().await;
-                                            state.active.as_ref().map(|c| c.on_partial.clone())
-                                        };
-                                        if let Some(cb) = cb {
-                                            cb(transcription);
-                                        }
-                                    }
-
-                                    ServerMessage::Final {
-                                        text,
-                                        confidence,
-                                        duration_ms,
-                                    } => {
-                                        log::debug!(
-                                            "Final: {} (conf: {:?}, dur: {}ms)",
-                                            text,
-                                            confidence,
-                                            duration_ms
-                                        );
-                                        let mut transcription = Transcription::final_result(text)
-                                            .with_timing(0.0, duration_ms as f64 / 1000.0);
-                                        if let Some(conf) = confidence {
-                                            transcription = transcription.with_confidence(conf);
-                                        }
-                                        let cb = {
-                                            let state = callbacks_state.lock().await;
-                                            state.active.as_ref().map(|c| c.on_final.clone())
-                                        };
-                                        if let Some(cb) = cb {
-                                            cb(transcription);
-                                        }
-                                    }
-
-                                    ServerMessage::UsageUpdate {
-                                        seconds_used,
-                                        seconds_remaining_plan,
-                                        seconds_remaining_total,
-                                        ..
-                                    } => {
-                                        let remaining = seconds_remaining_total
-                                            .unwrap_or(seconds_remaining_plan);
-                                        shared_remaining.store(remaining.to_bits(), Ordering::SeqCst);
-                                        log::debug!(
-                                            "Usage: used={:.1}s, remaining={:.1}s",
-                                            seconds_used,
-                                            remaining
-                                        );
-                                        if let Some(ref cb) = on_usage_cb {
-                                            cb(seconds_used, remaining);
-                                        }
-                                    }
-
-                                    ServerMessage::Resumed {
-                                        session_id,
-                                        last_seq_acked,
-                                    } => {
-                                        log::info!(
-                                            "Session resumed: {}, last_seq: {}",
-                                            session_id,
-                                            last_seq_acked
-                                        );
-                                        let cb = {
-                                            let state = callbacks_state.lock().await;
-                                            state
-                                                .active
-                                                .as_ref()
-                                                .map(|c| c.on_connection_quality.clone())
-                                        };
-                                        if let Some(cb) = cb {
-                                            cb("Good".to_string(), None);
-                                        }
-                                    }
-
-                                    ServerMessage::Error { code, message } => {
-                                        log::error!("Server error: {} - {}", code, message);
-                                        let cb = {
-                                            let state = callbacks_state.lock().await;
-                                            state.active.as_ref().map(|c| c.on_error.clone())
-                                        };
-                                        if let Some(cb) = cb {
-                                            let category = match code.as_str() {
-                                                "timeout" => Some(SttConnectionCategory::Timeout),
-                                                "rate_limit" | "too_many_sessions" => Some(SttConnectionCategory::RateLimited),
-                                                "LIMIT_EXCEEDED" => Some(SttConnectionCategory::LimitExceeded),
-                                                _ => Some(SttConnectionCategory::Unknown),
-                                            };
-                                            cb(SttError::Connection(SttConnectionError {
-                                                message,
-                                                details: SttConnectionDetails {
-                                                    category,
-                                                    server_code: Some(code),
-                                                    ..Default::default()
-                                                },
-                                            }));
-                                        }
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                log::warn!("Failed to parse server message: {} - {}", e, text);
-                            }
-                        }
-                    }
-
-                    Ok(Message::Close(frame)) => {
-                        log::info!("WebSocket closed by server: {:?}", frame);
-                        // Если мы сами инициировали закрытие (stop_stream) — не эмитим ошибку в UI.
-                        if is_closed_flag.load(Ordering::SeqCst) {
-                            break;
-                        }
-                        is_closed_flag.store(true, Ordering::SeqCst);
-                        let cb = {
-                            let state = callbacks_state.lock().await;
-                            state.active.as_ref().map(|c| c.on_error.clone())
-                        };
-                        if let Some(cb) = cb {
-                            let code_u16 = frame.as_ref().map(|f| u16::from(f.code));
-                            let mut category = match code_u16 {
-                                Some(1008) => SttConnectionCategory::LimitExceeded,
-                                Some(1012) | Some(1013) | Some(1014) => SttConnectionCategory::ServerUnavailable,
-                                Some(1000) => SttConnectionCategory::Closed,
-                                _ => SttConnectionCategory::ServerUnavailable,
-                            };
-
-                            // Fallback: сервер может закрыть WS без кода 1008 (race condition между
-                            // отправкой LIMIT_EXCEEDED и close frame). Если последний UsageUpdate
-                            // показывал почти нулевой остаток — это лимит, а не обрыв связи.
-                            let remaining = f32::from_bits(shared_remaining.load(Ordering::SeqCst));
-                            if category != SttConnectionCategory::LimitExceeded
-                                && remaining < LIMIT_REMAINING_THRESHOLD
-                            {
-                                log::warn!(
-                                    "Close frame without 1008, but last remaining={:.1}s < {:.0}s → treating as limit_exceeded",
-                                    remaining,
-                                    LIMIT_REMAINING_THRESHOLD
-                                );
-                                category = SttConnectionCategory::LimitExceeded;
-                            }
-
-                            cb(SttError::Connection(SttConnectionError {
-                                message: "WebSocket closed by server".to_string(),
-                                details: SttConnectionDetails {
-                                    category: Some(category),
-                                    ws_close_code: code_u16,
-                                    ..Default::default()
-                                },
-                            }));
-                        }
-                        break;
-                    }
+impl Default for BackendProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-                    Ok(Message::Ping(data)) => {
-                        log::trace!("Ping received");
-                        // Pong отправляется автоматически tokio-tungstenite
-                        let _ = data;
-                    }
+#[async_trait]
+impl SttProvider for BackendProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        log::info!("BackendProvider: Initializing");
 
-                    Ok(_) => {
-                        // Binary или другие сообщения — игнорируем
-                    }
+        // Получаем URL бэкенда (из конфига или авто-детект по окружению)
+        let backend_url = config
+            .backend_url
+            .clone()
+            .unwrap_or_else(get_default_backend_url);
 
-                    Err(e) => {
-                        log::error!("WebSocket error: {}", e);
-                        // Если закрытие инициировано нами — не поднимаем "ошибку соединения" в UI.
-                        if is_closed_flag.load(Ordering::SeqCst) {
-                            break;
-                        }
-                        is_closed_flag.store(true, Ordering::SeqCst);
-                        let cb = {
-                            let state = callbacks_state.lock().await;
-                            state.active.as_ref().map(|c| c.on_error.clone())
-                        };
-                        if let Some(cb) = cb {
-                            let mut details = match &e {
-                                tokio_tungstenite::tungstenite::Error::Io(ioe) => {
-                                    let kind = ioe.kind();
-                                    let kind_str = format!("{:?}", kind);
-                                    let os_error = ioe.raw_os_error();
-                                    let category = match kind {
-                                        std::io::ErrorKind::ConnectionRefused => SttConnectionCategory::Refused,
-                                        std::io::ErrorKind::ConnectionReset => SttConnectionCategory::Reset,
-                                        std::io::ErrorKind::BrokenPipe => SttConnectionCategory::ServerUnavailable,
-                                        std::io::ErrorKind::NotConnected
-                                        | std::io::ErrorKind::NetworkUnreachable
-                                        | std::io::ErrorKind::HostUnreachable
-                                        | std::io::ErrorKind::AddrNotAvailable => SttConnectionCategory::Offline,
-                                        std::io::ErrorKind::TimedOut => SttConnectionCategory::Timeout,
-                                        _ => SttConnectionCategory::Unknown,
-                                    };
-                                    SttConnectionDetails {
-                                        category: Some(category),
-                                        io_error_kind: Some(kind_str),
-                                        os_error,
-                                        ..Default::default()
-                                    }
-                                }
-                                tokio_tungstenite::tungstenite::Error::Tls(_) => SttConnectionDetails {
-                                    category: Some(SttConnectionCategory::Tls),
-                                    ..Default::default()
-                                },
-                                tokio_tungstenite::tungstenite::Error::ConnectionClosed
-                                | tokio_tungstenite::tungstenite::Error::AlreadyClosed => SttConnectionDetails {
-                                    category: Some(SttConnectionCategory::Closed),
-                                    ..Default::default()
-                                },
-                                _ => SttConnectionDetails {
-                                    category: Some(SttConnectionCategory::Unknown),
-                                    ..Default::default()
-                                },
-                            };
+        log::info!("BackendProvider: Using backend URL: {}", backend_url);
 
-                            // Fallback: обрыв соединения (reset/closed) при почти нулевом остатке
-                            // — скорее всего сервер закрыл из-за лимита без нормального close frame.
-                            let remaining = f32::from_bits(shared_remaining.load(Ordering::SeqCst));
-                            if details.category != Some(SttConnectionCategory::LimitExceeded)
-                                && remaining < LIMIT_REMAINING_THRESHOLD
-                            {
-                                log::warn!(
-                                    "WS error with last remaining={:.1}s < {:.0}s → treating as limit_exceeded",
-                                    remaining,
-                                    LIMIT_REMAINING_THRESHOLD
-                                );
-                                details.category = Some(SttConnectionCategory::LimitExceeded);
-                            }
+        // Получаем auth token из конфига
+        //
+        // В dev режиме для локального бэкенда (localhost) всегда используем dev-local-token.
+        // Это защищает от ситуации "я уже логинился в прод, а сейчас запускаю local" → 401.
+        log::info!(
+            "BackendProvider: config.backend_auth_token present: {}, len: {}",
+            config.backend_auth_token.is_some(),
+            config.backend_auth_token.as_ref().map(|t| t.len()).unwrap_or(0)
+        );
 
-                            cb(SttError::Connection(SttConnectionError {
-                                message: e.to_string(),
-                                details,
-                            }));
-                        }
-                        break;
-                    }
+        let auth_token = if cfg!(debug_assertions) {
+            if is_local_backend_url(&backend_url) {
+                if config.backend_auth_token.as_deref() != Some("dev-local-token") {
+                    log::info!(
+                        "DEV MODE: Local backend detected ({}). Using dev-local-token instead of saved token",
+                        backend_url
+                    );
+                } else {
+                    log::info!(
+                        "DEV MODE: Local backend detected ({}). Using dev-local-token",
+                        backend_url
+                    );
                 }
+                "dev-local-token".to_string()
+            } else {
+                config.backend_auth_token.clone().unwrap_or_else(|| {
+                    log::info!("DEV MODE: Using dev-local-token (no real token configured)");
+                    "dev-local-token".to_string()
+                })
             }
+        } else {
+            config.backend_auth_token.clone().ok_or_else(|| {
+                SttError::Configuration(
+                    "Backend auth token is required. Please activate your license.".to_string(),
+                )
+            })?
+        };
 
-            // На выходе из loop всегда помечаем соединение закрытым
-            is_closed_flag.store(true, Ordering::SeqCst);
-            log::info!("Backend receiver task finished");
-        });
-
-        self.receiver_task = Some(receiver_task);
+        log::info!("BackendProvider: auth_token len: {}", auth_token.len());
 
-        // KeepAlive task (best-effort): поддерживает соединение живым, когда пользователь
-        // быстро старт/стопит запись или просто прячет окно на пару секунд.
-        //
-        // Важно: само наличие открытого WS-соединения может держать ресурсы провайдера (Deepgram) на сервере.
-        // Поэтому держим TTL коротким и всегда закрываем соединение по таймеру в TranscriptionService.
-        let ws_write_for_keepalive = ws_write.clone();
-        let is_closed_for_keepalive = self.is_closed.clone();
-        let keepalive_task = tokio::spawn(async move {
-            log::debug!("Backend keepalive task started");
-            loop {
-                tokio::time::sleep(Duration::from_secs(20)).await;
-                if is_closed_for_keepalive.load(Ordering::SeqCst) {
-                    break;
-                }
-                let ping_fut = async {
-                    let mut guard = ws_write_for_keepalive.lock().await;
-                    guard.send(Message::Ping(Vec::new())).await
-                };
+        self.auth_token = Some(auth_token);
+        self.backend_url = backend_url;
+        self.config = Some(config.clone());
 
-                if tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), ping_fut)
-                    .await
-                    .ok()
-                    .and_then(|r| r.ok())
-                    .is_none()
-                {
-                    // Пинг не смогли отправить → считаем соединение закрытым/битым.
-                    is_closed_for_keepalive.store(true, Ordering::SeqCst);
-                    break;
-                }
-            }
-            log::debug!("Backend keepalive task ended");
-        });
-        self.keepalive_task = Some(keepalive_task);
+        Ok(())
+    }
 
-        self.is_streaming = true;
-        self.is_paused = false;
-        self.sent_chunks_count = 0;
-        self.sent_bytes_total = 0;
+    async fn start_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        log::info!("BackendProvider: Starting stream");
 
-        log::info!("BackendProvider: Stream started");
-        Ok(())
+        self.lifecycle.transition(StreamState::Connecting)?;
+        match self.open_stream(on_partial, on_final, on_error, on_connection_quality).await {
+            Ok(()) => {
+                // Сервер мог закрыть соединение ещё до конца старта — тогда переход не пройдёт
+                self.lifecycle.transition(StreamState::Streaming)?;
+                log::info!("BackendProvider: Stream started");
+                Ok(())
+            }
+            Err(e) => {
+                self.lifecycle.force_close();
+                Err(e)
+            }
+        }
     }
 
     async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
-        // Быстрая проверка атомарного флага (без async lock)
-        if self.is_closed.load(Ordering::SeqCst) {
+        // Быстрая проверка атомарного состояния (без async lock)
+        if !self.lifecycle.is_open() {
             // Если соединение закрыто И остаток был < порога — это лимит, а не обрыв.
             // Без этого audio processor loop будет 10 раз ретраить "connection" ошибку,
             // перезатирая корректный limit_exceeded с receiver task.
@@ -933,26 +604,28 @@ impl SttProvider for BackendProvider {
             } else {
                 SttConnectionCategory::Closed
             };
-            return Err(SttError::Connection(SttConnectionError::with_category(
+            return Err(SttError::from_connection(SttConnectionError::with_category(
                 "Connection closed".to_string(),
                 category,
             )));
         }
 
-        if !self.is_streaming {
-            return Err(SttError::Processing("Stream not active".to_string()));
-        }
-
-        if let Some(ref ws_write) = self.ws_write {
+        if let Some(ref transport) = self.transport {
             const SAMPLE_RATE_HZ: usize = 16_000;
             const FRAME_MS: usize = 30;
             const SAMPLES_PER_FRAME: usize = SAMPLE_RATE_HZ * FRAME_MS / 1000; // 480
             const BYTES_PER_SAMPLE: usize = 2;
             const FRAME_BYTES: usize = SAMPLES_PER_FRAME * BYTES_PER_SAMPLE; // 960
 
-            const MIN_FRAMES_PER_MESSAGE: usize = 1; // ~30ms
-            const MAX_FRAMES_PER_MESSAGE: usize = 10; // ~300ms, чтобы догонять беклог без роста msg/sec
-            const MAX_BATCH_WAIT_MS: u64 = 30; // верхняя граница задержки перед отправкой
+            // Размер сообщения — stt.audio_chunking (по умолчанию 30ms, один кадр)
+            let chunk_ms = self
+                .config
+                .as_ref()
+                .map_or(FRAME_MS as u32, |config| config.chunk_ms()) as usize;
+            let min_frames_per_message = chunk_ms.div_ceil(FRAME_MS).max(1);
+            // ~300ms (или одно сообщение заданного размера), чтобы догонять беклог без роста msg/sec
+            let max_frames_per_message = min_frames_per_message.max(10);
+            let max_batch_wait_ms = chunk_ms as u64; // верхняя граница задержки перед отправкой
             const MIN_SEND_INTERVAL_MS: u64 = 25; // 40 msg/s верхняя граница на клиенте
 
             self.audio_batch.reserve(chunk.data.len() * 2);
@@ -965,16 +638,29 @@ impl SttProvider for BackendProvider {
             }
             self.audio_batch_frames += 1;
 
+            // Backpressure: батч не должен расти бесконечно, если отправка не успевает за захватом.
+            // Выбрасываем самое старое аудио (длина батча всегда чётная — целые семплы).
+            const MAX_BATCH_BYTES: usize = SAMPLE_RATE_HZ * BYTES_PER_SAMPLE * 3; // ~3с
+            if self.audio_batch.len() > MAX_BATCH_BYTES {
+                let excess = self.audio_batch.len() - MAX_BATCH_BYTES;
+                self.audio_batch.drain(..excess);
+                self.audio_batch_frames = self.audio_batch_frames.min(MAX_BATCH_BYTES / FRAME_BYTES);
+                log::warn!(
+                    "Backend: audio batch overflow, dropped {} ms of oldest audio",
+                    excess * 1000 / (SAMPLE_RATE_HZ * BYTES_PER_SAMPLE)
+                );
+            }
+
             let batch_age_ms = self
                 .batch_started_at
                 .map(|t| now.saturating_duration_since(t).as_millis() as u64)
                 .unwrap_or(0);
-            let ready_to_send = self.audio_batch_frames >= MIN_FRAMES_PER_MESSAGE || batch_age_ms >= MAX_BATCH_WAIT_MS;
+            let ready_to_send = self.audio_batch_frames >= min_frames_per_message || batch_age_ms >= max_batch_wait_ms;
             if !ready_to_send {
                 return Ok(());
             }
 
-            let frames_to_send = self.audio_batch_frames.min(MAX_FRAMES_PER_MESSAGE);
+            let frames_to_send = self.audio_batch_frames.min(max_frames_per_message);
             let bytes_to_send = frames_to_send * FRAME_BYTES;
             if self.audio_batch.len() < bytes_to_send {
                 return Ok(());
@@ -1002,22 +688,20 @@ impl SttProvider for BackendProvider {
                 );
             }
 
-            let send_fut = async {
-                let mut guard = ws_write.lock().await;
-                guard.send(Message::Binary(bytes)).await
-            };
-
-            match tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), send_fut).await {
-                Ok(Ok(())) => {}
-                Ok(Err(e)) => {
-                    self.is_closed.store(true, Ordering::SeqCst);
+            match transport
+                .send_with_timeout(Message::Binary(bytes), Duration::from_secs(WS_SEND_TIMEOUT_SECS))
+                .await
+            {
+                Ok(()) => {}
+                Err(WsTransportError::Ws(e)) => {
+                    self.lifecycle.force_close();
                     return Err(SttError::Connection(SttConnectionError::simple(format!(
                         "Failed to send audio: {}",
                         e
                     ))));
                 }
-                Err(_) => {
-                    self.is_closed.store(true, Ordering::SeqCst);
+                Err(WsTransportError::Timeout) => {
+                    self.lifecycle.force_close();
                     return Err(SttError::Connection(SttConnectionError::with_category(
                         "WS send timeout".to_string(),
                         SttConnectionCategory::Timeout,
@@ -1038,59 +722,41 @@ impl SttProvider for BackendProvider {
     async fn stop_stream(&mut self) -> SttResult<()> {
         log::info!("BackendProvider: Stopping stream");
 
-        if !self.audio_batch.is_empty() && !self.is_closed.load(Ordering::SeqCst) {
-            if let Some(ref ws_write) = self.ws_write {
+        if !self.audio_batch.is_empty() && self.lifecycle.is_open() {
+            if let Some(ref transport) = self.transport {
                 let bytes = std::mem::take(&mut self.audio_batch);
                 self.audio_batch_frames = 0;
                 self.next_send_at = None;
                 self.batch_started_at = None;
                 self.sent_chunks_count += 1;
                 self.sent_bytes_total += bytes.len();
-                let flush_fut = async {
-                    let mut guard = ws_write.lock().await;
-                    guard.send(Message::Binary(bytes)).await
-                };
-                let _ = tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), flush_fut).await;
+                let _ = transport
+                    .send_with_timeout(Message::Binary(bytes), Duration::from_secs(WS_SEND_TIMEOUT_SECS))
+                    .await;
             }
         }
 
-        // ПЕРВЫМ ДЕЛОМ ставим флаг закрытия — это предотвращает race condition
-        self.is_closed.store(true, Ordering::SeqCst);
-
-        if !self.is_streaming {
-            return Ok(());
+        // ПЕРВЫМ ДЕЛОМ переходим в Closing — receiver task не примет наше закрытие за обрыв
+        if let Err(e) = self.lifecycle.transition(StreamState::Closing) {
+            // Соединение уже оборвалось само — ресурсы всё равно нужно освободить
+            if self.transport.is_none() {
+                return Ok(());
+            }
+            log::debug!("BackendProvider: cleaning up dropped connection ({})", e);
         }
 
         // Отправляем Close message
-        if self.ws_write.is_some() {
+        if self.transport.is_some() {
             let close_msg = ClientMessage::Close;
             let _ = self.send_json(&close_msg).await;
         }
 
-        // Закрываем WebSocket
-        if let Some(ref ws_write) = self.ws_write {
-            let close_fut = async {
-                let mut guard = ws_write.lock().await;
-                guard.close().await
-            };
-            let _ = tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), close_fut).await;
+        // Закрываем WebSocket и останавливаем receiver/keepalive
+        if let Some(transport) = self.transport.take() {
+            transport.close(Duration::from_secs(WS_SEND_TIMEOUT_SECS)).await;
         }
 
-        // Останавливаем receiver task
-        if let Some(task) = self.receiver_task.take() {
-            task.abort();
-            let _ = task.await;
-        }
-
-        // Останавливаем keepalive task
-        if let Some(task) = self.keepalive_task.take() {
-            task.abort();
-            let _ = task.await;
-        }
-
-        self.ws_write = None;
-        self.is_streaming = false;
-        self.is_paused = false;
+        self.lifecycle.force_close();
         self.session_id = None;
         self.next_send_at = None;
         self.batch_started_at = None;
@@ -1114,29 +780,14 @@ impl SttProvider for BackendProvider {
     async fn abort(&mut self) -> SttResult<()> {
         log::info!("BackendProvider: Aborting");
 
-        // ПЕРВЫМ ДЕЛОМ ставим флаг закрытия
-        self.is_closed.store(true, Ordering::SeqCst);
-
-        if let Some(task) = self.keepalive_task.take() {
-            task.abort();
-        }
+        // ПЕРВЫМ ДЕЛОМ закрываем состояние
+        self.lifecycle.force_close();
 
         // Принудительно закрываем без отправки Close
-        if let Some(ref ws_write) = self.ws_write {
-            let close_fut = async {
-                let mut guard = ws_write.lock().await;
-                guard.close().await
-            };
-            let _ = tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), close_fut).await;
+        if let Some(transport) = self.transport.take() {
+            transport.close(Duration::from_secs(WS_SEND_TIMEOUT_SECS)).await;
         }
 
-        if let Some(task) = self.receiver_task.take() {
-            task.abort();
-        }
-
-        self.ws_write = None;
-        self.is_streaming = false;
-        self.is_paused = false;
         self.session_id = None;
         {
             let mut state = self.callbacks.lock().await;
@@ -1150,27 +801,23 @@ impl SttProvider for BackendProvider {
     }
 
     async fn pause_stream(&mut self) -> SttResult<()> {
-        if !self.is_streaming {
-            return Err(SttError::Processing("Stream not active".to_string()));
-        }
-        if self.is_paused {
+        if self.lifecycle.is_paused() {
             return Ok(());
         }
+        self.lifecycle.transition(StreamState::Paused)?;
 
         // Флашим хвост батча, чтобы не потерять последние миллисекунды аудио перед паузой.
-        if !self.audio_batch.is_empty() && !self.is_closed.load(Ordering::SeqCst) {
-            if let Some(ref ws_write) = self.ws_write {
+        if !self.audio_batch.is_empty() && self.lifecycle.is_open() {
+            if let Some(ref transport) = self.transport {
                 let bytes = std::mem::take(&mut self.audio_batch);
                 self.audio_batch_frames = 0;
                 self.next_send_at = None;
                 self.batch_started_at = None;
                 self.sent_chunks_count += 1;
                 self.sent_bytes_total += bytes.len();
-                let flush_fut = async {
-                    let mut guard = ws_write.lock().await;
-                    guard.send(Message::Binary(bytes)).await
-                };
-                let _ = tokio::time::timeout(Duration::from_secs(WS_SEND_TIMEOUT_SECS), flush_fut).await;
+                let _ = transport
+                    .send_with_timeout(Message::Binary(bytes), Duration::from_secs(WS_SEND_TIMEOUT_SECS))
+                    .await;
             }
         }
 
@@ -1180,7 +827,6 @@ impl SttProvider for BackendProvider {
             log::warn!("BackendProvider: finalize failed on pause: {}", e);
         }
 
-        self.is_paused = true;
         Ok(())
     }
 
@@ -1191,15 +837,13 @@ impl SttProvider for BackendProvider {
         on_error: ErrorCallback,
         on_connection_quality: ConnectionQualityCallback,
     ) -> SttResult<()> {
-        if !self.is_streaming {
-            return Err(SttError::Processing("Stream not active".to_string()));
-        }
-        if self.is_closed.load(Ordering::SeqCst) {
+        if self.lifecycle.is_closed() {
             return Err(SttError::Connection(SttConnectionError::with_category(
                 "Connection closed".to_string(),
                 SttConnectionCategory::Closed,
             )));
         }
+        self.lifecycle.check(StreamState::Streaming)?;
 
         // Готовим pending callbacks. Активируем их только после первого ACK на новое аудио,
         // чтобы не словить "поздние" результаты от предыдущей записи в новую UI-сессию.
@@ -1215,7 +859,7 @@ impl SttProvider for BackendProvider {
             state.swap_after_seq = self.sent_chunks_count as u64;
         }
 
-        self.is_paused = false;
+        self.lifecycle.transition(StreamState::Streaming)?;
         Ok(())
     }
 
@@ -1232,27 +876,322 @@ impl SttProvider for BackendProvider {
     }
 
     fn is_connection_alive(&self) -> bool {
-        if !(self.is_streaming && self.is_paused && self.ws_write.is_some()) {
-            return false;
+        self.lifecycle.is_paused() && self.transport.as_ref().is_some_and(|t| t.is_alive())
+    }
+
+    fn remaining_quota_secs(&self) -> Option<f32> {
+        // f32::MAX — UsageUpdate на этом соединении ещё не приходил
+        let remaining = f32::from_bits(self.last_remaining_secs.load(Ordering::SeqCst));
+        (remaining < f32::MAX).then_some(remaining)
+    }
+
+    fn debug_state(&self) -> crate::domain::SttProviderDebugState {
+        crate::domain::SttProviderDebugState {
+            streaming: self.lifecycle.is_streaming(),
+            paused: self.lifecycle.is_paused(),
+            buffered_samples: self.audio_batch_frames,
+            receiver_alive: self.transport.as_ref().map(|t| t.is_alive()),
         }
-        if self.is_closed.load(Ordering::SeqCst) {
-            return false;
+    }
+
+    fn is_online(&self) -> bool {
+        true // Backend всегда онлайн (облачный сервис)
+    }
+}
+
+/// Разбор сообщений бэкенда внутри receiver task транспорта
+struct BackendCodec {
+    // Берём callbacks из общего состояния, чтобы они могли обновляться при resume_stream
+    callbacks: Arc<Mutex<CallbackState>>,
+    on_usage: Option<UsageUpdateCallback>,
+    lifecycle: StreamLifecycle,
+    remaining: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl SttMessageCodec for BackendCodec {
+    fn provider_name(&self) -> &'static str {
+        "Backend"
+    }
+
+    async fn on_text(&mut self, text: String) {
+        match serde_json::from_str::<ServerMessage>(&text) {
+            Ok(server_msg) => {
+                match server_msg {
+                    ServerMessage::Ready { session_id } => {
+                        log::info!("Session ready: {}", session_id);
+                        // Уведомляем о хорошем качестве связи
+                        let cb = {
+                            let state = self.callbacks.lock().await;
+                            state
+                                .active
+                                .as_ref()
+                                .map(|c| c.on_connection_quality.clone())
+                        };
+                        if let Some(cb) = cb {
+                            cb("Good".to_string(), None);
+                        }
+                    }
+
+                    ServerMessage::Ack { seq } => {
+                        log::trace!("Ack received: seq={}", seq);
+                        // Если есть pending callbacks (новая UI-сессия) — активируем их на первом ACK.
+                        // Это даёт чёткую границу между "старыми" и "новыми" результатами.
+                        let swapped = {
+                            let mut state = self.callbacks.lock().await;
+                            if state.swap_on_next_ack && seq > state.swap_after_seq {
+                                state.swap_on_next_ack = false;
+                                state.swap_after_seq = 0;
+                                if state.pending.is_some() {
+                                    state.active = state.pending.take();
+                                }
+                                true
+                            } else {
+                                false
+                            }
+                        };
+                        if swapped {
+                            log::debug!("Callbacks switched after first ACK (new recording session)");
+                        }
+                    }
+
+                    ServerMessage::Partial { text, confidence } => {
+                        log::debug!("Partial: {} (conf: {:?})", text, confidence);
+                        let mut transcription = Transcription::partial(text);
+                        if let Some(conf) = confidence {
+                            transcription = transcription.with_confidence(conf);
+                        }
+                        let cb = {
+                            let state = self.callbacks.lock().await;
+                            state.active.as_ref().map(|c| c.on_partial.clone())
+                        };
+                        if let Some(cb) = cb {
+                            cb(transcription);
+                        }
+                    }
+
+                    ServerMessage::Final {
+                        text,
+                        confidence,
+                        duration_ms,
+                        alternatives,
+                        speaker,
+                    } => {
+                        log::debug!(
+                            "Final: {} (conf: {:?}, dur: {}ms, alternatives: {}, speaker: {:?})",
+                            text,
+                            confidence,
+                            duration_ms,
+                            alternatives.len(),
+                            speaker
+                        );
+                        let alternatives = alternatives
+                            .into_iter()
+                            .filter(|a| !a.text.is_empty() && a.text != text)
+                            .map(|a| AlternativeText::new(a.text, a.confidence))
+                            .collect();
+                        let mut transcription = Transcription::final_result(text)
+                            .with_timing(0.0, duration_ms as f64 / 1000.0)
+                            .with_alternatives(alternatives)
+                            .with_speaker(speaker);
+                        if let Some(conf) = confidence {
+                            transcription = transcription.with_confidence(conf);
+                        }
+                        let cb = {
+                            let state = self.callbacks.lock().await;
+                            state.active.as_ref().map(|c| c.on_final.clone())
+                        };
+                        if let Some(cb) = cb {
+                            cb(transcription);
+                        }
+                    }
+
+                    ServerMessage::UsageUpdate {
+                        seconds_used,
+                        seconds_remaining_plan,
+                        seconds_remaining_total,
+                        ..
+                    } => {
+                        let remaining = seconds_remaining_total
+                            .unwrap_or(seconds_remaining_plan);
+                        self.remaining.store(remaining.to_bits(), Ordering::SeqCst);
+                        log::debug!(
+                            "Usage: used={:.1}s, remaining={:.1}s",
+                            seconds_used,
+                            remaining
+                        );
+                        if let Some(ref cb) = self.on_usage {
+                            cb(seconds_used, remaining);
+                        }
+                    }
+
+                    ServerMessage::Resumed {
+                        session_id,
+                        last_seq_acked,
+                    } => {
+                        log::info!(
+                            "Session resumed: {}, last_seq: {}",
+                            session_id,
+                            last_seq_acked
+                        );
+                        let cb = {
+                            let state = self.callbacks.lock().await;
+                            state
+                                .active
+                                .as_ref()
+                                .map(|c| c.on_connection_quality.clone())
+                        };
+                        if let Some(cb) = cb {
+                            cb("Good".to_string(), None);
+                        }
+                    }
+
+                    ServerMessage::Error { code, message } => {
+                        log::error!("Server error: {} - {}", code, message);
+                        let cb = {
+                            let state = self.callbacks.lock().await;
+                            state.active.as_ref().map(|c| c.on_error.clone())
+                        };
+                        if let Some(cb) = cb {
+                            let category = match code.as_str() {
+                                "timeout" => Some(SttConnectionCategory::Timeout),
+                                "rate_limit" | "too_many_sessions" => Some(SttConnectionCategory::RateLimited),
+                                "LIMIT_EXCEEDED" => Some(SttConnectionCategory::LimitExceeded),
+                                _ => Some(SttConnectionCategory::Unknown),
+                            };
+                            cb(SttError::from_connection(SttConnectionError {
+                                message,
+                                details: SttConnectionDetails {
+                                    category,
+                                    server_code: Some(code),
+                                    ..Default::default()
+                                },
+                            }));
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to parse server message: {} - {}", e, text);
+            }
         }
-        if let Some(task) = &self.receiver_task {
-            if task.is_finished() {
-                return false;
+    }
+
+    async fn on_close(&mut self, frame: Option<CloseFrame<'static>>) {
+        // Если мы сами инициировали закрытие (stop_stream) — не эмитим ошибку в UI.
+        if matches!(self.lifecycle.force_close(), StreamState::Closing | StreamState::Closed) {
+            return;
+        }
+        let cb = {
+            let state = self.callbacks.lock().await;
+            state.active.as_ref().map(|c| c.on_error.clone())
+        };
+        if let Some(cb) = cb {
+            let code_u16 = frame.as_ref().map(|f| u16::from(f.code));
+            let mut category = match code_u16 {
+                Some(1008) => SttConnectionCategory::LimitExceeded,
+                Some(1012) | Some(1013) | Some(1014) => SttConnectionCategory::ServerUnavailable,
+                Some(1000) => SttConnectionCategory::Closed,
+                _ => SttConnectionCategory::ServerUnavailable,
+            };
+
+            // Fallback: сервер может закрыть WS без кода 1008 (race condition между
+            // отправкой LIMIT_EXCEEDED и close frame). Если последний UsageUpdate
+            // показывал почти нулевой остаток — это лимит, а не обрыв связи.
+            let remaining = f32::from_bits(self.remaining.load(Ordering::SeqCst));
+            if category != SttConnectionCategory::LimitExceeded
+                && remaining < LIMIT_REMAINING_THRESHOLD
+            {
+                log::warn!(
+                    "Close frame without 1008, but last remaining={:.1}s < {:.0}s → treating as limit_exceeded",
+                    remaining,
+                    LIMIT_REMAINING_THRESHOLD
+                );
+                category = SttConnectionCategory::LimitExceeded;
             }
+
+            cb(SttError::from_connection(SttConnectionError {
+                message: "WebSocket closed by server".to_string(),
+                details: SttConnectionDetails {
+                    category: Some(category),
+                    ws_close_code: code_u16,
+                    ..Default::default()
+                },
+            }));
+        }
+    }
+
+    async fn on_error(&mut self, e: tungstenite::Error) {
+        // Если закрытие инициировано нами — не поднимаем "ошибку соединения" в UI.
+        if matches!(self.lifecycle.force_close(), StreamState::Closing | StreamState::Closed) {
+            return;
         }
-        if let Some(task) = &self.keepalive_task {
-            if task.is_finished() {
-                return false;
+        let cb = {
+            let state = self.callbacks.lock().await;
+            state.active.as_ref().map(|c| c.on_error.clone())
+        };
+        if let Some(cb) = cb {
+            let mut details = match &e {
+                tungstenite::Error::Io(ioe) => {
+                    let kind = ioe.kind();
+                    let kind_str = format!("{:?}", kind);
+                    let os_error = ioe.raw_os_error();
+                    let category = match kind {
+                        std::io::ErrorKind::ConnectionRefused => SttConnectionCategory::Refused,
+                        std::io::ErrorKind::ConnectionReset => SttConnectionCategory::Reset,
+                        std::io::ErrorKind::BrokenPipe => SttConnectionCategory::ServerUnavailable,
+                        std::io::ErrorKind::NotConnected
+                        | std::io::ErrorKind::NetworkUnreachable
+                        | std::io::ErrorKind::HostUnreachable
+                        | std::io::ErrorKind::AddrNotAvailable => SttConnectionCategory::Offline,
+                        std::io::ErrorKind::TimedOut => SttConnectionCategory::Timeout,
+                        _ => SttConnectionCategory::Unknown,
+                    };
+                    SttConnectionDetails {
+                        category: Some(category),
+                        io_error_kind: Some(kind_str),
+                        os_error,
+                        ..Default::default()
+                    }
+                }
+                tungstenite::Error::Tls(_) => SttConnectionDetails {
+                    category: Some(SttConnectionCategory::Tls),
+                    ..Default::default()
+                },
+                tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed => SttConnectionDetails {
+                    category: Some(SttConnectionCategory::Closed),
+                    ..Default::default()
+                },
+                _ => SttConnectionDetails {
+                    category: Some(SttConnectionCategory::Unknown),
+                    ..Default::default()
+                },
+            };
+
+            // Fallback: обрыв соединения (reset/closed) при почти нулевом остатке
+            // — скорее всего сервер закрыл из-за лимита без нормального close frame.
+            let remaining = f32::from_bits(self.remaining.load(Ordering::SeqCst));
+            if details.category != Some(SttConnectionCategory::LimitExceeded)
+                && remaining < LIMIT_REMAINING_THRESHOLD
+            {
+                log::warn!(
+                    "WS error with last remaining={:.1}s < {:.0}s → treating as limit_exceeded",
+                    remaining,
+                    LIMIT_REMAINING_THRESHOLD
+                );
+                details.category = Some(SttConnectionCategory::LimitExceeded);
             }
+
+            cb(SttError::from_connection(SttConnectionError {
+                message: e.to_string(),
+                details,
+            }));
         }
-        true
     }
 
-    fn is_online(&self) -> bool {
-        true // Backend всегда онлайн (облачный сервис)
+    async fn on_finished(&mut self) {
+        // На выходе из receiver всегда помечаем соединение закрытым
+        self.lifecycle.force_close();
     }
 }
 
@@ -1263,7 +1202,7 @@ mod tests {
     #[test]
     fn test_backend_provider_new() {
         let provider = BackendProvider::new();
-        assert!(!provider.is_streaming);
+        assert_eq!(provider.lifecycle.state(), StreamState::Idle);
         assert!(provider.auth_token.is_none());
         // В debug сборке (тесты) должен быть dev URL
         #[cfg(debug_assertions)]
@@ -1289,4 +1228,38 @@ mod tests {
         let provider = BackendProvider::new();
         assert!(provider.supports_streaming());
     }
+
+    fn handshake_rejection(code: &str) -> tungstenite::Error {
+        let resp = http::Response::builder()
+            .status(http::StatusCode::TOO_MANY_REQUESTS)
+            .header("x-voicetext-error-code", code)
+            .body(None)
+            .unwrap();
+        tungstenite::Error::Http(resp)
+    }
+
+    #[test]
+    fn test_limit_exceeded_handshake_is_quota_error() {
+        let provider = BackendProvider::new();
+        match provider.connect_error(handshake_rejection("LIMIT_EXCEEDED")) {
+            SttError::QuotaExceeded { details, .. } => {
+                assert_eq!(details.category, Some(SttConnectionCategory::LimitExceeded));
+                assert_eq!(details.http_status, Some(429));
+                assert_eq!(details.server_code.as_deref(), Some("LIMIT_EXCEEDED"));
+            }
+            other => panic!("expected quota error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_too_many_sessions_handshake_is_rate_limited() {
+        let provider = BackendProvider::new();
+        match provider.connect_error(handshake_rejection("TOO_MANY_SESSIONS")) {
+            SttError::RateLimited { details, .. } => {
+                assert_eq!(details.category, Some(SttConnectionCategory::RateLimited));
+                assert_eq!(details.http_status, Some(429));
+            }
+            other => panic!("expected rate limit error, got {:?}", other),
+        }
+    }
 }
@@ -16,6 +16,9 @@ pub enum ClientMessage {
         provider: String,
         /// Язык распознавания (ISO 639-1)
         language: String,
+        /// Модель провайдера (None — выбирает бэкенд)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
         /// Частота дискретизации в Hz
         sample_rate: u32,
         /// Количество каналов (1 = моно)
@@ -25,6 +28,12 @@ pub enum ClientMessage {
         /// Ключевые термины для улучшения распознавания
         #[serde(skip_serializing_if = "Option::is_none")]
         keyterms: Option<Vec<String>>,
+        /// Сколько гипотез (n-best) вернуть для финальных фраз
+        #[serde(skip_serializing_if = "Option::is_none")]
+        alternatives: Option<u8>,
+        /// Размечать финальные фразы номером говорящего
+        #[serde(skip_serializing_if = "Option::is_none")]
+        diarize: Option<bool>,
     },
 
     /// Клиент закрывает сессию
@@ -38,6 +47,14 @@ pub enum ClientMessage {
     Finalize,
 }
 
+/// Альтернативная гипотеза распознавания в Final сообщении
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerAlternative {
+    pub text: String,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+}
+
 /// Сообщения от бэкенда к клиенту
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -67,6 +84,12 @@ pub enum ServerMessage {
         confidence: Option<f32>,
         /// Длительность обработанного аудио в мс
         duration_ms: u64,
+        /// Остальные гипотезы (если запрошены в Config), лучшие первыми
+        #[serde(default)]
+        alternatives: Vec<ServerAlternative>,
+        /// Номер говорящего (если в Config была включена диаризация)
+        #[serde(default)]
+        speaker: Option<u32>,
     },
 
     /// Обновление usage (для отображения на клиенте)
@@ -102,15 +125,44 @@ mod tests {
             protocol_v: 1,
             provider: "deepgram".to_string(),
             language: "ru".to_string(),
+            model: None,
             sample_rate: 16000,
             channels: 1,
             encoding: "pcm_s16le".to_string(),
             keyterms: None,
+            alternatives: None,
+            diarize: None,
         };
 
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains(r#""type":"config""#));
         assert!(json.contains(r#""provider":"deepgram""#));
+        assert!(!json.contains("alternatives"));
+        assert!(!json.contains("diarize"));
+        assert!(!json.contains("model"));
+    }
+
+    #[test]
+    fn test_deserialize_final_message_with_and_without_alternatives() {
+        let json = r#"{"type":"final","text":"привет","confidence":0.9,"duration_ms":1200}"#;
+        match serde_json::from_str::<ServerMessage>(json).unwrap() {
+            ServerMessage::Final { alternatives, speaker, .. } => {
+                assert!(alternatives.is_empty());
+                assert_eq!(speaker, None);
+            }
+            _ => panic!("Expected Final message"),
+        }
+
+        let json = r#"{"type":"final","text":"привет","duration_ms":1200,"alternatives":[{"text":"превед","confidence":0.4}],"speaker":1}"#;
+        match serde_json::from_str::<ServerMessage>(json).unwrap() {
+            ServerMessage::Final { alternatives, speaker, .. } => {
+                assert_eq!(speaker, Some(1));
+                assert_eq!(alternatives.len(), 1);
+                assert_eq!(alternatives[0].text, "превед");
+                assert_eq!(alternatives[0].confidence, Some(0.4));
+            }
+            _ => panic!("Expected Final message"),
+        }
     }
 
     #[test]
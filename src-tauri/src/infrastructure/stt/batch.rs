@@ -0,0 +1,359 @@
+//! Prerecorded (REST) API провайдеров: запись целиком одним запросом, без WebSocket.
+//!
+//! Deepgram: POST /v1/listen с WAV в теле, ответ сразу.
+//! AssemblyAI: upload → создание transcript → опрос до статуса completed.
+
+use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::domain::{
+    BatchSttProvider, DEFAULT_DEEPGRAM_MODEL, SttConfig, SttConnectionCategory, SttConnectionDetails, SttConnectionError, SttError,
+    SttResult,
+};
+use crate::infrastructure::embedded_keys;
+use super::audio_encoder::wav_bytes;
+use super::ws_transport::parse_retry_after;
+
+const DEEPGRAM_LISTEN_URL: &str = "https://api.deepgram.com/v1/listen";
+const ASSEMBLYAI_API_URL: &str = "https://api.assemblyai.com/v2";
+
+/// Таймаут одного HTTP запроса (запись до нескольких минут загружается и расшифровывается не мгновенно)
+const BATCH_REQUEST_TIMEOUT_SECS: u64 = 300;
+
+/// AssemblyAI расшифровывает асинхронно: опрашиваем статус раз в секунду, не дольше 30 минут
+const ASSEMBLYAI_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const ASSEMBLYAI_MAX_POLLS: u32 = 1800;
+
+fn http_client() -> SttResult<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(BATCH_REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| SttError::Internal(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Ошибка сети (до ответа сервера)
+fn request_error(provider: &str, e: reqwest::Error) -> SttError {
+    let category = if e.is_timeout() {
+        SttConnectionCategory::Timeout
+    } else if e.is_connect() {
+        SttConnectionCategory::Refused
+    } else {
+        SttConnectionCategory::Unknown
+    };
+    SttError::Connection(SttConnectionError::with_category(
+        format!("{} request failed: {}", provider, e),
+        category,
+    ))
+}
+
+/// Ответ сервера с кодом ошибки → ошибка по таксономии SttError
+fn http_error(provider: &str, status: u16, retry_after_secs: Option<u64>, body: &str) -> SttError {
+    let message = format!("{} HTTP {}: {}", provider, status, body.chars().take(300).collect::<String>());
+    let details = |category| SttConnectionDetails {
+        category: Some(category),
+        http_status: Some(status),
+        ..Default::default()
+    };
+    match status {
+        401 | 403 => SttError::Authentication(message),
+        // Deepgram: 402 — на балансе не осталось средств
+        402 => SttError::QuotaExceeded {
+            message,
+            details: details(SttConnectionCategory::LimitExceeded),
+        },
+        429 => SttError::RateLimited {
+            message,
+            retry_after_secs,
+            details: details(SttConnectionCategory::RateLimited),
+        },
+        500..=599 => SttError::Connection(SttConnectionError {
+            message,
+            details: details(SttConnectionCategory::ServerUnavailable),
+        }),
+        _ => SttError::Connection(SttConnectionError {
+            message,
+            details: details(SttConnectionCategory::Http),
+        }),
+    }
+}
+
+/// Отправляет запрос и возвращает JSON тела (ошибки HTTP → SttError)
+async fn send_json(provider: &str, request: reqwest::RequestBuilder) -> SttResult<serde_json::Value> {
+    let response = request.send().await.map_err(|e| request_error(provider, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let retry_after_secs = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        let body = response.text().await.unwrap_or_default();
+        return Err(http_error(provider, status.as_u16(), retry_after_secs, &body));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| SttError::ProviderBug(format!("{} returned invalid JSON: {}", provider, e)))
+}
+
+/// Deepgram prerecorded API (тот же ключ и модель, что у стриминга)
+pub struct DeepgramBatchProvider {
+    client: reqwest::Client,
+    api_key: String,
+    endpoint: String,
+    config: SttConfig,
+}
+
+impl DeepgramBatchProvider {
+    pub fn from_config(config: &SttConfig) -> SttResult<Self> {
+        // Приоритет: пользовательский ключ → встроенный ключ
+        let api_key = config
+            .deepgram_api_key
+            .clone()
+            .or_else(|| {
+                embedded_keys::has_embedded_deepgram_key().then(|| embedded_keys::EMBEDDED_DEEPGRAM_KEY.to_string())
+            })
+            .ok_or_else(|| {
+                SttError::Configuration("Deepgram API key is required (either user key or embedded key)".to_string())
+            })?;
+        Ok(Self {
+            client: http_client()?,
+            api_key,
+            endpoint: DEEPGRAM_LISTEN_URL.to_string(),
+            config: config.clone(),
+        })
+    }
+
+    /// Подменяет endpoint (например, на локальный mock-сервер)
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    fn url(&self) -> String {
+        let model = self.config.effective_model().unwrap_or(DEFAULT_DEEPGRAM_MODEL);
+        let mut url = format!("{}?model={}&punctuate=true", self.endpoint, urlencoding::encode(model));
+        if self.config.auto_detect_language {
+            url.push_str("&detect_language=true");
+        } else {
+            url.push_str(&format!("&language={}", urlencoding::encode(&self.config.language)));
+        }
+        if let Some(raw) = self.config.deepgram_keyterms.as_deref() {
+            for term in raw.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                url.push_str(&format!("&keyterm={}", urlencoding::encode(term)));
+            }
+        }
+        if self.config.diarize {
+            url.push_str("&diarize=true");
+        }
+        url
+    }
+}
+
+#[async_trait]
+impl BatchSttProvider for DeepgramBatchProvider {
+    async fn transcribe(&self, samples: &[i16], sample_rate: u32) -> SttResult<String> {
+        if samples.is_empty() {
+            return Ok(String::new());
+        }
+        let request = self
+            .client
+            .post(self.url())
+            .header(reqwest::header::AUTHORIZATION, format!("Token {}", self.api_key))
+            .header(reqwest::header::CONTENT_TYPE, "audio/wav")
+            .body(wav_bytes(samples, sample_rate));
+        let json = send_json("Deepgram", request).await?;
+
+        json["results"]["channels"][0]["alternatives"][0]["transcript"]
+            .as_str()
+            .map(|t| t.trim().to_string())
+            .ok_or_else(|| SttError::ProviderBug("Deepgram response has no transcript".to_string()))
+    }
+
+    fn name(&self) -> &str {
+        "Deepgram (prerecorded)"
+    }
+}
+
+/// AssemblyAI async transcription API
+pub struct AssemblyAIBatchProvider {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+    config: SttConfig,
+}
+
+impl AssemblyAIBatchProvider {
+    pub fn from_config(config: &SttConfig) -> SttResult<Self> {
+        // Приоритет: пользовательский ключ → встроенный ключ
+        let api_key = config
+            .assemblyai_api_key
+            .clone()
+            .or_else(|| {
+                embedded_keys::has_embedded_assemblyai_key()
+                    .then(|| embedded_keys::EMBEDDED_ASSEMBLYAI_KEY.to_string())
+            })
+            .ok_or_else(|| {
+                SttError::Configuration("AssemblyAI API key is required (either user key or embedded key)".to_string())
+            })?;
+        Ok(Self {
+            client: http_client()?,
+            api_key,
+            base_url: ASSEMBLYAI_API_URL.to_string(),
+            config: config.clone(),
+        })
+    }
+
+    /// Подменяет базовый URL API (например, на локальный mock-сервер)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    fn transcript_request(&self, audio_url: &str) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "audio_url": audio_url,
+            "punctuate": true,
+            "format_text": true,
+            "speaker_labels": self.config.diarize,
+        });
+        if self.config.auto_detect_language {
+            body["language_detection"] = serde_json::Value::Bool(true);
+        } else {
+            body["language_code"] = serde_json::Value::String(self.config.language.clone());
+        }
+        body
+    }
+}
+
+#[async_trait]
+impl BatchSttProvider for AssemblyAIBatchProvider {
+    async fn transcribe(&self, samples: &[i16], sample_rate: u32) -> SttResult<String> {
+        if samples.is_empty() {
+            return Ok(String::new());
+        }
+
+        // 1. Загружаем аудио
+        let upload = send_json(
+            "AssemblyAI",
+            self.client
+                .post(format!("{}/upload", self.base_url))
+                .header(reqwest::header::AUTHORIZATION, &self.api_key)
+                .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                .body(wav_bytes(samples, sample_rate)),
+        )
+        .await?;
+        let audio_url = upload["upload_url"]
+            .as_str()
+            .ok_or_else(|| SttError::ProviderBug("AssemblyAI upload response has no upload_url".to_string()))?;
+
+        // 2. Создаём задачу расшифровки
+        let created = send_json(
+            "AssemblyAI",
+            self.client
+                .post(format!("{}/transcript", self.base_url))
+                .header(reqwest::header::AUTHORIZATION, &self.api_key)
+                .json(&self.transcript_request(audio_url)),
+        )
+        .await?;
+        let id = created["id"]
+            .as_str()
+            .ok_or_else(|| SttError::ProviderBug("AssemblyAI transcript response has no id".to_string()))?
+            .to_string();
+
+        // 3. Ждём результат
+        for _ in 0..ASSEMBLYAI_MAX_POLLS {
+            let transcript = send_json(
+                "AssemblyAI",
+                self.client
+                    .get(format!("{}/transcript/{}", self.base_url, id))
+                    .header(reqwest::header::AUTHORIZATION, &self.api_key),
+            )
+            .await?;
+            match transcript["status"].as_str() {
+                Some("completed") => {
+                    return Ok(transcript["text"].as_str().unwrap_or_default().trim().to_string());
+                }
+                Some("error") => {
+                    let error = transcript["error"].as_str().unwrap_or("unknown error");
+                    return Err(SttError::Processing(format!("AssemblyAI transcription failed: {}", error)));
+                }
+                _ => tokio::time::sleep(ASSEMBLYAI_POLL_INTERVAL).await,
+            }
+        }
+        Err(SttError::Connection(SttConnectionError::with_category(
+            format!("AssemblyAI transcript {} is not ready in time", id),
+            SttConnectionCategory::Timeout,
+        )))
+    }
+
+    fn name(&self) -> &str {
+        "AssemblyAI (async)"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SttProviderType;
+
+    fn config(provider: SttProviderType) -> SttConfig {
+        let mut config = SttConfig::new(provider).with_language("ru");
+        config.deepgram_api_key = Some("dg-key".to_string());
+        config.assemblyai_api_key = Some("aai-key".to_string());
+        config
+    }
+
+    #[test]
+    fn deepgram_url_carries_stream_settings() {
+        let mut config = config(SttProviderType::Deepgram);
+        config.deepgram_keyterms = Some("Kubernetes, Voice Text".to_string());
+        config.diarize = true;
+        let provider = DeepgramBatchProvider::from_config(&config).unwrap().with_endpoint("http://localhost/v1/listen");
+        assert_eq!(
+            provider.url(),
+            "http://localhost/v1/listen?model=nova-3&punctuate=true&language=ru\
+             &keyterm=Kubernetes&keyterm=Voice%20Text&diarize=true"
+        );
+
+        config.auto_detect_language = true;
+        config.model = Some("nova-2".to_string());
+        let provider = DeepgramBatchProvider::from_config(&config).unwrap();
+        assert!(provider.url().contains("model=nova-2&punctuate=true&detect_language=true"));
+
+        // Модель под язык важнее общей
+        config.auto_detect_language = false;
+        let config = config.with_language_model("ru", "nova-3");
+        let provider = DeepgramBatchProvider::from_config(&config).unwrap();
+        assert!(provider.url().contains("model=nova-3&punctuate=true&language=ru"));
+    }
+
+    #[test]
+    fn assemblyai_request_uses_language_or_detection() {
+        let mut config = config(SttProviderType::AssemblyAI);
+        let provider = AssemblyAIBatchProvider::from_config(&config).unwrap();
+        let body = provider.transcript_request("https://cdn/a");
+        assert_eq!(body["language_code"], "ru");
+        assert!(body.get("language_detection").is_none());
+
+        config.auto_detect_language = true;
+        let provider = AssemblyAIBatchProvider::from_config(&config).unwrap();
+        let body = provider.transcript_request("https://cdn/a");
+        assert_eq!(body["language_detection"], true);
+        assert!(body.get("language_code").is_none());
+    }
+
+    #[test]
+    fn http_errors_follow_stt_taxonomy() {
+        assert!(matches!(http_error("Deepgram", 401, None, ""), SttError::Authentication(_)));
+        assert!(matches!(http_error("Deepgram", 402, None, ""), SttError::QuotaExceeded { .. }));
+        assert!(matches!(
+            http_error("Deepgram", 429, Some(3), ""),
+            SttError::RateLimited { retry_after_secs: Some(3), .. }
+        ));
+        let server = http_error("AssemblyAI", 503, None, "busy");
+        assert!(server.is_retryable());
+        assert_eq!(server.connection_details().and_then(|d| d.http_status), Some(503));
+        assert!(!http_error("AssemblyAI", 400, None, "bad audio").is_retryable());
+    }
+}
@@ -1,25 +1,28 @@
 use async_trait::async_trait;
-use futures_util::{SinkExt, StreamExt};
-use http::Request;
-use serde_json::{json, Value};
+use serde_json::json;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::{Notify, Mutex};
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
-use tokio_tungstenite::{connect_async, tungstenite::Message, WebSocketStream, MaybeTlsStream};
-use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
 
 use crate::domain::{
-    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttConnectionCategory,
-    SttConnectionDetails, SttConnectionError, SttError, SttProvider, SttResult, Transcription,
-    TranscriptionCallback,
+    AlternativeText, AudioChunk, ConnectionQualityCallback, ConnectionQualityReason, ErrorCallback,
+    DEFAULT_DEEPGRAM_MODEL, SttConfig, SttConnectionCategory, SttConnectionDetails, SttConnectionError, SttError, SttProvider,
+    SttResult, Transcription, TranscriptionCallback,
 };
 use crate::infrastructure::embedded_keys;
+use super::audio_encoder::{create_audio_encoder, deepgram_encoding_param, AudioEncoder, Linear16Encoder};
+use super::deepgram_messages::{DeepgramAlternative, DeepgramMessage, DeepgramResults};
+use super::stream_state::{StreamLifecycle, StreamState};
+use super::ws_transport::{SttMessageCodec, WsKeepAlive, WsSttTransport};
 
 /// Deepgram cloud STT provider
 ///
-/// Endpoint: wss://api.deepgram.com/v1/listen
+/// Endpoint: wss://api.deepgram.com/v1/listen (если недоступен — wss://api.eu.deepgram.com/v1/listen)
 /// Pricing: ~$0.0077/min for Nova-3
 /// Model: Nova-3 (47+ языков, включая русский, английский, и др.)
 ///
@@ -30,17 +33,17 @@ use crate::infrastructure::embedded_keys;
 /// 4. Receive JSON messages: type=Results, is_final, speech_final
 const DEEPGRAM_WS_URL: &str = "wss://api.deepgram.com/v1/listen";
 
-type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+/// Региональные endpoint'ы: пробуются по порядку, если основной недоступен (DNS, маршрут, таймаут)
+const DEEPGRAM_FALLBACK_WS_URLS: &[&str] = &["wss://api.eu.deepgram.com/v1/listen"];
+
+/// Таймаут подключения к одному endpoint'у — после него переходим к следующему
+const DEEPGRAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct DeepgramProvider {
     config: Option<SttConfig>,
-    is_streaming: bool,
-    is_paused: bool, // для keep-alive: true когда соединение живо но не обрабатываем аудио
-    is_paused_flag: Arc<Mutex<bool>>, // shared флаг для receiver_task чтобы игнорировать сообщения во время паузы
+    lifecycle: StreamLifecycle, // Paused — keep-alive: соединение живо, но аудио и ответы не обрабатываем (shared с receiver task)
     api_key: Option<String>,
-    ws_write: Option<Arc<Mutex<futures_util::stream::SplitSink<WsStream, Message>>>>,
-    receiver_task: Option<JoinHandle<()>>,
-    keepalive_task: Option<JoinHandle<()>>, // отдельная задача для отправки KeepAlive
+    transport: Option<WsSttTransport>, // WebSocket + receiver и KeepAlive задачи
     session_ready: Arc<Notify>,
     audio_buffer: Vec<i16>,
     on_partial_callback: Option<TranscriptionCallback>, // сохраняем для resume
@@ -60,19 +63,18 @@ pub struct DeepgramProvider {
     is_reconnecting: bool, // флаг что идёт процесс переподключения
     reconnect_attempts: usize, // количество попыток переподключения
     audio_buffer_during_reconnect: Arc<Mutex<Vec<AudioChunk>>>, // буфер аудио во время reconnect
+
+    endpoint: String, // WebSocket endpoint (переопределяется для mock-сервера в тестах)
+    encoder: Box<dyn AudioEncoder>, // linear16 / flac / opus (пересоздаётся на каждое соединение)
 }
 
 impl DeepgramProvider {
     pub fn new() -> Self {
         Self {
             config: None,
-            is_streaming: false,
-            is_paused: false,
-            is_paused_flag: Arc::new(Mutex::new(false)),
+            lifecycle: StreamLifecycle::new(),
             api_key: None,
-            ws_write: None,
-            receiver_task: None,
-            keepalive_task: None,
+            transport: None,
             session_ready: Arc::new(Notify::new()),
             audio_buffer: Vec::new(),
             on_partial_callback: None,
@@ -88,10 +90,49 @@ impl DeepgramProvider {
             is_reconnecting: false,
             reconnect_attempts: 0,
             audio_buffer_during_reconnect: Arc::new(Mutex::new(Vec::new())),
+            endpoint: DEEPGRAM_WS_URL.to_string(),
+            encoder: Box::new(Linear16Encoder),
+        }
+    }
+
+    /// Подменяет WebSocket endpoint (например, на локальный mock-сервер)
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Создаёт кодировщик для нового соединения и возвращает значение `encoding` для URL
+    fn reset_encoder(&mut self) -> &'static str {
+        let requested = self.config.as_ref().map(|c| c.audio_encoding).unwrap_or_default();
+        self.encoder = create_audio_encoder(requested, 16000);
+        deepgram_encoding_param(self.encoder.encoding())
+    }
+
+    /// Запросы на подключение: основной endpoint, затем региональные.
+    /// Подменённый endpoint (mock-сервер) запасных не имеет
+    fn requests(&self, url: &str, api_key: &str) -> SttResult<Vec<http::Request<()>>> {
+        let mut urls = vec![url.to_string()];
+        if self.endpoint == DEEPGRAM_WS_URL {
+            urls.extend(
+                DEEPGRAM_FALLBACK_WS_URLS
+                    .iter()
+                    .map(|fallback| url.replacen(DEEPGRAM_WS_URL, fallback, 1)),
+            );
         }
+        urls.iter()
+            .map(|url| WsSttTransport::request(url, &endpoint_host(url), &format!("Token {}", api_key)))
+            .collect()
     }
 }
 
+/// Host заголовок для endpoint
+fn endpoint_host(url: &str) -> String {
+    url.parse::<http::Uri>()
+        .ok()
+        .and_then(|uri| uri.authority().map(|a| a.to_string()))
+        .unwrap_or_else(|| "api.deepgram.com".to_string())
+}
+
 impl Default for DeepgramProvider {
     fn default() -> Self {
         Self::new()
@@ -134,308 +175,22 @@ impl SttProvider for DeepgramProvider {
     ) -> SttResult<()> {
         log::info!("DeepgramProvider: Starting stream");
 
-        if self.is_streaming {
-            return Err(SttError::Processing(
-                "Stream already active".to_string(),
-            ));
-        }
-
-        let api_key = self.api_key.as_ref()
-            .ok_or_else(|| SttError::Configuration("API key not set".to_string()))?
-            .clone();
-
-        let language = self.config.as_ref()
-            .and_then(|c| Some(c.language.clone()))
-            .unwrap_or_else(|| "en".to_string());
-
-        // Nova-3 поддерживает 47+ языков, включая русский
-        let model = self.config.as_ref()
-            .and_then(|c| c.model.clone())
-            .unwrap_or_else(|| "nova-3".to_string());
-
-        log::info!("Using Deepgram model '{}' for language '{}'", model, language);
-
-        // Собираем URL с параметрами (добавляем channels=1 для mono)
-        let mut url = format!(
-            "{}?encoding=linear16&sample_rate=16000&channels=1&model={}&language={}&punctuate=true&interim_results=true",
-            DEEPGRAM_WS_URL,
-            model,
-            language
-        );
-
-        // Добавляем keyterms если заданы
-        if let Some(ref raw) = self.config.as_ref().and_then(|c| c.deepgram_keyterms.clone()) {
-            for term in raw.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
-                url.push_str(&format!("&keyterm={}", urlencoding::encode(term)));
+        self.lifecycle.transition(StreamState::Connecting)?;
+        match self.open_stream(on_partial, on_final, on_error, on_connection_quality).await {
+            Ok(()) => {
+                self.lifecycle.transition(StreamState::Streaming)?;
+                Ok(())
             }
-        }
-
-        log::debug!("Connecting to Deepgram: {}", url);
-
-        // Формируем WebSocket запрос с заголовком авторизации
-        let request = Request::builder()
-            .method("GET")
-            .uri(&url)
-            .header("Host", "api.deepgram.com")
-            .header("Connection", "Upgrade")
-            .header("Upgrade", "websocket")
-            .header("Sec-WebSocket-Version", "13")
-            .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
-            .header("Authorization", format!("Token {}", api_key))
-            .body(())
-            .map_err(|e| {
-                SttError::Connection(SttConnectionError::simple(format!(
-                    "Failed to build WS request: {}",
-                    e
-                )))
-            })?;
-
-        let (ws_stream, _response) = connect_async(request)
-            .await
-            .map_err(|e| {
-                SttError::Connection(SttConnectionError::simple(format!(
-                    "WS connection failed: {}",
-                    e
-                )))
-            })?;
-
-        log::info!("Deepgram WebSocket connected");
-
-        let (write, mut read) = ws_stream.split();
-
-        // Оборачиваем write в Arc<Mutex<>> для совместного использования в задачах
-        let ws_write = Arc::new(Mutex::new(write));
-
-        // Пересоздаем Notify для новой сессии (фикс повторного использования)
-        self.session_ready = Arc::new(Notify::new());
-
-        // Клонируем callbacks для передачи в receiver задачу
-        let on_partial_for_receiver = on_partial.clone();
-        let on_final_for_receiver = on_final.clone();
-        let on_error_for_receiver = on_error.clone();
-        let on_connection_quality_for_receiver = on_connection_quality.clone();
-
-        // Инициализируем мониторинг качества связи
-        self.consecutive_errors = 0;
-        self.last_successful_send = Some(Instant::now());
-        *self.last_server_response.lock().await = Some(Instant::now());
-        *self.current_quality.lock().await = "Good".to_string();
-
-        // Запускаем фоновую задачу для приема сообщений
-        let session_notify = self.session_ready.clone();
-        let last_server_response_for_receiver = self.last_server_response.clone();
-        let current_quality_for_receiver = self.current_quality.clone();
-        let is_paused_flag_for_receiver = self.is_paused_flag.clone(); // клон для receiver task
-        let receiver_task = tokio::spawn(async move {
-            log::debug!("Deepgram receiver task started");
-
-            // Запускаем отдельную задачу для мониторинга качества связи
-            let last_server_response_monitor = last_server_response_for_receiver.clone();
-            let current_quality_monitor = current_quality_for_receiver.clone();
-            let on_connection_quality_monitor = on_connection_quality_for_receiver.clone();
-            let is_paused_flag_for_monitor = is_paused_flag_for_receiver.clone();
-
-            let monitor_task = tokio::spawn(async move {
-                loop {
-                    tokio::time::sleep(Duration::from_secs(1)).await;
-
-                    // В режиме паузы не мониторим качество - Deepgram не отправляет сообщения
-                    if *is_paused_flag_for_monitor.lock().await {
-                        continue;
-                    }
-
-                    let last_response = *last_server_response_monitor.lock().await;
-                    let mut current_quality = current_quality_monitor.lock().await;
-
-                    if let Some(last_time) = last_response {
-                        let elapsed = last_time.elapsed();
-
-                        // Если нет ответа от сервера больше 3 секунд - плохая связь
-                        if elapsed > Duration::from_secs(3) && *current_quality == "Good" {
-                            log::warn!("Connection quality degraded: no server response for {:.1}s", elapsed.as_secs_f64());
-                            *current_quality = "Poor".to_string();
-                            on_connection_quality_monitor("Poor".to_string(), Some("No server response for 3+ seconds".to_string()));
-                        }
-                        // Если связь восстановилась (получили ответ после плохой связи)
-                        else if elapsed <= Duration::from_secs(2) && *current_quality == "Poor" {
-                            log::info!("Connection quality recovering: server responding again");
-                            *current_quality = "Recovering".to_string();
-                            on_connection_quality_monitor("Recovering".to_string(), None);
-
-                            // Через 2 секунды стабильной работы считаем что всё хорошо
-                            let quality_for_check = current_quality_monitor.clone();
-                            let callback_for_check = on_connection_quality_monitor.clone();
-                            tokio::spawn(async move {
-                                tokio::time::sleep(Duration::from_secs(2)).await;
-                                let mut q = quality_for_check.lock().await;
-                                if *q == "Recovering" {
-                                    log::info!("Connection fully recovered");
-                                    *q = "Good".to_string();
-                                    callback_for_check("Good".to_string(), None);
-                                }
-                            });
-                        }
-                    }
-                }
-            });
-
-            while let Some(msg_result) = read.next().await {
-                // Проверяем флаг паузы и игнорируем сообщения если на паузе
-                if *is_paused_flag_for_receiver.lock().await {
-                    log::trace!("Ignoring message from Deepgram - stream is paused (keep-alive mode)");
-                    continue;
-                }
-
-                // Обновляем время последнего ответа от сервера
-                *last_server_response_for_receiver.lock().await = Some(Instant::now());
-
-                match msg_result {
-                    Ok(Message::Text(text)) => {
-                        log::debug!("Deepgram received text: {}", text);
-
-                        match serde_json::from_str::<Value>(&text) {
-                            Ok(json) => {
-                                let msg_type = json["type"].as_str();
-
-                                // Уведомляем что сессия готова при получении Metadata
-                                if msg_type == Some("Metadata") {
-                                    log::info!("Deepgram session ready, metadata received");
-                                    session_notify.notify_one();
-                                }
-
-                                Self::handle_message(json, &on_partial_for_receiver, &on_final_for_receiver);
-                            }
-                            Err(e) => {
-                                log::error!("Failed to parse Deepgram message: {}", e);
-                                log::error!("Raw message: {}", text);
-                            }
-                        }
-                    }
-                    Ok(Message::Close(frame)) => {
-                        log::info!("Deepgram WebSocket closed: {:?}", frame);
-
-                        // Проверяем тип закрытия - если это ошибка, уведомляем UI
-                        if let Some(close_frame) = &frame {
-                            let reason = close_frame.reason.to_string();
-                            let code_u16 = u16::from(close_frame.code);
-
-                            // Вызываем error callback если это не нормальное закрытие
-                            if close_frame.code
-                                != tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode::Normal
-                            {
-                                let stt_err = if reason.to_lowercase().contains("auth")
-                                    || reason.contains("401")
-                                {
-                                    SttError::Authentication(reason.clone())
-                                } else {
-                                    let category = if reason.to_lowercase().contains("timeout")
-                                        || reason.to_lowercase().contains("net0001")
-                                    {
-                                        SttConnectionCategory::Timeout
-                                    } else if matches!(code_u16, 1012 | 1013 | 1014) {
-                                        SttConnectionCategory::ServerUnavailable
-                                    } else {
-                                        SttConnectionCategory::Unknown
-                                    };
-
-                                    SttError::Connection(SttConnectionError {
-                                        message: reason.clone(),
-                                        details: SttConnectionDetails {
-                                            category: Some(category),
-                                            ws_close_code: Some(code_u16),
-                                            ..Default::default()
-                                        },
-                                    })
-                                };
-
-                                log::error!(
-                                    "Deepgram connection closed with error: {} (code: {})",
-                                    reason,
-                                    code_u16
-                                );
-                                on_error_for_receiver(stt_err);
-                            }
-                        }
-
-                        break;
-                    }
-                    Ok(Message::Binary(data)) => {
-                        log::debug!("Deepgram received binary: {} bytes", data.len());
-                    }
-                    Ok(Message::Ping(_)) => {
-                        log::trace!("Deepgram received Ping");
-                    }
-                    Ok(Message::Pong(_)) => {
-                        log::trace!("Deepgram received Pong");
-                    }
-                    Err(e) => {
-                        log::error!("Deepgram WebSocket error: {}", e);
-                        break;
-                    }
-                    Ok(msg) => {
-                        log::warn!("Deepgram unexpected message: {:?}", msg);
-                    }
-                }
-            }
-
-            // Останавливаем задачу мониторинга качества связи
-            monitor_task.abort();
-            let _ = monitor_task.await;
-
-            log::debug!("Deepgram receiver task ended");
-        });
-
-        // Запускаем отдельную задачу для отправки KeepAlive (каждые 5 секунд)
-        // Это нужно для keep-alive функционала - держать соединение живым между записями
-        let ws_write_for_keepalive = ws_write.clone();
-        let keepalive_task = tokio::spawn(async move {
-            log::debug!("Deepgram KeepAlive task started");
-
-            loop {
-                tokio::time::sleep(Duration::from_secs(5)).await;
-
-                let keepalive_msg = json!({"type": "KeepAlive"});
-                let mut write = ws_write_for_keepalive.lock().await;
-                match write.send(Message::Text(keepalive_msg.to_string())).await {
-                    Ok(_) => {
-                        log::trace!("Sent KeepAlive to Deepgram");
-                    },
-                    Err(e) => {
-                        log::debug!("KeepAlive failed, connection closed: {}", e);
-                        break;
-                    }
-                }
+            Err(e) => {
+                self.lifecycle.force_close();
+                Err(e)
             }
-
-            log::debug!("Deepgram KeepAlive task ended");
-        });
-
-        self.ws_write = Some(ws_write);
-        self.receiver_task = Some(receiver_task);
-        self.keepalive_task = Some(keepalive_task);
-        self.is_streaming = true;
-        self.is_paused = false;
-
-        // Сбрасываем счетчики при новом соединении
-        self.sent_chunks_count = 0;
-        self.sent_bytes_total = 0;
-
-        // Сохраняем callbacks для возможности resume
-        self.on_partial_callback = Some(on_partial);
-        self.on_final_callback = Some(on_final);
-        self.on_error_callback = Some(on_error);
-        self.on_connection_quality_callback = Some(on_connection_quality);
-
-        // Примечание: Deepgram отправляет Metadata только после получения аудио данных
-        // Поэтому мы не ждем Metadata здесь, а считаем что соединение установлено успешно
-        log::info!("Deepgram WebSocket stream started successfully");
-        log::info!("Note: Metadata will be received after sending first audio chunk");
-        Ok(())
+        }
     }
 
     async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
-        if !self.is_streaming {
-            return Err(SttError::Processing("Not streaming".to_string()));
+        if !self.lifecycle.is_open() {
+            return Err(SttError::Processing(format!("Not streaming ({:?})", self.lifecycle.state())));
         }
 
         // Если идёт переподключение - буферизуем аудио и не пытаемся отправлять
@@ -456,11 +211,11 @@ impl SttProvider for DeepgramProvider {
         }
 
         // Если на паузе - не обрабатываем аудио (keep-alive режим)
-        if self.is_paused {
+        if self.lifecycle.is_paused() {
             return Ok(());
         }
 
-        let write = self.ws_write.as_ref()
+        let transport = self.transport.as_ref()
             .ok_or_else(|| SttError::Processing("WebSocket write handle not available".to_string()))?;
 
         // KeepAlive теперь отправляется отдельной задачей, не нужно здесь
@@ -468,26 +223,30 @@ impl SttProvider for DeepgramProvider {
         // Добавляем в буфер
         self.audio_buffer.extend_from_slice(&chunk.data);
 
-        // Отправляем чанки по 50ms для более быстрой реакции
-        // 50ms @ 16kHz = 800 samples (накапливается за ~2-3 чанка)
-        const MIN_SAMPLES: usize = 800;
+        // Отправляем порциями stt.audio_chunking (по умолчанию 50ms @ 16kHz = 800 samples, копится за ~2 кадра VAD)
+        let min_samples = self
+            .config
+            .as_ref()
+            .map_or(800, |config| config.chunk_samples(16000));
 
-        if self.audio_buffer.len() >= MIN_SAMPLES {
-            // Конвертируем i16 семплы в байты (little-endian PCM)
-            let bytes: Vec<u8> = self.audio_buffer
-                .iter()
-                .flat_map(|&sample| sample.to_le_bytes())
-                .collect();
+        if self.audio_buffer.len() >= min_samples {
+            // Кодируем семплы (linear16 → little-endian PCM, flac/opus → сжатый поток)
+            let bytes: Vec<u8> = self.encoder.encode(&self.audio_buffer).concat();
 
             // Очищаем буфер ПЕРЕД отправкой (фикс утечки памяти)
             self.audio_buffer.clear();
 
+            // Кодировщик мог ещё не набрать полный кадр
+            if bytes.is_empty() {
+                return Ok(());
+            }
+
             // Отправляем бинарные данные (обрабатываем ошибку если соединение закрыто)
             let send_start = std::time::Instant::now();
             let bytes_len = bytes.len();
 
-            let mut write_guard = write.lock().await;
-            match write_guard.send(Message::Binary(bytes)).await {
+            let send_result = transport.send(Message::Binary(bytes)).await;
+            match send_result {
                 Ok(_) => {
                     let send_duration = send_start.elapsed();
 
@@ -527,14 +286,6 @@ impl SttProvider for DeepgramProvider {
                         }
                     }
 
-                    // Логируем каждый 10-й чанк для диагностики
-                    if self.sent_chunks_count % 10 == 0 {
-                        log::debug!("Sent chunk #{} to Deepgram: {} bytes ({:.2} KB total, took {:.1}ms)",
-                            self.sent_chunks_count, bytes_len,
-                            self.sent_bytes_total as f64 / 1024.0,
-                            send_duration.as_millis());
-                    }
-
                     // Предупреждаем если отправка медленная (>100ms может быть проблемой сети)
                     if send_duration.as_millis() > 100 {
                         log::warn!("Slow WebSocket send detected: chunk #{} took {:.1}ms (network issue?)",
@@ -551,9 +302,6 @@ impl SttProvider for DeepgramProvider {
                     if self.consecutive_errors >= 3 {
                         log::warn!("Connection lost after {} errors, attempting reconnect", self.consecutive_errors);
 
-                        // Освобождаем write_guard перед вызовом reconnect (иначе будет ошибка borrow checker)
-                        drop(write_guard);
-
                         // Буферизуем текущий чанк перед попыткой reconnect
                         self.audio_buffer_during_reconnect.lock().await.push(chunk.clone());
 
@@ -579,7 +327,7 @@ impl SttProvider for DeepgramProvider {
                                     }));
                                 }
 
-                                self.is_streaming = false;
+                                self.lifecycle.force_close();
                                 return Err(reconnect_error);
                             }
                         }
@@ -600,8 +348,8 @@ impl SttProvider for DeepgramProvider {
     async fn stop_stream(&mut self) -> SttResult<()> {
         log::info!("DeepgramProvider: Stopping stream");
 
-        if !self.is_streaming {
-            log::warn!("Stream not active");
+        if let Err(e) = self.lifecycle.transition(StreamState::Closing) {
+            log::warn!("Stream not active: {}", e);
             return Ok(());
         }
 
@@ -610,33 +358,29 @@ impl SttProvider for DeepgramProvider {
             self.sent_chunks_count,
             self.sent_bytes_total as f64 / 1024.0);
 
-        // Отправляем остатки буфера (игнорируем ошибки если соединение уже закрыто)
-        if !self.audio_buffer.is_empty() {
-            if let Some(write) = self.ws_write.as_ref() {
-                let bytes: Vec<u8> = self.audio_buffer
-                    .iter()
-                    .flat_map(|&sample| sample.to_le_bytes())
-                    .collect();
-
-                log::debug!("Flushing remaining {} samples from buffer", self.audio_buffer.len());
+        // Отправляем остатки буфера и кодировщика (игнорируем ошибки если соединение уже закрыто)
+        let remaining_samples = self.audio_buffer.len();
+        let mut bytes: Vec<u8> = self.encoder.encode(&self.audio_buffer).concat();
+        bytes.extend(self.encoder.flush().concat());
+        self.audio_buffer.clear();
+        if !bytes.is_empty() {
+            if let Some(transport) = self.transport.as_ref() {
+                log::debug!("Flushing remaining {} samples from buffer", remaining_samples);
 
                 // Игнорируем ошибку если WebSocket уже закрыт
-                let mut write_guard = write.lock().await;
-                match write_guard.send(Message::Binary(bytes)).await {
+                match transport.send(Message::Binary(bytes)).await {
                     Ok(_) => {},
                     Err(e) => log::debug!("Could not send final buffer (connection may be closed): {}", e),
                 }
-                self.audio_buffer.clear();
             }
         }
 
         // Отправляем CloseStream сообщение (graceful shutdown по документации Deepgram)
-        if let Some(write) = self.ws_write.as_ref() {
+        if let Some(transport) = self.transport.as_ref() {
             let close_msg = json!({"type": "CloseStream"});
 
             // Игнорируем ошибки отправки если соединение уже закрыто
-            let mut write_guard = write.lock().await;
-            match write_guard.send(Message::Text(close_msg.to_string())).await {
+            match transport.send(Message::Text(close_msg.to_string())).await {
                 Ok(_) => {
                     log::debug!("CloseStream sent, waiting for final results...");
                     // Даем больше времени на получение финальных результатов (1 секунда)
@@ -651,21 +395,12 @@ impl SttProvider for DeepgramProvider {
         // Даем receiver task еще немного времени на обработку последних сообщений
         tokio::time::sleep(Duration::from_millis(200)).await;
 
-        // Останавливаем keepalive задачу
-        if let Some(task) = self.keepalive_task.take() {
-            task.abort();
-            let _ = task.await;
-        }
-
-        // Останавливаем фоновую задачу receiver
-        if let Some(task) = self.receiver_task.take() {
-            task.abort();
-            let _ = task.await;
+        // Останавливаем keepalive и receiver задачи
+        if let Some(transport) = self.transport.take() {
+            transport.shutdown().await;
         }
 
-        self.ws_write = None;
-        self.is_streaming = false;
-        self.is_paused = false;
+        self.lifecycle.force_close();
         self.on_partial_callback = None;
         self.on_final_callback = None;
         self.on_error_callback = None;
@@ -693,21 +428,12 @@ impl SttProvider for DeepgramProvider {
             self.sent_chunks_count,
             self.sent_bytes_total as f64 / 1024.0);
 
-        // Останавливаем keepalive задачу
-        if let Some(task) = self.keepalive_task.take() {
-            task.abort();
-            let _ = task.await;
+        // Останавливаем keepalive и receiver задачи
+        if let Some(transport) = self.transport.take() {
+            transport.shutdown().await;
         }
 
-        // Останавливаем receiver задачу
-        if let Some(task) = self.receiver_task.take() {
-            task.abort();
-            let _ = task.await;
-        }
-
-        self.ws_write = None;
-        self.is_streaming = false;
-        self.is_paused = false;
+        self.lifecycle.force_close();
         self.audio_buffer.clear();
         self.on_partial_callback = None;
         self.on_final_callback = None;
@@ -737,20 +463,15 @@ impl SttProvider for DeepgramProvider {
     async fn pause_stream(&mut self) -> SttResult<()> {
         log::info!("DeepgramProvider: Pausing stream (keep-alive mode)");
 
-        if !self.is_streaming {
-            return Err(SttError::Processing(
-                "Cannot pause - stream not active".to_string(),
-            ));
-        }
-
-        if self.is_paused {
+        if self.lifecycle.is_paused() {
             log::debug!("Stream already paused");
             return Ok(());
         }
 
-        self.is_paused = true;
-        *self.is_paused_flag.lock().await = true; // устанавливаем флаг для receiver_task
+        // Receiver task видит Paused сразу и начинает игнорировать сообщения
+        self.lifecycle.transition(StreamState::Paused)?;
         self.audio_buffer.clear(); // Очищаем буфер при паузе
+        self.encoder.discard_pending();
 
         // Очищаем reconnect state
         self.is_reconnecting = false;
@@ -772,16 +493,11 @@ impl SttProvider for DeepgramProvider {
     ) -> SttResult<()> {
         log::info!("DeepgramProvider: Resuming stream from pause");
 
-        if !self.is_streaming {
-            return Err(SttError::Processing(
-                "Cannot resume - stream not active".to_string(),
-            ));
-        }
-
-        if !self.is_paused {
-            return Err(SttError::Processing(
-                "Cannot resume - stream not paused".to_string(),
-            ));
+        if !self.lifecycle.is_paused() {
+            return Err(SttError::Processing(format!(
+                "Cannot resume - stream not paused ({:?})",
+                self.lifecycle.state()
+            )));
         }
 
         // Проверяем реальное состояние соединения перед resume
@@ -793,26 +509,18 @@ impl SttProvider for DeepgramProvider {
             );
             log::warn!("{}", error_msg);
 
-            // Сбрасываем флаги чтобы система создала новое соединение
-            self.is_streaming = false;
-            self.is_paused = false;
+            // Закрываем стрим чтобы система создала новое соединение
+            self.lifecycle.force_close();
 
-            // Очищаем мёртвые tasks и handles
-            if let Some(task) = self.receiver_task.take() {
-                task.abort();
-                let _ = task.await;
+            // Очищаем мёртвое соединение
+            if let Some(transport) = self.transport.take() {
+                transport.shutdown().await;
             }
-            if let Some(task) = self.keepalive_task.take() {
-                task.abort();
-                let _ = task.await;
-            }
-            self.ws_write = None;
 
             return Err(SttError::Connection(SttConnectionError::simple(error_msg)));
         }
 
-        self.is_paused = false;
-        *self.is_paused_flag.lock().await = false; // снимаем флаг для receiver_task
+        self.lifecycle.transition(StreamState::Streaming)?;
         self.audio_buffer.clear();
 
         // Обновляем callbacks
@@ -843,24 +551,23 @@ impl SttProvider for DeepgramProvider {
     }
 
     fn is_connection_alive(&self) -> bool {
-        // Базовая проверка (синхронная)
-        if !(self.is_streaming && self.is_paused && self.ws_write.is_some()) {
-            return false;
-        }
+        // Соединение на паузе, и его receiver/keepalive tasks не завершились
+        self.lifecycle.is_paused() && self.transport.as_ref().is_some_and(|t| t.is_alive())
+    }
 
-        // Проверяем что tasks не завершились
-        if let Some(task) = &self.receiver_task {
-            if task.is_finished() {
-                return false;
-            }
-        }
-        if let Some(task) = &self.keepalive_task {
-            if task.is_finished() {
-                return false;
-            }
+    fn debug_state(&self) -> crate::domain::SttProviderDebugState {
+        // Буфер переподключения занят — не ждём, считаем только основной
+        let reconnect_samples = self
+            .audio_buffer_during_reconnect
+            .try_lock()
+            .map(|buffer| buffer.iter().map(|chunk| chunk.data.len()).sum::<usize>())
+            .unwrap_or(0);
+        crate::domain::SttProviderDebugState {
+            streaming: self.lifecycle.is_streaming(),
+            paused: self.lifecycle.is_paused(),
+            buffered_samples: self.audio_buffer.len() + reconnect_samples,
+            receiver_alive: self.transport.as_ref().map(|t| t.is_alive()),
         }
-
-        true
     }
 
     fn is_online(&self) -> bool {
@@ -869,32 +576,150 @@ impl SttProvider for DeepgramProvider {
 }
 
 impl DeepgramProvider {
-    /// Проверяет реальное состояние соединения
-    /// Возвращает (is_healthy, reason_if_unhealthy)
-    async fn check_connection_health(&self) -> (bool, Option<String>) {
-        // Проверка 1: WebSocket write handle должен существовать
-        if self.ws_write.is_none() {
-            return (false, Some("WebSocket write handle not available".to_string()));
+    /// Кодек receiver task — общий для старта и переподключения
+    fn codec(
+        &self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> DeepgramCodec {
+        DeepgramCodec {
+            on_partial,
+            on_final,
+            on_error,
+            session_notify: self.session_ready.clone(),
+            lifecycle: self.lifecycle.clone(),
+            last_server_response: self.last_server_response.clone(),
+            quality_monitor: spawn_quality_monitor(
+                self.lifecycle.clone(),
+                self.last_server_response.clone(),
+                self.current_quality.clone(),
+                on_connection_quality,
+            ),
         }
+    }
 
-        // Проверка 2: Receiver task должен быть живым
-        if let Some(task) = &self.receiver_task {
-            if task.is_finished() {
-                return (false, Some("Receiver task has terminated".to_string()));
-            }
+    /// KeepAlive каждые 5 секунд — держит соединение живым между записями
+    fn keepalive() -> WsKeepAlive {
+        WsKeepAlive {
+            interval: Duration::from_secs(5),
+            message: || Message::Text(json!({"type": "KeepAlive"}).to_string()),
+            send_timeout: None,
+            lifecycle: None,
         }
+    }
 
-        // Проверка 3: KeepAlive task должен быть живым
-        if let Some(task) = &self.keepalive_task {
-            if task.is_finished() {
-                return (false, Some("KeepAlive task has terminated".to_string()));
+    /// Устанавливает WebSocket соединение и запускает фоновые задачи (состояние ведёт start_stream)
+    async fn open_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        let api_key = self.api_key.as_ref()
+            .ok_or_else(|| SttError::Configuration("API key not set".to_string()))?
+            .clone();
+
+        let language = self.config.as_ref()
+            .and_then(|c| Some(c.language.clone()))
+            .unwrap_or_else(|| "en".to_string());
+
+        // Модель под язык из language_models; по умолчанию Nova-3 (47+ языков, включая русский)
+        let model = self.config.as_ref()
+            .and_then(|c| c.effective_model())
+            .unwrap_or(DEFAULT_DEEPGRAM_MODEL)
+            .to_string();
+
+        log::info!("Using Deepgram model '{}' for language '{}'", model, language);
+
+        let encoding = self.reset_encoder();
+        log::info!("Deepgram audio encoding: {}", encoding);
+
+        // Собираем URL с параметрами (добавляем channels=1 для mono)
+        let mut url = format!(
+            "{}?encoding={}&sample_rate=16000&channels=1&model={}&language={}&punctuate=true&interim_results=true",
+            self.endpoint,
+            encoding,
+            model,
+            language
+        );
+
+        // Добавляем keyterms если заданы
+        if let Some(ref raw) = self.config.as_ref().and_then(|c| c.deepgram_keyterms.clone()) {
+            for term in raw.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                url.push_str(&format!("&keyterm={}", urlencoding::encode(term)));
             }
         }
 
-        // Проверка 4: Проверяем время последнего ответа от сервера
+        // n-best гипотезы для финальных фраз
+        if let Some(count) = self.config.as_ref().and_then(|c| c.requested_alternatives()) {
+            url.push_str(&format!("&alternatives={}", count));
+        }
+
+        if self.config.as_ref().is_some_and(|c| c.diarize) {
+            url.push_str("&diarize=true");
+        }
+
+        log::debug!("Connecting to Deepgram: {}", url);
+
+        let requests = self.requests(&url, &api_key)?;
+
+        // Пересоздаем Notify для новой сессии (фикс повторного использования)
+        self.session_ready = Arc::new(Notify::new());
+
+        // Инициализируем мониторинг качества связи
+        self.consecutive_errors = 0;
+        self.last_successful_send = Some(Instant::now());
+        *self.last_server_response.lock().await = Some(Instant::now());
+        *self.current_quality.lock().await = "Good".to_string();
+
+        let codec = self.codec(
+            on_partial.clone(),
+            on_final.clone(),
+            on_error.clone(),
+            on_connection_quality.clone(),
+        );
+        let mut transport =
+            WsSttTransport::connect_with_fallback(requests, Some(DEEPGRAM_CONNECT_TIMEOUT), codec).await?;
+        transport.start_keepalive(Self::keepalive());
+        self.transport = Some(transport);
+
+        // Сбрасываем счетчики при новом соединении
+        self.sent_chunks_count = 0;
+        self.sent_bytes_total = 0;
+
+        // Сохраняем callbacks для возможности resume
+        self.on_partial_callback = Some(on_partial);
+        self.on_final_callback = Some(on_final);
+        self.on_error_callback = Some(on_error);
+        self.on_connection_quality_callback = Some(on_connection_quality);
+
+        // Примечание: Deepgram отправляет Metadata только после получения аудио данных
+        // Поэтому мы не ждем Metadata здесь, а считаем что соединение установлено успешно
+        log::info!("Deepgram WebSocket stream started successfully");
+        log::info!("Note: Metadata will be received after sending first audio chunk");
+        Ok(())
+    }
+
+    /// Проверяет реальное состояние соединения
+    /// Возвращает (is_healthy, reason_if_unhealthy)
+    async fn check_connection_health(&self) -> (bool, Option<String>) {
+        // Проверка 1: соединение должно существовать
+        let Some(transport) = self.transport.as_ref() else {
+            return (false, Some("WebSocket write handle not available".to_string()));
+        };
+
+        // Проверка 2: receiver и keepalive tasks должны быть живы
+        if !transport.is_alive() {
+            return (false, Some("Receiver or KeepAlive task has terminated".to_string()));
+        }
+
+        // Проверка 3: Проверяем время последнего ответа от сервера
         // В режиме паузы Deepgram не отправляет сообщения - это нормально.
         // Достаточно что receiver и keepalive tasks живы.
-        if !self.is_paused {
+        if !self.lifecycle.is_paused() {
             if let Some(last_response) = *self.last_server_response.lock().await {
                 let elapsed = last_response.elapsed();
 
@@ -908,8 +733,8 @@ impl DeepgramProvider {
             }
         }
 
-        // Проверка 5: Стрим должен быть активен
-        if !self.is_streaming {
+        // Проверка 4: Стрим должен быть активен
+        if !self.lifecycle.is_open() {
             return (false, Some("Stream not active".to_string()));
         }
 
@@ -927,17 +752,12 @@ impl DeepgramProvider {
 
         // Отправляем событие Poor с reason
         if let Some(callback) = &self.on_connection_quality_callback {
-            callback("Poor".to_string(), Some("Connection lost, reconnecting...".to_string()));
+            callback("Poor".to_string(), Some(ConnectionQualityReason::ProviderConnectionLost));
         }
 
-        // Останавливаем старые задачи
-        if let Some(task) = self.keepalive_task.take() {
-            task.abort();
-            let _ = task.await;
-        }
-        if let Some(task) = self.receiver_task.take() {
-            task.abort();
-            let _ = task.await;
+        // Останавливаем старое соединение
+        if let Some(transport) = self.transport.take() {
+            transport.shutdown().await;
         }
 
         // Сохраняем callbacks для восстановления
@@ -947,7 +767,7 @@ impl DeepgramProvider {
         let on_final = self.on_final_callback.clone().ok_or_else(|| {
             SttError::Internal("on_final callback not set during reconnect".to_string())
         })?;
-        let _on_error = self.on_error_callback.clone().ok_or_else(|| {
+        let on_error = self.on_error_callback.clone().ok_or_else(|| {
             SttError::Internal("on_error callback not set during reconnect".to_string())
         })?;
         let on_connection_quality = self.on_connection_quality_callback.clone().ok_or_else(|| {
@@ -974,7 +794,10 @@ impl DeepgramProvider {
             if let Some(callback) = &self.on_connection_quality_callback {
                 callback(
                     "Poor".to_string(),
-                    Some(format!("Reconnecting (attempt {}/{})...", attempt, MAX_ATTEMPTS))
+                    Some(ConnectionQualityReason::Reconnecting {
+                        attempt: attempt as u32,
+                        max_attempts: MAX_ATTEMPTS as u32,
+                    })
                 );
             }
 
@@ -983,12 +806,14 @@ impl DeepgramProvider {
                 tokio::time::sleep(Duration::from_millis(delays_ms[attempt - 2])).await;
             }
 
-            // Пытаемся создать новое WebSocket соединение
+            // Пытаемся создать новое WebSocket соединение (новый поток → новый кодировщик)
+            let encoding = self.reset_encoder();
             let mut url = format!(
-                "{}?encoding=linear16&sample_rate=16000&channels=1&language={}&model={}",
-                DEEPGRAM_WS_URL,
+                "{}?encoding={}&sample_rate=16000&channels=1&language={}&model={}",
+                self.endpoint,
+                encoding,
                 config.language,
-                config.model.as_deref().unwrap_or("nova-3")
+                config.effective_model().unwrap_or(DEFAULT_DEEPGRAM_MODEL)
             );
 
             // Добавляем keyterms если заданы
@@ -998,17 +823,15 @@ impl DeepgramProvider {
                 }
             }
 
-            let request = match Request::builder()
-                .method("GET")
-                .uri(&url)
-                .header("Host", "api.deepgram.com")
-                .header("Connection", "Upgrade")
-                .header("Upgrade", "websocket")
-                .header("Sec-WebSocket-Version", "13")
-                .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
-                .header("Authorization", format!("Token {}", api_key))
-                .body(())
-            {
+            if let Some(count) = config.requested_alternatives() {
+                url.push_str(&format!("&alternatives={}", count));
+            }
+
+            if config.diarize {
+                url.push_str("&diarize=true");
+            }
+
+            let requests = match self.requests(&url, &api_key) {
                 Ok(req) => req,
                 Err(e) => {
                     log::warn!("Failed to build request (attempt {}/{}): {}", attempt, MAX_ATTEMPTS, e);
@@ -1016,8 +839,23 @@ impl DeepgramProvider {
                 }
             };
 
-            let ws_stream = match connect_async(request).await {
-                Ok((stream, _)) => stream,
+            // Пересоздаем Notify для новой сессии
+            self.session_ready = Arc::new(Notify::new());
+
+            let codec = self.codec(
+                on_partial.clone(),
+                on_final.clone(),
+                on_error.clone(),
+                on_connection_quality.clone(),
+            );
+            let mut transport = match WsSttTransport::connect_with_fallback(
+                requests,
+                Some(DEEPGRAM_CONNECT_TIMEOUT),
+                codec,
+            )
+            .await
+            {
+                Ok(transport) => transport,
                 Err(e) => {
                     log::warn!("Failed to connect (attempt {}/{}): {}", attempt, MAX_ATTEMPTS, e);
                     continue;
@@ -1026,140 +864,9 @@ impl DeepgramProvider {
 
             log::info!("WebSocket reconnected successfully (attempt {}/{})", attempt, MAX_ATTEMPTS);
 
-            // Разделяем стрим на read/write
-            let (write, mut read) = ws_stream.split();
-            let ws_write = Arc::new(Mutex::new(write));
-
-            // Пересоздаем Notify для новой сессии
-            self.session_ready = Arc::new(Notify::new());
-
-            // Клонируем callbacks для receiver задачи
-            let on_partial_for_receiver = on_partial.clone();
-            let on_final_for_receiver = on_final.clone();
-            let on_connection_quality_for_receiver = on_connection_quality.clone();
-
-            // Запускаем фоновую задачу для приема сообщений
-            let session_notify = self.session_ready.clone();
-            let last_server_response_for_receiver = self.last_server_response.clone();
-            let current_quality_for_receiver = self.current_quality.clone();
-            let is_paused_flag_for_receiver = self.is_paused_flag.clone(); // клон для receiver task
-
-            let receiver_task = tokio::spawn(async move {
-                log::debug!("Deepgram receiver task started after reconnect");
-
-                // Мониторинг качества связи
-                let last_server_response_monitor = last_server_response_for_receiver.clone();
-                let current_quality_monitor = current_quality_for_receiver.clone();
-                let on_connection_quality_monitor = on_connection_quality_for_receiver.clone();
-                let is_paused_flag_for_monitor = is_paused_flag_for_receiver.clone();
-
-                let monitor_task = tokio::spawn(async move {
-                    loop {
-                        tokio::time::sleep(Duration::from_secs(1)).await;
-
-                        // В режиме паузы не мониторим качество - Deepgram не отправляет сообщения
-                        if *is_paused_flag_for_monitor.lock().await {
-                            continue;
-                        }
-
-                        let last_response = *last_server_response_monitor.lock().await;
-                        let mut current_quality = current_quality_monitor.lock().await;
-
-                        if let Some(last_time) = last_response {
-                            let elapsed = last_time.elapsed();
-
-                            if elapsed > Duration::from_secs(3) && *current_quality == "Good" {
-                                log::warn!("Connection quality degraded after reconnect: no server response for {:.1}s", elapsed.as_secs_f64());
-                                *current_quality = "Poor".to_string();
-                                on_connection_quality_monitor("Poor".to_string(), Some("No server response for 3+ seconds".to_string()));
-                            } else if elapsed <= Duration::from_secs(2) && *current_quality == "Poor" {
-                                log::info!("Connection quality recovering after reconnect");
-                                *current_quality = "Recovering".to_string();
-                                on_connection_quality_monitor("Recovering".to_string(), None);
-
-                                let quality_for_check = current_quality_monitor.clone();
-                                let callback_for_check = on_connection_quality_monitor.clone();
-                                tokio::spawn(async move {
-                                    tokio::time::sleep(Duration::from_secs(2)).await;
-                                    let mut q = quality_for_check.lock().await;
-                                    if *q == "Recovering" {
-                                        log::info!("Connection fully recovered");
-                                        *q = "Good".to_string();
-                                        callback_for_check("Good".to_string(), None);
-                                    }
-                                });
-                            }
-                        }
-                    }
-                });
-
-                while let Some(msg_result) = read.next().await {
-                    // Проверяем флаг паузы и игнорируем сообщения если на паузе
-                    if *is_paused_flag_for_receiver.lock().await {
-                        log::trace!("Ignoring message from Deepgram after reconnect - stream is paused");
-                        continue;
-                    }
-
-                    *last_server_response_for_receiver.lock().await = Some(Instant::now());
-
-                    match msg_result {
-                        Ok(Message::Text(text)) => {
-                            log::debug!("Deepgram received text after reconnect: {}", text);
-
-                            match serde_json::from_str::<Value>(&text) {
-                                Ok(json) => {
-                                    let msg_type = json["type"].as_str();
-
-                                    if msg_type == Some("Metadata") {
-                                        log::info!("Deepgram session ready after reconnect");
-                                        session_notify.notify_one();
-                                    }
-
-                                    Self::handle_message(json, &on_partial_for_receiver, &on_final_for_receiver);
-                                }
-                                Err(e) => {
-                                    log::error!("Failed to parse Deepgram message after reconnect: {}", e);
-                                }
-                            }
-                        }
-                        Ok(Message::Close(frame)) => {
-                            log::info!("Deepgram WebSocket closed after reconnect: {:?}", frame);
-                            break;
-                        }
-                        Err(e) => {
-                            log::warn!("WebSocket error after reconnect: {}", e);
-                            break;
-                        }
-                        _ => {}
-                    }
-                }
-
-                monitor_task.abort();
-                let _ = monitor_task.await;
-
-                log::debug!("Deepgram receiver task ended after reconnect");
-            });
-
-            // Запускаем keepalive задачу
-            let ws_write_for_keepalive = ws_write.clone();
-            let keepalive_task = tokio::spawn(async move {
-                loop {
-                    tokio::time::sleep(Duration::from_secs(5)).await;
-
-                    let keepalive_msg = json!({"type": "KeepAlive"});
-                    let mut write_guard = ws_write_for_keepalive.lock().await;
-
-                    if let Err(e) = write_guard.send(Message::Text(keepalive_msg.to_string())).await {
-                        log::debug!("Could not send KeepAlive after reconnect (connection closed): {}", e);
-                        break;
-                    }
-                }
-            });
-
             // Сохраняем новое соединение
-            self.ws_write = Some(ws_write);
-            self.receiver_task = Some(receiver_task);
-            self.keepalive_task = Some(keepalive_task);
+            transport.start_keepalive(Self::keepalive());
+            self.transport = Some(transport);
 
             // Сбрасываем счетчики ошибок
             self.consecutive_errors = 0;
@@ -1183,13 +890,13 @@ impl DeepgramProvider {
                 for chunk in buffered_chunks {
                     // Отправляем через send_audio но НЕ через рекурсию
                     // Просто отправляем напрямую через WebSocket
-                    let bytes: Vec<u8> = chunk.data.iter()
-                        .flat_map(|&sample| sample.to_le_bytes())
-                        .collect();
+                    let bytes: Vec<u8> = self.encoder.encode(&chunk.data).concat();
+                    if bytes.is_empty() {
+                        continue;
+                    }
 
-                    if let Some(write) = self.ws_write.as_ref() {
-                        let mut write_guard = write.lock().await;
-                        if let Err(e) = write_guard.send(Message::Binary(bytes)).await {
+                    if let Some(transport) = self.transport.as_ref() {
+                        if let Err(e) = transport.send(Message::Binary(bytes)).await {
                             log::warn!("Failed to send buffered chunk: {}", e);
                             // Не критично - продолжаем
                         }
@@ -1212,7 +919,7 @@ impl DeepgramProvider {
         // Все попытки провалились
         log::error!("Failed to reconnect after {} attempts", MAX_ATTEMPTS);
         self.is_reconnecting = false;
-        self.is_streaming = false;
+        self.lifecycle.force_close();
 
         Err(SttError::Connection(SttConnectionError::simple(format!(
             "Failed to reconnect after {} attempts",
@@ -1220,122 +927,285 @@ impl DeepgramProvider {
         ))))
     }
 
+    /// Остальные гипотезы (alternatives[1..]) без пустых и дублей основной
+    fn parse_alternatives(rest: &[DeepgramAlternative], primary: &str) -> Vec<AlternativeText> {
+        let mut result: Vec<AlternativeText> = Vec::new();
+        for alt in rest {
+            let text = alt.transcript.as_str();
+            if text.is_empty() || text == primary || result.iter().any(|a| a.text == text) {
+                continue;
+            }
+            result.push(AlternativeText::new(text, alt.confidence));
+        }
+        result
+    }
+
+    /// Говорящий, которому принадлежит большинство слов фразы (words[].speaker при diarize=true)
+    fn dominant_speaker(alternative: &DeepgramAlternative) -> Option<u32> {
+        let mut counts: Vec<(u32, usize)> = Vec::new();
+        for speaker in alternative.words.iter().filter_map(|w| w.speaker) {
+            match counts.iter_mut().find(|(s, _)| *s == speaker) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((speaker, 1)),
+            }
+        }
+        // При равенстве — тот, кто заговорил первым
+        counts
+            .iter()
+            .fold(None, |best: Option<(u32, usize)>, &(speaker, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((speaker, count)),
+            })
+            .map(|(speaker, _)| speaker)
+    }
+
     /// Обрабатываем входящее сообщение от Deepgram
     fn handle_message(
-        json: Value,
+        message: DeepgramMessage,
         on_partial: &TranscriptionCallback,
         on_final: &TranscriptionCallback,
     ) {
-        let msg_type = json["type"].as_str();
-
-        match msg_type {
-            Some("Results") => {
-                // Получаем флаги финальности
-                let is_final = json["is_final"].as_bool().unwrap_or(false);
-                let speech_final = json["speech_final"].as_bool().unwrap_or(false);
-
-                // Получаем временные метки сегмента
-                let start = json["start"].as_f64().unwrap_or(0.0);
-                let duration = json["duration"].as_f64().unwrap_or(0.0);
+        match message {
+            DeepgramMessage::Results(results) => {
+                let DeepgramResults { is_final, speech_final, start, duration, channel } = results;
 
                 log::debug!("Processing Results: is_final={}, speech_final={}, start={:.2}s, duration={:.2}s",
                     is_final, speech_final, start, duration);
 
-                // Извлекаем транскрипцию из первой альтернативы
-                // Структура Streaming API: channel.alternatives[0]
-                if let Some(channel) = json.get("channel") {
-                    if let Some(alternatives) = channel.get("alternatives").and_then(|a| a.as_array()) {
-                        log::trace!("Found {} alternative(s)", alternatives.len());
-                        if let Some(first_alt) = alternatives.first() {
-                            let text = first_alt["transcript"].as_str().unwrap_or("");
-                            log::debug!("Extracted transcript: '{}' (start={:.2}s)", text, start);
-
-                            if !text.is_empty() {
-                                let confidence = first_alt["confidence"].as_f64().map(|v| v as f32);
-
-                                // Извлекаем язык из alternatives[0].languages (по документации)
-                                let detected_language = first_alt.get("languages")
-                                    .and_then(|l| l.as_array())
-                                    .and_then(|arr| arr.first())
-                                    .and_then(|lang| lang.as_str())
-                                    .map(|s| s.to_string());
-
-                                // Deepgram отправляет:
-                                // - is_final=false: промежуточный результат внутри сегмента
-                                // - is_final=true, speech_final=false: сегмент завершен, но речь продолжается
-                                // - is_final=true, speech_final=true: вся речь завершена
-
-                                let transcription = Transcription {
-                                    text: text.to_string(),
-                                    confidence,
-                                    is_final, // передаем оригинальный флаг is_final из Deepgram
-                                    language: detected_language,
-                                    timestamp: std::time::SystemTime::now()
-                                        .duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap_or_else(|_| std::time::Duration::from_secs(0))
-                                        .as_secs() as i64,
-                                    start, // передаем start время из Deepgram
-                                    duration, // передаем duration из Deepgram
-                                };
-
-                                // Детальное логирование для отладки
-                                log::info!("🔍 DEEPGRAM MSG: is_final={}, speech_final={}, text='{}', confidence={:?}, start={:.2}s, duration={:.2}s",
-                                    is_final, speech_final, text, confidence, start, duration);
-
-                                // Отправляем как final только когда ВСЯ речь завершена (speech_final=true)
-                                if is_final && speech_final {
-                                    log::info!("✅ Final transcript (speech_final=true): '{}' → вызываем on_final callback", text);
-                                    on_final(transcription);
-                                } else {
-                                    // Все остальные (промежуточные и финализированные сегменты) - как partial
-                                    // UI различит по флагу is_final
-                                    if is_final {
-                                        log::info!("🔒 Segment finalized (is_final=true, speech_final=false): '{}' → вызываем on_partial callback", text);
-                                    } else {
-                                        log::info!("📝 Partial transcript (is_final=false): '{}' → вызываем on_partial callback", text);
-                                    }
-                                    on_partial(transcription);
-                                }
-                            } else {
-                                log::trace!("Skipping empty transcript");
-                            }
-                        } else {
-                            log::trace!("No alternatives found");
-                        }
+                // Транскрипция из первой альтернативы (Streaming API: channel.alternatives[0])
+                log::trace!("Found {} alternative(s)", channel.alternatives.len());
+                let Some((first_alt, other_alts)) = channel.alternatives.split_first() else {
+                    log::trace!("No alternatives found");
+                    return;
+                };
+
+                let text = first_alt.transcript.as_str();
+                log::debug!("Extracted transcript: '{}' (start={:.2}s)", text, start);
+                if text.is_empty() {
+                    log::trace!("Skipping empty transcript");
+                    return;
+                }
+
+                let confidence = first_alt.confidence;
+
+                // Deepgram отправляет:
+                // - is_final=false: промежуточный результат внутри сегмента
+                // - is_final=true, speech_final=false: сегмент завершен, но речь продолжается
+                // - is_final=true, speech_final=true: вся речь завершена
+
+                let transcription = Transcription {
+                    text: text.to_string(),
+                    confidence,
+                    is_final, // передаем оригинальный флаг is_final из Deepgram
+                    // Язык из alternatives[0].languages (по документации)
+                    language: first_alt.languages.first().cloned(),
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_else(|_| std::time::Duration::from_secs(0))
+                        .as_secs() as i64,
+                    start, // передаем start время из Deepgram
+                    duration, // передаем duration из Deepgram
+                    alternatives: Self::parse_alternatives(other_alts, text),
+                    speaker: Self::dominant_speaker(first_alt),
+                    channel: None,
+                    calendar: None,
+                    app: None,
+                };
+
+                // Детальное логирование для отладки
+                log::info!("🔍 DEEPGRAM MSG: is_final={}, speech_final={}, text='{}', confidence={:?}, start={:.2}s, duration={:.2}s",
+                    is_final, speech_final, text, confidence, start, duration);
+
+                // Отправляем как final только когда ВСЯ речь завершена (speech_final=true)
+                if is_final && speech_final {
+                    log::info!("✅ Final transcript (speech_final=true): '{}' → вызываем on_final callback", text);
+                    on_final(transcription);
+                } else {
+                    // Все остальные (промежуточные и финализированные сегменты) - как partial
+                    // UI различит по флагу is_final
+                    if is_final {
+                        log::info!("🔒 Segment finalized (is_final=true, speech_final=false): '{}' → вызываем on_partial callback", text);
                     } else {
-                        log::trace!("No alternatives array");
+                        log::info!("📝 Partial transcript (is_final=false): '{}' → вызываем on_partial callback", text);
                     }
-                } else {
-                    log::trace!("No channel field in message");
+                    on_partial(transcription);
                 }
             }
 
-            Some("Metadata") => {
+            DeepgramMessage::Metadata(metadata) => {
                 log::debug!("Deepgram metadata received");
-                if let Some(request_id) = json["request_id"].as_str() {
-                    log::debug!("Request ID: {}", request_id);
-                }
+                log::debug!("Request ID: {}", metadata.request_id);
+            }
+
+            DeepgramMessage::UtteranceEnd(end) => {
+                log::debug!("Deepgram utterance end (last_word_end={:.2}s)", end.last_word_end);
             }
 
-            Some("Error") => {
-                log::error!("Deepgram error received: {:?}", json);
-                if let Some(err_msg) = json.get("err_msg").and_then(|e| e.as_str()) {
+            DeepgramMessage::Error(err) => {
+                log::error!("Deepgram error received: {:?}", err);
+                if let Some(err_msg) = err.err_msg {
                     log::error!("Error message: {}", err_msg);
                 }
-                if let Some(err_code) = json.get("err_code").and_then(|c| c.as_str()) {
+                if let Some(err_code) = err.err_code {
                     log::error!("Error code: {}", err_code);
                 }
             }
 
-            Some(other) => {
-                log::debug!("Deepgram message type: {}", other);
+            DeepgramMessage::Unknown => {
+                log::debug!("Deepgram message of unhandled type");
             }
+        }
+    }
+}
+
+/// Разбор сообщений Deepgram в receiver task
+struct DeepgramCodec {
+    on_partial: TranscriptionCallback,
+    on_final: TranscriptionCallback,
+    on_error: ErrorCallback,
+    session_notify: Arc<Notify>,
+    lifecycle: StreamLifecycle,
+    last_server_response: Arc<Mutex<Option<Instant>>>,
+    quality_monitor: JoinHandle<()>,
+}
 
-            None => {
-                log::warn!("Deepgram message without type field");
+impl Drop for DeepgramCodec {
+    fn drop(&mut self) {
+        // Мониторинг качества живёт ровно столько, сколько receiver task
+        self.quality_monitor.abort();
+    }
+}
+
+#[async_trait]
+impl SttMessageCodec for DeepgramCodec {
+    fn provider_name(&self) -> &'static str {
+        "Deepgram"
+    }
+
+    async fn accept_frame(&mut self) -> bool {
+        // На паузе игнорируем сообщения (keep-alive режим)
+        if self.lifecycle.is_paused() {
+            return false;
+        }
+
+        // Обновляем время последнего ответа от сервера
+        *self.last_server_response.lock().await = Some(Instant::now());
+        true
+    }
+
+    async fn on_text(&mut self, text: String) {
+        match serde_json::from_str::<DeepgramMessage>(&text) {
+            Ok(message) => {
+                // Уведомляем что сессия готова при получении Metadata
+                if matches!(message, DeepgramMessage::Metadata(_)) {
+                    log::info!("Deepgram session ready, metadata received");
+                    self.session_notify.notify_one();
+                }
+                if let DeepgramMessage::Error(err) = &message {
+                    (self.on_error)(SttError::ProviderBug(format!(
+                        "Deepgram error: {}",
+                        err.err_msg.as_deref().unwrap_or("unknown")
+                    )));
+                }
+
+                DeepgramProvider::handle_message(message, &self.on_partial, &self.on_final);
+            }
+            Err(e) => {
+                log::error!("Failed to parse Deepgram message: {}", e);
+                log::error!("Raw message: {}", text);
+                // Схема ответа разошлась с нашей — без этого транскрипция молча пропадает
+                (self.on_error)(SttError::ProviderBug(format!("Unexpected Deepgram message: {}", e)));
             }
         }
     }
+
+    async fn on_close(&mut self, frame: Option<CloseFrame<'static>>) {
+        // Проверяем тип закрытия - если это ошибка, уведомляем UI
+        let Some(close_frame) = frame else {
+            return;
+        };
+        if close_frame.code == CloseCode::Normal {
+            return;
+        }
+
+        let reason = close_frame.reason.to_string();
+        let code_u16 = u16::from(close_frame.code);
+        let stt_err = if reason.to_lowercase().contains("auth") || reason.contains("401") {
+            SttError::Authentication(reason.clone())
+        } else {
+            let category = if reason.to_lowercase().contains("timeout") || reason.to_lowercase().contains("net0001") {
+                SttConnectionCategory::Timeout
+            } else if matches!(code_u16, 1012 | 1013 | 1014) {
+                SttConnectionCategory::ServerUnavailable
+            } else {
+                SttConnectionCategory::Unknown
+            };
+
+            SttError::Connection(SttConnectionError {
+                message: reason.clone(),
+                details: SttConnectionDetails {
+                    category: Some(category),
+                    ws_close_code: Some(code_u16),
+                    ..Default::default()
+                },
+            })
+        };
+
+        log::error!("Deepgram connection closed with error: {} (code: {})", reason, code_u16);
+        (self.on_error)(stt_err);
+    }
+}
+
+/// Мониторинг качества связи по времени последнего ответа сервера (Good → Poor → Recovering → Good)
+fn spawn_quality_monitor(
+    lifecycle: StreamLifecycle,
+    last_server_response: Arc<Mutex<Option<Instant>>>,
+    current_quality: Arc<Mutex<String>>,
+    on_connection_quality: ConnectionQualityCallback,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            // В режиме паузы не мониторим качество - Deepgram не отправляет сообщения
+            if lifecycle.is_paused() {
+                continue;
+            }
+
+            let last_response = *last_server_response.lock().await;
+            let mut quality = current_quality.lock().await;
+
+            if let Some(last_time) = last_response {
+                let elapsed = last_time.elapsed();
+
+                // Если нет ответа от сервера больше 3 секунд - плохая связь
+                if elapsed > Duration::from_secs(3) && *quality == "Good" {
+                    log::warn!("Connection quality degraded: no server response for {:.1}s", elapsed.as_secs_f64());
+                    *quality = "Poor".to_string();
+                    on_connection_quality("Poor".to_string(), Some(ConnectionQualityReason::NoServerResponse { secs: 3 }));
+                }
+                // Если связь восстановилась (получили ответ после плохой связи)
+                else if elapsed <= Duration::from_secs(2) && *quality == "Poor" {
+                    log::info!("Connection quality recovering: server responding again");
+                    *quality = "Recovering".to_string();
+                    on_connection_quality("Recovering".to_string(), None);
+
+                    // Через 2 секунды стабильной работы считаем что всё хорошо
+                    let quality_for_check = current_quality.clone();
+                    let callback_for_check = on_connection_quality.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        let mut q = quality_for_check.lock().await;
+                        if *q == "Recovering" {
+                            log::info!("Connection fully recovered");
+                            *q = "Good".to_string();
+                            callback_for_check("Good".to_string(), None);
+                        }
+                    });
+                }
+            }
+        }
+    })
 }
 
 #[cfg(test)]
@@ -1343,11 +1213,16 @@ mod tests {
     use super::*;
     use crate::domain::SttProviderType;
 
+    /// Переводит стрим в Streaming, как после успешного start_stream
+    fn mark_streaming(provider: &DeepgramProvider) {
+        provider.lifecycle.transition(StreamState::Connecting).unwrap();
+        provider.lifecycle.transition(StreamState::Streaming).unwrap();
+    }
+
     #[test]
     fn test_provider_creation() {
         let provider = DeepgramProvider::new();
-        assert!(!provider.is_streaming);
-        assert!(!provider.is_paused);
+        assert_eq!(provider.lifecycle.state(), StreamState::Idle);
         assert_eq!(provider.audio_buffer.len(), 0);
         assert_eq!(provider.sent_chunks_count, 0);
     }
@@ -1355,7 +1230,7 @@ mod tests {
     #[test]
     fn test_provider_default() {
         let provider = DeepgramProvider::default();
-        assert!(!provider.is_streaming);
+        assert!(!provider.lifecycle.is_open());
     }
 
     #[test]
@@ -1370,6 +1245,22 @@ mod tests {
         assert!(provider.is_online());
     }
 
+    #[test]
+    fn test_regional_endpoints_follow_the_default_one() {
+        let url = format!("{}?model=nova-3&language=en", DEEPGRAM_WS_URL);
+        let requests = DeepgramProvider::new().requests(&url, "key").unwrap();
+        assert_eq!(requests.len(), 1 + DEEPGRAM_FALLBACK_WS_URLS.len());
+        assert_eq!(requests[1].uri().host(), Some("api.eu.deepgram.com"));
+        assert_eq!(requests[1].headers()["Host"], "api.eu.deepgram.com");
+        assert_eq!(requests[1].uri().query(), Some("model=nova-3&language=en"));
+
+        // У mock-сервера запасных endpoint'ов нет
+        let provider = DeepgramProvider::new().with_endpoint("ws://127.0.0.1:9000/v1/listen");
+        let requests = provider.requests("ws://127.0.0.1:9000/v1/listen?model=nova-3", "key").unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].headers()["Host"], "127.0.0.1:9000");
+    }
+
     #[test]
     fn test_provider_supports_keep_alive() {
         let provider = DeepgramProvider::new();
@@ -1378,21 +1269,21 @@ mod tests {
 
     #[test]
     fn test_connection_alive_requires_streaming_and_paused() {
-        let mut provider = DeepgramProvider::new();
+        let provider = DeepgramProvider::new();
 
         // Изначально не живо
         assert!(!provider.is_connection_alive());
 
         // Только streaming - не живо
-        provider.is_streaming = true;
+        mark_streaming(&provider);
         assert!(!provider.is_connection_alive());
 
-        // Streaming + paused без реального соединения - всё ещё не живо
-        provider.is_paused = true;
+        // Paused без реального соединения - всё ещё не живо
+        provider.lifecycle.transition(StreamState::Paused).unwrap();
         assert!(!provider.is_connection_alive());
 
-        // Только paused - не живо
-        provider.is_streaming = false;
+        // Закрыт - не живо
+        provider.lifecycle.force_close();
         assert!(!provider.is_connection_alive());
     }
 
@@ -1426,8 +1317,8 @@ mod tests {
         let mut provider = DeepgramProvider::new();
 
         // Устанавливаем состояние
-        provider.is_streaming = true;
-        provider.is_paused = true;
+        mark_streaming(&provider);
+        provider.lifecycle.transition(StreamState::Paused).unwrap();
         provider.audio_buffer = vec![1, 2, 3];
         provider.sent_chunks_count = 10;
         provider.sent_bytes_total = 1000;
@@ -1435,8 +1326,7 @@ mod tests {
         provider.abort().await.unwrap();
 
         // Проверяем что всё очистилось
-        assert!(!provider.is_streaming);
-        assert!(!provider.is_paused);
+        assert_eq!(provider.lifecycle.state(), StreamState::Closed);
         assert_eq!(provider.audio_buffer.len(), 0);
         assert_eq!(provider.sent_chunks_count, 0);
         assert_eq!(provider.sent_bytes_total, 0);
@@ -1455,12 +1345,12 @@ mod tests {
     #[tokio::test]
     async fn test_pause_stream_when_streaming() {
         let mut provider = DeepgramProvider::new();
-        provider.is_streaming = true;
+        mark_streaming(&provider);
         provider.audio_buffer = vec![1, 2, 3];
 
         let result = provider.pause_stream().await;
         assert!(result.is_ok());
-        assert!(provider.is_paused);
+        assert!(provider.lifecycle.is_paused());
         assert_eq!(provider.audio_buffer.len(), 0); // Буфер очищен
     }
 
@@ -1471,24 +1361,27 @@ mod tests {
         let on_partial = Arc::new(|_: Transcription| {});
         let on_final = Arc::new(|_: Transcription| {});
         let on_error: ErrorCallback = Arc::new(|_err: SttError| {});
-        let on_connection_quality = Arc::new(|_: String, _: Option<String>| {});
+        let on_connection_quality = Arc::new(|_: String, _: Option<ConnectionQualityReason>| {});
 
         // Не streaming - ошибка
         let result = provider.resume_stream(on_partial.clone(), on_final.clone(), on_error.clone(), on_connection_quality.clone()).await;
         assert!(result.is_err());
 
         // Streaming но не paused - ошибка
-        provider.is_streaming = true;
+        mark_streaming(&provider);
         let result = provider.resume_stream(on_partial.clone(), on_final.clone(), on_error.clone(), on_connection_quality.clone()).await;
         assert!(result.is_err());
 
-        // Streaming + paused без реального соединения - ошибка (health check)
-        provider.is_paused = true;
+        // Paused без реального соединения - ошибка (health check)
+        provider.lifecycle.transition(StreamState::Paused).unwrap();
         provider.audio_buffer = vec![1, 2, 3];
         let result = provider.resume_stream(on_partial, on_final, on_error, on_connection_quality).await;
         assert!(result.is_err());
-        assert!(!provider.is_streaming);
-        assert!(!provider.is_paused);
+        assert_eq!(provider.lifecycle.state(), StreamState::Closed);
+    }
+
+    fn parse<T: serde::de::DeserializeOwned>(json: serde_json::Value) -> T {
+        serde_json::from_value(json).expect("fixture must match Deepgram schema")
     }
 
     #[test]
@@ -1521,7 +1414,7 @@ mod tests {
             }
         });
 
-        DeepgramProvider::handle_message(json, &on_partial, &on_final);
+        DeepgramProvider::handle_message(parse(json), &on_partial, &on_final);
         assert!(*partial_called.lock().unwrap());
         assert!(!*final_called.lock().unwrap());
     }
@@ -1552,10 +1445,63 @@ mod tests {
             }
         });
 
-        DeepgramProvider::handle_message(json, &on_partial, &on_final);
+        DeepgramProvider::handle_message(parse(json), &on_partial, &on_final);
         assert!(*final_called.lock().unwrap());
     }
 
+    #[test]
+    fn test_handle_message_final_with_alternatives() {
+        let received = Arc::new(std::sync::Mutex::new(None));
+
+        let on_partial: TranscriptionCallback = Arc::new(|_: Transcription| {});
+        let r = received.clone();
+        let on_final: TranscriptionCallback = Arc::new(move |t: Transcription| {
+            *r.lock().unwrap() = Some(t);
+        });
+
+        let json = json!({
+            "type": "Results",
+            "is_final": true,
+            "speech_final": true,
+            "channel": {
+                "alternatives": [
+                    { "transcript": "ice cream", "confidence": 0.7 },
+                    { "transcript": "I scream", "confidence": 0.6 },
+                    { "transcript": "ice cream", "confidence": 0.5 },
+                    { "transcript": "", "confidence": 0.1 }
+                ]
+            }
+        });
+
+        DeepgramProvider::handle_message(parse(json), &on_partial, &on_final);
+        let t = received.lock().unwrap().take().expect("final must be delivered");
+        assert_eq!(t.text, "ice cream");
+        assert_eq!(t.alternatives, vec![AlternativeText::new("I scream", Some(0.6))]);
+    }
+
+    #[test]
+    fn test_dominant_speaker_from_words() {
+        let alt = json!({
+            "transcript": "да нет конечно",
+            "words": [
+                { "word": "да", "speaker": 1 },
+                { "word": "нет", "speaker": 0 },
+                { "word": "конечно", "speaker": 0 }
+            ]
+        });
+        assert_eq!(DeepgramProvider::dominant_speaker(&parse(alt)), Some(0));
+
+        // Ничья — первый заговоривший; без diarize слов со speaker нет
+        let tie = json!({
+            "transcript": "да нет",
+            "words": [{ "word": "да", "speaker": 2 }, { "word": "нет", "speaker": 1 }]
+        });
+        assert_eq!(DeepgramProvider::dominant_speaker(&parse(tie)), Some(2));
+        let no_speakers = json!({ "transcript": "да", "words": [{ "word": "да" }] });
+        assert_eq!(DeepgramProvider::dominant_speaker(&parse(no_speakers)), None);
+        assert_eq!(DeepgramProvider::dominant_speaker(&parse(json!({ "transcript": "да" }))), None);
+    }
+
     #[test]
     fn test_handle_message_empty_text() {
         let called = Arc::new(std::sync::Mutex::new(false));
@@ -1577,7 +1523,7 @@ mod tests {
             }
         });
 
-        DeepgramProvider::handle_message(json, &on_partial, &on_final);
+        DeepgramProvider::handle_message(parse(json), &on_partial, &on_final);
         assert!(!*called.lock().unwrap());
     }
 
@@ -1592,7 +1538,7 @@ mod tests {
             "request_id": "test-123"
         });
 
-        DeepgramProvider::handle_message(json, &on_partial, &on_final);
+        DeepgramProvider::handle_message(parse(json), &on_partial, &on_final);
         // Просто проверяем что не упали
     }
 }
@@ -0,0 +1,158 @@
+//! Сообщения Deepgram Streaming API (wss://api.deepgram.com/v1/listen)
+//!
+//! Обязательные поля без `#[serde(default)]`: если Deepgram поменяет схему,
+//! разбор упадёт с понятной ошибкой, а не тихо потеряет текст.
+
+use serde::Deserialize;
+
+/// Сообщения от Deepgram к клиенту
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum DeepgramMessage {
+    /// Результат распознавания (промежуточный или финальный)
+    Results(DeepgramResults),
+
+    /// Метаданные сессии — приходят первыми, значит сессия готова
+    Metadata(DeepgramMetadata),
+
+    /// Конец фразы по паузе (utterance_end_ms)
+    UtteranceEnd(DeepgramUtteranceEnd),
+
+    /// Ошибка на стороне Deepgram
+    Error(DeepgramError),
+
+    /// Остальные типы (SpeechStarted и т.п.) нам не нужны
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeepgramResults {
+    /// false — промежуточный результат внутри сегмента
+    #[serde(default)]
+    pub is_final: bool,
+    /// true — вся речь завершена (пауза после фразы)
+    #[serde(default)]
+    pub speech_final: bool,
+    /// Начало сегмента в секундах от начала стрима
+    #[serde(default)]
+    pub start: f64,
+    #[serde(default)]
+    pub duration: f64,
+    pub channel: DeepgramChannel,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeepgramChannel {
+    /// Гипотезы, лучшая первой
+    pub alternatives: Vec<DeepgramAlternative>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeepgramAlternative {
+    pub transcript: String,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    /// Определённые языки (при language=multi)
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub words: Vec<DeepgramWord>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[allow(dead_code)]
+pub struct DeepgramWord {
+    pub word: String,
+    /// Номер говорящего (только при diarize=true)
+    #[serde(default)]
+    pub speaker: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeepgramMetadata {
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeepgramUtteranceEnd {
+    pub last_word_end: f64,
+}
+
+/// Формат ошибок у Deepgram менялся: старый err_code/err_msg, новый description/variant
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeepgramError {
+    #[serde(default, alias = "variant")]
+    pub err_code: Option<String>,
+    #[serde(default, alias = "description")]
+    pub err_msg: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Сообщения, записанные с реального стрима nova-3 (language=ru, diarize=true)
+    const RESULTS_FIXTURE: &str = r#"{"type":"Results","channel_index":[0,1],"duration":1.98,"start":3.1,"is_final":true,"speech_final":true,"channel":{"alternatives":[{"transcript":"привет как дела","confidence":0.97,"words":[{"word":"привет","start":3.2,"end":3.6,"confidence":0.99,"speaker":0,"speaker_confidence":0.8},{"word":"как","start":3.7,"end":3.9,"confidence":0.96,"speaker":0,"speaker_confidence":0.8},{"word":"дела","start":3.9,"end":4.3,"confidence":0.95,"speaker":0,"speaker_confidence":0.8}]}]},"metadata":{"request_id":"5f0c2d8e-2b1a-4c55-9a1e-2f3c8d9b7a10","model_info":{"name":"general-nova-3","version":"2025-01-01.0","arch":"nova-3"},"model_uuid":"1e2f3a4b-0000-0000-0000-000000000000"},"from_finalize":false}"#;
+    const METADATA_FIXTURE: &str = r#"{"type":"Metadata","transaction_key":"deprecated","request_id":"5f0c2d8e-2b1a-4c55-9a1e-2f3c8d9b7a10","sha256":"9a1f","created":"2025-03-10T12:00:00.000Z","duration":0.0,"channels":1,"models":["1e2f3a4b-0000-0000-0000-000000000000"]}"#;
+    const UTTERANCE_END_FIXTURE: &str = r#"{"type":"UtteranceEnd","channel":[0,1],"last_word_end":4.3}"#;
+    const ERROR_FIXTURE: &str = r#"{"type":"Error","description":"Failed to decode audio","message":"","variant":"DATA-0000"}"#;
+
+    #[test]
+    fn test_deserialize_results_fixture() {
+        match serde_json::from_str::<DeepgramMessage>(RESULTS_FIXTURE).unwrap() {
+            DeepgramMessage::Results(results) => {
+                assert!(results.is_final);
+                assert!(results.speech_final);
+                assert!((results.start - 3.1).abs() < 1e-9);
+                let alt = &results.channel.alternatives[0];
+                assert_eq!(alt.transcript, "привет как дела");
+                assert_eq!(alt.confidence, Some(0.97));
+                assert_eq!(alt.words.len(), 3);
+                assert_eq!(alt.words[0].speaker, Some(0));
+            }
+            other => panic!("Expected Results message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_service_messages() {
+        match serde_json::from_str::<DeepgramMessage>(METADATA_FIXTURE).unwrap() {
+            DeepgramMessage::Metadata(meta) => {
+                assert_eq!(meta.request_id, "5f0c2d8e-2b1a-4c55-9a1e-2f3c8d9b7a10")
+            }
+            other => panic!("Expected Metadata message, got {:?}", other),
+        }
+
+        match serde_json::from_str::<DeepgramMessage>(UTTERANCE_END_FIXTURE).unwrap() {
+            DeepgramMessage::UtteranceEnd(end) => assert!((end.last_word_end - 4.3).abs() < 1e-9),
+            other => panic!("Expected UtteranceEnd message, got {:?}", other),
+        }
+
+        match serde_json::from_str::<DeepgramMessage>(ERROR_FIXTURE).unwrap() {
+            DeepgramMessage::Error(err) => {
+                assert_eq!(err.err_code.as_deref(), Some("DATA-0000"));
+                assert_eq!(err.err_msg.as_deref(), Some("Failed to decode audio"));
+            }
+            other => panic!("Expected Error message, got {:?}", other),
+        }
+
+        let json = r#"{"type":"SpeechStarted","channel":[0,1],"timestamp":2.9}"#;
+        assert!(matches!(
+            serde_json::from_str::<DeepgramMessage>(json).unwrap(),
+            DeepgramMessage::Unknown
+        ));
+    }
+
+    #[test]
+    fn test_schema_drift_is_an_error() {
+        // transcript переименовали — ошибка с именем поля, а не пустой текст
+        let json = r#"{"type":"Results","is_final":true,"channel":{"alternatives":[{"text":"привет"}]}}"#;
+        let err = serde_json::from_str::<DeepgramMessage>(json).unwrap_err();
+        assert!(err.to_string().contains("transcript"), "{}", err);
+
+        let json = r#"{"type":"Results","is_final":true}"#;
+        let err = serde_json::from_str::<DeepgramMessage>(json).unwrap_err();
+        assert!(err.to_string().contains("channel"), "{}", err);
+    }
+}
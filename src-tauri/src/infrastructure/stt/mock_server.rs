@@ -0,0 +1,474 @@
+//! Встроенный mock STT сервер (feature `mock-stt-server`)
+//!
+//! Локальный WebSocket сервер, который имитирует протоколы Deepgram, AssemblyAI (v3)
+//! и нашего Backend API. Нужен, чтобы интеграционные тесты провайдеров шли детерминированно
+//! без живых API ключей, и чтобы можно было диагностировать протокольные проблемы офлайн.
+//!
+//! Поведение по умолчанию: на каждые `partial_every_bytes` байт аудио сервер отдаёт partial
+//! с очередным префиксом скрипта, после последнего слова — final с полным текстом.
+//! Если клиент закрывает сессию раньше (CloseStream / terminate_session / close) —
+//! final отправляется сразу.
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Какой протокол имитирует сервер
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockSttProtocol {
+    Deepgram,
+    AssemblyAI,
+    Backend,
+}
+
+/// Сценарий ответа mock сервера
+#[derive(Debug, Clone)]
+pub struct MockSttScript {
+    words: Vec<String>,
+    partial_every_bytes: usize,
+    reject_handshake: Option<(u16, Option<String>)>,
+    close_after_bytes: Option<(usize, u16, String)>,
+}
+
+impl MockSttScript {
+    /// Сценарий, который "распознаёт" переданный текст
+    pub fn new(text: &str) -> Self {
+        Self {
+            words: text.split_whitespace().map(|w| w.to_string()).collect(),
+            // 100ms аудио @ 16kHz mono s16le
+            partial_every_bytes: 3200,
+            reject_handshake: None,
+            close_after_bytes: None,
+        }
+    }
+
+    /// Как часто (в байтах аудио) отдавать следующий partial
+    pub fn with_partial_every_bytes(mut self, bytes: usize) -> Self {
+        self.partial_every_bytes = bytes.max(1);
+        self
+    }
+
+    /// Отклонять WebSocket handshake с HTTP статусом (и опциональным кодом ошибки бэкенда)
+    pub fn reject_handshake(mut self, status: u16, server_code: Option<&str>) -> Self {
+        self.reject_handshake = Some((status, server_code.map(|c| c.to_string())));
+        self
+    }
+
+    /// Закрыть соединение с указанным close code после получения `bytes` байт аудио
+    pub fn close_after_bytes(mut self, bytes: usize, code: u16, reason: &str) -> Self {
+        self.close_after_bytes = Some((bytes, code, reason.to_string()));
+        self
+    }
+
+    fn full_text(&self) -> String {
+        self.words.join(" ")
+    }
+}
+
+/// Что сервер успел увидеть от клиента (для assert'ов в тестах)
+#[derive(Debug, Clone, Default)]
+pub struct MockSttStats {
+    pub connections: usize,
+    pub audio_bytes: usize,
+    pub audio_messages: usize,
+    /// Все текстовые (JSON) сообщения от клиента в порядке получения
+    pub text_messages: Vec<String>,
+    pub last_request_uri: Option<String>,
+    pub last_authorization: Option<String>,
+}
+
+/// Локальный mock STT сервер. Останавливается при drop.
+pub struct MockSttServer {
+    addr: SocketAddr,
+    protocol: MockSttProtocol,
+    stats: Arc<Mutex<MockSttStats>>,
+    accept_task: JoinHandle<()>,
+}
+
+impl MockSttServer {
+    /// Запускает сервер на случайном свободном порту 127.0.0.1
+    pub async fn start(protocol: MockSttProtocol, script: MockSttScript) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let stats = Arc::new(Mutex::new(MockSttStats::default()));
+
+        log::info!("MockSttServer ({:?}) listening on {}", protocol, addr);
+
+        let stats_for_task = stats.clone();
+        let accept_task = tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        log::warn!("MockSttServer accept error: {}", e);
+                        continue;
+                    }
+                };
+                log::debug!("MockSttServer: connection from {}", peer);
+
+                let script = script.clone();
+                let stats = stats_for_task.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, protocol, script, stats).await {
+                        log::debug!("MockSttServer connection ended with error: {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            protocol,
+            stats,
+            accept_task,
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// URL, который нужно передать провайдеру:
+    /// - Deepgram / AssemblyAI: полный endpoint для `with_endpoint`
+    /// - Backend: базовый URL для `SttConfig::backend_url` (путь провайдер добавляет сам)
+    pub fn url(&self) -> String {
+        match self.protocol {
+            MockSttProtocol::Deepgram => format!("ws://{}/v1/listen", self.addr),
+            MockSttProtocol::AssemblyAI => format!("ws://{}/v3/ws", self.addr),
+            MockSttProtocol::Backend => format!("ws://{}", self.addr),
+        }
+    }
+
+    /// Снимок статистики
+    pub async fn stats(&self) -> MockSttStats {
+        self.stats.lock().await.clone()
+    }
+}
+
+impl Drop for MockSttServer {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Состояние одной сессии
+struct SessionState {
+    audio_bytes: usize,
+    next_partial_at: usize,
+    words_sent: usize,
+    final_sent: bool,
+    ack_seq: u64,
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    protocol: MockSttProtocol,
+    script: MockSttScript,
+    stats: Arc<Mutex<MockSttStats>>,
+) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+    let handshake_info: Arc<std::sync::Mutex<(Option<String>, Option<String>)>> =
+        Arc::new(std::sync::Mutex::new((None, None)));
+    let info_for_cb = handshake_info.clone();
+    let reject = script.reject_handshake.clone();
+
+    let callback = move |req: &Request, resp: Response| -> Result<Response, ErrorResponse> {
+        let auth = req
+            .headers()
+            .get("Authorization")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        if let Ok(mut info) = info_for_cb.lock() {
+            *info = (Some(req.uri().to_string()), auth);
+        }
+
+        if let Some((status, code)) = reject {
+            let code_str = code.clone().unwrap_or_else(|| "MOCK_REJECTED".to_string());
+            let body = json!({
+                "error": { "code": code_str, "message": "Rejected by mock server" }
+            })
+            .to_string();
+            let mut builder = http::Response::builder().status(status);
+            if let Some(code) = code {
+                builder = builder.header("x-voicetext-error-code", code);
+            }
+            return Err(builder
+                .body(Some(body))
+                .unwrap_or_else(|_| http::Response::new(None)));
+        }
+
+        Ok(resp)
+    };
+
+    let accepted = tokio_tungstenite::accept_hdr_async(stream, callback).await;
+
+    {
+        let (uri, auth) = handshake_info
+            .lock()
+            .map(|info| info.clone())
+            .unwrap_or((None, None));
+        let mut s = stats.lock().await;
+        s.connections += 1;
+        s.last_request_uri = uri;
+        s.last_authorization = auth;
+    }
+
+    let ws = accepted?;
+    let (mut write, mut read) = ws.split();
+
+    let mut session = SessionState {
+        audio_bytes: 0,
+        next_partial_at: script.partial_every_bytes,
+        words_sent: 0,
+        final_sent: false,
+        ack_seq: 0,
+    };
+
+    // Приветствие (Backend отвечает Ready только после Config)
+    match protocol {
+        MockSttProtocol::Deepgram => {
+            write
+                .send(Message::Text(
+                    json!({"type": "Metadata", "request_id": "mock-request"}).to_string(),
+                ))
+                .await?;
+        }
+        MockSttProtocol::AssemblyAI => {
+            write
+                .send(Message::Text(json!({"type": "Begin", "id": "mock-session"}).to_string()))
+                .await?;
+        }
+        MockSttProtocol::Backend => {}
+    }
+
+    while let Some(msg) = read.next().await {
+        match msg? {
+            Message::Binary(data) => {
+                session.audio_bytes += data.len();
+                {
+                    let mut s = stats.lock().await;
+                    s.audio_bytes += data.len();
+                    s.audio_messages += 1;
+                }
+
+                if protocol == MockSttProtocol::Backend {
+                    session.ack_seq += 1;
+                    write
+                        .send(Message::Text(json!({"type": "ack", "seq": session.ack_seq}).to_string()))
+                        .await?;
+                }
+
+                if let Some((limit, code, reason)) = &script.close_after_bytes {
+                    if session.audio_bytes >= *limit {
+                        write
+                            .send(Message::Close(Some(CloseFrame {
+                                code: CloseCode::from(*code),
+                                reason: reason.clone().into(),
+                            })))
+                            .await?;
+                        break;
+                    }
+                }
+
+                while !session.final_sent && session.audio_bytes >= session.next_partial_at {
+                    session.next_partial_at += script.partial_every_bytes;
+                    session.words_sent += 1;
+
+                    if session.words_sent >= script.words.len() {
+                        send_final(&mut write, protocol, &script).await?;
+                        session.final_sent = true;
+                    } else {
+                        let text = script.words[..session.words_sent].join(" ");
+                        write.send(Message::Text(partial_message(protocol, &text))).await?;
+                    }
+                }
+            }
+            Message::Text(text) => {
+                stats.lock().await.text_messages.push(text.clone());
+
+                let json: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+                match protocol {
+                    MockSttProtocol::Deepgram => match json["type"].as_str() {
+                        Some("CloseStream") => {
+                            if !session.final_sent {
+                                send_final(&mut write, protocol, &script).await?;
+                            }
+                            write.send(Message::Close(None)).await?;
+                            break;
+                        }
+                        Some("Finalize") if !session.final_sent => {
+                            send_final(&mut write, protocol, &script).await?;
+                            session.final_sent = true;
+                        }
+                        _ => {} // KeepAlive и прочее
+                    },
+                    MockSttProtocol::AssemblyAI => {
+                        if json["terminate_session"].as_bool() == Some(true)
+                            || json["type"].as_str() == Some("Terminate")
+                        {
+                            if !session.final_sent {
+                                send_final(&mut write, protocol, &script).await?;
+                            }
+                            write
+                                .send(Message::Text(json!({"type": "Termination"}).to_string()))
+                                .await?;
+                            write.send(Message::Close(None)).await?;
+                            break;
+                        }
+                    }
+                    MockSttProtocol::Backend => match json["type"].as_str() {
+                        Some("config") => {
+                            write
+                                .send(Message::Text(
+                                    json!({"type": "ready", "session_id": "mock-session"}).to_string(),
+                                ))
+                                .await?;
+                        }
+                        Some("finalize") => {
+                            if !session.final_sent {
+                                send_final(&mut write, protocol, &script).await?;
+                            }
+                            // После finalize начинается новая "запись" в том же соединении
+                            session = SessionState {
+                                audio_bytes: 0,
+                                next_partial_at: script.partial_every_bytes,
+                                words_sent: 0,
+                                final_sent: false,
+                                ack_seq: session.ack_seq,
+                            };
+                        }
+                        Some("close") => {
+                            if !session.final_sent {
+                                send_final(&mut write, protocol, &script).await?;
+                            }
+                            write.send(Message::Close(None)).await?;
+                            break;
+                        }
+                        _ => {}
+                    },
+                }
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn partial_message(protocol: MockSttProtocol, text: &str) -> String {
+    match protocol {
+        MockSttProtocol::Deepgram => deepgram_results(text, false),
+        MockSttProtocol::AssemblyAI => json!({
+            "type": "Turn",
+            "transcript": text,
+            "end_of_turn": false,
+            "end_of_turn_confidence": 0.5,
+        })
+        .to_string(),
+        MockSttProtocol::Backend => json!({
+            "type": "partial",
+            "text": text,
+            "confidence": 0.9,
+        })
+        .to_string(),
+    }
+}
+
+async fn send_final<S>(
+    write: &mut S,
+    protocol: MockSttProtocol,
+    script: &MockSttScript,
+) -> Result<(), tokio_tungstenite::tungstenite::Error>
+where
+    S: futures_util::Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    let text = script.full_text();
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let msg = match protocol {
+        MockSttProtocol::Deepgram => deepgram_results(&text, true),
+        MockSttProtocol::AssemblyAI => json!({
+            "type": "Turn",
+            "transcript": text,
+            "end_of_turn": true,
+            "end_of_turn_confidence": 0.99,
+        })
+        .to_string(),
+        MockSttProtocol::Backend => json!({
+            "type": "final",
+            "text": text,
+            "confidence": 0.99,
+            "duration_ms": 1000,
+        })
+        .to_string(),
+    };
+
+    write.send(Message::Text(msg)).await
+}
+
+fn deepgram_results(text: &str, is_final: bool) -> String {
+    json!({
+        "type": "Results",
+        "is_final": is_final,
+        "speech_final": is_final,
+        "start": 0.0,
+        "duration": 1.0,
+        "channel": {
+            "alternatives": [
+                { "transcript": text, "confidence": if is_final { 0.99 } else { 0.9 } }
+            ]
+        }
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_splits_words() {
+        let script = MockSttScript::new("hello  mock world");
+        assert_eq!(script.words, vec!["hello", "mock", "world"]);
+        assert_eq!(script.full_text(), "hello mock world");
+    }
+
+    #[test]
+    fn test_partial_messages_match_provider_formats() {
+        let dg: Value = serde_json::from_str(&partial_message(MockSttProtocol::Deepgram, "hi")).unwrap();
+        assert_eq!(dg["type"], "Results");
+        assert_eq!(dg["channel"]["alternatives"][0]["transcript"], "hi");
+
+        let aai: Value = serde_json::from_str(&partial_message(MockSttProtocol::AssemblyAI, "hi")).unwrap();
+        assert_eq!(aai["type"], "Turn");
+        assert_eq!(aai["end_of_turn"], false);
+
+        let backend = partial_message(MockSttProtocol::Backend, "hi");
+        let parsed: super::super::backend_messages::ServerMessage = serde_json::from_str(&backend).unwrap();
+        assert!(matches!(
+            parsed,
+            super::super::backend_messages::ServerMessage::Partial { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_url_per_protocol() {
+        let server = MockSttServer::start(MockSttProtocol::Deepgram, MockSttScript::new("a"))
+            .await
+            .unwrap();
+        assert!(server.url().starts_with("ws://127.0.0.1:"));
+        assert!(server.url().ends_with("/v1/listen"));
+        assert_eq!(server.stats().await.connections, 0);
+    }
+}
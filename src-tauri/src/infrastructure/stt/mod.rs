@@ -1,12 +1,35 @@
 /// STT provider implementations
 
+mod audio_encoder;
 mod deepgram;
+mod deepgram_messages;
 mod whisper_local;
 mod assemblyai;
 mod backend;
 mod backend_messages;
+mod batch;
+mod split_channel;
+mod stream_state;
+// Замедление записи нужно только локальному Whisper
+#[cfg_attr(not(feature = "whisper"), allow(dead_code))]
+mod time_stretch;
+mod ws_transport;
+#[cfg(feature = "mock-stt-server")]
+mod mock_server;
 
+pub use audio_encoder::{create_audio_encoder, wav_bytes, AudioEncoder, FlacEncoder, Linear16Encoder};
+#[cfg(feature = "opus")]
+pub use audio_encoder::OggOpusEncoder;
 pub use deepgram::DeepgramProvider;
-pub use whisper_local::WhisperLocalProvider;
+pub use whisper_local::{
+    cancel_whisper_transcription, set_whisper_progress_listener, WhisperLocalProvider, WhisperProgress,
+    WhisperProgressListener,
+};
 pub use assemblyai::AssemblyAIProvider;
 pub use backend::BackendProvider;
+pub use batch::{AssemblyAIBatchProvider, DeepgramBatchProvider};
+pub use split_channel::SplitChannelProvider;
+pub use stream_state::{StreamLifecycle, StreamState, StreamTransitionError};
+pub use ws_transport::{SttMessageCodec, WsKeepAlive, WsSttTransport, WsTransportError};
+#[cfg(feature = "mock-stt-server")]
+pub use mock_server::{MockSttProtocol, MockSttScript, MockSttServer, MockSttStats};
@@ -0,0 +1,267 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::domain::{
+    AudioChunk, ConnectionQualityCallback, ErrorCallback, SttConfig, SttProvider, SttProviderDebugState, SttResult,
+    Transcription, TranscriptionCallback,
+};
+
+/// Split-channel transcription for stereo interviews
+///
+/// Интервьюер на левом канале, гость на правом: каждый канал уходит в свой стрим провайдера,
+/// поэтому голоса распознаются без диаризации, а фразы помечаются каналом (`Transcription::channel`)
+/// и чередуются в порядке прихода. Моно-чанки (устройство без второго канала) идут только в канал 0.
+///
+/// Каждый стрим — отдельное соединение (или отдельная модель у локального Whisper).
+pub struct SplitChannelProvider {
+    /// Стримы каналов: 0 — левый, 1 — правый
+    channels: [Box<dyn SttProvider>; 2],
+}
+
+impl SplitChannelProvider {
+    pub fn new(left: Box<dyn SttProvider>, right: Box<dyn SttProvider>) -> Self {
+        Self {
+            channels: [left, right],
+        }
+    }
+}
+
+/// Interleaved stereo PCM → (левый, правый)
+pub fn deinterleave_stereo(samples: &[i16]) -> (Vec<i16>, Vec<i16>) {
+    samples
+        .chunks_exact(2)
+        .map(|frame| (frame[0], frame[1]))
+        .unzip()
+}
+
+fn tag_channel(callback: TranscriptionCallback, channel: u32) -> TranscriptionCallback {
+    Arc::new(move |transcription: Transcription| callback(transcription.with_channel(Some(channel))))
+}
+
+fn mono_chunk(data: Vec<i16>, source: &AudioChunk) -> AudioChunk {
+    let mut chunk = AudioChunk::new(data, source.sample_rate, 1);
+    chunk.timestamp = source.timestamp;
+    chunk
+}
+
+#[async_trait]
+impl SttProvider for SplitChannelProvider {
+    async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
+        for provider in self.channels.iter_mut() {
+            provider.initialize(config).await?;
+        }
+        Ok(())
+    }
+
+    async fn start_stream(
+        &mut self,
+        on_partial: TranscriptionCallback,
+        on_final: TranscriptionCallback,
+        on_error: ErrorCallback,
+        on_connection_quality: ConnectionQualityCallback,
+    ) -> SttResult<()> {
+        let mut started = 0;
+        let mut failure = None;
+        for (channel, provider) in self.channels.iter_mut().enumerate() {
+            let result = provider
+                .start_stream(
+                    tag_channel(on_partial.clone(), channel as u32),
+                    tag_channel(on_final.clone(), channel as u32),
+                    on_error.clone(),
+                    on_connection_quality.clone(),
+                )
+                .await;
+            match result {
+                Ok(()) => started += 1,
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+        if let Some(e) = failure {
+            // Интервью с одним каналом — не то, что просил пользователь: закрываем уже открытые
+            for opened in self.channels[..started].iter_mut() {
+                let _ = opened.abort().await;
+            }
+            return Err(e);
+        }
+        log::info!("Split-channel streams started ({} channels)", self.channels.len());
+        Ok(())
+    }
+
+    async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+        if chunk.channels < 2 {
+            return self.channels[0].send_audio(chunk).await;
+        }
+
+        let (left, right) = if chunk.channels == 2 {
+            deinterleave_stereo(&chunk.data)
+        } else {
+            chunk
+                .data
+                .chunks_exact(chunk.channels as usize)
+                .map(|frame| (frame[0], frame[1]))
+                .unzip()
+        };
+        self.channels[0].send_audio(&mono_chunk(left, chunk)).await?;
+        self.channels[1].send_audio(&mono_chunk(right, chunk)).await
+    }
+
+    async fn stop_stream(&mut self) -> SttResult<()> {
+        // Останавливаем оба стрима, даже если первый вернул ошибку
+        let mut result = Ok(());
+        for provider in self.channels.iter_mut() {
+            if let Err(e) = provider.stop_stream().await {
+                log::warn!("Failed to stop split-channel stream ({}): {}", provider.name(), e);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    async fn abort(&mut self) -> SttResult<()> {
+        let mut result = Ok(());
+        for provider in self.channels.iter_mut() {
+            if let Err(e) = provider.abort().await {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    fn name(&self) -> &str {
+        self.channels[0].name()
+    }
+
+    fn remaining_quota_secs(&self) -> Option<f32> {
+        self.channels[0].remaining_quota_secs()
+    }
+
+    /// Мёртвая задача чтения в любом из каналов — мёртв весь стрим (watchdog перезапустит оба)
+    fn debug_state(&self) -> SttProviderDebugState {
+        let states: Vec<SttProviderDebugState> = self.channels.iter().map(|p| p.debug_state()).collect();
+        let receiver_alive = states
+            .iter()
+            .filter_map(|s| s.receiver_alive)
+            .reduce(|a, b| a && b);
+        SttProviderDebugState {
+            streaming: states.iter().all(|s| s.streaming),
+            paused: states.iter().any(|s| s.paused),
+            buffered_samples: states.iter().map(|s| s.buffered_samples).sum(),
+            receiver_alive,
+        }
+    }
+
+    fn is_online(&self) -> bool {
+        self.channels[0].is_online()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ConnectionQualityReason, SttError};
+    use std::sync::Mutex;
+
+    /// Запоминает отправленные чанки и отдаёт final на каждый
+    struct RecordingProvider {
+        sent: Arc<Mutex<Vec<Vec<i16>>>>,
+        on_final: Option<TranscriptionCallback>,
+    }
+
+    #[async_trait]
+    impl SttProvider for RecordingProvider {
+        async fn initialize(&mut self, _config: &SttConfig) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn start_stream(
+            &mut self,
+            _on_partial: TranscriptionCallback,
+            on_final: TranscriptionCallback,
+            _on_error: ErrorCallback,
+            _on_connection_quality: ConnectionQualityCallback,
+        ) -> SttResult<()> {
+            self.on_final = Some(on_final);
+            Ok(())
+        }
+
+        async fn send_audio(&mut self, chunk: &AudioChunk) -> SttResult<()> {
+            assert_eq!(chunk.channels, 1);
+            self.sent.lock().unwrap().push(chunk.data.clone());
+            if let Some(on_final) = self.on_final.as_ref() {
+                on_final(Transcription::final_result(format!("{:?}", chunk.data)));
+            }
+            Ok(())
+        }
+
+        async fn stop_stream(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        async fn abort(&mut self) -> SttResult<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn is_online(&self) -> bool {
+            false
+        }
+    }
+
+    fn recording() -> (Box<dyn SttProvider>, Arc<Mutex<Vec<Vec<i16>>>>) {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let provider = RecordingProvider {
+            sent: sent.clone(),
+            on_final: None,
+        };
+        (Box::new(provider), sent)
+    }
+
+    #[test]
+    fn deinterleaves_left_and_right() {
+        assert_eq!(deinterleave_stereo(&[1, -1, 2, -2, 3]), (vec![1, 2], vec![-1, -2]));
+    }
+
+    #[tokio::test]
+    async fn routes_each_channel_to_its_stream_and_tags_finals() {
+        let (left, left_sent) = recording();
+        let (right, right_sent) = recording();
+        let mut provider = SplitChannelProvider::new(left, right);
+
+        let finals = Arc::new(Mutex::new(Vec::new()));
+        let finals_in_callback = finals.clone();
+        provider
+            .start_stream(
+                Arc::new(|_: Transcription| {}),
+                Arc::new(move |t: Transcription| finals_in_callback.lock().unwrap().push((t.channel, t.text))),
+                Arc::new(|_: SttError| {}),
+                Arc::new(|_: String, _: Option<ConnectionQualityReason>| {}),
+            )
+            .await
+            .unwrap();
+
+        provider.send_audio(&AudioChunk::new(vec![1, -1, 2, -2], 16000, 2)).await.unwrap();
+        // Моно-устройство: всё в левый канал
+        provider.send_audio(&AudioChunk::new(vec![7, 8], 16000, 1)).await.unwrap();
+
+        assert_eq!(*left_sent.lock().unwrap(), vec![vec![1, 2], vec![7, 8]]);
+        assert_eq!(*right_sent.lock().unwrap(), vec![vec![-1, -2]]);
+        assert_eq!(
+            *finals.lock().unwrap(),
+            vec![
+                (Some(0), "[1, 2]".to_string()),
+                (Some(1), "[-1, -2]".to_string()),
+                (Some(0), "[7, 8]".to_string()),
+            ]
+        );
+    }
+}
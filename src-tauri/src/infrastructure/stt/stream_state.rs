@@ -0,0 +1,237 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use crate::domain::SttError;
+
+/// Lifecycle of a streaming STT connection
+///
+/// ```text
+/// Idle/Closed → Connecting → Streaming ⇄ Paused
+///                   │            │         │
+///                   └──────→ Closing ←─────┘ → Closed
+/// ```
+///
+/// Любое состояние можно принудительно перевести в Closed (abort, обрыв соединения).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum StreamState {
+    Idle = 0,
+    Connecting = 1,
+    Streaming = 2,
+    /// Соединение живо (keep-alive), но аудио не обрабатывается
+    Paused = 3,
+    Closing = 4,
+    Closed = 5,
+}
+
+impl StreamState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => StreamState::Idle,
+            1 => StreamState::Connecting,
+            2 => StreamState::Streaming,
+            3 => StreamState::Paused,
+            4 => StreamState::Closing,
+            _ => StreamState::Closed,
+        }
+    }
+
+    /// Таблица допустимых переходов
+    pub fn can_transition_to(self, to: StreamState) -> bool {
+        use StreamState::*;
+        matches!(
+            (self, to),
+            (Idle | Closed, Connecting)
+                | (Connecting, Streaming | Closing | Closed)
+                | (Streaming, Paused | Closing | Closed)
+                | (Paused, Streaming | Closing | Closed)
+                | (Closing, Closed)
+        )
+    }
+}
+
+/// Attempt to move a stream into a state that is not reachable from the current one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid stream transition: {from:?} -> {to:?}")]
+pub struct StreamTransitionError {
+    pub from: StreamState,
+    pub to: StreamState,
+}
+
+impl From<StreamTransitionError> for SttError {
+    fn from(err: StreamTransitionError) -> Self {
+        SttError::Processing(err.to_string())
+    }
+}
+
+/// Shared, lock-free stream state of a provider
+///
+/// Клонируется в фоновые задачи (receiver, keep-alive), так что они видят то же состояние,
+/// что и провайдер, без отдельных флагов под мьютексом.
+#[derive(Debug, Clone)]
+pub struct StreamLifecycle {
+    state: Arc<AtomicU8>,
+}
+
+impl Default for StreamLifecycle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamLifecycle {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(AtomicU8::new(StreamState::Idle as u8)),
+        }
+    }
+
+    pub fn state(&self) -> StreamState {
+        StreamState::from_u8(self.state.load(Ordering::SeqCst))
+    }
+
+    /// Соединение установлено (стримим или на паузе)
+    pub fn is_open(&self) -> bool {
+        matches!(self.state(), StreamState::Streaming | StreamState::Paused)
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.state() == StreamState::Streaming
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state() == StreamState::Paused
+    }
+
+    /// Закрытие начато нами или соединение уже закрыто
+    pub fn is_closed(&self) -> bool {
+        matches!(self.state(), StreamState::Closing | StreamState::Closed)
+    }
+
+    /// Проверяет переход, не выполняя его
+    pub fn check(&self, to: StreamState) -> Result<StreamState, StreamTransitionError> {
+        let from = self.state();
+        if from.can_transition_to(to) {
+            Ok(from)
+        } else {
+            Err(StreamTransitionError { from, to })
+        }
+    }
+
+    /// Атомарно выполняет переход; возвращает предыдущее состояние
+    pub fn transition(&self, to: StreamState) -> Result<StreamState, StreamTransitionError> {
+        let mut current = self.state.load(Ordering::SeqCst);
+        loop {
+            let from = StreamState::from_u8(current);
+            if !from.can_transition_to(to) {
+                return Err(StreamTransitionError { from, to });
+            }
+            match self
+                .state
+                .compare_exchange(current, to as u8, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => return Ok(from),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Closed из любого состояния; возвращает предыдущее
+    pub fn force_close(&self) -> StreamState {
+        StreamState::from_u8(self.state.swap(StreamState::Closed as u8, Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_lifecycle() {
+        let lifecycle = StreamLifecycle::new();
+        assert_eq!(lifecycle.state(), StreamState::Idle);
+        assert!(!lifecycle.is_open());
+        assert!(!lifecycle.is_closed());
+
+        lifecycle.transition(StreamState::Connecting).unwrap();
+        assert!(!lifecycle.is_open());
+        lifecycle.transition(StreamState::Streaming).unwrap();
+        assert!(lifecycle.is_open());
+        assert!(lifecycle.is_streaming());
+
+        assert_eq!(
+            lifecycle.transition(StreamState::Paused),
+            Ok(StreamState::Streaming)
+        );
+        assert!(lifecycle.is_open());
+        assert!(lifecycle.is_paused());
+        lifecycle.transition(StreamState::Streaming).unwrap();
+
+        lifecycle.transition(StreamState::Closing).unwrap();
+        assert!(!lifecycle.is_open());
+        assert!(lifecycle.is_closed());
+        lifecycle.transition(StreamState::Closed).unwrap();
+
+        // Закрытый стрим можно открыть заново
+        lifecycle.transition(StreamState::Connecting).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_transitions_are_rejected() {
+        let lifecycle = StreamLifecycle::new();
+
+        assert_eq!(
+            lifecycle.transition(StreamState::Streaming),
+            Err(StreamTransitionError {
+                from: StreamState::Idle,
+                to: StreamState::Streaming,
+            })
+        );
+        assert!(lifecycle.transition(StreamState::Paused).is_err());
+        assert_eq!(lifecycle.state(), StreamState::Idle);
+
+        lifecycle.transition(StreamState::Connecting).unwrap();
+        // Второй старт, пока первый не закончился
+        assert!(lifecycle.transition(StreamState::Connecting).is_err());
+        lifecycle.transition(StreamState::Streaming).unwrap();
+        assert!(lifecycle.transition(StreamState::Streaming).is_err());
+        assert!(lifecycle.transition(StreamState::Connecting).is_err());
+
+        lifecycle.transition(StreamState::Closing).unwrap();
+        assert!(lifecycle.transition(StreamState::Paused).is_err());
+        assert!(lifecycle.transition(StreamState::Streaming).is_err());
+    }
+
+    #[test]
+    fn test_check_does_not_change_state() {
+        let lifecycle = StreamLifecycle::new();
+        assert!(lifecycle.check(StreamState::Connecting).is_ok());
+        assert_eq!(lifecycle.state(), StreamState::Idle);
+        assert!(lifecycle.check(StreamState::Paused).is_err());
+    }
+
+    #[test]
+    fn test_force_close_from_any_state_and_shared_clones() {
+        let lifecycle = StreamLifecycle::new();
+        let task_view = lifecycle.clone();
+
+        lifecycle.transition(StreamState::Connecting).unwrap();
+        lifecycle.transition(StreamState::Streaming).unwrap();
+        lifecycle.transition(StreamState::Paused).unwrap();
+        assert!(task_view.is_paused());
+
+        assert_eq!(task_view.force_close(), StreamState::Paused);
+        assert_eq!(lifecycle.state(), StreamState::Closed);
+        assert!(!lifecycle.is_open());
+    }
+
+    #[test]
+    fn test_transition_error_maps_to_processing() {
+        let err: SttError = StreamTransitionError {
+            from: StreamState::Idle,
+            to: StreamState::Paused,
+        }
+        .into();
+        assert!(matches!(err, SttError::Processing(msg) if msg.contains("Idle -> Paused")));
+    }
+}
@@ -1,16 +1,151 @@
 use async_trait::async_trait;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use crate::domain::{
     AudioChunk, SttConfig, SttError, SttProvider, SttResult, TranscriptionCallback,
 };
 
+/// Whisper works on 16 kHz audio in windows of up to 30 seconds
+const WHISPER_SAMPLE_RATE: usize = 16000;
+const WINDOW_SECS: usize = 30;
+
+/// Progress of a local Whisper transcription
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhisperProgress {
+    /// Audio windows (up to 30s each) transcribed so far
+    pub processed_segments: usize,
+    /// Windows in the whole recording
+    pub estimated_segments: usize,
+}
+
+pub type WhisperProgressListener = Arc<dyn Fn(WhisperProgress) + Send + Sync>;
+
+/// Инференс идёт по одной задаче на выделенном потоке, поэтому состояние задачи — на процесс:
+/// отменить её можно, не дожидаясь lock'а провайдера, который держит stop_stream()
+static JOB_RUNNING: AtomicBool = AtomicBool::new(false);
+static CANCEL_REQUESTED: AtomicBool = AtomicBool::new(false);
+static PROGRESS_LISTENER: Mutex<Option<WhisperProgressListener>> = Mutex::new(None);
+
+/// Слушатель прогресса локальной расшифровки (UI показывает "обработано N из M" и кнопку отмены)
+pub fn set_whisper_progress_listener(listener: Option<WhisperProgressListener>) {
+    if let Ok(mut guard) = PROGRESS_LISTENER.lock() {
+        *guard = listener;
+    }
+}
+
+/// Просит прервать идущую локальную расшифровку после текущего окна; false — расшифровки нет
+pub fn cancel_whisper_transcription() -> bool {
+    if !JOB_RUNNING.load(Ordering::SeqCst) {
+        return false;
+    }
+    log::info!("WhisperLocalProvider: cancellation requested");
+    CANCEL_REQUESTED.store(true, Ordering::SeqCst);
+    true
+}
+
+/// Одна локальная расшифровка: окна аудио, кооперативная отмена между окнами, прогресс
+#[cfg_attr(not(feature = "whisper"), allow(dead_code))]
+struct WhisperJob {
+    windows: Vec<Range<usize>>,
+}
+
+#[cfg_attr(not(feature = "whisper"), allow(dead_code))]
+impl WhisperJob {
+    fn begin(total_samples: usize) -> Self {
+        CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+        JOB_RUNNING.store(true, Ordering::SeqCst);
+
+        let window = WHISPER_SAMPLE_RATE * WINDOW_SECS;
+        let windows = (0..total_samples)
+            .step_by(window)
+            .map(|start| start..(start + window).min(total_samples))
+            .collect();
+        Self { windows }
+    }
+
+    fn windows(&self) -> &[Range<usize>] {
+        &self.windows
+    }
+
+    fn is_cancelled(&self) -> bool {
+        CANCEL_REQUESTED.load(Ordering::SeqCst)
+    }
+
+    fn report_processed(&self, processed_segments: usize) {
+        let listener = PROGRESS_LISTENER.lock().ok().and_then(|guard| guard.clone());
+        if let Some(listener) = listener {
+            listener(WhisperProgress {
+                processed_segments,
+                estimated_segments: self.windows.len(),
+            });
+        }
+    }
+}
+
+impl Drop for WhisperJob {
+    fn drop(&mut self) {
+        JOB_RUNNING.store(false, Ordering::SeqCst);
+        CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+    }
+}
+
 // Полная реализация с whisper-rs (требуется feature "whisper" и cmake)
 #[cfg(feature = "whisper")]
 mod whisper_impl {
     use super::*;
-    use std::sync::Arc;
+    use std::sync::mpsc;
+    use std::sync::OnceLock;
     use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy};
+    use crate::domain::Transcription;
     use crate::infrastructure::models::whisper_models;
+    use super::super::time_stretch::time_stretch;
+
+    type InferenceTask = Box<dyn FnOnce() + Send>;
+
+    /// Выделенный поток инференса с пониженным приоритетом: длинная расшифровка не отнимает CPU
+    /// у UI и захвата звука и не занимает blocking-пул tokio (потоки распараллеливает сам whisper.cpp)
+    static INFERENCE_THREAD: OnceLock<mpsc::Sender<InferenceTask>> = OnceLock::new();
+
+    fn run_on_inference_thread<T: Send + 'static>(
+        task: impl FnOnce() -> T + Send + 'static,
+    ) -> tokio::sync::oneshot::Receiver<T> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let sender = INFERENCE_THREAD.get_or_init(|| {
+            let (sender, tasks) = mpsc::channel::<InferenceTask>();
+            let spawned = std::thread::Builder::new()
+                .name("whisper-inference".to_string())
+                .spawn(move || {
+                    lower_thread_priority();
+                    for task in tasks {
+                        task();
+                    }
+                });
+            if let Err(e) = spawned {
+                log::error!("WhisperLocalProvider: failed to spawn inference thread: {}", e);
+            }
+            sender
+        });
+        // Поток не поднялся — rx закроется, и вызывающий получит ошибку
+        let _ = sender.send(Box::new(move || {
+            let _ = tx.send(task());
+        }));
+        rx
+    }
+
+    fn lower_thread_priority() {
+        // macOS: QoS utility — планировщик отдаёт приоритет интерактивным потокам
+        #[cfg(target_os = "macos")]
+        unsafe {
+            libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_UTILITY, 0);
+        }
+        // Linux: nice задаётся на поток (who = 0 — вызывающий поток)
+        #[cfg(target_os = "linux")]
+        unsafe {
+            libc::setpriority(libc::PRIO_PROCESS, 0, 10);
+        }
+    }
 
     pub struct WhisperLocalProvider {
         config: Option<SttConfig>,
@@ -61,10 +196,7 @@ mod whisper_impl {
         async fn initialize(&mut self, config: &SttConfig) -> SttResult<()> {
             log::info!("WhisperLocalProvider: Initializing");
 
-            let model_name = config
-                .model
-                .clone()
-                .unwrap_or_else(|| "base".to_string());
+            let model_name = config.effective_model().unwrap_or("base").to_string();
 
             log::info!("WhisperLocalProvider: Using model: {}", model_name);
 
@@ -92,6 +224,7 @@ mod whisper_impl {
             _on_partial: TranscriptionCallback,
             on_final: TranscriptionCallback,
             _on_error: crate::domain::ErrorCallback,
+            _on_connection_quality: crate::domain::ConnectionQualityCallback,
         ) -> SttResult<()> {
             log::info!("WhisperLocalProvider: Starting stream (buffering mode)");
 
@@ -153,47 +286,80 @@ mod whisper_impl {
                 .and_then(|c| Some(c.language.clone()))
                 .unwrap_or_else(|| "ru".to_string());
 
-            let start_time = std::time::Instant::now();
+            let language_for_task = language.clone();
+            let decoding = self.config.as_ref()
+                .map(|c| c.whisper.clone())
+                .unwrap_or_default();
+            let transcription_result = run_on_inference_thread(move || {
+                let audio_f32 = match decoding.time_stretch_tempo() {
+                    Some(tempo) => {
+                        log::info!("WhisperLocalProvider: slowing audio down to {:.2}x before transcription", tempo);
+                        time_stretch(&audio_f32, WHISPER_SAMPLE_RATE, tempo)
+                    }
+                    None => audio_f32,
+                };
 
-            let transcription_result = tokio::task::spawn_blocking(move || {
-                let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-                params.set_language(Some(&language));
-                params.set_translate(false);
-                params.set_print_progress(false);
-                params.set_print_special(false);
-                params.set_print_realtime(false);
-                params.set_n_threads(num_cpus::get() as i32);
+                // Окнами по 30s: между ними проверяем отмену и сообщаем прогресс
+                let job = WhisperJob::begin(audio_f32.len());
+                // Одно ядро оставляем UI и захвату звука
+                let n_threads = num_cpus::get().saturating_sub(1).max(1) as i32;
 
                 let mut state = ctx.create_state()
                     .map_err(|e| SttError::Internal(format!("Failed to create Whisper state: {}", e)))?;
 
-                state.full(params, &audio_f32)
-                    .map_err(|e| SttError::Processing(format!("Transcription failed: {}", e)))?;
-
-                let num_segments = state.full_n_segments()
-                    .map_err(|e| SttError::Processing(format!("Failed to get segments: {}", e)))?;
-
                 let mut full_text = String::new();
-                for i in 0..num_segments {
-                    match state.full_get_segment_text(i) {
-                        Ok(segment_text) => {
-                            full_text.push_str(&segment_text);
-                            full_text.push(' ');
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to get segment {} text: {}", i, e);
+                for (index, window) in job.windows().iter().enumerate() {
+                    if job.is_cancelled() {
+                        log::info!("WhisperLocalProvider: cancelled after {}/{} windows", index, job.windows().len());
+                        return Err(SttError::Processing("Local transcription cancelled".to_string()));
+                    }
+
+                    let strategy = match decoding.beam_search_width() {
+                        Some(width) => SamplingStrategy::BeamSearch { beam_size: width as i32, patience: -1.0 },
+                        None => SamplingStrategy::Greedy { best_of: 1 },
+                    };
+                    let mut params = FullParams::new(strategy);
+                    params.set_language(Some(&language_for_task));
+                    params.set_temperature(decoding.temperature);
+                    params.set_no_speech_thold(decoding.no_speech_threshold);
+                    // Промпт задаёт словарь и стиль ("медицинские термины") — заметно точнее на узкой лексике
+                    if let Some(prompt) = decoding.initial_prompt() {
+                        params.set_initial_prompt(prompt);
+                    }
+                    params.set_translate(false);
+                    params.set_print_progress(false);
+                    params.set_print_special(false);
+                    params.set_print_realtime(false);
+                    params.set_n_threads(n_threads);
+
+                    state.full(params, &audio_f32[window.clone()])
+                        .map_err(|e| SttError::Processing(format!("Transcription failed: {}", e)))?;
+
+                    let num_segments = state.full_n_segments()
+                        .map_err(|e| SttError::Processing(format!("Failed to get segments: {}", e)))?;
+
+                    for i in 0..num_segments {
+                        match state.full_get_segment_text(i) {
+                            Ok(segment_text) => {
+                                full_text.push_str(&segment_text);
+                                full_text.push(' ');
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to get segment {} text: {}", i, e);
+                            }
                         }
                     }
+
+                    job.report_processed(index + 1);
                 }
 
                 Ok::<String, SttError>(full_text.trim().to_string())
             })
             .await
-            .map_err(|e| SttError::Internal(format!("Transcription task failed: {}", e)))??;
+            .map_err(|_| SttError::Internal("Whisper inference thread is not available".to_string()))??;
 
-            let elapsed = start_time.elapsed();
-            log::info!("WhisperLocalProvider: Transcription completed in {:.2}s: '{}'",
-                elapsed.as_secs_f32(), transcription_result);
+            // Время распознавания учитывает LatencyTracker (audio sent → final)
+            log::info!("WhisperLocalProvider: Transcription completed: '{}'", transcription_result);
 
             let transcription = Transcription {
                 text: transcription_result,
@@ -206,6 +372,11 @@ mod whisper_impl {
                     .as_secs() as i64,
                 start: 0.0, // Whisper Local не предоставляет start время
                 duration: 0.0, // Whisper Local не предоставляет duration
+                alternatives: Vec::new(),
+                speaker: None,
+                channel: None,
+                calendar: None,
+                app: None,
             };
 
             callback(transcription);
@@ -228,6 +399,15 @@ mod whisper_impl {
             "Whisper Local (Offline)"
         }
 
+        fn debug_state(&self) -> crate::domain::SttProviderDebugState {
+            // Всё аудио копится до stop_stream — буфер и есть "неотправленное"
+            crate::domain::SttProviderDebugState {
+                streaming: self.is_streaming,
+                buffered_samples: self.audio_buffer.len(),
+                ..Default::default()
+            }
+        }
+
         fn is_online(&self) -> bool {
             false
         }
@@ -307,3 +487,26 @@ mod whisper_impl {
 
 // Экспортируем реализацию (либо полную либо заглушку)
 pub use whisper_impl::WhisperLocalProvider;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_splits_audio_into_windows_and_honours_cancellation() {
+        assert!(!cancel_whisper_transcription());
+
+        let window = WHISPER_SAMPLE_RATE * WINDOW_SECS;
+        let job = WhisperJob::begin(window * 2 + 100);
+        assert_eq!(job.windows(), &[0..window, window..window * 2, window * 2..window * 2 + 100]);
+        assert!(!job.is_cancelled());
+
+        assert!(cancel_whisper_transcription());
+        assert!(job.is_cancelled());
+
+        // Следующая расшифровка начинается без старой отмены
+        drop(job);
+        assert!(!cancel_whisper_transcription());
+        assert!(!WhisperJob::begin(0).is_cancelled());
+    }
+}
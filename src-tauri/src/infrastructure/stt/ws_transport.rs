@@ -0,0 +1,540 @@
+//! Общий WebSocket-транспорт потоковых STT провайдеров.
+//!
+//! Транспорт отвечает за подключение, split, receiver task, keep-alive и закрытие.
+//! Провайдер описывает только протокол: `SttMessageCodec` разбирает входящие сообщения,
+//! а сам провайдер решает, что и когда отправлять.
+//!
+//! TCP подключается по happy eyeballs (RFC 8305): адреса IPv6 и IPv4 пробуются вперемешку,
+//! следующая попытка стартует, не дожидаясь зависшей предыдущей — сломанный IPv6 не тормозит запись.
+
+use async_trait::async_trait;
+use futures_util::stream::SplitSink;
+use futures_util::{SinkExt, StreamExt};
+use http::Request;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::{lookup_host, TcpStream};
+use tokio::sync::Mutex;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::Duration;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{client_async_tls, MaybeTlsStream, WebSocketStream};
+
+use super::stream_state::StreamLifecycle;
+use crate::domain::{SttConnectionCategory, SttConnectionDetails, SttConnectionError, SttError, SttResult};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type WsSink = SplitSink<WsStream, Message>;
+
+/// Пауза перед следующей попыткой подключения, пока предыдущая ещё висит (RFC 8305: 250 мс)
+const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Provider-specific handling of incoming WebSocket frames
+///
+/// Кодек живёт внутри receiver task и удаляется вместе с ней (в т.ч. при abort),
+/// поэтому свои фоновые задачи кодек может останавливать в Drop.
+#[async_trait]
+pub trait SttMessageCodec: Send + 'static {
+    /// Имя провайдера для логов
+    fn provider_name(&self) -> &'static str;
+
+    /// Вызывается на каждый входящий фрейм до разбора; false — фрейм игнорируется (например, пауза)
+    async fn accept_frame(&mut self) -> bool {
+        true
+    }
+
+    /// Текстовое (JSON) сообщение сервера
+    async fn on_text(&mut self, text: String);
+
+    /// Сервер закрыл соединение; после этого receiver task завершается
+    async fn on_close(&mut self, _frame: Option<CloseFrame<'static>>) {}
+
+    /// Ошибка чтения — соединение потеряно; после этого receiver task завершается
+    async fn on_error(&mut self, _error: tungstenite::Error) {}
+
+    /// Receiver task завершается (сервер закрыл поток или ошибка, но не abort)
+    async fn on_finished(&mut self) {}
+}
+
+/// Connect or send failure of the transport
+#[derive(Debug)]
+pub enum WsTransportError {
+    Timeout,
+    Ws(tungstenite::Error),
+}
+
+impl WsTransportError {
+    /// До сервера не достучались (DNS, маршрут, таймаут) — есть смысл попробовать другой endpoint.
+    /// Ответ сервера (HTTP статус handshake) другим endpoint'ом не исправить
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self, WsTransportError::Timeout | WsTransportError::Ws(tungstenite::Error::Io(_)))
+    }
+}
+
+impl std::fmt::Display for WsTransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WsTransportError::Timeout => write!(f, "timeout"),
+            WsTransportError::Ws(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Максимальная пауза из Retry-After, которую показываем и ждём (защита от мусорных заголовков)
+const MAX_RETRY_AFTER_SECS: u64 = 10 * 60;
+
+/// Retry-After в секундах: число секунд или HTTP-дата (RFC 7231); дата в прошлом — 0
+pub(crate) fn parse_retry_after(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let secs = match value.parse::<u64>() {
+        Ok(secs) => secs,
+        Err(_) => {
+            let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+            let delta = at.timestamp() - chrono::Utc::now().timestamp();
+            delta.max(0) as u64
+        }
+    };
+    Some(secs.min(MAX_RETRY_AFTER_SECS))
+}
+
+/// Ошибка подключения с разбором основных HTTP-статусов handshake (у Backend свой, подробный)
+impl From<WsTransportError> for SttError {
+    fn from(err: WsTransportError) -> Self {
+        match err {
+            WsTransportError::Timeout => SttError::Connection(SttConnectionError::with_category(
+                "WS connection timeout".to_string(),
+                SttConnectionCategory::Timeout,
+            )),
+            WsTransportError::Ws(tungstenite::Error::Http(resp)) => {
+                let status = resp.status();
+                let message = format!("WS connection failed: HTTP error: {}", status);
+                let details = SttConnectionDetails {
+                    http_status: Some(status.as_u16()),
+                    ..Default::default()
+                };
+                match status.as_u16() {
+                    401 | 403 => SttError::Authentication(format!("{} — check the API key", status)),
+                    402 => SttError::QuotaExceeded {
+                        message,
+                        details: SttConnectionDetails {
+                            category: Some(SttConnectionCategory::LimitExceeded),
+                            ..details
+                        },
+                    },
+                    429 => SttError::RateLimited {
+                        message,
+                        retry_after_secs: resp
+                            .headers()
+                            .get("Retry-After")
+                            .and_then(|v| v.to_str().ok())
+                            .and_then(parse_retry_after),
+                        details: SttConnectionDetails {
+                            category: Some(SttConnectionCategory::RateLimited),
+                            ..details
+                        },
+                    },
+                    code => SttError::Connection(SttConnectionError {
+                        message,
+                        details: SttConnectionDetails {
+                            category: Some(if matches!(code, 502..=504) {
+                                SttConnectionCategory::ServerUnavailable
+                            } else {
+                                SttConnectionCategory::Http
+                            }),
+                            ..details
+                        },
+                    }),
+                }
+            }
+            WsTransportError::Ws(e) => SttError::Connection(SttConnectionError::simple(format!(
+                "WS connection failed: {}",
+                e
+            ))),
+        }
+    }
+}
+
+/// Periodic keep-alive message
+pub struct WsKeepAlive {
+    pub interval: Duration,
+    pub message: fn() -> Message,
+    /// Таймаут отправки (None — ждём сколько угодно)
+    pub send_timeout: Option<Duration>,
+    /// Если задан: keep-alive прекращается, когда стрим закрывается,
+    /// а неудачная отправка переводит стрим в Closed
+    pub lifecycle: Option<StreamLifecycle>,
+}
+
+/// One WebSocket connection of a streaming STT provider
+pub struct WsSttTransport {
+    provider_name: &'static str,
+    writer: Arc<Mutex<WsSink>>,
+    receiver_task: JoinHandle<()>,
+    keepalive_task: Option<JoinHandle<()>>,
+}
+
+impl WsSttTransport {
+    /// WebSocket upgrade request with an Authorization header
+    pub fn request(url: &str, host: &str, authorization: &str) -> SttResult<Request<()>> {
+        Request::builder()
+            .method("GET")
+            .uri(url)
+            .header("Host", host)
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header(
+                "Sec-WebSocket-Key",
+                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+            )
+            .header("Authorization", authorization)
+            .body(())
+            .map_err(|e| {
+                SttError::Connection(SttConnectionError::simple(format!(
+                    "Failed to build WS request: {}",
+                    e
+                )))
+            })
+    }
+
+    /// Подключается и запускает receiver task с переданным кодеком
+    pub async fn connect<C: SttMessageCodec>(
+        request: Request<()>,
+        connect_timeout: Option<Duration>,
+        codec: C,
+    ) -> Result<Self, WsTransportError> {
+        let ws_stream = open_ws(request, connect_timeout).await?;
+        Ok(Self::start(ws_stream, codec))
+    }
+
+    /// Как `connect`, но если endpoint недоступен (DNS, маршрут, таймаут), пробует следующий
+    /// (например, региональный endpoint провайдера). Ошибку ответа сервера возвращает сразу
+    pub async fn connect_with_fallback<C: SttMessageCodec>(
+        requests: Vec<Request<()>>,
+        connect_timeout: Option<Duration>,
+        codec: C,
+    ) -> Result<Self, WsTransportError> {
+        let mut last_error = WsTransportError::Timeout;
+        let count = requests.len();
+        for (index, request) in requests.into_iter().enumerate() {
+            let host = request.uri().host().unwrap_or_default().to_string();
+            match open_ws(request, connect_timeout).await {
+                Ok(ws_stream) => {
+                    if index > 0 {
+                        log::warn!("{} connected via fallback endpoint {}", codec.provider_name(), host);
+                    }
+                    return Ok(Self::start(ws_stream, codec));
+                }
+                Err(e) if e.is_unreachable() && index + 1 < count => {
+                    log::warn!("{} endpoint {} is unreachable ({}), trying the next one", codec.provider_name(), host, e);
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    fn start<C: SttMessageCodec>(ws_stream: WsStream, codec: C) -> Self {
+        let provider_name = codec.provider_name();
+        log::info!("{} WebSocket connected", provider_name);
+
+        let (write, read) = ws_stream.split();
+        let receiver_task = tokio::spawn(run_receiver(read, codec));
+
+        Self {
+            provider_name,
+            writer: Arc::new(Mutex::new(write)),
+            receiver_task,
+            keepalive_task: None,
+        }
+    }
+
+    /// Запускает (или перезапускает) периодический keep-alive
+    pub fn start_keepalive(&mut self, keepalive: WsKeepAlive) {
+        if let Some(task) = self.keepalive_task.take() {
+            task.abort();
+        }
+
+        let writer = self.writer.clone();
+        let provider_name = self.provider_name;
+        self.keepalive_task = Some(tokio::spawn(async move {
+            log::debug!("{} keepalive task started", provider_name);
+            loop {
+                tokio::time::sleep(keepalive.interval).await;
+                if keepalive.lifecycle.as_ref().is_some_and(|l| l.is_closed()) {
+                    break;
+                }
+
+                let result = send_to(&writer, (keepalive.message)(), keepalive.send_timeout).await;
+                match result {
+                    Ok(()) => log::trace!("Sent keepalive to {}", provider_name),
+                    Err(e) => {
+                        log::debug!(
+                            "{} keepalive failed, connection closed: {}",
+                            provider_name,
+                            e
+                        );
+                        if let Some(lifecycle) = &keepalive.lifecycle {
+                            lifecycle.force_close();
+                        }
+                        break;
+                    }
+                }
+            }
+            log::debug!("{} keepalive task ended", provider_name);
+        }));
+    }
+
+    pub async fn send(&self, message: Message) -> Result<(), WsTransportError> {
+        send_to(&self.writer, message, None).await
+    }
+
+    pub async fn send_with_timeout(
+        &self,
+        message: Message,
+        timeout: Duration,
+    ) -> Result<(), WsTransportError> {
+        send_to(&self.writer, message, Some(timeout)).await
+    }
+
+    /// Receiver и keep-alive задачи ещё работают
+    pub fn is_alive(&self) -> bool {
+        !self.receiver_task.is_finished()
+            && !matches!(&self.keepalive_task, Some(task) if task.is_finished())
+    }
+
+    /// Корректно закрывает WebSocket (close frame) и останавливает задачи
+    pub async fn close(mut self, timeout: Duration) {
+        let close_fut = async {
+            let mut guard = self.writer.lock().await;
+            guard.close().await
+        };
+        let _ = tokio::time::timeout(timeout, close_fut).await;
+        self.stop_tasks().await;
+    }
+
+    /// Останавливает задачи без close frame — соединение просто бросается
+    pub async fn shutdown(mut self) {
+        self.stop_tasks().await;
+    }
+
+    async fn stop_tasks(&mut self) {
+        if let Some(task) = self.keepalive_task.take() {
+            task.abort();
+            let _ = task.await;
+        }
+        self.receiver_task.abort();
+        let _ = (&mut self.receiver_task).await;
+    }
+}
+
+impl Drop for WsSttTransport {
+    fn drop(&mut self) {
+        // Страховка: брошенный транспорт не должен оставлять живые задачи
+        self.receiver_task.abort();
+        if let Some(task) = &self.keepalive_task {
+            task.abort();
+        }
+    }
+}
+
+/// TCP + TLS + WebSocket handshake (весь путь укладывается в `connect_timeout`)
+async fn open_ws(request: Request<()>, connect_timeout: Option<Duration>) -> Result<WsStream, WsTransportError> {
+    let open = async {
+        let uri = request.uri();
+        let host = uri.host().unwrap_or_default().trim_matches(|c| c == '[' || c == ']').to_string();
+        let port = uri
+            .port_u16()
+            .unwrap_or(if uri.scheme_str() == Some("ws") { 80 } else { 443 });
+
+        let addrs = lookup_host((host.as_str(), port))
+            .await
+            .map_err(|e| WsTransportError::Ws(tungstenite::Error::Io(e)))?;
+        let tcp = connect_happy_eyeballs(interleave_families(addrs.collect()))
+            .await
+            .map_err(|e| WsTransportError::Ws(tungstenite::Error::Io(e)))?;
+        let _ = tcp.set_nodelay(true);
+
+        let (ws_stream, _response) = client_async_tls(request, tcp).await.map_err(WsTransportError::Ws)?;
+        Ok(ws_stream)
+    };
+
+    match connect_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, open)
+            .await
+            .map_err(|_| WsTransportError::Timeout)?,
+        None => open.await,
+    }
+}
+
+/// Порядок попыток по RFC 8305: семейства чередуются, первым идёт семейство первого ответа DNS
+fn interleave_families(addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let prefer_v6 = addrs.first().map(SocketAddr::is_ipv6).unwrap_or(false);
+    let (mut preferred, mut other): (VecDeque<_>, VecDeque<_>) =
+        addrs.into_iter().partition(|addr| addr.is_ipv6() == prefer_v6);
+
+    let mut ordered = Vec::with_capacity(preferred.len() + other.len());
+    loop {
+        match (preferred.pop_front(), other.pop_front()) {
+            (None, None) => break,
+            (first, second) => ordered.extend(first.into_iter().chain(second)),
+        }
+    }
+    ordered
+}
+
+/// Первое успешное TCP-соединение: следующий адрес пробуем через `CONNECTION_ATTEMPT_DELAY`
+/// или сразу после неудачи предыдущего, не отменяя ещё висящие попытки
+async fn connect_happy_eyeballs(addrs: Vec<SocketAddr>) -> std::io::Result<TcpStream> {
+    let mut pending = addrs.into_iter();
+    let mut attempts = JoinSet::new();
+    let mut last_error = None;
+
+    loop {
+        if let Some(addr) = pending.next() {
+            attempts.spawn(async move { TcpStream::connect(addr).await.map_err(|e| (addr, e)) });
+        } else if attempts.is_empty() {
+            return Err(last_error.unwrap_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotFound, "host resolved to no addresses")
+            }));
+        }
+
+        let finished = if !pending.as_slice().is_empty() {
+            match tokio::time::timeout(CONNECTION_ATTEMPT_DELAY, attempts.join_next()).await {
+                Ok(finished) => finished,
+                // Попытка висит — запускаем следующую параллельно
+                Err(_) => continue,
+            }
+        } else {
+            attempts.join_next().await
+        };
+
+        match finished {
+            Some(Ok(Ok(stream))) => return Ok(stream),
+            Some(Ok(Err((addr, e)))) => {
+                log::debug!("TCP connect to {} failed: {}", addr, e);
+                last_error = Some(e);
+            }
+            Some(Err(e)) => last_error = Some(std::io::Error::other(e)),
+            None => {}
+        }
+    }
+}
+
+async fn send_to(
+    writer: &Mutex<WsSink>,
+    message: Message,
+    timeout: Option<Duration>,
+) -> Result<(), WsTransportError> {
+    let send_fut = async {
+        let mut guard = writer.lock().await;
+        guard.send(message).await
+    };
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, send_fut)
+            .await
+            .map_err(|_| WsTransportError::Timeout)?,
+        None => send_fut.await,
+    }
+    .map_err(WsTransportError::Ws)
+}
+
+async fn run_receiver<C: SttMessageCodec>(
+    mut read: futures_util::stream::SplitStream<WsStream>,
+    mut codec: C,
+) {
+    let provider_name = codec.provider_name();
+    log::debug!("{} receiver task started", provider_name);
+
+    while let Some(msg_result) = read.next().await {
+        if !codec.accept_frame().await {
+            log::trace!(
+                "Ignoring message from {} - stream is paused (keep-alive mode)",
+                provider_name
+            );
+            continue;
+        }
+
+        match msg_result {
+            Ok(Message::Text(text)) => {
+                log::debug!("{} received text: {}", provider_name, text);
+                codec.on_text(text).await;
+            }
+            Ok(Message::Close(frame)) => {
+                log::info!("{} WebSocket closed by server: {:?}", provider_name, frame);
+                codec.on_close(frame).await;
+                break;
+            }
+            Ok(Message::Binary(data)) => {
+                log::debug!("{} received binary: {} bytes", provider_name, data.len());
+            }
+            Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {
+                // Pong на Ping отправляет tokio-tungstenite
+                log::trace!("{} received Ping/Pong", provider_name);
+            }
+            Ok(msg) => {
+                log::warn!("{} unexpected message: {:?}", provider_name, msg);
+            }
+            Err(e) => {
+                log::error!("{} WebSocket error: {}", provider_name, e);
+                codec.on_error(e).await;
+                break;
+            }
+        }
+    }
+
+    codec.on_finished().await;
+    log::debug!("{} receiver task ended", provider_name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_alternate_families_starting_with_the_first_answer() {
+        let v6a: SocketAddr = "[2001:db8::1]:443".parse().unwrap();
+        let v6b: SocketAddr = "[2001:db8::2]:443".parse().unwrap();
+        let v4a: SocketAddr = "192.0.2.1:443".parse().unwrap();
+        let v4b: SocketAddr = "192.0.2.2:443".parse().unwrap();
+        let v4c: SocketAddr = "192.0.2.3:443".parse().unwrap();
+
+        assert_eq!(
+            interleave_families(vec![v6a, v6b, v4a, v4b, v4c]),
+            vec![v6a, v4a, v6b, v4b, v4c]
+        );
+        assert_eq!(interleave_families(vec![v4a, v4b, v6a]), vec![v4a, v6a, v4b]);
+        assert!(interleave_families(Vec::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn happy_eyeballs_skips_unreachable_addresses() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let reachable = listener.local_addr().unwrap();
+        // Порт, который только что освободили: соединение туда сразу отклоняется
+        let refused = {
+            let probe = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            probe.local_addr().unwrap()
+        };
+
+        let stream = connect_happy_eyeballs(vec![refused, reachable]).await.unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), reachable);
+        assert!(connect_happy_eyeballs(vec![refused]).await.is_err());
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        assert_eq!(parse_retry_after(" 32 "), Some(32));
+        assert_eq!(parse_retry_after("86400"), Some(MAX_RETRY_AFTER_SECS));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(0));
+
+        let soon = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let secs = parse_retry_after(&soon).unwrap();
+        assert!((88..=90).contains(&secs), "{}", secs);
+
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! Общий словарь команды с нашего бэкенда (`GET /api/v1/team/glossary`).
+//!
+//! Словарь доступен только участникам команды с лицензией. Клиент передаёт известную ревизию
+//! в `If-None-Match`: если словарь не менялся, бэкенд отвечает 304 и тело не качаем.
+
+use serde::Deserialize;
+
+use crate::domain::TeamGlossary;
+
+const GLOSSARY_REQUEST_TIMEOUT_SECS: u64 = 15;
+
+/// Errors of the team glossary backend
+#[derive(Debug, thiserror::Error)]
+pub enum GlossaryError {
+    #[error("Not authenticated")]
+    Unauthenticated,
+
+    /// Пользователь не в команде или у команды нет лицензии
+    #[error("Team glossary is not available for this account")]
+    NotAvailable,
+
+    #[error("Glossary request failed: {0}")]
+    Request(String),
+}
+
+/// envelope: { data: { revision, terms: [...] } }
+#[derive(Deserialize)]
+struct GlossaryResponse {
+    data: GlossaryResponseData,
+}
+
+#[derive(Deserialize)]
+struct GlossaryResponseData {
+    revision: u64,
+    #[serde(default)]
+    terms: Vec<String>,
+}
+
+/// Client of `/api/v1/team/glossary`
+pub struct BackendGlossaryClient {
+    api_base_url: String,
+    access_token: String,
+}
+
+impl BackendGlossaryClient {
+    pub fn new(api_base_url: impl Into<String>, access_token: impl Into<String>) -> Self {
+        Self {
+            api_base_url: api_base_url.into(),
+            access_token: access_token.into(),
+        }
+    }
+
+    /// Словарь команды; None — с ревизии `known_revision` ничего не изменилось
+    pub async fn fetch(&self, known_revision: Option<u64>) -> Result<Option<TeamGlossary>, GlossaryError> {
+        let mut request = reqwest::Client::new()
+            .get(format!("{}/api/v1/team/glossary", self.api_base_url))
+            .timeout(std::time::Duration::from_secs(GLOSSARY_REQUEST_TIMEOUT_SECS))
+            .bearer_auth(&self.access_token)
+            .header("X-Client-Type", "native");
+        if let Some(revision) = known_revision {
+            request = request.header(reqwest::header::IF_NONE_MATCH, format!("\"{}\"", revision));
+        }
+        let response = request.send().await.map_err(|e| GlossaryError::Request(e.to_string()))?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_MODIFIED => return Ok(None),
+            reqwest::StatusCode::UNAUTHORIZED => return Err(GlossaryError::Unauthenticated),
+            reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::NOT_FOUND => {
+                return Err(GlossaryError::NotAvailable)
+            }
+            status if !status.is_success() => return Err(GlossaryError::Request(format!("HTTP {}", status))),
+            _ => {}
+        }
+
+        let body: GlossaryResponse = response
+            .json()
+            .await
+            .map_err(|e| GlossaryError::Request(format!("invalid response: {}", e)))?;
+        Ok(Some(TeamGlossary {
+            revision: body.data.revision,
+            terms: body.data.terms,
+            synced_at_ms: chrono::Utc::now().timestamp_millis(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_without_terms_is_an_empty_glossary() {
+        let body: GlossaryResponse = serde_json::from_str(r#"{"data":{"revision":7}}"#).unwrap();
+        assert_eq!(body.data.revision, 7);
+        assert!(body.data.terms.is_empty());
+    }
+}
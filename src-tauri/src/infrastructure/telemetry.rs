@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::domain::TelemetryEvent;
+
+const TELEMETRY_TIMEOUT_SECS: u64 = 15;
+
+/// Тело запроса телеметрии — ровно то, что уходит на сервер (его же показывает предпросмотр).
+/// Ни device_id, ни токена авторизации: события нельзя связать с пользователем.
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryBatch {
+    pub app_version: &'static str,
+    pub platform: &'static str,
+    pub events: Vec<TelemetryEvent>,
+}
+
+impl TelemetryBatch {
+    pub fn new(events: Vec<TelemetryEvent>) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION"),
+            platform: std::env::consts::OS,
+            events,
+        }
+    }
+}
+
+/// Отправляет пачку анонимных событий на backend
+pub async fn send_telemetry_batch(api_base_url: &str, batch: &TelemetryBatch) -> Result<()> {
+    let url = format!("{}/api/v1/telemetry/events", api_base_url);
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(Duration::from_secs(TELEMETRY_TIMEOUT_SECS))
+        .json(batch)
+        .send()
+        .await
+        .context("Failed to send telemetry")?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Telemetry endpoint returned HTTP {}", status);
+    }
+    Ok(())
+}
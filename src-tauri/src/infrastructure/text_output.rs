@@ -0,0 +1,297 @@
+//! Реализации sinks для доставки финального текста (clipboard, вставка, файл, webhook).
+
+use async_trait::async_trait;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    AppConfig, ClipboardFormat, TextDelivery, TextOutputError, TextOutputResult, TextOutputSink, TextOutputSinkConfig,
+};
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 5;
+
+/// Общий контекст для sinks, которым нужно состояние приложения
+#[derive(Clone, Default)]
+pub struct TextOutputContext {
+    /// Приложение, которое было активно до показа окна записи (macOS bundle ID)
+    pub last_focused_app_bundle_id: Arc<RwLock<Option<String>>>,
+    /// Настройки приложения (стратегия вставки и правила по приложениям)
+    pub config: Arc<RwLock<AppConfig>>,
+}
+
+/// Собирает sinks по конфигурации активного профиля
+pub fn create_text_output_sinks(
+    configs: &[TextOutputSinkConfig],
+    context: &TextOutputContext,
+) -> Vec<Arc<dyn TextOutputSink>> {
+    configs
+        .iter()
+        .map(|config| -> Arc<dyn TextOutputSink> {
+            match config {
+                TextOutputSinkConfig::Clipboard { format } => Arc::new(ClipboardSink::with_format(*format)),
+                TextOutputSinkConfig::AutoPaste { .. } => Arc::new(AutoPasteSink {
+                    config: config.clone(),
+                    last_focused_app_bundle_id: context.last_focused_app_bundle_id.clone(),
+                    app_config: context.config.clone(),
+                }),
+                TextOutputSinkConfig::Typing => Arc::new(TypingSink {
+                    config: config.clone(),
+                    app_config: context.config.clone(),
+                }),
+                TextOutputSinkConfig::FileAppend { path } => Arc::new(FileAppendSink {
+                    config: config.clone(),
+                    path: PathBuf::from(path),
+                }),
+                TextOutputSinkConfig::Webhook { url } => Arc::new(WebhookSink {
+                    config: config.clone(),
+                    url: url.clone(),
+                    client: reqwest::Client::new(),
+                }),
+            }
+        })
+        .collect()
+}
+
+async fn run_blocking<F>(f: F) -> TextOutputResult<()>
+where
+    F: FnOnce() -> anyhow::Result<()> + Send + 'static,
+{
+    // clipboard/enigo работают с синхронными нативными API
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| TextOutputError::Delivery(format!("Failed to join blocking task: {}", e)))?
+        .map_err(|e| TextOutputError::Delivery(e.to_string()))
+}
+
+#[cfg(target_os = "macos")]
+fn ensure_accessibility() -> TextOutputResult<()> {
+    if !crate::infrastructure::auto_paste::check_accessibility_permission() {
+        return Err(TextOutputError::PermissionDenied(
+            "Accessibility permission not granted. Please enable it in System Settings > Privacy & Security > Accessibility".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn ensure_accessibility() -> TextOutputResult<()> {
+    Ok(())
+}
+
+/// Копирует весь накопленный текст сессии в clipboard
+pub struct ClipboardSink {
+    config: TextOutputSinkConfig,
+    format: ClipboardFormat,
+}
+
+impl ClipboardSink {
+    pub fn new() -> Self {
+        Self::with_format(ClipboardFormat::PlainText)
+    }
+
+    /// Sink, который кроме plain text пишет HTML-вариант в выбранном формате
+    pub fn with_format(format: ClipboardFormat) -> Self {
+        Self {
+            config: TextOutputSinkConfig::Clipboard { format },
+            format,
+        }
+    }
+}
+
+impl Default for ClipboardSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TextOutputSink for ClipboardSink {
+    fn config(&self) -> &TextOutputSinkConfig {
+        &self.config
+    }
+
+    async fn deliver(&self, delivery: &TextDelivery) -> TextOutputResult<()> {
+        let text = delivery.full_text.clone();
+        match self.format.html(&text) {
+            Some(html) => run_blocking(move || crate::infrastructure::copy_rich_to_clipboard(&text, &html)).await,
+            None => run_blocking(move || crate::infrastructure::copy_to_clipboard(&text)).await,
+        }
+    }
+}
+
+/// Возвращает фокус в последнее активное приложение и печатает фразу
+pub struct AutoPasteSink {
+    config: TextOutputSinkConfig,
+    last_focused_app_bundle_id: Arc<RwLock<Option<String>>>,
+    app_config: Arc<RwLock<AppConfig>>,
+}
+
+#[async_trait]
+impl TextOutputSink for AutoPasteSink {
+    fn config(&self) -> &TextOutputSinkConfig {
+        &self.config
+    }
+
+    async fn deliver(&self, delivery: &TextDelivery) -> TextOutputResult<()> {
+        ensure_accessibility()?;
+
+        let last_bundle_id = self.last_focused_app_bundle_id.read().await.clone();
+        let strategy = self.app_config.read().await.paste_strategy_for_app(last_bundle_id.as_deref());
+        if let Some(bundle_id) = last_bundle_id {
+            log::info!("Attempting to activate last focused app: {}", bundle_id);
+
+            match crate::infrastructure::auto_paste::activate_app_by_bundle_id(&bundle_id) {
+                Ok(_) => {
+                    // Даем время окну активироваться
+                    tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
+                }
+                Err(e) => {
+                    // Не критично - просто вставим в текущее активное окно
+                    log::warn!("Failed to activate app '{}': {}. Pasting to active window", bundle_id, e);
+                    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+                }
+            }
+        } else {
+            log::info!("No saved window - pasting to currently active window");
+        }
+
+        let text = delivery.text.clone();
+        run_blocking(move || crate::infrastructure::auto_paste::paste_text_with_strategy(&text, strategy)).await
+    }
+}
+
+/// Печатает фразу в текущую позицию курсора без переключения приложений
+pub struct TypingSink {
+    config: TextOutputSinkConfig,
+    app_config: Arc<RwLock<AppConfig>>,
+}
+
+#[async_trait]
+impl TextOutputSink for TypingSink {
+    fn config(&self) -> &TextOutputSinkConfig {
+        &self.config
+    }
+
+    async fn deliver(&self, delivery: &TextDelivery) -> TextOutputResult<()> {
+        ensure_accessibility()?;
+        let active_app = crate::infrastructure::auto_paste::get_active_app_bundle_id();
+        let strategy = self.app_config.read().await.paste_strategy_for_app(active_app.as_deref());
+        let text = delivery.text.clone();
+        run_blocking(move || crate::infrastructure::auto_paste::paste_text_with_strategy(&text, strategy)).await
+    }
+}
+
+/// Дописывает каждую фразу отдельной строкой в файл
+pub struct FileAppendSink {
+    config: TextOutputSinkConfig,
+    path: PathBuf,
+}
+
+#[async_trait]
+impl TextOutputSink for FileAppendSink {
+    fn config(&self) -> &TextOutputSinkConfig {
+        &self.config
+    }
+
+    async fn deliver(&self, delivery: &TextDelivery) -> TextOutputResult<()> {
+        let line = delivery.text.trim().to_string();
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+            writeln!(file, "{}", line)
+        })
+        .await
+        .map_err(|e| TextOutputError::Delivery(format!("Failed to join blocking task: {}", e)))?
+        .map_err(|e| TextOutputError::Unavailable(format!("{}: {}", self.path.display(), e)))
+    }
+}
+
+/// Отправляет текст POST-запросом (JSON) на внешний URL
+pub struct WebhookSink {
+    config: TextOutputSinkConfig,
+    url: String,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl TextOutputSink for WebhookSink {
+    fn config(&self) -> &TextOutputSinkConfig {
+        &self.config
+    }
+
+    async fn deliver(&self, delivery: &TextDelivery) -> TextOutputResult<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .timeout(std::time::Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+            .json(&serde_json::json!({
+                "text": delivery.text.trim(),
+                "full_text": delivery.full_text,
+                "session_id": delivery.session_id,
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+            }))
+            .send()
+            .await
+            .map_err(|e| TextOutputError::Unavailable(format!("Webhook request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(TextOutputError::Delivery(format!(
+                "Webhook returned HTTP {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_append_sink_writes_one_line_per_phrase() {
+        let dir = std::env::temp_dir().join(format!("vtt-text-output-{}", std::process::id()));
+        let path = dir.join("nested").join("notes.txt");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let sinks = create_text_output_sinks(
+            &[TextOutputSinkConfig::FileAppend {
+                path: path.to_string_lossy().to_string(),
+            }],
+            &TextOutputContext::default(),
+        );
+        assert_eq!(sinks.len(), 1);
+
+        sinks[0].deliver(&TextDelivery::new("первая фраза")).await.unwrap();
+        sinks[0].deliver(&TextDelivery::new(" вторая фраза")).await.unwrap();
+        sinks[0].deliver(&TextDelivery::new("   ")).await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "первая фраза\nвторая фраза\n");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn creates_sink_per_config_in_order() {
+        let configs = vec![
+            TextOutputSinkConfig::Typing,
+            TextOutputSinkConfig::Webhook {
+                url: "http://localhost:1/hook".to_string(),
+            },
+            TextOutputSinkConfig::plain_clipboard(),
+        ];
+        let sinks = create_text_output_sinks(&configs, &TextOutputContext::default());
+        let built: Vec<TextOutputSinkConfig> = sinks.iter().map(|s| s.config().clone()).collect();
+        assert_eq!(built, configs);
+    }
+}
@@ -0,0 +1,135 @@
+//! Границы тем встречи от нашего бэкенда (LLM).
+//!
+//! Отправляем только текст фраз с номерами (и говорящих, если есть) — без аудио и таймингов.
+//! Бэкенд отвечает индексами фраз, с которых начинается новая тема, и короткими названиями.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{MeetingUtterance, TopicAdvisorError, TopicAdvisorResult, TopicBreak, TopicBreakAdvisor};
+
+/// LLM отвечает дольше обычных запросов; встреча уже закончилась, так что можно подождать
+const TOPIC_BREAKS_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Serialize)]
+struct TopicBreaksRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<&'a str>,
+    utterances: Vec<TopicBreaksUtterance<'a>>,
+}
+
+#[derive(Serialize)]
+struct TopicBreaksUtterance<'a> {
+    index: usize,
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speaker: Option<u32>,
+}
+
+/// envelope: { data: { breaks: [...] } }
+#[derive(Deserialize)]
+struct TopicBreaksResponse {
+    data: TopicBreaksResponseData,
+}
+
+#[derive(Deserialize)]
+struct TopicBreaksResponseData {
+    #[serde(default)]
+    breaks: Vec<TopicBreak>,
+}
+
+/// Topic break advisor backed by `POST /api/v1/llm/topic-breaks`
+pub struct BackendTopicAdvisor {
+    api_base_url: String,
+    access_token: Option<String>,
+}
+
+impl BackendTopicAdvisor {
+    pub fn new(api_base_url: impl Into<String>, access_token: Option<String>) -> Self {
+        Self {
+            api_base_url: api_base_url.into(),
+            access_token,
+        }
+    }
+}
+
+/// Оставляет осмысленные границы: в пределах списка фраз, без дублей, по возрастанию
+fn sanitize_breaks(mut breaks: Vec<TopicBreak>, utterance_count: usize) -> Vec<TopicBreak> {
+    breaks.retain(|b| b.utterance_index < utterance_count);
+    breaks.sort_by_key(|b| b.utterance_index);
+    breaks.dedup_by_key(|b| b.utterance_index);
+    breaks
+}
+
+#[async_trait]
+impl TopicBreakAdvisor for BackendTopicAdvisor {
+    async fn suggest_breaks(
+        &self,
+        utterances: &[MeetingUtterance],
+        language: Option<&str>,
+    ) -> TopicAdvisorResult<Vec<TopicBreak>> {
+        let Some(access_token) = self.access_token.as_deref() else {
+            return Err(TopicAdvisorError::Unauthenticated);
+        };
+        if utterances.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let request = TopicBreaksRequest {
+            language,
+            utterances: utterances
+                .iter()
+                .enumerate()
+                .map(|(index, u)| TopicBreaksUtterance {
+                    index,
+                    text: &u.text,
+                    speaker: u.speaker,
+                })
+                .collect(),
+        };
+
+        let url = format!("{}/api/v1/llm/topic-breaks", self.api_base_url);
+        let response = reqwest::Client::new()
+            .post(url)
+            .timeout(std::time::Duration::from_secs(TOPIC_BREAKS_TIMEOUT_SECS))
+            .bearer_auth(access_token)
+            .header("X-Client-Type", "native")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| TopicAdvisorError::Request(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(TopicAdvisorError::Unauthenticated);
+        }
+        if !response.status().is_success() {
+            return Err(TopicAdvisorError::Request(format!("HTTP {}", response.status())));
+        }
+
+        let body: TopicBreaksResponse = response
+            .json()
+            .await
+            .map_err(|e| TopicAdvisorError::Request(format!("invalid response: {}", e)))?;
+        Ok(sanitize_breaks(body.data.breaks, utterances.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_envelope_and_drops_out_of_range_breaks() {
+        let json = r#"{"data":{"breaks":[
+            {"utterance_index":7,"title":"Найм"},
+            {"utterance_index":2},
+            {"utterance_index":40,"title":"Лишняя"},
+            {"utterance_index":2,"title":"Дубль"}
+        ]}}"#;
+        let body: TopicBreaksResponse = serde_json::from_str(json).unwrap();
+        let breaks = sanitize_breaks(body.data.breaks, 10);
+        assert_eq!(breaks.len(), 2);
+        assert_eq!(breaks[0].utterance_index, 2);
+        assert_eq!(breaks[1].title.as_deref(), Some("Найм"));
+    }
+}
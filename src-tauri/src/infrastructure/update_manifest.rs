@@ -0,0 +1,184 @@
+//! Разбор манифеста обновлений (latest.json) и подписи артефакта до скачивания.
+//!
+//! Помимо стандартного `platforms` манифест может содержать частичные (delta) артефакты
+//! для конкретной исходной версии:
+//!
+//! ```json
+//! "deltas": {
+//!   "0.9.1": {
+//!     "darwin-aarch64-app": { "url": "...", "signature": "...", "size": 4194304 }
+//!   }
+//! }
+//! ```
+//!
+//! Ключ платформы тот же, что у полного артефакта в `platforms`. Delta-артефакт подписан тем же
+//! ключом и проверяется updater'ом при скачивании так же, как полный.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::Value;
+
+/// Артефакт обновления из манифеста
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateArtifact {
+    pub url: String,
+    pub signature: String,
+    pub size: Option<u64>,
+    /// Частичный артефакт для текущей версии (delta)
+    pub is_delta: bool,
+}
+
+/// Состояние подписи артефакта
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateSignatureStatus {
+    /// Артефакт скачан и подпись проверена
+    Verified,
+    /// Подпись сделана ключом приложения; содержимое проверяется при скачивании
+    TrustedKey,
+    /// Подпись сделана другим ключом — установка не пройдёт
+    KeyMismatch,
+    Missing,
+    Malformed,
+}
+
+fn artifact_from(entry: &Value, is_delta: bool) -> Option<UpdateArtifact> {
+    Some(UpdateArtifact {
+        url: entry.get("url")?.as_str()?.to_string(),
+        signature: entry.get("signature")?.as_str()?.to_string(),
+        size: entry.get("size").and_then(Value::as_u64),
+        is_delta,
+    })
+}
+
+/// Ключ платформы, полный артефакт которой скачивается по `download_url`
+fn platform_key<'a>(manifest: &'a Value, download_url: &str) -> Option<&'a str> {
+    manifest
+        .get("platforms")?
+        .as_object()?
+        .iter()
+        .find(|(_, entry)| entry.get("url").and_then(Value::as_str) == Some(download_url))
+        .map(|(key, _)| key.as_str())
+}
+
+/// Delta-артефакт для обновления с `current_version`; `download_url` — полный артефакт платформы
+pub fn delta_artifact(manifest: &Value, current_version: &str, download_url: &str) -> Option<UpdateArtifact> {
+    let key = platform_key(manifest, download_url)?;
+    artifact_from(manifest.get("deltas")?.get(current_version)?.get(key)?, true)
+}
+
+/// Находит артефакт по URL (полный или delta) — чтобы показать размер и тип перед установкой
+pub fn resolve_artifact(manifest: &Value, current_version: &str, download_url: &str) -> Option<UpdateArtifact> {
+    let full = manifest
+        .get("platforms")
+        .and_then(Value::as_object)
+        .and_then(|platforms| {
+            platforms
+                .values()
+                .find(|entry| entry.get("url").and_then(Value::as_str) == Some(download_url))
+        })
+        .and_then(|entry| artifact_from(entry, false));
+    if full.is_some() {
+        return full;
+    }
+
+    manifest
+        .get("deltas")?
+        .get(current_version)?
+        .as_object()?
+        .values()
+        .find(|entry| entry.get("url").and_then(Value::as_str) == Some(download_url))
+        .and_then(|entry| artifact_from(entry, true))
+}
+
+/// Key id из minisign-ключа/подписи (base64 от текстового файла minisign).
+/// Вторая строка файла — base64: 2 байта алгоритма + 8 байт key id + ...
+fn minisign_key_id(encoded: &str) -> Option<[u8; 8]> {
+    let text = String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let payload = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("untrusted comment:"))?;
+    let bytes = STANDARD.decode(payload).ok()?;
+    bytes.get(2..10)?.try_into().ok()
+}
+
+/// Проверяет подпись артефакта до скачивания: есть ли она и сделана ли ключом приложения
+pub fn signature_status(pubkey: Option<&str>, signature: &str) -> UpdateSignatureStatus {
+    if signature.trim().is_empty() {
+        return UpdateSignatureStatus::Missing;
+    }
+    let Some(signature_key) = minisign_key_id(signature) else {
+        return UpdateSignatureStatus::Malformed;
+    };
+    match pubkey.and_then(minisign_key_id) {
+        Some(key) if key == signature_key => UpdateSignatureStatus::TrustedKey,
+        _ => UpdateSignatureStatus::KeyMismatch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Публичный ключ из tauri.conf.json
+    const PUBKEY: &str = "dW50cnVzdGVkIGNvbW1lbnQ6IG1pbmlzaWduIHB1YmxpYyBrZXk6IDYyMTRFM0ZGQkExNzBBRDkKUldUWkNoZTYvK01VWXF2bzl0bm4xbnlRQU5wMEhmdDloNmNvUk1Sb0sxUnRUY0kzVStpT2o2cmoK";
+
+    fn signature_with_key(key_id: [u8; 8]) -> String {
+        let mut sig = b"ED".to_vec();
+        sig.extend_from_slice(&key_id);
+        sig.extend_from_slice(&[0u8; 64]);
+        let text = format!(
+            "untrusted comment: signature from tauri secret key\n{}\ntrusted comment: timestamp:0\n{}\n",
+            STANDARD.encode(&sig),
+            STANDARD.encode([0u8; 64])
+        );
+        STANDARD.encode(text)
+    }
+
+    fn manifest() -> Value {
+        serde_json::json!({
+            "version": "1.0.0",
+            "platforms": {
+                "darwin-aarch64": { "url": "https://example.com/full.tar.gz", "signature": "s1", "size": 80_000_000u64 }
+            },
+            "deltas": {
+                "0.9.1": {
+                    "darwin-aarch64": { "url": "https://example.com/delta.tar.gz", "signature": "s2", "size": 4_000_000u64 }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn picks_delta_only_for_matching_source_version() {
+        let manifest = manifest();
+        let delta = delta_artifact(&manifest, "0.9.1", "https://example.com/full.tar.gz").unwrap();
+        assert_eq!(delta.url, "https://example.com/delta.tar.gz");
+        assert_eq!(delta.size, Some(4_000_000));
+        assert!(delta.is_delta);
+
+        assert!(delta_artifact(&manifest, "0.8.0", "https://example.com/full.tar.gz").is_none());
+
+        let full = resolve_artifact(&manifest, "0.8.0", "https://example.com/full.tar.gz").unwrap();
+        assert!(!full.is_delta);
+        assert_eq!(full.size, Some(80_000_000));
+        assert!(resolve_artifact(&manifest, "0.9.1", "https://example.com/delta.tar.gz").unwrap().is_delta);
+    }
+
+    #[test]
+    fn checks_signature_key_against_app_pubkey() {
+        let app_key = minisign_key_id(PUBKEY).unwrap();
+        assert_eq!(app_key, [0xD9, 0x0A, 0x17, 0xBA, 0xFF, 0xE3, 0x14, 0x62]);
+
+        assert_eq!(
+            signature_status(Some(PUBKEY), &signature_with_key(app_key)),
+            UpdateSignatureStatus::TrustedKey
+        );
+        assert_eq!(
+            signature_status(Some(PUBKEY), &signature_with_key([1; 8])),
+            UpdateSignatureStatus::KeyMismatch
+        );
+        assert_eq!(signature_status(Some(PUBKEY), " "), UpdateSignatureStatus::Missing);
+        assert_eq!(signature_status(Some(PUBKEY), "not-base64!"), UpdateSignatureStatus::Malformed);
+    }
+}
@@ -4,10 +4,20 @@ use std::{
     time::Duration,
 };
 
-use tauri::{AppHandle, Emitter, Runtime};
-use tauri_plugin_updater::UpdaterExt;
-#[cfg(target_os = "windows")]
+use tauri::{AppHandle, Emitter, Runtime, Url};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
 use super::config_store::ConfigStore;
+use super::update_manifest::{delta_artifact, resolve_artifact, signature_status, UpdateSignatureStatus};
+use crate::domain::{UpdateChannel, UpdatePreferences};
+
+/// Фид beta-канала. Stable берёт endpoints из tauri.conf.json (releases/latest/download/latest.json),
+/// а GitHub "latest" никогда не указывает на pre-release — поэтому beta публикуется под отдельным тегом.
+const BETA_UPDATE_ENDPOINT: &str =
+    "https://github.com/777genius/voice-to-text/releases/download/beta/latest.json";
+
+/// "Напомнить позже" откладывает фоновое уведомление на сутки
+const REMIND_LATER_MS: i64 = 24 * 3600 * 1000;
 
 /// Защита от двойного старта установки.
 ///
@@ -16,11 +26,38 @@ use super::config_store::ConfigStore;
 /// глобальный lock на процесс.
 static INSTALL_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
 
+/// Скачанное, но ещё не установленное обновление (отложенная установка).
+/// Ставится при следующем install_update или при выходе из приложения.
+static DOWNLOADED_UPDATE: Mutex<Option<DownloadedUpdate>> = Mutex::new(None);
+
+struct DownloadedUpdate {
+    update: Update,
+    bytes: Vec<u8>,
+}
+
 /// Информация о доступном обновлении, которую отдаём во frontend.
 #[derive(Clone, serde::Serialize)]
 pub struct UpdateInfo {
     pub version: String,
     pub body: String,
+    pub channel: UpdateChannel,
+    /// Обновление уже скачано и ждёт установки
+    pub downloaded: bool,
+}
+
+/// Подробности обновления для диалога перед установкой
+#[derive(Clone, serde::Serialize)]
+pub struct UpdateDetails {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    pub release_notes: String,
+    /// Размер скачивания в байтах (None — сервер не сообщил)
+    pub size: Option<u64>,
+    /// Скачивается частичный (delta) артефакт вместо полного
+    pub is_delta: bool,
+    pub signature: UpdateSignatureStatus,
+    pub downloaded: bool,
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -47,10 +84,15 @@ pub fn start_background_update_check<R: Runtime>(app: AppHandle<R>) {
 
             match check_for_update(app.clone()).await {
                 Ok(Some(update)) => {
-                    log::info!("Update available: {}", update.version);
-                    // Уведомляем frontend о доступном обновлении
-                    if let Err(e) = app.emit("update:available", update) {
-                        log::error!("Failed to emit update event: {}", e);
+                    let prefs = load_preferences().await;
+                    if prefs.should_notify(&update.version, chrono::Utc::now().timestamp_millis()) {
+                        log::info!("Update available: {}", update.version);
+                        // Уведомляем frontend о доступном обновлении
+                        if let Err(e) = app.emit("update:available", update) {
+                            log::error!("Failed to emit update event: {}", e);
+                        }
+                    } else {
+                        log::info!("Update {} available, notification postponed or skipped", update.version);
                     }
                 }
                 Ok(None) => {
@@ -67,39 +109,359 @@ pub fn start_background_update_check<R: Runtime>(app: AppHandle<R>) {
     });
 }
 
+async fn load_preferences() -> UpdatePreferences {
+    ConfigStore::load_update_preferences().await.unwrap_or_else(|e| {
+        log::warn!("Failed to load update preferences: {}. Using defaults.", e);
+        UpdatePreferences::default()
+    })
+}
+
+/// Endpoint канала; None — endpoints из tauri.conf.json
+fn channel_endpoint(channel: UpdateChannel) -> Option<&'static str> {
+    match channel {
+        UpdateChannel::Stable => None,
+        UpdateChannel::Beta => Some(BETA_UPDATE_ENDPOINT),
+    }
+}
+
+fn build_updater<R: Runtime>(app: &AppHandle<R>, channel: UpdateChannel) -> Result<Updater, String> {
+    let mut builder = app.updater_builder();
+    if let Some(endpoint) = channel_endpoint(channel) {
+        let url = Url::parse(endpoint).map_err(|e| format!("Invalid update endpoint: {}", e))?;
+        builder = builder
+            .endpoints(vec![url])
+            .map_err(|e| format!("Failed to set update endpoint: {}", e))?;
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build updater: {}", e))
+}
+
+/// Ищет обновление в выбранном канале
+async fn find_update<R: Runtime>(app: &AppHandle<R>) -> Result<(Option<Update>, UpdateChannel), String> {
+    let channel = load_preferences().await.channel;
+    let updater = build_updater(app, channel)?;
+    let mut update = updater.check().await.map_err(|e| {
+        log::error!("Update check failed: {}", e);
+        format!("Update check failed: {}", e)
+    })?;
+    if let Some(update) = update.as_mut() {
+        use_delta_artifact(update);
+    }
+    Ok((update, channel))
+}
+
+/// Если в манифесте есть delta-артефакт для текущей версии — качаем его вместо полного
+fn use_delta_artifact(update: &mut Update) {
+    let download_url = update.download_url.to_string();
+    let Some(delta) = delta_artifact(&update.raw_json, &update.current_version, &download_url) else {
+        return;
+    };
+    match Url::parse(&delta.url) {
+        Ok(url) => {
+            log::info!(
+                "Using delta update artifact {} -> {} (size: {:?})",
+                update.current_version,
+                update.version,
+                delta.size
+            );
+            update.download_url = url;
+            update.signature = delta.signature;
+        }
+        Err(e) => log::warn!("Ignoring delta artifact with invalid url: {}", e),
+    }
+}
+
+/// Публичный ключ updater'а из tauri.conf.json
+fn updater_pubkey<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    app.config()
+        .plugins
+        .0
+        .get("updater")?
+        .get("pubkey")?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// Размер артефакта по Content-Length (когда в манифесте нет size)
+async fn fetch_artifact_size(url: &Url) -> Option<u64> {
+    let response = reqwest::Client::new()
+        .head(url.clone())
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.content_length().filter(|len| *len > 0)
+}
+
+fn is_downloaded(version: &str) -> bool {
+    DOWNLOADED_UPDATE
+        .lock()
+        .map(|pending| pending.as_ref().is_some_and(|d| d.update.version == version))
+        .unwrap_or(false)
+}
+
 /// Проверяет наличие обновлений (без установки)
 /// Возвращает версию если доступна, None если обновлений нет
 pub async fn check_for_update<R: Runtime>(
     app: AppHandle<R>,
 ) -> Result<Option<UpdateInfo>, String> {
-    let updater = app
-        .updater_builder()
-        .build()
-        .map_err(|e| format!("Failed to build updater: {}", e))?;
-
-    match updater.check().await {
-        Ok(Some(update)) => {
+    match find_update(&app).await? {
+        (Some(update), channel) => {
             log::info!(
-                "Update found: {} (current: {})",
+                "Update found: {} (current: {}, channel: {:?})",
                 update.version,
-                update.current_version
+                update.current_version,
+                channel
             );
             Ok(Some(UpdateInfo {
                 version: update.version.clone(),
                 body: update.body.clone().unwrap_or_default(),
+                channel,
+                downloaded: is_downloaded(&update.version),
             }))
         }
-        Ok(None) => {
+        (None, _) => {
             log::info!("App is up to date");
             Ok(None)
         }
-        Err(e) => {
-            log::error!("Update check failed: {}", e);
-            Err(format!("Update check failed: {}", e))
+    }
+}
+
+/// Детали обновления перед установкой: что и сколько будем качать, чем подписано
+pub async fn get_update_details<R: Runtime>(app: AppHandle<R>) -> Result<Option<UpdateDetails>, String> {
+    let (update, channel) = match find_update(&app).await? {
+        (Some(update), channel) => (update, channel),
+        (None, _) => return Ok(None),
+    };
+
+    let download_url = update.download_url.to_string();
+    let artifact = resolve_artifact(&update.raw_json, &update.current_version, &download_url);
+    let is_delta = artifact.as_ref().is_some_and(|a| a.is_delta);
+
+    let downloaded_size = DOWNLOADED_UPDATE.lock().ok().and_then(|pending| {
+        pending
+            .as_ref()
+            .filter(|d| d.update.version == update.version)
+            .map(|d| d.bytes.len() as u64)
+    });
+
+    let (size, signature) = match downloaded_size {
+        // download() уже проверил подпись содержимого
+        Some(size) => (Some(size), UpdateSignatureStatus::Verified),
+        None => {
+            let size = match artifact.and_then(|a| a.size) {
+                Some(size) => Some(size),
+                None => fetch_artifact_size(&update.download_url).await,
+            };
+            let pubkey = updater_pubkey(&app);
+            (size, signature_status(pubkey.as_deref(), &update.signature))
+        }
+    };
+
+    Ok(Some(UpdateDetails {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        channel,
+        release_notes: update.body.clone().unwrap_or_default(),
+        size,
+        is_delta,
+        signature,
+        downloaded: downloaded_size.is_some(),
+    }))
+}
+
+/// Колбэк прогресса скачивания → событие update:download-progress
+fn progress_reporter<R: Runtime>(app: AppHandle<R>, version: String) -> impl FnMut(usize, Option<u64>) {
+    let downloaded_total = Arc::new(Mutex::new(0u64));
+
+    move |chunk_length, content_length| {
+        let chunk_length = chunk_length as u64;
+
+        // В зависимости от платформы/реализации `chunk_length` может быть:
+        // - либо "сколько скачано всего"
+        // - либо "размер последнего чанка"
+        // Поэтому используем простую эвристику, чтобы корректно считать прогресс.
+        let mut downloaded_total = downloaded_total
+            .lock()
+            .expect("update downloaded_total mutex poisoned");
+
+        let previous = *downloaded_total;
+        let downloaded = if let Some(total) = content_length {
+            if chunk_length <= total && chunk_length >= previous {
+                chunk_length
+            } else {
+                previous.saturating_add(chunk_length)
+            }
+        } else {
+            previous.saturating_add(chunk_length)
+        };
+
+        *downloaded_total = downloaded;
+
+        let progress = content_length.and_then(|total| {
+            if total == 0 {
+                return Some(0);
+            }
+            let pct = ((*downloaded_total as f64 / total as f64) * 100.0)
+                .clamp(0.0, 100.0) as u8;
+            Some(pct)
+        });
+
+        let _ = app.emit(
+            "update:download-progress",
+            UpdateDownloadProgressPayload {
+                version: version.clone(),
+                downloaded: *downloaded_total,
+                total: content_length,
+                progress,
+            },
+        );
+    }
+}
+
+/// Скачивает обновление без установки (прогресс — событиями update:download-*).
+/// Установка позже: install_update или при выходе из приложения.
+pub async fn download_update<R: Runtime>(app: AppHandle<R>) -> Result<Option<UpdateInfo>, String> {
+    if INSTALL_IN_PROGRESS
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err("Update installation is already in progress".to_string());
+    }
+
+    let result = download_update_inner(&app).await;
+    INSTALL_IN_PROGRESS.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn download_update_inner<R: Runtime>(app: &AppHandle<R>) -> Result<Option<UpdateInfo>, String> {
+    let (update, channel) = match find_update(app).await? {
+        (Some(update), channel) => (update, channel),
+        (None, _) => {
+            log::info!("App is already up to date");
+            return Ok(None);
+        }
+    };
+
+    let info = UpdateInfo {
+        version: update.version.clone(),
+        body: update.body.clone().unwrap_or_default(),
+        channel,
+        downloaded: true,
+    };
+
+    if is_downloaded(&update.version) {
+        log::info!("Update {} is already downloaded", update.version);
+        return Ok(Some(info));
+    }
+
+    log::info!("Downloading update {} -> {}", update.current_version, update.version);
+    let _ = app.emit(
+        "update:download-started",
+        UpdateInstallStagePayload {
+            version: update.version.clone(),
+        },
+    );
+
+    let bytes = update
+        .download(progress_reporter(app.clone(), update.version.clone()), || {
+            log::info!("Update download completed");
+        })
+        .await
+        .map_err(|e| format!("Failed to download update: {}", e))?;
+
+    let _ = app.emit(
+        "update:downloaded",
+        UpdateInstallStagePayload {
+            version: update.version.clone(),
+        },
+    );
+    *DOWNLOADED_UPDATE
+        .lock()
+        .map_err(|_| "Downloaded update lock poisoned".to_string())? = Some(DownloadedUpdate { update, bytes });
+
+    Ok(Some(info))
+}
+
+/// Устанавливает ранее скачанное обновление (без перезапуска). false — скачанного обновления нет.
+pub fn install_downloaded_update<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
+    let pending = DOWNLOADED_UPDATE
+        .lock()
+        .map_err(|_| "Downloaded update lock poisoned".to_string())?
+        .take();
+    let Some(DownloadedUpdate { update, bytes }) = pending else {
+        return Ok(false);
+    };
+
+    log::info!("Installing downloaded update {}", update.version);
+    let _ = app.emit(
+        "update:installing",
+        UpdateInstallStagePayload {
+            version: update.version.clone(),
+        },
+    );
+    update
+        .install(bytes)
+        .map_err(|e| format!("Failed to install update: {}", e))?;
+    Ok(true)
+}
+
+/// Сбрасывает скачанное обновление (например, при смене канала)
+pub fn discard_downloaded_update() {
+    if let Ok(mut pending) = DOWNLOADED_UPDATE.lock() {
+        if let Some(discarded) = pending.take() {
+            log::info!("Discarded downloaded update {}", discarded.update.version);
         }
     }
 }
 
+/// Текущие настройки обновлений
+pub async fn get_update_preferences() -> UpdatePreferences {
+    load_preferences().await
+}
+
+/// Меняет канал обновлений. Скачанное из другого канала обновление отбрасывается.
+pub async fn set_update_channel(channel: UpdateChannel) -> Result<UpdatePreferences, String> {
+    let mut prefs = load_preferences().await;
+    if prefs.channel != channel {
+        discard_downloaded_update();
+        prefs.channel = channel;
+        // Пропуск/откладывание относились к версии из старого канала
+        prefs.skipped_version = None;
+        prefs.remind_after_ms = None;
+    }
+    ConfigStore::save_update_preferences(&prefs)
+        .await
+        .map_err(|e| format!("Failed to save update preferences: {}", e))?;
+    log::info!("Update channel set to {:?}", channel);
+    Ok(prefs)
+}
+
+/// "Напомнить позже": фоновая проверка не уведомляет следующие сутки
+pub async fn remind_update_later() -> Result<UpdatePreferences, String> {
+    let mut prefs = load_preferences().await;
+    prefs.remind_after_ms = Some(chrono::Utc::now().timestamp_millis() + REMIND_LATER_MS);
+    ConfigStore::save_update_preferences(&prefs)
+        .await
+        .map_err(|e| format!("Failed to save update preferences: {}", e))?;
+    Ok(prefs)
+}
+
+/// "Пропустить версию": о ней больше не уведомляем (о следующих — как обычно)
+pub async fn skip_update_version(version: String) -> Result<UpdatePreferences, String> {
+    let mut prefs = load_preferences().await;
+    prefs.skipped_version = Some(version);
+    prefs.remind_after_ms = None;
+    ConfigStore::save_update_preferences(&prefs)
+        .await
+        .map_err(|e| format!("Failed to save update preferences: {}", e))?;
+    Ok(prefs)
+}
+
 /// Проверяет и устанавливает обновление.
 ///
 /// Важно: подтверждение делаем во frontend (наш UpdateDialog), поэтому тут
@@ -107,11 +469,6 @@ pub async fn check_for_update<R: Runtime>(
 pub async fn check_and_install_update<R: Runtime>(
     app: AppHandle<R>,
 ) -> Result<String, String> {
-    let updater = app
-        .updater_builder()
-        .build()
-        .map_err(|e| format!("Failed to build updater: {}", e))?;
-
     if INSTALL_IN_PROGRESS
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
         .is_err()
@@ -119,90 +476,8 @@ pub async fn check_and_install_update<R: Runtime>(
         return Err("Update installation is already in progress".to_string());
     }
 
-    let result = match updater.check().await {
-        Ok(Some(update)) => {
-            let version = update.version.clone();
-            let current_version = update.current_version.clone();
-            let body = update.body.clone().unwrap_or_default();
-
-            log::info!("Update found: {} -> {}", current_version, version);
-            log::info!("Release notes: {}", body);
-
-            // Скачиваем и устанавливаем
-            log::info!("Downloading and installing update...");
-
-            let _ = app.emit(
-                "update:download-started",
-                UpdateInstallStagePayload {
-                    version: version.clone(),
-                },
-            );
-
-            let app_handle_progress = app.clone();
-            let version_progress = version.clone();
-            let app_handle_installing = app.clone();
-            let version_installing = version.clone();
-            let downloaded_total = Arc::new(Mutex::new(0u64));
-            let downloaded_total_progress = Arc::clone(&downloaded_total);
-
-            update
-                .download_and_install(
-                    move |chunk_length, content_length| {
-                        let chunk_length = chunk_length as u64;
-
-                        // В зависимости от платформы/реализации `chunk_length` может быть:
-                        // - либо "сколько скачано всего"
-                        // - либо "размер последнего чанка"
-                        // Поэтому используем простую эвристику, чтобы корректно считать прогресс.
-                        let mut downloaded_total = downloaded_total_progress
-                            .lock()
-                            .expect("update downloaded_total mutex poisoned");
-
-                        let previous = *downloaded_total;
-                        let downloaded = if let Some(total) = content_length {
-                            if chunk_length <= total && chunk_length >= previous {
-                                chunk_length
-                            } else {
-                                previous.saturating_add(chunk_length)
-                            }
-                        } else {
-                            previous.saturating_add(chunk_length)
-                        };
-
-                        *downloaded_total = downloaded;
-
-                        let progress = content_length.and_then(|total| {
-                            if total == 0 {
-                                return Some(0);
-                            }
-                            let pct = ((*downloaded_total as f64 / total as f64) * 100.0)
-                                .clamp(0.0, 100.0) as u8;
-                            Some(pct)
-                        });
-
-                        let _ = app_handle_progress.emit(
-                            "update:download-progress",
-                            UpdateDownloadProgressPayload {
-                                version: version_progress.clone(),
-                                downloaded: *downloaded_total,
-                                total: content_length,
-                                progress,
-                            },
-                        );
-                    },
-                    move || {
-                        log::info!("Download completed, installing...");
-                        let _ = app_handle_installing.emit(
-                            "update:installing",
-                            UpdateInstallStagePayload {
-                                version: version_installing.clone(),
-                            },
-                        );
-                    },
-                )
-                .await
-                .map_err(|e| format!("Failed to download/install update: {}", e))?;
-
+    let result = match install_update_inner(&app).await {
+        Ok(Some(version)) => {
             log::info!("Update installed successfully, restarting...");
 
             // На Windows приложение стартует скрытым (без taskbar), поэтому после апдейта
@@ -212,18 +487,14 @@ pub async fn check_and_install_update<R: Runtime>(
             if let Err(e) = ConfigStore::save_post_update_marker(&version).await {
                 log::warn!("Failed to save post-update marker: {}", e);
             }
+            #[cfg(not(target_os = "windows"))]
+            let _ = version;
 
             // Перезапускаем приложение
             app.restart();
         }
-        Ok(None) => {
-            log::info!("App is already up to date");
-            Ok("No updates available".to_string())
-        }
-        Err(e) => {
-            log::error!("Update check failed: {}", e);
-            Err(format!("Failed to check for updates: {}", e))
-        }
+        Ok(None) => Ok("No updates available".to_string()),
+        Err(e) => Err(e),
     };
 
     // В случае успеха приложение перезапустится (и код дальше не продолжится).
@@ -232,3 +503,72 @@ pub async fn check_and_install_update<R: Runtime>(
 
     result
 }
+
+/// Ставит скачанное обновление, а если его нет — скачивает и ставит. Возвращает установленную версию.
+async fn install_update_inner<R: Runtime>(app: &AppHandle<R>) -> Result<Option<String>, String> {
+    let pending_version = DOWNLOADED_UPDATE
+        .lock()
+        .ok()
+        .and_then(|pending| pending.as_ref().map(|d| d.update.version.clone()));
+    if let Some(version) = pending_version {
+        if install_downloaded_update(app)? {
+            return Ok(Some(version));
+        }
+    }
+
+    let update = match find_update(app).await? {
+        (Some(update), _) => update,
+        (None, _) => {
+            log::info!("App is already up to date");
+            return Ok(None);
+        }
+    };
+
+    let version = update.version.clone();
+    log::info!("Update found: {} -> {}", update.current_version, version);
+    log::info!("Release notes: {}", update.body.clone().unwrap_or_default());
+
+    // Скачиваем и устанавливаем
+    log::info!("Downloading and installing update...");
+
+    let _ = app.emit(
+        "update:download-started",
+        UpdateInstallStagePayload {
+            version: version.clone(),
+        },
+    );
+
+    let app_handle_installing = app.clone();
+    let version_installing = version.clone();
+
+    update
+        .download_and_install(
+            progress_reporter(app.clone(), version.clone()),
+            move || {
+                log::info!("Download completed, installing...");
+                let _ = app_handle_installing.emit(
+                    "update:installing",
+                    UpdateInstallStagePayload {
+                        version: version_installing.clone(),
+                    },
+                );
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to download/install update: {}", e))?;
+
+    Ok(Some(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beta_channel_has_own_endpoint() {
+        assert_eq!(channel_endpoint(UpdateChannel::Stable), None);
+        let beta = channel_endpoint(UpdateChannel::Beta).unwrap();
+        assert!(Url::parse(beta).is_ok());
+        assert!(beta.ends_with("/latest.json"));
+    }
+}
@@ -0,0 +1,185 @@
+//! Права процесса целевого окна (Windows).
+//!
+//! Windows молча отбрасывает SendInput из обычного процесса в окно процесса-администратора (UIPI):
+//! enigo сообщает об успехе, а текст не появляется. Поэтому перед вставкой проверяем права окна.
+//! На macOS/Linux такого ограничения нет — ввод считается доступным.
+
+use crate::domain::PasteCompatibility;
+
+/// Дойдёт ли вставка до окна: `target` — имя exe процесса (например, `regedit.exe`),
+/// None — текущее активное окно
+pub fn paste_compatibility(target: Option<&str>) -> PasteCompatibility {
+    let compatibility = detect(target);
+    if !compatibility.can_inject_input {
+        log::warn!(
+            "Target {:?} runs elevated - simulated input will be blocked (UIPI)",
+            compatibility.target
+        );
+    }
+    compatibility
+}
+
+#[cfg(windows)]
+fn detect(target: Option<&str>) -> PasteCompatibility {
+    let process = match target {
+        Some(name) => win::find_process(name).map(|pid| (pid, name.to_string())),
+        None => win::foreground_process(),
+    };
+    let (target, target_elevated) = match process {
+        Some((pid, name)) => (Some(name), win::is_process_elevated(pid)),
+        None => (target.map(str::to_string), false),
+    };
+    PasteCompatibility::evaluate(target, target_elevated, win::current_process_elevated(), win::current_process_ui_access())
+}
+
+#[cfg(not(windows))]
+fn detect(target: Option<&str>) -> PasteCompatibility {
+    PasteCompatibility::evaluate(target.map(str::to_string), false, false, false)
+}
+
+/// Процесс активного окна (pid, имя exe) — нужен и для контекста сессии записи
+#[cfg(windows)]
+pub(crate) use win::foreground_process;
+
+#[cfg(windows)]
+mod win {
+    use std::ffi::c_void;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Security::{GetTokenInformation, TokenElevation, TokenUIAccess, TOKEN_QUERY};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, OpenProcess, OpenProcessToken, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    /// Закрывает HANDLE при выходе из области видимости
+    struct OwnedHandle(HANDLE);
+
+    impl Drop for OwnedHandle {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    fn open_process(pid: u32) -> Option<OwnedHandle> {
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+        (!handle.is_null()).then_some(OwnedHandle(handle))
+    }
+
+    fn open_token(process: HANDLE) -> Option<OwnedHandle> {
+        let mut token: HANDLE = std::ptr::null_mut();
+        let ok = unsafe { OpenProcessToken(process, TOKEN_QUERY, &mut token) };
+        (ok != 0).then_some(OwnedHandle(token))
+    }
+
+    fn token_u32(token: HANDLE, class: i32) -> Option<u32> {
+        let mut value: u32 = 0;
+        let mut returned: u32 = 0;
+        let ok = unsafe {
+            GetTokenInformation(
+                token,
+                class,
+                &mut value as *mut u32 as *mut c_void,
+                std::mem::size_of::<u32>() as u32,
+                &mut returned,
+            )
+        };
+        (ok != 0).then_some(value)
+    }
+
+    /// TOKEN_ELEVATION — одно поле DWORD
+    fn token_elevated(token: HANDLE) -> Option<bool> {
+        token_u32(token, TokenElevation).map(|elevated| elevated != 0)
+    }
+
+    /// Токен процесса администратора обычному процессу не открыть — отказ в доступе и есть признак прав
+    pub fn is_process_elevated(pid: u32) -> bool {
+        let Some(process) = open_process(pid) else {
+            return true;
+        };
+        match open_token(process.0) {
+            Some(token) => token_elevated(token.0).unwrap_or(false),
+            None => true,
+        }
+    }
+
+    pub fn current_process_elevated() -> bool {
+        let process = unsafe { GetCurrentProcess() };
+        open_token(process)
+            .and_then(|token| token_elevated(token.0))
+            .unwrap_or(false)
+    }
+
+    pub fn current_process_ui_access() -> bool {
+        let process = unsafe { GetCurrentProcess() };
+        open_token(process)
+            .and_then(|token| token_u32(token.0, TokenUIAccess))
+            .map(|ui_access| ui_access != 0)
+            .unwrap_or(false)
+    }
+
+    fn process_name(pid: u32) -> Option<String> {
+        let process = open_process(pid)?;
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let ok = unsafe { QueryFullProcessImageNameW(process.0, PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut len) };
+        if ok == 0 {
+            return None;
+        }
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        path.rsplit(['\\', '/']).next().map(str::to_string)
+    }
+
+    /// Процесс активного окна: (pid, имя exe)
+    pub fn foreground_process() -> Option<(u32, String)> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.is_null() {
+            return None;
+        }
+        let mut pid: u32 = 0;
+        unsafe { GetWindowThreadProcessId(hwnd, &mut pid) };
+        if pid == 0 {
+            return None;
+        }
+        // Имя процесса администратора может быть недоступно — pid всё равно проверяем
+        Some((pid, process_name(pid).unwrap_or_else(|| format!("pid {}", pid))))
+    }
+
+    /// Первый процесс с таким именем exe (без учёта регистра)
+    pub fn find_process(name: &str) -> Option<u32> {
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+        if snapshot == INVALID_HANDLE_VALUE {
+            return None;
+        }
+        let snapshot = OwnedHandle(snapshot);
+
+        let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        let mut ok = unsafe { Process32FirstW(snapshot.0, &mut entry) };
+        while ok != 0 {
+            let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+            let exe = String::from_utf16_lossy(&entry.szExeFile[..len]);
+            if exe.eq_ignore_ascii_case(name) {
+                return Some(entry.th32ProcessID);
+            }
+            ok = unsafe { Process32NextW(snapshot.0, &mut entry) };
+        }
+        None
+    }
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_is_never_blocked_outside_windows() {
+        let compatibility = paste_compatibility(Some("regedit.exe"));
+        assert_eq!(compatibility.target.as_deref(), Some("regedit.exe"));
+        assert!(compatibility.can_inject_input);
+    }
+}
@@ -6,6 +6,9 @@ mod presentation;
 
 mod demo;
 
+// Контракт событий/команд для examples/export_api_schema.rs
+pub use presentation::api_schema::api_schema_json;
+
 use presentation::commands;
 use presentation::state::AppState;
 use tauri::{Emitter, Manager};
@@ -43,6 +46,7 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(infrastructure::autostart::plugin())
         ;
 
     // Добавляем NSPanel плагин на macOS для появления поверх fullscreen приложений
@@ -51,54 +55,75 @@ pub fn run() {
         builder = builder.plugin(tauri_nspanel::init());
     }
 
+    // Узкие состояния — части AppState: команды берут только ту, что им нужна
+    let app_state = AppState::default();
     builder
-        .plugin(
-            tauri_plugin_log::Builder::default()
-                .level(if cfg!(debug_assertions) {
-                    log::LevelFilter::Debug
-                } else {
-                    log::LevelFilter::Info
-                })
-                // Глушим слишком многословные модули (огромные JSON в DEBUG)
-                .level_for("tauri_plugin_updater", log::LevelFilter::Info)
-                .level_for("reqwest", log::LevelFilter::Warn)
-                .level_for("hyper", log::LevelFilter::Warn)
-                .format(|out, message, record| {
-                    use tauri_plugin_log::fern::colors::{Color, ColoredLevelConfig};
-
-                    // Цвета для уровней логирования
-                    let colors = ColoredLevelConfig::new()
-                        .error(Color::Red)
-                        .warn(Color::Yellow)
-                        .info(Color::Green)
-                        .debug(Color::Cyan)
-                        .trace(Color::Magenta);
-
-                    // Укорачиваем путь модуля - берём только последнюю часть
-                    let target = record.target();
-                    let short_target = target.rsplit("::").next().unwrap_or(target);
-
-                    // Время в локальном формате
-                    let now = chrono::Local::now();
-                    let time_str = now.format("%H:%M:%S");
-
-                    // Форматируем лог: время серым, уровень цветной, модуль серым, сообщение белым
-                    out.finish(format_args!(
-                        "\x1b[90m{}\x1b[0m {} \x1b[90m{}\x1b[0m  {}",
-                        time_str,
-                        colors.color(record.level()),
-                        short_target,
-                        message
-                    ))
-                })
-                .build(),
-        )
-        .manage(AppState::default())
+        .plugin(infrastructure::logging::plugin())
+        .manage(app_state.recording.clone())
+        .manage(app_state.settings.clone())
+        .manage(app_state.user_data.clone())
+        .manage(app_state.integrations.clone())
+        .manage(app_state)
         .manage(demo::DemoAppState::default())
         .invoke_handler(tauri::generate_handler![
             commands::start_recording,
             commands::stop_recording,
+            commands::quit_app,
             commands::get_recording_status,
+            commands::get_debug_state,
+            commands::get_transcription_metrics,
+            commands::get_pipeline_trace,
+            commands::export_session_timeline,
+            commands::get_memory_report,
+            commands::get_storage_paths,
+            commands::get_api_schema,
+            commands::get_recent_logs,
+            commands::get_log_level,
+            commands::set_log_level,
+            commands::get_session_stats,
+            commands::get_analytics,
+            commands::recover_last_session,
+            commands::discard_recovered_session,
+            commands::benchmark_providers,
+            commands::get_accuracy_scripts,
+            commands::start_accuracy_test,
+            commands::stop_accuracy_test,
+            commands::cancel_accuracy_test,
+            commands::get_accuracy_history,
+            commands::clear_accuracy_history,
+            commands::list_transcription_jobs,
+            commands::pause_job,
+            commands::resume_job,
+            commands::cancel_job,
+            commands::get_held_transcriptions,
+            commands::release_held_transcription,
+            commands::discard_held_transcription,
+            commands::replace_last_final,
+            commands::speak_text,
+            commands::share_session,
+            commands::revoke_session_share,
+            commands::list_session_shares,
+            commands::stop_speaking,
+            commands::get_corrections,
+            commands::learn_correction,
+            commands::learn_corrections_from_edit,
+            commands::remove_correction,
+            commands::prewarm_recording,
+            commands::deliver_text_output,
+            commands::get_paste_audit_log,
+            commands::clear_paste_audit_log,
+            commands::get_history,
+            commands::get_history_page,
+            commands::get_history_item,
+            commands::update_history_item_text,
+            commands::tag_history_item,
+            commands::set_history_item_favorite,
+            commands::list_tags,
+            commands::export_history,
+            commands::get_text_output_profiles,
+            commands::update_text_output_profiles,
+            commands::get_recording_profiles,
+            commands::update_recording_profiles,
             commands::toggle_window,
             commands::toggle_recording_with_window,
             commands::minimize_window,
@@ -112,21 +137,59 @@ pub fn run() {
             commands::update_app_config,
             commands::start_microphone_test,
             commands::stop_microphone_test,
+            commands::preview_processing_chain,
+            commands::play_microphone_test,
+            commands::stop_microphone_test_playback,
             commands::register_recording_hotkey,
             commands::unregister_recording_hotkey,
+            commands::check_hotkey_availability,
+            commands::suggest_available_hotkeys,
+            commands::set_spelling_mode,
+            commands::get_spelling_mode,
             commands::check_for_updates,
             commands::install_update,
+            commands::get_update_details,
+            commands::download_update,
+            commands::get_update_preferences,
+            commands::set_update_channel,
+            commands::remind_update_later,
+            commands::skip_update_version,
             commands::get_available_whisper_models,
             commands::check_whisper_model,
             commands::download_whisper_model,
+            commands::set_models_directory,
+            commands::set_mock_audio_file,
+            commands::verify_model,
             commands::delete_whisper_model,
+            commands::cancel_local_transcription,
             commands::get_audio_devices,
+            commands::get_output_audio_devices,
             commands::check_accessibility_permission,
             commands::request_accessibility_permission,
+            commands::check_permissions,
+            commands::open_permission_settings,
+            commands::get_paste_compatibility,
             commands::auto_paste_text,
             commands::copy_to_clipboard_native,
             commands::show_auth_window,
             commands::show_recording_window,
+            commands::get_telemetry_preview,
+            commands::get_glossary_status,
+            commands::sync_glossary,
+            commands::get_network_status,
+            commands::start_live_captions,
+            commands::stop_live_captions,
+            commands::get_loopback_audio_devices,
+            commands::start_companion_server,
+            commands::stop_companion_server,
+            commands::get_companion_server_status,
+            commands::start_meeting,
+            commands::stop_meeting,
+            commands::get_meeting_transcript,
+            commands::request_calendar_access,
+            commands::get_current_calendar_event,
+            commands::get_launch_at_login,
+            commands::set_launch_at_login,
             commands::show_settings_window,
             commands::show_profile_window,
             commands::set_authenticated,
@@ -135,6 +198,9 @@ pub fn run() {
             demo::update_demo_state,
         ])
         .setup(|app| {
+            // Логгер пропускает всё, реальный уровень — log::max_level() (меняется командой set_log_level)
+            log::set_max_level(infrastructure::logging::default_log_level());
+
             #[cfg(debug_assertions)]
             {
                 log::info!("VoicetextAI application started in debug mode");
@@ -181,6 +247,21 @@ pub fn run() {
                 }
             }
 
+            if let Some(dir) = infrastructure::storage::portable_data_dir() {
+                log::info!("Portable mode: app data is stored in {:?}", dir);
+            }
+
+            // Автозапуск при входе в систему: если так настроено, окна не показываем — ждём хоткея в трее
+            if infrastructure::autostart::launched_minimized(std::env::args()) {
+                let start_minimized = tauri::async_runtime::block_on(ConfigStore::load_app_config())
+                    .map(|config| config.start_minimized)
+                    .unwrap_or(true);
+                log::info!("Launched at login (start_minimized: {})", start_minimized);
+                app.state::<AppState>()
+                    .launched_minimized
+                    .store(start_minimized, std::sync::atomic::Ordering::Relaxed);
+            }
+
             // ЗАПАСНОЙ ВАРИАНТ: Если NSPanel с StyleMask не работает поверх fullscreen,
             // раскомментируйте строку ниже. Окно гарантированно появится поверх ВСЕГО,
             // но иконка исчезнет из Dock (app станет фоновым сервисом).
@@ -192,6 +273,92 @@ pub fn run() {
                 log::error!("Failed to create system tray: {}", e);
             }
 
+            // Соединение, открытое между записями: UI показывает, сколько оно держится (у AssemblyAI это расходы)
+            {
+                let app_handle = app.handle().clone();
+                app.state::<AppState>()
+                    .recording
+                    .transcription_service
+                    .set_idle_connection_listener(Some(std::sync::Arc::new(move |report: application::IdleConnectionReport| {
+                        let payload = presentation::events::ConnectionIdlePayload::from(report);
+                        if let Err(e) = app_handle.emit(presentation::events::EVENT_CONNECTION_IDLE, payload) {
+                            log::debug!("Failed to emit connection idle event: {}", e);
+                        }
+                    })));
+            }
+
+            // Режим по буквам переключается и голосом посреди записи: UI показывает индикатор
+            {
+                let app_handle = app.handle().clone();
+                app.state::<AppState>()
+                    .recording
+                    .transcription_service
+                    .set_spelling_mode_listener(Some(std::sync::Arc::new(move |enabled: bool| {
+                        let payload = presentation::events::SpellingModePayload { enabled };
+                        if let Err(e) = app_handle.emit(presentation::events::EVENT_SPELLING_MODE, payload) {
+                            log::debug!("Failed to emit spelling mode event: {}", e);
+                        }
+                    })));
+            }
+
+            // A/B сравнение провайдеров (stt comparison_provider): тексты обоих провайдеров в UI
+            {
+                let app_handle = app.handle().clone();
+                app.state::<AppState>()
+                    .recording
+                    .transcription_service
+                    .set_comparison_listener(Some(std::sync::Arc::new(move |transcript: application::ComparisonTranscript| {
+                        let session_id = app_handle
+                            .try_state::<AppState>()
+                            .map(|s| s.active_transcription_session_id.load(std::sync::atomic::Ordering::Relaxed))
+                            .unwrap_or(0);
+                        let payload = presentation::events::ComparisonTranscriptPayload::new(session_id, transcript);
+                        if let Err(e) = app_handle.emit(presentation::events::EVENT_TRANSCRIPTION_COMPARISON, payload) {
+                            log::debug!("Failed to emit comparison transcript event: {}", e);
+                        }
+                    })));
+
+                // Ансамбль двух провайдеров (только сборка с feature "ensemble")
+                let app_handle = app.handle().clone();
+                app.state::<AppState>()
+                    .recording
+                    .transcription_service
+                    .set_ensemble_listener(Some(std::sync::Arc::new(move |ensemble: application::EnsembleTranscript| {
+                        let session_id = app_handle
+                            .try_state::<AppState>()
+                            .map(|s| s.active_transcription_session_id.load(std::sync::atomic::Ordering::Relaxed))
+                            .unwrap_or(0);
+                        let payload = presentation::events::EnsembleTranscriptPayload::new(session_id, ensemble);
+                        if let Err(e) = app_handle.emit(presentation::events::EVENT_TRANSCRIPTION_ENSEMBLE, payload) {
+                            log::debug!("Failed to emit ensemble transcript event: {}", e);
+                        }
+                    })));
+            }
+
+            // Голосовые команды управления (stt voice_commands): стоп, смена языка, вставка
+            {
+                let app_handle = app.handle().clone();
+                app.state::<AppState>()
+                    .recording
+                    .transcription_service
+                    .set_voice_command_listener(Some(std::sync::Arc::new(move |command: application::VoiceCommand| {
+                        presentation::voice_commands::handle_voice_command(&app_handle, command);
+                    })));
+            }
+
+            // Прогресс локальной расшифровки Whisper: UI показывает "обработано N из M" и кнопку отмены
+            {
+                let app_handle = app.handle().clone();
+                infrastructure::stt::set_whisper_progress_listener(Some(std::sync::Arc::new(
+                    move |progress: infrastructure::stt::WhisperProgress| {
+                        let payload = presentation::events::WhisperProgressPayload::from(progress);
+                        if let Err(e) = app_handle.emit(presentation::events::EVENT_WHISPER_PROGRESS, payload) {
+                            log::debug!("Failed to emit whisper progress event: {}", e);
+                        }
+                    },
+                )));
+            }
+
             // Окно скрыто при старте независимо от режима
             // Открывается по горячей клавише (не забирает фокус)
             if let Some(window) = app.get_webview_window("main") {
@@ -247,14 +414,20 @@ pub fn run() {
                 // Настраиваем обработчик закрытия окна
                 // При попытке закрыть - скрываем вместо завершения приложения
                 let window_clone = window.clone();
-                window.on_window_event(move |event| {
-                    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let drop_app_handle = app.handle().clone();
+                window.on_window_event(move |event| match event {
+                    tauri::WindowEvent::CloseRequested { api, .. } => {
                         // Отменяем закрытие
                         api.prevent_close();
                         // Скрываем окно
                         let _ = window_clone.hide();
                         log::debug!("Window hidden instead of closed (app still running in tray)");
                     }
+                    // Брошенные на окно аудиофайлы расшифровываем целиком
+                    tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                        presentation::file_drop::handle_file_drop(&drop_app_handle, paths.clone());
+                    }
+                    _ => {}
                 });
             }
 
@@ -377,7 +550,7 @@ pub fn run() {
                             if loaded_from_disk {
                                 let _ = crate::infrastructure::ConfigStore::save_config(&stt).await;
                             }
-                            let _ = state.transcription_service.update_config(stt).await;
+                            let _ = state.recording.transcription_service.update_config(stt).await;
 
                             // Запускаем фоновый refresh (если возможен).
                             state.restart_auth_refresh_task(app_handle.clone()).await;
@@ -436,22 +609,22 @@ pub fn run() {
                         }
 
                         // Сохраняем токен если он уже был установлен (race condition с Vue set_authenticated)
-                        let current_config = state.transcription_service.get_config().await;
+                        let current_config = state.recording.transcription_service.get_config().await;
                         if current_config.backend_auth_token.is_some() && saved_config.backend_auth_token.is_none() {
                             log::info!("Preserving existing backend_auth_token from current config");
                             saved_config.backend_auth_token = current_config.backend_auth_token;
                         }
 
-                        if let Err(e) = state.transcription_service.update_config(saved_config.clone()).await {
+                        if let Err(e) = state.recording.transcription_service.update_config(saved_config.clone()).await {
                             log::error!("Failed to load saved STT config: {}", e);
                         } else {
                             // Синхронизируем с AppConfig
-                            state.config.write().await.stt = saved_config;
+                            state.settings.config.write().await.stt = saved_config;
                             log::info!("Loaded saved STT configuration");
 
                             // Важно: загрузка идёт асинхронно, и окна могут успеть стартануть sync раньше.
                             // Поэтому после успешной загрузки мы обязаны пнуть invalidation, иначе UI может остаться на дефолтах.
-                            let revision = AppState::bump_revision(&state.stt_config_revision).await;
+                            let revision = AppState::bump_revision(&state.settings.stt_config_revision).await;
                             let _ = app_handle.emit(
                                 crate::presentation::EVENT_STATE_SYNC_INVALIDATION,
                                 crate::presentation::StateSyncInvalidationPayload {
@@ -511,9 +684,13 @@ pub fn run() {
                             }
                         }
 
-                        *state.config.write().await = saved_app_config.clone();
+                        crate::infrastructure::models::set_models_dir_override(
+                            saved_app_config.models_dir.as_ref().map(std::path::PathBuf::from),
+                        );
+
+                        *state.settings.config.write().await = saved_app_config.clone();
 
-                        state.transcription_service
+                        state.recording.transcription_service
                             .set_microphone_sensitivity(saved_app_config.microphone_sensitivity)
                             .await;
 
@@ -531,7 +708,7 @@ pub fn run() {
                             saved_app_config.microphone_sensitivity, saved_app_config.selected_audio_device);
 
                         // Аналогично STT: после асинхронной загрузки пинаем invalidation.
-                        let revision = AppState::bump_revision(&state.app_config_revision).await;
+                        let revision = AppState::bump_revision(&state.settings.app_config_revision).await;
                         let _ = app_handle.emit(
                             crate::presentation::EVENT_STATE_SYNC_INVALIDATION,
                             crate::presentation::StateSyncInvalidationPayload {
@@ -549,10 +726,14 @@ pub fn run() {
                     match ConfigStore::load_ui_preferences().await {
                         Ok(prefs) => {
                             log::info!("Loaded UI preferences: theme={}, locale={}", prefs.theme, prefs.locale);
-                            *state.ui_preferences.write().await = prefs;
+                            presentation::tray::update_tray_language(
+                                &app_handle,
+                                presentation::i18n::UiLanguage::from_locale(&prefs.locale),
+                            );
+                            *state.settings.ui_preferences.write().await = prefs;
 
                             // Пинаем invalidation после загрузки prefs, чтобы окна, которые уже стартанули, догнали SoT.
-                            let revision = AppState::bump_revision(&state.ui_preferences_revision).await;
+                            let revision = AppState::bump_revision(&state.settings.ui_preferences_revision).await;
                             let _ = app_handle.emit(
                                 crate::presentation::EVENT_STATE_SYNC_INVALIDATION,
                                 crate::presentation::StateSyncInvalidationPayload {
@@ -569,10 +750,103 @@ pub fn run() {
                     }
                 }
 
+                // Загружаем статистику сессий (аналитика диктовки)
+                if let Some(state) = app_handle.try_state::<AppState>() {
+                    match ConfigStore::load_session_stats().await {
+                        Ok(mut stats) => {
+                            crate::application::retain_recent_session_stats(
+                                &mut stats,
+                                chrono::Utc::now().timestamp_millis(),
+                            );
+                            // Сессии, завершённые до загрузки, тоже сохраняем
+                            let mut current = state.user_data.session_stats.write().await;
+                            stats.append(&mut current);
+                            *current = stats;
+                        }
+                        Err(e) => {
+                            log::warn!("Failed to load session stats: {}", e);
+                        }
+                    }
+                }
+
+                // Загружаем историю диктовок (фразы, пришедшие до загрузки, остаются в конце)
+                if let Some(state) = app_handle.try_state::<AppState>() {
+                    let (keep_history, max_items) = {
+                        let config = state.settings.config.read().await;
+                        (config.keep_history, config.max_history_items)
+                    };
+                    if keep_history {
+                        match ConfigStore::load_history().await {
+                            Ok(mut items) => {
+                                let (compacted, snapshot) = {
+                                    let mut current = state.user_data.history.write().await;
+                                    for item in std::mem::take(&mut *current) {
+                                        crate::application::append_history(&mut items, item.transcription, max_items);
+                                    }
+                                    // История, сохранённая до сжатия, — полные тексты переносим в отдельные файлы
+                                    let compacted = crate::application::compact_history(&mut items);
+                                    *current = items;
+                                    (compacted, current.clone())
+                                };
+                                if !compacted.is_empty() {
+                                    commands::save_history_texts(&compacted).await;
+                                    if let Err(e) = ConfigStore::save_history(&snapshot).await {
+                                        log::warn!("Failed to save compacted history: {}", e);
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to load history: {}", e);
+                            }
+                        }
+                    }
+                }
+
+                // Загружаем журнал автовставок
+                if let Some(state) = app_handle.try_state::<AppState>() {
+                    match ConfigStore::load_paste_audit().await {
+                        Ok(mut entries) => {
+                            let mut current = state.user_data.paste_audit.write().await;
+                            let recorded = std::mem::take(&mut *current);
+                            crate::application::append_paste_audit(&mut entries, recorded);
+                            *current = entries;
+                        }
+                        Err(e) => {
+                            log::warn!("Failed to load paste audit log: {}", e);
+                        }
+                    }
+                }
+
+                // Офлайн-очередь телеметрии: подхватываем только если пользователь согласился
+                if let Some(state) = app_handle.try_state::<AppState>() {
+                    if state.settings.config.read().await.telemetry_enabled {
+                        match ConfigStore::load_telemetry_queue().await {
+                            Ok(events) => {
+                                let mut queue = state.integrations.telemetry_queue.write().await;
+                                let recorded = std::mem::take(&mut *queue);
+                                for event in events.into_iter().chain(recorded) {
+                                    crate::application::enqueue_telemetry(&mut queue, event);
+                                }
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to load telemetry queue: {}", e);
+                            }
+                        }
+                    } else if let Err(e) = ConfigStore::delete_telemetry_queue().await {
+                        log::warn!("Failed to delete telemetry queue: {}", e);
+                    }
+                }
+
+                // Задачи расшифровки файлов: прерванные выходом продолжаются с последней готовой главы
+                presentation::transcription_jobs::restore_transcription_jobs(&app_handle).await;
+
+                // Warm pool: прогрев соединения перед привычным временем диктовки (если включён)
+                presentation::warm_pool::start_warm_pool(&app_handle).await;
+
                 // Регистрируем горячую клавишу ПОСЛЕ загрузки app-config.
                 //
                 // Иначе возможна гонка: отдельная задача регистрирует дефолтный хоткей
-                // до того, как `load_app_config()` успеет обновить `state.config`,
+                // до того, как `load_app_config()` успеет обновить `state.settings.config`,
                 // и тогда UI показывает новое значение, а реально работает дефолт.
                 if let Some(state) = app_handle.try_state::<AppState>() {
                     let handle = app_handle.clone();
@@ -590,7 +864,7 @@ pub fn run() {
                 }
             });
 
-            // Регистрируем хоткей сразу (на дефолтном/текущем state.config),
+            // Регистрируем хоткей сразу (на дефолтном/текущем state.settings.config),
             // чтобы он работал даже до завершения загрузки конфигов.
             // После загрузки app-config выше мы перерегистрируем хоткей еще раз (итоговое значение).
             let app_handle_for_hotkey_init = app.handle().clone();
@@ -606,33 +880,61 @@ pub fn run() {
             // Запускаем обработчик VAD timeout событий
             if let Some(state) = app.try_state::<AppState>() {
                 state.start_vad_timeout_handler(app.handle().clone());
+                state.start_vad_activity_forwarder(app.handle().clone());
             }
 
             // Запускаем фоновую проверку обновлений (каждые 6 часов)
             log::info!("Starting background update checker");
             infrastructure::updater::start_background_update_check(app.handle().clone());
 
-            // Настраиваем deep link handler для OAuth callback
+            // Целостность и новые версии скачанных моделей Whisper (раз в сутки)
+            {
+                let app_handle = app.handle().clone();
+                infrastructure::models::start_background_model_check(move |update| {
+                    let payload = presentation::events::ModelUpdateAvailablePayload::from(update);
+                    if let Err(e) = app_handle.emit(presentation::events::EVENT_MODEL_UPDATE_AVAILABLE, payload) {
+                        log::error!("Failed to emit model update event: {}", e);
+                    }
+                });
+            }
+
+            // Отправка анонимной телеметрии (no-op, пока она не включена в настройках)
+            presentation::telemetry::start_telemetry_uploader(app.handle().clone());
+
+            // Монитор связи: без сети запись идёт через локальный Whisper
+            presentation::connectivity::start_connectivity_monitor(app.handle().clone());
+
+            // Размеры буферов и очередей: предупреждение, если пул вырос выше порога
+            presentation::memory::start_memory_monitor(app.handle().clone());
+
+            // Общий словарь команды: кэш сразу, бэкенд раз в 15 минут
+            presentation::glossary::start_glossary_sync(app.handle().clone());
+
+            // Настраиваем deep link handler (OAuth callback, voicetotext://record, voicetotext://activate)
             #[cfg(desktop)]
             {
                 use tauri_plugin_deep_link::DeepLinkExt;
 
                 // Регистрируем URL scheme
-                if let Err(e) = app.deep_link().register("voicetotext") {
+                if let Err(e) = app.deep_link().register(presentation::deep_link::DEEP_LINK_SCHEME) {
                     log::warn!("Failed to register deep link: {}", e);
                 }
 
+                // Приложение могло быть запущено самой ссылкой
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    for url in urls {
+                        log::info!("Launched with deep link: {}://{}", url.scheme(), url.host_str().unwrap_or_default());
+                        presentation::deep_link::handle_deep_link(app.handle(), url);
+                    }
+                }
+
                 // Обработчик deep link событий
                 let handle = app.handle().clone();
                 app.deep_link().on_open_url(move |event| {
-                    let urls = event.urls();
-                    for url in urls {
-                        log::info!("Received deep link: {}", url);
-                        if let Some(window) = handle.get_webview_window("main") {
-                            let _ = window.emit("deep-link", url.to_string());
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
+                    for url in event.urls() {
+                        // Полный URL не логируем: в нём может быть токен активации
+                        log::info!("Received deep link: {}://{}", url.scheme(), url.host_str().unwrap_or_default());
+                        presentation::deep_link::handle_deep_link(&handle, url);
                     }
                 });
             }
@@ -642,6 +944,22 @@ pub fn run() {
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|_app, _event| {
+            // Cmd+Q / выход из Dock: сначала корректно останавливаем запись и сохраняем состояние.
+            // app.exit(code) из самого shutdown приходит с code = Some(..) и не перехватывается.
+            if let tauri::RunEvent::ExitRequested { code: None, api, .. } = &_event {
+                api.prevent_exit();
+                presentation::shutdown::request_shutdown(_app);
+            }
+
+            // Отложенная установка: скачанное обновление ставим при выходе из приложения
+            if matches!(_event, tauri::RunEvent::Exit) {
+                match infrastructure::updater::install_downloaded_update(_app) {
+                    Ok(true) => log::info!("Downloaded update installed on exit"),
+                    Ok(false) => {}
+                    Err(e) => log::error!("Failed to install downloaded update on exit: {}", e),
+                }
+            }
+
             // Клик по иконке в Dock (только macOS)
             #[cfg(target_os = "macos")]
             if let tauri::RunEvent::Reopen { has_visible_windows, .. } = _event {
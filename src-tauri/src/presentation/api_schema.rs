@@ -0,0 +1,187 @@
+//! Машиночитаемый контракт IPC: JSON Schema payload'ов событий и ответов команд.
+//!
+//! Схемы строятся из тех же Rust типов, что сериализуются во frontend, поэтому не расходятся с ними.
+//! `get_api_schema` отдаёт контракт во время работы, а `cargo run --example export_api_schema`
+//! пишет его в файл — из него генерируются TS-типы и его же читают внешние интеграции.
+//! Несовместимое изменение payload'а (удаление/переименование поля, смена типа) — повод поднять
+//! `EVENT_CONTRACT_VERSION`.
+
+use std::collections::BTreeMap;
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::application::PipelineTrace;
+use crate::domain::{MemoryPoolUsage, MemoryReport, SessionStats, SessionTimeline, StoragePaths};
+use crate::presentation::events::*;
+use crate::presentation::validation::ValidationFailure;
+
+/// Machine-readable contract of events and command responses
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiSchema {
+    /// `EVENT_CONTRACT_VERSION`
+    pub version: u32,
+    /// Event name → payload schema
+    pub events: BTreeMap<&'static str, Schema>,
+    /// Command name → response schemas
+    pub commands: BTreeMap<&'static str, CommandSchema>,
+    /// Shared types referenced as `#/definitions/<Name>`
+    pub definitions: schemars::Map<String, Schema>,
+}
+
+/// Response schemas of one command
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandSchema {
+    /// Ok value
+    pub output: Schema,
+    /// Structured error sent as JSON in the error string (None — plain text error)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<Schema>,
+}
+
+struct ApiSchemaBuilder {
+    gen: SchemaGenerator,
+    events: BTreeMap<&'static str, Schema>,
+    commands: BTreeMap<&'static str, CommandSchema>,
+}
+
+impl ApiSchemaBuilder {
+    fn new() -> Self {
+        Self {
+            gen: SchemaSettings::draft07().into_generator(),
+            events: BTreeMap::new(),
+            commands: BTreeMap::new(),
+        }
+    }
+
+    fn event<T: JsonSchema>(&mut self, name: &'static str) -> &mut Self {
+        let schema = self.gen.subject_schema_for::<T>();
+        self.events.insert(name, schema);
+        self
+    }
+
+    fn command<T: JsonSchema>(&mut self, name: &'static str) -> &mut Self {
+        let output = self.gen.subject_schema_for::<T>();
+        self.commands.insert(name, CommandSchema { output, error: None });
+        self
+    }
+
+    fn command_with_error<T: JsonSchema, E: JsonSchema>(&mut self, name: &'static str) -> &mut Self {
+        let output = self.gen.subject_schema_for::<T>();
+        let error = Some(self.gen.subject_schema_for::<E>());
+        self.commands.insert(name, CommandSchema { output, error });
+        self
+    }
+
+    fn build(mut self) -> ApiSchema {
+        ApiSchema {
+            version: EVENT_CONTRACT_VERSION,
+            events: self.events,
+            commands: self.commands,
+            definitions: self.gen.take_definitions(),
+        }
+    }
+}
+
+/// Контракт всех событий из `events` и команд, чьи ответы описаны там же
+pub fn api_schema() -> ApiSchema {
+    let mut builder = ApiSchemaBuilder::new();
+    builder
+        .event::<PartialTranscriptionPayload>(EVENT_TRANSCRIPTION_PARTIAL)
+        .event::<FinalTranscriptionPayload>(EVENT_TRANSCRIPTION_FINAL)
+        .event::<HeldTranscriptionPayload>(EVENT_TRANSCRIPTION_HELD)
+        .event::<ComparisonTranscriptPayload>(EVENT_TRANSCRIPTION_COMPARISON)
+        .event::<EnsembleTranscriptPayload>(EVENT_TRANSCRIPTION_ENSEMBLE)
+        .event::<RecordingStatusPayload>(EVENT_RECORDING_STATUS)
+        .event::<RecordingTickPayload>(EVENT_RECORDING_TICK)
+        .event::<RecordingQuietPayload>(EVENT_RECORDING_QUIET)
+        .event::<AudioLevelPayload>(EVENT_AUDIO_LEVEL)
+        .event::<AudioSpectrumPayload>(EVENT_AUDIO_SPECTRUM)
+        .event::<VadActivityPayload>(EVENT_VAD_ACTIVITY)
+        .event::<MicrophoneTestLevelPayload>(EVENT_MICROPHONE_TEST_LEVEL)
+        .event::<AudioSpectrumPayload>(EVENT_MICROPHONE_TEST_SPECTRUM)
+        .event::<TranscriptionErrorPayload>(EVENT_TRANSCRIPTION_ERROR)
+        .event::<RetryCountdownPayload>(EVENT_TRANSCRIPTION_RETRY_COUNTDOWN)
+        .event::<ConnectionQualityPayload>(EVENT_CONNECTION_QUALITY)
+        .event::<ConnectionIdlePayload>(EVENT_CONNECTION_IDLE)
+        .event::<NetworkStatusPayload>(EVENT_NETWORK_OFFLINE)
+        .event::<NetworkStatusPayload>(EVENT_NETWORK_ONLINE)
+        .event::<PasteClipboardFallbackPayload>(EVENT_PASTE_CLIPBOARD_FALLBACK)
+        .event::<TranscriptionLatencyPayload>(EVENT_TRANSCRIPTION_LATENCY)
+        .event::<SessionStats>(EVENT_SESSION_STATS)
+        .event::<HistoryItemUpdatedPayload>(EVENT_HISTORY_ITEM_UPDATED)
+        .event::<MemoryPoolUsage>(EVENT_MEMORY_ALARM)
+        .event::<MeetingFinishedPayload>(EVENT_MEETING_FINISHED)
+        .event::<ShutdownPayload>(EVENT_APP_SHUTDOWN)
+        // Необработанная ссылка как есть
+        .event::<String>(EVENT_DEEP_LINK)
+        .event::<DeepLinkErrorPayload>(EVENT_DEEP_LINK_ERROR)
+        .event::<()>(EVENT_LICENSE_ACTIVATED)
+        .event::<FileTranscriptionRejectedPayload>(EVENT_FILE_TRANSCRIPTION_REJECTED)
+        .event::<FileTranscriptionProgressPayload>(EVENT_FILE_TRANSCRIPTION_PROGRESS)
+        .event::<FileTranscriptionChapterPayload>(EVENT_FILE_TRANSCRIPTION_CHAPTER)
+        .event::<FileTranscriptionResultPayload>(EVENT_FILE_TRANSCRIPTION_RESULT)
+        .event::<FileTranscriptionErrorPayload>(EVENT_FILE_TRANSCRIPTION_ERROR)
+        .event::<WhisperProgressPayload>(EVENT_WHISPER_PROGRESS)
+        .event::<ModelUpdateAvailablePayload>(EVENT_MODEL_UPDATE_AVAILABLE)
+        .event::<()>(EVENT_RECORDING_WINDOW_SHOWN)
+        .event::<SpellingModePayload>(EVENT_SPELLING_MODE)
+        .event::<VoiceCommandPayload>(EVENT_VOICE_COMMAND)
+        .event::<StateSyncInvalidationPayload>(EVENT_STATE_SYNC_INVALIDATION)
+        .command::<TranscriptionMetricsPayload>("get_transcription_metrics")
+        .command::<Vec<PipelineTrace>>("get_pipeline_trace")
+        .command::<SessionTimeline>("export_session_timeline")
+        .command::<MemoryReport>("get_memory_report")
+        .command::<StoragePaths>("get_storage_paths")
+        .command::<Vec<SessionStats>>("get_session_stats")
+        .command_with_error::<(), ValidationFailure>("update_stt_config");
+    builder.build()
+}
+
+/// Контракт в виде JSON (для `get_api_schema` и экспорта в файл)
+pub fn api_schema_json() -> String {
+    serde_json::to_string_pretty(&api_schema()).expect("API schema is always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_lists_events_and_resolves_payload_definitions() {
+        let schema: serde_json::Value = serde_json::from_str(&api_schema_json()).unwrap();
+        assert_eq!(schema["version"], EVENT_CONTRACT_VERSION);
+        assert_eq!(schema["events"].as_object().unwrap().len(), 40);
+
+        let final_ref = schema["events"][EVENT_TRANSCRIPTION_FINAL]["$ref"].as_str().unwrap();
+        assert_eq!(final_ref, "#/definitions/FinalTranscriptionPayload");
+        let final_payload = &schema["definitions"]["FinalTranscriptionPayload"];
+        assert!(final_payload["properties"]["alternatives"].is_object());
+        // Вложенные domain-типы попадают в definitions
+        assert!(schema["definitions"]["AlternativeText"].is_object());
+        assert!(schema["definitions"]["SessionCalendarTag"].is_object());
+
+        // serde rename учитывается: поле называется так же, как в JSON события
+        let invalidation = &schema["definitions"]["StateSyncInvalidationPayload"];
+        assert!(invalidation["properties"]["timestampMs"].is_object());
+        assert_eq!(schema["events"][EVENT_DEEP_LINK]["type"], "string");
+
+        // Тип замера задержки — перечисление, а не произвольная строка
+        let latency = &schema["definitions"]["TranscriptionLatencyPayload"];
+        assert_eq!(latency["properties"]["kind"]["$ref"], "#/definitions/LatencyKind");
+        let latency_kind = schema["definitions"]["LatencyKind"].to_string();
+        assert!(latency_kind.contains("\"partial\"") && latency_kind.contains("\"final\""));
+    }
+
+    #[test]
+    fn command_errors_are_described() {
+        let schema: serde_json::Value = serde_json::from_str(&api_schema_json()).unwrap();
+        let update = &schema["commands"]["update_stt_config"];
+        assert_eq!(update["error"]["$ref"], "#/definitions/ValidationFailure");
+        assert!(schema["commands"]["get_session_stats"].get("error").is_none());
+        assert_eq!(schema["commands"]["get_session_stats"]["output"]["type"], "array");
+        assert_eq!(schema["commands"]["export_session_timeline"]["output"]["$ref"], "#/definitions/SessionTimeline");
+    }
+}
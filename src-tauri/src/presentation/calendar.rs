@@ -0,0 +1,87 @@
+//! Названия сессий по календарю: встреча, которая шла в момент старта записи,
+//! становится названием и метками всех финальных фраз этой сессии в истории.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::domain::{session_event_at, CalendarConfig, CalendarEvent, CalendarProvider, SessionCalendarTag};
+use crate::infrastructure::calendar::create_calendar_provider;
+use crate::presentation::i18n::UiMessage;
+use crate::presentation::AppState;
+
+/// Календарь не должен задерживать запись: не успели — сессия остаётся без названия
+const CALENDAR_LOOKUP_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Источник событий для текущих настроек (переиспользуется, пока настройки не изменились — в нём кэш .ics)
+async fn calendar_provider(state: &AppState, config: &CalendarConfig) -> Option<Arc<dyn CalendarProvider>> {
+    let mut cached = state.integrations.calendar_provider.write().await;
+    if let Some((cached_config, provider)) = cached.as_ref() {
+        if cached_config == config {
+            return Some(provider.clone());
+        }
+    }
+    let provider = create_calendar_provider(config)?;
+    *cached = Some((config.clone(), provider.clone()));
+    Some(provider)
+}
+
+/// Встреча, идущая сейчас, по настроенному источнику (None — нет встречи)
+pub async fn current_calendar_event(state: &AppState) -> Result<Option<CalendarEvent>, String> {
+    let config = state.settings.config.read().await.calendar.clone();
+    let Some(provider) = calendar_provider(state, &config).await else {
+        return Err(state.localize(UiMessage::CalendarNotConfigured).await);
+    };
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let Ok(events) = tokio::time::timeout(CALENDAR_LOOKUP_TIMEOUT, provider.events_at(now_ms)).await else {
+        return Err(state.localize(UiMessage::CalendarTimedOut).await);
+    };
+    let events = events.map_err(|e| e.to_string())?;
+    Ok(session_event_at(&events, now_ms).cloned())
+}
+
+/// Запрашивает доступ к календарю (системный диалог для EventKit)
+pub async fn request_calendar_access(state: &AppState) -> Result<bool, String> {
+    let config = state.settings.config.read().await.calendar.clone();
+    let Some(provider) = calendar_provider(state, &config).await else {
+        return Err(state.localize(UiMessage::CalendarNotConfigured).await);
+    };
+    provider.request_access().await.map_err(|e| e.to_string())
+}
+
+/// В фоне ищет встречу для новой сессии записи (если названия по календарю включены)
+pub fn tag_session_from_calendar(app_handle: &AppHandle, session_id: u64) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app_handle.try_state::<AppState>() else {
+            return;
+        };
+        if !state.settings.config.read().await.calendar.enabled {
+            return;
+        }
+
+        match current_calendar_event(&state).await {
+            Ok(Some(event)) => {
+                log::info!("Recording session {} titled from calendar", session_id);
+                *state.integrations.session_calendar.write().await = Some((session_id, SessionCalendarTag::from_event(&event)));
+            }
+            Ok(None) => log::debug!("No calendar event in progress for session {}", session_id),
+            Err(e) => log::warn!("Calendar lookup failed: {}", e),
+        }
+    });
+}
+
+/// Название/метки сессии, если для неё нашлась встреча
+pub async fn session_calendar_tag(
+    session_calendar: &tokio::sync::RwLock<Option<(u64, SessionCalendarTag)>>,
+    session_id: u64,
+) -> Option<SessionCalendarTag> {
+    session_calendar
+        .read()
+        .await
+        .as_ref()
+        .filter(|(id, _)| *id == session_id)
+        .map(|(_, tag)| tag.clone())
+}
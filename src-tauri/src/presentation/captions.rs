@@ -0,0 +1,158 @@
+//! Живые субтитры: полоса с текущими partial поверх всех окон, клики проходят насквозь.
+//!
+//! Режим запускается отдельной командой: при необходимости берёт вход с loopback/monitor
+//! устройства (системный звук), отключает авто-стоп по тишине и стартует обычную запись.
+//! Текст окно берёт из тех же событий transcription:partial/final, что и главное окно.
+
+use tauri::{AppHandle, LogicalSize, Manager, PhysicalPosition, Position, Size, WebviewUrl, WebviewWindowBuilder};
+
+use crate::domain::{CaptionsConfig, CaptionsPosition};
+use crate::presentation::overlay::{monitor_under_cursor, MonitorArea, MENU_BAR_OFFSET};
+use crate::presentation::AppState;
+
+pub const CAPTIONS_WINDOW_LABEL: &str = "live-captions";
+
+/// Доля ширины экрана под субтитры
+const CAPTIONS_WIDTH_RATIO: f64 = 0.8;
+
+/// Межстрочный интервал и внутренние отступы полосы (логические пиксели)
+const CAPTIONS_LINE_HEIGHT: f64 = 1.35;
+const CAPTIONS_PADDING: f64 = 16.0;
+
+/// Отступ полосы от нижнего края экрана (над dock/taskbar)
+const CAPTIONS_BOTTOM_OFFSET: f64 = 96.0;
+
+/// Активный сеанс субтитров
+#[derive(Debug, Clone, Default)]
+pub struct LiveCaptionsSession {
+    /// Вход на время субтитров; None — микрофон из настроек.
+    /// start_recording пересоздаёт захват на каждом старте, так что после остановки вход возвращается сам
+    pub source_device: Option<String>,
+}
+
+/// Левый верхний угол и размер полосы субтитров (физические пиксели)
+fn captions_frame(config: &CaptionsConfig, monitor: MonitorArea) -> (i32, i32, u32, u32) {
+    let scale = monitor.scale_factor;
+    let width = (monitor.width as f64 * CAPTIONS_WIDTH_RATIO).round() as u32;
+    let content = config.font_size as f64 * CAPTIONS_LINE_HEIGHT * config.max_lines as f64;
+    let height = ((content + CAPTIONS_PADDING * 2.0) * scale).round() as u32;
+
+    let x = monitor.x + (monitor.width as i32 - width as i32) / 2;
+    let y = match config.position {
+        CaptionsPosition::Top => monitor.y + (MENU_BAR_OFFSET * scale).round() as i32,
+        CaptionsPosition::Bottom => {
+            monitor.y + monitor.height as i32 - height as i32 - (CAPTIONS_BOTTOM_OFFSET * scale).round() as i32
+        }
+    };
+    (x, y.max(monitor.y), width, height)
+}
+
+/// Показывает окно субтитров. Повторный вызов пересоздаёт окно с новыми настройками
+pub fn show_captions_window(app_handle: &AppHandle, config: &CaptionsConfig) {
+    hide_captions_window(app_handle);
+
+    let config = config.clone().normalized();
+    let url = format!("captions.html?fontSize={}", config.font_size);
+    let built = WebviewWindowBuilder::new(app_handle, CAPTIONS_WINDOW_LABEL, WebviewUrl::App(url.into()))
+        .title("VoicetextAI — Live captions")
+        .resizable(false)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .always_on_top(true)
+        .visible_on_all_workspaces(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .visible(false)
+        .build();
+    let window = match built {
+        Ok(window) => window,
+        Err(e) => {
+            log::warn!("Failed to create live captions window: {}", e);
+            return;
+        }
+    };
+
+    // Субтитры не должны мешать работе с приложением под ними
+    if let Err(e) = window.set_ignore_cursor_events(true) {
+        log::debug!("Failed to make live captions click-through: {}", e);
+    }
+
+    if let Some(monitor) = monitor_under_cursor(app_handle) {
+        let area = MonitorArea::from(&monitor);
+        let (x, y, width, height) = captions_frame(&config, area);
+        let size = LogicalSize::new(width as f64 / area.scale_factor, height as f64 / area.scale_factor);
+        if let Err(e) = window.set_size(Size::Logical(size)) {
+            log::warn!("Failed to size live captions window: {}", e);
+        }
+        if let Err(e) = window.set_position(Position::Physical(PhysicalPosition { x, y })) {
+            log::warn!("Failed to position live captions window: {}", e);
+        }
+    }
+
+    if let Err(e) = window.show() {
+        log::warn!("Failed to show live captions window: {}", e);
+    }
+}
+
+/// Выключает режим субтитров (если был включён): запись после этого снова обычная диктовка
+pub async fn end_live_captions(app_handle: &AppHandle) -> bool {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return false;
+    };
+    let was_active = state.recording.live_captions.write().await.take().is_some();
+    if was_active {
+        hide_captions_window(app_handle);
+        log::info!("Live captions stopped");
+    }
+    was_active
+}
+
+/// Уничтожает окно субтитров (если оно было создано)
+pub fn hide_captions_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(CAPTIONS_WINDOW_LABEL) {
+        if let Err(e) = window.destroy() {
+            log::warn!("Failed to destroy live captions window: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL_HD: MonitorArea = MonitorArea {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+        scale_factor: 1.0,
+    };
+
+    #[test]
+    fn captions_frame_is_centered_and_on_screen() {
+        let config = CaptionsConfig::default();
+        let (x, y, width, height) = captions_frame(&config, FULL_HD);
+        assert_eq!(width, 1536);
+        assert_eq!(x, 192);
+        // 28 * 1.35 * 2 + 32 = 107.6
+        assert_eq!(height, 108);
+        assert_eq!(y, 1080 - 108 - 96);
+
+        let top = CaptionsConfig {
+            position: CaptionsPosition::Top,
+            ..CaptionsConfig::default()
+        };
+        let retina = MonitorArea {
+            x: -2880,
+            scale_factor: 2.0,
+            width: 2880,
+            height: 1800,
+            ..FULL_HD
+        };
+        let (x, y, _, height) = captions_frame(&top, retina);
+        assert_eq!(x, -2880 + 288);
+        assert_eq!(y, 72);
+        assert_eq!(height, 215);
+    }
+}
@@ -2,13 +2,102 @@ use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow, Window};
 
-use crate::domain::{AudioCapture, RecordingStatus, SttConnectionCategory, SttError};
-use crate::infrastructure::{AuthSession, AuthStore, AuthUser, ConfigStore};
+use crate::application::{
+    append_history, append_paste_audit, benchmark_configs, builtin_accuracy_scripts, compact_history, compute_usage_analytics,
+    export_history_text, filter_history, list_history_tags, page_history, session_history, paste_audit_entries, score_accuracy_test, AnalyticsRange, CorrectionEngine,
+    InstantPasteTracker, LatencySample, MeetingRecorder, PartialCoalescer, PartialEmission, PipelineTrace, SinkTextOutputRouter, UsageAnalytics,
+};
+use crate::domain::{
+    AccuracyScript, AccuracyTestResult, AppContext, AudioCapture, BenchmarkSample, ConnectionQualityReason, CorrectionEntry, FeedbackConfig, FeedbackEvent, FocusMode, GlossaryStatus, HistoryFilter, HistoryItem, HistoryPage, HistoryTagCount,
+    normalize_history_tags, LowConfidenceAction, PasteAppRule, PasteAuditEntry, PasteStrategy, TextCasing,
+    CalendarConfig, CalendarEvent, CalendarSource, CaptionsConfig, MeetingConfig, MeetingTranscript, MemoryAlarmConfig, MemoryReport, RecordingOverlayConfig,
+    RecordingProfile, RecordingStatus, SessionShare, SessionStats, SessionTimeline, StoragePaths, SinkDeliveryOutcome, SttConnectionCategory, SttError, SttSessionOverride,
+    TelemetryEvent, TelemetryEventKind, MAX_BENCHMARK_AUDIO_SECS, ProviderBenchmarkReport,
+    TextDelivery, TextOutputProfile, TextOutputRouter, TextOutputSink, TextOutputSinkConfig, TranscriptionJob,
+    UpdateChannel, UpdatePreferences, WarmPoolConfig, WindowBehaviorOnStop, MAX_PARTIAL_UPDATE_INTERVAL_MS,
+    DEFAULT_SHARE_TTL_HOURS, MAX_SHARE_TTL_HOURS, PasteCompatibility, PermissionCapability, PermissionStatus,
+};
+use crate::infrastructure::companion::{CompanionEvent, CompanionServerInfo};
+use crate::infrastructure::logging::{self, LogRecord};
+use crate::infrastructure::session_share::{encrypt_transcript, share_url_with_key, BackendShareClient, ShareError};
+use crate::infrastructure::speech;
+use crate::infrastructure::{
+    create_text_output_sinks, AuthSession, AuthStore, AuthUser, ClipboardSink, ConfigStore, FileSessionJournal,
+    TextOutputContext,
+};
+use crate::presentation::calendar::{
+    current_calendar_event, request_calendar_access as request_calendar_provider_access, session_calendar_tag,
+    tag_session_from_calendar,
+};
+use crate::presentation::captions::{
+    end_live_captions, hide_captions_window, show_captions_window, LiveCaptionsSession,
+};
+use crate::presentation::i18n::{connection_quality_reason, UiLanguage, UiMessage};
+use crate::presentation::meeting::end_meeting;
+use crate::presentation::overlay::{hide_recording_overlay, show_recording_overlay};
+use crate::presentation::shutdown::request_shutdown;
+use crate::presentation::connectivity::network_status;
+use crate::presentation::glossary::sync_team_glossary;
+use crate::presentation::telemetry::{record_telemetry, telemetry_preview};
+use crate::presentation::toggle_intent::QueuedToggle;
+use crate::presentation::transcription_jobs;
+use crate::presentation::api_schema::{api_schema, ApiSchema};
+use crate::presentation::requests::UpdateSttConfigArgs;
+use crate::presentation::state::{ConfigState, HeldTranscription, HistoryState, IntegrationState, RecordingState};
 use crate::presentation::{
     events::*, AppState, AudioLevelPayload, FinalTranscriptionPayload, PartialTranscriptionPayload,
     RecordingStatusPayload, MicrophoneTestLevelPayload, TranscriptionErrorPayload, ConnectionQualityPayload,
 };
 
+/// Добавляет финальную фразу в историю (сверх `max_items` вытесняются старые, кроме избранных)
+/// и при `keep_history` сохраняет историю на диск.
+///
+/// При `keep_history` в памяти остаются только превью прошлых записей, полные тексты уходят
+/// на диск и догружаются через get_history_page. Без `keep_history` хранить их негде.
+async fn push_history(
+    history: &tokio::sync::RwLock<Vec<HistoryItem>>,
+    transcription: crate::domain::Transcription,
+    max_items: usize,
+    keep_history: bool,
+) {
+    let (compacted, snapshot) = {
+        let mut history = history.write().await;
+        append_history(&mut history, transcription, max_items);
+        if !keep_history {
+            return;
+        }
+        (compact_history(&mut history), history.clone())
+    };
+    save_history_texts(&compacted).await;
+    save_history(&snapshot).await;
+}
+
+/// Полные тексты сжатых записей — до history.json, чтобы превью не осталось без текста
+pub(crate) async fn save_history_texts(compacted: &[(u64, crate::domain::HistoryFullText)]) {
+    for (id, full) in compacted {
+        if let Err(e) = ConfigStore::save_history_text(*id, full).await {
+            log::warn!("Failed to save history text for item {}: {}", id, e);
+        }
+    }
+}
+
+/// Догружает с диска полные тексты и журналы правок сжатых записей (если не вышло — остаётся превью)
+async fn load_history_texts(items: &mut [HistoryItem]) {
+    for item in items.iter_mut().filter(|item| item.text_truncated) {
+        match ConfigStore::load_history_text(item.id).await {
+            Ok(Some(full)) => item.restore(full),
+            Ok(None) => log::warn!("History text for item {} is missing on disk", item.id),
+            Err(e) => log::warn!("Failed to load history text for item {}: {}", item.id, e),
+        }
+    }
+}
+
+async fn save_history(items: &[HistoryItem]) {
+    if let Err(e) = ConfigStore::save_history(items).await {
+        log::warn!("Failed to save history: {}", e);
+    }
+}
+
 fn classify_transcription_error_type_from_stt(err: &SttError) -> String {
     // ВАЖНО: во фронте error_type используется для connect-retry, поэтому
     // тут нельзя делать "умный" парсинг строки — только типы и детали.
@@ -24,15 +113,44 @@ fn classify_transcription_error_type_from_stt(err: &SttError) -> String {
                 "connection".to_string()
             }
         }
-        SttError::Processing(_) | SttError::Unsupported(_) | SttError::Internal(_) => "processing".to_string(),
+        // Фронт распознаёт rate limit по error_details.category
+        SttError::RateLimited { .. } => "connection".to_string(),
+        SttError::QuotaExceeded { .. } => "limit_exceeded".to_string(),
+        SttError::Processing(_) | SttError::Unsupported(_) | SttError::Internal(_) | SttError::ProviderBug(_) => {
+            "processing".to_string()
+        }
     }
 }
 
+/// Обратный отсчёт до повтора после rate limit (UI показывает "повтор через 32с" и повторяет сам).
+/// Отсчёт прекращается, как только началась новая сессия записи.
+fn spawn_retry_countdown(app_handle: AppHandle, session_id: u64, secs: u64) {
+    tauri::async_runtime::spawn(async move {
+        let mut remaining_secs = secs;
+        loop {
+            let still_current = app_handle
+                .try_state::<AppState>()
+                .map(|state| state.active_transcription_session_id.load(Ordering::Relaxed) == session_id)
+                .unwrap_or(false);
+            if !still_current {
+                return;
+            }
+
+            let payload = RetryCountdownPayload { session_id, remaining_secs };
+            if let Err(e) = app_handle.emit(EVENT_TRANSCRIPTION_RETRY_COUNTDOWN, payload) {
+                log::warn!("Failed to emit retry countdown event: {}", e);
+            }
+            if remaining_secs == 0 {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            remaining_secs -= 1;
+        }
+    });
+}
+
 fn error_details_from_stt(err: &SttError) -> Option<TranscriptionErrorDetailsPayload> {
-    match err {
-        SttError::Connection(conn) => Some(conn.details.clone().into()),
-        _ => None,
-    }
+    err.connection_details().cloned().map(Into::into)
 }
 
 /// Start recording voice
@@ -43,6 +161,10 @@ pub async fn start_recording(
 ) -> Result<String, String> {
     log::info!("Command: start_recording");
 
+    if state.recording.shutting_down.load(Ordering::SeqCst) {
+        return Err(state.localize(UiMessage::AppShuttingDown).await);
+    }
+
     // На macOS при отсутствии разрешения на микрофон CoreAudio может отдавать "тишину" (все нули),
     // и UI будет выглядеть как "не записывает".
     // Поэтому проверяем статус и даём явную ошибку.
@@ -55,37 +177,95 @@ pub async fn start_recording(
         match microphone_permission_status() {
             MicrophonePermissionStatus::Authorized | MicrophonePermissionStatus::NotDetermined => {}
             _ => {
-                return Err(
-                    "Нет доступа к микрофону. Откройте macOS System Settings → Privacy & Security → Microphone и включите доступ для приложения."
-                        .to_string(),
-                );
+                return Err(state.localize(UiMessage::MicrophoneAccessDenied).await);
             }
         }
     }
 
     // Новый идентификатор сессии записи. Маркируем им все события transcription:* и recording:status,
     // чтобы frontend мог игнорировать "поздние" сообщения от предыдущей сессии.
+    // Override профиля доставки действует только на одну запись (deep link выставит его заново)
+    *state.integrations.output_profile_override.write().await = None;
+    // Окно нужно новой записи — скрытие после вставки прошлой сессии отменяется
+    state.hide_window_after_paste.store(false, Ordering::SeqCst);
+
     let session_id = state.transcription_session_seq.fetch_add(1, Ordering::Relaxed) + 1;
     state
         .active_transcription_session_id
         .store(session_id, Ordering::Relaxed);
+    logging::set_log_session_id(session_id);
     log::info!("Recording session started: session_id={}", session_id);
+    tag_session_from_calendar(&app_handle, session_id);
+    tag_session_with_app(&state, session_id).await;
+
+    // Режим фокусировки ОС: транскрибируем как обычно, но без оверлея и звуков
+    let focus_mode = quiet_focus_mode(&state).await;
+    let feedback_config = state.settings.config.read().await.feedback;
+    state.feedback.begin_session(feedback_config, focus_mode.is_some());
 
     let app_handle_clone = app_handle.clone();
-    let state_partial = state.partial_transcription.clone();
+    let state_partial = state.recording.partial_transcription.clone();
+    let companion_partial = state.integrations.companion.clone();
+    let (partial_update_interval_ms, instant_paste) = {
+        let config = state.settings.config.read().await;
+        (config.partial_update_interval_ms, config.auto_paste_text && config.instant_paste)
+    };
+    let instant_paste_tx = instant_paste.then(|| spawn_instant_paste_worker(app_handle.clone(), session_id));
+    let instant_paste_partial = instant_paste_tx.clone();
+    // По коалесеру на канал: в стерео-интервью у каждого канала своя строка partial'а
+    let partial_coalescer = Arc::new(std::sync::Mutex::new([
+        PartialCoalescer::new(partial_update_interval_ms),
+        PartialCoalescer::new(partial_update_interval_ms),
+    ]));
+    let coalescer_partial = partial_coalescer.clone();
 
     // Callback for partial transcriptions
     let on_partial = Arc::new(move |transcription: crate::domain::Transcription| {
         let text = transcription.text.clone();
+        let is_segment_final = transcription.is_final;
         let app_handle = app_handle_clone.clone();
         let state_partial = state_partial.clone();
+        let companion = companion_partial.clone();
+        let coalescer = coalescer_partial.clone();
+        let slot = transcription.channel_slot();
+
+        // Стабилизированный сегмент больше не меняется — в instant paste вставляем его сразу
+        if is_segment_final {
+            if let Some(tx) = instant_paste_partial.as_ref() {
+                let _ = tx.send(transcription.clone());
+            }
+        }
+
+        // В webview — не чаще partial_update_interval_ms; решаем здесь, пока partial'ы идут по порядку
+        let emission = coalescer.lock().unwrap_or_else(|e| e.into_inner())[slot]
+            .push(transcription, tokio::time::Instant::now());
 
         tokio::spawn(async move {
             // Update state
             *state_partial.write().await = Some(text.clone());
 
+            companion.publish(&CompanionEvent::Partial {
+                session_id,
+                text,
+                is_segment_final,
+            });
+
             // Emit event to frontend
-            let payload = PartialTranscriptionPayload::from_transcription(transcription, session_id);
+            let transcription = match emission {
+                PartialEmission::Now(transcription) => transcription,
+                PartialEmission::FlushAfter(delay) => {
+                    tokio::time::sleep(delay).await;
+                    let pending = coalescer.lock().unwrap_or_else(|e| e.into_inner())[slot]
+                        .take_pending(tokio::time::Instant::now());
+                    match pending {
+                        Some(transcription) => transcription,
+                        None => return,
+                    }
+                }
+                PartialEmission::Buffered => return,
+            };
+            let payload = PartialTranscriptionPayload::from_transcription(transcription, session_id)
+                .with_instant_paste(instant_paste);
             if let Err(e) = app_handle.emit(EVENT_TRANSCRIPTION_PARTIAL, payload) {
                 log::error!("Failed to emit partial transcription event: {}", e);
             }
@@ -93,36 +273,112 @@ pub async fn start_recording(
     });
 
     let app_handle_final = app_handle.clone();
-    let state_final = state.final_transcription.clone();
-    let state_history = state.history.clone();
-    let state_config = state.config.clone();
+    let state_final = state.recording.final_transcription.clone();
+    let state_history = state.user_data.history.clone();
+    let state_held = state.recording.held_transcriptions.clone();
+    let state_config = state.settings.config.clone();
+    let companion_final = state.integrations.companion.clone();
+    let meeting_final = state.recording.meeting.clone();
+    let session_calendar_final = state.integrations.session_calendar.clone();
+    let session_app_final = state.session_app_context.clone();
+    let coalescer_final = partial_coalescer;
 
     // Callback for final transcription
     let on_final = Arc::new(move |transcription: crate::domain::Transcription| {
+        // Отложенный partial после финальной фразы показал бы в UI устаревший текст
+        coalescer_final.lock().unwrap_or_else(|e| e.into_inner())[transcription.channel_slot()].discard_pending();
+
+        // В очередь вставки — синхронно, чтобы конец фразы не обогнал её сегменты
+        if let Some(tx) = instant_paste_tx.as_ref() {
+            let _ = tx.send(transcription.clone());
+        }
+
+        // Встреча — архив всего сказанного: пишем до фильтра low confidence (confidence сохраняется)
+        // и синхронно, чтобы фразы, пришедшие до конца stop_recording, попали в транскрипт
+        if let Ok(mut meeting) = meeting_final.lock() {
+            if let Some(recorder) = meeting.as_mut() {
+                recorder.push_final(&transcription);
+            }
+        }
+
         let text = transcription.text.clone();
         let app_handle = app_handle_final.clone();
         let state_final = state_final.clone();
         let state_history = state_history.clone();
+        let state_held = state_held.clone();
         let state_config = state_config.clone();
+        let companion = companion_final.clone();
+        let session_calendar = session_calendar_final.clone();
+        let session_app = session_app_final.clone();
 
         tokio::spawn(async move {
+            // Встреча из календаря (если нашлась) — название записи в истории, в т.ч. для отложенных фраз
+            let mut transcription = transcription;
+            transcription.calendar = session_calendar_tag(&session_calendar, session_id).await;
+            transcription.app = session_app_context(&session_app, session_id).await;
+
+            let (verdict, max_items, keep_history) = {
+                let config = state_config.read().await;
+                (
+                    config.low_confidence_verdict(&transcription),
+                    config.max_history_items,
+                    config.keep_history,
+                )
+            };
+
+            match verdict {
+                Some(LowConfidenceAction::Suppress) => {
+                    log::info!(
+                        "Suppressing low-confidence final transcription (confidence: {:?})",
+                        transcription.confidence
+                    );
+                    return;
+                }
+                Some(LowConfidenceAction::Hold) => {
+                    log::info!(
+                        "Holding low-confidence final transcription for review (confidence: {:?})",
+                        transcription.confidence
+                    );
+                    let mut held = state_held.write().await;
+                    let id = held.iter().map(|h| h.id).max().unwrap_or(0) + 1;
+                    held.push(HeldTranscription {
+                        id,
+                        session_id,
+                        transcription: transcription.clone(),
+                    });
+                    // Не копим бесконечно, если пользователь не разбирает очередь
+                    let len = held.len();
+                    if len > max_items {
+                        held.drain(0..len - max_items);
+                    }
+                    drop(held);
+
+                    let payload = HeldTranscriptionPayload::from_transcription(id, transcription, session_id);
+                    if let Err(e) = app_handle.emit(EVENT_TRANSCRIPTION_HELD, payload) {
+                        log::error!("Failed to emit held transcription event: {}", e);
+                    }
+                    return;
+                }
+                Some(LowConfidenceAction::Flag) | None => {}
+            }
+
             // Update state
             *state_final.write().await = Some(text.clone());
 
-            // Add to history
-            state_history.write().await.push(transcription.clone());
+            companion.publish(&CompanionEvent::Final {
+                session_id,
+                text: text.clone(),
+            });
 
-            // Keep only last N items
-            let max_items = state_config.read().await.max_history_items;
-            let mut history = state_history.write().await;
-            let len = history.len();
-            if len > max_items {
-                history.drain(0..len - max_items);
+            // Фраза могла целиком состоять из слов-паразитов — пустую в историю не пишем
+            if !transcription.text.trim().is_empty() {
+                push_history(&state_history, transcription.clone(), max_items, keep_history).await;
             }
-            drop(history);
 
             // Emit event to frontend
-            let payload = FinalTranscriptionPayload::from_transcription(transcription.clone(), session_id);
+            let payload = FinalTranscriptionPayload::from_transcription(transcription.clone(), session_id)
+                .with_low_confidence(verdict.is_some())
+                .with_instant_paste(instant_paste);
             if let Err(e) = app_handle.emit(EVENT_TRANSCRIPTION_FINAL, payload) {
                 log::error!("Failed to emit final transcription event: {}", e);
             }
@@ -152,28 +408,53 @@ pub async fn start_recording(
     });
 
     let app_handle_error = app_handle.clone();
+    let telemetry_provider = state.settings.config.read().await.stt.provider;
+    let telemetry_config = state.settings.config.clone();
+    let telemetry_queue = state.integrations.telemetry_queue.clone();
+    let feedback = state.feedback.clone();
 
     // Callback for error handling
     let on_error = Arc::new(move |err: SttError| {
+        feedback.notify(FeedbackEvent::Error);
         let app_handle = app_handle_error.clone();
+        let telemetry_config = telemetry_config.clone();
+        let telemetry_queue = telemetry_queue.clone();
 
         tokio::spawn(async move {
             let error_type = classify_transcription_error_type_from_stt(&err);
             let error_details = error_details_from_stt(&err);
             let error = err.to_string();
+            let retryable = err.is_retryable();
+            let retry_after_secs = err.retry_after().map(|d| d.as_secs());
+            let message_key = err.message_key().map(str::to_string);
 
             log::error!("STT error occurred: {} (type: {})", error, error_type);
 
+            let event = TelemetryEvent::new(
+                TelemetryEventKind::Error {
+                    provider: telemetry_provider,
+                    error_type: error_type.clone(),
+                },
+                chrono::Utc::now().timestamp_millis(),
+            );
+            record_telemetry(&telemetry_config, &telemetry_queue, event).await;
+
             // Emit error event to frontend
             let payload = TranscriptionErrorPayload {
                 session_id,
                 error,
                 error_type,
                 error_details,
+                retryable,
+                retry_after_secs,
+                message_key,
             };
             if let Err(e) = app_handle.emit(EVENT_TRANSCRIPTION_ERROR, payload) {
                 log::error!("Failed to emit transcription error event: {}", e);
             }
+            if let Some(secs) = retry_after_secs {
+                spawn_retry_countdown(app_handle.clone(), session_id, secs);
+            }
 
             // Emit Error status
             let _ = app_handle.emit(
@@ -182,6 +463,7 @@ pub async fn start_recording(
                     session_id,
                     status: RecordingStatus::Error,
                     stopped_via_hotkey: false,
+                    window_behavior: None,
                 },
             );
         });
@@ -190,12 +472,19 @@ pub async fn start_recording(
     let app_handle_quality = app_handle.clone();
 
     // Callback for connection quality updates
-    let on_connection_quality = Arc::new(move |quality: String, reason: Option<String>| {
+    let on_connection_quality = Arc::new(move |quality: String, reason: Option<ConnectionQualityReason>| {
         let app_handle = app_handle_quality.clone();
 
         tokio::spawn(async move {
             log::info!("Connection quality changed: {} (reason: {:?})", quality, reason);
 
+            // Причина приходит ключом — текст собираем на языке интерфейса
+            let lang = match app_handle.try_state::<AppState>() {
+                Some(state) => state.ui_language().await,
+                None => UiLanguage::default(),
+            };
+            let reason_text = reason.as_ref().map(|reason| connection_quality_reason(reason, lang));
+
             // Emit connection quality event to frontend
             let payload = ConnectionQualityPayload {
                 session_id,
@@ -205,7 +494,8 @@ pub async fn start_recording(
                     "Recovering" => crate::presentation::events::ConnectionQuality::Recovering,
                     _ => crate::presentation::events::ConnectionQuality::Good,
                 },
-                reason,
+                reason: reason_text,
+                reason_code: reason,
             };
 
             if let Err(e) = app_handle.emit(EVENT_CONNECTION_QUALITY, payload) {
@@ -214,6 +504,74 @@ pub async fn start_recording(
         });
     });
 
+    // Метрики задержек: новая сессия + стрим замеров в диагностическую панель
+    let latency_tracker = state.recording.transcription_service.latency_tracker();
+    latency_tracker.begin_session(session_id);
+    state.recording.transcription_service.pipeline_tracer().begin_session(session_id);
+    let app_handle_latency = app_handle.clone();
+    latency_tracker.set_listener(Some(Arc::new(move |sample: LatencySample| {
+        let payload = TranscriptionLatencyPayload {
+            session_id: sample.session_id,
+            kind: sample.kind,
+            latency_ms: sample.latency_ms,
+            avg_ms: sample.stats.avg_ms,
+            p95_ms: sample.stats.p95_ms,
+        };
+        if let Err(e) = app_handle_latency.emit(EVENT_TRANSCRIPTION_LATENCY, payload) {
+            log::debug!("Failed to emit transcription latency event: {}", e);
+        }
+    })));
+
+    // Статистика сессии: итог приходит при остановке записи
+    let stats_tracker = state.recording.transcription_service.session_stats_tracker();
+    stats_tracker.begin_session(session_id);
+    let app_handle_stats = app_handle.clone();
+    let state_session_stats = state.user_data.session_stats.clone();
+    let telemetry_config = state.settings.config.clone();
+    let telemetry_queue = state.integrations.telemetry_queue.clone();
+    let session_app_stats = state.session_app_context.clone();
+    stats_tracker.set_listener(Some(Arc::new(move |stats: SessionStats| {
+        let app_handle = app_handle_stats.clone();
+        let state_session_stats = state_session_stats.clone();
+        let session_app = session_app_stats.clone();
+        let telemetry_config = telemetry_config.clone();
+        let telemetry_queue = telemetry_queue.clone();
+
+        tokio::spawn(async move {
+            // Для аналитики по приложениям (в телеметрию не уходит)
+            let mut stats = stats;
+            stats.app = session_app_context(&session_app, stats.session_id)
+                .await
+                .map(|context| context.key().to_string());
+
+            log::info!(
+                "Session stats: session_id={}, words={}, duration={:.1}s, wpm={:.1}, speaking_ratio={:.2}",
+                stats.session_id,
+                stats.word_count,
+                stats.duration_secs,
+                stats.words_per_minute,
+                stats.speaking_ratio
+            );
+
+            let snapshot = {
+                let mut all = state_session_stats.write().await;
+                all.push(stats.clone());
+                crate::application::retain_recent_session_stats(&mut all, chrono::Utc::now().timestamp_millis());
+                all.clone()
+            };
+            if let Err(e) = ConfigStore::save_session_stats(&snapshot).await {
+                log::warn!("Failed to save session stats: {}", e);
+            }
+
+            let event = TelemetryEvent::session_completed(&stats, chrono::Utc::now().timestamp_millis());
+            record_telemetry(&telemetry_config, &telemetry_queue, event).await;
+
+            if let Err(e) = app_handle.emit(EVENT_SESSION_STATS, stats) {
+                log::error!("Failed to emit session stats event: {}", e);
+            }
+        });
+    })));
+
     // Emit Starting status immediately
     log::debug!("Emitting status: Starting (stopped_via_hotkey: false)");
     let _ = app_handle.emit(
@@ -222,18 +580,31 @@ pub async fn start_recording(
             session_id,
             status: RecordingStatus::Starting,
             stopped_via_hotkey: false,
+            window_behavior: None,
         },
     );
 
     // Если в настройках выбран "Default" (selected_audio_device=None), то при подключении/смене микрофона
     // системное устройство по умолчанию может измениться, а захват останется привязанным к старому девайсу.
     // Поэтому перед стартом записи пересоздаём audio capture по текущему конфигу.
-    let selected_device = state.config.read().await.selected_audio_device.clone();
+    // В режиме субтитров вход может быть свой (loopback системного звука).
+    let captions_device = state.recording
+        .live_captions
+        .read()
+        .await
+        .as_ref()
+        .and_then(|session| session.source_device.clone());
+    let selected_device = match captions_device {
+        Some(device) => Some(device),
+        None => state.settings.config.read().await.selected_audio_device.clone(),
+    };
     if let Err(e) = state
         .recreate_audio_capture_with_device(selected_device, app_handle.clone())
         .await
     {
-        let error_msg = format!("Не удалось инициализировать устройство записи: {}", e);
+        let error_msg = state
+            .localize(UiMessage::RecorderInitFailed { error: e.to_string() })
+            .await;
         let stt_err = SttError::Configuration(e.to_string());
         let error_type = classify_transcription_error_type_from_stt(&stt_err);
 
@@ -242,23 +613,28 @@ pub async fn start_recording(
             error: error_msg.clone(),
             error_type,
             error_details: error_details_from_stt(&stt_err),
+            retryable: stt_err.is_retryable(),
+            retry_after_secs: None,
+            message_key: stt_err.message_key().map(str::to_string),
         };
         if let Err(emit_err) = app_handle.emit(EVENT_TRANSCRIPTION_ERROR, payload) {
             log::error!("Failed to emit transcription error event: {}", emit_err);
         }
+        state.feedback.notify(FeedbackEvent::Error);
         let _ = app_handle.emit(
             EVENT_RECORDING_STATUS,
             RecordingStatusPayload {
                 session_id,
                 status: RecordingStatus::Error,
                 stopped_via_hotkey: false,
+                window_behavior: None,
             },
         );
         return Err(error_msg);
     }
 
     // Start recording (async - WebSocket connect, audio capture start)
-    let start_result = state
+    let start_result = state.recording
         .transcription_service
         .start_recording(
             on_partial,
@@ -285,10 +661,20 @@ pub async fn start_recording(
 
         // Сначала transcription:error, потом recording:status=Error (во фронте есть логика suppression/retry).
         on_error(stt);
+        if let Ok(mut queue) = state.toggle_intent.lock() {
+            queue.clear();
+        }
 
         return Err(error);
     }
 
+    if let Some(focus_mode) = focus_mode {
+        log::info!("OS focus mode {:?} is on - recording quietly", focus_mode);
+        let _ = app_handle.emit(EVENT_RECORDING_QUIET, RecordingQuietPayload { session_id, focus_mode });
+    }
+
+    state.feedback.notify(FeedbackEvent::RecordingStarted);
+
     // Emit Recording status after successful start
     log::debug!("Emitting status: Recording (stopped_via_hotkey: false)");
     let _ = app_handle.emit(
@@ -297,9 +683,20 @@ pub async fn start_recording(
             session_id,
             status: RecordingStatus::Recording,
             stopped_via_hotkey: false,
+            window_behavior: None,
         },
     );
 
+    if focus_mode.is_none() {
+        let overlay_config = state.settings.config.read().await.recording_overlay;
+        show_recording_overlay(&app_handle, &overlay_config);
+    }
+    let provider = state.settings.config.read().await.stt.provider;
+    spawn_recording_ticker(app_handle.clone(), session_id, provider);
+
+    // Хоткей нажали ещё во время Starting — останавливаем сразу
+    run_queued_toggle(&app_handle, RecordingStatus::Recording);
+
     Ok("Recording started".to_string())
 }
 
@@ -313,12 +710,17 @@ pub async fn stop_recording(
 
     let session_id = state.active_transcription_session_id.load(Ordering::Relaxed);
 
-    let result = state
+    let result = state.recording
         .transcription_service
         .stop_recording()
         .await
         .map_err(|e| e.to_string())?;
 
+    hide_recording_overlay(&app_handle);
+    state.feedback.notify(FeedbackEvent::RecordingStopped);
+    end_live_captions(&app_handle).await;
+    end_meeting(&app_handle);
+
     // Emit status change
     log::debug!("Emitting status: Idle (stopped_via_hotkey: false)");
     let _ = app_handle.emit(
@@ -327,19 +729,38 @@ pub async fn stop_recording(
             session_id,
             status: RecordingStatus::Idle,
             stopped_via_hotkey: false,
+            window_behavior: None,
         },
     );
+    run_queued_toggle(&app_handle, RecordingStatus::Idle);
 
     Ok(result)
 }
 
+/// Quit the app gracefully: stops an active recording, waits for its final results and saves state first
+#[tauri::command]
+pub async fn quit_app(app_handle: AppHandle) -> Result<(), String> {
+    log::info!("Command: quit_app");
+    request_shutdown(&app_handle);
+    Ok(())
+}
+
 /// Get current recording status
 #[tauri::command]
-pub async fn get_recording_status(state: State<'_, AppState>) -> Result<RecordingStatus, String> {
+pub async fn get_recording_status(state: State<'_, RecordingState>) -> Result<RecordingStatus, String> {
     log::debug!("Command: get_recording_status");
     Ok(state.transcription_service.get_status().await)
 }
 
+/// Provider and session internals (stream flags, buffered/sent audio, last error, task health) for diagnosing stuck recordings
+#[tauri::command]
+pub async fn get_debug_state(
+    state: State<'_, RecordingState>,
+) -> Result<crate::application::TranscriptionDebugState, String> {
+    log::debug!("Command: get_debug_state");
+    Ok(state.transcription_service.debug_state().await)
+}
+
 use tauri::{PhysicalPosition, Position};
 
 /// Показывает окно на активном мониторе (где находится курсор мыши) - для Window
@@ -424,7 +845,11 @@ where
 #[cfg(test)]
 mod snapshot_contract_tests {
     use super::{AppConfigSnapshotData, SnapshotEnvelope, SttConfigSnapshotData};
-    use crate::domain::SttProviderType;
+    use crate::domain::{
+        AudioEncoding, CalendarConfig, CaptionsConfig, FeedbackConfig, FillerRemovalConfig, LowConfidenceAction,
+        MeetingConfig, MemoryAlarmConfig, PasteStrategy, RecordingOverlayConfig, SttProviderType, TextCasing,
+        TextNormalizationConfig, WarmPoolConfig, WhisperDecodingConfig, WindowBehaviorOnStop,
+    };
 
     fn assert_absent(json: &str, needles: &[&str]) {
         for needle in needles {
@@ -447,6 +872,30 @@ mod snapshot_contract_tests {
                 auto_copy_to_clipboard: true,
                 auto_paste_text: false,
                 selected_audio_device: None,
+                min_confidence: Some(0.6),
+                low_confidence_action: LowConfidenceAction::Hold,
+                recording_overlay: RecordingOverlayConfig::default(),
+                telemetry_enabled: false,
+                paste_strategy: PasteStrategy::SmartAppend,
+                paste_app_rules: Vec::new(),
+                text_casing: TextCasing::Lower,
+                captions: CaptionsConfig::default(),
+                meeting: MeetingConfig::default(),
+                calendar: CalendarConfig::default(),
+                warm_pool: WarmPoolConfig::default(),
+                models_dir: None,
+                partial_update_interval_ms: 150,
+                instant_paste: true,
+                window_behavior_on_stop: WindowBehaviorOnStop::HideAfterDelay { secs: 3 },
+                respect_focus_mode: true,
+                capture_app_context: false,
+                spelling_hotkey: Some("CmdOrCtrl+Shift+S".to_string()),
+                feedback: FeedbackConfig {
+                    sounds_enabled: true,
+                    ..FeedbackConfig::default()
+                },
+                memory_alarms: MemoryAlarmConfig::default(),
+                mock_audio_file: None,
             },
         };
 
@@ -472,6 +921,30 @@ mod snapshot_contract_tests {
         assert!(data.contains_key("auto_copy_to_clipboard"));
         assert!(data.contains_key("auto_paste_text"));
         assert!(data.contains_key("selected_audio_device"));
+        assert_eq!(data.get("low_confidence_action").and_then(|x| x.as_str()), Some("hold"));
+        assert_eq!(data["recording_overlay"]["position"], "menu_bar");
+        assert_eq!(data["telemetry_enabled"], false);
+        assert_eq!(data["paste_strategy"], "smart_append");
+        assert!(data["paste_app_rules"].as_array().is_some_and(|rules| rules.is_empty()));
+        assert_eq!(data["text_casing"], "lower");
+        assert_eq!(data["captions"]["position"], "bottom");
+        assert_eq!(data["captions"]["font_size"], 28);
+        assert_eq!(data["meeting"]["topic_gap_secs"], 8.0);
+        assert_eq!(data["meeting"]["llm_topic_breaks"], false);
+        assert_eq!(data["calendar"]["enabled"], false);
+        assert_eq!(data["calendar"]["source"], "system");
+        assert_eq!(data["warm_pool"]["enabled"], false);
+        assert_eq!(data["warm_pool"]["lead_secs"], 90);
+        assert_eq!(data["partial_update_interval_ms"], 150);
+        assert_eq!(data["instant_paste"], true);
+        assert_eq!(data["window_behavior_on_stop"]["mode"], "hide_after_delay");
+        assert_eq!(data["window_behavior_on_stop"]["secs"], 3);
+        assert_eq!(data["respect_focus_mode"], true);
+        assert_eq!(data["capture_app_context"], false);
+        assert_eq!(data["spelling_hotkey"], "CmdOrCtrl+Shift+S");
+        assert_eq!(data["feedback"]["sounds_enabled"], true);
+        assert_eq!(data["feedback"]["volume"], 60);
+        assert_eq!(data["memory_alarms"]["audio_buffer_mb"], 64);
     }
 
     #[test]
@@ -489,6 +962,26 @@ mod snapshot_contract_tests {
                 model: None,
                 keep_connection_alive: true,
                 deepgram_keyterms: None,
+                audio_encoding: crate::domain::AudioEncoding::Linear16,
+                idle_teardown_secs: None,
+                prewarm_connection: false,
+                max_alternatives: 3,
+                normalization: crate::domain::TextNormalizationConfig::default(),
+                filler_removal: crate::domain::FillerRemovalConfig::default(),
+                punctuate_locally: false,
+                diarize: true,
+                batch_concurrency: 4,
+                short_clip_secs: 10,
+                keep_alive_ttl_secs: 300,
+                language_models: Vec::new(),
+                partial_stability: 0,
+                pre_roll_ms: 1500,
+                whisper: crate::domain::WhisperDecodingConfig::default(),
+                split_channels: false,
+                paragraph_pause_ms: 0,
+                voice_commands: true,
+                comparison_provider: Some(SttProviderType::WhisperLocal),
+                audio_chunking: Vec::new(),
             },
         };
 
@@ -506,6 +999,46 @@ mod snapshot_contract_tests {
         assert!(data.contains_key("keep_connection_alive"));
     }
 }
+
+/// Приложение, в котором начата запись: активное сейчас или запомненное перед показом нашего окна
+async fn tag_session_with_app(state: &AppState, session_id: u64) {
+    let context = if state.settings.config.read().await.capture_app_context {
+        match crate::infrastructure::active_window::foreground_app() {
+            Some(context) => Some(context),
+            None => state.last_focused_app.read().await.clone(),
+        }
+    } else {
+        None
+    };
+    if let Some(context) = context.as_ref() {
+        log::info!("Recording session {} started in {}", session_id, context.app_name);
+    }
+    *state.session_app_context.write().await = context.map(|context| (session_id, context));
+}
+
+/// Контекст приложения сессии, если он был записан для неё
+async fn session_app_context(
+    session_app: &tokio::sync::RwLock<Option<(u64, AppContext)>>,
+    session_id: u64,
+) -> Option<AppContext> {
+    session_app
+        .read()
+        .await
+        .as_ref()
+        .filter(|(id, _)| *id == session_id)
+        .map(|(_, context)| context.clone())
+}
+
+/// Запоминает приложение, активное перед показом окна записи (контекст сессии, если включён capture_app_context)
+async fn remember_focused_app(state: &AppState) {
+    if !state.settings.config.read().await.capture_app_context {
+        return;
+    }
+    if let Some(context) = crate::infrastructure::active_window::foreground_app() {
+        *state.last_focused_app.write().await = Some(context);
+    }
+}
+
 /// Toggle window visibility
 #[tauri::command]
 pub async fn toggle_window(
@@ -522,223 +1055,1288 @@ pub async fn toggle_window(
         #[cfg(target_os = "macos")]
         {
             if let Some(bundle_id) = crate::infrastructure::auto_paste::get_active_app_bundle_id() {
-                *state.last_focused_app_bundle_id.write().await = Some(bundle_id.clone());
+                *state.integrations.last_focused_app_bundle_id.write().await = Some(bundle_id.clone());
                 log::info!("Saved last focused app bundle ID: {}", bundle_id);
             }
         }
+        remember_focused_app(&state).await;
 
         show_window_on_active_monitor(&window)?;
 
         // Сообщаем фронту, что окно показано (для надёжного reset UI).
         // Не используем focus, т.к. main на macOS может быть nonactivating NSPanel.
         let _ = window.emit(EVENT_RECORDING_WINDOW_SHOWN, ());
+
+        spawn_prewarm(window.app_handle().clone());
     }
 
     Ok(())
 }
 
-/// Toggle recording and show window if hidden
+/// Warm-start в фоне: открывает соединение заранее, пока пользователь ещё не нажал запись.
+///
+/// Ничего не делает, если prewarm выключен в настройках или пользователь не авторизован
+/// (иначе backend отклонит соединение, а мы зря потратим попытку).
+fn spawn_prewarm(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app_handle.try_state::<AppState>() else {
+            return;
+        };
+        if !*state.is_authenticated.read().await {
+            return;
+        }
+        match state.recording.transcription_service.prewarm().await {
+            Ok(true) => log::info!("Recording connection prewarmed"),
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to prewarm recording connection: {}", e),
+        }
+    });
+}
+
+/// Prewarm the provider connection and audio device (e.g. when the hotkey modifier is held)
 #[tauri::command]
-pub async fn toggle_recording_with_window(
-    state: State<'_, AppState>,
-    window: Window,
-    app_handle: AppHandle,
-) -> Result<(), String> {
-    log::info!("Command: toggle_recording_with_window");
+pub async fn prewarm_recording(app_handle: AppHandle) -> Result<(), String> {
+    log::debug!("Command: prewarm_recording");
+    spawn_prewarm(app_handle);
+    Ok(())
+}
 
-    // Если пользователь не авторизован — не показываем recording окно.
-    // Иначе получается странное поведение: окно может получить фокус, но UI в нём "none" (скрыт правилами windowMode).
-    let is_authenticated = *state.is_authenticated.read().await;
-    if !is_authenticated {
-        log::info!("toggle_recording_with_window: user not authenticated -> redirect to auth window");
-        show_auth_window(app_handle).await?;
-        return Ok(());
+/// Get transcription latency metrics (current session + recent history)
+#[tauri::command]
+pub async fn get_transcription_metrics(
+    state: State<'_, RecordingState>,
+) -> Result<TranscriptionMetricsPayload, String> {
+    let tracker = state.transcription_service.latency_tracker();
+    Ok(TranscriptionMetricsPayload {
+        current: tracker.current(),
+        history: tracker.history(),
+    })
+}
+
+/// Where latency is spent per pipeline stage: the last session by default, or all recent sessions
+#[tauri::command]
+pub async fn get_pipeline_trace(
+    state: State<'_, RecordingState>,
+    last_session: Option<bool>,
+) -> Result<Vec<PipelineTrace>, String> {
+    let tracer = state.transcription_service.pipeline_tracer();
+    if last_session.unwrap_or(true) {
+        Ok(tracer.last_session().into_iter().collect())
+    } else {
+        Ok(tracer.sessions())
     }
+}
 
-    // Переключаем состояние записи
-    let current_status = state.transcription_service.get_status().await;
+/// Final phrases of a recording session with start/end times and offsets into the session audio
+/// (mono `pcm_s16le`, as written by the session journal). Works for the current and recent sessions.
+#[tauri::command]
+pub async fn export_session_timeline(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+    session_id: u64,
+) -> Result<SessionTimeline, String> {
+    log::info!("Command: export_session_timeline - session_id: {}", session_id);
+    let lang = settings.ui_language().await;
+    recording
+        .transcription_service
+        .session_timeline()
+        .timeline(session_id)
+        .ok_or_else(|| UiMessage::SessionNotFound { id: session_id }.text(lang))
+}
 
-    match current_status {
-        RecordingStatus::Idle => {
-            // Показываем окно если оно скрыто (не забираем фокус)
-            if !window.is_visible().map_err(|e| e.to_string())? {
-                // Перед показом окна сохраняем bundle ID текущего активного приложения
-                #[cfg(target_os = "macos")]
-                {
-                    if let Some(bundle_id) = crate::infrastructure::auto_paste::get_active_app_bundle_id() {
-                        *state.last_focused_app_bundle_id.write().await = Some(bundle_id.clone());
-                        log::info!("Saved last focused app bundle ID: {}", bundle_id);
-                    }
-                }
+/// Approximate sizes of audio buffers, history and batching queues, with alarm thresholds
+#[tauri::command]
+pub async fn get_memory_report(state: State<'_, AppState>) -> Result<MemoryReport, String> {
+    log::debug!("Command: get_memory_report");
+    Ok(crate::presentation::memory::memory_report(&state).await)
+}
 
-                show_window_on_active_monitor(&window)?;
+/// Where settings, history, session audio and models are stored for the current OS user
+#[tauri::command]
+pub async fn get_storage_paths() -> Result<StoragePaths, String> {
+    log::debug!("Command: get_storage_paths");
+    ConfigStore::storage_paths().map_err(|e| e.to_string())
+}
 
-                // Сообщаем фронту, что окно показано (для надёжного reset UI).
-                let _ = window.emit(EVENT_RECORDING_WINDOW_SHOWN, ());
-            }
+/// JSON Schema of event payloads and command responses (for TS bindings and external integrations)
+#[tauri::command]
+pub async fn get_api_schema() -> Result<ApiSchema, String> {
+    log::debug!("Command: get_api_schema");
+    Ok(api_schema())
+}
 
-            // Запускаем запись
-            start_recording(state.clone(), app_handle).await?;
-            log::info!("Recording started via hotkey");
-        }
-        RecordingStatus::Starting => {
-            // Запись еще запускается - игнорируем повторное нажатие
-            log::debug!("Ignoring toggle - recording is starting (WebSocket connecting, audio capture initializing)");
-        }
-        RecordingStatus::Recording => {
-            // Останавливаем запись
-            let _result = state
-                .transcription_service
-                .stop_recording()
-                .await
-                .map_err(|e| e.to_string())?;
+/// Последние записи лога для панели диагностики (от старых к новым)
+#[tauri::command]
+pub async fn get_recent_logs(n: Option<usize>) -> Result<Vec<LogRecord>, String> {
+    Ok(logging::recent_logs(n.unwrap_or(200)))
+}
 
-            log::info!("Recording stopped via hotkey");
+/// Текущий уровень логирования
+#[tauri::command]
+pub async fn get_log_level() -> Result<String, String> {
+    Ok(logging::current_log_level().to_string().to_lowercase())
+}
 
-            // Эмитируем статус Idle с флагом stopped_via_hotkey
-            // Frontend скроет окно когда получит этот статус
-            let session_id = state.active_transcription_session_id.load(Ordering::Relaxed);
-            log::info!("Emitting status: Idle (stopped_via_hotkey: TRUE) - window will auto-hide");
-            let _ = app_handle.emit(
-                EVENT_RECORDING_STATUS,
-                RecordingStatusPayload {
-                    session_id,
-                    status: RecordingStatus::Idle,
-                    stopped_via_hotkey: true,
-                },
-            );
-        }
-        RecordingStatus::Processing => {
-            // Игнорируем - запись уже останавливается
-            log::debug!("Ignoring toggle - recording is already being processed");
-        }
-        RecordingStatus::Error => {
-            log::warn!("Cannot toggle recording - system is in error state");
-        }
-    }
+/// Меняет уровень логирования на лету (до перезапуска приложения)
+#[tauri::command]
+pub async fn set_log_level(level: String) -> Result<String, String> {
+    let filter = logging::set_log_level(&level)?;
+    log::warn!("Log level changed to {}", filter);
+    Ok(filter.to_string().to_lowercase())
+}
 
-    Ok(())
+/// Статистика завершённых сессий записи (от старых к новым)
+#[tauri::command]
+pub async fn get_session_stats(state: State<'_, HistoryState>) -> Result<Vec<SessionStats>, String> {
+    log::debug!("Command: get_session_stats");
+    Ok(state.session_stats.read().await.clone())
 }
 
-/// Internal version for calling from hotkey handler (without State wrapper)
-pub async fn toggle_recording_with_window_internal(
-    state: &AppState,
-    window: tauri::WebviewWindow,
-    app_handle: AppHandle,
-) -> Result<(), String> {
-    log::info!("toggle_recording_with_window_internal (from hotkey)");
+/// Аналитика диктовки за период (по дням, в локальном часовом поясе) для графиков
+#[tauri::command]
+pub async fn get_analytics(
+    state: State<'_, HistoryState>,
+    range: Option<AnalyticsRange>,
+) -> Result<UsageAnalytics, String> {
+    let range = range.unwrap_or_default();
+    log::debug!("Command: get_analytics - range: {:?}", range);
+    let stats = state.session_stats.read().await;
+    Ok(compute_usage_analytics(&stats, range, &chrono::Local::now()))
+}
 
-    // Проверяем авторизацию - если не авторизован, показываем auth окно
-    let is_authenticated = *state.is_authenticated.read().await;
-    if !is_authenticated {
-        log::info!("User not authenticated - showing auth window");
-        if let Some(auth) = app_handle.get_webview_window("auth") {
-            auth.show().map_err(|e| e.to_string())?;
-            auth.set_focus().map_err(|e| e.to_string())?;
+/// Восстановленная после краша сессия записи
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecoveredSessionData {
+    /// Текст из final-сегментов, успевших прийти до краша
+    pub text: String,
+    /// Результат перераспознавания "хвоста" (если запрошено и удалось)
+    pub tail_text: Option<String>,
+    /// Ошибка перераспознавания (текст при этом всё равно восстановлен)
+    pub tail_error: Option<String>,
+    pub provider: String,
+    pub started_at: String,
+    pub audio_duration_ms: u64,
+    pub tail_duration_ms: u64,
+}
+
+/// Recover the last recording session if the app crashed/was killed mid-recording.
+///
+/// Возвращает None, если восстанавливать нечего. Сессия остаётся на диске до `discard_recovered_session`,
+/// чтобы при неудачном перераспознавании можно было попробовать ещё раз.
+#[tauri::command]
+pub async fn recover_last_session(
+    state: State<'_, RecordingState>,
+    retranscribe_tail: Option<bool>,
+) -> Result<Option<RecoveredSessionData>, String> {
+    log::info!("Command: recover_last_session (retranscribe_tail: {:?})", retranscribe_tail);
+
+    let dir = ConfigStore::recovery_dir().map_err(|e| e.to_string())?;
+    let Some(session) = FileSessionJournal::load_recovered(&dir).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+
+    let mut tail_text = None;
+    let mut tail_error = None;
+    if retranscribe_tail.unwrap_or(false) && session.tail_duration_ms() > 0 {
+        let result = match session.read_tail_samples() {
+            Ok(samples) => state
+                .transcription_service
+                .transcribe_samples(&samples, session.meta.sample_rate)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match result {
+            Ok(text) => tail_text = Some(text),
+            Err(e) => {
+                log::warn!("Failed to re-transcribe recovered session tail: {}", e);
+                tail_error = Some(e);
+            }
         }
-        return Ok(());
     }
 
-    let current_status = state.transcription_service.get_status().await;
+    Ok(Some(RecoveredSessionData {
+        text: session.text(),
+        tail_text,
+        tail_error,
+        provider: session.meta.provider.clone(),
+        started_at: ms_to_rfc3339(session.meta.started_at_ms),
+        audio_duration_ms: session.audio_duration_ms(),
+        tail_duration_ms: session.tail_duration_ms(),
+    }))
+}
 
-    match current_status {
-        RecordingStatus::Idle => {
-            // Показываем окно если оно скрыто
-            if !window.is_visible().map_err(|e| e.to_string())? {
-                #[cfg(target_os = "macos")]
-                {
-                    if let Some(bundle_id) = crate::infrastructure::auto_paste::get_active_app_bundle_id() {
-                        *state.last_focused_app_bundle_id.write().await = Some(bundle_id.clone());
-                        log::info!("Saved last focused app bundle ID: {}", bundle_id);
+/// Discard the recovered session (after the user accepted or dismissed it)
+#[tauri::command]
+pub async fn discard_recovered_session() -> Result<(), String> {
+    log::info!("Command: discard_recovered_session");
+    let dir = ConfigStore::recovery_dir().map_err(|e| e.to_string())?;
+    FileSessionJournal::discard_recovered(&dir).map_err(|e| e.to_string())
+}
+
+/// Частота PCM после read_audio_file / bundled_benchmark_sample
+const BENCHMARK_SAMPLE_RATE: u32 = 16000;
+
+/// Compare STT providers on one audio sample: recognized texts side by side with latency.
+///
+/// Без `providers` сравниваются текущий провайдер и провайдеры профилей записи.
+/// Язык — встроенного сэмпла или, для файла пользователя, `language` / язык из настроек.
+#[tauri::command]
+pub async fn benchmark_providers(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+    sample: Option<BenchmarkSample>,
+    providers: Option<Vec<SttProviderType>>,
+    language: Option<String>,
+) -> Result<ProviderBenchmarkReport, String> {
+    use crate::infrastructure::audio::{bundled_benchmark_sample, read_audio_file, BUNDLED_BENCHMARK_SAMPLE_LANGUAGE};
+
+    let sample = sample.unwrap_or_default();
+    log::info!("Command: benchmark_providers - sample: {:?}, providers: {:?}", sample, providers);
+
+    let base = recording.transcription_service.get_config().await;
+    let (default_language, decoded) = match sample {
+        BenchmarkSample::Bundled => (
+            BUNDLED_BENCHMARK_SAMPLE_LANGUAGE.to_string(),
+            tokio::task::spawn_blocking(bundled_benchmark_sample).await,
+        ),
+        BenchmarkSample::File { path } => (
+            base.language.clone(),
+            tokio::task::spawn_blocking(move || read_audio_file(std::path::Path::new(&path))).await,
+        ),
+    };
+    let mut samples = decoded
+        .map_err(|e| format!("Failed to join decoding task: {}", e))?
+        .map_err(|e| e.to_string())?;
+    samples.truncate((MAX_BENCHMARK_AUDIO_SECS * BENCHMARK_SAMPLE_RATE) as usize);
+
+    let language = language
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .unwrap_or(default_language);
+
+    let overrides: Vec<SttSessionOverride> = match providers {
+        Some(providers) => providers
+            .into_iter()
+            .map(|provider| SttSessionOverride {
+                provider: Some(provider),
+                ..Default::default()
+            })
+            .collect(),
+        None => {
+            let config = settings.config.read().await;
+            std::iter::once(SttSessionOverride::default())
+                .chain(config.recording_profiles.iter().map(|p| p.stt.clone()))
+                .collect()
+        }
+    };
+    let configs = benchmark_configs(&base, &overrides, &language);
+    let results =
+        crate::application::benchmark_providers(&recording.transcription_service, &configs, &samples, BENCHMARK_SAMPLE_RATE)
+            .await;
+
+    Ok(ProviderBenchmarkReport {
+        language,
+        audio_ms: samples.len() as u64 * 1000 / BENCHMARK_SAMPLE_RATE as u64,
+        results,
+    })
+}
+
+/// Фразы, отложенные из-за низкой уверенности (от старых к новым)
+#[tauri::command]
+pub async fn get_held_transcriptions(
+    state: State<'_, RecordingState>,
+) -> Result<Vec<HeldTranscriptionPayload>, String> {
+    log::debug!("Command: get_held_transcriptions");
+    let held = state.held_transcriptions.read().await;
+    Ok(held
+        .iter()
+        .map(|h| HeldTranscriptionPayload::from_transcription(h.id, h.transcription.clone(), h.session_id))
+        .collect())
+}
+
+/// Пользователь подтвердил отложенную фразу: убираем её из очереди и добавляем в историю.
+///
+/// Возвращает фразу в формате transcription:final (с `low_confidence: true`), чтобы фронт
+/// мог доставить её так же, как обычный финальный текст. None — фраза уже разобрана.
+#[tauri::command]
+pub async fn release_held_transcription(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+    history: State<'_, HistoryState>,
+    id: u64,
+) -> Result<Option<FinalTranscriptionPayload>, String> {
+    log::info!("Command: release_held_transcription - id: {}", id);
+
+    let released = {
+        let mut held = recording.held_transcriptions.write().await;
+        let Some(pos) = held.iter().position(|h| h.id == id) else {
+            return Ok(None);
+        };
+        held.remove(pos)
+    };
+
+    let (max_items, keep_history) = {
+        let config = settings.config.read().await;
+        (config.max_history_items, config.keep_history)
+    };
+    *recording.final_transcription.write().await = Some(released.transcription.text.clone());
+    push_history(&history.history, released.transcription.clone(), max_items, keep_history).await;
+
+    Ok(Some(
+        FinalTranscriptionPayload::from_transcription(released.transcription, released.session_id)
+            .with_low_confidence(true),
+    ))
+}
+
+/// Отбросить отложенную фразу
+#[tauri::command]
+pub async fn discard_held_transcription(state: State<'_, RecordingState>, id: u64) -> Result<bool, String> {
+    log::info!("Command: discard_held_transcription - id: {}", id);
+    let mut held = state.held_transcriptions.write().await;
+    let len = held.len();
+    held.retain(|h| h.id != id);
+    Ok(held.len() != len)
+}
+
+async fn correction_engine(state: &AppState) -> Result<Arc<CorrectionEngine>, String> {
+    match state.recording.transcription_service.correction_engine() {
+        Some(engine) => Ok(engine),
+        None => Err(state.localize(UiMessage::CorrectionsUnavailable).await),
+    }
+}
+
+/// Выученные исправления (wrong → right)
+#[tauri::command]
+pub async fn get_corrections(state: State<'_, AppState>) -> Result<Vec<CorrectionEntry>, String> {
+    log::debug!("Command: get_corrections");
+    Ok(correction_engine(&state).await?.entries())
+}
+
+/// Пользователь исправил слово в UI: запоминаем пару и применяем её к следующим фразам
+#[tauri::command]
+pub async fn learn_correction(
+    state: State<'_, AppState>,
+    wrong: String,
+    right: String,
+) -> Result<CorrectionEntry, String> {
+    log::info!("Command: learn_correction - '{}' -> '{}'", wrong, right);
+    correction_engine(&state).await?
+        .learn(&wrong, &right)
+        .map_err(|e| e.to_string())
+}
+
+/// Пользователь отредактировал финальную фразу целиком: выучиваем изменённый фрагмент (если он короткий)
+#[tauri::command]
+pub async fn learn_corrections_from_edit(
+    state: State<'_, AppState>,
+    original: String,
+    edited: String,
+) -> Result<Vec<CorrectionEntry>, String> {
+    log::info!("Command: learn_corrections_from_edit");
+    correction_engine(&state).await?
+        .learn_from_edit(&original, &edited)
+        .map_err(|e| e.to_string())
+}
+
+/// Удалить исправление по исходному слову
+#[tauri::command]
+pub async fn remove_correction(state: State<'_, AppState>, wrong: String) -> Result<bool, String> {
+    log::info!("Command: remove_correction - '{}'", wrong);
+    correction_engine(&state).await?
+        .remove(&wrong)
+        .map_err(|e| e.to_string())
+}
+
+/// Результат замены последней финальной фразы на альтернативную гипотезу
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ReplacedFinalData {
+    /// Текст, который был заменён (фронт ищет его в поле ввода/вставленном тексте)
+    pub previous_text: String,
+    /// Фраза после замены; бывшая основная гипотеза теперь среди alternatives
+    pub transcription: FinalTranscriptionPayload,
+}
+
+/// Подставить альтернативную гипотезу вместо последней финальной фразы.
+///
+/// Замена обратима: повторный вызов с тем же индексом возвращает исходный текст.
+#[tauri::command]
+pub async fn replace_last_final(
+    state: State<'_, AppState>,
+    alternative_index: usize,
+) -> Result<ReplacedFinalData, String> {
+    log::info!("Command: replace_last_final - alternative_index: {}", alternative_index);
+
+    let lang = state.ui_language().await;
+    let keep_history = state.settings.config.read().await.keep_history;
+    let mut history = state.user_data.history.write().await;
+    let last = history
+        .last_mut()
+        .map(|item| &mut item.transcription)
+        .ok_or_else(|| UiMessage::NoFinalPhrase.text(lang))?;
+
+    let previous_text = last.swap_alternative(alternative_index).ok_or_else(|| {
+        UiMessage::AlternativeNotFound {
+            index: alternative_index,
+            available: last.alternatives.len(),
+        }
+        .text(lang)
+    })?;
+    let transcription = last.clone();
+    let snapshot = keep_history.then(|| history.clone());
+    drop(history);
+    if let Some(snapshot) = snapshot {
+        save_history(&snapshot).await;
+    }
+
+    *state.recording.final_transcription.write().await = Some(transcription.text.clone());
+
+    let session_id = state.active_transcription_session_id.load(Ordering::Relaxed);
+    Ok(ReplacedFinalData {
+        previous_text,
+        transcription: FinalTranscriptionPayload::from_transcription(transcription, session_id),
+    })
+}
+
+/// Read text aloud with the OS speech synthesizer (AVSpeechSynthesizer / SAPI / speech-dispatcher).
+///
+/// Чтобы проверить распознанное на слух перед вставкой. Голос — по языку распознавания
+/// (при автоопределении — голос системы); новая фраза прерывает предыдущую.
+#[tauri::command]
+pub async fn speak_text(state: State<'_, ConfigState>, text: String) -> Result<(), String> {
+    log::info!("Command: speak_text - {} chars", text.chars().count());
+
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err(state.localize(UiMessage::NothingToSpeak).await);
+    }
+    let language = {
+        let config = state.config.read().await;
+        if config.stt.auto_detect_language {
+            None
+        } else {
+            speech::speech_language(&config.stt.language)
+        }
+    };
+
+    let result = tokio::task::spawn_blocking(move || speech::speak(&text, language.as_deref()))
+        .await
+        .map_err(|e| format!("Speech task failed: {}", e))?;
+    if let Err(e) = result {
+        log::warn!("Failed to speak text: {:#}", e);
+        return Err(state.localize(UiMessage::SpeechFailed { error: e.to_string() }).await);
+    }
+    Ok(())
+}
+
+/// Stop reading text aloud
+#[tauri::command]
+pub async fn stop_speaking() -> Result<(), String> {
+    log::info!("Command: stop_speaking");
+    tokio::task::spawn_blocking(speech::stop_speaking)
+        .await
+        .map_err(|e| format!("Speech task failed: {}", e))
+}
+
+/// История диктовок под фильтром (метка, только избранное), новые первыми.
+///
+/// У сжатых записей (`text_truncated`) только превью текста — полный текст отдаёт get_history_page.
+#[tauri::command]
+pub async fn get_history(
+    state: State<'_, HistoryState>,
+    filter: Option<HistoryFilter>,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryItem>, String> {
+    let filter = filter.unwrap_or_default();
+    log::debug!("Command: get_history - filter: {:?}, limit: {:?}", filter, limit);
+    let history = state.history.read().await;
+    Ok(filter_history(&history, &filter, limit))
+}
+
+/// Страница истории под фильтром, новые первыми, с полными текстами записей (догружаются с диска)
+#[tauri::command]
+pub async fn get_history_page(
+    state: State<'_, HistoryState>,
+    offset: usize,
+    limit: usize,
+    filter: Option<HistoryFilter>,
+) -> Result<HistoryPage, String> {
+    let filter = filter.unwrap_or_default();
+    log::debug!("Command: get_history_page - offset: {}, limit: {}, filter: {:?}", offset, limit, filter);
+    let mut page = page_history(&state.history.read().await, &filter, offset, limit);
+    load_history_texts(&mut page.items).await;
+    Ok(page)
+}
+
+/// Запись истории с полным текстом (догружается с диска) и историей правок
+#[tauri::command]
+pub async fn get_history_item(
+    history: State<'_, HistoryState>,
+    config: State<'_, ConfigState>,
+    id: u64,
+) -> Result<HistoryItem, String> {
+    log::debug!("Command: get_history_item - id: {}", id);
+    let item = history.history.read().await.iter().find(|item| item.id == id).cloned();
+    let Some(item) = item else {
+        return Err(config.localize(UiMessage::HistoryItemNotFound { id }).await);
+    };
+    let mut items = [item];
+    load_history_texts(&mut items).await;
+    let [item] = items;
+    Ok(item)
+}
+
+/// Сохранить текст записи истории из редактора.
+///
+/// Прежний текст остаётся в `edits` (не больше MAX_HISTORY_EDITS версий), изменённый фрагмент
+/// выучивается движком исправлений, другие окна получают `history:item-updated`.
+#[tauri::command]
+pub async fn update_history_item_text(
+    history: State<'_, HistoryState>,
+    config: State<'_, ConfigState>,
+    recording: State<'_, RecordingState>,
+    app_handle: AppHandle,
+    window: Window,
+    id: u64,
+    text: String,
+) -> Result<HistoryItem, String> {
+    log::info!("Command: update_history_item_text - id: {}, chars: {}", id, text.chars().count());
+    let keep_history = config.config.read().await.keep_history;
+
+    // Правка заменяет полный текст, а не превью: у сжатой записи читаем его с диска до блокировки
+    let truncated = history
+        .history
+        .read()
+        .await
+        .iter()
+        .any(|item| item.id == id && item.text_truncated);
+    let full = if truncated {
+        ConfigStore::load_history_text(id).await.map_err(|e| e.to_string())?
+    } else {
+        None
+    };
+
+    let (item, previous, compacted, snapshot) = {
+        let mut history = history.history.write().await;
+        let Some(item) = history.iter_mut().find(|item| item.id == id) else {
+            drop(history);
+            return Err(config.localize(UiMessage::HistoryItemNotFound { id }).await);
+        };
+        if item.text_truncated {
+            match full {
+                Some(full) => item.restore(full),
+                None => return Err(format!("History text for item {} is missing on disk", id)),
+            }
+        }
+        let previous = item.edit_text(&text, chrono::Utc::now().timestamp());
+        let edited = item.clone();
+        // Журнал правок уходит в файл записи вместе с полным текстом, в памяти остаётся превью
+        let compacted = if keep_history && previous.is_some() { item.compact() } else { None };
+        let snapshot = (keep_history && previous.is_some()).then(|| history.clone());
+        (edited, previous, compacted, snapshot)
+    };
+    let Some(previous) = previous else {
+        return Ok(item);
+    };
+
+    if let Some(full) = compacted {
+        save_history_texts(&[(id, full)]).await;
+    }
+    if let Some(snapshot) = snapshot {
+        save_history(&snapshot).await;
+    }
+
+    if let Some(engine) = recording.transcription_service.correction_engine() {
+        match engine.learn_from_edit(&previous, &item.transcription.text) {
+            Ok(learned) if !learned.is_empty() => {
+                log::info!("Learned {} correction(s) from history item {} edit", learned.len(), id)
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to learn corrections from history item {} edit: {}", id, e),
+        }
+    }
+
+    let _ = app_handle.emit(
+        EVENT_HISTORY_ITEM_UPDATED,
+        crate::presentation::HistoryItemUpdatedPayload {
+            id,
+            text: item.transcription.text.clone(),
+            edited_at: item.edited_at,
+            source_id: Some(window.label().to_string()),
+        },
+    );
+    Ok(item)
+}
+
+/// Заменить метки записи истории (метки нормализуются: без '#', пустых и повторов)
+#[tauri::command]
+pub async fn tag_history_item(
+    history: State<'_, HistoryState>,
+    config: State<'_, ConfigState>,
+    id: u64,
+    tags: Vec<String>,
+) -> Result<HistoryItem, String> {
+    log::info!("Command: tag_history_item - id: {}, tags: {}", id, tags.len());
+    update_history_item(&history, &config, id, |item| item.tags = normalize_history_tags(&tags)).await
+}
+
+/// Отметить запись истории как избранную (избранное не вытесняется лимитом истории)
+#[tauri::command]
+pub async fn set_history_item_favorite(
+    history: State<'_, HistoryState>,
+    config: State<'_, ConfigState>,
+    id: u64,
+    favorite: bool,
+) -> Result<HistoryItem, String> {
+    log::info!("Command: set_history_item_favorite - id: {}, favorite: {}", id, favorite);
+    update_history_item(&history, &config, id, |item| item.favorite = favorite).await
+}
+
+async fn update_history_item(
+    history_state: &HistoryState,
+    config: &ConfigState,
+    id: u64,
+    update: impl FnOnce(&mut HistoryItem),
+) -> Result<HistoryItem, String> {
+    let keep_history = config.config.read().await.keep_history;
+    let (item, snapshot) = {
+        let mut history = history_state.history.write().await;
+        let Some(item) = history.iter_mut().find(|item| item.id == id) else {
+            drop(history);
+            return Err(config.localize(UiMessage::HistoryItemNotFound { id }).await);
+        };
+        update(item);
+        let item = item.clone();
+        (item, keep_history.then(|| history.clone()))
+    };
+    if let Some(snapshot) = snapshot {
+        save_history(&snapshot).await;
+    }
+    Ok(item)
+}
+
+/// Все метки истории с числом записей, частые первыми
+#[tauri::command]
+pub async fn list_tags(state: State<'_, HistoryState>) -> Result<Vec<HistoryTagCount>, String> {
+    log::debug!("Command: list_tags");
+    Ok(list_history_tags(&state.history.read().await))
+}
+
+/// Экспорт истории под фильтром (например, всё с меткой "blog") в текст, по времени
+#[tauri::command]
+pub async fn export_history(state: State<'_, HistoryState>, filter: Option<HistoryFilter>) -> Result<String, String> {
+    let filter = filter.unwrap_or_default();
+    log::info!("Command: export_history - filter: {:?}", filter);
+    let mut items = filter_history(&state.history.read().await, &filter, None);
+    load_history_texts(&mut items).await;
+    Ok(export_history_text(&items, &chrono::Local))
+}
+
+/// Share the transcript of a recording session through an expiring link.
+///
+/// Текст шифруется на устройстве; ключ есть только во fragment возвращаемой ссылки,
+/// бэкенд хранит шифротекст. `ttl_hours` — срок жизни ссылки (по умолчанию сутки, не больше недели).
+#[tauri::command]
+pub async fn share_session(
+    state: State<'_, AppState>,
+    session_id: u64,
+    ttl_hours: Option<u32>,
+) -> Result<SessionShare, String> {
+    log::info!("Command: share_session - session_id: {}, ttl_hours: {:?}", session_id, ttl_hours);
+    let lang = state.ui_language().await;
+
+    let stats = state.user_data
+        .session_stats
+        .read()
+        .await
+        .iter()
+        .find(|stats| stats.session_id == session_id)
+        .cloned()
+        .ok_or_else(|| UiMessage::SessionNotFound { id: session_id }.text(lang))?;
+    let mut items = session_history(&state.user_data.history.read().await, &stats);
+    load_history_texts(&mut items).await;
+    let text = items
+        .iter()
+        .map(|item| item.transcription.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.is_empty() {
+        return Err(UiMessage::SessionTranscriptEmpty.text(lang));
+    }
+
+    let client = share_client(&state).await?;
+    let ttl_hours = ttl_hours.unwrap_or(DEFAULT_SHARE_TTL_HOURS).clamp(1, MAX_SHARE_TTL_HOURS);
+    let transcript = encrypt_transcript(&text).map_err(|e| share_error(e, lang))?;
+    let created = client
+        .create(&transcript, ttl_hours as u64 * 3600)
+        .await
+        .map_err(|e| share_error(e, lang))?;
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let share = SessionShare {
+        share_id: created.id,
+        session_id,
+        url: share_url_with_key(&created.url, &transcript.key),
+        created_at_ms: now_ms,
+        expires_at_ms: now_ms + ttl_hours as i64 * 3600 * 1000,
+    };
+    log::info!("Session {} shared until {}", session_id, ms_to_rfc3339(share.expires_at_ms));
+
+    let mut shares = active_session_shares(now_ms).await;
+    shares.push(share.clone());
+    if let Err(e) = ConfigStore::save_session_shares(&shares).await {
+        log::warn!("Failed to save session shares: {}", e);
+    }
+    Ok(share)
+}
+
+/// Revoke a share link before it expires
+#[tauri::command]
+pub async fn revoke_session_share(state: State<'_, AppState>, share_id: String) -> Result<(), String> {
+    log::info!("Command: revoke_session_share - {}", share_id);
+    let lang = state.ui_language().await;
+
+    match share_client(&state).await?.revoke(&share_id).await {
+        // Ссылка уже истекла или отозвана с другого устройства
+        Ok(()) | Err(ShareError::NotFound) => {}
+        Err(e) => return Err(share_error(e, lang)),
+    }
+
+    let mut shares = active_session_shares(chrono::Utc::now().timestamp_millis()).await;
+    shares.retain(|share| share.share_id != share_id);
+    ConfigStore::save_session_shares(&shares)
+        .await
+        .map_err(|e| format!("Failed to save session shares: {}", e))
+}
+
+/// Share links that have not expired yet, newest first
+#[tauri::command]
+pub async fn list_session_shares() -> Result<Vec<SessionShare>, String> {
+    let mut shares = active_session_shares(chrono::Utc::now().timestamp_millis()).await;
+    shares.reverse();
+    Ok(shares)
+}
+
+async fn active_session_shares(now_ms: i64) -> Vec<SessionShare> {
+    let mut shares = ConfigStore::load_session_shares().await.unwrap_or_else(|e| {
+        log::warn!("Failed to load session shares: {}", e);
+        Vec::new()
+    });
+    shares.retain(|share| !share.is_expired(now_ms));
+    shares
+}
+
+async fn share_client(state: &AppState) -> Result<BackendShareClient, String> {
+    let access_token = state
+        .auth_store
+        .read()
+        .await
+        .session
+        .as_ref()
+        .map(|s| s.access_token.clone());
+    let Some(access_token) = access_token else {
+        return Err(state.localize(UiMessage::ShareSignInRequired).await);
+    };
+    Ok(BackendShareClient::new(AppState::get_api_base_url(), access_token))
+}
+
+fn share_error(error: ShareError, lang: UiLanguage) -> String {
+    match error {
+        ShareError::Unauthenticated => UiMessage::ShareSignInRequired.text(lang),
+        error => UiMessage::ShareFailed { error: error.to_string() }.text(lang),
+    }
+}
+
+/// Toggle recording and show window if hidden
+#[tauri::command]
+pub async fn toggle_recording_with_window(
+    state: State<'_, AppState>,
+    window: Window,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Command: toggle_recording_with_window");
+
+    // Если пользователь не авторизован — не показываем recording окно.
+    // Иначе получается странное поведение: окно может получить фокус, но UI в нём "none" (скрыт правилами windowMode).
+    let is_authenticated = *state.is_authenticated.read().await;
+    if !is_authenticated {
+        log::info!("toggle_recording_with_window: user not authenticated -> redirect to auth window");
+        show_auth_window(app_handle).await?;
+        return Ok(());
+    }
+
+    // Переключаем состояние записи
+    let current_status = state.recording.transcription_service.get_status().await;
+
+    match current_status {
+        RecordingStatus::Idle => {
+            // Показываем окно если оно скрыто (не забираем фокус)
+            if !window.is_visible().map_err(|e| e.to_string())? {
+                // Перед показом окна сохраняем bundle ID текущего активного приложения
+                #[cfg(target_os = "macos")]
+                {
+                    if let Some(bundle_id) = crate::infrastructure::auto_paste::get_active_app_bundle_id() {
+                        *state.integrations.last_focused_app_bundle_id.write().await = Some(bundle_id.clone());
+                        log::info!("Saved last focused app bundle ID: {}", bundle_id);
                     }
                 }
-                show_webview_window_on_active_monitor(&window)?;
+                remember_focused_app(&state).await;
+
+                // Режим фокусировки ОС: пишем, но окно не показываем (причину UI узнает из recording:quiet)
+                if let Some(focus_mode) = quiet_focus_mode(&state).await {
+                    log::info!("OS focus mode {:?} is on - recording window stays hidden", focus_mode);
+                } else {
+                    show_window_on_active_monitor(&window)?;
 
-                // Сообщаем фронту, что окно показано (для надёжного reset UI).
-                let _ = window.emit(EVENT_RECORDING_WINDOW_SHOWN, ());
+                    // Сообщаем фронту, что окно показано (для надёжного reset UI).
+                    let _ = window.emit(EVENT_RECORDING_WINDOW_SHOWN, ());
+                }
             }
 
-            // ВАЖНО: стартуем запись на Rust-стороне.
-            // Иначе, когда окно было скрыто, WebView/JS могут быть "усыплены" и не обработать event,
-            // из-за чего хоткей откроет окно, но запись не стартует и UI останется в старом состоянии.
-            let state_handle = app_handle
-                .try_state::<AppState>()
-                .ok_or_else(|| "AppState не доступен".to_string())?;
-            start_recording(state_handle, app_handle.clone()).await?;
-            log::info!("Recording started via hotkey (internal)");
+            // Запускаем запись
+            start_recording(state.clone(), app_handle).await?;
+            log::info!("Recording started via hotkey");
         }
         RecordingStatus::Starting => {
-            log::debug!("Ignoring toggle - recording is starting");
+            // Запись еще запускается - игнорируем повторное нажатие
+            log::debug!("Ignoring toggle - recording is starting (WebSocket connecting, audio capture initializing)");
         }
         RecordingStatus::Recording => {
-            let _result = state
+            // Останавливаем запись
+            let _result = state.recording
                 .transcription_service
                 .stop_recording()
                 .await
                 .map_err(|e| e.to_string())?;
 
             log::info!("Recording stopped via hotkey");
+            hide_recording_overlay(&app_handle);
+            state.feedback.notify(FeedbackEvent::RecordingStopped);
+            end_live_captions(&app_handle).await;
+            end_meeting(&app_handle);
+
+            // Эмитируем статус Idle с флагом stopped_via_hotkey; окно — по window_behavior_on_stop
             let session_id = state.active_transcription_session_id.load(Ordering::Relaxed);
+            let window_behavior = apply_window_behavior_on_stop(&state, &app_handle, session_id).await;
+            log::info!("Emitting status: Idle (stopped_via_hotkey: TRUE, window: {:?})", window_behavior);
             let _ = app_handle.emit(
                 EVENT_RECORDING_STATUS,
                 RecordingStatusPayload {
                     session_id,
                     status: RecordingStatus::Idle,
                     stopped_via_hotkey: true,
+                    window_behavior: Some(window_behavior),
                 },
             );
         }
         RecordingStatus::Processing => {
-            log::debug!("Ignoring toggle - recording is processing");
+            // Игнорируем - запись уже останавливается
+            log::debug!("Ignoring toggle - recording is already being processed");
         }
         RecordingStatus::Error => {
-            log::warn!("Cannot toggle recording - error state");
+            log::warn!("Cannot toggle recording - system is in error state");
         }
     }
 
     Ok(())
 }
 
-/// Minimize window
-#[tauri::command]
-pub async fn minimize_window(window: Window) -> Result<(), String> {
-    log::info!("Command: minimize_window");
-    window.minimize().map_err(|e| e.to_string())?;
-    Ok(())
+/// Режим фокусировки ОС, из-за которого запись идёт без окна и звуков;
+/// None — настройка respect_focus_mode выключена или режим не включён
+async fn quiet_focus_mode(state: &AppState) -> Option<FocusMode> {
+    if !state.settings.config.read().await.respect_focus_mode {
+        return None;
+    }
+    tokio::task::spawn_blocking(crate::infrastructure::focus_mode::active_focus_mode)
+        .await
+        .ok()
+        .flatten()
 }
 
-//
-// STT Configuration Commands
-//
+/// Страховка для hide_after_paste: если вставлять оказалось нечего, окно всё равно скрывается
+const HIDE_AFTER_PASTE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Окно записи после остановки хоткеем — по настройке window_behavior_on_stop.
+/// Возвращает применённое поведение (для recording:status, чтобы frontend знал, останется ли окно)
+async fn apply_window_behavior_on_stop(state: &AppState, app_handle: &AppHandle, session_id: u64) -> WindowBehaviorOnStop {
+    let behavior = {
+        let config = state.settings.config.read().await;
+        match config.window_behavior_on_stop.normalized() {
+            // Без auto-paste вставки не будет — ждать нечего
+            WindowBehaviorOnStop::HideAfterPaste if !config.auto_paste_text => WindowBehaviorOnStop::HideImmediately,
+            behavior => behavior,
+        }
+    };
+    match behavior {
+        WindowBehaviorOnStop::KeepWindow => {}
+        WindowBehaviorOnStop::HideImmediately => hide_main_window(app_handle),
+        WindowBehaviorOnStop::HideAfterPaste => {
+            state.hide_window_after_paste.store(true, Ordering::SeqCst);
+            schedule_main_window_hide(app_handle.clone(), session_id, HIDE_AFTER_PASTE_TIMEOUT);
+        }
+        WindowBehaviorOnStop::HideAfterDelay { secs } => {
+            schedule_main_window_hide(app_handle.clone(), session_id, std::time::Duration::from_secs(secs.into()));
+        }
+    }
+    behavior
+}
 
-use crate::domain::SttProviderType;
+/// Скрывает окно записи через `delay`, если за это время не началась новая запись
+fn schedule_main_window_hide(app_handle: AppHandle, session_id: u64, delay: std::time::Duration) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        let Some(state) = app_handle.try_state::<AppState>() else {
+            return;
+        };
+        if state.active_transcription_session_id.load(Ordering::Relaxed) != session_id
+            || state.recording.transcription_service.get_status().await != RecordingStatus::Idle
+        {
+            log::debug!("Delayed window hide skipped: a new recording has started");
+            return;
+        }
+        state.hide_window_after_paste.store(false, Ordering::SeqCst);
+        hide_main_window(&app_handle);
+    });
+}
 
-/// Update STT configuration
-#[tauri::command]
-pub async fn update_stt_config(
-    state: State<'_, AppState>,
-    app_handle: AppHandle,
-    window: Window,
-    provider: String,
-    language: String,
-    deepgram_api_key: Option<String>,
-    assemblyai_api_key: Option<String>,
+fn hide_main_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        if let Err(e) = window.hide() {
+            log::warn!("Failed to hide main window after recording stop: {}", e);
+        }
+    }
+}
+
+/// Internal version for calling from hotkey handler (without State wrapper)
+pub async fn toggle_recording_with_window_internal(
+    state: &AppState,
+    window: tauri::WebviewWindow,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("toggle_recording_with_window_internal (from hotkey)");
+
+    // Проверяем авторизацию - если не авторизован, показываем auth окно
+    let is_authenticated = *state.is_authenticated.read().await;
+    if !is_authenticated {
+        log::info!("User not authenticated - showing auth window");
+        if let Some(auth) = app_handle.get_webview_window("auth") {
+            auth.show().map_err(|e| e.to_string())?;
+            auth.set_focus().map_err(|e| e.to_string())?;
+        }
+        return Ok(());
+    }
+
+    let current_status = state.recording.transcription_service.get_status().await;
+
+    match current_status {
+        RecordingStatus::Idle => {
+            // Показываем окно если оно скрыто
+            if !window.is_visible().map_err(|e| e.to_string())? {
+                #[cfg(target_os = "macos")]
+                {
+                    if let Some(bundle_id) = crate::infrastructure::auto_paste::get_active_app_bundle_id() {
+                        *state.integrations.last_focused_app_bundle_id.write().await = Some(bundle_id.clone());
+                        log::info!("Saved last focused app bundle ID: {}", bundle_id);
+                    }
+                }
+                remember_focused_app(state).await;
+                if let Some(focus_mode) = quiet_focus_mode(state).await {
+                    log::info!("OS focus mode {:?} is on - recording window stays hidden", focus_mode);
+                } else {
+                    show_webview_window_on_active_monitor(&window)?;
+
+                    // Сообщаем фронту, что окно показано (для надёжного reset UI).
+                    let _ = window.emit(EVENT_RECORDING_WINDOW_SHOWN, ());
+                }
+            }
+
+            // ВАЖНО: стартуем запись на Rust-стороне.
+            // Иначе, когда окно было скрыто, WebView/JS могут быть "усыплены" и не обработать event,
+            // из-за чего хоткей откроет окно, но запись не стартует и UI останется в старом состоянии.
+            let state_handle = app_handle
+                .try_state::<AppState>()
+                .ok_or_else(|| "AppState не доступен".to_string())?;
+            start_recording(state_handle, app_handle.clone()).await?;
+            log::info!("Recording started via hotkey (internal)");
+        }
+        RecordingStatus::Starting | RecordingStatus::Processing => {
+            // Не игнорируем молча: выполним нажатие, как только переход закончится
+            match queue_toggle_intent(state, current_status) {
+                Some(intent) => log::info!("Toggle queued during {:?}: {:?}", current_status, intent),
+                None => log::info!("Queued toggle cancelled during {:?}", current_status),
+            }
+        }
+        RecordingStatus::Recording => {
+            let _result = state.recording
+                .transcription_service
+                .stop_recording()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            log::info!("Recording stopped via hotkey");
+            hide_recording_overlay(&app_handle);
+            state.feedback.notify(FeedbackEvent::RecordingStopped);
+            end_live_captions(&app_handle).await;
+            end_meeting(&app_handle);
+            let session_id = state.active_transcription_session_id.load(Ordering::Relaxed);
+            let window_behavior = apply_window_behavior_on_stop(state, &app_handle, session_id).await;
+            let _ = app_handle.emit(
+                EVENT_RECORDING_STATUS,
+                RecordingStatusPayload {
+                    session_id,
+                    status: RecordingStatus::Idle,
+                    stopped_via_hotkey: true,
+                    window_behavior: Some(window_behavior),
+                },
+            );
+            run_queued_toggle(&app_handle, RecordingStatus::Idle);
+        }
+        RecordingStatus::Error => {
+            log::warn!("Cannot toggle recording - error state");
+        }
+    }
+
+    Ok(())
+}
+
+/// Запускает запись как по хоткею, но с override STT-настроек и профиля доставки на эту сессию
+pub async fn start_recording_with_overrides_internal(
+    state: &AppState,
+    window: tauri::WebviewWindow,
+    app_handle: AppHandle,
+    stt_override: Option<SttSessionOverride>,
+    output_profile: Option<String>,
+) -> Result<(), String> {
+    state.recording.transcription_service.set_next_session_override(stt_override).await;
+    let result = toggle_recording_with_window_internal(state, window, app_handle).await;
+
+    // Запись не стартовала (ошибка, не авторизован) — override не должен достаться следующей сессии
+    if state.recording.transcription_service.get_status().await != RecordingStatus::Recording {
+        state.recording.transcription_service.set_next_session_override(None).await;
+        return result;
+    }
+
+    *state.integrations.output_profile_override.write().await = output_profile;
+    result
+}
+
+/// Хоткей профиля записи: в простое стартует запись с настройками профиля,
+/// во время записи работает как основной хоткей (останавливает её)
+pub async fn toggle_recording_with_profile_internal(
+    state: &AppState,
+    window: tauri::WebviewWindow,
+    app_handle: AppHandle,
+    profile_name: &str,
+) -> Result<(), String> {
+    if state.recording.transcription_service.get_status().await != RecordingStatus::Idle {
+        return toggle_recording_with_window_internal(state, window, app_handle).await;
+    }
+
+    let profile = state.settings.config.read().await.recording_profile(profile_name).cloned();
+    let Some(profile) = profile else {
+        return Err(state
+            .localize(UiMessage::RecordingProfileNotFound {
+                profile: profile_name.to_string(),
+            })
+            .await);
+    };
+    log::info!("Recording with profile '{}': {:?}", profile.name, profile.stt);
+    state.feedback.set_next_session_override(profile.feedback);
+    let result =
+        start_recording_with_overrides_internal(state, window, app_handle, Some(profile.stt), profile.output_profile)
+            .await;
+    // Старт забирает звуки профиля себе; если запись не стартовала, следующей сессии они не достанутся
+    state.feedback.set_next_session_override(None);
+    result
+}
+
+fn queue_toggle_intent(state: &AppState, status: RecordingStatus) -> Option<QueuedToggle> {
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    state
+        .toggle_intent
+        .lock()
+        .map(|mut queue| queue.press(status, now_ms))
+        .unwrap_or(None)
+}
+
+/// Выполняет нажатие, отложенное до статуса `reached` (см. toggle_intent)
+/// Живой счётчик записи: recording:tick раз в секунду, пока идёт эта сессия
+fn spawn_recording_ticker(app_handle: AppHandle, session_id: u64, provider: crate::domain::SttProviderType) {
+    tauri::async_runtime::spawn(async move {
+        let started_at = std::time::Instant::now();
+        let mut quota_remaining_secs = None;
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            let Some(state) = app_handle.try_state::<AppState>() else {
+                return;
+            };
+            // Запись остановлена (кнопкой, хоткеем, VAD или ошибкой) или уже началась следующая
+            if state.active_transcription_session_id.load(Ordering::Relaxed) != session_id
+                || state.recording.transcription_service.get_status().await != RecordingStatus::Recording
+            {
+                return;
+            }
+            // UsageUpdate приходит раз в несколько секунд — между ними показываем последний остаток
+            quota_remaining_secs = state.recording.transcription_service.remaining_quota_secs().or(quota_remaining_secs);
+            let payload = RecordingTickPayload::new(session_id, started_at.elapsed(), provider, quota_remaining_secs);
+            let _ = app_handle.emit(EVENT_RECORDING_TICK, payload);
+        }
+    });
+}
+
+pub(crate) fn run_queued_toggle(app_handle: &AppHandle, reached: RecordingStatus) {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let intent = state
+        .toggle_intent
+        .lock()
+        .map(|mut queue| queue.take(reached, now_ms))
+        .unwrap_or(None);
+    if let Some(intent) = intent {
+        log::info!("Running queued toggle: {:?}", intent);
+        tauri::async_runtime::spawn(queued_toggle_task(app_handle.clone()));
+    }
+}
+
+// Не async fn: явный Box<dyn Future + Send> разрывает цикл типов
+// (toggle → start_recording → run_queued_toggle → toggle)
+fn queued_toggle_task(
+    app_handle: AppHandle,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> {
+    Box::pin(async move {
+        let (Some(state), Some(window)) = (
+            app_handle.try_state::<AppState>(),
+            app_handle.get_webview_window("main"),
+        ) else {
+            return;
+        };
+        if let Err(e) = toggle_recording_with_window_internal(state.inner(), window, app_handle.clone()).await {
+            log::error!("Failed to run queued toggle: {}", e);
+        }
+    })
+}
+
+/// Minimize window
+#[tauri::command]
+pub async fn minimize_window(window: Window) -> Result<(), String> {
+    log::info!("Command: minimize_window");
+    window.minimize().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+//
+// STT Configuration Commands
+//
+
+use crate::domain::SttProviderType;
+
+/// Update STT configuration.
+///
+/// Arguments are validated together: any invalid field rejects the whole update with a JSON
+/// `ValidationFailure` (field-level errors) as the error string.
+#[tauri::command]
+pub async fn update_stt_config(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    provider: String,
+    language: String,
+    deepgram_api_key: Option<String>,
+    assemblyai_api_key: Option<String>,
     model: Option<String>,
     // Важно: двойной Option позволяет отличить "поле не прислали" (None)
     // от "поле прислали как null" (Some(None)). Это нужно, чтобы
     // частичные обновления (например, только language) не затирали keyterms.
     deepgram_keyterms: Option<Option<String>>,
+    // "linear16" | "flac" | "opus"; None — не меняем
+    audio_encoding: Option<String>,
+    // Энергосбережение: None — не меняем, Some(None) — выключить, Some(Some(secs)) — TTL простоя
+    idle_teardown_secs: Option<Option<u64>>,
+    // Warm-start соединения при показе окна; None — не меняем
+    prewarm_connection: Option<bool>,
+    // Сколько n-best гипотез запрашивать (0/1 — выключено); None — не меняем
+    max_alternatives: Option<u8>,
+    // Нормализация чисел/дат/сумм по языкам; None — не меняем
+    normalization: Option<crate::domain::TextNormalizationConfig>,
+    // Удаление слов-паразитов (уровень и свои слова по языкам); None — не меняем
+    filler_removal: Option<crate::domain::FillerRemovalConfig>,
+    // Локальная пунктуация для провайдеров без неё; None — не меняем
+    punctuate_locally: Option<bool>,
+    // Метки говорящих в финальных фразах; None — не меняем
+    diarize: Option<bool>,
+    // Параллельные REST запросы при расшифровке файлов; None — не меняем
+    batch_concurrency: Option<u8>,
+    // Порог коротких записей для REST запроса (0 — выключено); None — не меняем
+    short_clip_secs: Option<u32>,
+    // Сколько держать соединение после остановки записи; None — не меняем
+    keep_alive_ttl_secs: Option<u64>,
+    // Модель под язык у провайдера (весь список целиком); None — не меняем
+    language_models: Option<Vec<crate::domain::LanguageModelOverride>>,
+    // Сколько подряд partial'ов должно совпасть, чтобы слово показалось (0/1 — выключено); None — не меняем
+    partial_stability: Option<u8>,
+    // Сколько мс речи, записанной до открытия стрима, досылать провайдеру (0 — выключено); None — не меняем
+    pre_roll_ms: Option<u32>,
+    // Параметры whisper.cpp для локального провайдера (промпт, температура, beam, порог тишины); None — не меняем
+    whisper: Option<crate::domain::WhisperDecodingConfig>,
+    // Стерео-интервью: каналы L/R — отдельные стримы с меткой канала; None — не меняем
+    split_channels: Option<bool>,
+    // Пауза (мс), после которой начинается новый абзац (0 — выключено); None — не меняем
+    paragraph_pause_ms: Option<u32>,
+    // Голосовые команды управления ("stop recording", "switch to English", "paste that"); None — не меняем
+    voice_commands: Option<bool>,
+    // A/B сравнение со вторым провайдером: None — не меняем, Some(None)/"" — выключить
+    comparison_provider: Option<Option<String>>,
+    // Размер аудио в одном сообщении провайдеру, мс (весь список целиком); None — не меняем
+    audio_chunking: Option<Vec<crate::domain::AudioChunkingOverride>>,
 ) -> Result<(), String> {
     log::info!("Command: update_stt_config - provider: {}, language: {}, model: {:?}", provider, language, model);
 
+    let args = UpdateSttConfigArgs {
+        provider,
+        language,
+        model,
+        deepgram_keyterms,
+        audio_encoding,
+        idle_teardown_secs,
+        prewarm_connection,
+        max_alternatives,
+        normalization,
+        filler_removal,
+        punctuate_locally,
+        diarize,
+        batch_concurrency,
+        short_clip_secs,
+        keep_alive_ttl_secs,
+        language_models,
+        partial_stability,
+        pre_roll_ms,
+        whisper,
+        split_channels,
+        paragraph_pause_ms,
+        voice_commands,
+        comparison_provider,
+        audio_chunking,
+    };
+    let request = match args.validate() {
+        Ok(request) => request,
+        Err(errors) => {
+            log::warn!("update_stt_config rejected, invalid fields: {:?}", errors.fields());
+            return Err(errors.to_command_error(state.ui_language().await));
+        }
+    };
+
     // Выбор провайдера отключён — всегда используем Backend.
-    // Параметр provider оставлен, чтобы не ломать совместимость API.
-    let _ = provider;
+    // Параметр provider оставлен (и проверяется), чтобы не ломать совместимость API.
+    let _ = request.provider;
     let provider_type = SttProviderType::Backend;
 
     // Снимаем текущее состояние для сравнения после сохранения
     let old_stt = {
-        let config = state.config.read().await;
+        let config = state.settings.config.read().await;
         config.stt.clone()
     };
 
@@ -747,10 +2345,10 @@ pub async fn update_stt_config(
 
     // Обновляем только переданные параметры
     config.provider = provider_type;
-    config.language = language;
+    config.language = request.language.code().to_string();
 
     // Whisper/model больше не используем в backend-only архитектуре.
-    let _ = model;
+    let _ = request.model;
     config.model = None;
 
     // В backend-only режиме keep-alive полезен: это снижает latency при повторном старте записи,
@@ -774,12 +2372,94 @@ pub async fn update_stt_config(
     // - None: не меняем существующее значение
     // - Some(None): очищаем
     // - Some(Some(v)): устанавливаем v
-    if let Some(next) = deepgram_keyterms {
+    if let Some(next) = request.deepgram_keyterms {
         config.deepgram_keyterms = next;
     }
 
+    if let Some(encoding) = request.audio_encoding {
+        config.audio_encoding = encoding;
+    }
+
+    if let Some(next) = request.idle_teardown_secs {
+        config.idle_teardown_secs = next;
+    }
+
+    if let Some(enabled) = request.prewarm_connection {
+        config.prewarm_connection = enabled;
+    }
+
+    if let Some(count) = request.max_alternatives {
+        config.max_alternatives = count;
+    }
+
+    if let Some(next) = request.normalization {
+        config.normalization = next;
+    }
+
+    if let Some(next) = request.filler_removal {
+        config.filler_removal = next;
+    }
+
+    if let Some(enabled) = request.punctuate_locally {
+        config.punctuate_locally = enabled;
+    }
+
+    if let Some(enabled) = request.diarize {
+        config.diarize = enabled;
+    }
+
+    if let Some(count) = request.batch_concurrency {
+        config.batch_concurrency = count;
+    }
+
+    if let Some(secs) = request.short_clip_secs {
+        config.short_clip_secs = secs;
+    }
+
+    // Для Backend сервис всё равно поднимет TTL до своего минимума (см. TranscriptionService::update_config)
+    if let Some(secs) = request.keep_alive_ttl_secs {
+        config.keep_alive_ttl_secs = secs;
+    }
+
+    if let Some(overrides) = request.language_models {
+        config.language_models = overrides;
+    }
+
+    if let Some(required) = request.partial_stability {
+        config.partial_stability = required;
+    }
+
+    if let Some(ms) = request.pre_roll_ms {
+        config.pre_roll_ms = ms;
+    }
+
+    if let Some(whisper) = request.whisper {
+        config.whisper = whisper;
+    }
+
+    if let Some(enabled) = request.split_channels {
+        config.split_channels = enabled;
+    }
+
+    if let Some(ms) = request.paragraph_pause_ms {
+        config.paragraph_pause_ms = ms;
+    }
+
+    if let Some(enabled) = request.voice_commands {
+        config.voice_commands = enabled;
+    }
+
+    if let Some(next) = request.comparison_provider {
+        config.comparison_provider = next;
+    }
+
+    // Применяется со следующей записи: провайдер читает размер чанка при старте стрима
+    if let Some(overrides) = request.audio_chunking {
+        config.audio_chunking = overrides;
+    }
+
     // Обновляем конфигурацию в сервисе
-    state
+    state.recording
         .transcription_service
         .update_config(config.clone())
         .await
@@ -788,426 +2468,1341 @@ pub async fn update_stt_config(
     // ВАЖНО: синхронизируем STT конфигурацию в AppConfig чтобы при сохранении
     // app_config.json не перезаписывались старые значения
     {
-        let mut app_config = state.config.write().await;
+        let mut app_config = state.settings.config.write().await;
         app_config.stt = config.clone();
     }
 
-    // Сохраняем конфигурацию на диск (без API ключа)
-    ConfigStore::save_config(&config)
-        .await
-        .map_err(|e| format!("Failed to save config: {}", e))?;
+    // Сохраняем конфигурацию на диск (без API ключа)
+    ConfigStore::save_config(&config)
+        .await
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    // Синхронизация между окнами — бампим ревизию при любых изменениях STT конфига,
+    // чтобы state-sync корректно подтягивал актуальный snapshot (включая keyterms и т.д.)
+    let stt_changed = config.language != old_stt.language
+        || config.deepgram_keyterms != old_stt.deepgram_keyterms
+        || config.audio_encoding != old_stt.audio_encoding
+        || config.idle_teardown_secs != old_stt.idle_teardown_secs
+        || config.prewarm_connection != old_stt.prewarm_connection
+        || config.max_alternatives != old_stt.max_alternatives
+        || config.normalization != old_stt.normalization
+        || config.filler_removal != old_stt.filler_removal
+        || config.punctuate_locally != old_stt.punctuate_locally
+        || config.diarize != old_stt.diarize
+        || config.batch_concurrency != old_stt.batch_concurrency
+        || config.short_clip_secs != old_stt.short_clip_secs
+        || config.keep_alive_ttl_secs != old_stt.keep_alive_ttl_secs
+        || config.language_models != old_stt.language_models
+        || config.partial_stability != old_stt.partial_stability
+        || config.pre_roll_ms != old_stt.pre_roll_ms
+        || config.whisper != old_stt.whisper
+        || config.split_channels != old_stt.split_channels
+        || config.paragraph_pause_ms != old_stt.paragraph_pause_ms
+        || config.voice_commands != old_stt.voice_commands
+        || config.comparison_provider != old_stt.comparison_provider
+        || config.audio_chunking != old_stt.audio_chunking
+        || config.provider != old_stt.provider;
+    if stt_changed {
+        let revision = AppState::bump_revision(&state.settings.stt_config_revision).await;
+        let _ = app_handle.emit(
+            EVENT_STATE_SYNC_INVALIDATION,
+            crate::presentation::StateSyncInvalidationPayload {
+                topic: "stt-config".to_string(),
+                revision,
+                source_id: Some(window.label().to_string()),
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            },
+        );
+    }
+
+    log::info!("STT configuration updated and saved successfully");
+    Ok(())
+}
+
+//
+// App Configuration Commands
+//
+
+/// Обёртка snapshot для state-sync протокола
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SnapshotEnvelope<T: serde::Serialize> {
+    pub revision: String,
+    pub data: T,
+}
+
+/// Минимальный "public" снапшот app-config для фронтенда.
+///
+/// Важно: не включаем STT конфиг и тем более токены — снапшоты идут во все окна через IPC.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AppConfigSnapshotData {
+    pub microphone_sensitivity: u8,
+    pub recording_hotkey: String,
+    pub auto_copy_to_clipboard: bool,
+    pub auto_paste_text: bool,
+    pub selected_audio_device: Option<String>,
+    pub min_confidence: Option<f32>,
+    pub low_confidence_action: LowConfidenceAction,
+    pub recording_overlay: RecordingOverlayConfig,
+    pub telemetry_enabled: bool,
+    pub paste_strategy: PasteStrategy,
+    pub paste_app_rules: Vec<PasteAppRule>,
+    pub text_casing: TextCasing,
+    pub captions: CaptionsConfig,
+    pub meeting: MeetingConfig,
+    pub calendar: CalendarConfig,
+    pub warm_pool: WarmPoolConfig,
+    pub models_dir: Option<String>,
+    pub partial_update_interval_ms: u64,
+    pub instant_paste: bool,
+    pub window_behavior_on_stop: WindowBehaviorOnStop,
+    pub respect_focus_mode: bool,
+    pub capture_app_context: bool,
+    pub spelling_hotkey: Option<String>,
+    pub feedback: FeedbackConfig,
+    pub memory_alarms: MemoryAlarmConfig,
+    pub mock_audio_file: Option<String>,
+}
+
+/// Get current application configuration + revision (for cross-window sync)
+#[tauri::command]
+pub async fn get_app_config_snapshot(
+    state: State<'_, ConfigState>,
+) -> Result<SnapshotEnvelope<AppConfigSnapshotData>, String> {
+    log::debug!("Command: get_app_config_snapshot");
+    let config = state.config.read().await.clone();
+    let data = AppConfigSnapshotData {
+        microphone_sensitivity: config.microphone_sensitivity,
+        recording_hotkey: config.recording_hotkey,
+        auto_copy_to_clipboard: config.auto_copy_to_clipboard,
+        auto_paste_text: config.auto_paste_text,
+        selected_audio_device: config.selected_audio_device,
+        min_confidence: config.min_confidence,
+        low_confidence_action: config.low_confidence_action,
+        recording_overlay: config.recording_overlay,
+        telemetry_enabled: config.telemetry_enabled,
+        paste_strategy: config.paste_strategy,
+        paste_app_rules: config.paste_app_rules,
+        text_casing: config.text_casing,
+        captions: config.captions,
+        meeting: config.meeting,
+        calendar: config.calendar,
+        warm_pool: config.warm_pool,
+        models_dir: config.models_dir,
+        partial_update_interval_ms: config.partial_update_interval_ms,
+        instant_paste: config.instant_paste,
+        window_behavior_on_stop: config.window_behavior_on_stop,
+        respect_focus_mode: config.respect_focus_mode,
+        capture_app_context: config.capture_app_context,
+        spelling_hotkey: config.spelling_hotkey,
+        feedback: config.feedback,
+        memory_alarms: config.memory_alarms,
+        mock_audio_file: config.mock_audio_file,
+    };
+    let revision = state.app_config_revision.read().await.to_string();
+    Ok(SnapshotEnvelope { revision, data })
+}
+
+/// Минимальный "public" снапшот stt-config для фронтенда.
+///
+/// Важно: не включаем backend_auth_token / backend_url (секреты), потому что снапшоты идут во все окна через IPC.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SttConfigSnapshotData {
+    pub provider: crate::domain::SttProviderType,
+    pub language: String,
+    pub auto_detect_language: bool,
+    pub enable_punctuation: bool,
+    pub filter_profanity: bool,
+    pub deepgram_api_key: Option<String>,
+    pub assemblyai_api_key: Option<String>,
+    pub model: Option<String>,
+    pub keep_connection_alive: bool,
+    pub deepgram_keyterms: Option<String>,
+    pub audio_encoding: crate::domain::AudioEncoding,
+    pub idle_teardown_secs: Option<u64>,
+    pub prewarm_connection: bool,
+    pub max_alternatives: u8,
+    pub normalization: crate::domain::TextNormalizationConfig,
+    pub filler_removal: crate::domain::FillerRemovalConfig,
+    pub punctuate_locally: bool,
+    pub diarize: bool,
+    pub batch_concurrency: u8,
+    pub short_clip_secs: u32,
+    pub keep_alive_ttl_secs: u64,
+    pub language_models: Vec<crate::domain::LanguageModelOverride>,
+    pub partial_stability: u8,
+    pub pre_roll_ms: u32,
+    pub whisper: crate::domain::WhisperDecodingConfig,
+    pub split_channels: bool,
+    pub paragraph_pause_ms: u32,
+    pub voice_commands: bool,
+    pub comparison_provider: Option<crate::domain::SttProviderType>,
+    pub audio_chunking: Vec<crate::domain::AudioChunkingOverride>,
+}
+
+/// Get current STT configuration snapshot
+#[tauri::command]
+pub async fn get_stt_config_snapshot(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+) -> Result<SnapshotEnvelope<SttConfigSnapshotData>, String> {
+    log::debug!("Command: get_stt_config_snapshot");
+    let config = recording.transcription_service.get_config().await;
+    let data = SttConfigSnapshotData {
+        provider: config.provider,
+        language: config.language,
+        auto_detect_language: config.auto_detect_language,
+        enable_punctuation: config.enable_punctuation,
+        filter_profanity: config.filter_profanity,
+        deepgram_api_key: config.deepgram_api_key,
+        assemblyai_api_key: config.assemblyai_api_key,
+        model: config.model,
+        keep_connection_alive: config.keep_connection_alive,
+        deepgram_keyterms: config.deepgram_keyterms,
+        audio_encoding: config.audio_encoding,
+        idle_teardown_secs: config.idle_teardown_secs,
+        prewarm_connection: config.prewarm_connection,
+        max_alternatives: config.max_alternatives,
+        normalization: config.normalization,
+        filler_removal: config.filler_removal,
+        punctuate_locally: config.punctuate_locally,
+        diarize: config.diarize,
+        batch_concurrency: config.batch_concurrency,
+        short_clip_secs: config.short_clip_secs,
+        keep_alive_ttl_secs: config.keep_alive_ttl_secs,
+        language_models: config.language_models,
+        partial_stability: config.partial_stability,
+        pre_roll_ms: config.pre_roll_ms,
+        whisper: config.whisper,
+        split_channels: config.split_channels,
+        paragraph_pause_ms: config.paragraph_pause_ms,
+        voice_commands: config.voice_commands,
+        comparison_provider: config.comparison_provider,
+        audio_chunking: config.audio_chunking,
+    };
+    let revision = settings.stt_config_revision.read().await.to_string();
+    Ok(SnapshotEnvelope { revision, data })
+}
+
+/// Данные для snapshot авторизации
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AuthStateData {
+    pub is_authenticated: bool,
+}
+
+/// Get current auth state snapshot
+#[tauri::command]
+pub async fn get_auth_state_snapshot(state: State<'_, AppState>) -> Result<SnapshotEnvelope<AuthStateData>, String> {
+    log::trace!("Command: get_auth_state_snapshot");
+    let is_authenticated = *state.is_authenticated.read().await;
+    let revision = state.auth_state_revision.read().await.to_string();
+    Ok(SnapshotEnvelope {
+        revision,
+        data: AuthStateData { is_authenticated },
+    })
+}
+
+/// Полный снапшот auth-session (device_id + tokens).
+///
+/// В отличие от auth-state, этот снапшот содержит секреты (access/refresh),
+/// поэтому его нельзя логировать/сериализовать в публичные конфиги.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AuthSessionSnapshotData {
+    pub device_id: String,
+    pub session: Option<AuthSessionSnapshotSessionData>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AuthSessionSnapshotSessionData {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub access_expires_at: String,
+    pub refresh_expires_at: Option<String>,
+    pub user: Option<AuthSessionSnapshotUserData>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AuthSessionSnapshotUserData {
+    pub id: String,
+    pub email: String,
+    pub email_verified: bool,
+}
+
+fn ms_to_rfc3339(ms: i64) -> String {
+    // Важно: если ms некорректный — fallback на epoch, чтобы не падать.
+    chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms)
+        .unwrap_or_else(|| chrono::DateTime::<chrono::Utc>::from_timestamp(0, 0).unwrap())
+        .to_rfc3339()
+}
+
+/// Get current auth session snapshot (for cross-window sync).
+#[tauri::command]
+pub async fn get_auth_session_snapshot(
+    state: State<'_, AppState>,
+) -> Result<SnapshotEnvelope<AuthSessionSnapshotData>, String> {
+    log::trace!("Command: get_auth_session_snapshot");
+
+    let store = state.auth_store.read().await.clone();
+    let data = AuthSessionSnapshotData {
+        device_id: store.device_id,
+        session: store.session.map(|s| AuthSessionSnapshotSessionData {
+            access_token: s.access_token,
+            refresh_token: s.refresh_token,
+            access_expires_at: ms_to_rfc3339(s.access_expires_at_ms),
+            refresh_expires_at: s.refresh_expires_at_ms.map(ms_to_rfc3339),
+            user: s.user.map(|u| AuthSessionSnapshotUserData {
+                id: u.id,
+                email: u.email,
+                email_verified: u.email_verified,
+            }),
+        }),
+    };
+
+    let revision = state.auth_session_revision.read().await.to_string();
+    Ok(SnapshotEnvelope { revision, data })
+}
+
+/// Get current UI preferences snapshot
+#[tauri::command]
+pub async fn get_ui_preferences_snapshot(state: State<'_, ConfigState>) -> Result<SnapshotEnvelope<crate::domain::UiPreferences>, String> {
+    log::debug!("Command: get_ui_preferences_snapshot");
+    let data = state.ui_preferences.read().await.clone();
+    let revision = state.ui_preferences_revision.read().await.to_string();
+    Ok(SnapshotEnvelope { revision, data })
+}
+
+/// Обновить UI-настройки (тема, локаль) и уведомить все окна
+#[tauri::command]
+pub async fn update_ui_preferences(
+    state: State<'_, ConfigState>,
+    app_handle: AppHandle,
+    window: Window,
+    theme: String,
+    locale: String,
+    use_system_theme: Option<bool>,
+) -> Result<(), String> {
+    let use_system_theme = use_system_theme.unwrap_or(false);
+    log::info!(
+        "Command: update_ui_preferences - theme: {}, locale: {}, use_system_theme: {}",
+        theme,
+        locale,
+        use_system_theme
+    );
+
+    {
+        let current = state.ui_preferences.read().await;
+        if current.theme == theme && current.locale == locale && current.use_system_theme == use_system_theme {
+            return Ok(());
+        }
+    }
+
+    let prefs = crate::domain::UiPreferences {
+        theme: theme.clone(),
+        locale: locale.clone(),
+        use_system_theme,
+    };
+
+    // Сохраняем в state
+    *state.ui_preferences.write().await = prefs.clone();
+    crate::presentation::tray::update_tray_language(&app_handle, UiLanguage::from_locale(&locale));
+
+    // Сохраняем на диск
+    ConfigStore::save_ui_preferences(&prefs)
+        .await
+        .map_err(|e| format!("Failed to save UI preferences: {}", e))?;
+
+    // Bump revision и отправляем invalidation
+    let revision = AppState::bump_revision(&state.ui_preferences_revision).await;
+    let _ = app_handle.emit(
+        EVENT_STATE_SYNC_INVALIDATION,
+        crate::presentation::StateSyncInvalidationPayload {
+            topic: "ui-preferences".to_string(),
+            revision,
+            source_id: Some(window.label().to_string()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Update application configuration (e.g., microphone sensitivity, recording hotkey, auto-copy/paste)
+#[tauri::command]
+pub async fn update_app_config(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    microphone_sensitivity: Option<u8>,
+    recording_hotkey: Option<String>,
+    auto_copy_to_clipboard: Option<bool>,
+    auto_paste_text: Option<bool>,
+    selected_audio_device: Option<String>,
+    min_confidence: Option<Option<f32>>,
+    low_confidence_action: Option<LowConfidenceAction>,
+    recording_overlay: Option<RecordingOverlayConfig>,
+    telemetry_enabled: Option<bool>,
+    paste_strategy: Option<PasteStrategy>,
+    paste_app_rules: Option<Vec<PasteAppRule>>,
+    text_casing: Option<TextCasing>,
+    captions: Option<CaptionsConfig>,
+    meeting: Option<MeetingConfig>,
+    calendar: Option<CalendarConfig>,
+    warm_pool: Option<WarmPoolConfig>,
+    partial_update_interval_ms: Option<u64>,
+    instant_paste: Option<bool>,
+    window_behavior_on_stop: Option<WindowBehaviorOnStop>,
+    respect_focus_mode: Option<bool>,
+    capture_app_context: Option<bool>,
+    spelling_hotkey: Option<String>, // пустая строка — без хоткея
+    feedback: Option<FeedbackConfig>,
+    memory_alarms: Option<MemoryAlarmConfig>,
+) -> Result<(), String> {
+    log::info!("Command: update_app_config - sensitivity: {:?}, hotkey: {:?}, auto_copy: {:?}, auto_paste: {:?}, device: {:?}, min_confidence: {:?}, low_confidence_action: {:?}, recording_overlay: {:?}, telemetry: {:?}, paste_strategy: {:?}, paste_app_rules: {:?}, text_casing: {:?}, captions: {:?}, meeting: {:?}, calendar: {:?}, warm_pool: {:?}, partial_update_interval_ms: {:?}, instant_paste: {:?}, window_behavior_on_stop: {:?}, respect_focus_mode: {:?}, capture_app_context: {:?}, spelling_hotkey: {:?}, feedback: {:?}, memory_alarms: {:?}",
+        microphone_sensitivity, recording_hotkey, auto_copy_to_clipboard, auto_paste_text, selected_audio_device, min_confidence, low_confidence_action, recording_overlay, telemetry_enabled, paste_strategy, paste_app_rules, text_casing, captions, meeting, calendar, warm_pool, partial_update_interval_ms, instant_paste, window_behavior_on_stop, respect_focus_mode, capture_app_context, spelling_hotkey, feedback, memory_alarms);
+
+    // Защита от "тихих" провалов: если фронт случайно отправил snake_case ключи,
+    // Tauri не сматчит аргументы, и сюда придут одни None.
+    // Тогда лучше вернуть явную ошибку, чем сделать вид что всё ок.
+    if microphone_sensitivity.is_none()
+        && recording_hotkey.is_none()
+        && auto_copy_to_clipboard.is_none()
+        && auto_paste_text.is_none()
+        && selected_audio_device.is_none()
+        && min_confidence.is_none()
+        && low_confidence_action.is_none()
+        && recording_overlay.is_none()
+        && telemetry_enabled.is_none()
+        && paste_strategy.is_none()
+        && paste_app_rules.is_none()
+        && text_casing.is_none()
+        && captions.is_none()
+        && meeting.is_none()
+        && calendar.is_none()
+        && warm_pool.is_none()
+        && partial_update_interval_ms.is_none()
+        && instant_paste.is_none()
+        && window_behavior_on_stop.is_none()
+        && respect_focus_mode.is_none()
+        && capture_app_context.is_none()
+        && spelling_hotkey.is_none()
+        && feedback.is_none()
+        && memory_alarms.is_none()
+    {
+        return Err("update_app_config: не получены поля для обновления. Проверьте, что фронтенд отправляет args в camelCase (например microphoneSensitivity, recordingHotkey, autoCopyToClipboard, autoPasteText, selectedAudioDevice, minConfidence, lowConfidenceAction, recordingOverlay, telemetryEnabled, pasteStrategy, pasteAppRules, textCasing, captions, meeting, calendar, warmPool, partialUpdateIntervalMs, instantPaste, windowBehaviorOnStop, respectFocusMode, captureAppContext, spellingHotkey, feedback, memoryAlarms).".to_string());
+    }
+
+    if let Some(Some(threshold)) = min_confidence {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(format!("min_confidence должен быть в диапазоне 0.0-1.0, получено {}", threshold));
+        }
+    }
+
+    let mut config = state.settings.config.write().await;
+    let mut hotkey_changed = false;
+    let mut any_changed = false;
+
+    if let Some(sensitivity) = microphone_sensitivity {
+        let clamped = sensitivity.min(200); // Ensure 0-200 range
+        if config.microphone_sensitivity != clamped {
+            log::info!("Updating microphone sensitivity: {} -> {}", config.microphone_sensitivity, clamped);
+            config.microphone_sensitivity = clamped;
+            any_changed = true;
+        }
+
+        // Обновляем также в TranscriptionService для применения в реальном времени
+        state.recording.transcription_service.set_microphone_sensitivity(clamped).await;
+    }
+
+    if let Some(new_hotkey) = recording_hotkey {
+        if new_hotkey != config.recording_hotkey {
+            // Валидируем что это корректная комбинация клавиш
+            use tauri_plugin_global_shortcut::Shortcut;
+            let lang = state.ui_language().await;
+            let Ok(shortcut) = new_hotkey.parse::<Shortcut>() else {
+                return Err(UiMessage::InvalidHotkey { hotkey: new_hotkey }.text(lang));
+            };
+            // Занятое сочетание не сохраняем: иначе регистрация упадёт уже после сохранения
+            if let Some(conflict) = hotkey_conflict(&app_handle, &shortcut) {
+                return Err(hotkey_conflict_message(&app_handle, &new_hotkey, &conflict, lang));
+            }
+
+            log::info!("Updating recording hotkey: {} -> {}", config.recording_hotkey, new_hotkey);
+            config.recording_hotkey = new_hotkey;
+            hotkey_changed = true;
+            any_changed = true;
+        }
+    }
+
+    if let Some(auto_copy) = auto_copy_to_clipboard {
+        if config.auto_copy_to_clipboard != auto_copy {
+            log::info!("Updating auto_copy_to_clipboard: {} -> {}", config.auto_copy_to_clipboard, auto_copy);
+            config.auto_copy_to_clipboard = auto_copy;
+            any_changed = true;
+        }
+    }
+
+    if let Some(auto_paste) = auto_paste_text {
+        if config.auto_paste_text != auto_paste {
+            log::info!("Updating auto_paste_text: {} -> {}", config.auto_paste_text, auto_paste);
+            config.auto_paste_text = auto_paste;
+            any_changed = true;
+        }
+    }
+
+    if let Some(threshold) = min_confidence {
+        if config.min_confidence != threshold {
+            log::info!("Updating min_confidence: {:?} -> {:?}", config.min_confidence, threshold);
+            config.min_confidence = threshold;
+            any_changed = true;
+        }
+    }
+
+    if let Some(action) = low_confidence_action {
+        if config.low_confidence_action != action {
+            log::info!("Updating low_confidence_action: {:?} -> {:?}", config.low_confidence_action, action);
+            config.low_confidence_action = action;
+            any_changed = true;
+        }
+    }
+
+    if let Some(overlay) = recording_overlay {
+        if config.recording_overlay != overlay {
+            log::info!("Updating recording_overlay: {:?} -> {:?}", config.recording_overlay, overlay);
+            config.recording_overlay = overlay;
+            any_changed = true;
+
+            // Выключили во время записи — убираем сразу, включение подхватится при следующем старте
+            if !overlay.enabled {
+                hide_recording_overlay(&app_handle);
+            }
+        }
+    }
+
+    if let Some(enabled) = telemetry_enabled {
+        if config.telemetry_enabled != enabled {
+            log::info!("Updating telemetry_enabled: {} -> {}", config.telemetry_enabled, enabled);
+            config.telemetry_enabled = enabled;
+            any_changed = true;
+
+            // Отказ от телеметрии: накопленное не отправляем никогда
+            if !enabled {
+                crate::presentation::telemetry::clear_telemetry_queue(&state.integrations.telemetry_queue).await;
+            }
+        }
+    }
+
+    if let Some(strategy) = paste_strategy {
+        if config.paste_strategy != strategy {
+            log::info!("Updating paste_strategy: {:?} -> {:?}", config.paste_strategy, strategy);
+            config.paste_strategy = strategy;
+            any_changed = true;
+        }
+    }
+
+    if let Some(rules) = paste_app_rules {
+        let rules: Vec<PasteAppRule> = rules
+            .into_iter()
+            .map(|rule| PasteAppRule {
+                app_id: rule.app_id.trim().to_string(),
+                ..rule
+            })
+            .filter(|rule| !rule.app_id.is_empty())
+            .collect();
+        if config.paste_app_rules != rules {
+            log::info!("Updating paste_app_rules: {} rules", rules.len());
+            config.paste_app_rules = rules;
+            any_changed = true;
+        }
+    }
+
+    if let Some(casing) = text_casing {
+        if config.text_casing != casing {
+            log::info!("Updating text_casing: {:?} -> {:?}", config.text_casing, casing);
+            config.text_casing = casing;
+            any_changed = true;
+        }
+    }
+
+    if let Some(captions) = captions {
+        let captions = captions.normalized();
+        if config.captions != captions {
+            log::info!("Updating captions: {:?} -> {:?}", config.captions, captions);
+            config.captions = captions.clone();
+            any_changed = true;
+
+            // Шрифт/позиция применяются сразу; новый источник звука — со следующего запуска субтитров
+            if state.recording.live_captions.read().await.is_some() {
+                show_captions_window(&app_handle, &captions);
+            }
+        }
+    }
+
+    // Идущая встреча сегментируется по настройкам, с которыми она началась
+    if let Some(meeting) = meeting {
+        let meeting = meeting.normalized();
+        if config.meeting != meeting {
+            log::info!("Updating meeting: {:?} -> {:?}", config.meeting, meeting);
+            config.meeting = meeting;
+            any_changed = true;
+        }
+    }
+
+    if let Some(calendar) = calendar {
+        let calendar = calendar.normalized();
+        if config.calendar != calendar {
+            log::info!(
+                "Updating calendar: enabled={}, source={:?}, ics_url set={}",
+                calendar.enabled,
+                calendar.source,
+                calendar.ics_url.is_some()
+            );
+            // Системный календарь: диалог доступа показываем сразу при включении, а не на первой записи
+            let request_access = calendar.enabled
+                && calendar.source == CalendarSource::System
+                && !(config.calendar.enabled && config.calendar.source == CalendarSource::System);
+            config.calendar = calendar;
+            any_changed = true;
+
+            if request_access {
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(state) = app_handle.try_state::<AppState>() {
+                        if let Err(e) = request_calendar_provider_access(&state).await {
+                            log::warn!("Calendar access request failed: {}", e);
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    if let Some(warm_pool) = warm_pool {
+        let warm_pool = warm_pool.normalized();
+        if config.warm_pool != warm_pool {
+            log::info!("Updating warm_pool: {:?} -> {:?}", config.warm_pool, warm_pool);
+            config.warm_pool = warm_pool;
+            any_changed = true;
+        }
+    }
+
+    // Идущая запись продолжает со старым интервалом, новый — со следующей
+    if let Some(interval_ms) = partial_update_interval_ms {
+        let interval_ms = interval_ms.min(MAX_PARTIAL_UPDATE_INTERVAL_MS);
+        if config.partial_update_interval_ms != interval_ms {
+            log::info!(
+                "Updating partial_update_interval_ms: {} -> {}",
+                config.partial_update_interval_ms,
+                interval_ms
+            );
+            config.partial_update_interval_ms = interval_ms;
+            any_changed = true;
+        }
+    }
+
+    if let Some(instant) = instant_paste {
+        if config.instant_paste != instant {
+            log::info!("Updating instant_paste: {} -> {}", config.instant_paste, instant);
+            config.instant_paste = instant;
+            any_changed = true;
+        }
+    }
+
+    if let Some(behavior) = window_behavior_on_stop {
+        let behavior = behavior.normalized();
+        if config.window_behavior_on_stop != behavior {
+            log::info!(
+                "Updating window_behavior_on_stop: {:?} -> {:?}",
+                config.window_behavior_on_stop,
+                behavior
+            );
+            config.window_behavior_on_stop = behavior;
+            any_changed = true;
+        }
+    }
+
+    if let Some(respect) = respect_focus_mode {
+        if config.respect_focus_mode != respect {
+            log::info!("Updating respect_focus_mode: {} -> {}", config.respect_focus_mode, respect);
+            config.respect_focus_mode = respect;
+            any_changed = true;
+        }
+    }
+
+    if let Some(capture) = capture_app_context {
+        if config.capture_app_context != capture {
+            log::info!("Updating capture_app_context: {} -> {}", config.capture_app_context, capture);
+            config.capture_app_context = capture;
+            any_changed = true;
+        }
+    }
+
+    if let Some(hotkey) = spelling_hotkey {
+        let hotkey = hotkey.trim();
+        let hotkey = if hotkey.is_empty() {
+            None
+        } else {
+            use tauri_plugin_global_shortcut::Shortcut;
+            let lang = state.ui_language().await;
+            let Some((normalized, shortcut)) = crate::infrastructure::hotkey::normalize_recording_hotkey(hotkey)
+                .and_then(|normalized| normalized.parse::<Shortcut>().ok().map(|sc| (normalized, sc)))
+            else {
+                return Err(UiMessage::InvalidHotkey { hotkey: hotkey.to_string() }.text(lang));
+            };
+            if config.spelling_hotkey.as_deref() != Some(normalized.as_str()) {
+                if let Some(conflict) = hotkey_conflict(&app_handle, &shortcut) {
+                    return Err(hotkey_conflict_message(&app_handle, &normalized, &conflict, lang));
+                }
+            }
+            Some(normalized)
+        };
+        if config.spelling_hotkey != hotkey {
+            log::info!("Updating spelling_hotkey: {:?} -> {:?}", config.spelling_hotkey, hotkey);
+            config.spelling_hotkey = hotkey;
+            hotkey_changed = true;
+            any_changed = true;
+        }
+    }
+
+    if let Some(feedback) = feedback {
+        let feedback = feedback.normalized();
+        if config.feedback != feedback {
+            log::info!("Updating feedback: {:?} -> {:?}", config.feedback, feedback);
+            config.feedback = feedback;
+            any_changed = true;
+        }
+    }
+
+    if let Some(memory_alarms) = memory_alarms {
+        let memory_alarms = memory_alarms.normalized();
+        if config.memory_alarms != memory_alarms {
+            log::info!("Updating memory_alarms: {:?} -> {:?}", config.memory_alarms, memory_alarms);
+            config.memory_alarms = memory_alarms;
+            any_changed = true;
+        }
+    }
+
+    let mut device_changed = false;
+    if let Some(device) = selected_audio_device {
+        let device_opt = if device.is_empty() { None } else { Some(device.clone()) };
+
+        // Проверяем изменилось ли устройство
+        if config.selected_audio_device != device_opt {
+            log::info!("Updating selected_audio_device: {:?} -> {:?}", config.selected_audio_device, device_opt);
+            config.selected_audio_device = device_opt;
+            device_changed = true;
+            any_changed = true;
+        }
+    }
+
+    // Если ничего не менялось — выходим без лишнего I/O и invalidation
+    if !any_changed {
+        drop(config);
+        log::info!("App config unchanged, skipping save");
+        return Ok(());
+    }
+
+    log::info!("Saving app config to disk: sensitivity={}, hotkey={}, provider={:?}, language={}, device={:?}",
+        config.microphone_sensitivity, config.recording_hotkey, config.stt.provider, config.stt.language, config.selected_audio_device);
+
+    // Запоминаем selected_audio_device для применения после сохранения
+    let device_to_apply = if device_changed {
+        Some(config.selected_audio_device.clone())
+    } else {
+        None
+    };
+
+    // Сохраняем конфигурацию на диск
+    ConfigStore::save_app_config(&config)
+        .await
+        .map_err(|e| format!("Failed to save app config: {}", e))?;
+
+    // Если горячая клавиша изменилась - перерегистрируем её
+    if hotkey_changed {
+        drop(config); // освобождаем lock перед async операцией
+
+        log::info!("Re-registering recording hotkey");
+
+        // Перерегистрируем горячую клавишу
+        register_recording_hotkey(state.clone(), app_handle.clone()).await?;
+    } else {
+        drop(config); // освобождаем lock если не было hotkey_changed
+    }
+
+    // Если устройство изменилось - пересоздаем audio capture
+    if let Some(device_opt) = device_to_apply {
+        log::info!("Applying changed audio device: {:?}", device_opt);
+
+        if let Err(e) = state
+            .recreate_audio_capture_with_device(device_opt.clone(), app_handle.clone())
+            .await
+        {
+            log::error!("Failed to apply new audio device: {}", e);
+            return Err(state.localize(UiMessage::RecorderSwitchFailed { error: e }).await);
+        }
+
+        log::info!("Audio device changed and applied successfully");
+    }
+
+    // Синхронизация между окнами через state-sync
+    let revision = AppState::bump_revision(&state.settings.app_config_revision).await;
+    let _ = app_handle.emit(
+        EVENT_STATE_SYNC_INVALIDATION,
+        crate::presentation::StateSyncInvalidationPayload {
+            topic: "app-config".to_string(),
+            revision,
+            source_id: Some(window.label().to_string()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+
+    log::info!("App configuration updated and saved successfully");
+    Ok(())
+}
+
+/// Preview of the next anonymous telemetry request — exactly what would be sent
+#[tauri::command]
+pub async fn get_telemetry_preview(
+    state: State<'_, AppState>,
+) -> Result<crate::infrastructure::telemetry::TelemetryBatch, String> {
+    log::debug!("Command: get_telemetry_preview");
+    Ok(telemetry_preview(&state).await)
+}
+
+/// Whether the network is reachable and what records while it isn't (cloud providers are unavailable offline)
+#[tauri::command]
+pub async fn get_network_status(state: State<'_, AppState>) -> Result<NetworkStatusPayload, String> {
+    log::debug!("Command: get_network_status");
+    Ok(network_status(&state))
+}
+
+/// Team glossary sync status: revision, term count, last successful sync and last error
+#[tauri::command]
+pub async fn get_glossary_status(state: State<'_, AppState>) -> Result<GlossaryStatus, String> {
+    log::debug!("Command: get_glossary_status");
+    Ok(state.glossary_status.read().await.clone())
+}
+
+/// Sync the team glossary now (otherwise it is synced every 15 minutes)
+#[tauri::command]
+pub async fn sync_glossary(state: State<'_, AppState>) -> Result<GlossaryStatus, String> {
+    log::info!("Command: sync_glossary");
+    Ok(sync_team_glossary(&state).await)
+}
+
+/// Start live captions: click-through caption strip + continuous recognition (no silence auto-stop).
+///
+/// Audio comes from `captions.source_device` (e.g. a system-audio monitor/loopback device) or the microphone.
+#[tauri::command]
+pub async fn start_live_captions(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Command: start_live_captions");
+
+    let status = recording.transcription_service.get_status().await;
+    if status != RecordingStatus::Idle && status != RecordingStatus::Error {
+        return Err(settings
+            .localize(UiMessage::CaptionsDuringRecording {
+                status: format!("{:?}", status),
+            })
+            .await);
+    }
+
+    let captions = settings.config.read().await.captions.clone().normalized();
+    *recording.live_captions.write().await = Some(LiveCaptionsSession {
+        source_device: captions.source_device.clone(),
+    });
+    show_captions_window(&app_handle, &captions);
+
+    let state_handle = app_handle
+        .try_state::<AppState>()
+        .ok_or_else(|| "AppState не доступен".to_string())?;
+    if let Err(e) = start_recording(state_handle, app_handle.clone()).await {
+        *recording.live_captions.write().await = None;
+        hide_captions_window(&app_handle);
+        return Err(e);
+    }
+    Ok(())
+}
 
-    // Синхронизация между окнами — бампим ревизию при любых изменениях STT конфига,
-    // чтобы state-sync корректно подтягивал актуальный snapshot (включая keyterms и т.д.)
-    let stt_changed = config.language != old_stt.language
-        || config.deepgram_keyterms != old_stt.deepgram_keyterms
-        || config.provider != old_stt.provider;
-    if stt_changed {
-        let revision = AppState::bump_revision(&state.stt_config_revision).await;
-        let _ = app_handle.emit(
-            EVENT_STATE_SYNC_INVALIDATION,
-            crate::presentation::StateSyncInvalidationPayload {
-                topic: "stt-config".to_string(),
-                revision,
-                source_id: Some(window.label().to_string()),
-                timestamp_ms: chrono::Utc::now().timestamp_millis(),
-            },
-        );
+/// Stop live captions and the recording behind them
+#[tauri::command]
+pub async fn stop_live_captions(state: State<'_, RecordingState>, app_handle: AppHandle) -> Result<(), String> {
+    log::info!("Command: stop_live_captions");
+
+    if !end_live_captions(&app_handle).await {
+        hide_captions_window(&app_handle);
+        return Ok(());
     }
 
-    log::info!("STT configuration updated and saved successfully");
+    if state.transcription_service.get_status().await == RecordingStatus::Recording {
+        let state_handle = app_handle
+            .try_state::<AppState>()
+            .ok_or_else(|| "AppState не доступен".to_string())?;
+        stop_recording(state_handle, app_handle.clone()).await?;
+    }
     Ok(())
 }
 
-//
-// App Configuration Commands
-//
-
-/// Обёртка snapshot для state-sync протокола
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct SnapshotEnvelope<T: serde::Serialize> {
-    pub revision: String,
-    pub data: T,
+/// Input devices that capture system audio (monitor/loopback) — candidates for `captions.source_device`
+#[tauri::command]
+pub async fn get_loopback_audio_devices() -> Result<Vec<String>, String> {
+    let devices = get_audio_devices().await?;
+    Ok(devices
+        .into_iter()
+        .filter(|name| crate::infrastructure::audio::is_loopback_device_name(name))
+        .collect())
 }
 
-/// Минимальный "public" снапшот app-config для фронтенда.
+/// Start meeting mode: one long recording (no silence auto-stop) segmented into topics when it ends.
 ///
-/// Важно: не включаем STT конфиг и тем более токены — снапшоты идут во все окна через IPC.
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct AppConfigSnapshotData {
-    pub microphone_sensitivity: u8,
-    pub recording_hotkey: String,
-    pub auto_copy_to_clipboard: bool,
-    pub auto_paste_text: bool,
-    pub selected_audio_device: Option<String>,
+/// The structured transcript is saved as JSON and delivered via the `meeting:finished` event.
+#[tauri::command]
+pub async fn start_meeting(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    log::info!("Command: start_meeting");
+
+    if recording.meeting_active() {
+        return Err(settings.localize(UiMessage::MeetingAlreadyRunning).await);
+    }
+    let status = recording.transcription_service.get_status().await;
+    if status != RecordingStatus::Idle && status != RecordingStatus::Error {
+        return Err(settings
+            .localize(UiMessage::MeetingDuringRecording {
+                status: format!("{:?}", status),
+            })
+            .await);
+    }
+
+    let config = settings.config.read().await.meeting.clone();
+    if let Ok(mut meeting) = recording.meeting.lock() {
+        *meeting = Some(MeetingRecorder::new(config));
+    }
+
+    let state_handle = app_handle
+        .try_state::<AppState>()
+        .ok_or_else(|| "AppState не доступен".to_string())?;
+    if let Err(e) = start_recording(state_handle, app_handle.clone()).await {
+        if let Ok(mut meeting) = recording.meeting.lock() {
+            *meeting = None;
+        }
+        return Err(e);
+    }
+    Ok(())
 }
 
-/// Get current application configuration + revision (for cross-window sync)
+/// Stop meeting mode: stops the recording, then segments, saves and emits the transcript
 #[tauri::command]
-pub async fn get_app_config_snapshot(
-    state: State<'_, AppState>,
-) -> Result<SnapshotEnvelope<AppConfigSnapshotData>, String> {
-    log::debug!("Command: get_app_config_snapshot");
-    let config = state.config.read().await.clone();
-    let data = AppConfigSnapshotData {
-        microphone_sensitivity: config.microphone_sensitivity,
-        recording_hotkey: config.recording_hotkey,
-        auto_copy_to_clipboard: config.auto_copy_to_clipboard,
-        auto_paste_text: config.auto_paste_text,
-        selected_audio_device: config.selected_audio_device,
-    };
-    let revision = state.app_config_revision.read().await.to_string();
-    Ok(SnapshotEnvelope { revision, data })
+pub async fn stop_meeting(state: State<'_, RecordingState>, app_handle: AppHandle) -> Result<(), String> {
+    log::info!("Command: stop_meeting");
+
+    if state.transcription_service.get_status().await == RecordingStatus::Recording {
+        // stop_recording сам завершит встречу после последних финальных фраз
+        let state_handle = app_handle
+            .try_state::<AppState>()
+            .ok_or_else(|| "AppState не доступен".to_string())?;
+        stop_recording(state_handle, app_handle.clone()).await?;
+    } else if !end_meeting(&app_handle) {
+        log::debug!("Meeting was not active");
+    }
+    Ok(())
 }
 
-/// Минимальный "public" снапшот stt-config для фронтенда.
-///
-/// Важно: не включаем backend_auth_token / backend_url (секреты), потому что снапшоты идут во все окна через IPC.
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct SttConfigSnapshotData {
-    pub provider: crate::domain::SttProviderType,
-    pub language: String,
-    pub auto_detect_language: bool,
-    pub enable_punctuation: bool,
-    pub filter_profanity: bool,
-    pub deepgram_api_key: Option<String>,
-    pub assemblyai_api_key: Option<String>,
-    pub model: Option<String>,
-    pub keep_connection_alive: bool,
-    pub deepgram_keyterms: Option<String>,
+/// Transcript of the meeting in progress, segmented by silence gaps so far (None — no meeting)
+#[tauri::command]
+pub async fn get_meeting_transcript(state: State<'_, RecordingState>) -> Result<Option<MeetingTranscript>, String> {
+    let meeting = state
+        .meeting
+        .lock()
+        .map_err(|_| "Meeting state is poisoned".to_string())?;
+    Ok(meeting.as_ref().map(|recorder| recorder.transcript(None)))
 }
 
-/// Get current STT configuration snapshot
+/// Ask for access to the configured calendar (system prompt for the macOS calendar).
+///
+/// Returns whether sessions can be titled from it.
 #[tauri::command]
-pub async fn get_stt_config_snapshot(
-    state: State<'_, AppState>,
-) -> Result<SnapshotEnvelope<SttConfigSnapshotData>, String> {
-    log::debug!("Command: get_stt_config_snapshot");
-    let config = state.transcription_service.get_config().await;
-    let data = SttConfigSnapshotData {
-        provider: config.provider,
-        language: config.language,
-        auto_detect_language: config.auto_detect_language,
-        enable_punctuation: config.enable_punctuation,
-        filter_profanity: config.filter_profanity,
-        deepgram_api_key: config.deepgram_api_key,
-        assemblyai_api_key: config.assemblyai_api_key,
-        model: config.model,
-        keep_connection_alive: config.keep_connection_alive,
-        deepgram_keyterms: config.deepgram_keyterms,
-    };
-    let revision = state.stt_config_revision.read().await.to_string();
-    Ok(SnapshotEnvelope { revision, data })
+pub async fn request_calendar_access(state: State<'_, AppState>) -> Result<bool, String> {
+    log::info!("Command: request_calendar_access");
+    request_calendar_provider_access(&state).await
 }
 
-/// Данные для snapshot авторизации
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct AuthStateData {
-    pub is_authenticated: bool,
+/// Calendar event in progress right now for the configured source (None — nothing scheduled)
+#[tauri::command]
+pub async fn get_current_calendar_event(state: State<'_, AppState>) -> Result<Option<CalendarEvent>, String> {
+    log::debug!("Command: get_current_calendar_event");
+    current_calendar_event(&state).await
 }
 
-/// Get current auth state snapshot
+/// Start LAN companion mode: mirrors partial/final transcriptions to a viewer on another device.
+///
+/// `port` — None → default port, 0 → any free port. Already running → returns current state.
 #[tauri::command]
-pub async fn get_auth_state_snapshot(state: State<'_, AppState>) -> Result<SnapshotEnvelope<AuthStateData>, String> {
-    log::trace!("Command: get_auth_state_snapshot");
-    let is_authenticated = *state.is_authenticated.read().await;
-    let revision = state.auth_state_revision.read().await.to_string();
-    Ok(SnapshotEnvelope {
-        revision,
-        data: AuthStateData { is_authenticated },
-    })
+pub async fn start_companion_server(
+    state: State<'_, IntegrationState>,
+    port: Option<u16>,
+) -> Result<CompanionServerInfo, String> {
+    log::info!("Command: start_companion_server - port: {:?}", port);
+    state.companion.start(port).await.map_err(|e| format!("{:#}", e))
 }
 
-/// Полный снапшот auth-session (device_id + tokens).
-///
-/// В отличие от auth-state, этот снапшот содержит секреты (access/refresh),
-/// поэтому его нельзя логировать/сериализовать в публичные конфиги.
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct AuthSessionSnapshotData {
-    pub device_id: String,
-    pub session: Option<AuthSessionSnapshotSessionData>,
+/// Stop companion mode and disconnect all viewers
+#[tauri::command]
+pub async fn stop_companion_server(state: State<'_, IntegrationState>) -> Result<(), String> {
+    log::info!("Command: stop_companion_server");
+    if !state.companion.stop() {
+        log::debug!("Companion server was not running");
+    }
+    Ok(())
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct AuthSessionSnapshotSessionData {
-    pub access_token: String,
-    pub refresh_token: Option<String>,
-    pub access_expires_at: String,
-    pub refresh_expires_at: Option<String>,
-    pub user: Option<AuthSessionSnapshotUserData>,
+/// Companion server state (None — not running)
+#[tauri::command]
+pub async fn get_companion_server_status(
+    state: State<'_, IntegrationState>,
+) -> Result<Option<CompanionServerInfo>, String> {
+    Ok(state.companion.info())
 }
 
+/// Настройки автозапуска при входе в систему
 #[derive(Debug, Clone, serde::Serialize)]
-pub struct AuthSessionSnapshotUserData {
-    pub id: String,
-    pub email: String,
-    pub email_verified: bool,
+pub struct LaunchAtLoginData {
+    pub enabled: bool,
+    pub start_minimized: bool,
+    /// false в портативном режиме — переключатель в настройках неактивен
+    pub available: bool,
 }
 
-fn ms_to_rfc3339(ms: i64) -> String {
-    // Важно: если ms некорректный — fallback на epoch, чтобы не падать.
-    chrono::DateTime::<chrono::Utc>::from_timestamp_millis(ms)
-        .unwrap_or_else(|| chrono::DateTime::<chrono::Utc>::from_timestamp(0, 0).unwrap())
-        .to_rfc3339()
+/// Get launch at login state
+#[tauri::command]
+pub async fn get_launch_at_login(
+    state: State<'_, ConfigState>,
+    app_handle: AppHandle,
+) -> Result<LaunchAtLoginData, String> {
+    log::debug!("Command: get_launch_at_login");
+    // Источник правды — система: пользователь мог убрать приложение из Login Items вручную
+    let enabled = crate::infrastructure::autostart::is_launch_at_login_enabled(&app_handle)
+        .map_err(|e| e.to_string())?;
+    Ok(LaunchAtLoginData {
+        enabled,
+        start_minimized: state.config.read().await.start_minimized,
+        available: crate::infrastructure::autostart::is_available(),
+    })
 }
 
-/// Get current auth session snapshot (for cross-window sync).
+/// Enable/disable launch at login (and optionally whether autostart keeps windows hidden)
 #[tauri::command]
-pub async fn get_auth_session_snapshot(
-    state: State<'_, AppState>,
-) -> Result<SnapshotEnvelope<AuthSessionSnapshotData>, String> {
-    log::trace!("Command: get_auth_session_snapshot");
+pub async fn set_launch_at_login(
+    state: State<'_, ConfigState>,
+    app_handle: AppHandle,
+    enabled: bool,
+    start_minimized: Option<bool>,
+) -> Result<(), String> {
+    log::info!("Command: set_launch_at_login - enabled: {}, start_minimized: {:?}", enabled, start_minimized);
 
-    let store = state.auth_store.read().await.clone();
-    let data = AuthSessionSnapshotData {
-        device_id: store.device_id,
-        session: store.session.map(|s| AuthSessionSnapshotSessionData {
-            access_token: s.access_token,
-            refresh_token: s.refresh_token,
-            access_expires_at: ms_to_rfc3339(s.access_expires_at_ms),
-            refresh_expires_at: s.refresh_expires_at_ms.map(ms_to_rfc3339),
-            user: s.user.map(|u| AuthSessionSnapshotUserData {
-                id: u.id,
-                email: u.email,
-                email_verified: u.email_verified,
-            }),
-        }),
-    };
+    crate::infrastructure::autostart::set_launch_at_login(&app_handle, enabled).map_err(|e| e.to_string())?;
 
-    let revision = state.auth_session_revision.read().await.to_string();
-    Ok(SnapshotEnvelope { revision, data })
+    let mut config = state.config.write().await;
+    config.launch_at_login = enabled;
+    if let Some(start_minimized) = start_minimized {
+        config.start_minimized = start_minimized;
+    }
+    ConfigStore::save_app_config(&config)
+        .await
+        .map_err(|e| format!("Failed to save app config: {}", e))
 }
 
-/// Get current UI preferences snapshot
-#[tauri::command]
-pub async fn get_ui_preferences_snapshot(state: State<'_, AppState>) -> Result<SnapshotEnvelope<crate::domain::UiPreferences>, String> {
-    log::debug!("Command: get_ui_preferences_snapshot");
-    let data = state.ui_preferences.read().await.clone();
-    let revision = state.ui_preferences_revision.read().await.to_string();
-    Ok(SnapshotEnvelope { revision, data })
-}
+//
+// Microphone Test Commands
+//
 
-/// Обновить UI-настройки (тема, локаль) и уведомить все окна
+use crate::application::{
+    apply_gain, limited_gain, run_gain_chain, AudioSpectrumAnalyzer, InputLevelMonitor, sensitivity_gain, AudioLevelMetrics, PREVIEW_CHUNK_SAMPLES,
+};
+use crate::infrastructure::audio::{
+    output_device_names, play_pcm_blocking, SystemAudioCapture, VadProcessor, VadSummary,
+};
+use crate::domain::AudioConfig;
+
+/// Start microphone test
 #[tauri::command]
-pub async fn update_ui_preferences(
-    state: State<'_, AppState>,
+pub async fn start_microphone_test(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
     app_handle: AppHandle,
-    window: Window,
-    theme: String,
-    locale: String,
-    use_system_theme: Option<bool>,
+    sensitivity: Option<u8>,
+    device_name: Option<String>,
 ) -> Result<(), String> {
-    let use_system_theme = use_system_theme.unwrap_or(false);
-    log::info!(
-        "Command: update_ui_preferences - theme: {}, locale: {}, use_system_theme: {}",
-        theme,
-        locale,
-        use_system_theme
-    );
+    log::info!("Command: start_microphone_test - device: {:?}", device_name);
 
+    #[cfg(target_os = "macos")]
     {
-        let current = state.ui_preferences.read().await;
-        if current.theme == theme && current.locale == locale && current.use_system_theme == use_system_theme {
-            return Ok(());
+        use crate::infrastructure::microphone_permission::{
+            microphone_permission_status, MicrophonePermissionStatus,
+        };
+
+        match microphone_permission_status() {
+            MicrophonePermissionStatus::Authorized | MicrophonePermissionStatus::NotDetermined => {}
+            _ => {
+                return Err(settings.localize(UiMessage::MicrophoneAccessDenied).await);
+            }
         }
     }
 
-    let prefs = crate::domain::UiPreferences {
-        theme: theme.clone(),
-        locale: locale.clone(),
-        use_system_theme,
+    let mut test_state = recording.microphone_test.write().await;
+
+    if test_state.is_testing {
+        return Err("Microphone test already running".to_string());
+    }
+
+    // Иначе воспроизведение прошлой записи попадёт в новую
+    if let Some(cancel) = test_state.playback_cancel.take() {
+        cancel.store(true, Ordering::Relaxed);
+    }
+
+    // Создаем новый audio capture для теста с выбранным устройством
+    let device_to_use = device_name.filter(|s| !s.is_empty()); // None если пустая строка
+    let mut capture = Box::new(
+        SystemAudioCapture::with_device(device_to_use.clone())
+            .map_err(|e| format!("Failed to create audio capture: {}", e))?,
+    );
+
+    // Инициализируем захват
+    capture
+        .initialize(AudioConfig::default())
+        .await
+        .map_err(|e| format!("Failed to initialize audio capture: {}", e))?;
+
+    // Сбрасываем буфер
+    test_state.buffer.lock().await.clear();
+    test_state.raw_buffer.lock().await.clear();
+
+    // Получаем ссылку на shared buffer
+    let buffer_for_task = test_state.buffer.clone();
+    let raw_buffer_for_task = test_state.raw_buffer.clone();
+
+    // Используем переданную чувствительность или загружаем из сохраненной конфигурации
+    let sensitivity = match sensitivity {
+        Some(s) => s.min(200),
+        None => settings.config.read().await.microphone_sensitivity,
     };
 
-    // Сохраняем в state
-    *state.ui_preferences.write().await = prefs.clone();
+    log::info!("Starting microphone test with sensitivity: {}%", sensitivity);
 
-    // Сохраняем на диск
-    ConfigStore::save_ui_preferences(&prefs)
-        .await
-        .map_err(|e| format!("Failed to save UI preferences: {}", e))?;
+    // Создаем канал для передачи данных из callback
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
 
-    // Bump revision и отправляем invalidation
-    let revision = AppState::bump_revision(&state.ui_preferences_revision).await;
-    let _ = app_handle.emit(
-        EVENT_STATE_SYNC_INVALIDATION,
-        crate::presentation::StateSyncInvalidationPayload {
-            topic: "ui-preferences".to_string(),
-            revision,
-            source_id: Some(window.label().to_string()),
-            timestamp_ms: chrono::Utc::now().timestamp_millis(),
-        },
-    );
+    let on_chunk = Arc::new(move |chunk: crate::domain::AudioChunk| {
+        let _ = tx.send(chunk);
+    });
 
-    Ok(())
-}
+    // Запускаем обработчик чанков в async контексте
+    let app_handle_clone = app_handle.clone();
 
-/// Update application configuration (e.g., microphone sensitivity, recording hotkey, auto-copy/paste)
-#[tauri::command]
-pub async fn update_app_config(
-    state: State<'_, AppState>,
-    app_handle: AppHandle,
-    window: Window,
-    microphone_sensitivity: Option<u8>,
-    recording_hotkey: Option<String>,
-    auto_copy_to_clipboard: Option<bool>,
-    auto_paste_text: Option<bool>,
-    selected_audio_device: Option<String>,
-) -> Result<(), String> {
-    log::info!("Command: update_app_config - sensitivity: {:?}, hotkey: {:?}, auto_copy: {:?}, auto_paste: {:?}, device: {:?}",
-        microphone_sensitivity, recording_hotkey, auto_copy_to_clipboard, auto_paste_text, selected_audio_device);
+    tokio::spawn(async move {
+        // Та же логика усиления, что в TranscriptionService
+        let requested_gain = sensitivity_gain(sensitivity);
 
-    // Защита от "тихих" провалов: если фронт случайно отправил snake_case ключи,
-    // Tauri не сматчит аргументы, и сюда придут одни None.
-    // Тогда лучше вернуть явную ошибку, чем сделать вид что всё ок.
-    if microphone_sensitivity.is_none()
-        && recording_hotkey.is_none()
-        && auto_copy_to_clipboard.is_none()
-        && auto_paste_text.is_none()
-        && selected_audio_device.is_none()
-    {
-        return Err("update_app_config: не получены поля для обновления. Проверьте, что фронтенд отправляет args в camelCase (например microphoneSensitivity, recordingHotkey, autoCopyToClipboard, autoPasteText, selectedAudioDevice).".to_string());
-    }
+        log::info!(
+            "Microphone test: sensitivity={}%, requested_gain={:.2}x",
+            sensitivity,
+            requested_gain
+        );
 
-    let mut config = state.config.write().await;
-    let mut hotkey_changed = false;
-    let mut any_changed = false;
+        let mut level_monitor = InputLevelMonitor::new(requested_gain);
+        let mut spectrum = AudioSpectrumAnalyzer::new();
 
-    if let Some(sensitivity) = microphone_sensitivity {
-        let clamped = sensitivity.min(200); // Ensure 0-200 range
-        if config.microphone_sensitivity != clamped {
-            log::info!("Updating microphone sensitivity: {} -> {}", config.microphone_sensitivity, clamped);
-            config.microphone_sensitivity = clamped;
-            any_changed = true;
-        }
+        while let Some(chunk) = rx.recv().await {
+            // Вычисляем уровень громкости ДО усиления
+            let max_amplitude: i32 = chunk
+                .data
+                .iter()
+                .map(|&s| (s as i32).abs())
+                .max()
+                .unwrap_or(0);
+            let normalized_level = (max_amplitude as f32 / 32767.0).sqrt().min(1.0);
+            let flags = level_monitor.push(&chunk.data);
 
-        // Обновляем также в TranscriptionService для применения в реальном времени
-        state.transcription_service.set_microphone_sensitivity(clamped).await;
-    }
+            // Отправляем событие в UI (показываем уровень ДО усиления для честной индикации,
+            // а клиппинг/тишину — с учётом усиления: "на этом усилении вход клиппирует")
+            let _ = app_handle_clone.emit(
+                EVENT_MICROPHONE_TEST_LEVEL,
+                MicrophoneTestLevelPayload {
+                    level: normalized_level,
+                    clipping: flags.clipping,
+                    too_quiet: flags.too_quiet,
+                    peak_dbfs: flags.peak_dbfs,
+                },
+            );
 
-    if let Some(new_hotkey) = recording_hotkey {
-        if new_hotkey != config.recording_hotkey {
-            // Валидируем что это корректная комбинация клавиш
-            use tauri_plugin_global_shortcut::Shortcut;
-            if new_hotkey.parse::<Shortcut>().is_err() {
-                return Err(format!("Неверный формат горячей клавиши: {}", new_hotkey));
+            let effective_gain = limited_gain(requested_gain, max_amplitude);
+            let amplified_data = apply_gain(&chunk.data, effective_gain);
+
+            if let Some(bars) = spectrum.push_samples(&amplified_data) {
+                let _ = app_handle_clone.emit(
+                    EVENT_MICROPHONE_TEST_SPECTRUM,
+                    AudioSpectrumPayload { bars: bars.to_vec() },
+                );
             }
 
-            log::info!("Updating recording hotkey: {} -> {}", config.recording_hotkey, new_hotkey);
-            config.recording_hotkey = new_hotkey;
-            hotkey_changed = true;
-            any_changed = true;
-        }
-    }
+            // Сохраняем усиленный звук в буфер (для честного воспроизведения)
+            let mut buffer = buffer_for_task.lock().await;
+            buffer.extend_from_slice(&amplified_data);
+            // Ограничиваем размер буфера (максимум 5 секунд = 80000 samples @ 16kHz)
+            let buffer_len = buffer.len();
+            if buffer_len > 80000 {
+                buffer.drain(0..buffer_len - 80000);
+            }
+            drop(buffer);
 
-    if let Some(auto_copy) = auto_copy_to_clipboard {
-        if config.auto_copy_to_clipboard != auto_copy {
-            log::info!("Updating auto_copy_to_clipboard: {} -> {}", config.auto_copy_to_clipboard, auto_copy);
-            config.auto_copy_to_clipboard = auto_copy;
-            any_changed = true;
+            let mut raw_buffer = raw_buffer_for_task.lock().await;
+            raw_buffer.extend_from_slice(&chunk.data);
+            let raw_len = raw_buffer.len();
+            if raw_len > 80000 {
+                raw_buffer.drain(0..raw_len - 80000);
+            }
         }
-    }
+    });
 
-    if let Some(auto_paste) = auto_paste_text {
-        if config.auto_paste_text != auto_paste {
-            log::info!("Updating auto_paste_text: {} -> {}", config.auto_paste_text, auto_paste);
-            config.auto_paste_text = auto_paste;
-            any_changed = true;
-        }
-    }
+    // Запускаем захват
+    capture
+        .start_capture(on_chunk)
+        .await
+        .map_err(|e| format!("Failed to start audio capture: {}", e))?;
 
-    let mut device_changed = false;
-    if let Some(device) = selected_audio_device {
-        let device_opt = if device.is_empty() { None } else { Some(device.clone()) };
+    test_state.capture = Some(capture);
+    test_state.is_testing = true;
 
-        // Проверяем изменилось ли устройство
-        if config.selected_audio_device != device_opt {
-            log::info!("Updating selected_audio_device: {:?} -> {:?}", config.selected_audio_device, device_opt);
-            config.selected_audio_device = device_opt;
-            device_changed = true;
-            any_changed = true;
-        }
-    }
+    log::info!("Microphone test started");
+    Ok(())
+}
 
-    // Если ничего не менялось — выходим без лишнего I/O и invalidation
-    if !any_changed {
-        drop(config);
-        log::info!("App config unchanged, skipping save");
-        return Ok(());
+/// Stop microphone test and return recorded audio
+#[tauri::command]
+pub async fn stop_microphone_test(
+    state: State<'_, RecordingState>,
+) -> Result<Vec<i16>, String> {
+    log::info!("Command: stop_microphone_test");
+
+    let mut test_state = state.microphone_test.write().await;
+
+    if !test_state.is_testing {
+        return Err("Microphone test not running".to_string());
     }
 
-    log::info!("Saving app config to disk: sensitivity={}, hotkey={}, provider={:?}, language={}, device={:?}",
-        config.microphone_sensitivity, config.recording_hotkey, config.stt.provider, config.stt.language, config.selected_audio_device);
+    // Останавливаем захват
+    if let Some(mut capture) = test_state.capture.take() {
+        capture
+            .stop_capture()
+            .await
+            .map_err(|e| format!("Failed to stop audio capture: {}", e))?;
+    }
 
-    // Запоминаем selected_audio_device для применения после сохранения
-    let device_to_apply = if device_changed {
-        Some(config.selected_audio_device.clone())
-    } else {
-        None
-    };
+    test_state.is_testing = false;
 
-    // Сохраняем конфигурацию на диск
-    ConfigStore::save_app_config(&config)
-        .await
-        .map_err(|e| format!("Failed to save app config: {}", e))?;
+    // Возвращаем копию буфера; сам буфер остаётся для play_microphone_test до следующего теста
+    let buffer = test_state.buffer.lock().await.clone();
 
-    // Если горячая клавиша изменилась - перерегистрируем её
-    if hotkey_changed {
-        drop(config); // освобождаем lock перед async операцией
+    log::info!("Microphone test stopped, buffer size: {} samples", buffer.len());
+    Ok(buffer)
+}
 
-        log::info!("Re-registering recording hotkey");
+/// Play the last microphone test recording natively on an output device (None — system default output).
+///
+/// Resolves when playback ends; a new call or stop_microphone_test_playback cancels the current one.
+#[tauri::command]
+pub async fn play_microphone_test(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+    device: Option<String>,
+) -> Result<(), String> {
+    log::info!("Command: play_microphone_test - device: {:?}", device);
 
-        // Перерегистрируем горячую клавишу
-        register_recording_hotkey(state.clone(), app_handle.clone()).await?;
-    } else {
-        drop(config); // освобождаем lock если не было hotkey_changed
-    }
+    let lang = settings.ui_language().await;
+    let (samples, cancel) = {
+        let mut test_state = recording.microphone_test.write().await;
+        if test_state.is_testing {
+            return Err(UiMessage::MicrophoneTestRunning.text(lang));
+        }
+        let samples = test_state.buffer.lock().await.clone();
+        if samples.is_empty() {
+            return Err(UiMessage::MicrophoneTestMissing.text(lang));
+        }
 
-    // Если устройство изменилось - пересоздаем audio capture
-    if let Some(device_opt) = device_to_apply {
-        log::info!("Applying changed audio device: {:?}", device_opt);
+        if let Some(previous) = test_state.playback_cancel.take() {
+            previous.store(true, Ordering::Relaxed);
+        }
+        let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        test_state.playback_cancel = Some(cancel.clone());
+        (samples, cancel)
+    };
 
-        state.recreate_audio_capture_with_device(device_opt.clone(), app_handle.clone())
-            .await
-            .map_err(|e| {
-                log::error!("Failed to apply new audio device: {}", e);
-                format!("Настройки сохранены, но не удалось применить новое устройство записи: {}", e)
-            })?;
+    let device = device.filter(|name| !name.is_empty());
+    let sample_rate = AudioConfig::default().sample_rate;
+    let cancel_for_task = cancel.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        play_pcm_blocking(&samples, sample_rate, device.as_deref(), &cancel_for_task)
+    })
+    .await
+    .map_err(|e| format!("Playback task failed: {}", e))?;
 
-        log::info!("Audio device changed and applied successfully");
+    let mut test_state = recording.microphone_test.write().await;
+    if test_state
+        .playback_cancel
+        .as_ref()
+        .is_some_and(|current| Arc::ptr_eq(current, &cancel))
+    {
+        test_state.playback_cancel = None;
     }
+    result.map_err(|e| e.to_string())
+}
 
-    // Синхронизация между окнами через state-sync
-    let revision = AppState::bump_revision(&state.app_config_revision).await;
-    let _ = app_handle.emit(
-        EVENT_STATE_SYNC_INVALIDATION,
-        crate::presentation::StateSyncInvalidationPayload {
-            topic: "app-config".to_string(),
-            revision,
-            source_id: Some(window.label().to_string()),
-            timestamp_ms: chrono::Utc::now().timestamp_millis(),
-        },
-    );
-
-    log::info!("App configuration updated and saved successfully");
+/// Stop native playback of the microphone test recording
+#[tauri::command]
+pub async fn stop_microphone_test_playback(state: State<'_, RecordingState>) -> Result<(), String> {
+    log::info!("Command: stop_microphone_test_playback");
+    if let Some(cancel) = state.microphone_test.write().await.playback_cancel.take() {
+        cancel.store(true, Ordering::Relaxed);
+    }
     Ok(())
 }
 
 //
-// Microphone Test Commands
+// Accuracy Self-Test Commands
 //
 
-use crate::infrastructure::audio::SystemAudioCapture;
-use crate::domain::AudioConfig;
+/// Запись теста точности: не больше 2 минут @ 16kHz (эталонный текст читается примерно за полминуты)
+const MAX_ACCURACY_TEST_SAMPLES: usize = 16_000 * 120;
 
-/// Start microphone test
+/// Сколько результатов тестов точности хранить для графика прогресса
+const MAX_ACCURACY_RESULTS: usize = 100;
+
+fn find_accuracy_script(script_id: &str) -> Option<AccuracyScript> {
+    builtin_accuracy_scripts().into_iter().find(|s| s.id == script_id)
+}
+
+/// Reference scripts for the accuracy self-test (`language` — only scripts in this language)
 #[tauri::command]
-pub async fn start_microphone_test(
-    state: State<'_, AppState>,
-    app_handle: AppHandle,
-    sensitivity: Option<u8>,
-    device_name: Option<String>,
+pub async fn get_accuracy_scripts(language: Option<String>) -> Result<Vec<AccuracyScript>, String> {
+    let scripts = builtin_accuracy_scripts();
+    let Some(language) = language.filter(|l| !l.trim().is_empty()) else {
+        return Ok(scripts);
+    };
+    let base = language.trim().split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    Ok(scripts.into_iter().filter(|s| s.language == base).collect())
+}
+
+/// Start recording the user reading a reference script (microphone and gain from the saved settings)
+#[tauri::command]
+pub async fn start_accuracy_test(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+    script_id: String,
 ) -> Result<(), String> {
-    log::info!("Command: start_microphone_test - device: {:?}", device_name);
+    log::info!("Command: start_accuracy_test - script: {}", script_id);
 
     #[cfg(target_os = "macos")]
     {
@@ -1218,165 +3813,305 @@ pub async fn start_microphone_test(
         match microphone_permission_status() {
             MicrophonePermissionStatus::Authorized | MicrophonePermissionStatus::NotDetermined => {}
             _ => {
-                return Err(
-                    "Нет доступа к микрофону. Откройте macOS System Settings → Privacy & Security → Microphone и включите доступ для приложения."
-                        .to_string(),
-                );
+                return Err(settings.localize(UiMessage::MicrophoneAccessDenied).await);
             }
         }
     }
 
-    let mut test_state = state.microphone_test.write().await;
-
-    if test_state.is_testing {
-        return Err("Microphone test already running".to_string());
+    let mut test_state = recording.accuracy_test.write().await;
+    if test_state.script_id.is_some() {
+        return Err(settings.localize(UiMessage::AccuracyTestRunning).await);
+    }
+    if find_accuracy_script(&script_id).is_none() {
+        return Err(settings.localize(UiMessage::AccuracyScriptNotFound { script: script_id }).await);
     }
 
-    // Создаем новый audio capture для теста с выбранным устройством
-    let device_to_use = device_name.filter(|s| !s.is_empty()); // None если пустая строка
+    let (device, sensitivity) = {
+        let config = settings.config.read().await;
+        (
+            config.selected_audio_device.clone().filter(|d| !d.is_empty()),
+            config.microphone_sensitivity,
+        )
+    };
     let mut capture = Box::new(
-        SystemAudioCapture::with_device(device_to_use.clone())
-            .map_err(|e| format!("Failed to create audio capture: {}", e))?,
+        SystemAudioCapture::with_device(device).map_err(|e| format!("Failed to create audio capture: {}", e))?,
     );
-
-    // Инициализируем захват
     capture
         .initialize(AudioConfig::default())
         .await
         .map_err(|e| format!("Failed to initialize audio capture: {}", e))?;
 
-    // Сбрасываем буфер
     test_state.buffer.lock().await.clear();
-
-    // Получаем ссылку на shared buffer
     let buffer_for_task = test_state.buffer.clone();
-
-    // Используем переданную чувствительность или загружаем из сохраненной конфигурации
-    let sensitivity = match sensitivity {
-        Some(s) => s.min(200),
-        None => state.config.read().await.microphone_sensitivity,
-    };
-
-    log::info!("Starting microphone test with sensitivity: {}%", sensitivity);
-
-    // Создаем канал для передачи данных из callback
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
-
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<crate::domain::AudioChunk>();
     let on_chunk = Arc::new(move |chunk: crate::domain::AudioChunk| {
         let _ = tx.send(chunk);
     });
 
-    // Запускаем обработчик чанков в async контексте
-    let app_handle_clone = app_handle.clone();
-
     tokio::spawn(async move {
-        // Вычисляем коэффициент усиления (та же логика что в TranscriptionService)
-        let requested_gain = if sensitivity <= 100 {
-            // 0-100% → 0.0x-1.0x (приглушение/нормальный уровень)
-            sensitivity as f32 / 100.0
-        } else {
-            // 100-200% → 1.0x-5.0x (усиление для тихих микрофонов)
-            1.0 + (sensitivity - 100) as f32 / 100.0 * 4.0
-        };
-
-        log::info!(
-            "Microphone test: sensitivity={}%, requested_gain={:.2}x",
-            sensitivity,
-            requested_gain
-        );
-
+        // Усиление как при обычной записи: тест должен мерить те же настройки
+        let requested_gain = sensitivity_gain(sensitivity);
         while let Some(chunk) = rx.recv().await {
-            // Вычисляем уровень громкости ДО усиления
-            let max_amplitude: i32 = chunk
-                .data
-                .iter()
-                .map(|&s| (s as i32).abs())
-                .max()
-                .unwrap_or(0);
-            let normalized_level = (max_amplitude as f32 / 32767.0).sqrt().min(1.0);
-
-            // Отправляем событие в UI (показываем уровень ДО усиления для честной индикации)
-            let _ = app_handle_clone.emit(
-                EVENT_MICROPHONE_TEST_LEVEL,
-                MicrophoneTestLevelPayload {
-                    level: normalized_level,
-                },
-            );
-
-            // Простой limiter: если requested_gain приводит к клиппингу — уменьшаем gain для этого чанка.
-            let effective_gain = if max_amplitude <= 0 {
-                requested_gain
-            } else {
-                let headroom = 0.98_f32;
-                let limiter_gain = (32767.0 * headroom) / (max_amplitude as f32);
-                requested_gain.min(limiter_gain)
-            };
-
-            // Применяем gain к каждому сэмплу с защитой от clipping
-            let amplified_data: Vec<i16> = chunk
-                .data
-                .iter()
-                .map(|&sample| {
-                    let amplified = (sample as f32 * effective_gain).clamp(-32767.0, 32767.0);
-                    amplified as i16
-                })
-                .collect();
+            let max_amplitude = chunk.data.iter().map(|&s| (s as i32).abs()).max().unwrap_or(0);
+            let amplified = apply_gain(&chunk.data, limited_gain(requested_gain, max_amplitude));
 
-            // Сохраняем усиленный звук в буфер (для честного воспроизведения)
             let mut buffer = buffer_for_task.lock().await;
-            buffer.extend_from_slice(&amplified_data);
-            // Ограничиваем размер буфера (максимум 5 секунд = 80000 samples @ 16kHz)
-            let buffer_len = buffer.len();
-            if buffer_len > 80000 {
-                buffer.drain(0..buffer_len - 80000);
-            }
+            let room = MAX_ACCURACY_TEST_SAMPLES.saturating_sub(buffer.len());
+            buffer.extend_from_slice(&amplified[..amplified.len().min(room)]);
         }
     });
 
-    // Запускаем захват
     capture
         .start_capture(on_chunk)
         .await
         .map_err(|e| format!("Failed to start audio capture: {}", e))?;
 
     test_state.capture = Some(capture);
-    test_state.is_testing = true;
-
-    log::info!("Microphone test started");
+    test_state.script_id = Some(script_id);
+    log::info!("Accuracy test started");
     Ok(())
 }
 
-/// Stop microphone test and return recorded audio
+/// Stop the accuracy test, transcribe the reading with the current settings and score it (WER/CER).
+///
+/// Результат сохраняется в историю тестов, чтобы отслеживать прогресс после смены настроек.
 #[tauri::command]
-pub async fn stop_microphone_test(
-    state: State<'_, AppState>,
-) -> Result<Vec<i16>, String> {
-    log::info!("Command: stop_microphone_test");
+pub async fn stop_accuracy_test(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+) -> Result<AccuracyTestResult, String> {
+    log::info!("Command: stop_accuracy_test");
+
+    let (script_id, samples) = {
+        let mut test_state = recording.accuracy_test.write().await;
+        let Some(script_id) = test_state.script_id.take() else {
+            return Err(settings.localize(UiMessage::AccuracyTestNotRunning).await);
+        };
+        if let Some(mut capture) = test_state.capture.take() {
+            if let Err(e) = capture.stop_capture().await {
+                log::warn!("Failed to stop accuracy test capture: {}", e);
+            }
+        }
+        let samples = std::mem::take(&mut *test_state.buffer.lock().await);
+        (script_id, samples)
+    };
 
-    let mut test_state = state.microphone_test.write().await;
+    if samples.is_empty() {
+        return Err(settings.localize(UiMessage::AccuracyTestNoAudio).await);
+    }
+    let script =
+        find_accuracy_script(&script_id).ok_or_else(|| format!("Unknown accuracy script: {}", script_id))?;
 
-    if !test_state.is_testing {
-        return Err("Microphone test not running".to_string());
+    // Язык — эталонного текста, остальное — текущие настройки
+    let mut config = recording.transcription_service.get_config().await;
+    SttSessionOverride::language(script.language.clone()).apply_to(&mut config);
+
+    let sample_rate = AudioConfig::default().sample_rate;
+    let transcript = recording
+        .transcription_service
+        .transcribe_samples_with(&config, &samples, sample_rate)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let audio_ms = samples.len() as u64 * 1000 / sample_rate as u64;
+    let result = score_accuracy_test(&script, &transcript, &config, audio_ms, chrono::Utc::now().timestamp());
+    log::info!(
+        "Accuracy test '{}': WER {:.1}%, CER {:.1}% ({:?})",
+        script.id,
+        result.word_error_rate * 100.0,
+        result.char_error_rate * 100.0,
+        config.provider
+    );
+
+    let mut results = ConfigStore::load_accuracy_results().await.unwrap_or_else(|e| {
+        log::warn!("Failed to load accuracy test results: {}", e);
+        Vec::new()
+    });
+    results.push(result.clone());
+    let excess = results.len().saturating_sub(MAX_ACCURACY_RESULTS);
+    results.drain(..excess);
+    if let Err(e) = ConfigStore::save_accuracy_results(&results).await {
+        log::warn!("Failed to save accuracy test results: {}", e);
     }
 
-    // Останавливаем захват
+    Ok(result)
+}
+
+/// Cancel the accuracy test without scoring
+#[tauri::command]
+pub async fn cancel_accuracy_test(state: State<'_, RecordingState>) -> Result<(), String> {
+    log::info!("Command: cancel_accuracy_test");
+    let mut test_state = state.accuracy_test.write().await;
+    test_state.script_id = None;
     if let Some(mut capture) = test_state.capture.take() {
         capture
             .stop_capture()
             .await
             .map_err(|e| format!("Failed to stop audio capture: {}", e))?;
     }
+    test_state.buffer.lock().await.clear();
+    Ok(())
+}
 
-    test_state.is_testing = false;
+/// Past accuracy test results, oldest first
+#[tauri::command]
+pub async fn get_accuracy_history() -> Result<Vec<AccuracyTestResult>, String> {
+    ConfigStore::load_accuracy_results().await.map_err(|e| e.to_string())
+}
 
-    // Возвращаем копию буфера и очищаем его
-    let mut buffer_guard = test_state.buffer.lock().await;
-    let buffer = buffer_guard.clone();
-    buffer_guard.clear();
-    drop(buffer_guard);
+/// Forget past accuracy test results
+#[tauri::command]
+pub async fn clear_accuracy_history() -> Result<(), String> {
+    log::info!("Command: clear_accuracy_history");
+    ConfigStore::delete_accuracy_results().await.map_err(|e| e.to_string())
+}
 
-    log::info!("Microphone test stopped, buffer size: {} samples", buffer.len());
-    Ok(buffer)
+//
+// File Transcription Job Commands
+//
+
+/// File transcription jobs (dropped audio files), oldest first
+#[tauri::command]
+pub async fn list_transcription_jobs(state: State<'_, RecordingState>) -> Result<Vec<TranscriptionJob>, String> {
+    Ok(state.transcription_jobs.read().await.clone())
+}
+
+/// Pause a queued or running job; the chapter being transcribed is finished first
+#[tauri::command]
+pub async fn pause_job(state: State<'_, AppState>, job_id: String) -> Result<(), String> {
+    log::info!("Command: pause_job - {}", job_id);
+    change_transcription_job(&state, &job_id, TranscriptionJob::pause, UiMessage::TranscriptionJobNotActive).await
+}
+
+/// Resume a paused or failed job from the first chapter that is not transcribed yet
+#[tauri::command]
+pub async fn resume_job(state: State<'_, AppState>, app_handle: AppHandle, job_id: String) -> Result<(), String> {
+    log::info!("Command: resume_job - {}", job_id);
+    change_transcription_job(&state, &job_id, TranscriptionJob::resume, UiMessage::TranscriptionJobNotResumable)
+        .await?;
+    transcription_jobs::spawn_worker(&app_handle);
+    Ok(())
+}
+
+/// Cancel a job; chapters transcribed so far are kept
+#[tauri::command]
+pub async fn cancel_job(state: State<'_, AppState>, job_id: String) -> Result<(), String> {
+    log::info!("Command: cancel_job - {}", job_id);
+    change_transcription_job(&state, &job_id, TranscriptionJob::cancel, UiMessage::TranscriptionJobFinished).await
+}
+
+async fn change_transcription_job(
+    state: &AppState,
+    job_id: &str,
+    change: fn(&mut TranscriptionJob) -> bool,
+    refused: UiMessage,
+) -> Result<(), String> {
+    match transcription_jobs::update_job(state, job_id, change).await {
+        None => Err(state
+            .localize(UiMessage::TranscriptionJobNotFound { id: job_id.to_string() })
+            .await),
+        Some(false) => Err(state.localize(refused).await),
+        Some(true) => {
+            transcription_jobs::persist(state).await;
+            Ok(())
+        }
+    }
+}
+
+/// Не больше 30 секунд @ 16kHz — это тестовый стенд, а не обработка файлов
+const MAX_PREVIEW_SAMPLES: usize = 16_000 * 30;
+
+/// One stage of the capture processing chain, in the order the live recording applies them
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum ProcessingStageMetrics {
+    /// VAD видит сырой сигнал (до усиления), как VadCaptureWrapper при записи
+    Vad(VadSummary),
+    Gain {
+        sensitivity: u8,
+        requested_gain: f32,
+        output: AudioLevelMetrics,
+    },
+    /// Лимитер — единственная автоматическая регулировка уровня (отдельных AGC/шумоподавления в цепочке нет)
+    Limiter {
+        min_effective_gain: f32,
+        limited_chunks: usize,
+        total_chunks: usize,
+        output: AudioLevelMetrics,
+    },
+}
+
+/// Processed audio plus per-stage metrics for the microphone test UI
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProcessingChainPreview {
+    pub sample_rate: u32,
+    /// Звук после цепочки — то, что ушло бы в STT
+    pub samples: Vec<i16>,
+    pub input: AudioLevelMetrics,
+    pub stages: Vec<ProcessingStageMetrics>,
+}
+
+/// Run a short unprocessed 16kHz mono buffer through the capture processing chain.
+///
+/// `samples` — None → raw audio of the last microphone test (stop_microphone_test returns it already amplified).
+/// `sensitivity` / `vad_silence_timeout_ms` override the saved settings so the UI can compare values.
+#[tauri::command]
+pub async fn preview_processing_chain(
+    recording: State<'_, RecordingState>,
+    settings: State<'_, ConfigState>,
+    samples: Option<Vec<i16>>,
+    sensitivity: Option<u8>,
+    vad_silence_timeout_ms: Option<u64>,
+) -> Result<ProcessingChainPreview, String> {
+    let samples = match samples {
+        Some(samples) => samples,
+        None => recording.microphone_test.read().await.raw_buffer.lock().await.clone(),
+    };
+    log::debug!("Command: preview_processing_chain - samples: {}", samples.len());
+
+    if samples.is_empty() {
+        return Err(settings.localize(UiMessage::MicrophoneTestNoAudio).await);
+    }
+    if samples.len() > MAX_PREVIEW_SAMPLES {
+        return Err(settings
+            .localize(UiMessage::MicrophoneTestTooLong {
+                samples: samples.len(),
+                max: MAX_PREVIEW_SAMPLES,
+            })
+            .await);
+    }
+
+    let (saved_sensitivity, saved_timeout_ms) = {
+        let config = settings.config.read().await;
+        (config.microphone_sensitivity, config.vad_silence_timeout_ms)
+    };
+    let sensitivity = sensitivity.unwrap_or(saved_sensitivity).min(200);
+
+    let vad_summary = VadProcessor::new(Some(vad_silence_timeout_ms.unwrap_or(saved_timeout_ms)), None)
+        .and_then(|mut vad| vad.summarize(&samples))
+        .map_err(|e| format!("VAD error: {}", e))?;
+
+    let (processed, gain) = run_gain_chain(&samples, sensitivity, PREVIEW_CHUNK_SAMPLES);
+
+    Ok(ProcessingChainPreview {
+        sample_rate: AudioConfig::default().sample_rate,
+        input: AudioLevelMetrics::measure(&samples),
+        stages: vec![
+            ProcessingStageMetrics::Vad(vad_summary),
+            ProcessingStageMetrics::Gain {
+                sensitivity: gain.sensitivity,
+                requested_gain: gain.requested_gain,
+                output: gain.after_gain,
+            },
+            ProcessingStageMetrics::Limiter {
+                min_effective_gain: gain.min_effective_gain,
+                limited_chunks: gain.limited_chunks,
+                total_chunks: gain.total_chunks,
+                output: gain.after_limiter,
+            },
+        ],
+        samples: processed,
+    })
 }
 
 //
@@ -1390,9 +4125,8 @@ pub async fn register_recording_hotkey(
     app_handle: AppHandle,
 ) -> Result<(), String> {
     use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
-    use std::sync::atomic::Ordering;
 
-    let hotkey = state.config.read().await.recording_hotkey.clone();
+    let hotkey = state.settings.config.read().await.recording_hotkey.clone();
     log::info!("Command: register_recording_hotkey - hotkey: {}", hotkey);
 
     // ВАЖНО: сначала убеждаемся, что хоткей парсится, и только потом снимаем текущие регистрации.
@@ -1414,7 +4148,7 @@ pub async fn register_recording_hotkey(
                         // чтобы UI и фактический хоткей не расходились.
                         if normalized != hotkey {
                             let (should_save, config_snapshot) = {
-                                let mut cfg = state.config.write().await;
+                                let mut cfg = state.settings.config.write().await;
                                 let changed = cfg.recording_hotkey != normalized;
                                 if changed {
                                     cfg.recording_hotkey = normalized.clone();
@@ -1444,7 +4178,7 @@ pub async fn register_recording_hotkey(
 
                         // Синхронизируем SoT на дефолт, чтобы UI не показывал неработающее значение.
                         let config_snapshot = {
-                            let mut cfg = state.config.write().await;
+                            let mut cfg = state.settings.config.write().await;
                             cfg.recording_hotkey = fallback.clone();
                             cfg.clone()
                         };
@@ -1453,7 +4187,7 @@ pub async fn register_recording_hotkey(
                         }
 
                         // Пинаем invalidation, чтобы UI получил реальный (рабочий) хоткей.
-                        let revision = AppState::bump_revision(&state.app_config_revision).await;
+                        let revision = AppState::bump_revision(&state.settings.app_config_revision).await;
                         let _ = app_handle.emit(
                             EVENT_STATE_SYNC_INVALIDATION,
                             crate::presentation::StateSyncInvalidationPayload {
@@ -1480,7 +4214,7 @@ pub async fn register_recording_hotkey(
                 );
 
                 let config_snapshot = {
-                    let mut cfg = state.config.write().await;
+                    let mut cfg = state.settings.config.write().await;
                     cfg.recording_hotkey = fallback.clone();
                     cfg.clone()
                 };
@@ -1488,7 +4222,7 @@ pub async fn register_recording_hotkey(
                     log::warn!("Failed to persist fallback hotkey to app_config.json: {}", e);
                 }
 
-                let revision = AppState::bump_revision(&state.app_config_revision).await;
+                let revision = AppState::bump_revision(&state.settings.app_config_revision).await;
                 let _ = app_handle.emit(
                     EVENT_STATE_SYNC_INVALIDATION,
                     crate::presentation::StateSyncInvalidationPayload {
@@ -1525,16 +4259,10 @@ pub async fn register_recording_hotkey(
             if let (Some(state), Some(window)) = (state_opt, window_opt) {
                 let app_for_call = app_clone.clone();
 
-                // Дебаунс: защищаемся от key repeat / двойных срабатываний.
-                // Иначе окно может "мигать" (показ/скрытие несколько раз подряд).
-                let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
-                let last_ms = state.inner().last_recording_hotkey_ms.load(Ordering::Relaxed);
-                let delta = now_ms.saturating_sub(last_ms);
-                if delta < 450 {
-                    log::debug!("Hotkey ignored (debounced): {}ms since last trigger", delta);
+                if recording_hotkey_debounced(state.inner()) {
                     return;
                 }
-                state.inner().last_recording_hotkey_ms.store(now_ms, Ordering::Relaxed);
+                crate::presentation::warm_pool::record_hotkey_activity(&app_clone);
 
                 if let Err(e) = crate::presentation::commands::toggle_recording_with_window_internal(
                     state.inner(),
@@ -1545,12 +4273,294 @@ pub async fn register_recording_hotkey(
                 }
             }
         });
-    }).map_err(|e| format!("Failed to register hotkey '{}': {}", effective_hotkey, e))?;
+    }).map_err(|e| {
+        let suggestions = suggest_free_hotkeys(&app_handle, 3);
+        if suggestions.is_empty() {
+            format!("Failed to register hotkey '{}': {}", effective_hotkey, e)
+        } else {
+            format!(
+                "Failed to register hotkey '{}': {}. Free alternatives: {}",
+                effective_hotkey,
+                e,
+                suggestions.join(", ")
+            )
+        }
+    })?;
 
     log::info!("Successfully registered hotkey: {}", effective_hotkey);
+
+    let (profiles, spelling_hotkey) = {
+        let config = state.settings.config.read().await;
+        (config.recording_profiles.clone(), config.spelling_hotkey.clone())
+    };
+    let registered = register_recording_profile_hotkeys(&app_handle, &shortcut, &profiles);
+    if let Some(hotkey) = spelling_hotkey {
+        register_spelling_hotkey(&app_handle, &registered, &hotkey);
+    }
+    Ok(())
+}
+
+/// Дебаунс общий для всех хоткеев записи: защищаемся от key repeat / двойных срабатываний.
+/// Иначе окно может "мигать" (показ/скрытие несколько раз подряд).
+fn recording_hotkey_debounced(state: &AppState) -> bool {
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let last_ms = state.last_recording_hotkey_ms.load(Ordering::Relaxed);
+    let delta = now_ms.saturating_sub(last_ms);
+    if delta < 450 {
+        log::debug!("Hotkey ignored (debounced): {}ms since last trigger", delta);
+        return true;
+    }
+    state.last_recording_hotkey_ms.store(now_ms, Ordering::Relaxed);
+    false
+}
+
+/// Регистрирует хоткеи профилей записи поверх основного.
+///
+/// Ошибка одного профиля (не парсится, занято) не мешает остальным и основному хоткею — только лог.
+/// Возвращает все зарегистрированные хоткеи записи (основной и профилей).
+fn register_recording_profile_hotkeys(
+    app_handle: &AppHandle,
+    main_shortcut: &tauri_plugin_global_shortcut::Shortcut,
+    profiles: &[RecordingProfile],
+) -> Vec<tauri_plugin_global_shortcut::Shortcut> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+    let mut registered: Vec<Shortcut> = vec![*main_shortcut];
+    for profile in profiles {
+        let Some(hotkey) = profile.hotkey.as_deref().map(str::trim).filter(|h| !h.is_empty()) else {
+            continue;
+        };
+        let Some(shortcut) = crate::infrastructure::hotkey::normalize_recording_hotkey(hotkey)
+            .and_then(|normalized| normalized.parse::<Shortcut>().ok())
+        else {
+            log::warn!("Recording profile '{}': invalid hotkey '{}', skipping", profile.name, hotkey);
+            continue;
+        };
+        if registered.contains(&shortcut) {
+            log::warn!(
+                "Recording profile '{}': hotkey '{}' is already used by another recording hotkey, skipping",
+                profile.name,
+                hotkey
+            );
+            continue;
+        }
+
+        let profile_name = profile.name.clone();
+        let result = app_handle.global_shortcut().on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state != ShortcutState::Pressed {
+                return;
+            }
+            log::debug!("Recording profile hotkey pressed: {}", profile_name);
+            let app_clone = app.clone();
+            let profile_name = profile_name.clone();
+            let _ = tauri::async_runtime::spawn(async move {
+                let state_opt = app_clone.try_state::<AppState>();
+                let window_opt = app_clone.get_webview_window("main");
+                let (Some(state), Some(window)) = (state_opt, window_opt) else {
+                    return;
+                };
+                if recording_hotkey_debounced(state.inner()) {
+                    return;
+                }
+                crate::presentation::warm_pool::record_hotkey_activity(&app_clone);
+                if let Err(e) =
+                    toggle_recording_with_profile_internal(state.inner(), window, app_clone.clone(), &profile_name).await
+                {
+                    log::error!("Failed to toggle recording with profile '{}': {}", profile_name, e);
+                }
+            });
+        });
+        match result {
+            Ok(()) => {
+                log::info!("Registered hotkey '{}' for recording profile '{}'", hotkey, profile.name);
+                registered.push(shortcut);
+            }
+            Err(e) => log::warn!(
+                "Failed to register hotkey '{}' for recording profile '{}': {}",
+                hotkey,
+                profile.name,
+                e
+            ),
+        }
+    }
+    registered
+}
+
+/// Хоткей режима по буквам: переключает режим, запись не трогает.
+/// Совпадение с хоткеем записи или ошибка регистрации — только лог.
+fn register_spelling_hotkey(
+    app_handle: &AppHandle,
+    recording_shortcuts: &[tauri_plugin_global_shortcut::Shortcut],
+    hotkey: &str,
+) {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+    let Some(shortcut) = crate::infrastructure::hotkey::normalize_recording_hotkey(hotkey)
+        .and_then(|normalized| normalized.parse::<Shortcut>().ok())
+    else {
+        log::warn!("Spelling mode: invalid hotkey '{}', skipping", hotkey);
+        return;
+    };
+    if recording_shortcuts.contains(&shortcut) {
+        log::warn!("Spelling mode: hotkey '{}' is already used for recording, skipping", hotkey);
+        return;
+    }
+
+    let result = app_handle.global_shortcut().on_shortcut(shortcut, move |app, _shortcut, event| {
+        if event.state != ShortcutState::Pressed {
+            return;
+        }
+        if let Some(state) = app.try_state::<AppState>() {
+            let service = &state.recording.transcription_service;
+            service.set_spelling_mode(!service.spelling_mode());
+        }
+    });
+    match result {
+        Ok(()) => log::info!("Registered spelling mode hotkey '{}'", hotkey),
+        Err(e) => log::warn!("Failed to register spelling mode hotkey '{}': {}", hotkey, e),
+    }
+}
+
+/// Включает/выключает диктовку по буквам (то же делают хоткей и голосовые команды).
+/// Режим действует до конца текущей записи; переключение приходит событием recording:spelling-mode
+#[tauri::command]
+pub async fn set_spelling_mode(state: State<'_, RecordingState>, enabled: bool) -> Result<(), String> {
+    log::info!("Command: set_spelling_mode - enabled: {}", enabled);
+    state.transcription_service.set_spelling_mode(enabled);
     Ok(())
 }
 
+/// Включена ли диктовка по буквам
+#[tauri::command]
+pub async fn get_spelling_mode(state: State<'_, RecordingState>) -> Result<bool, String> {
+    Ok(state.transcription_service.spelling_mode())
+}
+
+/// Почему сочетание нельзя использовать для записи
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HotkeyConflict {
+    /// Зарезервировано системой (Spotlight, скриншоты, копирование …)
+    System { owner: String },
+    /// Регистрация не прошла — сочетание держит другое приложение
+    OtherApp,
+}
+
+/// Результат проверки хоткея
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HotkeyAvailability {
+    pub hotkey: String,
+    pub available: bool,
+    pub conflict: Option<HotkeyConflict>,
+    /// Свободные альтернативы (только если сочетание занято)
+    pub suggestions: Vec<String>,
+}
+
+/// Свободно ли сочетание: пробная регистрация + снятие.
+/// Наш текущий хоткей считается свободным. На macOS чужие регистрации видны не всегда.
+fn probe_hotkey_registration(app_handle: &AppHandle, shortcut: &tauri_plugin_global_shortcut::Shortcut) -> bool {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let global_shortcut = app_handle.global_shortcut();
+    if global_shortcut.is_registered(*shortcut) {
+        return true;
+    }
+    match global_shortcut.register(*shortcut) {
+        Ok(()) => {
+            let _ = global_shortcut.unregister(*shortcut);
+            true
+        }
+        Err(e) => {
+            log::debug!("Hotkey probe failed: {}", e);
+            false
+        }
+    }
+}
+
+fn hotkey_conflict(
+    app_handle: &AppHandle,
+    shortcut: &tauri_plugin_global_shortcut::Shortcut,
+) -> Option<HotkeyConflict> {
+    if let Some(owner) = crate::infrastructure::hotkey::reserved_shortcut_owner(shortcut) {
+        return Some(HotkeyConflict::System {
+            owner: owner.to_string(),
+        });
+    }
+    if !probe_hotkey_registration(app_handle, shortcut) {
+        return Some(HotkeyConflict::OtherApp);
+    }
+    None
+}
+
+fn suggest_free_hotkeys(app_handle: &AppHandle, limit: usize) -> Vec<String> {
+    crate::infrastructure::hotkey::suggest_hotkeys(
+        crate::infrastructure::hotkey::HOTKEY_SUGGESTION_CANDIDATES,
+        limit,
+        |_, shortcut| probe_hotkey_registration(app_handle, shortcut),
+    )
+}
+
+fn hotkey_conflict_message(
+    app_handle: &AppHandle,
+    hotkey: &str,
+    conflict: &HotkeyConflict,
+    lang: UiLanguage,
+) -> String {
+    let hotkey = hotkey.to_string();
+    let reason = match conflict {
+        HotkeyConflict::System { owner } => UiMessage::HotkeyTakenBySystem {
+            hotkey,
+            owner: owner.clone(),
+        },
+        HotkeyConflict::OtherApp => UiMessage::HotkeyTakenByOtherApp { hotkey },
+    }
+    .text(lang);
+    let suggestions = suggest_free_hotkeys(app_handle, 3);
+    if suggestions.is_empty() {
+        reason
+    } else {
+        UiMessage::HotkeyConflictWithSuggestions { reason, suggestions }.text(lang)
+    }
+}
+
+/// Проверить, свободно ли сочетание, до сохранения (для UI настройки хоткея)
+#[tauri::command]
+pub async fn check_hotkey_availability(
+    state: State<'_, ConfigState>,
+    app_handle: AppHandle,
+    hotkey: String,
+) -> Result<HotkeyAvailability, String> {
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    log::info!("Command: check_hotkey_availability - hotkey: {}", hotkey);
+    let Ok(shortcut) = hotkey.parse::<Shortcut>() else {
+        return Err(state.localize(UiMessage::InvalidHotkey { hotkey }).await);
+    };
+
+    let conflict = hotkey_conflict(&app_handle, &shortcut);
+    let suggestions = if conflict.is_some() {
+        suggest_free_hotkeys(&app_handle, 5)
+    } else {
+        Vec::new()
+    };
+    Ok(HotkeyAvailability {
+        hotkey,
+        available: conflict.is_none(),
+        conflict,
+        suggestions,
+    })
+}
+
+/// Свободные сочетания для записи, от более удобных к менее
+#[tauri::command]
+pub async fn suggest_available_hotkeys(
+    app_handle: AppHandle,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    log::info!("Command: suggest_available_hotkeys");
+    Ok(suggest_free_hotkeys(&app_handle, limit.unwrap_or(5)))
+}
+
 /// Временно снять регистрацию горячей клавиши (пока пользователь настраивает новую)
 #[tauri::command]
 pub async fn unregister_recording_hotkey(
@@ -1580,11 +4590,56 @@ pub async fn check_for_updates(
     crate::infrastructure::updater::check_for_update(app_handle).await
 }
 
-/// Check and install application update with user confirmation
+/// Check and install application update with user confirmation
+#[tauri::command]
+pub async fn install_update(app_handle: AppHandle) -> Result<String, String> {
+    log::info!("Command: install_update");
+    crate::infrastructure::updater::check_and_install_update(app_handle).await
+}
+
+/// Get update details (version, notes, size, delta/full, signature status) before installing
+#[tauri::command]
+pub async fn get_update_details(
+    app_handle: AppHandle,
+) -> Result<Option<crate::infrastructure::updater::UpdateDetails>, String> {
+    log::info!("Command: get_update_details");
+    crate::infrastructure::updater::get_update_details(app_handle).await
+}
+
+/// Download update in the background without installing it (installed on next install_update or app exit)
+#[tauri::command]
+pub async fn download_update(
+    app_handle: AppHandle,
+) -> Result<Option<crate::infrastructure::updater::UpdateInfo>, String> {
+    log::info!("Command: download_update");
+    crate::infrastructure::updater::download_update(app_handle).await
+}
+
+/// Get update preferences (channel, skipped version, remind-later deadline)
+#[tauri::command]
+pub async fn get_update_preferences() -> Result<UpdatePreferences, String> {
+    Ok(crate::infrastructure::updater::get_update_preferences().await)
+}
+
+/// Switch update channel (stable/beta)
+#[tauri::command]
+pub async fn set_update_channel(channel: UpdateChannel) -> Result<UpdatePreferences, String> {
+    log::info!("Command: set_update_channel - {:?}", channel);
+    crate::infrastructure::updater::set_update_channel(channel).await
+}
+
+/// Postpone background update notifications ("remind me later")
 #[tauri::command]
-pub async fn install_update(app_handle: AppHandle) -> Result<String, String> {
-    log::info!("Command: install_update");
-    crate::infrastructure::updater::check_and_install_update(app_handle).await
+pub async fn remind_update_later() -> Result<UpdatePreferences, String> {
+    log::info!("Command: remind_update_later");
+    crate::infrastructure::updater::remind_update_later().await
+}
+
+/// Stop notifying about a specific version ("skip this version")
+#[tauri::command]
+pub async fn skip_update_version(version: String) -> Result<UpdatePreferences, String> {
+    log::info!("Command: skip_update_version - {}", version);
+    crate::infrastructure::updater::skip_update_version(version).await
 }
 
 //
@@ -1593,14 +4648,17 @@ pub async fn install_update(app_handle: AppHandle) -> Result<String, String> {
 
 use crate::infrastructure::models::{
     WhisperModelInfo, download_model, get_available_models,
-    is_model_downloaded, get_model_size, delete_model,
+    is_model_downloaded, get_model_size, delete_model, relocate_models_dir, ModelVerification,
 };
 
 /// Get list of available Whisper models
 #[tauri::command]
-pub async fn get_available_whisper_models() -> Result<Vec<WhisperModelInfo>, String> {
+pub async fn get_available_whisper_models(
+    state: State<'_, ConfigState>,
+) -> Result<Vec<WhisperModelInfo>, String> {
     log::debug!("Command: get_available_whisper_models");
 
+    let lang = state.ui_language().await;
     let mut models = get_available_models();
 
     // Обогащаем данными о локальном наличии
@@ -1614,12 +4672,11 @@ pub async fn get_available_whisper_models() -> Result<Vec<WhisperModelInfo>, Str
 
         // Добавляем информацию в description если модель скачана
         if is_downloaded {
-            if let Some(size) = local_size {
-                model.description = format!("{} (Скачана, {} на диске)",
-                    model.description, format_size_human(size));
-            } else {
-                model.description = format!("{} (Скачана)", model.description);
+            model.description = UiMessage::ModelDownloaded {
+                description: std::mem::take(&mut model.description),
+                size: local_size.map(format_size_human),
             }
+            .text(lang);
         }
     }
 
@@ -1688,6 +4745,121 @@ pub async fn download_whisper_model(
     Ok(format!("Model '{}' downloaded successfully", model_name))
 }
 
+/// Move Whisper models to another folder (e.g. an external drive); None — back to the default app data folder.
+///
+/// Existing models are migrated; returns the resulting models directory.
+#[tauri::command]
+pub async fn set_models_directory(
+    state: State<'_, ConfigState>,
+    app_handle: AppHandle,
+    window: Window,
+    path: Option<String>,
+) -> Result<String, String> {
+    log::info!("Command: set_models_directory - path: {:?}", path);
+
+    let new_dir = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(p) => {
+            let dir = std::path::PathBuf::from(p);
+            if !dir.is_absolute() {
+                return Err(format!("Models directory must be an absolute path: {}", p));
+            }
+            Some(dir)
+        }
+        None => None,
+    };
+
+    // Перенос гигабайтных моделей на другой диск — блокирующий IO
+    let models_dir_setting = new_dir.as_ref().map(|dir| dir.to_string_lossy().into_owned());
+    let target = tokio::task::spawn_blocking(move || relocate_models_dir(new_dir))
+        .await
+        .map_err(|e| format!("Models relocation task failed: {}", e))?
+        .map_err(|e| format!("Failed to move models: {}", e))?;
+
+    {
+        let mut config = state.config.write().await;
+        config.models_dir = models_dir_setting;
+        ConfigStore::save_app_config(&config)
+            .await
+            .map_err(|e| format!("Failed to save app config: {}", e))?;
+    }
+
+    let revision = AppState::bump_revision(&state.app_config_revision).await;
+    let _ = app_handle.emit(
+        EVENT_STATE_SYNC_INVALIDATION,
+        crate::presentation::StateSyncInvalidationPayload {
+            topic: "app-config".to_string(),
+            revision,
+            source_id: Some(window.label().to_string()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+
+    Ok(target.display().to_string())
+}
+
+/// Play a WAV/MP3 file instead of the microphone (debug setting for demos and hardware-free checks);
+/// None — back to the regular input.
+///
+/// The file is decoded up front, so a missing or unsupported file is rejected here and not on the next recording.
+#[tauri::command]
+pub async fn set_mock_audio_file(
+    state: State<'_, ConfigState>,
+    app_handle: AppHandle,
+    window: Window,
+    path: Option<String>,
+) -> Result<(), String> {
+    use crate::infrastructure::audio::FileAudioCapture;
+
+    log::info!("Command: set_mock_audio_file - path: {:?}", path);
+
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(p) = path.clone() {
+        if !std::path::Path::new(&p).is_absolute() {
+            return Err(format!("Mock audio file must be an absolute path: {}", p));
+        }
+        // Декодирование целого файла — блокирующий IO
+        let capture = tokio::task::spawn_blocking(move || FileAudioCapture::from_file(&p))
+            .await
+            .map_err(|e| format!("Failed to join decoding task: {}", e))?
+            .map_err(|e| e.to_string())?;
+        log::warn!(
+            "Microphone will be replaced by {:?} ({} ms) on the next recording",
+            capture.path(),
+            capture.duration_ms()
+        );
+    }
+
+    {
+        let mut config = state.config.write().await;
+        config.mock_audio_file = path;
+        ConfigStore::save_app_config(&config)
+            .await
+            .map_err(|e| format!("Failed to save app config: {}", e))?;
+    }
+
+    let revision = AppState::bump_revision(&state.app_config_revision).await;
+    let _ = app_handle.emit(
+        EVENT_STATE_SYNC_INVALIDATION,
+        crate::presentation::StateSyncInvalidationPayload {
+            topic: "app-config".to_string(),
+            revision,
+            source_id: Some(window.label().to_string()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Re-hash a downloaded Whisper model and compare it with the download-time and latest release checksums
+#[tauri::command]
+pub async fn verify_model(model: String) -> Result<ModelVerification, String> {
+    log::info!("Command: verify_model - model: {}", model);
+    crate::infrastructure::models::verify_model(&model)
+        .await
+        .map_err(|e| format!("Failed to verify model: {}", e))
+}
+
 /// Delete Whisper model
 #[tauri::command]
 pub async fn delete_whisper_model(model_name: String) -> Result<String, String> {
@@ -1699,6 +4871,13 @@ pub async fn delete_whisper_model(model_name: String) -> Result<String, String>
     Ok(format!("Model '{}' deleted successfully", model_name))
 }
 
+/// Abort the local Whisper transcription running after stop (false — nothing is running)
+#[tauri::command]
+pub async fn cancel_local_transcription() -> Result<bool, String> {
+    log::info!("Command: cancel_local_transcription");
+    Ok(crate::infrastructure::stt::cancel_whisper_transcription())
+}
+
 /// Get available audio input devices
 #[tauri::command]
 pub async fn get_audio_devices() -> Result<Vec<String>, String> {
@@ -1721,6 +4900,13 @@ pub async fn get_audio_devices() -> Result<Vec<String>, String> {
     Ok(devices)
 }
 
+/// Get list of available audio output devices (for microphone test playback)
+#[tauri::command]
+pub async fn get_output_audio_devices() -> Result<Vec<String>, String> {
+    log::info!("Command: get_output_audio_devices");
+    output_device_names().map_err(|e| e.to_string())
+}
+
 fn format_size_human(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
@@ -1758,6 +4944,184 @@ pub async fn request_accessibility_permission() -> Result<(), String> {
         .map_err(|e| e.to_string())
 }
 
+/// Статус разрешений macOS по функциям (микрофон, Accessibility, Input Monitoring, Screen Recording)
+/// со ссылками на нужные разделы System Settings. На других платформах всё `not_required`
+#[tauri::command]
+pub async fn check_permissions() -> Result<Vec<PermissionStatus>, String> {
+    log::debug!("Command: check_permissions");
+    Ok(crate::infrastructure::permissions::check_permissions())
+}
+
+/// Открывает раздел System Settings для разрешения
+#[tauri::command]
+pub async fn open_permission_settings(capability: PermissionCapability) -> Result<(), String> {
+    log::info!("Command: open_permission_settings({:?})", capability);
+    crate::infrastructure::permissions::open_permission_settings(capability).map_err(|e| e.to_string())
+}
+
+/// Дойдёт ли вставка до окна `target` (имя exe, например `regedit.exe`; по умолчанию — активное окно).
+/// На Windows окна администратора принимают ввод только от администратора или сборки с uiAccess;
+/// в этом случае `guidance` объясняет, как это включить
+#[tauri::command]
+pub async fn get_paste_compatibility(
+    state: State<'_, ConfigState>,
+    target: Option<String>,
+) -> Result<PasteCompatibility, String> {
+    log::debug!("Command: get_paste_compatibility - target: {:?}", target);
+    let mut compatibility = crate::infrastructure::window_elevation::paste_compatibility(target.as_deref());
+    if !compatibility.can_inject_input {
+        compatibility.guidance = Some(state.localize(UiMessage::ElevatedPasteGuidance).await);
+    }
+    Ok(compatibility)
+}
+
+fn text_output_context(state: &AppState) -> TextOutputContext {
+    TextOutputContext {
+        last_focused_app_bundle_id: state.integrations.last_focused_app_bundle_id.clone(),
+        config: state.settings.config.clone(),
+    }
+}
+
+/// Вставка и печать не доходят до окна администратора (Windows UIPI): вместо них текст уходит
+/// в clipboard, а frontend показывает уведомление, почему текст не вставился
+async fn sinks_for_paste_target(
+    state: &AppState,
+    app_handle: &AppHandle,
+    sinks: Vec<TextOutputSinkConfig>,
+    session_id: Option<u64>,
+) -> Vec<TextOutputSinkConfig> {
+    let injects_input = sinks
+        .iter()
+        .any(|sink| matches!(sink, TextOutputSinkConfig::AutoPaste { .. } | TextOutputSinkConfig::Typing));
+    if !injects_input {
+        return sinks;
+    }
+
+    let compatibility = crate::infrastructure::window_elevation::paste_compatibility(None);
+    if compatibility.can_inject_input {
+        return sinks;
+    }
+
+    let payload = PasteClipboardFallbackPayload {
+        message: state
+            .localize(UiMessage::PasteBlockedByElevatedWindow {
+                target: compatibility.target.clone(),
+            })
+            .await,
+        target: compatibility.target.clone(),
+        session_id,
+    };
+    if let Err(e) = app_handle.emit(EVENT_PASTE_CLIPBOARD_FALLBACK, payload) {
+        log::warn!("Failed to emit {} event: {}", EVENT_PASTE_CLIPBOARD_FALLBACK, e);
+    }
+    compatibility.restrict_sinks(&sinks)
+}
+
+/// Доставляет текст в один sink (без фоллбеков) — для явных действий пользователя
+async fn deliver_to_sink(
+    state: &AppState,
+    app_handle: &AppHandle,
+    sink: TextOutputSinkConfig,
+    delivery: TextDelivery,
+) -> Result<(), String> {
+    let sinks = sinks_for_paste_target(state, app_handle, vec![sink], delivery.session_id).await;
+    let sinks = create_text_output_sinks(&sinks, &text_output_context(state));
+    let router = SinkTextOutputRouter::new(sinks);
+    let outcomes = router.route(&delivery).await;
+    record_paste_audit(state, &delivery, &outcomes).await;
+    match outcomes.into_iter().find_map(|o| o.error) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Записывает действия auto-paste/typing в журнал автовставок и сохраняет его на диск
+async fn record_paste_audit(state: &AppState, delivery: &TextDelivery, outcomes: &[SinkDeliveryOutcome]) {
+    let has_auto_paste = outcomes
+        .iter()
+        .any(|o| matches!(o.sink, TextOutputSinkConfig::AutoPaste { .. }));
+    let target_app = delivery_target_app(state, has_auto_paste).await;
+
+    let entries = paste_audit_entries(delivery, outcomes, target_app.as_deref(), chrono::Utc::now().timestamp_millis());
+    if entries.is_empty() {
+        return;
+    }
+
+    let snapshot = {
+        let mut audit = state.user_data.paste_audit.write().await;
+        append_paste_audit(&mut audit, entries);
+        audit.clone()
+    };
+    if let Err(e) = ConfigStore::save_paste_audit(&snapshot).await {
+        log::warn!("Failed to save paste audit log: {}", e);
+    }
+}
+
+/// Приложение, куда попадёт текст: auto-paste возвращает фокус в сохранённое приложение,
+/// typing печатает в текущее активное
+async fn delivery_target_app(state: &AppState, has_auto_paste: bool) -> Option<String> {
+    let saved_app = if has_auto_paste {
+        state.integrations.last_focused_app_bundle_id.read().await.clone()
+    } else {
+        None
+    };
+    saved_app.or_else(crate::infrastructure::auto_paste::get_active_app_bundle_id)
+}
+
+/// Instant paste: вставляет стабилизированные сегменты сессии по одному, в порядке прихода.
+///
+/// Фразы, которые low confidence фильтр не пропустил бы (suppress/hold), не вставляются.
+/// Задача завершается вместе с колбэками сессии, которые держат отправитель.
+fn spawn_instant_paste_worker(
+    app_handle: AppHandle,
+    session_id: u64,
+) -> tokio::sync::mpsc::UnboundedSender<crate::domain::Transcription> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<crate::domain::Transcription>();
+    tokio::spawn(async move {
+        let mut tracker = InstantPasteTracker::new();
+        while let Some(transcription) = rx.recv().await {
+            let Some(state) = app_handle.try_state::<AppState>() else {
+                return;
+            };
+            let verdict = state.settings.config.read().await.low_confidence_verdict(&transcription);
+            if matches!(verdict, Some(LowConfidenceAction::Suppress | LowConfidenceAction::Hold)) {
+                continue;
+            }
+            let Some(text) = tracker.accept(&transcription) else {
+                continue;
+            };
+
+            let sink = TextOutputSinkConfig::AutoPaste {
+                fallback_to_clipboard: false,
+            };
+            match deliver_to_sink(&state, &app_handle, sink, TextDelivery::new(text).with_session_id(session_id)).await {
+                Ok(()) => after_text_inserted(&state, &app_handle),
+                Err(e) => log::warn!("Instant paste failed: {}", e),
+            }
+        }
+    });
+    tx
+}
+
+/// После вставки: окно скрывается, если его ждёт window_behavior_on_stop = hide_after_paste,
+/// иначе остаётся поверх всех окон
+fn after_text_inserted(state: &AppState, app_handle: &AppHandle) {
+    if state.hide_window_after_paste.swap(false, Ordering::SeqCst) {
+        log::debug!("Text pasted after recording stop - hiding window");
+        hide_main_window(app_handle);
+    } else {
+        keep_main_window_on_top(app_handle);
+    }
+}
+
+/// Не скрываем окно VoicetextAI после вставки — возвращаем его поверх всех окон (но без фокуса)
+fn keep_main_window_on_top(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.set_always_on_top(true);
+        log::debug!("VoicetextAI window kept on top");
+    }
+}
+
 /// Автоматически вставляет текст в последнее активное окно
 /// Требует разрешения Accessibility на macOS
 #[tauri::command]
@@ -1767,75 +5131,274 @@ pub async fn auto_paste_text(
     text: String,
 ) -> Result<(), String> {
     log::info!("Command: auto_paste_text - text length: {}", text.len());
+    paste_text_internal(&state, &app_handle, text).await?;
+    log::info!("Text auto-pasted successfully");
+    Ok(())
+}
 
-    // Проверяем разрешение Accessibility на macOS
-    #[cfg(target_os = "macos")]
-    {
-        if !crate::infrastructure::auto_paste::check_accessibility_permission() {
-            return Err("Accessibility permission not granted. Please enable it in System Settings > Privacy & Security > Accessibility".to_string());
-        }
+/// Вставка текста в последнее активное окно (auto_paste_text, голосовая команда "paste that")
+pub(crate) async fn paste_text_internal(state: &AppState, app_handle: &AppHandle, text: String) -> Result<(), String> {
+    let sink = TextOutputSinkConfig::AutoPaste {
+        fallback_to_clipboard: false,
+    };
+    deliver_to_sink(state, app_handle, sink, TextDelivery::new(text))
+        .await
+        .map_err(|e| format!("Failed to paste text: {}", e))?;
+
+    after_text_inserted(state, app_handle);
+    Ok(())
+}
+
+/// Доставляет финальный текст во все sinks активного профиля (clipboard, вставка, файл, webhook).
+///
+/// `text` — новая фраза, `full_text` — весь накопленный текст сессии (для clipboard).
+/// Перед доставкой применяется регистр текста (правило приложения → профиль → общая настройка).
+/// Возвращает результат по каждому sink; ошибка одного sink не прерывает остальные.
+#[tauri::command]
+pub async fn deliver_text_output(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    text: String,
+    full_text: Option<String>,
+    session_id: Option<u64>,
+) -> Result<Vec<SinkDeliveryOutcome>, String> {
+    log::info!("Command: deliver_text_output - text length: {}", text.len());
+
+    let profile_override = state.integrations.output_profile_override.read().await.clone();
+    let sinks_config = {
+        let config = state.settings.config.read().await;
+        profile_override
+            .as_deref()
+            .and_then(|name| config.profile_sinks(name))
+            .unwrap_or_else(|| config.active_output_sinks())
+    };
+    let sinks_config = sinks_for_paste_target(&state, &app_handle, sinks_config, session_id).await;
+    let router = SinkTextOutputRouter::new(create_text_output_sinks(&sinks_config, &text_output_context(&state)))
+        .with_clipboard_fallback(Arc::new(ClipboardSink::new()));
+    if router.is_empty() {
+        return Ok(Vec::new());
     }
 
-    // Получаем bundle ID последнего активного окна
-    let last_bundle_id = state.last_focused_app_bundle_id.read().await.clone();
+    let has_auto_paste = sinks_config
+        .iter()
+        .any(|sink| matches!(sink, TextOutputSinkConfig::AutoPaste { .. }));
+    let target_app = delivery_target_app(&state, has_auto_paste).await;
+    let casing = state.settings
+        .config
+        .read()
+        .await
+        .text_casing_for(profile_override.as_deref(), target_app.as_deref());
 
-    // Не скрываем окно VoicetextAI - оставляем его видимым поверх всех
-    // (оно уже настроено с alwaysOnTop: true в tauri.conf.json)
+    let mut delivery = TextDelivery::new(casing.apply(&text));
+    if let Some(full_text) = full_text {
+        delivery = delivery.with_full_text(casing.apply(&full_text));
+    }
+    if let Some(session_id) = session_id {
+        delivery = delivery.with_session_id(session_id);
+    }
 
-    // Если есть сохраненное окно - пытаемся активировать его
-    if let Some(bundle_id) = last_bundle_id {
-        log::info!("Attempting to activate last focused app: {}", bundle_id);
+    let outcomes = router.route(&delivery).await;
+    record_paste_audit(&state, &delivery, &outcomes).await;
 
-        match crate::infrastructure::auto_paste::activate_app_by_bundle_id(&bundle_id) {
-            Ok(_) => {
-                log::info!("✅ Successfully activated app: {}", bundle_id);
-                // Даем время окну активироваться
-                tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
-            }
-            Err(e) => {
-                log::warn!("⚠️ Failed to activate app '{}': {}", bundle_id, e);
-                log::info!("💡 Will paste to currently active window instead");
-                // Не критично - просто вставим в текущее активное окно
-                // Даем небольшую паузу для переключения фокуса вручную если нужно
-                tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-            }
-        }
-    } else {
-        log::info!("ℹ️ No saved window - pasting to currently active window");
+    let inserted_text = outcomes.iter().any(|o| {
+        matches!(o.sink, TextOutputSinkConfig::AutoPaste { .. } | TextOutputSinkConfig::Typing)
+    });
+    if inserted_text {
+        after_text_inserted(&state, &app_handle);
     }
 
-    // Вставляем текст в blocking thread (enigo работает с синхронными нативными API)
-    let text_clone = text.clone();
-    tokio::task::spawn_blocking(move || {
-        crate::infrastructure::auto_paste::paste_text(&text_clone)
+    Ok(outcomes)
+}
+
+/// Журнал автовставок: последние `limit` записей (по умолчанию все), новые первыми
+#[tauri::command]
+pub async fn get_paste_audit_log(
+    state: State<'_, HistoryState>,
+    limit: Option<usize>,
+) -> Result<Vec<PasteAuditEntry>, String> {
+    log::debug!("Command: get_paste_audit_log - limit: {:?}", limit);
+    let audit = state.paste_audit.read().await;
+    Ok(audit
+        .iter()
+        .rev()
+        .take(limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect())
+}
+
+/// Очищает журнал автовставок (в памяти и на диске)
+#[tauri::command]
+pub async fn clear_paste_audit_log(state: State<'_, HistoryState>) -> Result<(), String> {
+    log::info!("Command: clear_paste_audit_log");
+    state.paste_audit.write().await.clear();
+    ConfigStore::delete_paste_audit().await.map_err(|e| e.to_string())
+}
+
+/// Профили доставки текста + активный профиль
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TextOutputProfilesData {
+    pub profiles: Vec<TextOutputProfile>,
+    pub active_profile: Option<String>,
+    /// Sinks, которые реально будут использованы (с учётом фоллбека на старые флаги)
+    pub active_sinks: Vec<TextOutputSinkConfig>,
+}
+
+/// Get text output profiles
+#[tauri::command]
+pub async fn get_text_output_profiles(state: State<'_, ConfigState>) -> Result<TextOutputProfilesData, String> {
+    let config = state.config.read().await;
+    Ok(TextOutputProfilesData {
+        profiles: config.output_profiles.clone(),
+        active_profile: config.active_output_profile.clone(),
+        active_sinks: config.active_output_sinks(),
     })
-    .await
-    .map_err(|e| format!("Failed to join blocking task: {}", e))?
-    .map_err(|e| format!("Failed to paste text: {}", e))?;
+}
 
-    // Возвращаем окно VoicetextAI поверх всех окон (но без фокуса)
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.set_always_on_top(true);
-        log::debug!("VoicetextAI window kept on top");
+/// Update text output profiles and the active profile
+#[tauri::command]
+pub async fn update_text_output_profiles(
+    state: State<'_, ConfigState>,
+    app_handle: AppHandle,
+    window: Window,
+    profiles: Vec<TextOutputProfile>,
+    active_profile: Option<String>,
+) -> Result<(), String> {
+    log::info!(
+        "Command: update_text_output_profiles - {} profiles, active: {:?}",
+        profiles.len(),
+        active_profile
+    );
+
+    if let Some(name) = active_profile.as_deref() {
+        if !profiles.iter().any(|p| p.name == name) {
+            return Err(format!("Unknown output profile: {}", name));
+        }
     }
 
-    log::info!("Text auto-pasted successfully");
+    {
+        let mut config = state.config.write().await;
+        config.output_profiles = profiles;
+        config.active_output_profile = active_profile;
+        ConfigStore::save_app_config(&config)
+            .await
+            .map_err(|e| format!("Failed to save app config: {}", e))?;
+    }
+
+    let revision = AppState::bump_revision(&state.app_config_revision).await;
+    let _ = app_handle.emit(
+        EVENT_STATE_SYNC_INVALIDATION,
+        crate::presentation::StateSyncInvalidationPayload {
+            topic: "app-config".to_string(),
+            revision,
+            source_id: Some(window.label().to_string()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+
     Ok(())
 }
 
+/// Get recording profiles (provider/language per hotkey)
+#[tauri::command]
+pub async fn get_recording_profiles(state: State<'_, ConfigState>) -> Result<Vec<RecordingProfile>, String> {
+    Ok(state.config.read().await.recording_profiles.clone())
+}
+
+/// Update recording profiles and re-register their hotkeys
+#[tauri::command]
+pub async fn update_recording_profiles(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    window: Window,
+    profiles: Vec<RecordingProfile>,
+) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    log::info!("Command: update_recording_profiles - {} profiles", profiles.len());
+
+    let (main_hotkey, output_profiles) = {
+        let config = state.settings.config.read().await;
+        (config.recording_hotkey.clone(), config.output_profiles.clone())
+    };
+    // Хоткей профиля не может совпадать с основным хоткеем записи и с хоткеями других профилей
+    let mut shortcuts: Vec<Shortcut> = main_hotkey.parse::<Shortcut>().into_iter().collect();
+    let mut names: Vec<String> = Vec::new();
+    let mut profiles = profiles;
+    for profile in profiles.iter_mut() {
+        profile.name = profile.name.trim().to_string();
+        if names.contains(&profile.name) {
+            return Err(state
+                .localize(UiMessage::RecordingProfileConflict {
+                    profile: profile.name.clone(),
+                })
+                .await);
+        }
+        names.push(profile.name.clone());
+
+        if let Some(output_profile) = profile.output_profile.as_deref() {
+            if !output_profiles.iter().any(|p| p.name == output_profile) {
+                return Err(state
+                    .localize(UiMessage::DeliveryProfileNotFound {
+                        profile: output_profile.to_string(),
+                    })
+                    .await);
+            }
+        }
+
+        let hotkey = profile.hotkey.as_deref().map(str::trim).unwrap_or_default().to_string();
+        if hotkey.is_empty() {
+            profile.hotkey = None;
+            continue;
+        }
+        let Some((normalized, shortcut)) = crate::infrastructure::hotkey::normalize_recording_hotkey(&hotkey)
+            .and_then(|normalized| normalized.parse::<Shortcut>().ok().map(|sc| (normalized, sc)))
+        else {
+            return Err(state.localize(UiMessage::InvalidHotkey { hotkey }).await);
+        };
+        if shortcuts.contains(&shortcut) {
+            return Err(state
+                .localize(UiMessage::RecordingProfileConflict {
+                    profile: profile.name.clone(),
+                })
+                .await);
+        }
+        shortcuts.push(shortcut);
+        profile.hotkey = Some(normalized);
+    }
+
+    {
+        let mut config = state.settings.config.write().await;
+        config.recording_profiles = profiles;
+        ConfigStore::save_app_config(&config)
+            .await
+            .map_err(|e| format!("Failed to save app config: {}", e))?;
+    }
+
+    let revision = AppState::bump_revision(&state.settings.app_config_revision).await;
+    let _ = app_handle.emit(
+        EVENT_STATE_SYNC_INVALIDATION,
+        crate::presentation::StateSyncInvalidationPayload {
+            topic: "app-config".to_string(),
+            revision,
+            source_id: Some(window.label().to_string()),
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+
+    // Перерегистрируем основной хоткей вместе с хоткеями профилей
+    register_recording_hotkey(state, app_handle).await
+}
+
 /// Копирует текст в системный clipboard используя arboard (кроссплатформенно)
 /// Работает БЕЗ активации приложения - решает проблему с nonactivating_panel на macOS
 #[tauri::command]
 pub async fn copy_to_clipboard_native(text: String) -> Result<(), String> {
     log::debug!("Command: copy_to_clipboard_native - text length: {}", text.len());
 
-    // Используем blocking task (arboard работает с синхронными системными API, как enigo)
-    tokio::task::spawn_blocking(move || {
-        crate::infrastructure::copy_to_clipboard(&text)
-    })
-    .await
-    .map_err(|e| format!("Failed to join blocking task: {}", e))?
-    .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    ClipboardSink::new()
+        .deliver(&TextDelivery::new(text))
+        .await
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
 
     log::info!("Text copied to clipboard successfully");
     Ok(())
@@ -1883,9 +5446,16 @@ pub async fn show_auth_window(app_handle: AppHandle) -> Result<(), String> {
 
 /// Показывает recording окно (main) и скрывает auth
 #[tauri::command]
-pub async fn show_recording_window(app_handle: AppHandle) -> Result<(), String> {
+pub async fn show_recording_window(state: State<'_, AppState>, app_handle: AppHandle) -> Result<(), String> {
     log::info!("Command: show_recording_window");
 
+    // Автозапуск при входе в систему: первое авто-открытие окна после старта пропускаем
+    if state.launched_minimized.swap(false, Ordering::Relaxed) {
+        log::info!("Launched minimized - keeping recording window hidden");
+        spawn_prewarm(app_handle);
+        return Ok(());
+    }
+
     // Скрываем auth окно
     if let Some(auth) = app_handle.get_webview_window("auth") {
         if let Err(e) = auth.hide() {
@@ -1916,6 +5486,8 @@ pub async fn show_recording_window(app_handle: AppHandle) -> Result<(), String>
         }
     }
 
+    spawn_prewarm(app_handle);
+
     Ok(())
 }
 
@@ -1950,8 +5522,8 @@ pub async fn show_settings_window(
     // Важно: не делаем это фатальным — если чтение упало, показываем окно с текущим in-memory состоянием.
     {
         if let Ok(saved_app) = ConfigStore::load_app_config().await {
-            *state.config.write().await = saved_app.clone();
-            state.transcription_service
+            *state.settings.config.write().await = saved_app.clone();
+            state.recording.transcription_service
                 .set_microphone_sensitivity(saved_app.microphone_sensitivity)
                 .await;
         }
@@ -1966,12 +5538,12 @@ pub async fn show_settings_window(
                 .as_ref()
                 .map(|s| s.access_token.clone());
             saved_stt.backend_auth_token = token;
-            let _ = state.transcription_service.update_config(saved_stt.clone()).await;
-            state.config.write().await.stt = saved_stt;
+            let _ = state.recording.transcription_service.update_config(saved_stt.clone()).await;
+            state.settings.config.write().await.stt = saved_stt;
         }
 
         if let Ok(prefs) = ConfigStore::load_ui_preferences().await {
-            *state.ui_preferences.write().await = prefs;
+            *state.settings.ui_preferences.write().await = prefs;
         }
     }
 
@@ -2159,7 +5731,7 @@ pub async fn set_auth_session(
                 "Failed to load STT config for token update: {}. Using current in-memory config.",
                 e
             );
-            (state.transcription_service.get_config().await, false)
+            (state.recording.transcription_service.get_config().await, false)
         }
     };
     stt.backend_auth_token = stt_token;
@@ -2170,7 +5742,7 @@ pub async fn set_auth_session(
             log::warn!("Failed to persist STT config token: {}", e);
         }
     }
-    if let Err(e) = state.transcription_service.update_config(stt).await {
+    if let Err(e) = state.recording.transcription_service.update_config(stt).await {
         log::warn!("Failed to update transcription service config token: {}", e);
     }
 
@@ -2226,7 +5798,7 @@ pub async fn set_authenticated(
                             "Failed to load STT config for token refresh: {}. Using current in-memory config.",
                             e
                         );
-                        (state.transcription_service.get_config().await, false)
+                        (state.recording.transcription_service.get_config().await, false)
                     }
                 };
                 if config.backend_auth_token.as_deref() != Some(t.as_str()) {
@@ -2235,7 +5807,7 @@ pub async fn set_authenticated(
                     if loaded_from_disk {
                         let _ = ConfigStore::save_config(&config).await;
                     }
-                    let _ = state.transcription_service.update_config(config).await;
+                    let _ = state.recording.transcription_service.update_config(config).await;
                 }
             }
         }
@@ -2249,7 +5821,7 @@ pub async fn set_authenticated(
         Ok(c) => c,
         Err(e) => {
             log::warn!("Failed to load STT config for auth change: {}. Using current in-memory config.", e);
-            state.transcription_service.get_config().await
+            state.recording.transcription_service.get_config().await
         }
     };
     if authenticated {
@@ -2272,7 +5844,7 @@ pub async fn set_authenticated(
     if let Err(e) = ConfigStore::save_config(&config).await {
         log::warn!("Failed to save STT config during auth change: {}", e);
     }
-    let _ = state.transcription_service.update_config(config).await;
+    let _ = state.recording.transcription_service.update_config(config).await;
 
     // Синхронизация между окнами через state-sync
     let revision = AppState::bump_revision(&state.auth_state_revision).await;
@@ -0,0 +1,89 @@
+//! Монитор связи: без сети облачные провайдеры недоступны, запись идёт через локальный Whisper.
+//!
+//! Сохранённый провайдер не меняется — подмена действует, пока сети нет (см.
+//! `TranscriptionService::set_offline_fallback`). Смена состояния уходит во frontend событиями
+//! `network:offline` / `network:online`.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::domain::{SttProviderType, SttSessionOverride, OFFLINE_WHISPER_MODELS};
+use crate::infrastructure::connectivity::is_network_available;
+use crate::infrastructure::models::is_model_downloaded;
+use crate::presentation::{AppState, NetworkStatusPayload, EVENT_NETWORK_OFFLINE, EVENT_NETWORK_ONLINE};
+
+const ONLINE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Без сети проверяем чаще, чтобы быстро вернуть облачного провайдера
+const OFFLINE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Чем записывать без сети: локальный Whisper со скачанной моделью
+fn offline_fallback() -> Option<SttSessionOverride> {
+    if !cfg!(feature = "whisper") {
+        return None;
+    }
+    let model = OFFLINE_WHISPER_MODELS
+        .iter()
+        .find(|model| is_model_downloaded(model))?;
+    Some(SttSessionOverride {
+        provider: Some(SttProviderType::WhisperLocal),
+        model: Some(model.to_string()),
+        language: None,
+    })
+}
+
+/// Текущее состояние сети (для `get_network_status` и событий)
+pub fn network_status(state: &AppState) -> NetworkStatusPayload {
+    let online = state.network_online.load(Ordering::Relaxed);
+    let fallback = if online { None } else { offline_fallback() };
+    NetworkStatusPayload {
+        online,
+        cloud_providers_available: online,
+        offline_provider: fallback.as_ref().and_then(|f| f.provider),
+        offline_model: fallback.and_then(|f| f.model),
+    }
+}
+
+async fn check_connectivity(app_handle: &AppHandle, state: &AppState) -> bool {
+    let online = is_network_available(&AppState::get_api_base_url()).await;
+    let was_online = state.network_online.swap(online, Ordering::Relaxed);
+
+    // Модель могли скачать или удалить, пока сети нет — обновляем подмену на каждой проверке
+    let fallback = if online { None } else { offline_fallback() };
+    state.recording.transcription_service.set_offline_fallback(fallback);
+
+    if online != was_online {
+        let payload = network_status(state);
+        if online {
+            log::info!("Network is back online");
+        } else {
+            log::warn!(
+                "Network is offline; local provider: {:?} ({:?})",
+                payload.offline_provider,
+                payload.offline_model
+            );
+        }
+        let event = if online { EVENT_NETWORK_ONLINE } else { EVENT_NETWORK_OFFLINE };
+        if let Err(e) = app_handle.emit(event, payload) {
+            log::warn!("Failed to emit {} event: {}", event, e);
+        }
+    }
+    online
+}
+
+/// Фоновая проверка связи
+pub fn start_connectivity_monitor(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let online = match app_handle.try_state::<AppState>() {
+                Some(state) => check_connectivity(&app_handle, &state).await,
+                None => true,
+            };
+
+            let interval = if online { ONLINE_CHECK_INTERVAL } else { OFFLINE_CHECK_INTERVAL };
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
@@ -0,0 +1,287 @@
+//! Обработчик deep links `voicetotext://`.
+//!
+//! - `voicetotext://record?lang=en&profile=work` — старт записи с языком/профилем доставки только на эту сессию
+//! - `voicetotext://activate?token=...` — активация лицензии по ссылке с сайта
+//! - остальное (OAuth callback) уходит во frontend событием `deep-link`, как и раньше
+
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+use crate::domain::{RecordingStatus, SttSessionOverride};
+use crate::presentation::commands;
+use crate::presentation::events::{
+    DeepLinkErrorPayload, EVENT_DEEP_LINK, EVENT_DEEP_LINK_ERROR, EVENT_LICENSE_ACTIVATED,
+};
+use crate::presentation::i18n::{UiLanguage, UiMessage};
+use crate::presentation::AppState;
+
+pub const DEEP_LINK_SCHEME: &str = "voicetotext";
+
+const LICENSE_ACTIVATION_TIMEOUT_SECS: u64 = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkAction {
+    Record {
+        language: Option<String>,
+        profile: Option<String>,
+    },
+    Activate {
+        token: String,
+    },
+    /// Ссылка не для Rust-обработчика — отдаём во frontend
+    Forward,
+}
+
+impl DeepLinkAction {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Record { .. } => "record",
+            Self::Activate { .. } => "activate",
+            Self::Forward => "forward",
+        }
+    }
+}
+
+/// Код языка вида "en", "pt-BR", "zh_Hans"
+fn is_valid_language(language: &str) -> bool {
+    (2..=16).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Разбирает ссылку. Ошибка — ссылка наша, но параметры некорректны.
+pub fn parse_deep_link(url: &Url) -> Result<DeepLinkAction, UiMessage> {
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Ok(DeepLinkAction::Forward);
+    }
+
+    // voicetotext://record → host "record"; voicetotext:record → path "record"
+    let action = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| url.path().trim_matches('/'));
+    let param = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    match action {
+        "record" => {
+            let language = param("lang");
+            if let Some(language) = language.as_deref() {
+                if !is_valid_language(language) {
+                    return Err(UiMessage::DeepLinkInvalidLanguage {
+                        language: language.to_string(),
+                    });
+                }
+            }
+            Ok(DeepLinkAction::Record {
+                language,
+                profile: param("profile"),
+            })
+        }
+        "activate" => match param("token") {
+            Some(token) => Ok(DeepLinkAction::Activate { token }),
+            None => Err(UiMessage::DeepLinkMissingToken),
+        },
+        _ => Ok(DeepLinkAction::Forward),
+    }
+}
+
+/// Обрабатывает одну входящую ссылку (из on_open_url или при запуске приложения по ссылке)
+pub fn handle_deep_link(app_handle: &AppHandle, url: Url) {
+    let action = match parse_deep_link(&url) {
+        Ok(action) => action,
+        Err(error) => {
+            log::warn!("Invalid deep link: {:?}", error);
+            let app_handle = app_handle.clone();
+            let action = url.host_str().unwrap_or_default().to_string();
+            tauri::async_runtime::spawn(async move {
+                let error = match app_handle.try_state::<AppState>() {
+                    Some(state) => state.localize(error).await,
+                    None => error.text(UiLanguage::default()),
+                };
+                emit_error(&app_handle, &action, error);
+            });
+            return;
+        }
+    };
+
+    if action == DeepLinkAction::Forward {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.emit(EVENT_DEEP_LINK, url.to_string());
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let name = action.name();
+        let result = match action {
+            DeepLinkAction::Record { language, profile } => start_recording(&app_handle, language, profile).await,
+            DeepLinkAction::Activate { token } => activate_license(&app_handle, token).await,
+            DeepLinkAction::Forward => Ok(()),
+        };
+        if let Err(error) = result {
+            log::error!("Deep link '{}' failed: {}", name, error);
+            emit_error(&app_handle, name, error);
+        }
+    });
+}
+
+fn emit_error(app_handle: &AppHandle, action: &str, error: String) {
+    let payload = DeepLinkErrorPayload {
+        action: action.to_string(),
+        error,
+    };
+    if let Err(e) = app_handle.emit(EVENT_DEEP_LINK_ERROR, payload) {
+        log::error!("Failed to emit deep link error event: {}", e);
+    }
+}
+
+/// voicetotext://record: запускает запись как по хоткею, но с override языка/профиля на эту сессию
+async fn start_recording(
+    app_handle: &AppHandle,
+    language: Option<String>,
+    profile: Option<String>,
+) -> Result<(), String> {
+    log::info!("Deep link: record (lang: {:?}, profile: {:?})", language, profile);
+
+    let state = app_handle
+        .try_state::<AppState>()
+        .ok_or_else(|| "AppState не доступен".to_string())?;
+
+    if let Some(profile) = profile.as_deref() {
+        if state.settings.config.read().await.profile_sinks(profile).is_none() {
+            return Err(state
+                .localize(UiMessage::DeliveryProfileNotFound {
+                    profile: profile.to_string(),
+                })
+                .await);
+        }
+    }
+
+    let status = state.recording.transcription_service.get_status().await;
+    if status != RecordingStatus::Idle {
+        log::info!("Deep link record ignored - recording is not idle (status: {:?})", status);
+        return Ok(());
+    }
+
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    commands::start_recording_with_overrides_internal(
+        &state,
+        window,
+        app_handle.clone(),
+        language.map(SttSessionOverride::language),
+        profile,
+    )
+    .await
+}
+
+#[derive(serde::Serialize)]
+struct ClaimLicenseRequest<'a> {
+    license_key: &'a str,
+}
+
+/// voicetotext://activate: активирует лицензию текущего пользователя токеном с сайта
+async fn activate_license(app_handle: &AppHandle, token: String) -> Result<(), String> {
+    log::info!("Deep link: activate license");
+
+    let state = app_handle
+        .try_state::<AppState>()
+        .ok_or_else(|| "AppState не доступен".to_string())?;
+
+    let access_token = state
+        .auth_store
+        .read()
+        .await
+        .session
+        .as_ref()
+        .map(|s| s.access_token.clone());
+    let Some(access_token) = access_token else {
+        commands::show_auth_window(app_handle.clone()).await?;
+        return Err(state.localize(UiMessage::LicenseSignInRequired).await);
+    };
+
+    let lang = state.ui_language().await;
+    let url = format!("{}/api/v1/account/licenses/claim", AppState::get_api_base_url());
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(std::time::Duration::from_secs(LICENSE_ACTIVATION_TIMEOUT_SECS))
+        .bearer_auth(access_token)
+        .header("X-Client-Type", "native")
+        .json(&ClaimLicenseRequest { license_key: &token })
+        .send()
+        .await
+        .map_err(|e| UiMessage::ServerUnreachable { error: e.to_string() }.text(lang))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        // envelope: { error: { code, message } }
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v["error"]["message"].as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| format!("HTTP {}", status));
+        return Err(UiMessage::LicenseActivationFailed { message }.text(lang));
+    }
+
+    log::info!("License activated via deep link");
+    if let Err(e) = app_handle.emit(EVENT_LICENSE_ACTIVATED, ()) {
+        log::error!("Failed to emit license activated event: {}", e);
+    }
+
+    // Показываем профиль с обновлённой лицензией
+    commands::show_profile_window(state, app_handle.clone(), Some("license".to_string())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Result<DeepLinkAction, UiMessage> {
+        parse_deep_link(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_record_with_overrides() {
+        assert_eq!(
+            parse("voicetotext://record?lang=en&profile=work").unwrap(),
+            DeepLinkAction::Record {
+                language: Some("en".to_string()),
+                profile: Some("work".to_string()),
+            }
+        );
+        assert_eq!(
+            parse("voicetotext:record?profile=%D0%A7%D0%B0%D1%82").unwrap(),
+            DeepLinkAction::Record {
+                language: None,
+                profile: Some("Чат".to_string()),
+            }
+        );
+        assert!(parse("voicetotext://record?lang=en;rm").is_err());
+    }
+
+    #[test]
+    fn parses_activate_and_forwards_the_rest() {
+        assert_eq!(
+            parse("voicetotext://activate?token=abc123").unwrap(),
+            DeepLinkAction::Activate {
+                token: "abc123".to_string()
+            }
+        );
+        assert_eq!(parse("voicetotext://activate"), Err(UiMessage::DeepLinkMissingToken));
+
+        // OAuth callback обрабатывает frontend
+        assert_eq!(
+            parse("voicetotext://oauth/callback?exchange_code=x").unwrap(),
+            DeepLinkAction::Forward
+        );
+        assert_eq!(parse("https://voicetext.site/record").unwrap(), DeepLinkAction::Forward);
+    }
+}
@@ -1,27 +1,101 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::domain::{RecordingStatus, Transcription};
+use crate::domain::{AlternativeText, AppContext, FocusMode, RecordingStatus, SessionCalendarTag, SttProviderType, Transcription, WindowBehaviorOnStop};
 use crate::domain::{SttConnectionCategory, SttConnectionDetails};
+use crate::infrastructure::audio::{VadActivity, VadActivityKind};
+
+/// Версия контракта событий (см. api_schema): растёт при несовместимых изменениях payload'ов
+pub const EVENT_CONTRACT_VERSION: u32 = 1;
 
 /// Event names for Tauri event system
 pub const EVENT_TRANSCRIPTION_PARTIAL: &str = "transcription:partial";
 pub const EVENT_TRANSCRIPTION_FINAL: &str = "transcription:final";
+// Финальная фраза ниже min_confidence отложена до решения пользователя
+pub const EVENT_TRANSCRIPTION_HELD: &str = "transcription:held";
+// A/B сравнение провайдеров (stt comparison_provider): текст каждого провайдера; payload — ComparisonTranscriptPayload
+pub const EVENT_TRANSCRIPTION_COMPARISON: &str = "transcription:comparison";
+// Слитый текст обоих провайдеров A/B сравнения (сборка с feature "ensemble"); payload — EnsembleTranscriptPayload
+pub const EVENT_TRANSCRIPTION_ENSEMBLE: &str = "transcription:ensemble";
 pub const EVENT_RECORDING_STATUS: &str = "recording:status";
+// Раз в секунду во время записи: длительность, оценка стоимости, остаток квоты; payload — RecordingTickPayload
+pub const EVENT_RECORDING_TICK: &str = "recording:tick";
+// Запись идёт без окна и звуков из-за режима фокусировки ОС; payload — RecordingQuietPayload
+pub const EVENT_RECORDING_QUIET: &str = "recording:quiet";
 pub const EVENT_AUDIO_LEVEL: &str = "audio:level";
 pub const EVENT_AUDIO_SPECTRUM: &str = "audio:spectrum";
 pub const EVENT_MICROPHONE_TEST_LEVEL: &str = "microphone_test:level";
+// Решения VAD во время записи (полосы речи в UI, отладка авто-стопа); payload — VadActivityPayload
+pub const EVENT_VAD_ACTIVITY: &str = "vad:activity";
+// Спектр теста микрофона (после усиления, как услышит STT); payload — AudioSpectrumPayload
+pub const EVENT_MICROPHONE_TEST_SPECTRUM: &str = "microphone_test:spectrum";
 
 pub const EVENT_TRANSCRIPTION_ERROR: &str = "transcription:error";
+// Rate limit провайдера: раз в секунду сколько осталось до повтора (0 — можно повторять); payload — RetryCountdownPayload
+pub const EVENT_TRANSCRIPTION_RETRY_COUNTDOWN: &str = "transcription:retry-countdown";
 pub const EVENT_CONNECTION_QUALITY: &str = "connection:quality";
+// Соединение открыто между записями (keep-alive пауза / prewarm): сколько держится, когда закроется
+pub const EVENT_CONNECTION_IDLE: &str = "connection:idle";
+
+// Диагностика: замеры задержек STT (audio sent → partial/final)
+pub const EVENT_TRANSCRIPTION_LATENCY: &str = "transcription:latency";
+
+// Итог сессии записи (WPM, слова, длительность); payload — domain::SessionStats
+pub const EVENT_SESSION_STATS: &str = "session:stats";
+
+// Текст записи истории изменён в редакторе (update_history_item_text); payload — HistoryItemUpdatedPayload
+pub const EVENT_HISTORY_ITEM_UPDATED: &str = "history:item-updated";
+
+// Пул памяти (буфер аудио, история, очередь) вырос выше порога; payload — domain::MemoryPoolUsage
+pub const EVENT_MEMORY_ALARM: &str = "diagnostics:memory-alarm";
+
+// Встреча завершена: структурированный транскрипт и путь сохранённого JSON
+pub const EVENT_MEETING_FINISHED: &str = "meeting:finished";
+
+// Выход из приложения: текущий этап (окна могут показать "Сохраняем запись…")
+pub const EVENT_APP_SHUTDOWN: &str = "app:shutdown";
+
+// Deep links voicetotext://: необработанные ссылки (OAuth callback) уходят во frontend как есть
+pub const EVENT_DEEP_LINK: &str = "deep-link";
+pub const EVENT_DEEP_LINK_ERROR: &str = "deep-link:error";
+// Лицензия активирована по ссылке с сайта (voicetotext://activate)
+pub const EVENT_LICENSE_ACTIVATED: &str = "license:activated";
+
+// Расшифровка аудиофайла, брошенного на главное окно (drag-and-drop)
+pub const EVENT_FILE_TRANSCRIPTION_REJECTED: &str = "file_transcription:rejected";
+pub const EVENT_FILE_TRANSCRIPTION_PROGRESS: &str = "file_transcription:progress";
+// Очередная глава длинного файла готова (приходят по мере расшифровки)
+pub const EVENT_FILE_TRANSCRIPTION_CHAPTER: &str = "file_transcription:chapter";
+pub const EVENT_FILE_TRANSCRIPTION_RESULT: &str = "file_transcription:result";
+pub const EVENT_FILE_TRANSCRIPTION_ERROR: &str = "file_transcription:error";
+
+// Прогресс локальной расшифровки Whisper (после остановки записи); прервать — cancel_local_transcription
+pub const EVENT_WHISPER_PROGRESS: &str = "whisper:progress";
+
+// Скачанную модель Whisper стоит перекачать (фоновая проверка раз в сутки); payload — ModelUpdateAvailablePayload
+pub const EVENT_MODEL_UPDATE_AVAILABLE: &str = "model:update-available";
 
 // UI lifecycle events
 // Важно: это не "focus", потому что main окно на macOS может быть nonactivating NSPanel и не получать фокус.
 pub const EVENT_RECORDING_WINDOW_SHOWN: &str = "recording:window-shown";
 
+// Диктовка по буквам включена/выключена (хоткей, голосовая команда, set_spelling_mode); payload — SpellingModePayload
+pub const EVENT_SPELLING_MODE: &str = "recording:spelling-mode";
+
+// Выполняется голосовая команда управления (stt voice_commands); payload — VoiceCommandPayload
+pub const EVENT_VOICE_COMMAND: &str = "recording:voice-command";
+
+// Пропала/вернулась сеть (монитор связи); payload — NetworkStatusPayload
+pub const EVENT_NETWORK_OFFLINE: &str = "network:offline";
+pub const EVENT_NETWORK_ONLINE: &str = "network:online";
+
+// Вставка в окно администратора заблокирована Windows (UIPI): текст ушёл в clipboard; payload — PasteClipboardFallbackPayload
+pub const EVENT_PASTE_CLIPBOARD_FALLBACK: &str = "paste:clipboard-fallback";
+
 // State-sync протокол: invalidation event для синхронизации между окнами
 pub const EVENT_STATE_SYNC_INVALIDATION: &str = "state-sync:invalidation";
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StateSyncInvalidationPayload {
     pub topic: String,
@@ -32,7 +106,7 @@ pub struct StateSyncInvalidationPayload {
 }
 
 /// Payload for partial transcription event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct PartialTranscriptionPayload {
     /// Уникальный идентификатор сессии записи (монотонно растёт).
     /// Нужен, чтобы frontend мог игнорировать "поздние" события от предыдущей сессии.
@@ -42,6 +116,11 @@ pub struct PartialTranscriptionPayload {
     pub is_segment_final: bool, // true когда сегмент финализирован (is_final=true в Deepgram)
     pub start: f64, // start время utterance в секундах (от Deepgram)
     pub duration: f64, // длительность utterance в секундах (от Deepgram)
+    /// Канал в режиме стерео-интервью: у каждого канала своя строка partial'а
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u32>,
+    /// Текст сессии вставляет backend (instant paste) — frontend сам не вставляет
+    pub instant_paste: bool,
 }
 
 impl PartialTranscriptionPayload {
@@ -53,12 +132,19 @@ impl PartialTranscriptionPayload {
             is_segment_final: t.is_final, // передаем флаг финализации сегмента
             start: t.start,
             duration: t.duration,
+            channel: t.channel,
+            instant_paste: false,
         }
     }
+
+    pub fn with_instant_paste(mut self, instant_paste: bool) -> Self {
+        self.instant_paste = instant_paste;
+        self
+    }
 }
 
 /// Payload for final transcription event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct FinalTranscriptionPayload {
     /// Уникальный идентификатор сессии записи (монотонно растёт).
     pub session_id: u64,
@@ -66,6 +152,24 @@ pub struct FinalTranscriptionPayload {
     pub confidence: Option<f32>,
     pub language: Option<String>,
     pub timestamp: i64,
+    /// Уверенность ниже настроенного min_confidence
+    pub low_confidence: bool,
+    /// n-best гипотезы (если запрошены в stt config), лучшие первыми
+    pub alternatives: Vec<AlternativeText>,
+    /// Номер говорящего (при включённой диаризации)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<u32>,
+    /// Канал в режиме стерео-интервью (0 — левый, 1 — правый)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u32>,
+    /// Встреча из календаря, шедшая в начале сессии (название/метки записи)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<SessionCalendarTag>,
+    /// Активное приложение при старте записи (если включено capture_app_context)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<AppContext>,
+    /// Текст сессии вставляет backend (instant paste) — frontend сам не вставляет
+    pub instant_paste: bool,
 }
 
 impl FinalTranscriptionPayload {
@@ -76,53 +180,235 @@ impl FinalTranscriptionPayload {
             confidence: t.confidence,
             language: t.language,
             timestamp: t.timestamp,
+            low_confidence: false,
+            alternatives: t.alternatives,
+            speaker: t.speaker,
+            channel: t.channel,
+            calendar: t.calendar,
+            app: t.app,
+            instant_paste: false,
+        }
+    }
+
+    pub fn with_low_confidence(mut self, low_confidence: bool) -> Self {
+        self.low_confidence = low_confidence;
+        self
+    }
+
+    pub fn with_instant_paste(mut self, instant_paste: bool) -> Self {
+        self.instant_paste = instant_paste;
+        self
+    }
+}
+
+/// Финальная фраза, отложенная из-за низкой уверенности (low_confidence_action = hold)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HeldTranscriptionPayload {
+    /// Идентификатор отложенной фразы (для release/discard)
+    pub id: u64,
+    pub session_id: u64,
+    pub text: String,
+    pub confidence: Option<f32>,
+    pub language: Option<String>,
+    pub timestamp: i64,
+}
+
+impl HeldTranscriptionPayload {
+    pub fn from_transcription(id: u64, t: Transcription, session_id: u64) -> Self {
+        Self {
+            id,
+            session_id,
+            text: t.text,
+            confidence: t.confidence,
+            language: t.language,
+            timestamp: t.timestamp,
         }
     }
 }
 
 /// Payload for recording status event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct RecordingStatusPayload {
     /// Уникальный идентификатор сессии записи (монотонно растёт).
     pub session_id: u64,
     pub status: RecordingStatus,
     #[serde(default)]
     pub stopped_via_hotkey: bool,
+    /// Что станет с окном после остановки хоткеем (window_behavior_on_stop); None — окно не трогаем
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_behavior: Option<WindowBehaviorOnStop>,
 }
 
 /// Payload for audio level event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct AudioLevelPayload {
     /// Normalized audio level (0.0 - 1.0)
     pub level: f32,
 }
 
+/// Payload for VAD activity event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct VadActivityPayload {
+    pub session_id: u64,
+    pub kind: VadActivityKind,
+    /// Offset from the capture start, ms
+    pub offset_ms: u64,
+    /// Share of speech frames in the last 300ms (0.0 - 1.0)
+    pub probability: f32,
+    /// Continuous silence so far, ms
+    pub silence_ms: u64,
+    /// Auto-stop threshold, ms (silence_ms reaching it triggers silence_timeout)
+    pub silence_timeout_ms: u64,
+}
+
+impl VadActivityPayload {
+    pub fn new(session_id: u64, activity: VadActivity, silence_timeout_ms: u64) -> Self {
+        Self {
+            session_id,
+            kind: activity.kind,
+            offset_ms: activity.offset_ms,
+            probability: activity.probability,
+            silence_ms: activity.silence_ms,
+            silence_timeout_ms,
+        }
+    }
+}
+
+/// Payload for recording quiet event: why the recording window stayed hidden
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RecordingQuietPayload {
+    pub session_id: u64,
+    /// Режим ОС, из-за которого окно не показано, а звуки выключены (транскрипция идёт как обычно)
+    pub focus_mode: FocusMode,
+}
+
+/// Payload for recording tick event (live stopwatch and usage counter)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RecordingTickPayload {
+    pub session_id: u64,
+    /// Time since recording started, ms
+    pub elapsed_ms: u64,
+    /// Provider streaming price × elapsed time, USD; None — billed by plan minutes (Backend)
+    pub estimated_cost_usd: Option<f64>,
+    /// Remaining backend quota from the last usage update, seconds; None — not reported yet
+    pub quota_remaining_secs: Option<f32>,
+}
+
+impl RecordingTickPayload {
+    pub fn new(session_id: u64, elapsed: std::time::Duration, provider: SttProviderType, quota_remaining_secs: Option<f32>) -> Self {
+        Self {
+            session_id,
+            elapsed_ms: elapsed.as_millis() as u64,
+            estimated_cost_usd: provider
+                .streaming_usd_per_minute()
+                .map(|rate| rate * elapsed.as_secs_f64() / 60.0),
+            quota_remaining_secs,
+        }
+    }
+}
+
 /// Payload for audio spectrum event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct AudioSpectrumPayload {
     /// Normalized bars (48 values, each 0.0 - 1.0)
     pub bars: Vec<f32>,
 }
 
 /// Payload for microphone test level event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct MicrophoneTestLevelPayload {
     /// Normalized audio level (0.0 - 1.0)
     pub level: f32,
+    /// Input clips at the current gain (or already on the device)
+    pub clipping: bool,
+    /// Input stays too quiet even after gain
+    pub too_quiet: bool,
+    /// Chunk peak after gain, dBFS
+    pub peak_dbfs: f32,
 }
 
 /// Payload for transcription error event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct TranscriptionErrorPayload {
     pub session_id: u64,
     pub error: String,
     pub error_type: String, // "connection", "configuration", "processing", "timeout", "authentication"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_details: Option<TranscriptionErrorDetailsPayload>,
+    /// Повтор подключения может помочь (false — auth, лимит, ошибка конфигурации)
+    pub retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+    /// Ключ сообщения в `errors.*` i18n фронтенда (None — фронт выбирает текст сам)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_key: Option<String>,
+}
+
+/// Payload for network offline/online events and `get_network_status`
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct NetworkStatusPayload {
+    pub online: bool,
+    /// Провайдеры, которым нужна сеть, недоступны, пока её нет
+    pub cloud_providers_available: bool,
+    /// Чем записываем без сети (локальный Whisper, если скачана модель); None — записывать нечем
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offline_provider: Option<SttProviderType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offline_model: Option<String>,
+}
+
+/// Payload for paste clipboard fallback event (toast: why the text was only copied)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PasteClipboardFallbackPayload {
+    /// Процесс окна, куда не дошла вставка
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<u64>,
+    /// Локализованный текст уведомления
+    pub message: String,
+}
+
+/// Payload for spelling mode switch event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SpellingModePayload {
+    pub enabled: bool,
+}
+
+/// Payload for voice command event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct VoiceCommandPayload {
+    /// stop_recording | switch_language | paste_text
+    pub command: String,
+    /// Новый язык (только switch_language)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+impl From<&crate::application::VoiceCommand> for VoiceCommandPayload {
+    fn from(command: &crate::application::VoiceCommand) -> Self {
+        let language = match command {
+            crate::application::VoiceCommand::SwitchLanguage { language } => Some(language.clone()),
+            _ => None,
+        };
+        Self {
+            command: command.name().to_string(),
+            language,
+        }
+    }
+}
+
+/// Payload for retry countdown event after a rate limit error
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RetryCountdownPayload {
+    /// Сессия, которую оборвал rate limit
+    pub session_id: u64,
+    /// Секунд до повтора; 0 — повтор доступен
+    pub remaining_secs: u64,
 }
 
 /// Детали ошибки для UI (сериализуемый формат).
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TranscriptionErrorDetailsPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -170,7 +456,7 @@ fn stt_category_to_string(cat: SttConnectionCategory) -> String {
     .to_string()
 }
 /// Connection quality states
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 pub enum ConnectionQuality {
     /// Connection is working normally
@@ -181,11 +467,228 @@ pub enum ConnectionQuality {
     Recovering,
 }
 
+/// Payload for transcription latency event (diagnostics panel)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TranscriptionLatencyPayload {
+    pub session_id: u64,
+    pub kind: crate::application::LatencyKind,
+    pub latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p95_ms: Option<f64>,
+}
+
+/// Payload for get_transcription_metrics command
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TranscriptionMetricsPayload {
+    pub current: crate::application::TranscriptionMetrics,
+    /// Предыдущие сессии (от старых к новым)
+    pub history: Vec<crate::application::TranscriptionMetrics>,
+}
+
 /// Payload for connection quality event
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct ConnectionQualityPayload {
     pub session_id: u64,
     pub quality: ConnectionQuality,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>, // дополнительная информация о причине
+    /// Machine-readable reason for the frontend to localize
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason_code: Option<crate::domain::ConnectionQualityReason>,
+}
+
+/// Payload for connection idle event ("connection held open for X s")
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ConnectionIdlePayload {
+    pub provider: crate::domain::SttProviderType,
+    pub held_secs: u64,
+    pub limit_secs: u64,
+    /// Провайдер тарифицирует время соединения — простой стоит денег
+    pub billed_while_idle: bool,
+    /// Соединение закрыто по таймеру простоя
+    pub closed: bool,
+}
+
+impl From<crate::application::IdleConnectionReport> for ConnectionIdlePayload {
+    fn from(report: crate::application::IdleConnectionReport) -> Self {
+        Self {
+            provider: report.provider,
+            held_secs: report.held_secs,
+            limit_secs: report.limit_secs,
+            billed_while_idle: report.billed_while_idle,
+            closed: report.closed,
+        }
+    }
+}
+
+/// Payload for provider comparison event (one per provider segment)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ComparisonTranscriptPayload {
+    pub session_id: u64,
+    pub provider: crate::domain::SttProviderType,
+    /// Основной провайдер сессии (его текст идёт в окно и вставку)
+    pub primary: bool,
+    pub text: String,
+    pub is_final: bool,
+    /// Весь текст провайдера за сессию
+    pub transcript: String,
+}
+
+impl ComparisonTranscriptPayload {
+    pub fn new(session_id: u64, transcript: crate::application::ComparisonTranscript) -> Self {
+        Self {
+            session_id,
+            provider: transcript.provider,
+            primary: transcript.primary,
+            text: transcript.text,
+            is_final: transcript.is_final,
+            transcript: transcript.transcript,
+        }
+    }
+}
+
+/// Payload for ensemble transcript event (experimental)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EnsembleTranscriptPayload {
+    pub session_id: u64,
+    /// Весь слитый текст сессии
+    pub transcript: String,
+    /// Сколько слов взято у сравниваемого провайдера
+    pub secondary_words: usize,
+}
+
+impl EnsembleTranscriptPayload {
+    pub fn new(session_id: u64, ensemble: crate::application::EnsembleTranscript) -> Self {
+        Self {
+            session_id,
+            transcript: ensemble.transcript,
+            secondary_words: ensemble.secondary_words,
+        }
+    }
+}
+
+/// Payload for deep link error event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DeepLinkErrorPayload {
+    /// Действие ссылки ("record", "activate")
+    pub action: String,
+    pub error: String,
+}
+
+/// Payload for history item updated event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HistoryItemUpdatedPayload {
+    pub id: u64,
+    pub text: String,
+    /// Время правки (unix seconds)
+    pub edited_at: Option<i64>,
+    /// Окно, из которого пришла правка: оно уже показывает этот текст
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+}
+
+/// Payload for meeting finished event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MeetingFinishedPayload {
+    pub transcript: crate::domain::MeetingTranscript,
+    /// Файл с экспортом (None — сохранить не удалось, транскрипт есть только в payload)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saved_path: Option<String>,
+}
+
+/// Stage of the graceful shutdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownStage {
+    /// Останавливаем захват и ждём финальные фразы от провайдера
+    StoppingRecording,
+    /// Сохраняем встречу и состояние на диск
+    Persisting,
+    Exiting,
+}
+
+/// Payload for app shutdown event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ShutdownPayload {
+    pub stage: ShutdownStage,
+}
+
+/// Payload for file transcription rejected event (файл не принят в работу)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FileTranscriptionRejectedPayload {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Payload for file transcription progress event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FileTranscriptionProgressPayload {
+    pub job_id: String,
+    pub path: String,
+    pub processed_ms: u64,
+    pub total_ms: u64,
+}
+
+/// Payload for local Whisper transcription progress event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WhisperProgressPayload {
+    /// Audio windows (up to 30s each) transcribed so far
+    pub processed_segments: usize,
+    /// Windows in the whole recording
+    pub estimated_segments: usize,
+}
+
+impl From<crate::infrastructure::stt::WhisperProgress> for WhisperProgressPayload {
+    fn from(progress: crate::infrastructure::stt::WhisperProgress) -> Self {
+        Self {
+            processed_segments: progress.processed_segments,
+            estimated_segments: progress.estimated_segments,
+        }
+    }
+}
+
+/// Payload for model update available event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ModelUpdateAvailablePayload {
+    pub model: String,
+    pub reason: crate::infrastructure::models::ModelUpdateReason,
+    /// Размер модели для загрузки
+    pub size_bytes: u64,
+}
+
+impl From<crate::infrastructure::models::ModelUpdateAvailable> for ModelUpdateAvailablePayload {
+    fn from(update: crate::infrastructure::models::ModelUpdateAvailable) -> Self {
+        Self {
+            model: update.model,
+            reason: update.reason,
+            size_bytes: update.size_bytes,
+        }
+    }
+}
+
+/// Payload for file transcription chapter event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FileTranscriptionChapterPayload {
+    pub job_id: String,
+    pub path: String,
+    pub chapter: crate::domain::TranscriptionChapter,
+}
+
+/// Payload for file transcription result event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FileTranscriptionResultPayload {
+    pub job_id: String,
+    pub path: String,
+    pub text: String,
+    pub duration_ms: u64,
+}
+
+/// Payload for file transcription error event (файл принят, но расшифровать не удалось)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FileTranscriptionErrorPayload {
+    pub job_id: String,
+    pub path: String,
+    pub error: String,
 }
@@ -0,0 +1,87 @@
+//! Drag-and-drop аудиофайлов на главное окно.
+//!
+//! Брошенные WAV/MP3 ставятся в очередь расшифровки (см. `transcription_jobs`), оттуда приходят
+//! события progress/chapter/result/error. Неподходящие файлы сразу отклоняются событием
+//! `file_transcription:rejected` с причиной.
+
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::infrastructure::audio::{audio_file_extension, is_supported_audio_file};
+use crate::presentation::events::{FileTranscriptionRejectedPayload, EVENT_FILE_TRANSCRIPTION_REJECTED};
+use crate::presentation::i18n::UiMessage;
+use crate::presentation::transcription_jobs;
+use crate::presentation::AppState;
+
+/// Почему файл не принят в работу (None — принят)
+pub fn rejection_reason(path: &Path) -> Option<UiMessage> {
+    if !path.is_file() {
+        return Some(UiMessage::DroppedFileNotAFile);
+    }
+    if !is_supported_audio_file(path) {
+        return Some(UiMessage::DroppedFileUnsupported {
+            extension: audio_file_extension(path),
+        });
+    }
+    None
+}
+
+/// Обрабатывает `WindowEvent::DragDrop(Drop)` главного окна: подходящие файлы уходят в очередь расшифровки
+pub fn handle_file_drop(app_handle: &AppHandle, paths: Vec<PathBuf>) {
+    log::info!("Files dropped on main window: {:?}", paths);
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app_handle.try_state::<AppState>() else {
+            log::error!("AppState не доступен, брошенные файлы пропущены");
+            return;
+        };
+
+        for path in paths {
+            match rejection_reason(&path) {
+                Some(reason) => {
+                    let reason = state.localize(reason).await;
+                    log::info!("Dropped file rejected: {:?} ({})", path, reason);
+                    let payload = FileTranscriptionRejectedPayload {
+                        path: path.display().to_string(),
+                        reason,
+                    };
+                    if let Err(e) = app_handle.emit(EVENT_FILE_TRANSCRIPTION_REJECTED, payload) {
+                        log::error!("Failed to emit file transcription rejected event: {}", e);
+                    }
+                }
+                None => {
+                    transcription_jobs::enqueue_file(&app_handle, &state, &path).await;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_directories_and_unsupported_formats() {
+        let dir = std::env::temp_dir().join(format!("file_drop_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ogg = dir.join("note.ogg");
+        let wav = dir.join("note.WAV");
+        std::fs::write(&ogg, b"OggS").unwrap();
+        std::fs::write(&wav, b"RIFF").unwrap();
+
+        assert_eq!(rejection_reason(&dir), Some(UiMessage::DroppedFileNotAFile));
+        assert_eq!(
+            rejection_reason(&ogg),
+            Some(UiMessage::DroppedFileUnsupported {
+                extension: "ogg".to_string()
+            })
+        );
+        // Содержимое проверяет декодер — здесь только расширение
+        assert_eq!(rejection_reason(&wav), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
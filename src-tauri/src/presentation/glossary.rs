@@ -0,0 +1,123 @@
+//! Общий словарь команды (лицензия для команд).
+//!
+//! Словарь раз в 15 минут забирается с бэкенда, кэшируется на диске (работает офлайн)
+//! и добавляется к keyterms пользователя при каждом новом соединении STT.
+//! Сохранённый SttConfig не меняется: локальный список терминов остаётся как был.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::domain::{GlossaryStatus, TeamGlossary};
+use crate::infrastructure::team_glossary::{BackendGlossaryClient, GlossaryError};
+use crate::infrastructure::ConfigStore;
+use crate::presentation::AppState;
+
+const GLOSSARY_SYNC_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Задержка первой синхронизации после старта (сессия auth к этому времени уже загружена)
+const GLOSSARY_INITIAL_DELAY: Duration = Duration::from_secs(20);
+
+/// Подключает словарь к распознаванию и отражает его в статусе
+async fn apply_glossary(state: &AppState, glossary: Option<&TeamGlossary>) {
+    let terms = glossary.map(TeamGlossary::unique_terms).unwrap_or_default();
+    let mut status = state.glossary_status.write().await;
+    status.available = glossary.is_some();
+    status.revision = glossary.map(|g| g.revision);
+    status.term_count = terms.len();
+    status.last_synced_at_ms = glossary.map(|g| g.synced_at_ms);
+    state.recording.transcription_service.set_team_glossary(terms);
+}
+
+/// Словарь из кэша — чтобы термины команды работали сразу после старта и без сети
+pub async fn load_cached_glossary(state: &AppState) {
+    match ConfigStore::load_team_glossary().await {
+        Ok(Some(glossary)) => {
+            log::info!("Team glossary loaded from cache (revision {})", glossary.revision);
+            apply_glossary(state, Some(&glossary)).await;
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to load team glossary: {}", e),
+    }
+}
+
+/// Одна синхронизация с бэкендом. Ошибка сети — остаётся словарь из кэша.
+pub async fn sync_team_glossary(state: &AppState) -> GlossaryStatus {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    state.glossary_status.write().await.last_attempt_at_ms = Some(now_ms);
+
+    let access_token = state
+        .auth_store
+        .read()
+        .await
+        .session
+        .as_ref()
+        .map(|s| s.access_token.clone());
+    let Some(access_token) = access_token else {
+        // Не вошёл в аккаунт — словаря команды нет
+        forget_glossary(state).await;
+        return state.glossary_status.read().await.clone();
+    };
+
+    let known_revision = state.glossary_status.read().await.revision;
+    let client = BackendGlossaryClient::new(AppState::get_api_base_url(), access_token);
+    match client.fetch(known_revision).await {
+        Ok(Some(glossary)) => {
+            log::info!(
+                "Team glossary synced: revision {}, {} terms",
+                glossary.revision,
+                glossary.terms.len()
+            );
+            if let Err(e) = ConfigStore::save_team_glossary(&glossary).await {
+                log::warn!("Failed to save team glossary: {}", e);
+            }
+            apply_glossary(state, Some(&glossary)).await;
+            state.glossary_status.write().await.last_error = None;
+        }
+        Ok(None) => {
+            log::debug!("Team glossary is up to date");
+            let mut status = state.glossary_status.write().await;
+            status.last_synced_at_ms = Some(now_ms);
+            status.last_error = None;
+        }
+        Err(GlossaryError::NotAvailable) => {
+            log::debug!("Team glossary is not available for this account");
+            forget_glossary(state).await;
+        }
+        Err(e) => {
+            log::warn!("Team glossary sync failed: {}", e);
+            state.glossary_status.write().await.last_error = Some(e.to_string());
+        }
+    }
+
+    state.glossary_status.read().await.clone()
+}
+
+async fn forget_glossary(state: &AppState) {
+    state.glossary_status.write().await.last_error = None;
+    if state.glossary_status.read().await.revision.is_none() {
+        return;
+    }
+    apply_glossary(state, None).await;
+    if let Err(e) = ConfigStore::delete_team_glossary().await {
+        log::warn!("Failed to delete team glossary: {}", e);
+    }
+}
+
+/// Фоновая синхронизация словаря
+pub fn start_glossary_sync(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Some(state) = app_handle.try_state::<AppState>() {
+            load_cached_glossary(&state).await;
+        }
+        tokio::time::sleep(GLOSSARY_INITIAL_DELAY).await;
+
+        loop {
+            if let Some(state) = app_handle.try_state::<AppState>() {
+                sync_team_glossary(&state).await;
+            }
+
+            tokio::time::sleep(GLOSSARY_SYNC_INTERVAL).await;
+        }
+    });
+}
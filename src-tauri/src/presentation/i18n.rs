@@ -0,0 +1,564 @@
+//! Локализация строк, которые бэкенд отдаёт во frontend: ошибки команд, причины качества связи, меню трея.
+//!
+//! Язык берётся из `UiPreferences.locale` (тот же, что у интерфейса). Полные каталоги есть для ru и en,
+//! остальные локали интерфейса (es, fr, de, uk) получают английский текст.
+
+use crate::domain::ConnectionQualityReason;
+
+/// Language of backend-generated user-facing strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiLanguage {
+    Ru,
+    #[default]
+    En,
+}
+
+impl UiLanguage {
+    /// "ru", "ru-RU", "ru_RU" → Ru; всё остальное — En
+    pub fn from_locale(locale: &str) -> Self {
+        let primary = locale
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "ru" => UiLanguage::Ru,
+            _ => UiLanguage::En,
+        }
+    }
+}
+
+/// User-facing message produced by the backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UiMessage {
+    AppShuttingDown,
+    MicrophoneAccessDenied,
+    RecorderInitFailed { error: String },
+    /// Настройки сохранены, но новое устройство записи не применилось
+    RecorderSwitchFailed { error: String },
+    CorrectionsUnavailable,
+    NoFinalPhrase,
+    AlternativeNotFound { index: usize, available: usize },
+    HistoryItemNotFound { id: u64 },
+    InvalidHotkey { hotkey: String },
+    HotkeyTakenBySystem { hotkey: String, owner: String },
+    HotkeyTakenByOtherApp { hotkey: String },
+    /// Конфликт хоткея со списком свободных вариантов
+    HotkeyConflictWithSuggestions { reason: String, suggestions: Vec<String> },
+    CaptionsDuringRecording { status: String },
+    MeetingAlreadyRunning,
+    MeetingDuringRecording { status: String },
+    MicrophoneTestRunning,
+    MicrophoneTestMissing,
+    MicrophoneTestNoAudio,
+    MicrophoneTestTooLong { samples: usize, max: usize },
+    AccuracyTestRunning,
+    AccuracyTestNotRunning,
+    AccuracyTestNoAudio,
+    AccuracyScriptNotFound { script: String },
+    /// Описание скачанной модели (с размером на диске, если известен)
+    ModelDownloaded { description: String, size: Option<String> },
+    CalendarNotConfigured,
+    CalendarTimedOut,
+    NothingToSpeak,
+    SpeechFailed { error: String },
+    SessionNotFound { id: u64 },
+    SessionTranscriptEmpty,
+    ShareSignInRequired,
+    ShareFailed { error: String },
+    DeepLinkInvalidLanguage { language: String },
+    DeepLinkMissingToken,
+    DroppedFileUnsupported { extension: String },
+    DroppedFileNotAFile,
+    TranscriptionJobNotFound { id: String },
+    TranscriptionJobNotActive,
+    TranscriptionJobNotResumable,
+    TranscriptionJobFinished,
+    DeliveryProfileNotFound { profile: String },
+    /// Windows не пропускает вставку в окно администратора — текст скопирован в clipboard
+    PasteBlockedByElevatedWindow { target: Option<String> },
+    ElevatedPasteGuidance,
+    RecordingProfileNotFound { profile: String },
+    /// Два профиля записи с одним именем или хоткеем
+    RecordingProfileConflict { profile: String },
+    LicenseSignInRequired,
+    ServerUnreachable { error: String },
+    LicenseActivationFailed { message: String },
+    /// Ошибки полей команды (см. presentation::validation)
+    FieldRequired,
+    FieldUnsupportedValue { value: String },
+    FieldOutOfRange { value: String, min: String, max: String },
+    FieldTooLong { max: usize },
+    ModelUnsupportedForLanguage { model: String, language: String },
+    ValidationFailed { fields: Vec<String> },
+    TrayOpen,
+    TraySettings,
+    TrayProfile,
+    TrayCheckUpdates,
+    TrayQuit,
+}
+
+impl UiMessage {
+    /// Stable message id (для логов и тестов каталога)
+    pub fn key(&self) -> &'static str {
+        match self {
+            UiMessage::AppShuttingDown => "app-shutting-down",
+            UiMessage::MicrophoneAccessDenied => "microphone-access-denied",
+            UiMessage::RecorderInitFailed { .. } => "recorder-init-failed",
+            UiMessage::RecorderSwitchFailed { .. } => "recorder-switch-failed",
+            UiMessage::CorrectionsUnavailable => "corrections-unavailable",
+            UiMessage::NoFinalPhrase => "no-final-phrase",
+            UiMessage::AlternativeNotFound { .. } => "alternative-not-found",
+            UiMessage::HistoryItemNotFound { .. } => "history-item-not-found",
+            UiMessage::InvalidHotkey { .. } => "invalid-hotkey",
+            UiMessage::HotkeyTakenBySystem { .. } => "hotkey-taken-by-system",
+            UiMessage::HotkeyTakenByOtherApp { .. } => "hotkey-taken-by-other-app",
+            UiMessage::HotkeyConflictWithSuggestions { .. } => "hotkey-conflict-with-suggestions",
+            UiMessage::CaptionsDuringRecording { .. } => "captions-during-recording",
+            UiMessage::MeetingAlreadyRunning => "meeting-already-running",
+            UiMessage::MeetingDuringRecording { .. } => "meeting-during-recording",
+            UiMessage::MicrophoneTestRunning => "microphone-test-running",
+            UiMessage::MicrophoneTestMissing => "microphone-test-missing",
+            UiMessage::MicrophoneTestNoAudio => "microphone-test-no-audio",
+            UiMessage::MicrophoneTestTooLong { .. } => "microphone-test-too-long",
+            UiMessage::AccuracyTestRunning => "accuracy-test-running",
+            UiMessage::AccuracyTestNotRunning => "accuracy-test-not-running",
+            UiMessage::AccuracyTestNoAudio => "accuracy-test-no-audio",
+            UiMessage::AccuracyScriptNotFound { .. } => "accuracy-script-not-found",
+            UiMessage::ModelDownloaded { .. } => "model-downloaded",
+            UiMessage::CalendarNotConfigured => "calendar-not-configured",
+            UiMessage::CalendarTimedOut => "calendar-timed-out",
+            UiMessage::NothingToSpeak => "nothing-to-speak",
+            UiMessage::SpeechFailed { .. } => "speech-failed",
+            UiMessage::SessionNotFound { .. } => "session-not-found",
+            UiMessage::SessionTranscriptEmpty => "session-transcript-empty",
+            UiMessage::ShareSignInRequired => "share-sign-in-required",
+            UiMessage::ShareFailed { .. } => "share-failed",
+            UiMessage::DeepLinkInvalidLanguage { .. } => "deep-link-invalid-language",
+            UiMessage::DeepLinkMissingToken => "deep-link-missing-token",
+            UiMessage::DroppedFileUnsupported { .. } => "dropped-file-unsupported",
+            UiMessage::DroppedFileNotAFile => "dropped-file-not-a-file",
+            UiMessage::TranscriptionJobNotFound { .. } => "transcription-job-not-found",
+            UiMessage::TranscriptionJobNotActive => "transcription-job-not-active",
+            UiMessage::TranscriptionJobNotResumable => "transcription-job-not-resumable",
+            UiMessage::TranscriptionJobFinished => "transcription-job-finished",
+            UiMessage::DeliveryProfileNotFound { .. } => "delivery-profile-not-found",
+            UiMessage::PasteBlockedByElevatedWindow { .. } => "paste-blocked-by-elevated-window",
+            UiMessage::ElevatedPasteGuidance => "elevated-paste-guidance",
+            UiMessage::RecordingProfileNotFound { .. } => "recording-profile-not-found",
+            UiMessage::RecordingProfileConflict { .. } => "recording-profile-conflict",
+            UiMessage::LicenseSignInRequired => "license-sign-in-required",
+            UiMessage::ServerUnreachable { .. } => "server-unreachable",
+            UiMessage::LicenseActivationFailed { .. } => "license-activation-failed",
+            UiMessage::FieldRequired => "field-required",
+            UiMessage::FieldUnsupportedValue { .. } => "field-unsupported-value",
+            UiMessage::FieldOutOfRange { .. } => "field-out-of-range",
+            UiMessage::FieldTooLong { .. } => "field-too-long",
+            UiMessage::ModelUnsupportedForLanguage { .. } => "model-unsupported-for-language",
+            UiMessage::ValidationFailed { .. } => "validation-failed",
+            UiMessage::TrayOpen => "tray-open",
+            UiMessage::TraySettings => "tray-settings",
+            UiMessage::TrayProfile => "tray-profile",
+            UiMessage::TrayCheckUpdates => "tray-check-updates",
+            UiMessage::TrayQuit => "tray-quit",
+        }
+    }
+
+    pub fn text(&self, lang: UiLanguage) -> String {
+        match lang {
+            UiLanguage::Ru => self.ru(),
+            UiLanguage::En => self.en(),
+        }
+    }
+
+    fn ru(&self) -> String {
+        match self {
+            UiMessage::AppShuttingDown => "Приложение закрывается".to_string(),
+            UiMessage::MicrophoneAccessDenied => "Нет доступа к микрофону. Откройте macOS System Settings → Privacy & Security → Microphone и включите доступ для приложения.".to_string(),
+            UiMessage::RecorderInitFailed { error } => format!("Не удалось инициализировать устройство записи: {}", error),
+            UiMessage::RecorderSwitchFailed { error } => {
+                format!("Настройки сохранены, но не удалось применить новое устройство записи: {}", error)
+            }
+            UiMessage::CorrectionsUnavailable => "Словарь исправлений недоступен".to_string(),
+            UiMessage::NoFinalPhrase => "Нет финальной фразы для замены".to_string(),
+            UiMessage::AlternativeNotFound { index, available } => {
+                format!("Альтернатива {} не найдена (доступно: {})", index, available)
+            }
+            UiMessage::HistoryItemNotFound { id } => format!("Запись истории {} не найдена", id),
+            UiMessage::InvalidHotkey { hotkey } => format!("Неверный формат горячей клавиши: {}", hotkey),
+            UiMessage::HotkeyTakenBySystem { hotkey, owner } => {
+                format!("Сочетание {} занято системой ({})", hotkey, owner)
+            }
+            UiMessage::HotkeyTakenByOtherApp { hotkey } => {
+                format!("Сочетание {} уже используется другим приложением", hotkey)
+            }
+            UiMessage::HotkeyConflictWithSuggestions { reason, suggestions } => {
+                format!("{}. Свободные варианты: {}", reason, suggestions.join(", "))
+            }
+            UiMessage::CaptionsDuringRecording { status } => {
+                format!("Нельзя включить субтитры во время записи (статус: {})", status)
+            }
+            UiMessage::MeetingAlreadyRunning => "Встреча уже идёт".to_string(),
+            UiMessage::MeetingDuringRecording { status } => {
+                format!("Нельзя начать встречу во время записи (статус: {})", status)
+            }
+            UiMessage::MicrophoneTestRunning => "Сначала остановите тест микрофона".to_string(),
+            UiMessage::MicrophoneTestMissing => "Нет записи теста микрофона".to_string(),
+            UiMessage::MicrophoneTestNoAudio => "Нет аудио для обработки: сначала запишите тест микрофона".to_string(),
+            UiMessage::MicrophoneTestTooLong { samples, max } => {
+                format!("Слишком длинный буфер: {} сэмплов (максимум {})", samples, max)
+            }
+            UiMessage::AccuracyTestRunning => "Тест точности уже идёт".to_string(),
+            UiMessage::AccuracyTestNotRunning => "Тест точности не запущен".to_string(),
+            UiMessage::AccuracyTestNoAudio => "Запись пустая: прочитайте текст вслух и попробуйте снова".to_string(),
+            UiMessage::AccuracyScriptNotFound { script } => format!("Текст для теста не найден: {}", script),
+            UiMessage::ModelDownloaded { description, size: Some(size) } => {
+                format!("{} (Скачана, {} на диске)", description, size)
+            }
+            UiMessage::ModelDownloaded { description, size: None } => format!("{} (Скачана)", description),
+            UiMessage::CalendarNotConfigured => "Календарь не настроен".to_string(),
+            UiMessage::CalendarTimedOut => "Календарь не ответил вовремя".to_string(),
+            UiMessage::NothingToSpeak => "Нет текста для озвучивания".to_string(),
+            UiMessage::SpeechFailed { error } => format!("Не удалось озвучить текст: {}", error),
+            UiMessage::SessionNotFound { id } => format!("Сессия записи не найдена: {}", id),
+            UiMessage::SessionTranscriptEmpty => "В истории нет текста этой сессии".to_string(),
+            UiMessage::ShareSignInRequired => "Войдите в аккаунт, чтобы делиться сессиями".to_string(),
+            UiMessage::ShareFailed { error } => format!("Не удалось поделиться сессией: {}", error),
+            UiMessage::DeepLinkInvalidLanguage { language } => format!("Некорректный язык: {}", language),
+            UiMessage::DeepLinkMissingToken => "В ссылке активации нет токена".to_string(),
+            UiMessage::DroppedFileUnsupported { extension } if extension.is_empty() => {
+                "Формат файла не поддерживается (нужен WAV или MP3)".to_string()
+            }
+            UiMessage::DroppedFileUnsupported { extension } => {
+                format!("Формат .{} не поддерживается (нужен WAV или MP3)", extension)
+            }
+            UiMessage::DroppedFileNotAFile => "Это не аудиофайл: перетащите WAV или MP3".to_string(),
+            UiMessage::TranscriptionJobNotFound { id } => format!("Задача расшифровки не найдена: {}", id),
+            UiMessage::TranscriptionJobNotActive => "Задача уже не выполняется".to_string(),
+            UiMessage::TranscriptionJobNotResumable => "Продолжить можно только приостановленную или прерванную ошибкой задачу".to_string(),
+            UiMessage::TranscriptionJobFinished => "Задача уже завершена".to_string(),
+            UiMessage::DeliveryProfileNotFound { profile } => format!("Профиль доставки не найден: {}", profile),
+            UiMessage::PasteBlockedByElevatedWindow { target } => format!(
+                "{} запущено от имени администратора, и Windows не даёт вставить в него текст. Текст скопирован — нажмите Ctrl+V.",
+                target.as_deref().unwrap_or("Приложение")
+            ),
+            UiMessage::ElevatedPasteGuidance => "Чтобы вставлять текст в окна администратора, запустите VoicetextAI от имени администратора или установите подписанную сборку в Program Files (uiAccess в манифесте).".to_string(),
+            UiMessage::RecordingProfileNotFound { profile } => format!("Профиль записи не найден: {}", profile),
+            UiMessage::RecordingProfileConflict { profile } => {
+                format!("Профиль записи {} повторяет имя или хоткей другого профиля", profile)
+            }
+            UiMessage::LicenseSignInRequired => "Войдите в аккаунт, чтобы активировать лицензию".to_string(),
+            UiMessage::ServerUnreachable { error } => format!("Не удалось связаться с сервером: {}", error),
+            UiMessage::LicenseActivationFailed { message } => format!("Не удалось активировать лицензию: {}", message),
+            UiMessage::FieldRequired => "Обязательное поле".to_string(),
+            UiMessage::FieldUnsupportedValue { value } => format!("Неподдерживаемое значение: {}", value),
+            UiMessage::FieldOutOfRange { value, min, max } => {
+                format!("Значение {} вне допустимого диапазона {}–{}", value, min, max)
+            }
+            UiMessage::FieldTooLong { max } => format!("Не длиннее {} символов", max),
+            UiMessage::ModelUnsupportedForLanguage { model, language } => {
+                format!("Модель {} не поддерживает язык {}", model, language)
+            }
+            UiMessage::ValidationFailed { fields } => format!("Проверьте поля: {}", fields.join(", ")),
+            UiMessage::TrayOpen => "Открыть".to_string(),
+            UiMessage::TraySettings => "Настройки".to_string(),
+            UiMessage::TrayProfile => "Профиль".to_string(),
+            UiMessage::TrayCheckUpdates => "Проверить обновления".to_string(),
+            UiMessage::TrayQuit => "Выход".to_string(),
+        }
+    }
+
+    fn en(&self) -> String {
+        match self {
+            UiMessage::AppShuttingDown => "The app is shutting down".to_string(),
+            UiMessage::MicrophoneAccessDenied => "No microphone access. Open macOS System Settings → Privacy & Security → Microphone and allow access for the app.".to_string(),
+            UiMessage::RecorderInitFailed { error } => format!("Failed to initialize the recording device: {}", error),
+            UiMessage::RecorderSwitchFailed { error } => {
+                format!("Settings saved, but the new recording device could not be applied: {}", error)
+            }
+            UiMessage::CorrectionsUnavailable => "The corrections dictionary is unavailable".to_string(),
+            UiMessage::NoFinalPhrase => "There is no final phrase to replace".to_string(),
+            UiMessage::AlternativeNotFound { index, available } => {
+                format!("Alternative {} not found ({} available)", index, available)
+            }
+            UiMessage::HistoryItemNotFound { id } => format!("History item {} not found", id),
+            UiMessage::InvalidHotkey { hotkey } => format!("Invalid hotkey format: {}", hotkey),
+            UiMessage::HotkeyTakenBySystem { hotkey, owner } => {
+                format!("{} is reserved by the system ({})", hotkey, owner)
+            }
+            UiMessage::HotkeyTakenByOtherApp { hotkey } => format!("{} is already used by another app", hotkey),
+            UiMessage::HotkeyConflictWithSuggestions { reason, suggestions } => {
+                format!("{}. Available alternatives: {}", reason, suggestions.join(", "))
+            }
+            UiMessage::CaptionsDuringRecording { status } => {
+                format!("Captions cannot be turned on while recording (status: {})", status)
+            }
+            UiMessage::MeetingAlreadyRunning => "A meeting is already in progress".to_string(),
+            UiMessage::MeetingDuringRecording { status } => {
+                format!("A meeting cannot be started while recording (status: {})", status)
+            }
+            UiMessage::MicrophoneTestRunning => "Stop the microphone test first".to_string(),
+            UiMessage::MicrophoneTestMissing => "There is no microphone test recording".to_string(),
+            UiMessage::MicrophoneTestNoAudio => "No audio to process: record a microphone test first".to_string(),
+            UiMessage::MicrophoneTestTooLong { samples, max } => {
+                format!("Buffer is too long: {} samples (maximum {})", samples, max)
+            }
+            UiMessage::AccuracyTestRunning => "The accuracy test is already running".to_string(),
+            UiMessage::AccuracyTestNotRunning => "The accuracy test is not running".to_string(),
+            UiMessage::AccuracyTestNoAudio => "Nothing was recorded: read the text aloud and try again".to_string(),
+            UiMessage::AccuracyScriptNotFound { script } => format!("Accuracy test script not found: {}", script),
+            UiMessage::ModelDownloaded { description, size: Some(size) } => {
+                format!("{} (Downloaded, {} on disk)", description, size)
+            }
+            UiMessage::ModelDownloaded { description, size: None } => format!("{} (Downloaded)", description),
+            UiMessage::CalendarNotConfigured => "Calendar is not configured".to_string(),
+            UiMessage::CalendarTimedOut => "Calendar did not respond in time".to_string(),
+            UiMessage::NothingToSpeak => "There is no text to read aloud".to_string(),
+            UiMessage::SpeechFailed { error } => format!("Failed to read the text aloud: {}", error),
+            UiMessage::SessionNotFound { id } => format!("Recording session not found: {}", id),
+            UiMessage::SessionTranscriptEmpty => "History has no text for this session".to_string(),
+            UiMessage::ShareSignInRequired => "Sign in to share sessions".to_string(),
+            UiMessage::ShareFailed { error } => format!("Failed to share the session: {}", error),
+            UiMessage::DeepLinkInvalidLanguage { language } => format!("Invalid language: {}", language),
+            UiMessage::DeepLinkMissingToken => "The activation link has no token".to_string(),
+            UiMessage::DroppedFileUnsupported { extension } if extension.is_empty() => {
+                "This file format is not supported (use WAV or MP3)".to_string()
+            }
+            UiMessage::DroppedFileUnsupported { extension } => {
+                format!("The .{} format is not supported (use WAV or MP3)", extension)
+            }
+            UiMessage::DroppedFileNotAFile => "This is not an audio file: drop a WAV or MP3".to_string(),
+            UiMessage::TranscriptionJobNotFound { id } => format!("Transcription job not found: {}", id),
+            UiMessage::TranscriptionJobNotActive => "The job is not running".to_string(),
+            UiMessage::TranscriptionJobNotResumable => "Only a paused or failed job can be resumed".to_string(),
+            UiMessage::TranscriptionJobFinished => "The job has already finished".to_string(),
+            UiMessage::DeliveryProfileNotFound { profile } => format!("Delivery profile not found: {}", profile),
+            UiMessage::PasteBlockedByElevatedWindow { target } => format!(
+                "{} runs as administrator, so Windows blocks pasting into it. The text was copied — press Ctrl+V.",
+                target.as_deref().unwrap_or("The app")
+            ),
+            UiMessage::ElevatedPasteGuidance => "To paste into administrator windows, run VoicetextAI as administrator or install the signed build into Program Files (uiAccess in the manifest).".to_string(),
+            UiMessage::RecordingProfileNotFound { profile } => format!("Recording profile not found: {}", profile),
+            UiMessage::RecordingProfileConflict { profile } => {
+                format!("Recording profile {} reuses the name or hotkey of another profile", profile)
+            }
+            UiMessage::LicenseSignInRequired => "Sign in to activate the license".to_string(),
+            UiMessage::ServerUnreachable { error } => format!("Could not reach the server: {}", error),
+            UiMessage::LicenseActivationFailed { message } => format!("Failed to activate the license: {}", message),
+            UiMessage::FieldRequired => "This field is required".to_string(),
+            UiMessage::FieldUnsupportedValue { value } => format!("Unsupported value: {}", value),
+            UiMessage::FieldOutOfRange { value, min, max } => {
+                format!("Value {} is outside the allowed range {}–{}", value, min, max)
+            }
+            UiMessage::FieldTooLong { max } => format!("At most {} characters", max),
+            UiMessage::ModelUnsupportedForLanguage { model, language } => {
+                format!("Model {} does not support language {}", model, language)
+            }
+            UiMessage::ValidationFailed { fields } => format!("Check these fields: {}", fields.join(", ")),
+            UiMessage::TrayOpen => "Open".to_string(),
+            UiMessage::TraySettings => "Settings".to_string(),
+            UiMessage::TrayProfile => "Profile".to_string(),
+            UiMessage::TrayCheckUpdates => "Check for Updates".to_string(),
+            UiMessage::TrayQuit => "Quit".to_string(),
+        }
+    }
+}
+
+/// Текст причины изменения качества связи (ConnectionQualityPayload.reason)
+pub fn connection_quality_reason(reason: &ConnectionQualityReason, lang: UiLanguage) -> String {
+    use ConnectionQualityReason::*;
+    match lang {
+        UiLanguage::Ru => match reason {
+            AudioStreamLost => "Потерян аудиопоток (микрофон недоступен?). Пробую восстановить...".to_string(),
+            AudioStreamRestored => "Аудио восстановлено".to_string(),
+            MicrophoneSilent => "Не поступает сигнал с микрофона (все семплы = 0). Проверьте выбранное устройство и разрешение на микрофон в macOS.".to_string(),
+            AudioSendBacklog { backlog_secs } => format!("Отставание отправки аудио {:.1}с", backlog_secs),
+            AudioDropped { backlog_secs, dropped_secs } => format!(
+                "Аудио не успевает отправляться: отставание {:.1}с, пропущено {:.1}с старого аудио",
+                backlog_secs, dropped_secs
+            ),
+            AudioBacklogCleared => "Отправка аудио догнала запись".to_string(),
+            AudioSendTooSlow => "Аудио не успевает отправляться (плохое соединение?)".to_string(),
+            ProviderConnectionLost => "Соединение с провайдером потеряно".to_string(),
+            ConnectionRecovering => "Соединение восстанавливается".to_string(),
+            CriticalConnectionError => "Критическая ошибка соединения".to_string(),
+            ConnectionUnstable => "Соединение нестабильно, запись остановлена".to_string(),
+            StreamError { error_type, message } => format!("{}: {}", error_type, message),
+            Reconnecting { attempt, max_attempts } => {
+                format!("Переподключение (попытка {}/{})...", attempt, max_attempts)
+            }
+            NoServerResponse { secs } => format!("Сервер не отвечает больше {} с", secs),
+            StreamRestarted { attempt, max_attempts } => format!(
+                "Распознавание перестало отвечать, соединение открыто заново ({}/{})",
+                attempt, max_attempts
+            ),
+        },
+        UiLanguage::En => match reason {
+            AudioStreamLost => "Audio stream lost (microphone unavailable?). Trying to recover...".to_string(),
+            AudioStreamRestored => "Audio restored".to_string(),
+            MicrophoneSilent => "No signal from the microphone (all samples are 0). Check the selected device and the microphone permission in macOS.".to_string(),
+            AudioSendBacklog { backlog_secs } => format!("Audio upload is {:.1}s behind", backlog_secs),
+            AudioDropped { backlog_secs, dropped_secs } => format!(
+                "Audio upload can't keep up: {:.1}s behind, skipped {:.1}s of old audio",
+                backlog_secs, dropped_secs
+            ),
+            AudioBacklogCleared => "Audio upload caught up with the recording".to_string(),
+            AudioSendTooSlow => "Audio upload can't keep up (poor connection?)".to_string(),
+            ProviderConnectionLost => "Connection to the provider lost".to_string(),
+            ConnectionRecovering => "Connection is recovering".to_string(),
+            CriticalConnectionError => "Critical connection error".to_string(),
+            ConnectionUnstable => "Connection is unstable, recording stopped".to_string(),
+            StreamError { error_type, message } => format!("{}: {}", error_type, message),
+            Reconnecting { attempt, max_attempts } => {
+                format!("Reconnecting (attempt {}/{})...", attempt, max_attempts)
+            }
+            NoServerResponse { secs } => format!("No server response for {}+ seconds", secs),
+            StreamRestarted { attempt, max_attempts } => format!(
+                "Transcription stopped responding, reopened the connection ({}/{})",
+                attempt, max_attempts
+            ),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn all_messages() -> Vec<UiMessage> {
+        let s = || "x".to_string();
+        vec![
+            UiMessage::AppShuttingDown,
+            UiMessage::MicrophoneAccessDenied,
+            UiMessage::RecorderInitFailed { error: s() },
+            UiMessage::RecorderSwitchFailed { error: s() },
+            UiMessage::CorrectionsUnavailable,
+            UiMessage::NoFinalPhrase,
+            UiMessage::AlternativeNotFound { index: 1, available: 0 },
+            UiMessage::HistoryItemNotFound { id: 1 },
+            UiMessage::InvalidHotkey { hotkey: s() },
+            UiMessage::HotkeyTakenBySystem { hotkey: s(), owner: s() },
+            UiMessage::HotkeyTakenByOtherApp { hotkey: s() },
+            UiMessage::HotkeyConflictWithSuggestions { reason: s(), suggestions: vec![s()] },
+            UiMessage::CaptionsDuringRecording { status: s() },
+            UiMessage::MeetingAlreadyRunning,
+            UiMessage::MeetingDuringRecording { status: s() },
+            UiMessage::MicrophoneTestRunning,
+            UiMessage::MicrophoneTestMissing,
+            UiMessage::MicrophoneTestNoAudio,
+            UiMessage::MicrophoneTestTooLong { samples: 2, max: 1 },
+            UiMessage::AccuracyTestRunning,
+            UiMessage::AccuracyTestNotRunning,
+            UiMessage::AccuracyTestNoAudio,
+            UiMessage::AccuracyScriptNotFound { script: s() },
+            UiMessage::ModelDownloaded { description: s(), size: None },
+            UiMessage::CalendarNotConfigured,
+            UiMessage::CalendarTimedOut,
+            UiMessage::NothingToSpeak,
+            UiMessage::SpeechFailed { error: s() },
+            UiMessage::SessionNotFound { id: 1 },
+            UiMessage::SessionTranscriptEmpty,
+            UiMessage::ShareSignInRequired,
+            UiMessage::ShareFailed { error: s() },
+            UiMessage::DeepLinkInvalidLanguage { language: s() },
+            UiMessage::DeepLinkMissingToken,
+            UiMessage::DroppedFileUnsupported { extension: s() },
+            UiMessage::DroppedFileNotAFile,
+            UiMessage::TranscriptionJobNotFound { id: s() },
+            UiMessage::TranscriptionJobNotActive,
+            UiMessage::TranscriptionJobNotResumable,
+            UiMessage::TranscriptionJobFinished,
+            UiMessage::DeliveryProfileNotFound { profile: s() },
+            UiMessage::PasteBlockedByElevatedWindow { target: Some(s()) },
+            UiMessage::ElevatedPasteGuidance,
+            UiMessage::RecordingProfileNotFound { profile: s() },
+            UiMessage::RecordingProfileConflict { profile: s() },
+            UiMessage::LicenseSignInRequired,
+            UiMessage::ServerUnreachable { error: s() },
+            UiMessage::LicenseActivationFailed { message: s() },
+            UiMessage::FieldRequired,
+            UiMessage::FieldUnsupportedValue { value: s() },
+            UiMessage::FieldOutOfRange { value: s(), min: s(), max: s() },
+            UiMessage::FieldTooLong { max: 1 },
+            UiMessage::ModelUnsupportedForLanguage { model: s(), language: s() },
+            UiMessage::ValidationFailed { fields: vec![s()] },
+            UiMessage::TrayOpen,
+            UiMessage::TraySettings,
+            UiMessage::TrayProfile,
+            UiMessage::TrayCheckUpdates,
+            UiMessage::TrayQuit,
+        ]
+    }
+
+    #[test]
+    fn test_locale_resolution() {
+        assert_eq!(UiLanguage::from_locale("ru"), UiLanguage::Ru);
+        assert_eq!(UiLanguage::from_locale("ru-RU"), UiLanguage::Ru);
+        assert_eq!(UiLanguage::from_locale("RU_ru"), UiLanguage::Ru);
+        assert_eq!(UiLanguage::from_locale("en"), UiLanguage::En);
+        // Локали без своего каталога получают английский
+        assert_eq!(UiLanguage::from_locale("uk"), UiLanguage::En);
+        assert_eq!(UiLanguage::from_locale(""), UiLanguage::En);
+    }
+
+    #[test]
+    fn test_catalog_is_complete_in_both_languages() {
+        let messages = all_messages();
+        let keys: HashSet<_> = messages.iter().map(|m| m.key()).collect();
+        assert_eq!(keys.len(), messages.len(), "message keys must be unique");
+
+        for message in &messages {
+            let ru = message.text(UiLanguage::Ru);
+            let en = message.text(UiLanguage::En);
+            assert!(!ru.is_empty() && !en.is_empty(), "{}", message.key());
+            assert!(
+                !en.chars().any(|c| matches!(c, 'а'..='я' | 'А'..='Я' | 'ё' | 'Ё')),
+                "English text of {} contains Cyrillic: {}",
+                message.key(),
+                en
+            );
+        }
+    }
+
+    #[test]
+    fn test_arguments_are_substituted() {
+        let message = UiMessage::HotkeyConflictWithSuggestions {
+            reason: UiMessage::HotkeyTakenByOtherApp {
+                hotkey: "Cmd+Shift+X".to_string(),
+            }
+            .text(UiLanguage::En),
+            suggestions: vec!["Cmd+Shift+Y".to_string(), "Cmd+Alt+X".to_string()],
+        };
+        assert_eq!(
+            message.text(UiLanguage::En),
+            "Cmd+Shift+X is already used by another app. Available alternatives: Cmd+Shift+Y, Cmd+Alt+X"
+        );
+
+        let model = UiMessage::ModelDownloaded {
+            description: "Base".to_string(),
+            size: Some("142 MB".to_string()),
+        };
+        assert_eq!(model.text(UiLanguage::Ru), "Base (Скачана, 142 MB на диске)");
+    }
+
+    #[test]
+    fn test_connection_quality_reasons() {
+        let reason = ConnectionQualityReason::AudioDropped {
+            backlog_secs: 0.96,
+            dropped_secs: 2.88,
+        };
+        assert_eq!(
+            connection_quality_reason(&reason, UiLanguage::Ru),
+            "Аудио не успевает отправляться: отставание 1.0с, пропущено 2.9с старого аудио"
+        );
+        assert_eq!(
+            connection_quality_reason(
+                &ConnectionQualityReason::Reconnecting { attempt: 2, max_attempts: 3 },
+                UiLanguage::En
+            ),
+            "Reconnecting (attempt 2/3)..."
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Режим встречи: одна длинная сессия записи без авто-стопа по тишине.
+//!
+//! Финальные фразы копятся в `MeetingRecorder`; по окончании встреча делится на темы
+//! (паузы + опционально границы от LLM), сохраняется JSON-файлом и уходит во frontend событием.
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::application::MeetingRecorder;
+use crate::domain::{MeetingUtterance, TopicBreak, TopicBreakAdvisor};
+use crate::infrastructure::topic_advisor::BackendTopicAdvisor;
+use crate::infrastructure::ConfigStore;
+use crate::presentation::{AppState, MeetingFinishedPayload, EVENT_MEETING_FINISHED};
+
+fn take_meeting(app_handle: &AppHandle) -> Option<MeetingRecorder> {
+    let state = app_handle.try_state::<AppState>()?;
+    let mut recorder = state.recording.meeting.lock().ok().and_then(|mut meeting| meeting.take())?;
+    recorder.stop();
+    log::info!("Meeting stopped ({} utterances)", recorder.utterances().len());
+    Some(recorder)
+}
+
+/// Завершает встречу (если она шла). Вызывается после остановки записи, чтобы последние
+/// финальные фразы уже были в транскрипте.
+///
+/// Разбиение на темы (с запросом к LLM), сохранение и событие `meeting:finished` идут в фоне —
+/// остановка записи их не ждёт.
+pub fn end_meeting(app_handle: &AppHandle) -> bool {
+    let Some(recorder) = take_meeting(app_handle) else {
+        return false;
+    };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        finish_meeting(&app_handle, recorder, true).await;
+    });
+    true
+}
+
+/// Завершает встречу и дожидается сохранения транскрипта (выход из приложения).
+/// Без запроса к LLM: выход не должен зависеть от сети — темы делятся только по паузам.
+pub async fn end_meeting_and_save(app_handle: &AppHandle) -> bool {
+    let Some(recorder) = take_meeting(app_handle) else {
+        return false;
+    };
+    finish_meeting(app_handle, recorder, false).await;
+    true
+}
+
+async fn finish_meeting(app_handle: &AppHandle, recorder: MeetingRecorder, allow_llm: bool) {
+    let advised_breaks = match app_handle.try_state::<AppState>() {
+        Some(state) if allow_llm && recorder.config().llm_topic_breaks => {
+            request_topic_breaks(&state, recorder.utterances(), recorder.language()).await
+        }
+        _ => None,
+    };
+    let transcript = recorder.transcript(advised_breaks.as_deref());
+    log::info!(
+        "Meeting finished: {:.0}s, {} segments, {} utterances",
+        transcript.duration_secs,
+        transcript.segments.len(),
+        transcript.utterances().count()
+    );
+
+    let saved_path = match ConfigStore::save_meeting_transcript(&transcript).await {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            log::error!("Failed to save meeting transcript: {}", e);
+            None
+        }
+    };
+
+    let payload = MeetingFinishedPayload { transcript, saved_path };
+    if let Err(e) = app_handle.emit(EVENT_MEETING_FINISHED, payload) {
+        log::error!("Failed to emit meeting finished event: {}", e);
+    }
+}
+
+/// Границы тем от бэкенда; при любой ошибке — None (остаётся разбиение по паузам)
+async fn request_topic_breaks(
+    state: &AppState,
+    utterances: &[MeetingUtterance],
+    language: Option<&str>,
+) -> Option<Vec<TopicBreak>> {
+    let access_token = state
+        .auth_store
+        .read()
+        .await
+        .session
+        .as_ref()
+        .map(|s| s.access_token.clone());
+    let advisor = BackendTopicAdvisor::new(AppState::get_api_base_url(), access_token);
+
+    match advisor.suggest_breaks(utterances, language).await {
+        Ok(breaks) => {
+            log::info!("LLM suggested {} topic breaks", breaks.len());
+            Some(breaks)
+        }
+        Err(e) => {
+            log::warn!("LLM topic breaks unavailable, using silence gaps only: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Отчёт о памяти: размеры буферов аудио, истории и очередей пакетной обработки.
+//!
+//! `get_memory_report` отдаёт текущие размеры, фоновая проверка раз в минуту пишет предупреждение
+//! в лог и шлёт `diagnostics:memory-alarm`, когда пул вырос выше порога из `AppConfig::memory_alarms`.
+//! Так в поле ловятся буферы, которые растут без границ.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::application::{audio_pool, items_pool, MemoryAlarmMonitor};
+use crate::domain::{MemoryPoolKind, MemoryReport};
+use crate::presentation::{AppState, EVENT_MEMORY_ALARM};
+
+const MEMORY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Текущие размеры пулов с порогами из настроек
+pub async fn memory_report(state: &AppState) -> MemoryReport {
+    let mut pools = state.recording.transcription_service.audio_buffer_usage();
+    {
+        let microphone_test = state.recording.microphone_test.read().await;
+        let samples = microphone_test.buffer.lock().await.len() + microphone_test.raw_buffer.lock().await.len();
+        pools.push(audio_pool("microphone_test", samples));
+    }
+    {
+        let accuracy_test = state.recording.accuracy_test.read().await;
+        let samples = accuracy_test.buffer.lock().await.len();
+        pools.push(audio_pool("accuracy_test", samples));
+    }
+
+    pools.push(items_pool("history", MemoryPoolKind::History, &state.user_data.history.read().await));
+    pools.push(items_pool("session_stats", MemoryPoolKind::History, &state.user_data.session_stats.read().await));
+    pools.push(items_pool("paste_audit", MemoryPoolKind::History, &state.user_data.paste_audit.read().await));
+    pools.push(items_pool("hotkey_activity", MemoryPoolKind::History, &state.user_data.hotkey_activity.read().await));
+
+    pools.push(items_pool("telemetry_queue", MemoryPoolKind::Queue, &state.integrations.telemetry_queue.read().await));
+    pools.push(items_pool("transcription_jobs", MemoryPoolKind::Queue, &state.recording.transcription_jobs.read().await));
+    {
+        let held = state.recording.held_transcriptions.read().await;
+        let transcriptions: Vec<_> = held.iter().map(|held| &held.transcription).collect();
+        pools.push(items_pool("held_transcriptions", MemoryPoolKind::Queue, &transcriptions));
+    }
+
+    let config = state.settings.config.read().await.memory_alarms;
+    MemoryReport::new(pools, &config)
+}
+
+/// Периодически сверяет пулы с порогами; о выходе за порог — один warning на пересечение
+pub fn start_memory_monitor(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut monitor = MemoryAlarmMonitor::new();
+        loop {
+            tokio::time::sleep(MEMORY_CHECK_INTERVAL).await;
+            let Some(state) = app_handle.try_state::<AppState>() else {
+                continue;
+            };
+            let report = memory_report(&state).await;
+            for pool in monitor.check(&report) {
+                log::warn!(
+                    "Memory pool '{}' ({:?}) is above its threshold: {} bytes in {} items (threshold {} bytes)",
+                    pool.name,
+                    pool.kind,
+                    pool.bytes,
+                    pool.items,
+                    pool.threshold_bytes.unwrap_or_default()
+                );
+                if let Err(e) = app_handle.emit(EVENT_MEMORY_ALARM, &pool) {
+                    log::error!("Failed to emit memory alarm event: {}", e);
+                }
+            }
+        }
+    });
+}
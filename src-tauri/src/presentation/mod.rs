@@ -4,7 +4,26 @@
 pub mod commands;
 pub mod state;
 pub mod events;
+pub mod api_schema;
+pub mod i18n;
+pub mod validation;
+pub mod requests;
 pub mod tray;
+pub mod overlay;
+pub mod captions;
+pub mod calendar;
+pub mod meeting;
+pub mod shutdown;
+pub mod deep_link;
+pub mod file_drop;
+pub mod transcription_jobs;
+pub mod warm_pool;
+pub mod telemetry;
+pub mod glossary;
+pub mod connectivity;
+pub mod toggle_intent;
+pub mod voice_commands;
+pub mod memory;
 
 pub use state::AppState;
 pub use events::*;
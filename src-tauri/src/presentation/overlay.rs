@@ -0,0 +1,181 @@
+//! Оверлей-"пилюля" записи: маленькое окно поверх всех окон (как системная диктовка macOS).
+//!
+//! Окно создаётся при старте записи и уничтожается при остановке. Состояние и уровень звука
+//! оно берёт из обычных событий recording:status / audio:level — отдельного протокола нет.
+
+use tauri::{AppHandle, Manager, PhysicalPosition, Position, WebviewUrl, WebviewWindowBuilder};
+
+use crate::domain::{RecordingOverlayConfig, RecordingOverlayPosition};
+
+pub const OVERLAY_WINDOW_LABEL: &str = "recording-overlay";
+
+/// Размер пилюли (логические пиксели)
+const OVERLAY_WIDTH: f64 = 132.0;
+const OVERLAY_HEIGHT: f64 = 36.0;
+
+/// Отступ от курсора, чтобы пилюля не перекрывала место ввода
+const CURSOR_OFFSET: f64 = 18.0;
+
+/// Отступ от верхнего края экрана (под строкой меню macOS)
+pub(crate) const MENU_BAR_OFFSET: f64 = 36.0;
+
+/// Область монитора в физических пикселях
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct MonitorArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl From<&tauri::Monitor> for MonitorArea {
+    fn from(monitor: &tauri::Monitor) -> Self {
+        Self {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+}
+
+/// Позиция левого верхнего угла пилюли (физические пиксели); пилюля всегда целиком на мониторе
+fn overlay_position(
+    position: RecordingOverlayPosition,
+    cursor: Option<(f64, f64)>,
+    monitor: MonitorArea,
+) -> (i32, i32) {
+    let width = (OVERLAY_WIDTH * monitor.scale_factor).round() as i32;
+    let height = (OVERLAY_HEIGHT * monitor.scale_factor).round() as i32;
+
+    let (x, y) = match (position, cursor) {
+        (RecordingOverlayPosition::Cursor, Some((cursor_x, cursor_y))) => {
+            let offset = CURSOR_OFFSET * monitor.scale_factor;
+            ((cursor_x + offset).round() as i32, (cursor_y + offset).round() as i32)
+        }
+        // Курсор неизвестен — показываем под строкой меню
+        _ => (
+            monitor.x + (monitor.width as i32 - width) / 2,
+            monitor.y + (MENU_BAR_OFFSET * monitor.scale_factor).round() as i32,
+        ),
+    };
+
+    let max_x = monitor.x + (monitor.width as i32 - width).max(0);
+    let max_y = monitor.y + (monitor.height as i32 - height).max(0);
+    (x.clamp(monitor.x, max_x), y.clamp(monitor.y, max_y))
+}
+
+/// Монитор, на котором сейчас курсор (иначе основной)
+pub(crate) fn monitor_under_cursor(app_handle: &AppHandle) -> Option<tauri::Monitor> {
+    app_handle
+        .cursor_position()
+        .ok()
+        .and_then(|c| app_handle.monitor_from_point(c.x, c.y).ok().flatten())
+        .or_else(|| app_handle.primary_monitor().ok().flatten())
+}
+
+/// Показывает оверлей записи (если включён в настройках). Повторный вызов только переставляет окно.
+pub fn show_recording_overlay(app_handle: &AppHandle, config: &RecordingOverlayConfig) {
+    if !config.enabled {
+        return;
+    }
+
+    let window = match app_handle.get_webview_window(OVERLAY_WINDOW_LABEL) {
+        Some(window) => window,
+        None => {
+            let built = WebviewWindowBuilder::new(app_handle, OVERLAY_WINDOW_LABEL, WebviewUrl::App("overlay.html".into()))
+                .title("VoicetextAI — Recording")
+                .inner_size(OVERLAY_WIDTH, OVERLAY_HEIGHT)
+                .resizable(false)
+                .decorations(false)
+                .transparent(true)
+                .shadow(false)
+                .always_on_top(true)
+                .visible_on_all_workspaces(true)
+                .skip_taskbar(true)
+                .focused(false)
+                .visible(false)
+                .build();
+            match built {
+                Ok(window) => window,
+                Err(e) => {
+                    log::warn!("Failed to create recording overlay window: {}", e);
+                    return;
+                }
+            }
+        }
+    };
+
+    // Оверлей только показывает состояние — клики проходят в окно под ним
+    if let Err(e) = window.set_ignore_cursor_events(true) {
+        log::debug!("Failed to make recording overlay click-through: {}", e);
+    }
+
+    let cursor = app_handle.cursor_position().ok();
+    if let Some(monitor) = monitor_under_cursor(app_handle) {
+        let (x, y) = overlay_position(
+            config.position,
+            cursor.map(|c| (c.x, c.y)),
+            MonitorArea::from(&monitor),
+        );
+        if let Err(e) = window.set_position(Position::Physical(PhysicalPosition { x, y })) {
+            log::warn!("Failed to position recording overlay: {}", e);
+        }
+    }
+
+    if let Err(e) = window.show() {
+        log::warn!("Failed to show recording overlay: {}", e);
+    }
+}
+
+/// Уничтожает оверлей записи (если он был создан)
+pub fn hide_recording_overlay(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(OVERLAY_WINDOW_LABEL) {
+        if let Err(e) = window.destroy() {
+            log::warn!("Failed to destroy recording overlay: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RETINA: MonitorArea = MonitorArea {
+        x: 0,
+        y: 0,
+        width: 2880,
+        height: 1800,
+        scale_factor: 2.0,
+    };
+
+    #[test]
+    fn menu_bar_position_is_top_center() {
+        let (x, y) = overlay_position(RecordingOverlayPosition::MenuBar, Some((10.0, 10.0)), RETINA);
+        assert_eq!(x, (2880 - 264) / 2);
+        assert_eq!(y, 72);
+    }
+
+    #[test]
+    fn cursor_position_follows_cursor_and_stays_on_screen() {
+        let (x, y) = overlay_position(RecordingOverlayPosition::Cursor, Some((100.0, 200.0)), RETINA);
+        assert_eq!((x, y), (136, 236));
+
+        // У правого нижнего угла пилюля прижимается к краю монитора
+        let (x, y) = overlay_position(RecordingOverlayPosition::Cursor, Some((2870.0, 1790.0)), RETINA);
+        assert_eq!((x, y), (2880 - 264, 1800 - 72));
+
+        // Второй монитор слева от основного, курсор неизвестен — под строкой меню
+        let left = MonitorArea {
+            x: -1920,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            scale_factor: 1.0,
+        };
+        let (x, y) = overlay_position(RecordingOverlayPosition::Cursor, None, left);
+        assert_eq!((x, y), (-1920 + (1920 - 132) / 2, 36));
+    }
+}
@@ -0,0 +1,497 @@
+//! Типизированные запросы команд.
+//!
+//! Команда принимает аргументы как есть (строки из invoke(), совместимо с текущим frontend),
+//! а `validate()` превращает их в DTO с enum'ами или в ошибки полей (см. `validation`).
+//! Enum'ы разбираются serde, поэтому допустимые значения совпадают с тем, что backend отдаёт в snapshot'ах.
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    model_support, AudioChunkingOverride, AudioEncoding, FillerRemovalConfig, LanguageModelOverride, ModelSupport, SttProviderType, TextNormalizationConfig,
+    WhisperDecodingConfig, MAX_BATCH_CONCURRENCY, MAX_KEEP_ALIVE_TTL_SECS, MAX_PARAGRAPH_PAUSE_MS, MAX_PARTIAL_STABILITY, MAX_PRE_ROLL_MS,
+    MAX_SHORT_CLIP_SECS, MAX_TRANSCRIPTION_ALTERNATIVES, MAX_WHISPER_BEAM_SIZE, MAX_WHISPER_PROMPT_CHARS,
+    MIN_KEEP_ALIVE_TTL_SECS, MIN_WHISPER_TEMPO,
+};
+use crate::presentation::i18n::UiMessage;
+use crate::presentation::validation::ValidationErrors;
+
+/// Recognition language (full Deepgram Nova-3 list, same as STT_LANGUAGES in the frontend)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SttLanguage {
+    En,
+    Ru,
+    Uk,
+    Es,
+    Fr,
+    De,
+    Ja,
+    Ko,
+    Pt,
+    It,
+    Nl,
+    Pl,
+    Cs,
+    Sk,
+    Hu,
+    Ro,
+    Bg,
+    Hr,
+    Sr,
+    Sl,
+    Bs,
+    Mk,
+    El,
+    Tr,
+    Da,
+    Sv,
+    No,
+    Fi,
+    Et,
+    Lv,
+    Lt,
+    Be,
+    Hi,
+    Bn,
+    Ta,
+    Te,
+    Kn,
+    Mr,
+    Id,
+    Ms,
+    Vi,
+    Tl,
+    Ca,
+    Ar,
+    /// Многоязычная модель (code-switching)
+    Multi,
+}
+
+impl SttLanguage {
+    /// Код языка, как он хранится в SttConfig.language
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Ru => "ru",
+            Self::Uk => "uk",
+            Self::Es => "es",
+            Self::Fr => "fr",
+            Self::De => "de",
+            Self::Ja => "ja",
+            Self::Ko => "ko",
+            Self::Pt => "pt",
+            Self::It => "it",
+            Self::Nl => "nl",
+            Self::Pl => "pl",
+            Self::Cs => "cs",
+            Self::Sk => "sk",
+            Self::Hu => "hu",
+            Self::Ro => "ro",
+            Self::Bg => "bg",
+            Self::Hr => "hr",
+            Self::Sr => "sr",
+            Self::Sl => "sl",
+            Self::Bs => "bs",
+            Self::Mk => "mk",
+            Self::El => "el",
+            Self::Tr => "tr",
+            Self::Da => "da",
+            Self::Sv => "sv",
+            Self::No => "no",
+            Self::Fi => "fi",
+            Self::Et => "et",
+            Self::Lv => "lv",
+            Self::Lt => "lt",
+            Self::Be => "be",
+            Self::Hi => "hi",
+            Self::Bn => "bn",
+            Self::Ta => "ta",
+            Self::Te => "te",
+            Self::Kn => "kn",
+            Self::Mr => "mr",
+            Self::Id => "id",
+            Self::Ms => "ms",
+            Self::Vi => "vi",
+            Self::Tl => "tl",
+            Self::Ca => "ca",
+            Self::Ar => "ar",
+            Self::Multi => "multi",
+        }
+    }
+}
+
+/// Recognition model name accepted by update_stt_config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SttModel {
+    #[serde(rename = "nova-3")]
+    Nova3,
+    #[serde(rename = "nova-2")]
+    Nova2,
+    /// Модели Whisper (локальный провайдер)
+    Tiny,
+    Base,
+    Small,
+    Medium,
+    Large,
+}
+
+/// Raw update_stt_config arguments as received from invoke()
+#[derive(Debug, Clone, Default)]
+pub struct UpdateSttConfigArgs {
+    pub provider: String,
+    pub language: String,
+    pub model: Option<String>,
+    pub deepgram_keyterms: Option<Option<String>>,
+    pub audio_encoding: Option<String>,
+    pub idle_teardown_secs: Option<Option<u64>>,
+    pub prewarm_connection: Option<bool>,
+    pub max_alternatives: Option<u8>,
+    pub normalization: Option<TextNormalizationConfig>,
+    pub filler_removal: Option<FillerRemovalConfig>,
+    pub punctuate_locally: Option<bool>,
+    pub diarize: Option<bool>,
+    pub batch_concurrency: Option<u8>,
+    pub short_clip_secs: Option<u32>,
+    pub keep_alive_ttl_secs: Option<u64>,
+    pub language_models: Option<Vec<LanguageModelOverride>>,
+    pub partial_stability: Option<u8>,
+    pub pre_roll_ms: Option<u32>,
+    pub whisper: Option<WhisperDecodingConfig>,
+    pub split_channels: Option<bool>,
+    pub paragraph_pause_ms: Option<u32>,
+    pub voice_commands: Option<bool>,
+    /// Some(None) — выключить A/B сравнение
+    pub comparison_provider: Option<Option<String>>,
+    pub audio_chunking: Option<Vec<AudioChunkingOverride>>,
+}
+
+/// Validated update_stt_config request; None — field not sent, keep the saved value
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateSttConfigRequest {
+    pub provider: SttProviderType,
+    pub language: SttLanguage,
+    pub model: Option<SttModel>,
+    /// Some(None) — очистить keyterms
+    pub deepgram_keyterms: Option<Option<String>>,
+    pub audio_encoding: Option<AudioEncoding>,
+    /// Some(None) — выключить энергосбережение
+    pub idle_teardown_secs: Option<Option<u64>>,
+    pub prewarm_connection: Option<bool>,
+    pub max_alternatives: Option<u8>,
+    pub normalization: Option<TextNormalizationConfig>,
+    pub filler_removal: Option<FillerRemovalConfig>,
+    pub punctuate_locally: Option<bool>,
+    pub diarize: Option<bool>,
+    pub batch_concurrency: Option<u8>,
+    pub short_clip_secs: Option<u32>,
+    pub keep_alive_ttl_secs: Option<u64>,
+    /// Some(vec![]) — убрать все override'ы моделей
+    pub language_models: Option<Vec<LanguageModelOverride>>,
+    pub partial_stability: Option<u8>,
+    pub pre_roll_ms: Option<u32>,
+    pub whisper: Option<WhisperDecodingConfig>,
+    pub split_channels: Option<bool>,
+    pub paragraph_pause_ms: Option<u32>,
+    pub voice_commands: Option<bool>,
+    /// Some(None) — выключить A/B сравнение
+    pub comparison_provider: Option<Option<SttProviderType>>,
+    /// Some(vec![]) — размер чанков по умолчанию для всех провайдеров
+    pub audio_chunking: Option<Vec<AudioChunkingOverride>>,
+}
+
+impl UpdateSttConfigArgs {
+    /// Проверяет все поля сразу; ошибки — с camelCase именами аргументов
+    pub fn validate(self) -> Result<UpdateSttConfigRequest, ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let provider = errors.parse_enum::<SttProviderType>("provider", &self.provider);
+        let language = errors.parse_enum::<SttLanguage>("language", &self.language);
+        let model = errors.parse_optional_enum::<SttModel>("model", self.model.as_deref());
+        let audio_encoding = errors.parse_optional_enum::<AudioEncoding>("audioEncoding", self.audio_encoding.as_deref());
+        let max_alternatives =
+            errors.in_range("maxAlternatives", self.max_alternatives, 0, MAX_TRANSCRIPTION_ALTERNATIVES);
+        let batch_concurrency = errors.in_range("batchConcurrency", self.batch_concurrency, 1, MAX_BATCH_CONCURRENCY);
+        let short_clip_secs = errors.in_range("shortClipSecs", self.short_clip_secs, 0, MAX_SHORT_CLIP_SECS);
+        let keep_alive_ttl_secs = errors.in_range(
+            "keepAliveTtlSecs",
+            self.keep_alive_ttl_secs,
+            MIN_KEEP_ALIVE_TTL_SECS,
+            MAX_KEEP_ALIVE_TTL_SECS,
+        );
+        let partial_stability = errors.in_range("partialStability", self.partial_stability, 0, MAX_PARTIAL_STABILITY);
+        let pre_roll_ms = errors.in_range("preRollMs", self.pre_roll_ms, 0, MAX_PRE_ROLL_MS);
+        let paragraph_pause_ms =
+            errors.in_range("paragraphPauseMs", self.paragraph_pause_ms, 0, MAX_PARAGRAPH_PAUSE_MS);
+        let comparison_provider = self
+            .comparison_provider
+            .map(|provider| errors.parse_optional_enum::<SttProviderType>("comparisonProvider", provider.as_deref()));
+        let whisper = self.whisper.and_then(|whisper| validate_whisper_decoding(&mut errors, whisper));
+        let language_models = self.language_models.map(|overrides| validate_language_models(&mut errors, overrides));
+        let audio_chunking = self.audio_chunking.map(|overrides| validate_audio_chunking(&mut errors, overrides));
+
+        // Не разобрались — ошибка уже записана
+        let (Some(provider), Some(language)) = (provider, language) else {
+            return Err(errors);
+        };
+        errors.finish(|| UpdateSttConfigRequest {
+            provider,
+            language,
+            model,
+            deepgram_keyterms: self.deepgram_keyterms,
+            audio_encoding,
+            idle_teardown_secs: self.idle_teardown_secs,
+            prewarm_connection: self.prewarm_connection,
+            max_alternatives,
+            normalization: self.normalization,
+            filler_removal: self.filler_removal,
+            punctuate_locally: self.punctuate_locally,
+            diarize: self.diarize,
+            batch_concurrency,
+            short_clip_secs,
+            keep_alive_ttl_secs,
+            language_models,
+            partial_stability,
+            pre_roll_ms,
+            whisper,
+            split_channels: self.split_channels,
+            paragraph_pause_ms,
+            voice_commands: self.voice_commands,
+            comparison_provider,
+            audio_chunking,
+        })
+    }
+}
+
+/// Параметры whisper.cpp целиком: одно неверное поле — весь блок не применяется
+fn validate_whisper_decoding(
+    errors: &mut ValidationErrors,
+    whisper: WhisperDecodingConfig,
+) -> Option<WhisperDecodingConfig> {
+    let before = errors.fields().len();
+    errors.in_range("whisper.temperature", Some(whisper.temperature), 0.0, 1.0);
+    errors.in_range("whisper.beamSize", Some(whisper.beam_size), 0, MAX_WHISPER_BEAM_SIZE);
+    errors.in_range("whisper.noSpeechThreshold", Some(whisper.no_speech_threshold), 0.0, 1.0);
+    errors.in_range("whisper.tempo", Some(whisper.tempo), MIN_WHISPER_TEMPO, 1.0);
+    if whisper.initial_prompt().is_some_and(|prompt| prompt.chars().count() > MAX_WHISPER_PROMPT_CHARS) {
+        errors.push("whisper.initialPrompt", UiMessage::FieldTooLong { max: MAX_WHISPER_PROMPT_CHARS });
+    }
+    (errors.fields().len() == before).then_some(whisper)
+}
+
+/// Каждая пара язык → модель сверяется с каталогом моделей провайдера; одна пара на (провайдер, язык)
+fn validate_language_models(
+    errors: &mut ValidationErrors,
+    overrides: Vec<LanguageModelOverride>,
+) -> Vec<LanguageModelOverride> {
+    const FIELD: &str = "languageModels";
+    let mut valid: Vec<LanguageModelOverride> = Vec::new();
+    for item in overrides {
+        let Some(language) = errors.parse_enum::<SttLanguage>(FIELD, &item.language) else {
+            continue;
+        };
+        let Some(model) = errors.require(FIELD, &item.model) else {
+            continue;
+        };
+        let language = language.code();
+        match model_support(item.provider, model, language) {
+            ModelSupport::Supported => {}
+            ModelSupport::UnknownModel => {
+                errors.push(FIELD, UiMessage::FieldUnsupportedValue { value: model.to_string() });
+                continue;
+            }
+            ModelSupport::UnsupportedLanguage => {
+                errors.push(
+                    FIELD,
+                    UiMessage::ModelUnsupportedForLanguage { model: model.to_string(), language: language.to_string() },
+                );
+                continue;
+            }
+        }
+        let item = LanguageModelOverride {
+            provider: item.provider,
+            language: language.to_string(),
+            model: model.to_string(),
+        };
+        // Повтор того же языка у провайдера — побеждает последний
+        valid.retain(|o| !(o.provider == item.provider && o.language == item.language));
+        valid.push(item);
+    }
+    valid
+}
+
+/// Размер чанка сверяется с диапазоном провайдера; один override на провайдера
+fn validate_audio_chunking(
+    errors: &mut ValidationErrors,
+    overrides: Vec<AudioChunkingOverride>,
+) -> Vec<AudioChunkingOverride> {
+    let mut valid: Vec<AudioChunkingOverride> = Vec::new();
+    for item in overrides {
+        let range = item.provider.chunk_ms_range();
+        if errors.in_range("audioChunking", Some(item.chunk_ms), *range.start(), *range.end()).is_none() {
+            continue;
+        }
+        // Повтор провайдера — побеждает последний
+        valid.retain(|o| o.provider != item.provider);
+        valid.push(item);
+    }
+    valid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(provider: &str, language: &str) -> UpdateSttConfigArgs {
+        UpdateSttConfigArgs {
+            provider: provider.to_string(),
+            language: language.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn valid_args_become_typed_request() {
+        let request = UpdateSttConfigArgs {
+            model: Some("nova-3".to_string()),
+            audio_encoding: Some("flac".to_string()),
+            batch_concurrency: Some(4),
+            keep_alive_ttl_secs: Some(300),
+            ..args("backend", "multi")
+        }
+        .validate()
+        .unwrap();
+
+        assert_eq!(request.provider, SttProviderType::Backend);
+        assert_eq!(request.language, SttLanguage::Multi);
+        assert_eq!(request.language.code(), "multi");
+        assert_eq!(request.model, Some(SttModel::Nova3));
+        assert_eq!(request.audio_encoding, Some(AudioEncoding::Flac));
+        assert_eq!(request.batch_concurrency, Some(4));
+        assert_eq!(request.keep_alive_ttl_secs, Some(300));
+        assert_eq!(request.short_clip_secs, None);
+
+        // Пустая модель — "не задано", как null из frontend
+        let request = UpdateSttConfigArgs { model: Some(String::new()), ..args("deepgram", "ru") }.validate().unwrap();
+        assert_eq!(request.model, None);
+    }
+
+    #[test]
+    fn language_codes_round_trip_through_serde() {
+        for code in ["en", "ru", "uk", "pt", "no", "tl", "multi"] {
+            let language: SttLanguage = serde_json::from_value(serde_json::Value::String(code.into())).unwrap();
+            assert_eq!(language.code(), code);
+            assert_eq!(serde_json::to_value(language).unwrap(), code);
+        }
+    }
+
+    #[test]
+    fn language_models_are_checked_against_catalog() {
+        let entry = |provider, language: &str, model: &str| LanguageModelOverride {
+            provider,
+            language: language.to_string(),
+            model: model.to_string(),
+        };
+        let request = UpdateSttConfigArgs {
+            language_models: Some(vec![
+                entry(SttProviderType::Backend, "en", "nova-2"),
+                entry(SttProviderType::Backend, " en ", " nova-3 "),
+                entry(SttProviderType::Backend, "ru", "nova-2"),
+                entry(SttProviderType::WhisperLocal, "de", "large"),
+            ]),
+            ..args("backend", "ru")
+        }
+        .validate()
+        .unwrap();
+        let models = request.language_models.unwrap();
+        assert_eq!(models.len(), 3);
+        assert_eq!(models[0], entry(SttProviderType::Backend, "en", "nova-3"));
+        assert_eq!(models[1], entry(SttProviderType::Backend, "ru", "nova-2"));
+
+        let errors = UpdateSttConfigArgs {
+            language_models: Some(vec![
+                entry(SttProviderType::Deepgram, "ar", "nova-2"),
+                entry(SttProviderType::Deepgram, "de", "large"),
+                entry(SttProviderType::Deepgram, "xx", "nova-3"),
+            ]),
+            ..args("backend", "ru")
+        }
+        .validate()
+        .unwrap_err();
+        let failure = errors.to_failure(crate::presentation::i18n::UiLanguage::En);
+        let codes: Vec<_> = failure.errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, vec!["model-unsupported-for-language", "field-unsupported-value", "field-unsupported-value"]);
+        assert!(failure.errors.iter().all(|e| e.field == "languageModels"));
+    }
+
+    #[test]
+    fn audio_chunking_is_checked_against_provider_range() {
+        let entry = |provider, chunk_ms| AudioChunkingOverride { provider, chunk_ms };
+        let request = UpdateSttConfigArgs {
+            audio_chunking: Some(vec![
+                entry(SttProviderType::Deepgram, 100),
+                entry(SttProviderType::Backend, 30),
+                entry(SttProviderType::Deepgram, 250),
+            ]),
+            ..args("backend", "ru")
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(
+            request.audio_chunking.unwrap(),
+            vec![entry(SttProviderType::Backend, 30), entry(SttProviderType::Deepgram, 250)]
+        );
+
+        // AssemblyAI не принимает сообщения короче 50 мс
+        let errors = UpdateSttConfigArgs {
+            audio_chunking: Some(vec![entry(SttProviderType::AssemblyAI, 30), entry(SttProviderType::Backend, 5000)]),
+            ..args("backend", "ru")
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(errors.fields(), vec!["audioChunking", "audioChunking"]);
+    }
+
+    #[test]
+    fn every_invalid_field_is_reported() {
+        let errors = UpdateSttConfigArgs {
+            model: Some("gpt".to_string()),
+            audio_encoding: Some("mp3".to_string()),
+            max_alternatives: Some(MAX_TRANSCRIPTION_ALTERNATIVES + 1),
+            batch_concurrency: Some(0),
+            short_clip_secs: Some(MAX_SHORT_CLIP_SECS + 1),
+            keep_alive_ttl_secs: Some(1),
+            partial_stability: Some(MAX_PARTIAL_STABILITY + 1),
+            pre_roll_ms: Some(MAX_PRE_ROLL_MS + 1),
+            paragraph_pause_ms: Some(MAX_PARAGRAPH_PAUSE_MS + 1),
+            whisper: Some(WhisperDecodingConfig {
+                initial_prompt: Some("а".repeat(MAX_WHISPER_PROMPT_CHARS + 1)),
+                temperature: 1.5,
+                tempo: 0.5,
+                ..Default::default()
+            }),
+            ..args("watson", "")
+        }
+        .validate()
+        .unwrap_err();
+
+        assert_eq!(
+            errors.fields(),
+            vec![
+                "provider",
+                "language",
+                "model",
+                "audioEncoding",
+                "maxAlternatives",
+                "batchConcurrency",
+                "shortClipSecs",
+                "keepAliveTtlSecs",
+                "partialStability",
+                "preRollMs",
+                "paragraphPauseMs",
+                "whisper.temperature",
+                "whisper.tempo",
+                "whisper.initialPrompt",
+            ]
+        );
+    }
+}
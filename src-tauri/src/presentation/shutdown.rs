@@ -0,0 +1,156 @@
+//! Корректный выход из приложения (трей "Выход", quit_app, Cmd+Q).
+//!
+//! Выход во время записи раньше терял хвост сессии. Теперь выход идёт по этапам:
+//! остановка захвата → финальные фразы от провайдера (с таймаутом) → встреча и состояние на диск → exit.
+//! Каждый этап ограничен по времени, а весь выход страхуется общим таймаутом.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::domain::RecordingStatus;
+use crate::infrastructure::ConfigStore;
+use crate::presentation::captions::end_live_captions;
+use crate::presentation::meeting::end_meeting_and_save;
+use crate::presentation::overlay::hide_recording_overlay;
+use crate::presentation::{
+    AppState, RecordingStatusPayload, ShutdownPayload, ShutdownStage, EVENT_APP_SHUTDOWN, EVENT_RECORDING_STATUS,
+};
+
+/// Сколько ждём выхода из Starting/Processing, прежде чем останавливать запись
+const TRANSITION_TIMEOUT: Duration = Duration::from_secs(5);
+/// Остановка провайдера вместе с ожиданием финальных результатов
+const STOP_RECORDING_TIMEOUT: Duration = Duration::from_secs(8);
+/// Колбэки финальных фраз пишут историю/встречу в отдельных задачах — даём им отработать
+const FINALS_SETTLE: Duration = Duration::from_millis(300);
+const PERSIST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Что бы ни зависло — приложение всё равно закроется
+const SHUTDOWN_WATCHDOG: Duration = Duration::from_secs(20);
+
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Начинает корректный выход. Повторные вызовы во время выхода ничего не делают.
+pub fn request_shutdown(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        app_handle.exit(0);
+        return;
+    };
+    if state.recording.shutting_down.swap(true, Ordering::SeqCst) {
+        log::debug!("Shutdown already in progress");
+        return;
+    }
+    log::info!("Graceful shutdown requested");
+
+    let watchdog_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SHUTDOWN_WATCHDOG).await;
+        log::error!("Graceful shutdown timed out, exiting anyway");
+        watchdog_handle.exit(0);
+    });
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        run_shutdown(&app_handle).await;
+        emit_stage(&app_handle, ShutdownStage::Exiting);
+        log::info!("Graceful shutdown finished, exiting");
+        app_handle.exit(0);
+    });
+}
+
+fn emit_stage(app_handle: &AppHandle, stage: ShutdownStage) {
+    log::info!("Shutdown stage: {:?}", stage);
+    let _ = app_handle.emit(EVENT_APP_SHUTDOWN, ShutdownPayload { stage });
+}
+
+async fn run_shutdown(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+
+    emit_stage(app_handle, ShutdownStage::StoppingRecording);
+    stop_active_recording(app_handle, &state).await;
+
+    emit_stage(app_handle, ShutdownStage::Persisting);
+    hide_recording_overlay(app_handle);
+    end_live_captions(app_handle).await;
+    if tokio::time::timeout(PERSIST_TIMEOUT, end_meeting_and_save(app_handle))
+        .await
+        .is_err()
+    {
+        log::warn!("Saving meeting transcript timed out during shutdown");
+    }
+    if tokio::time::timeout(PERSIST_TIMEOUT, persist_state(&state)).await.is_err() {
+        log::warn!("Persisting state timed out during shutdown");
+    }
+}
+
+/// Дожидается конца Starting/Processing и останавливает запись, получая финальные фразы
+async fn stop_active_recording(app_handle: &AppHandle, state: &AppState) {
+    let service = &state.recording.transcription_service;
+
+    let deadline = tokio::time::Instant::now() + TRANSITION_TIMEOUT;
+    let mut status = service.get_status().await;
+    while matches!(status, RecordingStatus::Starting | RecordingStatus::Processing)
+        && tokio::time::Instant::now() < deadline
+    {
+        tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+        status = service.get_status().await;
+    }
+
+    if status != RecordingStatus::Recording {
+        log::info!("No active recording at shutdown (status: {:?})", status);
+        return;
+    }
+
+    log::info!("Stopping active recording before exit");
+    match tokio::time::timeout(STOP_RECORDING_TIMEOUT, service.stop_recording_hard()).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => log::warn!("Failed to stop recording during shutdown: {}", e),
+        Err(_) => log::warn!("Stopping recording timed out during shutdown, the session tail may be lost"),
+    }
+    tokio::time::sleep(FINALS_SETTLE).await;
+
+    let session_id = state.active_transcription_session_id.load(Ordering::Relaxed);
+    let _ = app_handle.emit(
+        EVENT_RECORDING_STATUS,
+        RecordingStatusPayload {
+            session_id,
+            status: RecordingStatus::Idle,
+            stopped_via_hotkey: false,
+            window_behavior: None,
+        },
+    );
+}
+
+/// Всё, что обычно сохраняется по ходу работы, — ещё раз, с последними изменениями
+async fn persist_state(state: &AppState) {
+    let config = state.settings.config.read().await.clone();
+    if let Err(e) = ConfigStore::save_app_config(&config).await {
+        log::warn!("Failed to save app config on shutdown: {}", e);
+    }
+
+    let stats = state.user_data.session_stats.read().await.clone();
+    if let Err(e) = ConfigStore::save_session_stats(&stats).await {
+        log::warn!("Failed to save session stats on shutdown: {}", e);
+    }
+
+    if config.keep_history {
+        let history = state.user_data.history.read().await.clone();
+        if let Err(e) = ConfigStore::save_history(&history).await {
+            log::warn!("Failed to save history on shutdown: {}", e);
+        }
+    }
+
+    let audit = state.user_data.paste_audit.read().await.clone();
+    if let Err(e) = ConfigStore::save_paste_audit(&audit).await {
+        log::warn!("Failed to save paste audit log on shutdown: {}", e);
+    }
+
+    if config.telemetry_enabled {
+        let queue = state.integrations.telemetry_queue.read().await.clone();
+        if let Err(e) = ConfigStore::save_telemetry_queue(&queue).await {
+            log::warn!("Failed to save telemetry queue on shutdown: {}", e);
+        }
+    }
+}
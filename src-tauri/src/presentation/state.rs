@@ -1,14 +1,23 @@
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use tokio::sync::RwLock;
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::application::TranscriptionService;
-use crate::domain::{AppConfig, Transcription, AudioCapture, UiPreferences};
+use crate::application::{
+    CorrectionEngine, FeedbackService, MeetingRecorder, PipelineStage, PipelineTracer, TranscriptionService,
+};
+use crate::domain::{
+    AppConfig, AppContext, Transcription, AudioCapture, CalendarConfig, CalendarProvider, GlossaryStatus, HistoryItem, SessionCalendarTag,
+    PasteAuditEntry, SessionStats, TelemetryEvent, TranscriptionJob, UiPreferences,
+};
+use crate::presentation::captions::LiveCaptionsSession;
+use crate::presentation::i18n::{UiLanguage, UiMessage};
+use crate::presentation::toggle_intent::ToggleIntentQueue;
+use crate::infrastructure::companion::CompanionHub;
 use crate::infrastructure::{
-    audio::{SystemAudioCapture, VadCaptureWrapper, VadProcessor},
+    audio::{FileAudioCapture, SystemAudioCapture, VadActivity, VadCaptureWrapper, VadProcessor},
     AuthSession, AuthStore, AuthStoreData, AuthUser, ConfigStore,
-    DefaultSttProviderFactory,
+    create_punctuator, DefaultSttProviderFactory, FileAudioSpool, FileCorrectionStore, FileSessionJournal, SoundFeedback,
 };
 
 /// State for microphone testing
@@ -17,8 +26,12 @@ pub struct MicrophoneTestState {
     pub capture: Option<Box<dyn AudioCapture>>,
     /// Shared buffer of recorded samples during test
     pub buffer: Arc<tokio::sync::Mutex<Vec<i16>>>,
+    /// Тот же звук до усиления — вход для preview_processing_chain (живёт до следующего теста)
+    pub raw_buffer: Arc<tokio::sync::Mutex<Vec<i16>>>,
     /// Is test currently running
     pub is_testing: bool,
+    /// Флаг отмены текущего воспроизведения записи теста (play_microphone_test)
+    pub playback_cancel: Option<Arc<AtomicBool>>,
 }
 
 impl Default for MicrophoneTestState {
@@ -26,54 +39,214 @@ impl Default for MicrophoneTestState {
         Self {
             capture: None,
             buffer: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            raw_buffer: Arc::new(tokio::sync::Mutex::new(Vec::new())),
             is_testing: false,
+            playback_cancel: None,
         }
     }
 }
 
-/// Global application state managed by Tauri
+/// State of the running accuracy self-test recording
+#[derive(Default)]
+pub struct AccuracyTestState {
+    pub capture: Option<Box<dyn AudioCapture>>,
+    /// Записанный звук (16kHz mono, с усилением как при обычной записи)
+    pub buffer: Arc<tokio::sync::Mutex<Vec<i16>>>,
+    /// Какой текст читает пользователь; Some — тест идёт
+    pub script_id: Option<String>,
+}
+
+/// Final transcription held back because of low confidence, waiting for user review
+#[derive(Debug, Clone)]
+pub struct HeldTranscription {
+    pub id: u64,
+    pub session_id: u64,
+    pub transcription: Transcription,
+}
+
+/// Recording pipeline: transcription service, live session results and recording-like modes
 ///
-/// This state is shared across all Tauri commands and can be accessed
-/// using State<AppState> parameter in command functions
-pub struct AppState {
+/// Узкие состояния — части AppState и одновременно отдельные managed state Tauri (клоны тех же Arc).
+/// Команда, которой нужна только история, берёт `State<'_, HistoryState>` и не видит сервис записи
+/// и конфиг — не может ни захватить их блокировки, ни случайно держать две сразу.
+#[derive(Clone)]
+pub struct RecordingState {
     /// Main transcription service
     pub transcription_service: Arc<TranscriptionService>,
 
+    /// Latest partial transcription
+    pub partial_transcription: Arc<RwLock<Option<String>>>,
+
+    /// Latest final transcription
+    pub final_transcription: Arc<RwLock<Option<String>>>,
+
+    /// Финальные фразы с низкой уверенностью, отложенные до решения пользователя
+    pub held_transcriptions: Arc<RwLock<Vec<HeldTranscription>>>,
+
+    /// Microphone test state
+    pub microphone_test: Arc<RwLock<MicrophoneTestState>>,
+
+    /// Запись теста точности (чтение эталонного текста)
+    pub accuracy_test: Arc<RwLock<AccuracyTestState>>,
+
+    /// Задачи расшифровки аудиофайлов (сохраняются на диск после каждой главы)
+    pub transcription_jobs: Arc<RwLock<Vec<TranscriptionJob>>>,
+
+    /// Воркер очереди задач запущен (задачи выполняются по одной)
+    pub transcription_job_worker: Arc<AtomicBool>,
+
+    /// Активный режим живых субтитров (None — выключен)
+    pub live_captions: Arc<RwLock<Option<LiveCaptionsSession>>>,
+
+    /// Активная встреча (None — обычная диктовка).
+    /// std Mutex: финальные фразы пишутся прямо в callback провайдера, до остановки записи
+    pub meeting: Arc<std::sync::Mutex<Option<MeetingRecorder>>>,
+
+    /// Идёт выход из приложения (quit_app): новые записи не начинаем
+    pub shutting_down: Arc<AtomicBool>,
+}
+
+impl RecordingState {
+    fn new(transcription_service: Arc<TranscriptionService>) -> Self {
+        Self {
+            transcription_service,
+            partial_transcription: Arc::new(RwLock::new(None)),
+            final_transcription: Arc::new(RwLock::new(None)),
+            held_transcriptions: Arc::new(RwLock::new(Vec::new())),
+            microphone_test: Arc::new(RwLock::new(MicrophoneTestState::default())),
+            accuracy_test: Arc::new(RwLock::new(AccuracyTestState::default())),
+            transcription_jobs: Arc::new(RwLock::new(Vec::new())),
+            transcription_job_worker: Arc::new(AtomicBool::new(false)),
+            live_captions: Arc::new(RwLock::new(None)),
+            meeting: Arc::new(std::sync::Mutex::new(None)),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Идёт ли сейчас встреча (режим встречи включён)
+    pub fn meeting_active(&self) -> bool {
+        self.meeting.lock().map(|meeting| meeting.is_some()).unwrap_or(false)
+    }
+}
+
+/// App/STT configuration, UI preferences and their state-sync revisions
+#[derive(Clone)]
+pub struct ConfigState {
     /// Application configuration
     pub config: Arc<RwLock<AppConfig>>,
 
     /// Per-topic ревизии для state-sync протокола (монотонно растут)
     pub app_config_revision: Arc<RwLock<u64>>,
     pub stt_config_revision: Arc<RwLock<u64>>,
-    pub auth_state_revision: Arc<RwLock<u64>>,
     pub ui_preferences_revision: Arc<RwLock<u64>>,
 
     /// UI-настройки (тема, локаль)
     pub ui_preferences: Arc<RwLock<UiPreferences>>,
+}
 
-    /// Transcription history
-    pub history: Arc<RwLock<Vec<Transcription>>>,
+impl ConfigState {
+    fn new(config: AppConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            app_config_revision: Arc::new(RwLock::new(0)),
+            stt_config_revision: Arc::new(RwLock::new(0)),
+            ui_preferences_revision: Arc::new(RwLock::new(0)),
+            ui_preferences: Arc::new(RwLock::new(UiPreferences::default())),
+        }
+    }
 
-    /// Latest partial transcription
-    pub partial_transcription: Arc<RwLock<Option<String>>>,
+    /// Язык интерфейса для строк, которые бэкенд отдаёт во frontend
+    pub async fn ui_language(&self) -> UiLanguage {
+        UiLanguage::from_locale(&self.ui_preferences.read().await.locale)
+    }
 
-    /// Latest final transcription
-    pub final_transcription: Arc<RwLock<Option<String>>>,
+    /// Текст сообщения на языке интерфейса
+    pub async fn localize(&self, message: UiMessage) -> String {
+        message.text(self.ui_language().await)
+    }
+}
 
-    /// Microphone test state
-    pub microphone_test: Arc<RwLock<MicrophoneTestState>>,
+/// Persisted user data: dictation history, session stats, paste audit, hotkey activity
+#[derive(Clone, Default)]
+pub struct HistoryState {
+    /// Transcription history (с метками и избранным; сохраняется на диск, если включён keep_history)
+    pub history: Arc<RwLock<Vec<HistoryItem>>>,
+
+    /// Статистика завершённых сессий записи (сохраняется на диск для аналитики)
+    pub session_stats: Arc<RwLock<Vec<SessionStats>>>,
+
+    /// Журнал автовставок (auto-paste / typing), сохраняется на диск
+    pub paste_audit: Arc<RwLock<Vec<PasteAuditEntry>>>,
+
+    /// Нажатия хоткея записи (unix ms) за последние дни — для прогрева соединения (warm pool)
+    pub hotkey_activity: Arc<RwLock<Vec<i64>>>,
+}
+
+/// Integrations with the outside world: focused app, output profiles, companion, calendar, telemetry
+#[derive(Clone, Default)]
+pub struct IntegrationState {
+    /// Bundle ID последнего активного приложения (перед показом VoicetextAI окна)
+    /// Используется для автоматической вставки текста в правильное окно
+    pub last_focused_app_bundle_id: Arc<RwLock<Option<String>>>,
+
+    /// Профиль доставки текста только для текущей записи (deep link voicetotext://record?profile=..)
+    pub output_profile_override: Arc<RwLock<Option<String>>>,
+
+    /// Companion mode: трансляция partial/final на второе устройство в LAN
+    pub companion: Arc<CompanionHub>,
+
+    /// Источник календаря для текущих настроек (кэш .ics живёт внутри)
+    pub calendar_provider: Arc<RwLock<Option<(CalendarConfig, Arc<dyn CalendarProvider>)>>>,
+
+    /// Встреча из календаря для сессии записи: (session_id, название и метки)
+    pub session_calendar: Arc<RwLock<Option<(u64, SessionCalendarTag)>>>,
+
+    /// Офлайн-очередь анонимной телеметрии (пусто, если телеметрия выключена)
+    pub telemetry_queue: Arc<RwLock<Vec<TelemetryEvent>>>,
+}
+
+/// Global application state managed by Tauri
+///
+/// This state is shared across all Tauri commands and can be accessed
+/// using State<AppState> parameter in command functions.
+/// Новым командам лучше брать узкое состояние (RecordingState, ConfigState, HistoryState,
+/// IntegrationState) — каждое из них Tauri управляет отдельно, а здесь лежит та же копия.
+pub struct AppState {
+    /// Запись: сервис распознавания, результаты текущей сессии, тесты микрофона и режимы записи
+    pub recording: RecordingState,
+
+    /// Конфигурация приложения и UI-настройки с их ревизиями
+    pub settings: ConfigState,
+
+    /// История, статистика сессий и другие пользовательские данные
+    pub user_data: HistoryState,
+
+    /// Фокус, профили вывода, companion, календарь, телеметрия
+    pub integrations: IntegrationState,
+
+    /// Ревизия auth-state topic для state-sync протокола
+    pub auth_state_revision: Arc<RwLock<u64>>,
+
+    /// Синхронизация общего словаря команды (get_glossary_status)
+    pub glossary_status: Arc<RwLock<GlossaryStatus>>,
 
     /// Receiver для VAD silence timeout событий
     /// Используется в setup для установки обработчика
     pub vad_timeout_tx: tokio::sync::mpsc::UnboundedSender<()>,
     pub vad_timeout_rx: Arc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<()>>>,
 
+    /// Решения VAD для UI (vad:activity): wrapper пишет в channel, forwarder эмитит события
+    pub vad_activity_tx: tokio::sync::mpsc::UnboundedSender<VadActivity>,
+    pub vad_activity_rx: Arc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<VadActivity>>>,
+
     /// VAD timeout handler task (для перезапуска при смене устройства)
     vad_handler_task: Arc<RwLock<Option<tauri::async_runtime::JoinHandle<()>>>>,
 
-    /// Bundle ID последнего активного приложения (перед показом VoicetextAI окна)
-    /// Используется для автоматической вставки текста в правильное окно
-    pub last_focused_app_bundle_id: Arc<RwLock<Option<String>>>,
+    /// Приложение и окно, активные перед показом окна VoicetextAI (контекст записи по хоткею)
+    pub last_focused_app: Arc<RwLock<Option<AppContext>>>,
+
+    /// Приложение, в котором начата сессия записи: (session_id, контекст). Только при capture_app_context
+    pub session_app_context: Arc<RwLock<Option<(u64, AppContext)>>>,
 
     /// Флаг авторизации пользователя (синхронизируется из frontend)
     /// Используется для определения какое окно показывать при нажатии hotkey
@@ -99,45 +272,59 @@ pub struct AppState {
     /// Нужен из‑за key repeat / случайных двойных срабатываний, которые выглядят как "мигание" окна.
     pub last_recording_hotkey_ms: AtomicU64,
 
+    /// Нажатие хоткея во время Starting/Processing, выполняемое по окончании перехода
+    pub toggle_intent: std::sync::Mutex<ToggleIntentQueue>,
+
+    /// Сеть доступна (монитор связи); без сети облачные провайдеры подменяются локальным Whisper
+    pub network_online: Arc<AtomicBool>,
+
     /// Счётчик сессий записи. Нужен, чтобы маркировать события transcription:* и не смешивать сессии.
     pub transcription_session_seq: AtomicU64,
 
     /// Активная (последняя запущенная) сессия записи.
     /// Используется для маркировки статусов Idle/Error, которые эмитятся "в обход" start_recording callbacks.
     pub active_transcription_session_id: AtomicU64,
+
+    /// Приложение запущено автозапуском и должно остаться в трее:
+    /// первый show_recording_window после старта пропускается
+    pub launched_minimized: AtomicBool,
+
+    /// Окно записи ждёт вставки текста, чтобы скрыться (window_behavior_on_stop = hide_after_paste)
+    pub hide_window_after_paste: AtomicBool,
+
+    /// Звуки (и в будущем вибро) на старт/остановку/ошибку записи
+    pub feedback: Arc<FeedbackService>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         // Initialize real audio capture with VAD
-        let system_audio = match SystemAudioCapture::new() {
+        let system_audio = match Self::create_input_capture(None, None) {
             Ok(capture) => capture,
             Err(e) => {
                 log::error!("Failed to initialize system audio: {}. Using mock.", e);
                 // Fallback to mock if no audio device
                 let mock = crate::infrastructure::audio::MockAudioCapture::new();
-                let stt_factory = Arc::new(DefaultSttProviderFactory::new());
-                let service = Arc::new(TranscriptionService::new(Box::new(mock), stt_factory));
+                let service = Self::create_transcription_service(Box::new(mock), Arc::new(PipelineTracer::new()));
 
                 // Создаем dummy channel для VAD (не будет использоваться с mock)
                 let (vad_tx, vad_rx) = tokio::sync::mpsc::unbounded_channel();
+                let (activity_tx, activity_rx) = tokio::sync::mpsc::unbounded_channel();
 
                 return Self {
-                    transcription_service: service,
-                    config: Arc::new(RwLock::new(AppConfig::default())),
-                    app_config_revision: Arc::new(RwLock::new(0)),
-                    stt_config_revision: Arc::new(RwLock::new(0)),
+                    recording: RecordingState::new(service),
+                    settings: ConfigState::new(AppConfig::default()),
+                    user_data: HistoryState::default(),
+                    integrations: IntegrationState::default(),
                     auth_state_revision: Arc::new(RwLock::new(0)),
-                    ui_preferences_revision: Arc::new(RwLock::new(0)),
-                    ui_preferences: Arc::new(RwLock::new(UiPreferences::default())),
-                    history: Arc::new(RwLock::new(Vec::new())),
-                    partial_transcription: Arc::new(RwLock::new(None)),
-                    final_transcription: Arc::new(RwLock::new(None)),
-                    microphone_test: Arc::new(RwLock::new(MicrophoneTestState::default())),
+                    glossary_status: Arc::new(RwLock::new(GlossaryStatus::default())),
                     vad_timeout_tx: vad_tx,
                     vad_timeout_rx: Arc::new(tokio::sync::Mutex::new(vad_rx)),
+                    vad_activity_tx: activity_tx,
+                    vad_activity_rx: Arc::new(tokio::sync::Mutex::new(activity_rx)),
                     vad_handler_task: Arc::new(RwLock::new(None)),
-                    last_focused_app_bundle_id: Arc::new(RwLock::new(None)),
+                    last_focused_app: Arc::new(RwLock::new(None)),
+                    session_app_context: Arc::new(RwLock::new(None)),
                     is_authenticated: Arc::new(RwLock::new(false)),
                     auth_store: Arc::new(RwLock::new(AuthStoreData {
                         device_id: format!("desktop-{}", uuid::Uuid::new_v4()),
@@ -147,8 +334,13 @@ impl AppState {
                     auth_refresh_task: Arc::new(RwLock::new(None)),
                     auth_refresh_task_guard: Arc::new(tokio::sync::Mutex::new(())),
                     last_recording_hotkey_ms: AtomicU64::new(0),
+                    toggle_intent: std::sync::Mutex::new(ToggleIntentQueue::default()),
+                    network_online: Arc::new(AtomicBool::new(true)),
                     transcription_session_seq: AtomicU64::new(0),
                     active_transcription_session_id: AtomicU64::new(0),
+                    launched_minimized: AtomicBool::new(false),
+                    hide_window_after_paste: AtomicBool::new(false),
+                    feedback: Self::create_feedback_service(),
                 };
             }
         };
@@ -160,28 +352,26 @@ impl AppState {
             Err(e) => {
                 log::error!("Failed to initialize VAD: {}. Proceeding without VAD.", e);
                 // Fallback: use system audio without VAD
-                let stt_factory = Arc::new(DefaultSttProviderFactory::new());
-                let service = Arc::new(TranscriptionService::new(Box::new(system_audio), stt_factory));
+                let service = Self::create_transcription_service(system_audio, Arc::new(PipelineTracer::new()));
 
                 // Создаем dummy channel для VAD (не будет использоваться без VAD)
                 let (vad_tx, vad_rx) = tokio::sync::mpsc::unbounded_channel();
+                let (activity_tx, activity_rx) = tokio::sync::mpsc::unbounded_channel();
 
                 return Self {
-                    transcription_service: service,
-                    config: Arc::new(RwLock::new(app_config)),
-                    app_config_revision: Arc::new(RwLock::new(0)),
-                    stt_config_revision: Arc::new(RwLock::new(0)),
+                    recording: RecordingState::new(service),
+                    settings: ConfigState::new(app_config),
+                    user_data: HistoryState::default(),
+                    integrations: IntegrationState::default(),
                     auth_state_revision: Arc::new(RwLock::new(0)),
-                    ui_preferences_revision: Arc::new(RwLock::new(0)),
-                    ui_preferences: Arc::new(RwLock::new(UiPreferences::default())),
-                    history: Arc::new(RwLock::new(Vec::new())),
-                    partial_transcription: Arc::new(RwLock::new(None)),
-                    final_transcription: Arc::new(RwLock::new(None)),
-                    microphone_test: Arc::new(RwLock::new(MicrophoneTestState::default())),
+                    glossary_status: Arc::new(RwLock::new(GlossaryStatus::default())),
                     vad_timeout_tx: vad_tx,
                     vad_timeout_rx: Arc::new(tokio::sync::Mutex::new(vad_rx)),
+                    vad_activity_tx: activity_tx,
+                    vad_activity_rx: Arc::new(tokio::sync::Mutex::new(activity_rx)),
                     vad_handler_task: Arc::new(RwLock::new(None)),
-                    last_focused_app_bundle_id: Arc::new(RwLock::new(None)),
+                    last_focused_app: Arc::new(RwLock::new(None)),
+                    session_app_context: Arc::new(RwLock::new(None)),
                     is_authenticated: Arc::new(RwLock::new(false)),
                     auth_store: Arc::new(RwLock::new(AuthStoreData {
                         device_id: format!("desktop-{}", uuid::Uuid::new_v4()),
@@ -191,8 +381,13 @@ impl AppState {
                     auth_refresh_task: Arc::new(RwLock::new(None)),
                     auth_refresh_task_guard: Arc::new(tokio::sync::Mutex::new(())),
                     last_recording_hotkey_ms: AtomicU64::new(0),
+                    toggle_intent: std::sync::Mutex::new(ToggleIntentQueue::default()),
+                    network_online: Arc::new(AtomicBool::new(true)),
                     transcription_session_seq: AtomicU64::new(0),
                     active_transcription_session_id: AtomicU64::new(0),
+                    launched_minimized: AtomicBool::new(false),
+                    hide_window_after_paste: AtomicBool::new(false),
+                    feedback: Self::create_feedback_service(),
                 };
             }
         };
@@ -201,7 +396,7 @@ impl AppState {
         let (vad_tx, vad_rx) = tokio::sync::mpsc::unbounded_channel();
 
         // Wrap system audio with VAD
-        let mut vad_wrapper = VadCaptureWrapper::new(Box::new(system_audio), vad);
+        let mut vad_wrapper = VadCaptureWrapper::new(system_audio, vad);
 
         // Устанавливаем callback который отправляет событие в channel
         let vad_tx_for_cb = vad_tx.clone();
@@ -210,30 +405,41 @@ impl AppState {
             let _ = vad_tx_for_cb.send(());
         }));
 
+        // Решения VAD уходят в UI через отдельный channel (см. start_vad_activity_forwarder)
+        let (activity_tx, activity_rx) = tokio::sync::mpsc::unbounded_channel();
+        let activity_tx_for_cb = activity_tx.clone();
+        vad_wrapper.set_activity_callback(Arc::new(move |activity| {
+            let _ = activity_tx_for_cb.send(activity);
+        }));
+
+        // Время VAD пишется в трассировку конвейера сервиса (get_pipeline_trace)
+        let pipeline = Arc::new(PipelineTracer::new());
+        let pipeline_for_cb = pipeline.clone();
+        vad_wrapper.set_timing_callback(Arc::new(move |elapsed| {
+            pipeline_for_cb.record(PipelineStage::Vad, elapsed);
+        }));
+
         let audio_capture = Box::new(vad_wrapper);
-        let stt_factory = Arc::new(DefaultSttProviderFactory::new());
 
-        let transcription_service = Arc::new(TranscriptionService::new(audio_capture, stt_factory));
+        let transcription_service = Self::create_transcription_service(audio_capture, pipeline);
 
         log::info!("AppState initialized with SystemAudioCapture + VAD (timeout: {}ms)",
             app_config.vad_silence_timeout_ms);
 
         Self {
-            transcription_service,
-            config: Arc::new(RwLock::new(app_config)),
-            app_config_revision: Arc::new(RwLock::new(0)),
-            stt_config_revision: Arc::new(RwLock::new(0)),
+            recording: RecordingState::new(transcription_service),
+            settings: ConfigState::new(app_config),
+            user_data: HistoryState::default(),
+            integrations: IntegrationState::default(),
             auth_state_revision: Arc::new(RwLock::new(0)),
-            ui_preferences_revision: Arc::new(RwLock::new(0)),
-            ui_preferences: Arc::new(RwLock::new(UiPreferences::default())),
-            history: Arc::new(RwLock::new(Vec::new())),
-            partial_transcription: Arc::new(RwLock::new(None)),
-            final_transcription: Arc::new(RwLock::new(None)),
-            microphone_test: Arc::new(RwLock::new(MicrophoneTestState::default())),
+            glossary_status: Arc::new(RwLock::new(GlossaryStatus::default())),
             vad_timeout_tx: vad_tx,
             vad_timeout_rx: Arc::new(tokio::sync::Mutex::new(vad_rx)),
+            vad_activity_tx: activity_tx,
+            vad_activity_rx: Arc::new(tokio::sync::Mutex::new(activity_rx)),
             vad_handler_task: Arc::new(RwLock::new(None)),
-            last_focused_app_bundle_id: Arc::new(RwLock::new(None)),
+            last_focused_app: Arc::new(RwLock::new(None)),
+            session_app_context: Arc::new(RwLock::new(None)),
             is_authenticated: Arc::new(RwLock::new(false)),
             auth_store: Arc::new(RwLock::new(AuthStoreData {
                 device_id: format!("desktop-{}", uuid::Uuid::new_v4()),
@@ -243,9 +449,95 @@ impl AppState {
             auth_refresh_task: Arc::new(RwLock::new(None)),
             auth_refresh_task_guard: Arc::new(tokio::sync::Mutex::new(())),
             last_recording_hotkey_ms: AtomicU64::new(0),
+            toggle_intent: std::sync::Mutex::new(ToggleIntentQueue::default()),
+            network_online: Arc::new(AtomicBool::new(true)),
             transcription_session_seq: AtomicU64::new(0),
             active_transcription_session_id: AtomicU64::new(0),
+            launched_minimized: AtomicBool::new(false),
+            hide_window_after_paste: AtomicBool::new(false),
+            feedback: Self::create_feedback_service(),
+        }
+    }
+
+    /// Создаёт TranscriptionService с журналом незавершённой записи (crash recovery).
+    ///
+    /// Журнал — best-effort: если директорию не удалось открыть, запись работает и без него.
+    fn create_feedback_service() -> Arc<FeedbackService> {
+        Arc::new(FeedbackService::new(vec![Arc::new(SoundFeedback::new())]))
+    }
+
+    fn create_transcription_service(
+        audio_capture: Box<dyn AudioCapture>,
+        pipeline: Arc<PipelineTracer>,
+    ) -> Arc<TranscriptionService> {
+        let stt_factory = Arc::new(DefaultSttProviderFactory::new());
+        let mut service = TranscriptionService::new(audio_capture, stt_factory).with_pipeline_tracer(pipeline);
+
+        match ConfigStore::corrections_path() {
+            Ok(path) => {
+                let store = Arc::new(FileCorrectionStore::new(path));
+                service = service.with_correction_engine(Arc::new(CorrectionEngine::new(store)));
+            }
+            Err(e) => log::warn!("Corrections dictionary is unavailable: {}", e),
+        }
+
+        if let Some(punctuator) = create_punctuator() {
+            service = service.with_punctuator(punctuator);
+        }
+
+        // Недосланное аудио — запись пользователя: в его папке данных, не в общем temp
+        match ConfigStore::audio_spool_dir().and_then(FileAudioSpool::open) {
+            Ok(spool) => service = service.with_audio_spool(Arc::new(spool)),
+            Err(e) => log::warn!("Audio spool is unavailable (unsent audio will be dropped): {}", e),
+        }
+
+        match ConfigStore::recovery_dir().and_then(FileSessionJournal::open) {
+            Ok(journal) => Arc::new(service.with_session_journal(Arc::new(journal))),
+            Err(e) => {
+                log::warn!("Session journal is unavailable (crash recovery disabled): {}", e);
+                Arc::new(service)
+            }
+        }
+    }
+
+    /// Создаёт источник аудио: системный микрофон или файл из настройки `mock_audio_file`.
+    ///
+    /// Файловый источник нужен для демо и проверки без реального железа.
+    /// Если файл не удалось загрузить (удалён после настройки) — откатываемся на системный микрофон.
+    fn create_input_capture(
+        device_name: Option<String>,
+        mock_audio_file: Option<&str>,
+    ) -> Result<Box<dyn AudioCapture>, String> {
+        if let Some(path) = mock_audio_file {
+            match FileAudioCapture::from_file(path) {
+                Ok(capture) => {
+                    log::warn!("Using mock audio file instead of microphone: {:?}", path);
+                    return Ok(Box::new(capture));
+                }
+                Err(e) => {
+                    log::error!("Failed to load mock audio file {:?}: {}. Using system audio.", path, e);
+                }
+            }
         }
+
+        SystemAudioCapture::with_device(device_name.clone())
+            .map(|capture| Box::new(capture) as Box<dyn AudioCapture>)
+            .map_err(|e| format!("Failed to create audio capture with device {:?}: {}", device_name, e))
+    }
+
+    /// Идёт ли сейчас встреча (режим встречи включён)
+    pub fn meeting_active(&self) -> bool {
+        self.recording.meeting_active()
+    }
+
+    /// Язык интерфейса для строк, которые бэкенд отдаёт во frontend
+    pub async fn ui_language(&self) -> UiLanguage {
+        self.settings.ui_language().await
+    }
+
+    /// Текст сообщения на языке интерфейса
+    pub async fn localize(&self, message: UiMessage) -> String {
+        self.settings.localize(message).await
     }
 
     /// Инкрементирует ревизию и возвращает её строковое представление
@@ -255,7 +547,7 @@ impl AppState {
         rev.to_string()
     }
 
-    fn get_api_base_url() -> String {
+    pub(crate) fn get_api_base_url() -> String {
         std::env::var("VOICE_TO_TEXT_API_URL")
             .unwrap_or_else(|_| "https://api.voicetext.site".to_string())
     }
@@ -270,7 +562,7 @@ impl AppState {
         // Best-effort: ошибки не должны блокировать UX, но они важны для диагностики.
         // Важно: берём текущий in-memory config, чтобы не "сбрасывать" keep-alive и другие поля
         // при конкурирующих disk-write сценариях.
-        let mut config = self.transcription_service.get_config().await;
+        let mut config = self.recording.transcription_service.get_config().await;
         if config.backend_auth_token == token {
             return;
         }
@@ -278,7 +570,7 @@ impl AppState {
         if let Err(e) = ConfigStore::save_config(&config).await {
             log::warn!("Failed to persist STT config token: {}", e);
         }
-        if let Err(e) = self.transcription_service.update_config(config).await {
+        if let Err(e) = self.recording.transcription_service.update_config(config).await {
             log::warn!("Failed to update transcription service config token: {}", e);
         }
     }
@@ -330,7 +622,7 @@ impl AppState {
         let auth_state_revision = self.auth_state_revision.clone();
         let auth_session_revision = self.auth_session_revision.clone();
         let app_handle_for_task = app_handle.clone();
-        let service_for_task = self.transcription_service.clone();
+        let service_for_task = self.recording.transcription_service.clone();
 
         let task = tauri::async_runtime::spawn(async move {
             const REFRESH_BUFFER_MS: i64 = 2 * 60 * 1000; // 2 minutes before access expiry
@@ -595,7 +887,8 @@ impl AppState {
     /// Запускает обработчик VAD timeout событий (вызывается из setup)
     /// Слушает channel и автоматически останавливает запись
     pub fn start_vad_timeout_handler(&self, app_handle: tauri::AppHandle) {
-        let service = self.transcription_service.clone();
+        let service = self.recording.transcription_service.clone();
+        let feedback = self.feedback.clone();
         let rx = self.vad_timeout_rx.clone();
 
         let handle = tauri::async_runtime::spawn(async move {
@@ -604,6 +897,25 @@ impl AppState {
             while let Some(_) = rx_guard.recv().await {
                 log::info!("VAD silence timeout detected - auto-stopping recording");
 
+                // Субтитры идут непрерывно: пауза в видео/звонке не должна их останавливать
+                let captions_active = match app_handle.try_state::<AppState>() {
+                    Some(state) => state.recording.live_captions.read().await.is_some(),
+                    None => false,
+                };
+                if captions_active {
+                    log::debug!("VAD timeout ignored - live captions active");
+                    continue;
+                }
+
+                // Встреча — одна длинная сессия: паузы в разговоре становятся границами тем
+                let meeting_active = app_handle
+                    .try_state::<AppState>()
+                    .is_some_and(|state| state.meeting_active());
+                if meeting_active {
+                    log::debug!("VAD timeout ignored - meeting mode active");
+                    continue;
+                }
+
                 // Проверяем что действительно идет запись
                 let status = service.get_status().await;
                 if status != crate::domain::RecordingStatus::Recording {
@@ -615,6 +927,8 @@ impl AppState {
                 match service.stop_recording().await {
                     Ok(_) => {
                         log::info!("Recording stopped successfully by VAD timeout");
+                        crate::presentation::overlay::hide_recording_overlay(&app_handle);
+                        feedback.notify(crate::domain::FeedbackEvent::RecordingStopped);
 
                         // Эмитим событие в UI
                         use tauri::Emitter;
@@ -628,11 +942,14 @@ impl AppState {
                                 session_id,
                                 status: crate::domain::RecordingStatus::Idle,
                                 stopped_via_hotkey: false,
+                                window_behavior: None,
                             },
                         );
 
                         // Также эмитим специальное событие VAD timeout (для информирования)
                         let _ = app_handle.emit("vad-silence-timeout", ());
+
+                        crate::presentation::commands::run_queued_toggle(&app_handle, crate::domain::RecordingStatus::Idle);
                     }
                     Err(e) => {
                         log::error!("Failed to stop recording on VAD timeout: {}", e);
@@ -652,6 +969,30 @@ impl AppState {
         log::info!("VAD auto-stop handler started");
     }
 
+    /// Запускает пересылку решений VAD в UI (событие vad:activity, вызывается из setup)
+    pub fn start_vad_activity_forwarder(&self, app_handle: tauri::AppHandle) {
+        let rx = self.vad_activity_rx.clone();
+        let config = self.settings.config.clone();
+
+        tauri::async_runtime::spawn(async move {
+            let mut rx_guard = rx.lock().await;
+
+            while let Some(activity) = rx_guard.recv().await {
+                let Some(state) = app_handle.try_state::<AppState>() else {
+                    continue;
+                };
+                let session_id = state.active_transcription_session_id.load(Ordering::Relaxed);
+                let silence_timeout_ms = config.read().await.vad_silence_timeout_ms;
+                let _ = app_handle.emit(
+                    crate::presentation::events::EVENT_VAD_ACTIVITY,
+                    crate::presentation::events::VadActivityPayload::new(session_id, activity, silence_timeout_ms),
+                );
+            }
+
+            log::warn!("VAD activity forwarder exited");
+        });
+    }
+
     /// Перезапускает VAD timeout handler (используется при смене устройства)
     pub async fn restart_vad_timeout_handler(&self, app_handle: tauri::AppHandle) {
         log::info!("Restarting VAD timeout handler");
@@ -678,19 +1019,21 @@ impl AppState {
     ) -> Result<(), String> {
         log::info!("Recreating audio capture with device: {:?}", device_name);
 
-        // Создаем новый SystemAudioCapture с выбранным устройством
-        let system_audio = SystemAudioCapture::with_device(device_name.clone())
-            .map_err(|e| format!("Failed to create audio capture with device {:?}: {}", device_name, e))?;
+        // Получаем текущий VAD timeout и отладочный файл-источник из конфига
+        let (vad_timeout_ms, mock_audio_file) = {
+            let config = self.settings.config.read().await;
+            (config.vad_silence_timeout_ms, config.mock_audio_file.clone())
+        };
 
-        // Получаем текущий VAD timeout из конфига
-        let vad_timeout_ms = self.config.read().await.vad_silence_timeout_ms;
+        // Создаем новый SystemAudioCapture с выбранным устройством
+        let system_audio = Self::create_input_capture(device_name.clone(), mock_audio_file.as_deref())?;
 
         // Создаем VAD processor
         let vad = VadProcessor::new(Some(vad_timeout_ms), None)
             .map_err(|e| format!("Failed to create VAD processor: {}", e))?;
 
         // Wrap system audio with VAD
-        let mut vad_wrapper = VadCaptureWrapper::new(Box::new(system_audio), vad);
+        let mut vad_wrapper = VadCaptureWrapper::new(system_audio, vad);
 
         // Используем общий VAD timeout sender, чтобы избежать гонок/дедлоков при смене устройства.
         // Receiver слушается единственным обработчиком, а при смене устройства меняется только callback.
@@ -699,9 +1042,17 @@ impl AppState {
             log::info!("VAD silence timeout triggered - sending notification");
             let _ = vad_tx.send(());
         }));
+        let activity_tx = self.vad_activity_tx.clone();
+        vad_wrapper.set_activity_callback(Arc::new(move |activity| {
+            let _ = activity_tx.send(activity);
+        }));
+        let pipeline = self.recording.transcription_service.pipeline_tracer();
+        vad_wrapper.set_timing_callback(Arc::new(move |elapsed| {
+            pipeline.record(PipelineStage::Vad, elapsed);
+        }));
 
         // Заменяем audio capture в TranscriptionService
-        self.transcription_service
+        self.recording.transcription_service
             .replace_audio_capture(Box::new(vad_wrapper))
             .await
             .map_err(|e| format!("Failed to replace audio capture: {}", e))?;
@@ -0,0 +1,103 @@
+//! Анонимная телеметрия (opt-in через AppConfig.telemetry_enabled).
+//!
+//! События копятся в офлайн-очереди (на диске) и раз в полчаса уходят пачками на backend.
+//! Пока телеметрия выключена, события не создаются вовсе; при выключении очередь удаляется.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+use crate::application::{acknowledge_telemetry_batch, enqueue_telemetry, next_telemetry_batch};
+use crate::domain::{AppConfig, TelemetryEvent};
+use crate::infrastructure::telemetry::{send_telemetry_batch, TelemetryBatch};
+use crate::infrastructure::ConfigStore;
+use crate::presentation::AppState;
+
+const TELEMETRY_FLUSH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Задержка первой отправки после старта (не конкурируем с инициализацией)
+const TELEMETRY_INITIAL_DELAY: Duration = Duration::from_secs(60);
+
+/// Ставит событие в очередь, если пользователь согласился на телеметрию
+pub async fn record_telemetry(
+    config: &Arc<RwLock<AppConfig>>,
+    queue: &Arc<RwLock<Vec<TelemetryEvent>>>,
+    event: TelemetryEvent,
+) {
+    if !config.read().await.telemetry_enabled {
+        return;
+    }
+
+    let snapshot = {
+        let mut queue = queue.write().await;
+        enqueue_telemetry(&mut queue, event);
+        queue.clone()
+    };
+    if let Err(e) = ConfigStore::save_telemetry_queue(&snapshot).await {
+        log::warn!("Failed to save telemetry queue: {}", e);
+    }
+}
+
+/// Очищает очередь в памяти и на диске
+pub async fn clear_telemetry_queue(queue: &Arc<RwLock<Vec<TelemetryEvent>>>) {
+    queue.write().await.clear();
+    if let Err(e) = ConfigStore::delete_telemetry_queue().await {
+        log::warn!("Failed to delete telemetry queue: {}", e);
+    }
+}
+
+/// Ровно то, что уйдёт следующим запросом
+pub async fn telemetry_preview(state: &AppState) -> TelemetryBatch {
+    TelemetryBatch::new(next_telemetry_batch(&state.integrations.telemetry_queue.read().await))
+}
+
+/// Отправляет очередь пачками. Ошибка сети — события остаются в очереди до следующей попытки.
+pub async fn flush_telemetry(state: &AppState) -> Result<usize, String> {
+    let mut sent_total = 0;
+    loop {
+        if !state.settings.config.read().await.telemetry_enabled {
+            return Ok(sent_total);
+        }
+
+        let batch = next_telemetry_batch(&state.integrations.telemetry_queue.read().await);
+        if batch.is_empty() {
+            return Ok(sent_total);
+        }
+
+        let payload = TelemetryBatch::new(batch);
+        send_telemetry_batch(&AppState::get_api_base_url(), &payload)
+            .await
+            .map_err(|e| e.to_string())?;
+        sent_total += payload.events.len();
+
+        let snapshot = {
+            let mut queue = state.integrations.telemetry_queue.write().await;
+            acknowledge_telemetry_batch(&mut queue, &payload.events);
+            queue.clone()
+        };
+        if let Err(e) = ConfigStore::save_telemetry_queue(&snapshot).await {
+            log::warn!("Failed to save telemetry queue: {}", e);
+        }
+    }
+}
+
+/// Фоновая отправка очереди
+pub fn start_telemetry_uploader(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(TELEMETRY_INITIAL_DELAY).await;
+
+        loop {
+            if let Some(state) = app_handle.try_state::<AppState>() {
+                match flush_telemetry(&state).await {
+                    Ok(0) => {}
+                    Ok(sent) => log::debug!("Telemetry sent: {} events", sent),
+                    Err(e) => log::debug!("Telemetry upload postponed: {}", e),
+                }
+            }
+
+            tokio::time::sleep(TELEMETRY_FLUSH_INTERVAL).await;
+        }
+    });
+}
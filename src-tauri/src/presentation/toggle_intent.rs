@@ -0,0 +1,105 @@
+//! Очередь нажатий хоткея во время переходных состояний записи.
+//!
+//! Нажатие в Starting — "останови, как только запись стартует", в Processing — "начни новую,
+//! как только закончим". Повторное нажатие отменяет намерение, устаревшее намерение отбрасывается.
+
+use crate::domain::RecordingStatus;
+
+/// Сколько живёт отложенное нажатие: если переход затянулся, пользователь уже забыл о нём
+pub const QUEUED_TOGGLE_GRACE_MS: u64 = 5_000;
+
+/// Повторное нажатие быстрее этого — дребезг/key repeat, а не отмена
+pub const QUEUED_TOGGLE_DEBOUNCE_MS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueuedToggle {
+    /// Остановить запись, когда она перейдёт в Recording
+    Stop,
+    /// Начать запись, когда обработка закончится (Idle)
+    Start,
+}
+
+impl QueuedToggle {
+    /// Статус, по достижении которого выполняется намерение
+    fn target_status(self) -> RecordingStatus {
+        match self {
+            Self::Stop => RecordingStatus::Recording,
+            Self::Start => RecordingStatus::Idle,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ToggleIntentQueue {
+    pending: Option<(QueuedToggle, u64)>,
+}
+
+impl ToggleIntentQueue {
+    /// Нажатие во время `status`. Возвращает намерение, которое теперь ждёт выполнения (None — отменено/не нужно).
+    pub fn press(&mut self, status: RecordingStatus, now_ms: u64) -> Option<QueuedToggle> {
+        let intent = match status {
+            RecordingStatus::Starting => QueuedToggle::Stop,
+            RecordingStatus::Processing => QueuedToggle::Start,
+            _ => return None,
+        };
+
+        match self.pending {
+            Some((pending, queued_at)) if pending == intent => {
+                if now_ms.saturating_sub(queued_at) < QUEUED_TOGGLE_DEBOUNCE_MS {
+                    return Some(pending);
+                }
+                // Второе осознанное нажатие — передумал
+                self.pending = None;
+                None
+            }
+            _ => {
+                self.pending = Some((intent, now_ms));
+                Some(intent)
+            }
+        }
+    }
+
+    /// Забирает намерение, если достигнут его статус и оно не устарело
+    pub fn take(&mut self, reached: RecordingStatus, now_ms: u64) -> Option<QueuedToggle> {
+        let (intent, queued_at) = self.pending?;
+        if intent.target_status() != reached {
+            return None;
+        }
+        self.pending = None;
+        (now_ms.saturating_sub(queued_at) <= QUEUED_TOGGLE_GRACE_MS).then_some(intent)
+    }
+
+    pub fn clear(&mut self) {
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn press_during_starting_stops_once_recording() {
+        let mut queue = ToggleIntentQueue::default();
+        assert_eq!(queue.press(RecordingStatus::Starting, 1_000), Some(QueuedToggle::Stop));
+        // key repeat не отменяет
+        assert_eq!(queue.press(RecordingStatus::Starting, 1_100), Some(QueuedToggle::Stop));
+
+        assert_eq!(queue.take(RecordingStatus::Idle, 1_500), None);
+        assert_eq!(queue.take(RecordingStatus::Recording, 1_500), Some(QueuedToggle::Stop));
+        assert_eq!(queue.take(RecordingStatus::Recording, 1_600), None);
+    }
+
+    #[test]
+    fn second_press_cancels_and_stale_intent_expires() {
+        let mut queue = ToggleIntentQueue::default();
+        assert_eq!(queue.press(RecordingStatus::Processing, 1_000), Some(QueuedToggle::Start));
+        assert_eq!(queue.press(RecordingStatus::Processing, 2_000), None);
+        assert_eq!(queue.take(RecordingStatus::Idle, 2_100), None);
+
+        assert_eq!(queue.press(RecordingStatus::Processing, 3_000), Some(QueuedToggle::Start));
+        assert_eq!(queue.take(RecordingStatus::Idle, 3_000 + QUEUED_TOGGLE_GRACE_MS + 1), None);
+
+        assert_eq!(queue.press(RecordingStatus::Recording, 9_000), None);
+    }
+}
@@ -0,0 +1,316 @@
+//! Очередь расшифровки аудиофайлов (брошенных на главное окно).
+//!
+//! Файл режется на главы у пауз; каждая готовая глава сразу сохраняется на диск и уходит
+//! во frontend событием `file_transcription:chapter`. Поэтому многочасовой файл не начинается
+//! заново после выхода из приложения: прерванная задача продолжается с первой нерасшифрованной главы.
+//! Задачи выполняются по одной (главы одной задачи через REST API — параллельно, см. `batch_concurrency`);
+//! пауза и отмена применяются между главами.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use futures_util::StreamExt;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::application::{file_chunk_ranges, transcribe_chunks};
+use crate::domain::{AudioConfig, TranscriptionChapter, TranscriptionJob, TranscriptionJobStatus};
+use crate::infrastructure::audio::read_audio_file;
+use crate::infrastructure::ConfigStore;
+use crate::presentation::events::{
+    FileTranscriptionChapterPayload, FileTranscriptionErrorPayload, FileTranscriptionProgressPayload,
+    FileTranscriptionResultPayload, EVENT_FILE_TRANSCRIPTION_CHAPTER, EVENT_FILE_TRANSCRIPTION_ERROR,
+    EVENT_FILE_TRANSCRIPTION_PROGRESS, EVENT_FILE_TRANSCRIPTION_RESULT,
+};
+use crate::presentation::AppState;
+
+/// Сколько завершённых/отменённых задач хранить (активные и приостановленные не удаляются)
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Ставит файл в очередь и запускает воркер; возвращает id задачи
+pub async fn enqueue_file(app_handle: &AppHandle, state: &AppState, path: &Path) -> String {
+    let job = TranscriptionJob::new(
+        uuid::Uuid::new_v4().to_string(),
+        path.display().to_string(),
+        chrono::Utc::now().timestamp(),
+    );
+    let id = job.id.clone();
+    log::info!("Transcription job {} queued: {}", id, job.path);
+
+    {
+        let mut jobs = state.recording.transcription_jobs.write().await;
+        jobs.push(job);
+        prune_finished(&mut jobs);
+    }
+    persist(state).await;
+    spawn_worker(app_handle);
+    id
+}
+
+/// Подхватывает задачи с диска при запуске. Прерванные выходом из приложения (Running)
+/// возвращаются в очередь и продолжаются с того места, где остановились.
+pub async fn restore_transcription_jobs(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+    let mut loaded = match ConfigStore::load_transcription_jobs().await {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            log::warn!("Failed to load transcription jobs: {}", e);
+            return;
+        }
+    };
+
+    for job in &mut loaded {
+        if job.status == TranscriptionJobStatus::Running {
+            job.status = TranscriptionJobStatus::Queued;
+        }
+    }
+    let has_queued = {
+        let mut jobs = state.recording.transcription_jobs.write().await;
+        // Файлы, брошенные до окончания загрузки, идут после восстановленных
+        loaded.append(&mut jobs);
+        *jobs = loaded;
+        jobs.iter().any(|j| j.status == TranscriptionJobStatus::Queued)
+    };
+    if has_queued {
+        log::info!("Resuming interrupted transcription jobs");
+        spawn_worker(app_handle);
+    }
+}
+
+/// Запускает воркер очереди, если он ещё не запущен
+pub fn spawn_worker(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+    if state.recording
+        .transcription_job_worker
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app_handle.try_state::<AppState>() else {
+            return;
+        };
+        loop {
+            while let Some(id) = claim_next(&state).await {
+                run_job(&app_handle, &state, &id).await;
+            }
+            state.recording.transcription_job_worker.store(false, Ordering::Release);
+
+            // Задачу могли поставить между последней проверкой и сбросом флага
+            let has_queued = state.recording
+                .transcription_jobs
+                .read()
+                .await
+                .iter()
+                .any(|j| j.status == TranscriptionJobStatus::Queued);
+            if !has_queued
+                || state.recording
+                    .transcription_job_worker
+                    .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                    .is_err()
+            {
+                break;
+            }
+        }
+    });
+}
+
+/// Меняет задачу под блокировкой; None — задачи нет
+pub async fn update_job<R>(state: &AppState, id: &str, f: impl FnOnce(&mut TranscriptionJob) -> R) -> Option<R> {
+    let mut jobs = state.recording.transcription_jobs.write().await;
+    jobs.iter_mut().find(|j| j.id == id).map(f)
+}
+
+/// Сохраняет все задачи на диск
+pub async fn persist(state: &AppState) {
+    let jobs = state.recording.transcription_jobs.read().await.clone();
+    if let Err(e) = ConfigStore::save_transcription_jobs(&jobs).await {
+        log::warn!("Failed to save transcription jobs: {}", e);
+    }
+}
+
+fn prune_finished(jobs: &mut Vec<TranscriptionJob>) {
+    let finished = jobs.iter().filter(|j| j.is_finished()).count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+    // Самые старые — в начале списка
+    jobs.retain(|j| {
+        if excess > 0 && j.is_finished() {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+async fn claim_next(state: &AppState) -> Option<String> {
+    let mut jobs = state.recording.transcription_jobs.write().await;
+    let job = jobs.iter_mut().find(|j| j.status == TranscriptionJobStatus::Queued)?;
+    job.status = TranscriptionJobStatus::Running;
+    Some(job.id.clone())
+}
+
+async fn job_status(state: &AppState, id: &str) -> Option<TranscriptionJobStatus> {
+    update_job(state, id, |job| job.status).await
+}
+
+async fn run_job(app_handle: &AppHandle, state: &AppState, id: &str) {
+    let Some((path, offset)) = update_job(state, id, |job| (job.path.clone(), job.processed_samples)).await else {
+        return;
+    };
+    log::info!("Transcription job {} started: {} (from sample {})", id, path, offset);
+
+    let path_for_decode = PathBuf::from(&path);
+    let samples = match tokio::task::spawn_blocking(move || read_audio_file(&path_for_decode)).await {
+        Ok(Ok(samples)) => samples,
+        Ok(Err(e)) => return fail_job(app_handle, state, id, &path, e.to_string()).await,
+        Err(e) => return fail_job(app_handle, state, id, &path, format!("Audio decoding task failed: {}", e)).await,
+    };
+
+    let sample_rate = AudioConfig::default().sample_rate;
+    let to_ms = |len: usize| len as u64 * 1000 / sample_rate as u64;
+    let total_ms = to_ms(samples.len());
+    update_job(state, id, |job| job.total_ms = total_ms).await;
+    let config = state.recording.transcription_service.get_config().await;
+
+    // REST API провайдера (Deepgram/AssemblyAI) — куски параллельно; стриминг — по одному
+    let batch = match state.recording.transcription_service.batch_provider(&config) {
+        Ok(batch) => batch,
+        Err(e) => return fail_job(app_handle, state, id, &path, format!("{:#}", e)).await,
+    };
+    let concurrency = if batch.is_some() { config.effective_batch_concurrency() } else { 1 };
+    log::info!("Transcription job {}: {} chunk(s) in flight", id, concurrency);
+
+    let service = &state.recording.transcription_service;
+    let ranges = file_chunk_ranges(&samples, sample_rate, offset);
+    let mut chunks = std::pin::pin!(transcribe_chunks(ranges, concurrency, |range: Range<usize>| {
+        let batch = batch.clone();
+        let chunk = &samples[range];
+        let config = &config;
+        async move { service.transcribe_chunk(batch.as_deref(), config, chunk, sample_rate).await }
+    }));
+
+    while let Some((range, result)) = chunks.next().await {
+        let text = match result {
+            Ok(text) => text.trim().to_string(),
+            Err(e) => return fail_job(app_handle, state, id, &path, format!("{:#}", e)).await,
+        };
+
+        // Пауза во время главы: глава всё равно сохраняется; отмена — выбрасывается
+        let chapter = update_job(state, id, |job| {
+            if job.status == TranscriptionJobStatus::Cancelled {
+                return None;
+            }
+            let chapter = TranscriptionChapter {
+                index: job.chapters.len(),
+                start_ms: to_ms(range.start),
+                end_ms: to_ms(range.end),
+                text,
+            };
+            job.chapters.push(chapter.clone());
+            job.processed_samples = range.end;
+            Some(chapter)
+        })
+        .await
+        .flatten();
+        persist(state).await;
+
+        if let Some(chapter) = chapter {
+            emit(app_handle, EVENT_FILE_TRANSCRIPTION_CHAPTER, FileTranscriptionChapterPayload {
+                job_id: id.to_string(),
+                path: path.clone(),
+                chapter,
+            });
+            emit(app_handle, EVENT_FILE_TRANSCRIPTION_PROGRESS, FileTranscriptionProgressPayload {
+                job_id: id.to_string(),
+                path: path.clone(),
+                processed_ms: to_ms(range.end),
+                total_ms,
+            });
+        }
+
+        // Пауза/отмена применяются между главами; запросы, что уже в полёте, выбрасываются
+        if job_status(state, id).await != Some(TranscriptionJobStatus::Running) {
+            log::info!("Transcription job {} stopped after sample {}", id, range.end);
+            return;
+        }
+    }
+
+    let text = update_job(state, id, |job| {
+        if job.status != TranscriptionJobStatus::Running {
+            return None;
+        }
+        job.status = TranscriptionJobStatus::Completed;
+        Some(job.text())
+    })
+    .await
+    .flatten();
+    persist(state).await;
+
+    if let Some(text) = text {
+        log::info!("Transcription job {} completed ({} ms, {} chars)", id, total_ms, text.len());
+        emit(app_handle, EVENT_FILE_TRANSCRIPTION_RESULT, FileTranscriptionResultPayload {
+            job_id: id.to_string(),
+            path,
+            text,
+            duration_ms: total_ms,
+        });
+    }
+}
+
+/// Ошибка главы или декодирования: готовые главы остаются, resume повторит с той же главы
+async fn fail_job(app_handle: &AppHandle, state: &AppState, id: &str, path: &str, error: String) {
+    log::error!("Transcription job {} failed: {}: {}", id, path, error);
+    update_job(state, id, |job| {
+        if job.status == TranscriptionJobStatus::Cancelled {
+            return;
+        }
+        job.status = TranscriptionJobStatus::Failed;
+        job.error = Some(error.clone());
+    })
+    .await;
+    persist(state).await;
+
+    emit(app_handle, EVENT_FILE_TRANSCRIPTION_ERROR, FileTranscriptionErrorPayload {
+        job_id: id.to_string(),
+        path: path.to_string(),
+        error,
+    });
+}
+
+fn emit<P: serde::Serialize + Clone>(app_handle: &AppHandle, event: &str, payload: P) {
+    if let Err(e) = app_handle.emit(event, payload) {
+        log::error!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_drops_oldest_finished_jobs_only() {
+        let mut jobs: Vec<TranscriptionJob> = (0..MAX_FINISHED_JOBS + 2)
+            .map(|i| {
+                let mut job = TranscriptionJob::new(i.to_string(), "a.wav", i as i64);
+                job.status = TranscriptionJobStatus::Completed;
+                job
+            })
+            .collect();
+        jobs[0].status = TranscriptionJobStatus::Paused;
+        jobs.push(TranscriptionJob::new("new", "b.wav", 100));
+
+        prune_finished(&mut jobs);
+        assert_eq!(jobs.iter().filter(|j| j.is_finished()).count(), MAX_FINISHED_JOBS);
+        assert_eq!(jobs[0].id, "0");
+        assert_eq!(jobs[1].id, "2");
+        assert_eq!(jobs.last().unwrap().id, "new");
+    }
+}
@@ -1,25 +1,27 @@
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager, Runtime,
+    AppHandle, Emitter, Manager, Wry,
 };
 
 use crate::presentation::commands::show_webview_window_on_active_monitor;
 use crate::presentation::events::EVENT_RECORDING_WINDOW_SHOWN;
+use crate::presentation::i18n::{UiLanguage, UiMessage};
 
-/// Создает и настраивает system tray иконку с меню
-pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
-    // Создаем элементы меню
-    let show_item = MenuItem::with_id(app, "show", "Открыть", true, None::<&str>)?;
-    let settings_item = MenuItem::with_id(app, "settings", "Настройки", true, None::<&str>)?;
-    let profile_item = MenuItem::with_id(app, "profile", "Профиль", true, None::<&str>)?;
-    let check_updates_item =
-        MenuItem::with_id(app, "check_updates", "Проверить обновления", true, None::<&str>)?;
+/// Идентификатор иконки в трее (по нему меню пересобирается при смене языка)
+const TRAY_ID: &str = "main";
+
+/// Меню трея на языке интерфейса
+fn build_menu(app: &AppHandle, lang: UiLanguage) -> tauri::Result<Menu<Wry>> {
+    let item = |id: &str, message: UiMessage| MenuItem::with_id(app, id, message.text(lang), true, None::<&str>);
+    let show_item = item("show", UiMessage::TrayOpen)?;
+    let settings_item = item("settings", UiMessage::TraySettings)?;
+    let profile_item = item("profile", UiMessage::TrayProfile)?;
+    let check_updates_item = item("check_updates", UiMessage::TrayCheckUpdates)?;
     let separator = tauri::menu::PredefinedMenuItem::separator(app)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Выход", true, None::<&str>)?;
+    let quit_item = item("quit", UiMessage::TrayQuit)?;
 
-    // Собираем меню
-    let menu = Menu::with_items(
+    Menu::with_items(
         app,
         &[
             &show_item,
@@ -29,10 +31,34 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
             &separator,
             &quit_item,
         ],
-    )?;
+    )
+}
+
+/// Пересобирает меню трея после смены языка интерфейса
+pub fn update_tray_language(app: &AppHandle, lang: UiLanguage) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app, lang) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::error!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => log::error!("Failed to build tray menu: {}", e),
+    }
+}
+
+/// Создает и настраивает system tray иконку с меню
+///
+/// UI-настройки к этому моменту ещё не загружены, поэтому меню строится на языке по умолчанию
+/// и пересобирается после загрузки (`update_tray_language`).
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    let lang = UiLanguage::from_locale(&crate::domain::UiPreferences::default().locale);
+    let menu = build_menu(app, lang)?;
 
     // Создаем tray иконку
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .icon(app.default_window_icon().unwrap().clone())
         .tooltip("VoicetextAI")
@@ -109,7 +135,7 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                 }
                 "quit" => {
                     log::info!("Quitting application from tray menu");
-                    app.exit(0);
+                    crate::presentation::shutdown::request_shutdown(app);
                 }
                 _ => {}
             }
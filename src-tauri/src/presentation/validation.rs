@@ -0,0 +1,187 @@
+//! Проверка аргументов команд.
+//!
+//! Ошибки всех полей собираются за один проход и уходят во frontend одной структурированной ошибкой
+//! (JSON в строке ошибки команды): форма настроек подсвечивает каждое неверное поле, а не только первое.
+//! Имена полей — camelCase, как в аргументах invoke().
+
+use std::fmt::Display;
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::presentation::i18n::{UiLanguage, UiMessage};
+
+/// Значение `kind` у структурированной ошибки валидации
+pub const VALIDATION_ERROR_KIND: &str = "validation";
+
+/// One invalid command argument
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    /// Argument name as the frontend passes it (camelCase)
+    pub field: String,
+    /// Stable error id (`UiMessage::key`), for frontend-side handling
+    pub code: String,
+    /// Localized text for the form
+    pub message: String,
+}
+
+/// Validation failure returned as the command error (serialized to JSON)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationFailure {
+    /// Always "validation" — lets the frontend tell it apart from plain string errors
+    pub kind: String,
+    /// Localized summary for a toast
+    pub message: String,
+    pub errors: Vec<FieldError>,
+}
+
+/// Собирает ошибки полей одного запроса
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    issues: Vec<(&'static str, UiMessage)>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, field: &'static str, message: UiMessage) {
+        self.issues.push((field, message));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Имена неверных полей в порядке проверки
+    pub fn fields(&self) -> Vec<&'static str> {
+        self.issues.iter().map(|(field, _)| *field).collect()
+    }
+
+    /// Ok(value), если ошибок нет
+    pub fn finish<T>(self, value: impl FnOnce() -> T) -> Result<T, Self> {
+        if self.is_empty() {
+            Ok(value())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Обязательная непустая строка
+    pub fn require<'a>(&mut self, field: &'static str, raw: &'a str) -> Option<&'a str> {
+        let value = raw.trim();
+        if value.is_empty() {
+            self.push(field, UiMessage::FieldRequired);
+            return None;
+        }
+        Some(value)
+    }
+
+    /// Значение enum по его serde-имени ("deepgram", "opus", ...)
+    pub fn parse_enum<T: DeserializeOwned>(&mut self, field: &'static str, raw: &str) -> Option<T> {
+        let value = self.require(field, raw)?;
+        match serde_json::from_value(serde_json::Value::String(value.to_string())) {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                self.push(field, UiMessage::FieldUnsupportedValue { value: value.to_string() });
+                None
+            }
+        }
+    }
+
+    /// Необязательный enum: None и пустая строка — "не задано"
+    pub fn parse_optional_enum<T: DeserializeOwned>(&mut self, field: &'static str, raw: Option<&str>) -> Option<T> {
+        match raw.map(str::trim) {
+            Some(value) if !value.is_empty() => self.parse_enum(field, value),
+            _ => None,
+        }
+    }
+
+    /// Число в диапазоне [min, max] (None — поле не прислали)
+    pub fn in_range<T: PartialOrd + Display + Copy>(
+        &mut self,
+        field: &'static str,
+        value: Option<T>,
+        min: T,
+        max: T,
+    ) -> Option<T> {
+        let value = value?;
+        if value < min || value > max {
+            self.push(
+                field,
+                UiMessage::FieldOutOfRange {
+                    value: value.to_string(),
+                    min: min.to_string(),
+                    max: max.to_string(),
+                },
+            );
+            return None;
+        }
+        Some(value)
+    }
+
+    pub fn to_failure(&self, lang: UiLanguage) -> ValidationFailure {
+        let fields = self.fields().into_iter().map(str::to_string).collect();
+        ValidationFailure {
+            kind: VALIDATION_ERROR_KIND.to_string(),
+            message: UiMessage::ValidationFailed { fields }.text(lang),
+            errors: self
+                .issues
+                .iter()
+                .map(|(field, message)| FieldError {
+                    field: field.to_string(),
+                    code: message.key().to_string(),
+                    message: message.text(lang),
+                })
+                .collect(),
+        }
+    }
+
+    /// Ошибка команды: JSON `ValidationFailure`
+    pub fn to_command_error(&self, lang: UiLanguage) -> String {
+        let failure = self.to_failure(lang);
+        serde_json::to_string(&failure).unwrap_or(failure.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::AudioEncoding;
+
+    #[test]
+    fn collects_errors_of_every_invalid_field() {
+        let mut errors = ValidationErrors::new();
+        assert_eq!(errors.parse_enum::<AudioEncoding>("audioEncoding", " opus "), Some(AudioEncoding::Opus));
+        assert_eq!(errors.parse_enum::<AudioEncoding>("audioEncoding", "mp3"), None);
+        assert_eq!(errors.require("language", "  "), None);
+        assert_eq!(errors.in_range("batchConcurrency", Some(9u8), 1, 8), None);
+        assert_eq!(errors.in_range("shortClipSecs", Some(10u32), 0, 60), Some(10));
+        assert_eq!(errors.in_range::<u32>("keepAliveTtlSecs", None, 10, 3600), None);
+        assert_eq!(errors.parse_optional_enum::<AudioEncoding>("model", Some("")), None);
+
+        assert_eq!(errors.fields(), vec!["audioEncoding", "language", "batchConcurrency"]);
+        assert!(errors.finish(|| ()).is_err());
+        assert_eq!(ValidationErrors::new().finish(|| 1), Ok(1));
+    }
+
+    #[test]
+    fn command_error_is_structured_json() {
+        let mut errors = ValidationErrors::new();
+        errors.parse_enum::<AudioEncoding>("audioEncoding", "mp3");
+        errors.in_range("maxAlternatives", Some(12u8), 0, 5);
+
+        let json: serde_json::Value = serde_json::from_str(&errors.to_command_error(UiLanguage::En)).unwrap();
+        assert_eq!(json["kind"], VALIDATION_ERROR_KIND);
+        assert!(json["message"].as_str().unwrap().contains("audioEncoding, maxAlternatives"));
+        assert_eq!(json["errors"][0]["field"], "audioEncoding");
+        assert_eq!(json["errors"][0]["code"], "field-unsupported-value");
+        assert!(json["errors"][0]["message"].as_str().unwrap().contains("mp3"));
+        assert_eq!(json["errors"][1]["code"], "field-out-of-range");
+        assert!(json["errors"][1]["message"].as_str().unwrap().contains("0–5"));
+    }
+}
@@ -0,0 +1,100 @@
+//! Выполнение голосовых команд управления (`SttConfig::voice_commands`).
+//!
+//! Команды распознаёт `VoiceCommandSpotter` внутри сессии записи; здесь — действия приложения:
+//! - "stop recording" — остановка как по хоткею
+//! - "switch to English" — перезапуск записи с языком только на новую сессию (как deep link `record?lang=`)
+//! - "paste that" — вставка надиктованного в активное окно, запись продолжается
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::application::VoiceCommand;
+use crate::domain::{RecordingStatus, SttSessionOverride};
+use crate::presentation::commands;
+use crate::presentation::events::{VoiceCommandPayload, EVENT_VOICE_COMMAND};
+use crate::presentation::AppState;
+
+/// Сколько ждём завершения остановленной сессии перед стартом с новым языком
+const RESTART_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+const RESTART_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Вызывается из колбэка STT: само действие выполняется асинхронно, не блокируя поток распознавания
+pub fn handle_voice_command(app_handle: &AppHandle, command: VoiceCommand) {
+    if let Err(e) = app_handle.emit(EVENT_VOICE_COMMAND, VoiceCommandPayload::from(&command)) {
+        log::error!("Failed to emit voice command event: {}", e);
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let name = command.name();
+        let result = match command {
+            VoiceCommand::StopRecording => stop_recording(&app_handle).await,
+            VoiceCommand::SwitchLanguage { language } => switch_language(&app_handle, language).await,
+            VoiceCommand::PasteText { text } => paste_text(&app_handle, text).await,
+        };
+        if let Err(error) = result {
+            log::error!("Voice command '{}' failed: {}", name, error);
+        }
+    });
+}
+
+async fn stop_recording(app_handle: &AppHandle) -> Result<(), String> {
+    let state = app_handle
+        .try_state::<AppState>()
+        .ok_or_else(|| "AppState не доступен".to_string())?;
+
+    let status = state.recording.transcription_service.get_status().await;
+    if status != RecordingStatus::Recording {
+        log::info!("Voice command stop ignored - not recording (status: {:?})", status);
+        return Ok(());
+    }
+
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    commands::toggle_recording_with_window_internal(&state, window, app_handle.clone()).await
+}
+
+async fn switch_language(app_handle: &AppHandle, language: String) -> Result<(), String> {
+    let state = app_handle
+        .try_state::<AppState>()
+        .ok_or_else(|| "AppState не доступен".to_string())?;
+
+    let status = state.recording.transcription_service.get_status().await;
+    if status != RecordingStatus::Recording {
+        log::info!("Voice command switch language ignored - not recording (status: {:?})", status);
+        return Ok(());
+    }
+    log::info!("Voice command: restarting recording with language '{}'", language);
+
+    // Останавливаем как кнопкой в окне (без сворачивания окна), текст сессии сохраняется как обычно
+    commands::stop_recording(app_handle.state::<AppState>(), app_handle.clone()).await?;
+
+    let deadline = tokio::time::Instant::now() + RESTART_IDLE_TIMEOUT;
+    while state.recording.transcription_service.get_status().await != RecordingStatus::Idle {
+        if tokio::time::Instant::now() >= deadline {
+            return Err("Recording did not stop in time to switch language".to_string());
+        }
+        tokio::time::sleep(RESTART_POLL_INTERVAL).await;
+    }
+
+    let window = app_handle
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    commands::start_recording_with_overrides_internal(
+        &state,
+        window,
+        app_handle.clone(),
+        Some(SttSessionOverride::language(language)),
+        None,
+    )
+    .await
+}
+
+async fn paste_text(app_handle: &AppHandle, text: String) -> Result<(), String> {
+    let state = app_handle
+        .try_state::<AppState>()
+        .ok_or_else(|| "AppState не доступен".to_string())?;
+    commands::paste_text_internal(&state, app_handle, text).await
+}
@@ -0,0 +1,78 @@
+//! Warm pool: поднимает соединение с провайдером незадолго до привычного времени диктовки.
+//!
+//! Решение принимает `application::should_prewarm` по недавним нажатиям хоткея записи.
+//! Само соединение открывается так же, как при prewarm: сразу на паузе и закрывается по keep-alive TTL.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::application::should_prewarm;
+use crate::infrastructure::ConfigStore;
+use crate::presentation::AppState;
+
+/// Как часто проверять, не пора ли прогреть соединение
+const WARM_POOL_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Запоминает нажатие хоткея записи (в фоне, с сохранением на диск)
+pub fn record_hotkey_activity(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app_handle.try_state::<AppState>() else {
+            return;
+        };
+        let snapshot = {
+            let mut activity = state.user_data.hotkey_activity.write().await;
+            crate::application::record_hotkey_activity(&mut activity, chrono::Utc::now().timestamp_millis());
+            activity.clone()
+        };
+        if let Err(e) = ConfigStore::save_hotkey_activity(&snapshot).await {
+            log::warn!("Failed to save hotkey activity: {}", e);
+        }
+    });
+}
+
+/// Загружает историю нажатий с диска и запускает периодическую проверку warm pool
+pub async fn start_warm_pool(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+    match ConfigStore::load_hotkey_activity().await {
+        Ok(mut loaded) => {
+            // Нажатия, случившиеся до загрузки, идут после сохранённых
+            let mut activity = state.user_data.hotkey_activity.write().await;
+            loaded.append(&mut activity);
+            *activity = loaded;
+        }
+        Err(e) => log::warn!("Failed to load hotkey activity: {}", e),
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app_handle.try_state::<AppState>() else {
+            return;
+        };
+        loop {
+            tokio::time::sleep(WARM_POOL_CHECK_INTERVAL).await;
+
+            let warm_pool = state.settings.config.read().await.warm_pool.clone();
+            // Без авторизации backend отклонит соединение
+            if !warm_pool.enabled || !*state.is_authenticated.read().await {
+                continue;
+            }
+            let due = {
+                let activity = state.user_data.hotkey_activity.read().await;
+                should_prewarm(&activity, &chrono::Local::now(), warm_pool.lead_secs)
+            };
+            if !due {
+                continue;
+            }
+
+            match state.recording.transcription_service.warm_connection().await {
+                Ok(true) => log::info!("Warm pool: connection re-established ahead of expected dictation"),
+                Ok(false) => {}
+                Err(e) => log::warn!("Warm pool: failed to warm up connection: {}", e),
+            }
+        }
+    });
+}
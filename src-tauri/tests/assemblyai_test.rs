@@ -164,6 +164,7 @@ async fn test_assemblyai_callbacks() {
         timestamp: 0,
         start: 0.0,
         duration: 0.0,
+        alternatives: Vec::new(),
     };
 
     on_partial(test_transcription.clone());
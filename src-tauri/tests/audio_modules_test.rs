@@ -1,6 +1,8 @@
-use app_lib::infrastructure::audio::MockAudioCapture;
+use app_lib::infrastructure::audio::{FileAudioCapture, MockAudioCapture};
 use app_lib::domain::{AudioCapture, AudioConfig, AudioChunk};
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 // ============================================================================
 // MOCK AUDIO CAPTURE ТЕСТЫ
@@ -89,6 +91,83 @@ async fn test_mock_audio_multiple_sessions() {
     capture.stop_capture().await.unwrap();
 }
 
+// ============================================================================
+// FILE AUDIO CAPTURE ТЕСТЫ
+// ============================================================================
+
+/// 16kHz mono PCM16 WAV во временной папке
+fn write_wav(samples: &[i16]) -> PathBuf {
+    let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&16000u32.to_le_bytes());
+    bytes.extend_from_slice(&32000u32.to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&data);
+
+    let dir = std::env::temp_dir().join(format!("file_capture_it_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("replay.wav");
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+/// Проигрывает файл ~50ms и возвращает первый чанк
+async fn first_chunk(mut capture: FileAudioCapture) -> AudioChunk {
+    capture.initialize(AudioConfig::default()).await.unwrap();
+    let chunks = Arc::new(Mutex::new(Vec::<AudioChunk>::new()));
+    let chunks_cb = chunks.clone();
+    capture
+        .start_capture(Arc::new(move |chunk| chunks_cb.lock().unwrap().push(chunk)))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    capture.stop_capture().await.unwrap();
+    assert!(!capture.is_capturing());
+
+    let chunks = chunks.lock().unwrap();
+    chunks.first().cloned().expect("no chunks from file capture")
+}
+
+#[tokio::test]
+async fn test_file_capture_replays_wav_in_a_loop() {
+    // 50ms файла: в 100ms чанке он звучит дважды
+    let samples: Vec<i16> = (1..=800).map(|i| i as i16).collect();
+    let path = write_wav(&samples);
+    let capture = FileAudioCapture::from_file(&path).unwrap();
+    assert_eq!(capture.duration_ms(), 50);
+
+    let chunk = first_chunk(capture).await;
+    assert_eq!(chunk.sample_rate, 16000);
+    assert_eq!(chunk.channels, 1);
+    assert_eq!(chunk.data.len(), 1600);
+    assert_eq!(&chunk.data[..800], samples.as_slice());
+    assert_eq!(&chunk.data[800..], samples.as_slice());
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[tokio::test]
+async fn test_file_capture_pads_silence_after_end_of_file() {
+    let path = write_wav(&[700i16; 800]);
+    let capture = FileAudioCapture::from_file(&path).unwrap().with_looping(false);
+
+    let chunk = first_chunk(capture).await;
+    assert_eq!(chunk.data.len(), 1600);
+    assert!(chunk.data[..800].iter().all(|&s| s == 700));
+    assert!(chunk.data[800..].iter().all(|&s| s == 0));
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
 // AUDIO CHUNK ТЕСТЫ
 // ============================================================================
 
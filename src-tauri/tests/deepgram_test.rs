@@ -213,6 +213,7 @@ async fn test_deepgram_callbacks() {
         timestamp: 0,
         start: 0.0,
         duration: 0.0,
+        alternatives: Vec::new(),
     };
 
     on_partial(test_transcription.clone());
@@ -0,0 +1,279 @@
+//! Офлайн интеграционные тесты провайдеров против встроенного mock STT сервера.
+//!
+//! Запуск: cargo test --features mock-stt-server --test mock_stt_server_test
+#![cfg(feature = "mock-stt-server")]
+
+mod test_support;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use app_lib::domain::{
+    AudioChunk, AudioEncoding, SttConfig, SttConnectionCategory, SttError, SttProvider, SttProviderType,
+    Transcription, TranscriptionCallback,
+};
+use app_lib::infrastructure::stt::{
+    AssemblyAIProvider, BackendProvider, DeepgramProvider, MockSttProtocol, MockSttScript,
+    MockSttServer,
+};
+use test_support::{noop_connection_quality, noop_error, SttConfigTestExt};
+
+type Collected = Arc<Mutex<Vec<String>>>;
+
+fn collector() -> (Collected, TranscriptionCallback) {
+    let store: Collected = Arc::new(Mutex::new(Vec::new()));
+    let store_clone = store.clone();
+    let cb: TranscriptionCallback = Arc::new(move |t: Transcription| {
+        store_clone.lock().unwrap().push(t.text);
+    });
+    (store, cb)
+}
+
+/// 100ms тона @ 16kHz (не тишина, чтобы не зависеть от детекторов тишины)
+fn speech_chunk() -> AudioChunk {
+    let data: Vec<i16> = (0..1600).map(|i| ((i % 32) as i16 - 16) * 500).collect();
+    AudioChunk::new(data, 16000, 1)
+}
+
+async fn wait_for<F: Fn() -> bool>(condition: F) {
+    for _ in 0..50 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_deepgram_against_mock_server() {
+    let server = MockSttServer::start(
+        MockSttProtocol::Deepgram,
+        MockSttScript::new("hello from mock server"),
+    )
+    .await
+    .unwrap();
+
+    let mut provider = DeepgramProvider::new().with_endpoint(server.url());
+    let config = SttConfig::new(SttProviderType::Deepgram)
+        .with_language("en")
+        .with_api_key("mock-key");
+    provider.initialize(&config).await.unwrap();
+
+    let (partials, on_partial) = collector();
+    let (finals, on_final) = collector();
+    provider
+        .start_stream(on_partial, on_final, noop_error(), noop_connection_quality())
+        .await
+        .unwrap();
+
+    for _ in 0..4 {
+        provider.send_audio(&speech_chunk()).await.unwrap();
+    }
+
+    wait_for(|| !finals.lock().unwrap().is_empty()).await;
+    provider.stop_stream().await.unwrap();
+
+    assert_eq!(*finals.lock().unwrap(), vec!["hello from mock server".to_string()]);
+    assert_eq!(
+        *partials.lock().unwrap(),
+        vec!["hello".to_string(), "hello from".to_string(), "hello from mock".to_string()]
+    );
+
+    let stats = server.stats().await;
+    assert_eq!(stats.connections, 1);
+    assert_eq!(stats.audio_bytes, 4 * 3200);
+    assert_eq!(stats.last_authorization.as_deref(), Some("Token mock-key"));
+    let uri = stats.last_request_uri.unwrap();
+    assert!(uri.contains("encoding=linear16"), "uri: {}", uri);
+    assert!(uri.contains("language=en"), "uri: {}", uri);
+    assert!(stats.text_messages.iter().any(|m| m.contains("CloseStream")));
+}
+
+#[tokio::test]
+async fn test_deepgram_final_on_close_stream() {
+    // Скрипт длиннее отправленного аудио — final должен прийти по CloseStream
+    let server = MockSttServer::start(
+        MockSttProtocol::Deepgram,
+        MockSttScript::new("one two three four five six"),
+    )
+    .await
+    .unwrap();
+
+    let mut provider = DeepgramProvider::new().with_endpoint(server.url());
+    let config = SttConfig::new(SttProviderType::Deepgram).with_api_key("mock-key");
+    provider.initialize(&config).await.unwrap();
+
+    let (_partials, on_partial) = collector();
+    let (finals, on_final) = collector();
+    provider
+        .start_stream(on_partial, on_final, noop_error(), noop_connection_quality())
+        .await
+        .unwrap();
+
+    provider.send_audio(&speech_chunk()).await.unwrap();
+    provider.stop_stream().await.unwrap();
+
+    assert_eq!(*finals.lock().unwrap(), vec!["one two three four five six".to_string()]);
+}
+
+#[tokio::test]
+async fn test_deepgram_flac_encoding_against_mock_server() {
+    let server = MockSttServer::start(
+        MockSttProtocol::Deepgram,
+        MockSttScript::new("compressed audio works"),
+    )
+    .await
+    .unwrap();
+
+    let mut provider = DeepgramProvider::new().with_endpoint(server.url());
+    let config = SttConfig::new(SttProviderType::Deepgram)
+        .with_api_key("mock-key")
+        .with_audio_encoding(AudioEncoding::Flac);
+    provider.initialize(&config).await.unwrap();
+
+    let (_partials, on_partial) = collector();
+    let (finals, on_final) = collector();
+    provider
+        .start_stream(on_partial, on_final, noop_error(), noop_connection_quality())
+        .await
+        .unwrap();
+
+    for _ in 0..4 {
+        provider.send_audio(&speech_chunk()).await.unwrap();
+    }
+    provider.stop_stream().await.unwrap();
+
+    assert_eq!(*finals.lock().unwrap(), vec!["compressed audio works".to_string()]);
+
+    let stats = server.stats().await;
+    assert!(stats.last_request_uri.unwrap().contains("encoding=flac"));
+    // FLAC должен быть заметно меньше linear16 (4 * 3200 байт)
+    assert!(stats.audio_bytes > 0 && stats.audio_bytes < 4 * 3200, "bytes: {}", stats.audio_bytes);
+}
+
+#[tokio::test]
+async fn test_assemblyai_against_mock_server() {
+    let server = MockSttServer::start(
+        MockSttProtocol::AssemblyAI,
+        MockSttScript::new("привет из мока"),
+    )
+    .await
+    .unwrap();
+
+    let mut provider = AssemblyAIProvider::new().with_endpoint(server.url());
+    let config = SttConfig::new(SttProviderType::AssemblyAI)
+        .with_language("ru")
+        .with_api_key("mock-key");
+    provider.initialize(&config).await.unwrap();
+
+    let (partials, on_partial) = collector();
+    let (finals, on_final) = collector();
+    provider
+        .start_stream(on_partial, on_final, noop_error(), noop_connection_quality())
+        .await
+        .unwrap();
+
+    for _ in 0..3 {
+        provider.send_audio(&speech_chunk()).await.unwrap();
+    }
+
+    wait_for(|| !finals.lock().unwrap().is_empty()).await;
+    provider.stop_stream().await.unwrap();
+
+    assert_eq!(*finals.lock().unwrap(), vec!["привет из мока".to_string()]);
+    assert_eq!(partials.lock().unwrap().len(), 2);
+
+    let stats = server.stats().await;
+    assert_eq!(stats.last_authorization.as_deref(), Some("mock-key"));
+    assert!(stats.last_request_uri.unwrap().contains("language_code=ru"));
+}
+
+#[tokio::test]
+async fn test_backend_against_mock_server() {
+    let server = MockSttServer::start(
+        MockSttProtocol::Backend,
+        MockSttScript::new("backend mock text").with_partial_every_bytes(960),
+    )
+    .await
+    .unwrap();
+
+    let mut provider = BackendProvider::new();
+    let mut config = SttConfig::new(SttProviderType::Backend);
+    config.backend_url = Some(server.url());
+    provider.initialize(&config).await.unwrap();
+
+    let (partials, on_partial) = collector();
+    let (finals, on_final) = collector();
+    provider
+        .start_stream(on_partial, on_final, noop_error(), noop_connection_quality())
+        .await
+        .unwrap();
+
+    for _ in 0..6 {
+        provider.send_audio(&speech_chunk()).await.unwrap();
+    }
+
+    wait_for(|| !finals.lock().unwrap().is_empty()).await;
+    provider.stop_stream().await.unwrap();
+
+    assert_eq!(*finals.lock().unwrap(), vec!["backend mock text".to_string()]);
+    assert!(!partials.lock().unwrap().is_empty());
+
+    let stats = server.stats().await;
+    assert!(stats.text_messages[0].contains(r#""type":"config""#));
+    assert!(stats.last_request_uri.unwrap().ends_with("/api/v1/transcribe/stream"));
+}
+
+#[tokio::test]
+async fn test_backend_handshake_limit_exceeded() {
+    let server = MockSttServer::start(
+        MockSttProtocol::Backend,
+        MockSttScript::new("unused").reject_handshake(429, Some("LIMIT_EXCEEDED")),
+    )
+    .await
+    .unwrap();
+
+    let mut provider = BackendProvider::new();
+    let mut config = SttConfig::new(SttProviderType::Backend);
+    config.backend_url = Some(server.url());
+    provider.initialize(&config).await.unwrap();
+
+    let (_p, on_partial) = collector();
+    let (_f, on_final) = collector();
+    let err = provider
+        .start_stream(on_partial, on_final, noop_error(), noop_connection_quality())
+        .await
+        .unwrap_err();
+
+    match err {
+        SttError::QuotaExceeded { details, .. } => {
+            assert_eq!(details.http_status, Some(429));
+            assert_eq!(details.category, Some(SttConnectionCategory::LimitExceeded));
+        }
+        other => panic!("expected quota error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_backend_handshake_unauthorized() {
+    let server = MockSttServer::start(
+        MockSttProtocol::Backend,
+        MockSttScript::new("unused").reject_handshake(401, None),
+    )
+    .await
+    .unwrap();
+
+    let mut provider = BackendProvider::new();
+    let mut config = SttConfig::new(SttProviderType::Backend);
+    config.backend_url = Some(server.url());
+    provider.initialize(&config).await.unwrap();
+
+    let (_p, on_partial) = collector();
+    let (_f, on_final) = collector();
+    let err = provider
+        .start_stream(on_partial, on_final, noop_error(), noop_connection_quality())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, SttError::Authentication(_)), "got {:?}", err);
+}
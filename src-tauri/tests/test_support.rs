@@ -18,6 +18,9 @@ pub fn classify_error_type(err: &SttError) -> &'static str {
         SttError::Authentication(_) => "authentication",
         SttError::Configuration(_) => "configuration",
         SttError::Processing(_) | SttError::Unsupported(_) | SttError::Internal(_) => "processing",
+        SttError::ProviderBug(_) => "provider_bug",
+        SttError::QuotaExceeded { .. } => "limit_exceeded",
+        SttError::RateLimited { .. } => "rate_limited",
         SttError::Connection(conn) => match conn.details.category {
             Some(SttConnectionCategory::Timeout) => "timeout",
             Some(SttConnectionCategory::LimitExceeded) => "limit_exceeded",
@@ -66,6 +66,7 @@ impl MockSttProvider {
                 timestamp: 0,
                 start: 0.0,
                 duration: 0.0,
+                alternatives: Vec::new(),
             });
         }
     }
@@ -80,6 +81,7 @@ impl MockSttProvider {
                 timestamp: 0,
                 start: 0.0,
                 duration: 0.0,
+                alternatives: Vec::new(),
             });
         }
     }
@@ -0,0 +1,40 @@
+import { listen } from '@tauri-apps/api/event';
+import {
+  EVENT_TRANSCRIPTION_FINAL,
+  EVENT_TRANSCRIPTION_PARTIAL,
+  type FinalTranscriptionPayload,
+  type PartialTranscriptionPayload,
+} from '../types';
+import './captions.css';
+
+// Окно живых субтитров: только слушает события, команд не вызывает.
+// Окно создаёт/уничтожает Rust (start_live_captions / stop_live_captions),
+// размер шрифта приходит в query (?fontSize=28), высоту окна под нужное число строк задаёт Rust.
+
+// Сколько финального текста держим для "прокрутки" (видны только последние строки)
+const MAX_FINAL_CHARS = 600;
+
+const params = new URLSearchParams(location.search);
+const root = document.documentElement;
+root.style.setProperty('--captions-font-size', `${Number(params.get('fontSize')) || 28}px`);
+
+const finalEl = document.getElementById('final') as HTMLElement;
+const partialEl = document.getElementById('partial') as HTMLElement;
+
+let finalText = '';
+
+function render(partial: string): void {
+  finalEl.textContent = finalText;
+  partialEl.textContent = partial ? `${finalText ? ' ' : ''}${partial}` : '';
+}
+
+void listen<PartialTranscriptionPayload>(EVENT_TRANSCRIPTION_PARTIAL, (event) => {
+  render(event.payload.text);
+});
+
+void listen<FinalTranscriptionPayload>(EVENT_TRANSCRIPTION_FINAL, (event) => {
+  const text = event.payload.text.trim();
+  if (!text) return;
+  finalText = `${finalText} ${text}`.trim().slice(-MAX_FINAL_CHARS);
+  render('');
+});
@@ -14,6 +14,8 @@ export interface LanguageOption {
 export interface ProviderOption {
   value: SttProviderType;
   label: string;
+  /** Облачный провайдер без сети */
+  disabled?: boolean;
 }
 
 // Опция аудио устройства
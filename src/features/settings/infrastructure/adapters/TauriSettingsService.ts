@@ -14,10 +14,14 @@ import {
 } from '@/windowing/stateSync';
 import type { UpdateAppConfigInvokeArgs, UpdateSttConfigInvokeArgs } from '@/windowing/stateSync';
 import type { AppConfigSnapshotData, SttConfigSnapshotData, TauriSnapshotEnvelope } from '@/windowing/stateSync';
+import type { PermissionCapability, PermissionStatus } from '@/types';
 
 // Payload события уровня громкости
 interface MicrophoneLevelPayload {
   level: number;
+  clipping: boolean; // вход клиппирует при текущем усилении
+  too_quiet: boolean; // слишком тихо даже после усиления
+  peak_dbfs: number;
 }
 
 class TauriSettingsService {
@@ -121,6 +125,16 @@ class TauriSettingsService {
     await invoke('request_accessibility_permission');
   }
 
+  // Все разрешения macOS (Input Monitoring, Screen Recording и т.д.)
+
+  async checkPermissions(): Promise<PermissionStatus[]> {
+    return invoke<PermissionStatus[]>('check_permissions');
+  }
+
+  async openPermissionSettings(capability: PermissionCapability): Promise<void> {
+    await invoke('open_permission_settings', { capability });
+  }
+
   // Whisper модели
 
   async checkWhisperModel(modelName: string): Promise<boolean> {
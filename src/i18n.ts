@@ -34,6 +34,7 @@ const messages = {
         hintCloudTitle: 'AssemblyAI and Deepgram:',
         hintCloudBody: 'cloud services with high quality.',
         hintDeepgramNote: 'Uses Deepgram Nova-3 model with support for 47+ languages.',
+        offlineNotice: 'No internet connection: cloud providers are unavailable, recording uses local Whisper.',
       },
       language: {
         label: 'Language',
@@ -217,10 +218,12 @@ const messages = {
       connectionTls: 'Secure connection failed (TLS/certificate). Check system time or proxy/VPN.',
       connectionHttp: 'Server returned an error ({status}). Try again later.',
       rateLimited: 'Too many active sessions. Please wait and try again.',
+      rateLimitedRetryIn: 'Too many requests. Retry available in {seconds}s.',
       limitExceeded: 'Usage limit reached. Upgrade your plan to continue.',
       limitExceededDetailed: 'Usage limit reached ({used}/{total} min, {plan}). Activate a license to continue.',
       authentication: 'Authentication error. Sign in again or check the keys in Settings.',
       processing: 'Audio processing error. Try restarting the recording.',
+      providerError: 'The speech recognition service returned an unexpected response. Try again; if it repeats, update the app.',
       audioDeviceUnavailable:
         'Microphone is unavailable. Reconnect it and select it in your system sound settings, then try again.',
       audioDeviceNotFound:
@@ -373,6 +376,7 @@ const messages = {
         hintCloudTitle: 'AssemblyAI и Deepgram:',
         hintCloudBody: 'облачные сервисы с высоким качеством.',
         hintDeepgramNote: 'Используется модель Deepgram Nova-3 с поддержкой 47+ языков.',
+        offlineNotice: 'Нет интернета: облачные провайдеры недоступны, запись идёт через локальный Whisper.',
       },
       language: {
         label: 'Язык',
@@ -556,10 +560,12 @@ const messages = {
       connectionTls: 'Не удалось установить защищённое соединение (TLS/сертификат). Проверьте системное время, VPN/прокси.',
       connectionHttp: 'Сервер вернул ошибку ({status}). Попробуйте позже.',
       rateLimited: 'Слишком много активных сессий. Подождите и попробуйте снова.',
+      rateLimitedRetryIn: 'Слишком много запросов. Повтор через {seconds} с.',
       limitExceeded: 'Лимит использования исчерпан. Обновите тариф для продолжения.',
       limitExceededDetailed: 'Лимит исчерпан ({used}/{total} мин, {plan}). Активируйте лицензию для продолжения.',
       authentication: 'Ошибка авторизации. Войдите заново или проверьте ключи в настройках.',
       processing: 'Ошибка обработки аудио. Попробуйте перезапустить запись.',
+      providerError: 'Сервис распознавания вернул неожиданный ответ. Попробуйте снова; если повторяется — обновите приложение.',
       audioDeviceUnavailable:
         'Микрофон недоступен. Подключите/переподключите его и выберите в настройках звука системы, затем попробуйте снова.',
       audioDeviceNotFound:
@@ -712,6 +718,7 @@ const messages = {
         hintCloudTitle: 'AssemblyAI y Deepgram:',
         hintCloudBody: 'servicios en la nube con alta calidad.',
         hintDeepgramNote: 'Utiliza el modelo Deepgram Nova-3 con soporte para más de 47 idiomas.',
+        offlineNotice: 'Sin conexión a internet: los proveedores en la nube no están disponibles, se graba con Whisper local.',
       },
       language: {
         label: 'Idioma',
@@ -895,10 +902,12 @@ const messages = {
       connectionTls: 'Falló la conexión segura (TLS/certificado). Compruebe la hora del sistema o proxy/VPN.',
       connectionHttp: 'El servidor devolvió un error ({status}). Inténtelo más tarde.',
       rateLimited: 'Demasiadas sesiones activas. Espere e inténtelo de nuevo.',
+      rateLimitedRetryIn: 'Demasiadas solicitudes. Reintento disponible en {seconds} s.',
       limitExceeded: 'Límite de uso alcanzado. Actualice su plan para continuar.',
       limitExceededDetailed: 'Límite alcanzado ({used}/{total} min, {plan}). Active una licencia para continuar.',
       authentication: 'Error de autenticación. Inicie sesión de nuevo o compruebe las claves en ajustes.',
       processing: 'Error de procesamiento de audio. Reinicie la grabación.',
+      providerError: 'El servicio de reconocimiento devolvió una respuesta inesperada. Inténtelo de nuevo; si se repite, actualice la aplicación.',
       audioDeviceUnavailable:
         'El micrófono no está disponible. Vuelva a conectarlo y selecciónelo en la configuración de sonido del sistema, luego inténtelo de nuevo.',
       audioDeviceNotFound:
@@ -1051,6 +1060,7 @@ const messages = {
         hintCloudTitle: 'AssemblyAI et Deepgram:',
         hintCloudBody: 'services cloud de haute qualité.',
         hintDeepgramNote: 'Utilise le modèle Deepgram Nova-3 avec prise en charge de plus de 47 langues.',
+        offlineNotice: 'Pas de connexion Internet : les fournisseurs cloud sont indisponibles, l\'enregistrement utilise Whisper local.',
       },
       language: {
         label: 'Langue',
@@ -1238,6 +1248,7 @@ const messages = {
       limitExceededDetailed: 'Limite atteinte ({used}/{total} min, {plan}). Activez une licence pour continuer.',
       authentication: "Erreur d'authentification. Reconnectez-vous ou vérifiez les clés dans les paramètres.",
       processing: "Erreur de traitement audio. Redémarrez l'enregistrement.",
+      providerError: "Le service de reconnaissance a renvoyé une réponse inattendue. Réessayez ; si cela se répète, mettez à jour l'application.",
       audioDeviceUnavailable:
         'Le microphone est indisponible. Reconnectez-le et sélectionnez-le dans les réglages audio du système, puis réessayez.',
       audioDeviceNotFound:
@@ -1390,6 +1401,7 @@ const messages = {
         hintCloudTitle: 'AssemblyAI und Deepgram:',
         hintCloudBody: 'Cloud-Dienste mit hoher Qualität.',
         hintDeepgramNote: 'Verwendet das Deepgram Nova-3-Modell mit Unterstützung für über 47 Sprachen.',
+        offlineNotice: 'Keine Internetverbindung: Cloud-Anbieter sind nicht verfügbar, die Aufnahme nutzt lokales Whisper.',
       },
       language: {
         label: 'Sprache',
@@ -1577,6 +1589,7 @@ const messages = {
       limitExceededDetailed: 'Limit erreicht ({used}/{total} Min, {plan}). Aktivieren Sie eine Lizenz, um fortzufahren.',
       authentication: 'Authentifizierungsfehler. Bitte erneut anmelden oder die Schlüssel in den Einstellungen prüfen.',
       processing: 'Audioverarbeitungsfehler. Aufnahme neu starten.',
+      providerError: 'Der Erkennungsdienst hat eine unerwartete Antwort geliefert. Erneut versuchen; falls es sich wiederholt, die App aktualisieren.',
       audioDeviceUnavailable:
         'Mikrofon nicht verfügbar. Bitte neu verbinden und in den Sound-Einstellungen des Systems auswählen, dann erneut versuchen.',
       audioDeviceNotFound:
@@ -1729,6 +1742,7 @@ const messages = {
         hintCloudTitle: 'AssemblyAI та Deepgram:',
         hintCloudBody: 'хмарні сервіси з високою якістю.',
         hintDeepgramNote: 'Використовується модель Deepgram Nova-3 з підтримкою 47+ мов.',
+        offlineNotice: 'Немає інтернету: хмарні провайдери недоступні, запис іде через локальний Whisper.',
       },
       language: {
         label: 'Мова',
@@ -1916,6 +1930,7 @@ const messages = {
       limitExceededDetailed: 'Ліміт вичерпано ({used}/{total} хв, {plan}). Активуйте ліцензію для продовження.',
       authentication: 'Помилка автентифікації. Увійдіть знову або перевірте ключі в налаштуваннях.',
       processing: 'Помилка обробки аудіо. Спробуйте перезапустити запис.',
+      providerError: 'Сервіс розпізнавання повернув неочікувану відповідь. Спробуйте ще раз; якщо повторюється — оновіть застосунок.',
       audioDeviceUnavailable:
         'Мікрофон недоступний. Під’єднайте/перепід’єднайте його та виберіть у налаштуваннях звуку системи, потім спробуйте ще раз.',
       generic: 'Помилка: {error}',
@@ -0,0 +1,35 @@
+import { listen } from '@tauri-apps/api/event';
+import { EVENT_RECORDING_STATUS, RecordingStatus, type RecordingStatusPayload } from '../types';
+import './overlay.css';
+
+// Оверлей-"пилюля" записи: только слушает события, команд не вызывает.
+// Окно создаёт/уничтожает Rust (start_recording / stop_recording).
+
+const EVENT_AUDIO_LEVEL = 'audio:level';
+
+// Разная "чувствительность" столбиков, чтобы уровень выглядел живым
+const BAR_WEIGHTS = [0.5, 0.8, 1, 0.8, 0.5];
+
+const pill = document.getElementById('pill') as HTMLElement;
+const bars = Array.from(document.querySelectorAll<HTMLElement>('.bar'));
+
+function renderLevel(level: number): void {
+  const clamped = Math.max(0, Math.min(1, level));
+  bars.forEach((bar, i) => {
+    const scale = 0.2 + 0.8 * clamped * (BAR_WEIGHTS[i] ?? 1);
+    bar.style.transform = `scaleY(${scale.toFixed(2)})`;
+  });
+}
+
+void listen<{ level: number }>(EVENT_AUDIO_LEVEL, (event) => {
+  renderLevel(event.payload.level);
+});
+
+void listen<RecordingStatusPayload>(EVENT_RECORDING_STATUS, (event) => {
+  pill.dataset.status = event.payload.status;
+  if (event.payload.status !== RecordingStatus.Recording) {
+    renderLevel(0);
+  }
+});
+
+renderLevel(0);
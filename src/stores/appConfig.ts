@@ -19,6 +19,7 @@ export const useAppConfigStore = defineStore('appConfig', () => {
   const autoPasteText = ref(false);
   const microphoneSensitivity = ref(100);
   const selectedAudioDevice = ref('');
+  const nativeSoundsEnabled = ref(false);
 
   let syncHandle: RevisionSyncHandle | null = null;
 
@@ -29,6 +30,7 @@ export const useAppConfigStore = defineStore('appConfig', () => {
     autoPasteText.value = data.auto_paste_text ?? autoPasteText.value;
     microphoneSensitivity.value = data.microphone_sensitivity ?? microphoneSensitivity.value;
     selectedAudioDevice.value = data.selected_audio_device ?? '';
+    nativeSoundsEnabled.value = data.feedback?.sounds_enabled ?? false;
     isLoaded.value = true;
   }
 
@@ -82,6 +84,7 @@ export const useAppConfigStore = defineStore('appConfig', () => {
     autoPasteText,
     microphoneSensitivity,
     selectedAudioDevice,
+    nativeSoundsEnabled,
 
     hasSelectedAudioDevice: computed(() => Boolean(selectedAudioDevice.value)),
 
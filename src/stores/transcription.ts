@@ -3,6 +3,7 @@ import { ref, computed, watch } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { isTauriAvailable } from '../utils/tauri';
+import { joinSegments, PARAGRAPH_BREAK, startsParagraph } from '../utils/segments';
 import { i18n } from '../i18n';
 import { api } from '../features/auth/infrastructure/api/apiClient';
 import { useAuthStore } from '../features/auth/store/authStore';
@@ -18,11 +19,13 @@ import {
   RecordingStatusPayload,
   TranscriptionErrorPayload,
   ConnectionQualityPayload,
+  RetryCountdownPayload,
   EVENT_TRANSCRIPTION_PARTIAL,
   EVENT_TRANSCRIPTION_FINAL,
   EVENT_RECORDING_STATUS,
   EVENT_TRANSCRIPTION_ERROR,
   EVENT_CONNECTION_QUALITY,
+  EVENT_TRANSCRIPTION_RETRY_COUNTDOWN,
 } from '../types';
 
 export const useTranscriptionStore = defineStore('transcription', () => {
@@ -47,6 +50,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
   const errorType = ref<TranscriptionErrorPayload['error_type'] | null>(null);
   const lastFinalizedText = ref<string>(''); // последний финализированный текст (для дедупликации)
   const connectionQuality = ref<ConnectionQuality>(ConnectionQuality.Good);
+  // Секунд до повтора после rate limit (Retry-After провайдера); null — отсчёта нет
+  const retryCountdownSecs = ref<number | null>(null);
+  // Сессия, для которой показана ошибка rate limit с отсчётом
+  let rateLimitCountdownSessionId: number | null = null;
 
   // Retry логика подключения (когда запись ещё не стартанула и мы пытаемся подключиться к STT)
   const isConnecting = ref<boolean>(false);
@@ -134,6 +141,9 @@ export const useTranscriptionStore = defineStore('transcription', () => {
   // Хранит значение finalText на момент последней успешной вставки
   const lastPastedFinalText = ref<string>('');
 
+  // Instant paste: сегменты сессии уже вставляет backend — свою вставку пропускаем
+  const backendPastesSession = ref<boolean>(false);
+
   // Отслеживание utterances по start времени
   const currentUtteranceStart = ref<number>(-1); // start время текущей utterance (-1 = нет активной)
 
@@ -152,6 +162,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
   let unlistenStatus: UnlistenFn | null = null;
   let unlistenError: UnlistenFn | null = null;
   let unlistenConnectionQuality: UnlistenFn | null = null;
+  let unlistenRetryCountdown: UnlistenFn | null = null;
 
   function bumpLastSeenSessionId(next: number): void {
     if (next > lastSeenSessionId.value) {
@@ -508,6 +519,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
             return;
           }
 
+          if (event.payload.instant_paste) {
+            backendPastesSession.value = true;
+          }
+
           // Детальное логирование для отладки
           console.log('📝 PARTIAL EVENT:', {
             text: event.payload.text,
@@ -536,9 +551,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
             console.log('🔒 [BEFORE ACCUMULATE] accumulated:', oldAccumulated);
             console.log('🔒 [BEFORE ACCUMULATE] newText:', newText);
 
-            accumulatedText.value = accumulatedText.value
-              ? `${accumulatedText.value} ${newText}`
-              : newText;
+            accumulatedText.value = joinSegments(accumulatedText.value, newText);
 
             lastFinalizedText.value = newText;
 
@@ -596,9 +609,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
                 console.log('💾 [BEFORE SAVE] finalText:', oldFinalText);
                 console.log('💾 [BEFORE SAVE] accumulated:', accumulatedText.value);
 
-                finalText.value = finalText.value
-                  ? `${finalText.value} ${accumulatedText.value}`
-                  : accumulatedText.value;
+                finalText.value = joinSegments(finalText.value, accumulatedText.value);
 
                 console.log('💾 [AFTER SAVE] finalText:', finalText.value);
                 console.log('💾 Successfully saved accumulated text to finalText');
@@ -629,6 +640,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
             return;
           }
 
+          if (event.payload.instant_paste) {
+            backendPastesSession.value = true;
+          }
+
           // Детальное логирование для отладки
           console.log('✅ FINAL EVENT (speech_final=true):', {
             text: event.payload.text,
@@ -656,10 +671,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
           // РЕШЕНИЕ: ВСЕГДА добавляем accumulated к FINAL тексту (если есть).
           // Дублирования не будет, т.к. accumulated очищается только при сохранении в finalText.
           if (event.payload.text || accumulatedText.value || partialText.value) {
-            const currentUtteranceText = [accumulatedText.value, event.payload.text || partialText.value]
-              .filter(Boolean)
-              .join(' ')
-              .trim();
+            const currentUtteranceText = joinSegments(accumulatedText.value, event.payload.text || partialText.value);
 
             console.log('🔗 [SPEECH_FINAL] Combining utterance:', {
               accumulated: accumulatedText.value,
@@ -700,21 +712,23 @@ export const useTranscriptionStore = defineStore('transcription', () => {
             }
 
             // Добавляем к финальному тексту
-            finalText.value = finalText.value
-              ? `${finalText.value} ${currentUtteranceText}`
-              : currentUtteranceText;
+            finalText.value = joinSegments(finalText.value, currentUtteranceText);
 
             console.log('📋 [AFTER ADD] finalText:', finalText.value);
             console.log('📋 Successfully added utterance to finalText');
 
             // Auto-paste финальной фразы (вся utterance целиком)
-            if (autoPasteEnabled.value && currentUtteranceText.trim()) {
+            if (autoPasteEnabled.value && !backendPastesSession.value && currentUtteranceText.trim()) {
               // Защита от дубликатов: проверяем что мы еще не вставляли эту версию finalText
               if (finalText.value !== lastPastedFinalText.value) {
                 try {
-                  // Добавляем пробел перед фразой если это не первая фраза
+                  // Добавляем пробел перед фразой если это не первая фраза (фраза с нового абзаца
+                  // уже начинается с разрыва; перед первой фразой разрыв не нужен)
                   const needsSpace = oldFinalText.length > 0;
-                  const textToInsert = needsSpace ? ` ${currentUtteranceText}` : currentUtteranceText;
+                  const paragraph = startsParagraph(currentUtteranceText);
+                  const textToInsert = paragraph
+                    ? needsSpace ? currentUtteranceText : currentUtteranceText.trimStart()
+                    : needsSpace ? ` ${currentUtteranceText}` : currentUtteranceText;
                   console.log('📝 Auto-pasting final utterance:', textToInsert);
                   await invoke('auto_paste_text', { text: textToInsert });
                   console.log('✅ Auto-pasted successfully');
@@ -837,6 +851,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
 
             // Сбрасываем флаг auto-paste
             lastPastedFinalText.value = '';
+            backendPastesSession.value = false;
 
             // Очищаем анимированный текст
             animatedPartialText.value = '';
@@ -890,18 +905,19 @@ export const useTranscriptionStore = defineStore('transcription', () => {
               }
 
               // Auto-paste: вставляем только НОВУЮ часть
-              if (autoPasteEnabled.value) {
+              if (autoPasteEnabled.value && !backendPastesSession.value) {
                 // Определяем что нужно вставить (только новое)
                 let textToInsert = currentText;
 
                 if (lastPastedFinalText.value) {
                   // Если уже что-то вставляли, вставляем только новую часть
                   if (currentText.startsWith(lastPastedFinalText.value)) {
-                    textToInsert = currentText.slice(lastPastedFinalText.value.length).trim();
+                    const rest = currentText.slice(lastPastedFinalText.value.length);
+                    textToInsert = rest.trim();
 
-                    // Добавляем пробел если нужно
+                    // Добавляем пробел (или разрыв абзаца) если нужно
                     if (textToInsert && lastPastedFinalText.value) {
-                      textToInsert = ' ' + textToInsert;
+                      textToInsert = (startsParagraph(rest) ? PARAGRAPH_BREAK : ' ') + textToInsert;
                     }
                   }
                 }
@@ -923,9 +939,11 @@ export const useTranscriptionStore = defineStore('transcription', () => {
               }
             }
 
-            // UX: после остановки через hotkey окно сразу скрывается.
+            // UX: после остановки через hotkey окно сразу скрывается (window_behavior_on_stop по умолчанию).
             // Следующее открытие должно начинаться с "чистого листа", без текста прошлой сессии.
-            if (event.payload.stopped_via_hotkey) {
+            // Если окно остаётся (пусть и на время) — текст сессии не трогаем.
+            const hidesImmediately = (event.payload.window_behavior?.mode ?? 'hide_immediately') === 'hide_immediately';
+            if (event.payload.stopped_via_hotkey && hidesImmediately) {
               resetTextStateBeforeStart();
             }
           }
@@ -1054,7 +1072,9 @@ export const useTranscriptionStore = defineStore('transcription', () => {
 
             if (wasStarting && rateLimitRetryCount < RATE_LIMIT_MAX_RETRIES) {
               rateLimitRetryCount++;
-              const delaySec = serverCode === 'TOO_MANY_SESSIONS' ? 2 : 5;
+              // Retry-After от провайдера точнее наших эвристик
+              const delaySec = event.payload.retry_after_secs
+                ?? (serverCode === 'TOO_MANY_SESSIONS' ? 2 : 5);
               console.warn(`[STT] 429 (${serverCode ?? 'unknown'}), auto-retry #${rateLimitRetryCount} через ${delaySec}с`);
               suppressNextErrorStatus = true;
               status.value = RecordingStatus.Starting;
@@ -1071,7 +1091,11 @@ export const useTranscriptionStore = defineStore('transcription', () => {
                 console.warn(`[STT] 429 retry limit reached (${RATE_LIMIT_MAX_RETRIES}), showing error`);
               }
               rateLimitRetryCount = 0;
-              error.value = mapErrorMessage('connection', event.payload.error, event.payload.error_details);
+              const retryAfter = event.payload.retry_after_secs;
+              rateLimitCountdownSessionId = retryAfter ? event.payload.session_id : null;
+              error.value = retryAfter
+                ? i18n.global.t('errors.rateLimitedRetryIn', { seconds: retryAfter })
+                : mapErrorMessage('connection', event.payload.error, event.payload.error_details);
               errorType.value = 'connection';
               status.value = RecordingStatus.Error;
             }
@@ -1144,7 +1168,9 @@ export const useTranscriptionStore = defineStore('transcription', () => {
             return;
           }
 
-          error.value = mapErrorMessage(normalizedType, event.payload.error, event.payload.error_details);
+          error.value =
+            messageFromKey(event.payload) ??
+            mapErrorMessage(normalizedType, event.payload.error, event.payload.error_details);
           errorType.value = normalizedType;
           isDeviceNotFoundError.value =
             normalizedType === 'configuration' && isDeviceNotFoundInRaw(event.payload.error);
@@ -1160,7 +1186,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
             return;
           }
 
-          console.log('Connection quality changed:', event.payload.quality, event.payload.reason);
+          console.log('Connection quality changed:', event.payload.quality, event.payload.reason_code ?? event.payload.reason);
           connectionQuality.value = event.payload.quality;
 
           // Сбрасываем connection quality обратно в Good когда запись останавливается
@@ -1171,6 +1197,25 @@ export const useTranscriptionStore = defineStore('transcription', () => {
         }
       );
 
+      unlistenRetryCountdown = await listen<RetryCountdownPayload>(
+        EVENT_TRANSCRIPTION_RETRY_COUNTDOWN,
+        (event) => {
+          const { session_id, remaining_secs } = event.payload;
+          retryCountdownSecs.value = remaining_secs > 0 ? remaining_secs : null;
+
+          // Ошибка с отсчётом всё ещё на экране — обновляем секунды
+          if (rateLimitCountdownSessionId !== session_id || status.value !== RecordingStatus.Error) {
+            return;
+          }
+          error.value = remaining_secs > 0
+            ? i18n.global.t('errors.rateLimitedRetryIn', { seconds: remaining_secs })
+            : i18n.global.t('errors.rateLimited');
+          if (remaining_secs === 0) {
+            rateLimitCountdownSessionId = null;
+          }
+        }
+      );
+
       console.log('Event listeners initialized successfully');
     } catch (err) {
       console.error('Failed to initialize event listeners:', err);
@@ -1364,6 +1409,12 @@ export const useTranscriptionStore = defineStore('transcription', () => {
     return i18n.global.t('errors.connection');
   }
 
+  function messageFromKey(payload: TranscriptionErrorPayload): string | null {
+    const key = payload.message_key ? `errors.${payload.message_key}` : null;
+    if (!key || !i18n.global.te(key)) return null;
+    return i18n.global.t(key, { status: payload.error_details?.httpStatus ?? '' });
+  }
+
   function mapErrorMessage(
     type: TranscriptionErrorPayload['error_type'] | null,
     raw: string,
@@ -1560,6 +1611,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
 
     // Сбрасываем флаг auto-paste
     lastPastedFinalText.value = '';
+    backendPastesSession.value = false;
 
     // Очищаем анимированный текст
     animatedPartialText.value = '';
@@ -1814,6 +1866,10 @@ export const useTranscriptionStore = defineStore('transcription', () => {
       unlistenConnectionQuality();
       unlistenConnectionQuality = null;
     }
+    if (unlistenRetryCountdown) {
+      unlistenRetryCountdown();
+      unlistenRetryCountdown = null;
+    }
 
     // Очищаем таймеры анимации
     if (partialAnimationTimer) {
@@ -1835,6 +1891,7 @@ export const useTranscriptionStore = defineStore('transcription', () => {
     error,
     errorType,
     connectionQuality,
+    retryCountdownSecs,
 
     // Computed
     isStarting,
@@ -14,6 +14,8 @@ export interface Transcription {
   confidence?: number;
   language?: string;
   timestamp: number;
+  calendar?: SessionCalendarTag | null; // встреча из календаря на момент начала записи
+  app?: AppContext | null; // приложение, в котором начата запись (app capture_app_context)
 }
 
 export interface PartialTranscriptionPayload {
@@ -23,6 +25,8 @@ export interface PartialTranscriptionPayload {
   is_segment_final: boolean; // true когда сегмент финализирован (но речь продолжается)
   start: number; // start время utterance в секундах (от Deepgram)
   duration: number; // длительность utterance в секундах (от Deepgram)
+  channel?: number; // канал стерео-интервью (stt split_channels): 0 — левый, 1 — правый
+  instant_paste?: boolean; // текст сессии вставляет backend (app instant_paste)
 }
 
 export interface FinalTranscriptionPayload {
@@ -31,12 +35,55 @@ export interface FinalTranscriptionPayload {
   confidence?: number;
   language?: string;
   timestamp: number;
+  speaker?: number; // при включённой диаризации (stt diarize)
+  channel?: number; // канал стерео-интервью (stt split_channels): 0 — левый, 1 — правый
+  calendar?: SessionCalendarTag; // при включённых названиях по календарю (calendar.enabled)
+  app?: AppContext; // при включённом контексте приложения (app capture_app_context)
+  instant_paste?: boolean; // текст сессии вставляет backend (app instant_paste)
 }
 
+// Что станет с окном записи после остановки хоткеем (app window_behavior_on_stop)
+export type WindowBehaviorOnStop =
+  | { mode: 'keep_window' }
+  | { mode: 'hide_immediately' }
+  | { mode: 'hide_after_paste' }
+  | { mode: 'hide_after_delay'; secs: number };
+
 export interface RecordingStatusPayload {
   session_id: number;
   status: RecordingStatus;
   stopped_via_hotkey?: boolean;
+  window_behavior?: WindowBehaviorOnStop; // только при остановке хоткеем; окно скрывает backend
+}
+
+export type FocusMode = 'macos_focus' | 'windows_focus_assist' | 'linux_do_not_disturb';
+
+// Запись хоткеем во время режима фокусировки ОС: окно не показывается, звуков нет
+export interface RecordingQuietPayload {
+  session_id: number;
+  focus_mode: FocusMode;
+}
+
+export interface RecordingTickPayload {
+  session_id: number;
+  elapsed_ms: number;
+  estimated_cost_usd: number | null; // null — минуты списываются с тарифа (Backend)
+  quota_remaining_secs: number | null; // null — UsageUpdate ещё не приходил
+}
+
+// get_debug_state: диагностика "зависшей" записи
+export interface TranscriptionDebugState {
+  status: RecordingStatus;
+  provider: string | null; // null — стрим не открыт
+  provider_locked: boolean; // провайдер занят > 500ms (обычно висит отправка)
+  streaming: boolean;
+  paused: boolean;
+  buffered_samples: number;
+  receiver_alive: boolean | null; // null — у провайдера нет задачи чтения ответов
+  sent_chunks: number;
+  sent_bytes: number;
+  last_error: string | null;
+  processor_running: boolean;
 }
 
 export interface ErrorPayload {
@@ -49,6 +96,17 @@ export interface TranscriptionErrorPayload {
   error: string;
   error_type: 'connection' | 'configuration' | 'processing' | 'timeout' | 'authentication' | 'limit_exceeded';
   error_details?: TranscriptionErrorDetailsPayload;
+  /** Повтор подключения может помочь (false — auth, лимит, конфигурация) */
+  retryable?: boolean;
+  retry_after_secs?: number;
+  /** Ключ в `errors.*` i18n, выбранный бэкендом по типу ошибки */
+  message_key?: string;
+}
+
+/** Обратный отсчёт до повтора после rate limit; remaining_secs = 0 — повтор доступен */
+export interface RetryCountdownPayload {
+  session_id: number;
+  remaining_secs: number;
 }
 
 export interface TranscriptionErrorDetailsPayload {
@@ -78,10 +136,29 @@ export enum ConnectionQuality {
   Recovering = 'Recovering',
 }
 
+// Код причины изменения качества связи — текст по нему собирается на локали UI
+export type ConnectionQualityReason =
+  | { kind: 'audio_stream_lost' }
+  | { kind: 'audio_stream_restored' }
+  | { kind: 'microphone_silent' }
+  | { kind: 'audio_send_backlog'; backlog_secs: number }
+  | { kind: 'audio_dropped'; backlog_secs: number; dropped_secs: number }
+  | { kind: 'audio_backlog_cleared' }
+  | { kind: 'audio_send_too_slow' }
+  | { kind: 'provider_connection_lost' }
+  | { kind: 'connection_recovering' }
+  | { kind: 'critical_connection_error' }
+  | { kind: 'connection_unstable' }
+  | { kind: 'stream_error'; error_type: string; message: string }
+  | { kind: 'reconnecting'; attempt: number; max_attempts: number }
+  | { kind: 'no_server_response'; secs: number }
+  | { kind: 'stream_restarted'; attempt: number; max_attempts: number };
+
 export interface ConnectionQualityPayload {
   session_id: number;
   quality: ConnectionQuality;
   reason?: string;
+  reason_code?: ConnectionQualityReason;
 }
 
 // Event names (must match Rust backend)
@@ -89,9 +166,11 @@ export const EVENT_TRANSCRIPTION_PARTIAL = 'transcription:partial';
 export const EVENT_TRANSCRIPTION_FINAL = 'transcription:final';
 export const EVENT_RECORDING_STATUS = 'recording:status';
 export const EVENT_TRANSCRIPTION_ERROR = 'transcription:error';
+export const EVENT_TRANSCRIPTION_RETRY_COUNTDOWN = 'transcription:retry-countdown';
 export const EVENT_CONNECTION_QUALITY = 'connection:quality';
 export const EVENT_ERROR = 'app:error';
 export const EVENT_RECORDING_WINDOW_SHOWN = 'recording:window-shown';
+export const EVENT_RECORDING_QUIET = 'recording:quiet';
 
 // STT Configuration types
 export enum SttProviderType {
@@ -104,6 +183,173 @@ export enum SttProviderType {
   Azure = 'azure',
 }
 
+/** Состояние сети: без неё облачные провайдеры недоступны, запись идёт через offline_provider */
+export interface NetworkStatusPayload {
+  online: boolean;
+  cloud_providers_available: boolean;
+  offline_provider?: SttProviderType;
+  offline_model?: string;
+}
+
+export const EVENT_NETWORK_OFFLINE = 'network:offline';
+export const EVENT_NETWORK_ONLINE = 'network:online';
+
+/** Вставка в окно администратора заблокирована Windows (UIPI): текст скопирован в clipboard */
+export interface PasteClipboardFallbackPayload {
+  target?: string;
+  session_id?: number;
+  message: string;
+}
+
+export const EVENT_PASTE_CLIPBOARD_FALLBACK = 'paste:clipboard-fallback';
+
+/** Диктовка по буквам включена/выключена (хоткей, голосовая команда, set_spelling_mode) */
+export interface SpellingModePayload {
+  enabled: boolean;
+}
+
+export const EVENT_SPELLING_MODE = 'recording:spelling-mode';
+
+/** Голосовая команда управления (stt voice_commands) */
+export interface VoiceCommandPayload {
+  command: 'stop_recording' | 'switch_language' | 'paste_text';
+  language?: string;
+}
+
+export const EVENT_VOICE_COMMAND = 'recording:voice-command';
+
+/** Текст одного провайдера в A/B сравнении (stt comparison_provider) */
+export interface ComparisonTranscriptPayload {
+  session_id: number;
+  provider: SttProviderType;
+  primary: boolean; // основной провайдер: его текст идёт в окно и вставку
+  text: string;
+  is_final: boolean;
+  transcript: string; // весь текст провайдера за сессию
+}
+
+export const EVENT_TRANSCRIPTION_COMPARISON = 'transcription:comparison';
+
+/** Слитый текст обоих провайдеров A/B сравнения (экспериментально, сборка с feature "ensemble") */
+export interface EnsembleTranscriptPayload {
+  session_id: number;
+  transcript: string;
+  secondary_words: number; // сколько слов взято у сравниваемого провайдера
+}
+
+export const EVENT_TRANSCRIPTION_ENSEMBLE = 'transcription:ensemble';
+
+/** Прежняя версия текста записи истории (HistoryItem.edits, старые первыми) */
+export interface HistoryEdit {
+  text: string;
+  edited_at: number; // unix seconds
+}
+
+/** Текст записи истории изменён в редакторе (update_history_item_text) */
+export interface HistoryItemUpdatedPayload {
+  id: number;
+  text: string;
+  edited_at: number | null;
+  source_id?: string; // окно, из которого пришла правка
+}
+
+export const EVENT_HISTORY_ITEM_UPDATED = 'history:item-updated';
+
+export type MemoryPoolKind = 'audio_buffer' | 'history' | 'queue';
+
+/** Размер пула памяти (get_memory_report, событие diagnostics:memory-alarm) */
+export interface MemoryPoolUsage {
+  name: string;
+  kind: MemoryPoolKind;
+  items: number;
+  bytes: number;
+  threshold_bytes: number | null;
+}
+
+export interface MemoryReport {
+  pools: MemoryPoolUsage[];
+  total_bytes: number;
+  over_threshold: string[];
+}
+
+export const EVENT_MEMORY_ALARM = 'diagnostics:memory-alarm';
+
+/** Раскладка аудио сессии, в которую указывают смещения сегментов (моно pcm_s16le) */
+export interface TimelineAudio {
+  format: string;
+  sample_rate: number;
+  channels: number;
+}
+
+/** Положение сегмента в аудио сессии (конец не включается) */
+export interface TimelineAudioSpan {
+  start_sample: number;
+  end_sample: number;
+  start_byte: number;
+  end_byte: number;
+}
+
+export interface TimelineSegment {
+  index: number;
+  text: string;
+  start_secs: number; // от старта записи
+  end_secs: number;
+  confidence: number | null;
+  speaker: number | null;
+  channel: number | null;
+  language: string | null;
+  audio: TimelineAudioSpan;
+}
+
+/** Ответ export_session_timeline */
+export interface SessionTimeline {
+  format_version: number;
+  session_id: number;
+  started_at: number; // unix ms
+  duration_secs: number;
+  provider: SttProviderType;
+  in_progress: boolean;
+  audio: TimelineAudio;
+  segments: TimelineSegment[];
+}
+
+/** Ответ get_storage_paths: где лежат данные текущего пользователя ОС */
+export interface StoragePaths {
+  config_dir: string;
+  history_file: string;
+  history_texts_dir: string;
+  session_audio_dir: string; // журнал записи (восстановление после сбоя)
+  audio_spool_dir: string; // недосланное провайдеру аудио
+  models_dir: string;
+  custom_models_dir: boolean; // папка моделей выбрана пользователем
+  meetings_dir: string;
+  logs_dir: string;
+  portable: boolean; // портативный режим: всё в data/ рядом с исполняемым файлом
+}
+
+/** Ответ get_paste_compatibility: дойдёт ли вставка до окна и как это исправить */
+export interface PasteCompatibility {
+  target?: string;
+  target_elevated: boolean;
+  self_elevated: boolean;
+  ui_access: boolean;
+  can_inject_input: boolean;
+  guidance?: string;
+}
+
+/** Разрешение macOS (Privacy & Security), от которого зависят функции приложения */
+export type PermissionCapability = 'microphone' | 'accessibility' | 'input_monitoring' | 'screen_recording';
+
+export type PermissionState = 'granted' | 'denied' | 'not_determined' | 'not_required';
+
+/** Ответ check_permissions: статус разрешения и ссылка на раздел System Settings */
+export interface PermissionStatus {
+  capability: PermissionCapability;
+  state: PermissionState;
+  features: string[];
+  settings_url?: string;
+}
+
 export interface SttConfig {
   provider: SttProviderType;
   language: string;
@@ -113,6 +359,14 @@ export interface SttConfig {
   deepgram_api_key?: string;
   assemblyai_api_key?: string;
   model?: string;
+  comparison_provider?: SttProviderType | null; // A/B сравнение: второй провайдер на том же аудио
+  audio_chunking?: AudioChunkingOverride[]; // мс аудио в одном сообщении провайдеру
+}
+
+/** Размер чанка потоковой отправки для провайдера (update_stt_config audioChunking) */
+export interface AudioChunkingOverride {
+  provider: SttProviderType;
+  chunk_ms: number;
 }
 
 // Whisper Model Management types
@@ -137,11 +391,227 @@ export interface WhisperModelDownloadProgress {
 export const EVENT_WHISPER_DOWNLOAD_STARTED = 'whisper-model:download-started';
 export const EVENT_WHISPER_DOWNLOAD_PROGRESS = 'whisper-model:download-progress';
 export const EVENT_WHISPER_DOWNLOAD_COMPLETED = 'whisper-model:download-completed';
+// Прогресс локальной расшифровки (окна по 30s); прервать — cancel_local_transcription
+export const EVENT_WHISPER_PROGRESS = 'whisper:progress';
+
+export interface WhisperProgressPayload {
+  processed_segments: number;
+  estimated_segments: number;
+}
+
+// Целостность и новые версии скачанных моделей (verify_model; фоновая проверка раз в сутки)
+export const EVENT_MODEL_UPDATE_AVAILABLE = 'model:update-available';
+
+export type ModelIntegrity = 'verified' | 'corrupted' | 'unverified' | 'missing';
+
+export interface ModelVerification {
+  model: string;
+  integrity: ModelIntegrity;
+  sha256: string | null;
+  latest_sha256: string | null; // null — HuggingFace недоступен
+  update_available: boolean;
+}
+
+export interface ModelUpdateAvailablePayload {
+  model: string;
+  reason: 'new_release' | 'integrity_failed';
+  size_bytes: number;
+}
+
+// Paste strategies (update_app_config: pasteStrategy / pasteAppRules)
+export type PasteStrategy = 'insert_at_cursor' | 'replace_selection' | 'smart_append';
+
+// Регистр финального текста (update_app_config: textCasing; также в профиле доставки и правиле приложения)
+export type TextCasing = 'as_is' | 'sentence' | 'lower' | 'upper' | 'title';
+
+export interface PasteAppRule {
+  app_id: string; // macOS bundle ID
+  strategy: PasteStrategy;
+  casing?: TextCasing | null; // null — как у профиля/в общих настройках
+}
+
+// Live captions (update_app_config: captions; start_live_captions / stop_live_captions)
+export type CaptionsPosition = 'bottom' | 'top';
+
+export interface CaptionsConfig {
+  font_size: number;
+  position: CaptionsPosition;
+  max_lines: number;
+  source_device: string | null; // get_loopback_audio_devices
+}
+
+// Meeting mode (update_app_config: meeting; start_meeting / stop_meeting / get_meeting_transcript)
+export interface MeetingConfig {
+  topic_gap_secs: number;
+  min_topic_secs: number;
+  llm_topic_breaks: boolean;
+}
+
+export interface MeetingUtterance {
+  text: string;
+  start_secs: number;
+  end_secs: number;
+  speaker: number | null; // только при update_stt_config({ diarize: true })
+  channel: number | null; // только при update_stt_config({ splitChannels: true })
+  confidence: number | null;
+}
+
+export interface MeetingSegment {
+  index: number;
+  start_secs: number;
+  end_secs: number;
+  title: string | null;
+  speakers: number[];
+  utterances: MeetingUtterance[];
+}
+
+export interface MeetingTranscript {
+  format_version: number;
+  started_at: number; // unix ms
+  ended_at: number;
+  duration_secs: number;
+  language: string | null;
+  diarized: boolean;
+  llm_topic_breaks: boolean;
+  segments: MeetingSegment[];
+}
+
+export const EVENT_MEETING_FINISHED = 'meeting:finished';
+
+export interface MeetingFinishedPayload {
+  transcript: MeetingTranscript;
+  saved_path?: string;
+}
+
+// Graceful shutdown (quit_app / tray "Выход" / Cmd+Q)
+export const EVENT_APP_SHUTDOWN = 'app:shutdown';
+
+export type ShutdownStage = 'stopping_recording' | 'persisting' | 'exiting';
+
+export interface ShutdownPayload {
+  stage: ShutdownStage;
+}
+
+// Решения VAD во время записи: полосы речи/тишины, отладка авто-стопа
+export const EVENT_VAD_ACTIVITY = 'vad:activity';
+
+export type VadActivityKind = 'speech_start' | 'speech_end' | 'level' | 'silence_timeout';
+
+export interface VadActivityPayload {
+  session_id: number;
+  kind: VadActivityKind;
+  offset_ms: number;
+  probability: number; // 0..1, доля речи за последние 300ms
+  silence_ms: number;
+  silence_timeout_ms: number;
+}
+
+// Audio processing test bench (preview_processing_chain: samples = null → raw audio of the last microphone test)
+export interface AudioLevelMetrics {
+  peak: number; // 0..1
+  rms: number; // 0..1
+  rms_dbfs: number; // тишина = -120
+  clipped_samples: number;
+}
+
+export type ProcessingStageMetrics =
+  | {
+      stage: 'vad'; // на сыром сигнале, до усиления
+      frames: number;
+      speech_frames: number;
+      speech_ratio: number;
+      first_speech_ms: number | null;
+      last_speech_ms: number | null;
+      silence_timeout_ms: number;
+      auto_stop_at_ms: number | null;
+      noise_floor_rms: number | null; // шумовой фон (RMS i16) после первой секунды; null — запись короче
+    }
+  | { stage: 'gain'; sensitivity: number; requested_gain: number; output: AudioLevelMetrics }
+  | {
+      stage: 'limiter';
+      min_effective_gain: number;
+      limited_chunks: number;
+      total_chunks: number;
+      output: AudioLevelMetrics;
+    };
+
+export interface ProcessingChainPreview {
+  sample_rate: number;
+  samples: number[]; // i16 PCM после цепочки
+  input: AudioLevelMetrics;
+  stages: ProcessingStageMetrics[];
+}
+
+// Calendar-aware session titles (update_app_config: calendar; request_calendar_access / get_current_calendar_event)
+export type CalendarSource = 'system' | 'ics_url';
+
+export interface CalendarConfig {
+  enabled: boolean;
+  source: CalendarSource; // system — только macOS
+  ics_url: string | null;
+}
+
+export interface CalendarEvent {
+  id: string | null;
+  title: string;
+  start_ms: number;
+  end_ms: number;
+  all_day: boolean;
+  location: string | null;
+  categories: string[];
+}
+
+export interface SessionCalendarTag {
+  title: string;
+  tags: string[];
+  event_id: string | null;
+}
+
+// Активное приложение на момент начала записи (хранится только локально)
+export interface AppContext {
+  app_name: string;
+  app_id: string | null; // bundle ID на macOS, имя exe на Windows
+  window_title: string | null;
+}
+
+// Companion mode (start_companion_server / stop_companion_server)
+export interface CompanionServerInfo {
+  port: number;
+  viewer_url: string | null;
+  service_name: string;
+  mdns_announced: boolean;
+  clients: number;
+}
 
 // App update types/events
+export type UpdateChannel = 'stable' | 'beta';
+
 export interface AppUpdateInfo {
   version: string;
   body: string;
+  channel: UpdateChannel;
+  // Уже скачано и ждёт установки (при install_update или выходе из приложения)
+  downloaded: boolean;
+}
+
+export type UpdateSignatureStatus = 'verified' | 'trusted_key' | 'key_mismatch' | 'missing' | 'malformed';
+
+// get_update_details: что будет скачано перед установкой
+export interface AppUpdateDetails {
+  version: string;
+  current_version: string;
+  channel: UpdateChannel;
+  release_notes: string;
+  size: number | null;
+  is_delta: boolean;
+  signature: UpdateSignatureStatus;
+  downloaded: boolean;
+}
+
+export interface UpdatePreferences {
+  channel: UpdateChannel;
+  skipped_version: string | null;
+  remind_after_ms: number | null;
 }
 
 export interface AppUpdateDownloadProgress {
@@ -154,6 +624,7 @@ export interface AppUpdateDownloadProgress {
 export const EVENT_UPDATE_AVAILABLE = 'update:available';
 export const EVENT_UPDATE_DOWNLOAD_STARTED = 'update:download-started';
 export const EVENT_UPDATE_DOWNLOAD_PROGRESS = 'update:download-progress';
+export const EVENT_UPDATE_DOWNLOADED = 'update:downloaded';
 export const EVENT_UPDATE_INSTALLING = 'update:installing';
 
 // Settings focus events (между окнами)
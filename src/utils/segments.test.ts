@@ -0,0 +1,21 @@
+import { describe, expect, it } from 'vitest';
+import { joinSegments } from './segments';
+
+describe('joinSegments', () => {
+  it('joins segments with a single space', () => {
+    expect(joinSegments('Мы пошли', 'и купили хлеб.')).toBe('Мы пошли и купили хлеб.');
+    expect(joinSegments('', '  hello ', undefined, 'there')).toBe('hello there');
+  });
+
+  it('attaches punctuation to the previous segment', () => {
+    expect(joinSegments('привет', ', как дела?')).toBe('привет, как дела?');
+    expect(joinSegments('see the notes (page two', ')')).toBe('see the notes (page two)');
+  });
+
+  it('keeps paragraph breaks from the backend', () => {
+    expect(joinSegments('Первый абзац.', '\n\nВторой абзац.')).toBe('Первый абзац.\n\nВторой абзац.');
+    // Фраза, начавшаяся с абзаца, сохраняет разрыв для склейки с предыдущими фразами
+    expect(joinSegments('', '\n\nВторой', 'абзац')).toBe('\n\nВторой абзац');
+    expect(joinSegments('Раз.', joinSegments('', '\n\nДва.'))).toBe('Раз.\n\nДва.');
+  });
+});
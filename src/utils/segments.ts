@@ -0,0 +1,19 @@
+// Склейка сегментов распознавания: backend уже привёл регистр и пробелы на стыке
+// (TranscriptAssembler), здесь остаётся не ставить пробел перед знаком препинания.
+// Сегмент после долгой паузы backend начинает с разрыва абзаца ("\n\n") — его сохраняем.
+const ATTACHED_PUNCTUATION = /^[.,!?;:…)\]}»]/;
+export const PARAGRAPH_BREAK = '\n\n';
+
+export function startsParagraph(text: string | null | undefined): boolean {
+  return /^[ \t]*\n/.test(text ?? '');
+}
+
+export function joinSegments(...parts: Array<string | null | undefined>): string {
+  return parts.reduce<string>((text, part) => {
+    const segment = (part ?? '').trim();
+    if (!segment) return text;
+    if (startsParagraph(part)) return `${text.trimEnd()}${PARAGRAPH_BREAK}${segment}`;
+    if (!text) return segment;
+    return ATTACHED_PUNCTUATION.test(segment) ? `${text}${segment}` : `${text} ${segment}`;
+  }, '');
+}
@@ -20,6 +20,7 @@ export type AppConfigSnapshotData = {
   auto_copy_to_clipboard: boolean;
   auto_paste_text: boolean;
   selected_audio_device: string | null;
+  feedback?: { sounds_enabled: boolean }; // звуки записи играет backend (rodio)
 };
 
 /**
@@ -60,11 +60,13 @@ export default defineConfig({
     minify: !process.env.TAURI_DEBUG ? 'esbuild' : false,
     // produce sourcemaps for debug builds
     sourcemap: !!process.env.TAURI_DEBUG,
-    // Multi-page: основное приложение + демо окно
+    // Multi-page: основное приложение + демо окно + оверлей записи + живые субтитры
     rollupOptions: {
       input: {
         main: resolve(__dirname, 'index.html'),
         demo: resolve(__dirname, 'demo.html'),
+        overlay: resolve(__dirname, 'overlay.html'),
+        captions: resolve(__dirname, 'captions.html'),
       },
     },
   },